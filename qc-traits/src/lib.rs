@@ -2,3 +2,6 @@
 
 mod html;
 pub use html::HtmlReport;
+
+mod sink;
+pub use sink::{HtmlSink, JsonSink, ReportSink, ReportTable, ReportValue, Reportable, TextSink};
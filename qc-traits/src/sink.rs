@@ -0,0 +1,547 @@
+//! Pluggable outputs for analysis results
+use crate::HtmlReport;
+use horrorshow::{box_html, helper::doctype, html, RenderBox};
+use serde_json::{Map, Value};
+use std::io::{Result, Write};
+
+/// A single reported value
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReportValue {
+    /// Textual value (identifiers, dates, durations..)
+    Text(String),
+    /// Integer value (counters..)
+    Integer(i64),
+    /// Floating point value (physical quantities..)
+    Float(f64),
+    /// Boolean value
+    Bool(bool),
+    /// Value that could not be determined
+    Missing,
+}
+
+impl std::fmt::Display for ReportValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Text(text) => f.write_str(text),
+            Self::Integer(value) => write!(f, "{}", value),
+            Self::Float(value) => write!(f, "{:.6}", value),
+            Self::Bool(value) => write!(f, "{}", value),
+            Self::Missing => f.write_str("-"),
+        }
+    }
+}
+
+impl From<&str> for ReportValue {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+impl From<String> for ReportValue {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<i64> for ReportValue {
+    fn from(value: i64) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<usize> for ReportValue {
+    fn from(value: usize) -> Self {
+        Self::Integer(value as i64)
+    }
+}
+
+impl From<u32> for ReportValue {
+    fn from(value: u32) -> Self {
+        Self::Integer(value as i64)
+    }
+}
+
+impl From<f64> for ReportValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<bool> for ReportValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl<T: Into<ReportValue>> From<Option<T>> for ReportValue {
+    fn from(value: Option<T>) -> Self {
+        value.map(|value| value.into()).unwrap_or(Self::Missing)
+    }
+}
+
+impl ReportValue {
+    /*
+     * JSON representation: non finite numbers are not representable
+     */
+    fn to_json(&self) -> Value {
+        match self {
+            Self::Text(text) => Value::from(text.as_str()),
+            Self::Integer(value) => Value::from(*value),
+            Self::Float(value) => Value::from(*value),
+            Self::Bool(value) => Value::from(*value),
+            Self::Missing => Value::Null,
+        }
+    }
+}
+
+/// Tabulated results: one row per item (satellite, epoch..)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReportTable {
+    /// Table name
+    pub name: String,
+    /// Column names
+    pub columns: Vec<String>,
+    /// Rows, each one has as many values as there are columns
+    pub rows: Vec<Vec<ReportValue>>,
+}
+
+impl ReportTable {
+    /// Creates an empty [`ReportTable`] with given columns
+    pub fn new(name: &str, columns: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+    /// Appends a new row
+    pub fn push(&mut self, row: Vec<ReportValue>) {
+        self.rows.push(row);
+    }
+}
+
+/// [`ReportSink`] is the destination of analysis results (QC, statistics,
+/// differencing, positioning..). Results are reported as sections, made of
+/// named values and tables, so they can be routed to any kind of output:
+/// terminal ([`TextSink`]), [`JsonSink`], [`HtmlSink`], or a database
+/// by implementing this trait.
+/// ```
+/// use rinex_qc_traits::{ReportSink, ReportTable, TextSink};
+/// let mut sink = TextSink::new(Vec::<u8>::new());
+/// sink.section("Sampling").unwrap();
+/// sink.value("epochs", &2880_usize.into()).unwrap();
+///
+/// let mut gaps = ReportTable::new("gaps", &["start", "duration"]);
+/// gaps.push(vec!["2022-01-01T10:00:00 GPST".into(), "1 h".into()]);
+/// sink.table(&gaps).unwrap();
+/// sink.finish().unwrap();
+///
+/// let text = String::from_utf8(sink.into_inner()).unwrap();
+/// assert!(text.contains("epochs: 2880"));
+/// ```
+pub trait ReportSink {
+    /// Opens a new section, named after the analysis
+    fn section(&mut self, title: &str) -> Result<()>;
+    /// Reports a named value, within current section
+    fn value(&mut self, key: &str, value: &ReportValue) -> Result<()>;
+    /// Reports a table, within current section
+    fn table(&mut self, table: &ReportTable) -> Result<()>;
+    /// Concludes the report: flushes and closes the output
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Results that can be written through any [`ReportSink`]
+pub trait Reportable {
+    /// Writes self through given sink
+    fn report(&self, sink: &mut dyn ReportSink) -> Result<()>;
+}
+
+/// Results are written through all sinks
+impl ReportSink for Vec<Box<dyn ReportSink>> {
+    fn section(&mut self, title: &str) -> Result<()> {
+        self.iter_mut().try_for_each(|sink| sink.section(title))
+    }
+    fn value(&mut self, key: &str, value: &ReportValue) -> Result<()> {
+        self.iter_mut().try_for_each(|sink| sink.value(key, value))
+    }
+    fn table(&mut self, table: &ReportTable) -> Result<()> {
+        self.iter_mut().try_for_each(|sink| sink.table(table))
+    }
+    fn finish(&mut self) -> Result<()> {
+        self.iter_mut().try_for_each(|sink| sink.finish())
+    }
+}
+
+/// [`TextSink`] writes human readable results, as they come
+#[derive(Debug)]
+pub struct TextSink<W: Write> {
+    writer: W,
+}
+
+impl TextSink<std::io::Stdout> {
+    /// Writes to stdout
+    pub fn stdout() -> Self {
+        Self::new(std::io::stdout())
+    }
+}
+
+impl<W: Write> TextSink<W> {
+    /// Writes to given writer
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+    /// Returns the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> ReportSink for TextSink<W> {
+    fn section(&mut self, title: &str) -> Result<()> {
+        writeln!(self.writer, "\n[{}]", title)
+    }
+    fn value(&mut self, key: &str, value: &ReportValue) -> Result<()> {
+        writeln!(self.writer, "{}: {}", key, value)
+    }
+    fn table(&mut self, table: &ReportTable) -> Result<()> {
+        let rows: Vec<Vec<String>> = table
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|value| value.to_string()).collect())
+            .collect();
+        let widths: Vec<usize> = (0..table.columns.len())
+            .map(|i| {
+                rows.iter()
+                    .filter_map(|row| row.get(i).map(|value| value.len()))
+                    .chain(std::iter::once(table.columns[i].len()))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        writeln!(self.writer, "{}:", table.name)?;
+        for row in std::iter::once(&table.columns).chain(rows.iter()) {
+            let line: Vec<String> = row
+                .iter()
+                .zip(widths.iter())
+                .map(|(value, width)| format!("{:<width$}", value, width = width))
+                .collect();
+            writeln!(self.writer, "  {}", line.join("  ").trim_end())?;
+        }
+        Ok(())
+    }
+    fn finish(&mut self) -> Result<()> {
+        self.writer.flush()
+    }
+}
+
+/*
+ * Reported content, for sinks that render everything at once
+ */
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Section {
+    title: String,
+    values: Vec<(String, ReportValue)>,
+    tables: Vec<ReportTable>,
+}
+
+/*
+ * Current section: content reported prior any section
+ * is gathered in an untitled section
+ */
+fn current(sections: &mut Vec<Section>) -> &mut Section {
+    if sections.is_empty() {
+        sections.push(Section::default());
+    }
+    sections.last_mut().unwrap()
+}
+
+/// [`JsonSink`] writes a single JSON document when the report is concluded:
+/// `{"sections": [{"title", "values": {}, "tables": [{"name", "columns", "rows"}]}]}`
+#[derive(Debug)]
+pub struct JsonSink<W: Write> {
+    writer: W,
+    sections: Vec<Section>,
+}
+
+impl<W: Write> JsonSink<W> {
+    /// Writes to given writer
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            sections: Vec::new(),
+        }
+    }
+    /// Returns the JSON document
+    pub fn to_json(&self) -> Value {
+        let sections: Vec<Value> = self
+            .sections
+            .iter()
+            .map(|section| {
+                let mut values = Map::new();
+                for (key, value) in &section.values {
+                    values.insert(key.clone(), value.to_json());
+                }
+                let tables: Vec<Value> = section
+                    .tables
+                    .iter()
+                    .map(|table| {
+                        let rows: Vec<Value> = table
+                            .rows
+                            .iter()
+                            .map(|row| Value::from_iter(row.iter().map(|v| v.to_json())))
+                            .collect();
+                        let mut content = Map::new();
+                        content.insert("name".to_string(), Value::from(table.name.as_str()));
+                        content.insert("columns".to_string(), Value::from(table.columns.clone()));
+                        content.insert("rows".to_string(), Value::from(rows));
+                        Value::Object(content)
+                    })
+                    .collect();
+                let mut content = Map::new();
+                content.insert("title".to_string(), Value::from(section.title.as_str()));
+                content.insert("values".to_string(), Value::Object(values));
+                content.insert("tables".to_string(), Value::from(tables));
+                Value::Object(content)
+            })
+            .collect();
+        let mut document = Map::new();
+        document.insert("sections".to_string(), Value::from(sections));
+        Value::Object(document)
+    }
+    /// Returns the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> ReportSink for JsonSink<W> {
+    fn section(&mut self, title: &str) -> Result<()> {
+        self.sections.push(Section {
+            title: title.to_string(),
+            ..Default::default()
+        });
+        Ok(())
+    }
+    fn value(&mut self, key: &str, value: &ReportValue) -> Result<()> {
+        current(&mut self.sections)
+            .values
+            .push((key.to_string(), value.clone()));
+        Ok(())
+    }
+    fn table(&mut self, table: &ReportTable) -> Result<()> {
+        current(&mut self.sections).tables.push(table.clone());
+        Ok(())
+    }
+    fn finish(&mut self) -> Result<()> {
+        let document = self.to_json();
+        serde_json::to_writer_pretty(&mut self.writer, &document)?;
+        writeln!(self.writer)?;
+        self.writer.flush()
+    }
+}
+
+/// [`HtmlSink`] renders the results as an HTML section, that can either
+/// be embedded in other reports ([`HtmlReport`]), or written as a whole
+/// HTML document when the report is concluded.
+#[derive(Debug)]
+pub struct HtmlSink<W: Write> {
+    writer: W,
+    sections: Vec<Section>,
+}
+
+impl HtmlSink<std::io::Sink> {
+    /// Gathers results, to be embedded in other reports
+    pub fn embedded() -> Self {
+        Self::new(std::io::sink())
+    }
+}
+
+impl<W: Write> HtmlSink<W> {
+    /// Writes to given writer
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            sections: Vec::new(),
+        }
+    }
+    /// Returns the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> ReportSink for HtmlSink<W> {
+    fn section(&mut self, title: &str) -> Result<()> {
+        self.sections.push(Section {
+            title: title.to_string(),
+            ..Default::default()
+        });
+        Ok(())
+    }
+    fn value(&mut self, key: &str, value: &ReportValue) -> Result<()> {
+        current(&mut self.sections)
+            .values
+            .push((key.to_string(), value.clone()));
+        Ok(())
+    }
+    fn table(&mut self, table: &ReportTable) -> Result<()> {
+        current(&mut self.sections).tables.push(table.clone());
+        Ok(())
+    }
+    fn finish(&mut self) -> Result<()> {
+        let html = self.to_html();
+        write!(self.writer, "{}", html)?;
+        self.writer.flush()
+    }
+}
+
+impl<W: Write> HtmlReport for HtmlSink<W> {
+    fn to_html(&self) -> String {
+        format!(
+            "{}",
+            html! {
+                : doctype::HTML;
+                html {
+                    head {
+                        meta(content="text/html", charset="utf-8");
+                        meta(name="viewport", content="width=device-width, initial-scale=1");
+                        link(rel="stylesheet", href="https:////cdn.jsdelivr.net/npm/bulma@0.9.4/css/bulma.min.css");
+                        title {
+                            : "Analysis report"
+                        }
+                    }
+                    body {
+                        : self.to_inline_html()
+                    }
+                }
+            }
+        )
+    }
+    fn to_inline_html(&self) -> Box<dyn RenderBox + '_> {
+        box_html! {
+            @ for section in &self.sections {
+                div(class="section") {
+                    h3(class="title") {
+                        : &section.title
+                    }
+                    @ if !section.values.is_empty() {
+                        table(class="table is-bordered; style=\"margin-bottom: 20px\"") {
+                            tbody {
+                                @ for (key, value) in &section.values {
+                                    tr {
+                                        th {
+                                            : key
+                                        }
+                                        td {
+                                            : value.to_string()
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    @ for table in &section.tables {
+                        h4(class="subtitle") {
+                            : &table.name
+                        }
+                        table(class="table is-bordered; style=\"margin-bottom: 20px\"") {
+                            thead {
+                                tr {
+                                    @ for column in &table.columns {
+                                        th {
+                                            : column
+                                        }
+                                    }
+                                }
+                            }
+                            tbody {
+                                @ for row in &table.rows {
+                                    tr {
+                                        @ for value in row {
+                                            td {
+                                                : value.to_string()
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn report(sink: &mut dyn ReportSink) {
+        sink.section("Sampling").unwrap();
+        sink.value("epochs", &2880_usize.into()).unwrap();
+        sink.value("mean snr", &Option::<f64>::None.into()).unwrap();
+        let mut table = ReportTable::new("gaps", &["start", "duration [s]"]);
+        table.push(vec!["10:00:00".into(), 3600.0.into()]);
+        table.push(vec!["12:00:00".into(), f64::NAN.into()]);
+        sink.table(&table).unwrap();
+        sink.section("Satellites").unwrap();
+        sink.value("G01", &true.into()).unwrap();
+        sink.finish().unwrap();
+    }
+    #[test]
+    fn text_sink() {
+        let mut sink = TextSink::new(Vec::<u8>::new());
+        report(&mut sink);
+        let text = String::from_utf8(sink.into_inner()).unwrap();
+        assert_eq!(
+            text,
+            "
+[Sampling]
+epochs: 2880
+mean snr: -
+gaps:
+  start     duration [s]
+  10:00:00  3600.000000
+  12:00:00  NaN
+
+[Satellites]
+G01: true
+"
+        );
+    }
+    #[test]
+    fn json_sink() {
+        let mut sink = JsonSink::new(Vec::<u8>::new());
+        report(&mut sink);
+        let content = String::from_utf8(sink.into_inner()).unwrap();
+        let json: Value = serde_json::from_str(&content).unwrap();
+        let sections = json["sections"].as_array().unwrap();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0]["title"], "Sampling");
+        assert_eq!(sections[0]["values"]["epochs"], 2880);
+        assert_eq!(sections[0]["values"]["mean snr"], Value::Null);
+        let table = &sections[0]["tables"][0];
+        assert_eq!(table["columns"][1], "duration [s]");
+        assert_eq!(table["rows"][0][1], 3600.0);
+        assert_eq!(table["rows"][1][1], Value::Null);
+        assert_eq!(sections[1]["values"]["G01"], true);
+    }
+    #[test]
+    fn fan_out() {
+        let mut sinks: Vec<Box<dyn ReportSink>> = vec![
+            Box::new(HtmlSink::embedded()),
+            Box::new(TextSink::new(std::io::sink())),
+        ];
+        report(&mut sinks);
+
+        let mut sink = HtmlSink::new(Vec::<u8>::new());
+        report(&mut sink);
+        let inline = format!("{}", html! { : sink.to_inline_html() });
+        assert!(inline.contains("<th>epochs</th><td>2880</td>"));
+        assert!(inline.contains("<th>duration [s]</th>"));
+        let content = String::from_utf8(sink.into_inner()).unwrap();
+        assert!(content.starts_with("<!DOCTYPE html>"));
+    }
+}
@@ -0,0 +1,94 @@
+//! Parsing, compression, decompression and production throughput
+use crate::Error;
+use clap::ArgMatches;
+use rinex::prelude::Rinex;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/*
+ * Runs given operation, returns the mean execution time
+ */
+fn measure<F: FnMut() -> Result<(), Error>>(iterations: u32, mut op: F) -> Result<Duration, Error> {
+    let t0 = Instant::now();
+    for _ in 0..iterations {
+        op()?;
+    }
+    Ok(t0.elapsed() / iterations)
+}
+
+fn report(operation: &str, bytes: u64, elapsed: Duration) {
+    let throughput = bytes as f64 / 1.0E6 / elapsed.as_secs_f64();
+    println!(
+        "  {:<14}{:>12.3} ms {:>10.3} MB/s",
+        operation,
+        elapsed.as_secs_f64() * 1.0E3,
+        throughput
+    );
+}
+
+fn bench_file(path: &PathBuf, iterations: u32, output: &Path) -> Result<(), Error> {
+    let bytes = std::fs::metadata(path)?.len();
+    let output = output.to_string_lossy().to_string();
+    let crinex = format!("{}.crx", output);
+
+    println!("{} ({} bytes)", path.display(), bytes);
+
+    let rinex = Rinex::from_path(path)?;
+    let elapsed = measure(iterations, || {
+        let _ = Rinex::from_path(path)?;
+        Ok(())
+    })?;
+    report("parsing", bytes, elapsed);
+
+    if rinex.is_observation_rinex() {
+        // compression: CRINEX production of the readable record
+        let readable = rinex.crnx2rnx();
+        let compressed = readable.rnx2crnx();
+        let elapsed = measure(iterations, || {
+            compressed.to_file(&crinex)?;
+            Ok(())
+        })?;
+        report("compression", bytes, elapsed);
+
+        // decompression: parsing of the CRINEX we just produced
+        let elapsed = measure(iterations, || {
+            let _ = Rinex::from_file(&crinex)?;
+            Ok(())
+        })?;
+        report("decompression", bytes, elapsed);
+
+        let _ = std::fs::remove_file(&crinex);
+
+        // production of the readable record
+        let elapsed = measure(iterations, || {
+            readable.to_file(&output)?;
+            Ok(())
+        })?;
+        report("production", bytes, elapsed);
+    } else {
+        let elapsed = measure(iterations, || {
+            rinex.to_file(&output)?;
+            Ok(())
+        })?;
+        report("production", bytes, elapsed);
+    }
+
+    let _ = std::fs::remove_file(&output);
+    Ok(())
+}
+
+/// Benchmarks every file passed on the command line
+pub fn bench(matches: &ArgMatches) -> Result<(), Error> {
+    let iterations = *matches.get_one::<u32>("iterations").unwrap();
+    if iterations == 0 {
+        error!("--iterations must be a positive number");
+        return Ok(());
+    }
+    let output = std::env::temp_dir().join("rinex-cli-bench.rnx");
+    for path in matches.get_many::<PathBuf>("files").unwrap() {
+        if let Err(e) = bench_file(path, iterations, &output) {
+            error!("\"{}\": {:?}", path.display(), e);
+        }
+    }
+    Ok(())
+}
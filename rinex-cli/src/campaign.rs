@@ -0,0 +1,134 @@
+//! Campaign statistics: station health monitoring across an archive
+use crate::cli::Cli;
+#[cfg(feature = "plot")]
+use crate::fops::open_with_web_browser;
+#[cfg(feature = "plot")]
+use crate::graph::{build_chart_epoch_axis, PlotContext};
+use crate::Error;
+use clap::ArgMatches;
+#[cfg(feature = "plot")]
+use plotly::common::Mode;
+#[cfg(feature = "plot")]
+use rinex::campaign::PeriodStatistics;
+use rinex::campaign::{Campaign, Period};
+#[cfg(feature = "plot")]
+use rinex::prelude::Epoch;
+use rinex_qc_traits::{ReportSink, ReportTable, Reportable};
+use std::fs::File;
+#[cfg(feature = "plot")]
+use std::io::Write;
+#[cfg(feature = "plot")]
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/*
+ * Campaign opmode: ingests an archive and reports aggregated statistics
+ */
+pub fn campaign(cli: &Cli, matches: &ArgMatches) -> Result<(), Error> {
+    let archive = matches.get_one::<PathBuf>("archive").unwrap();
+    let station = matches.get_one::<String>("station");
+    let period = matches.get_one::<String>("period").unwrap();
+    let period = Period::from_str(period)?;
+
+    let campaign = Campaign::from_directory(archive, station.map(|s| s.as_str()))?;
+    if campaign.sessions.is_empty() {
+        error!("no observation rinex found in \"{}\"", archive.display());
+        return Ok(());
+    }
+    let station = campaign.station().unwrap_or("UNKNOWN");
+    info!(
+        "station \"{}\": {} sessions ingested",
+        station,
+        campaign.sessions.len()
+    );
+
+    let workspace = cli.workspace(&format!("CAMPAIGN-{}", station));
+
+    /* CSV report */
+    let csv_path = workspace.join("campaign.csv");
+    let mut fd = File::create(&csv_path)
+        .unwrap_or_else(|e| panic!("failed to create {}: {:?}", csv_path.display(), e));
+    campaign.to_csv(period, &mut fd)?;
+    info!("\"{}\" has been generated", csv_path.display());
+
+    /* report sinks */
+    let stats = campaign.aggregate(period);
+    let mut sinks = crate::report::open(&cli.report_sinks())?;
+    campaign.report(&mut sinks)?;
+    let mut table = ReportTable::new(
+        "periods",
+        &[
+            "start",
+            "sessions",
+            "completeness [%]",
+            "slips per day",
+            "mean snr [dB.Hz]",
+        ],
+    );
+    for s in &stats {
+        table.push(vec![
+            s.start.to_string().into(),
+            s.sessions.into(),
+            s.completeness().into(),
+            s.slips_per_day().into(),
+            s.mean_snr.into(),
+        ]);
+    }
+    sinks.table(&table)?;
+    sinks.finish()?;
+
+    #[cfg(feature = "plot")]
+    plot_trends(cli, &workspace, &stats);
+    Ok(())
+}
+
+/*
+ * Trend plots: completeness, cycle slips and mean SNR per period
+ */
+#[cfg(feature = "plot")]
+fn plot_trends(cli: &Cli, workspace: &Path, stats: &[PeriodStatistics]) {
+    let epochs: Vec<Epoch> = stats.iter().map(|s| s.start).collect();
+    let mut plot_ctx = PlotContext::new();
+
+    plot_ctx.add_timedomain_plot("Completeness", "Completeness [%]");
+    let completeness: Vec<f64> = stats.iter().map(|s| s.completeness()).collect();
+    let trace = build_chart_epoch_axis(
+        "completeness",
+        Mode::LinesMarkers,
+        epochs.clone(),
+        completeness,
+    );
+    plot_ctx.add_trace(trace);
+
+    plot_ctx.add_timedomain_plot("Cycle slips", "Slips per day");
+    let slips: Vec<f64> = stats.iter().map(|s| s.slips_per_day()).collect();
+    let trace = build_chart_epoch_axis("slips/day", Mode::LinesMarkers, epochs.clone(), slips);
+    plot_ctx.add_trace(trace);
+
+    let snr: Vec<(Epoch, f64)> = stats
+        .iter()
+        .filter_map(|s| Some((s.start, s.mean_snr?)))
+        .collect();
+    if !snr.is_empty() {
+        plot_ctx.add_timedomain_plot("Mean SNR", "SNR [dB.Hz]");
+        let trace = build_chart_epoch_axis(
+            "mean snr",
+            Mode::LinesMarkers,
+            snr.iter().map(|(t, _)| *t).collect(),
+            snr.iter().map(|(_, snr)| *snr).collect(),
+        );
+        plot_ctx.add_trace(trace);
+    }
+
+    let html_path = workspace.join("campaign.html");
+    let mut fd = File::create(&html_path)
+        .unwrap_or_else(|e| panic!("failed to create {}: {:?}", html_path.display(), e));
+    write!(fd, "{}", plot_ctx.to_html()).unwrap_or_else(|e| {
+        panic!("failed to render HTML content: {:?}", e);
+    });
+    info!("html rendered in \"{}\"", html_path.display());
+    if !cli.quiet() {
+        open_with_web_browser(html_path.to_string_lossy().as_ref());
+    }
+}
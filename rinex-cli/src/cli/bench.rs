@@ -0,0 +1,48 @@
+// Throughput benchmarking opmode
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
+
+pub fn subcommand() -> Command {
+    Command::new("bench")
+        .long_flag("bench")
+        .arg_required_else_help(true)
+        .about(
+            "Measures parsing, Hatanaka compression, decompression and production (write)
+throughput on your own files, to compare machines or library versions.
+Compression and decompression only apply to Observation RINEX.
+Does not require --fp nor --dir.",
+        )
+        .arg(
+            Arg::new("files")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Append)
+                .required(true)
+                .help("RINEX file(s) to benchmark."),
+        )
+        .arg(
+            Arg::new("iterations")
+                .short('n')
+                .long("iterations")
+                .value_parser(value_parser!(u32).range(1..))
+                .value_name("N")
+                .action(ArgAction::Set)
+                .default_value("5")
+                .help("Number of iterations of each operation. Reported throughput is the mean."),
+        )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn iterations_parsing() {
+        assert!(subcommand()
+            .try_get_matches_from(["bench", "-n", "0", "obs.rnx"])
+            .is_err());
+        let matches = subcommand()
+            .try_get_matches_from(["bench", "-n", "1", "obs.rnx"])
+            .unwrap();
+        assert_eq!(matches.get_one::<u32>("iterations"), Some(&1));
+    }
+}
@@ -0,0 +1,41 @@
+// Campaign (archive statistics) opmode
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
+
+pub fn subcommand() -> Command {
+    Command::new("campaign")
+        .long_flag("campaign")
+        .arg_required_else_help(true)
+        .about(
+            "Ingests a directory tree of (daily) Observation RINEX files of a single station,
+aggregates QC metrics (completeness, cycle slips per day, mean SNR) per period,
+and generates trend plots and a CSV report, for station health monitoring.
+Does not require --fp nor --dir.",
+        )
+        .arg(
+            Arg::new("archive")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("DIRECTORY")
+                .action(ArgAction::Set)
+                .required(true)
+                .help("Archive directory, walked recursively."),
+        )
+        .arg(
+            Arg::new("station")
+                .long("station")
+                .value_name("NAME")
+                .action(ArgAction::Set)
+                .help(
+                    "Station (marker) name. Files of other stations are ignored.
+When not specified, the first file (alphabetical order) defines the station.",
+                ),
+        )
+        .arg(
+            Arg::new("period")
+                .long("period")
+                .value_name("daily|weekly|monthly")
+                .action(ArgAction::Set)
+                .default_value("weekly")
+                .help("Aggregation period. Weeks are GPS weeks, starting on sundays."),
+        )
+}
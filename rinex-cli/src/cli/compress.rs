@@ -0,0 +1,18 @@
+// Hatanaka compression opmode
+use clap::{Arg, ArgAction, Command};
+
+pub fn subcommand() -> Command {
+    Command::new("compress")
+        .long_flag("compress")
+        .about("Hatanaka compression of Observation RINEX, CRINEX is generated in the workspace.")
+        .arg(
+            Arg::new("dry-run")
+                .long("dry-run")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Do not generate any output, but report the achievable compressed size,
+entropy statistics per observable and number of kernel resets.
+Helps spotting receivers producing poorly compressible data.",
+                ),
+        )
+}
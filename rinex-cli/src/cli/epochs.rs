@@ -0,0 +1,64 @@
+// Epochs listing opmode
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
+
+pub fn subcommand() -> Command {
+    Command::new("epochs")
+        .long_flag("epochs")
+        .arg_required_else_help(true)
+        .about(
+            "Prints the epochs of a file, in various forms. Without options, all epochs are listed.
+Selected forms are printed in this order: count, first, last, list, gaps, hourly.
+Does not require --fp nor --dir.",
+        )
+        .arg(
+            Arg::new("file")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Set)
+                .required(true)
+                .help("RINEX file to inspect."),
+        )
+        .arg(
+            Arg::new("list")
+                .long("list")
+                .action(ArgAction::SetTrue)
+                .help("List all epochs, one per line (default)."),
+        )
+        .arg(
+            Arg::new("count")
+                .long("count")
+                .action(ArgAction::SetTrue)
+                .help("Print the number of epochs."),
+        )
+        .arg(
+            Arg::new("first")
+                .long("first")
+                .action(ArgAction::SetTrue)
+                .help("Print the first epoch."),
+        )
+        .arg(
+            Arg::new("last")
+                .long("last")
+                .action(ArgAction::SetTrue)
+                .help("Print the last epoch."),
+        )
+        .arg(
+            Arg::new("gaps")
+                .long("gaps")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "List the data gaps: starting epoch and duration of each gap
+larger than the dominant sample rate.",
+                ),
+        )
+        .arg(
+            Arg::new("hourly")
+                .long("hourly")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Print the number of epochs per hour, compared to the number of epochs
+expected at the dominant sample rate. Hours without any epoch are reported too.",
+                ),
+        )
+}
@@ -0,0 +1,24 @@
+// SQLite export mode
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
+
+pub fn subcommand() -> Command {
+    Command::new("export")
+        .long_flag("export")
+        .arg_required_else_help(true)
+        .about(
+            "Exports the parsed records into normalized SQL tables
+(files, epochs, observations, ephemerides), for SQL analysis of observation archives.
+Each input file (--fp, --dir) and each Navigation file (--nav) is exported on its own.
+Exporting into an existing database appends to its tables.",
+        )
+        .arg(
+            Arg::new("sqlite")
+                .long("sqlite")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Set)
+                .required(true)
+                .help("SQLite database, created if need be."),
+        )
+}
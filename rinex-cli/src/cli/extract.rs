@@ -0,0 +1,55 @@
+// Extraction opmode
+use clap::{value_parser, Arg, ArgAction, Command};
+use rinex::prelude::Epoch;
+use std::path::PathBuf;
+
+pub fn subcommand() -> Command {
+    Command::new("extract")
+        .long_flag("extract")
+        .arg_required_else_help(true)
+        .about(
+            "Extracts a subset of the primary file (Observation RINEX if provided, otherwise Navigation, Meteo..)
+and generates a reduced, yet valid, RINEX file. The header is updated to only describe
+the retained observables. Applies on top of other preprocessing options, like -P.",
+        )
+        .arg(
+            Arg::new("sv")
+                .long("sv")
+                .value_name("SV")
+                .value_delimiter(',')
+                .action(ArgAction::Append)
+                .help("Retain these satellites only, for example \"G01,E11\"."),
+        )
+        .arg(
+            Arg::new("obs")
+                .long("obs")
+                .value_name("OBSERVABLE")
+                .value_delimiter(',')
+                .action(ArgAction::Append)
+                .help("Retain these observables only, for example \"L1C,C1C\"."),
+        )
+        .arg(
+            Arg::new("window")
+                .long("window")
+                .value_parser(value_parser!(Epoch))
+                .value_names(["START", "END"])
+                .num_args(2)
+                .action(ArgAction::Set)
+                .help(
+                    "Retain this time window only, both ends included.
+For example --window \"2020-06-25T00:00:00 GPST\" \"2020-06-25T12:00:00 GPST\".",
+                ),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Set)
+                .help(
+                    "Output file name. Relative paths are generated in the workspace.
+Defaults to the input file name, suffixed with \"-extract\".",
+                ),
+        )
+}
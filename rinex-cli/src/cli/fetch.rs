@@ -0,0 +1,63 @@
+// Products fetcher opmode
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
+
+pub fn subcommand() -> Command {
+    Command::new("fetch")
+        .long_flag("fetch")
+        .arg_required_else_help(true)
+        .about(
+            "Downloads the IGS products of given day (broadcast navigation, SP3 orbits,
+precise clocks and DCB) into a local cache, and prints the companion
+arguments (--nav, --sp3, --clk) to use in the positioning and QC opmodes.
+Requires curl. Does not require --fp nor --dir.",
+        )
+        .arg(
+            Arg::new("date")
+                .value_name("YYYY-MM-DD")
+                .action(ArgAction::Set)
+                .required(true)
+                .help("Day of interest."),
+        )
+        .arg(
+            Arg::new("products")
+                .long("products")
+                .value_name("nav,sp3,clk,dcb")
+                .action(ArgAction::Set)
+                .default_value("nav,sp3,clk")
+                .help("Comma separated list of products to download."),
+        )
+        .arg(
+            Arg::new("mirror")
+                .long("mirror")
+                .value_name("URL")
+                .action(ArgAction::Append)
+                .help(
+                    "Archive base URL (https:// or ftp://), following the CDDIS directory layout.
+Mirrors are tried in order. The default is https://cddis.nasa.gov/archive.",
+                ),
+        )
+        .arg(
+            Arg::new("token-file")
+                .long("token-file")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Set)
+                .help(
+                    "File containing the bearer token (Earthdata login), for CDDIS authentication.
+The env. variable CDDIS_TOKEN is used when not specified.
+Otherwise, credentials are picked up from ~/.netrc, if it exists.",
+                ),
+        )
+        .arg(
+            Arg::new("cache")
+                .long("cache")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("DIRECTORY")
+                .action(ArgAction::Set)
+                .help(
+                    "Cache directory. Products already present are not downloaded again.
+The default is the \"CACHE\" folder of the workspace.",
+                ),
+        )
+}
@@ -42,6 +42,37 @@ When METEO RINEX is provided, data from meteo sensors is plotted too.",
                 .action(ArgAction::SetTrue)
                 .help("Plot Code Multipath. Requires OBS RINEX."),
         )
+        .arg(
+            Arg::new("noise")
+                .long("noise")
+                .action(ArgAction::SetTrue)
+                .help("Plot Pseudo Range and Phase measurement noise versus time,
+estimated from third order time differences, per satellite and signal. Requires OBS RINEX."),
+        )
+        .arg(
+            Arg::new("ssi")
+                .long("ssi")
+                .action(ArgAction::SetTrue)
+                .help("Plot SSI indicators (RINEX signal strength digit) versus time, per satellite and signal,
+as approximate dB-Hz, along the signal strength observations when they exist.
+Some receivers only populate the indicator. Requires OBS RINEX."),
+        )
+        .arg(
+            Arg::new("rx-clock-offset")
+                .long("rx-clock-offset")
+                .action(ArgAction::SetTrue)
+                .help("Plot the receiver clock offsets reported in the epochs of OBS RINEX,
+along the linear drift fitted over the time frame (expressed in ppb).
+Unlike --rx-clock, this does not require NAV RINEX nor SP3."),
+        )
+        .arg(
+            Arg::new("snr-elev")
+                .long("snr-elev")
+                .action(ArgAction::SetTrue)
+                .help("Plot SNR versus elevation, with fitted template curve per signal.
+Useful for antenna gain characterization and multipath site studies.
+Requires both OBS and NAV RINEX, and the receiver location to be defined."),
+        )
         .next_help_heading("GNSS combinations (requires OBS RINEX)")
         .arg(
             Arg::new("if")
@@ -109,6 +140,15 @@ or we post processed determined a CS.",
 Requires both NAV RINEX and SP3 that overlap in time.",
                 ),
         )
+        .arg(
+            Arg::new("dop")
+                .long("dop")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Dilutions of precision (GDOP, PDOP, HDOP, VDOP, TDOP), from the satellite geometry.
+Requires both OBS and NAV RINEX, and the receiver location. Masks are taken into account.",
+                ),
+        )
         .arg(
             Arg::new("naviplot")
                 .long("naviplot")
@@ -126,6 +166,13 @@ Ideal for precise positioning decision making.",
                 .action(ArgAction::SetTrue)
                 .help("SV clock bias (offset, drift, drift changes)."),
         )
+        .arg(
+            Arg::new("rx-clock")
+                .long("rx-clk")
+                .action(ArgAction::SetTrue)
+                .help("Receiver clock offset, estimated at each Epoch from Pseudo Range observations.
+Requires OBS RINEX with a reference position, and either NAV RINEX or SP3."),
+        )
         .next_help_heading("Atmosphere conditions")
         .arg(
             Arg::new("tropo")
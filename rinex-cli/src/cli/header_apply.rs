@@ -0,0 +1,30 @@
+// Header substitution opmode
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
+
+pub fn subcommand() -> Command {
+    Command::new("header-apply")
+        .long_flag("header-apply")
+        .arg_required_else_help(true)
+        .about(
+            "Rewrites the headers of the input files (--fp, --dir) to match the station metadata
+of a sites database: marker, observer, receiver, antenna and approximate position.
+Each file is rewritten on its own, into the workspace. Refer to doc/header-apply.md.",
+        )
+        .arg(
+            Arg::new("database")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Set)
+                .required(true)
+                .help("Sites database (TOML, or JSON when named \"*.json\")."),
+        )
+        .arg(
+            Arg::new("site")
+                .long("site")
+                .value_name("SITE")
+                .action(ArgAction::Set)
+                .required(true)
+                .help("Site to apply, for example \"ABMF00GLP\" (case insensitive)."),
+        )
+}
@@ -0,0 +1,84 @@
+// Archive indexing opmode
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
+
+fn catalog() -> Arg {
+    Arg::new("catalog")
+        .short('o')
+        .long("catalog")
+        .value_parser(value_parser!(PathBuf))
+        .value_name("FILE")
+        .action(ArgAction::Set)
+        .default_value("catalog.json")
+        .help("Catalog file.")
+}
+
+fn query_subcommand() -> Command {
+    Command::new("query")
+        .about(
+            "Lists the files of a catalog that match all given criteria.
+Unspecified criteria match any file.",
+        )
+        .arg(catalog())
+        .arg(
+            Arg::new("station")
+                .long("station")
+                .value_name("NAME")
+                .action(ArgAction::Set)
+                .help("Station (marker) name, or its first letters."),
+        )
+        .arg(
+            Arg::new("year")
+                .long("year")
+                .value_parser(value_parser!(i32))
+                .value_name("YYYY")
+                .action(ArgAction::Set)
+                .help("Year of the first symbol."),
+        )
+        .arg(
+            Arg::new("doy")
+                .long("doy")
+                .value_parser(value_parser!(u32))
+                .value_name("DDD")
+                .action(ArgAction::Set)
+                .help("Day of year of the first symbol."),
+        )
+        .arg(
+            Arg::new("constellation")
+                .long("constellation")
+                .value_name("GNSS")
+                .action(ArgAction::Set)
+                .help("Files that describe this constellation."),
+        )
+        .arg(
+            Arg::new("type")
+                .long("type")
+                .value_name("TYPE")
+                .action(ArgAction::Set)
+                .help("RINEX type, for example \"observation data\" or \"nav data\"."),
+        )
+}
+
+pub fn subcommand() -> Command {
+    Command::new("index")
+        .long_flag("index")
+        .arg_required_else_help(true)
+        .args_conflicts_with_subcommands(true)
+        .subcommand_negates_reqs(true)
+        .about(
+            "Walks a directory tree and catalogs the RINEX files it contains
+(station, type, time span, sampling interval and constellations),
+from their header section only. The catalog is then queried with \"index query\".
+Does not require --fp nor --dir.",
+        )
+        .arg(
+            Arg::new("archive")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("DIRECTORY")
+                .action(ArgAction::Set)
+                .required(true)
+                .help("Archive directory, walked recursively."),
+        )
+        .arg(catalog())
+        .subcommand(query_subcommand())
+}
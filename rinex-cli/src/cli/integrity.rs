@@ -0,0 +1,25 @@
+// Fast integrity check opmode
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
+
+pub fn subcommand() -> Command {
+    Command::new("integrity")
+        .long_flag("integrity")
+        .arg_required_else_help(true)
+        .about(
+            "Fast integrity check, prior parsing: detects truncated files (incomplete final epoch,
+missing final line ending, truncated gzip stream), binary garbage, mixed line endings
+and non ASCII bytes. Each finding is reported with its byte offset.
+Reports PASS or FAIL for each file, and fails if any file did not pass.
+Does not require --fp nor --dir.",
+        )
+        .arg(
+            Arg::new("files")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Append)
+                .num_args(1..)
+                .required(true)
+                .help("RINEX files, possibly gzip compressed."),
+        )
+}
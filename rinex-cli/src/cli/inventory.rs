@@ -0,0 +1,36 @@
+// Observables and constellations enumeration opmodes
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
+
+fn files() -> Arg {
+    Arg::new("files")
+        .value_parser(value_parser!(PathBuf))
+        .value_name("FILE")
+        .action(ArgAction::Append)
+        .required(true)
+        .help("RINEX file(s) to inspect.")
+}
+
+pub fn observables_subcommand() -> Command {
+    Command::new("observables")
+        .long_flag("observables")
+        .arg_required_else_help(true)
+        .about(
+            "Lists the observables actually present in the file body (not just the header),
+per constellation, with the number of observations of each.
+Does not require --fp nor --dir.",
+        )
+        .arg(files())
+}
+
+pub fn constellations_subcommand() -> Command {
+    Command::new("constellations")
+        .long_flag("constellations")
+        .arg_required_else_help(true)
+        .about(
+            "Lists the constellations actually present in the file body (not just the header),
+with the number of satellites, epochs and observations (or ephemerides) of each.
+Does not require --fp nor --dir.",
+        )
+        .arg(files())
+}
@@ -0,0 +1,34 @@
+// Navigation merge (BRDC generation) opmode
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
+
+pub fn subcommand() -> Command {
+    Command::new("merge-nav")
+        .long_flag("merge-nav")
+        .arg_required_else_help(true)
+        .about(
+            "Combines the Navigation files of several stations into a single
+multi station (BRDC) file: ephemerides are deduplicated and the header
+ionospheric and time system corrections are merged (one per kind).
+Does not require --fp nor --dir.",
+        )
+        .arg(
+            Arg::new("files")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Append)
+                .num_args(1..)
+                .required(true)
+                .help("Navigation RINEX files, possibly gzip compressed."),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Set)
+                .default_value("brdc.rnx")
+                .help("Output file."),
+        )
+}
@@ -0,0 +1,9 @@
+// Metadata dump opmode
+use clap::Command;
+
+pub fn subcommand() -> Command {
+    Command::new("meta").long_flag("meta").about(
+        "Prints a teqc (+meta) compatible metadata block, for each input RINEX file.
+Eases the migration of scripts that scrap teqc outputs.",
+    )
+}
@@ -1,24 +1,47 @@
-use log::info;
+use log::{info, LevelFilter};
 use std::{
-    fs::create_dir_all,
+    fs::{create_dir_all, read_to_string},
     io::Write,
     path::{Path, PathBuf},
     str::FromStr,
 };
 
 use clap::{value_parser, Arg, ArgAction, ArgMatches, ColorChoice, Command};
-use map_3d::{ecef2geodetic, geodetic2ecef, rad2deg, Ellipsoid};
+use map_3d::{ecef2geodetic, geodetic2ecef, Ellipsoid};
+use rinex::observation::MappingProfile;
 use rinex::prelude::*;
+use rinex_qc_traits::ReportSink;
 use walkdir::WalkDir;
 
+#[cfg(feature = "progress")]
+use crate::progress;
 use crate::{fops::open_with_web_browser, Error};
 
+// benchmarking mode
+mod bench;
+// compression mode
+mod compress;
+// SQLite export mode
+#[cfg(feature = "sql")]
+mod export;
+// extraction mode
+mod extract;
+// products download
+#[cfg(feature = "fetch")]
+mod fetch;
 // identification mode
 mod identify;
+// archive indexing mode
+mod index;
 // graph mode
+#[cfg(feature = "plot")]
 mod graph;
+// header substitution mode
+mod header_apply;
 // merge mode
 mod merge;
+// metadata dump
+mod meta;
 // split mode
 mod split;
 // tbin mode
@@ -29,6 +52,48 @@ mod substract;
 mod qc;
 // positioning mode
 mod positioning;
+// static PPP mode
+mod ppp;
+// campaign mode
+mod campaign;
+// visibility prediction mode
+mod visibility;
+// satellite health mode
+mod sv_health;
+// known position residual analysis mode
+mod residuals;
+// sidereal stacking mode
+mod stack;
+// observed TEC versus ionosphere maps mode
+mod tec;
+// CRINEX integrity verification mode
+mod verify_crx;
+// interactive terminal browser mode
+#[cfg(feature = "tui")]
+mod tui;
+// fast integrity check mode
+mod integrity;
+// epochs listing mode
+mod epochs;
+// navigation merge (BRDC generation) mode
+mod merge_nav;
+
+mod inventory;
+
+/*
+ * Parses a signed time offset, like "-18s" or "+1 h"
+ */
+fn parse_time_shift(desc: &str) -> Result<Duration, String> {
+    let desc = desc.trim();
+    let (negative, magnitude) = match desc.strip_prefix('-') {
+        Some(magnitude) => (true, magnitude),
+        None => (false, desc.strip_prefix('+').unwrap_or(desc)),
+    };
+    let offset = SamplingInterval::from_str(magnitude)
+        .map_err(|e| format!("invalid time offset \"{}\": {}", desc, e))?
+        .duration();
+    Ok(if negative { -offset } else { offset })
+}
 
 pub struct Cli {
     /// Arguments passed by user
@@ -57,6 +122,10 @@ pub struct Context {
     ///  1. manually defined by CLI
     ///  2. determined from dataset
     pub rx_ecef: Option<(f64, f64, f64)>,
+    /// PGM / RUN BY / DATE stamp of generated files
+    pub stamp: Stamp,
+    /// Report sinks defined by user (--sink)
+    pub sinks: Vec<String>,
 }
 
 impl Context {
@@ -109,71 +178,72 @@ impl Context {
             open_with_web_browser(path.to_string_lossy().as_ref());
         }
     }
+    /*
+     * Opens the report sinks defined by user
+     */
+    pub fn report_sinks(&self) -> Result<Vec<Box<dyn ReportSink>>, Error> {
+        crate::report::open(&self.sinks)
+    }
     /*
      * Creates File/Data context defined by user.
      * Regroups all provided files/folders,
      */
     pub fn from_cli(cli: &Cli) -> Result<Self, Error> {
         let mut data = RnxContext::default();
-        let max_depth = match cli.matches.get_one::<u8>("depth") {
-            Some(value) => *value as usize,
-            None => 5usize,
-        };
-
-        /* load all directories recursively, one by one */
-        for dir in cli.input_directories() {
-            let walkdir = WalkDir::new(dir).max_depth(max_depth);
-            for entry in walkdir.into_iter().filter_map(|e| e.ok()) {
-                if !entry.path().is_dir() {
-                    let path = entry.path();
-                    let ret = data.load(&path.to_path_buf());
-                    if ret.is_err() {
-                        warn!(
-                            "failed to load \"{}\": {}",
-                            path.display(),
-                            ret.err().unwrap()
-                        );
-                    }
-                }
+        data.parser_settings = data.parser_settings.with_cache(cli.cache());
+        let profile = cli.mapping_profile()?;
+        /* load all directories recursively, then individual files */
+        for path in cli.input_paths() {
+            #[cfg(feature = "progress")]
+            let ret = {
+                let bar = progress::parser_bar(&path, cli.quiet());
+                let ret = data.load_with_progress(
+                    &path,
+                    profile.as_ref(),
+                    &mut |bytes: u64, epochs: usize| progress::update(&bar, bytes, epochs),
+                );
+                bar.finish_and_clear();
+                ret
+            };
+            #[cfg(not(feature = "progress"))]
+            let ret = data.load_with_progress(&path, profile.as_ref(), &mut |_, _| {});
+            if ret.is_err() {
+                warn!(
+                    "failed to load \"{}\": {}",
+                    path.display(),
+                    ret.err().unwrap()
+                );
             }
         }
-        // load individual files, if any
-        for filepath in cli.input_files() {
-            let ret = data.load(&Path::new(filepath).to_path_buf());
-            if ret.is_err() {
-                warn!("failed to load \"{}\": {}", filepath, ret.err().unwrap());
+        // load companion files: their role is explicit,
+        // so we do not tolerate a content mismatch
+        for (arg, product) in [
+            ("nav", ProductType::BroadcastNavigation),
+            ("sp3", ProductType::HighPrecisionOrbit),
+            ("clk", ProductType::HighPrecisionClock),
+            ("atx", ProductType::Antex),
+            ("met", ProductType::MeteoObservation),
+        ] {
+            for filepath in cli.companion_files(arg) {
+                data.load_product(&Path::new(filepath).to_path_buf(), product)
+                    .map_err(|e| {
+                        Error::CompanionFileError(arg, filepath.to_string(), e.to_string())
+                    })?;
+                info!("loaded {:?} companion \"{}\"", product, filepath);
             }
         }
         let data_stem = Self::context_stem(&data);
-        let data_position = data.ground_position();
+        let data_position = data.antenna_position(cli.apply_antenna_offset());
         Ok(Self {
             data,
             quiet: cli.matches.get_flag("quiet"),
-            workspace: {
-                let path = match std::env::var("RINEX_WORKSPACE") {
-                    Ok(path) => Path::new(&path).join(data_stem).to_path_buf(),
-                    _ => match cli.matches.get_one::<PathBuf>("workspace") {
-                        Some(base_dir) => Path::new(base_dir).join(data_stem).to_path_buf(),
-                        None => Path::new("WORKSPACE").join(data_stem).to_path_buf(),
-                    },
-                };
-                // make sure the workspace is viable and exists, otherwise panic
-                create_dir_all(&path).unwrap_or_else(|e| {
-                    panic!(
-                        "failed to create session workspace \"{}\": {:?}",
-                        path.display(),
-                        e
-                    )
-                });
-                info!("session workspace is \"{}\"", path.to_string_lossy());
-                path
-            },
+            workspace: cli.workspace(&data_stem),
             rx_ecef: {
                 match cli.manual_position() {
                     Some((x, y, z)) => {
                         let (mut lat, mut lon, _) = ecef2geodetic(x, y, z, Ellipsoid::WGS84);
-                        lat = rad2deg(lat);
-                        lon = rad2deg(lon);
+                        lat = lat.to_degrees();
+                        lon = lon.to_degrees();
                         info!(
                             "using manually defined position: {:?} [ECEF] (lat={:.5}°, lon={:.5}°",
                             (x, y, z),
@@ -186,8 +256,8 @@ impl Context {
                         if let Some(data_pos) = data_position {
                             let (x, y, z) = data_pos.to_ecef_wgs84();
                             let (mut lat, mut lon, _) = ecef2geodetic(x, y, z, Ellipsoid::WGS84);
-                            lat = rad2deg(lat);
-                            lon = rad2deg(lon);
+                            lat = lat.to_degrees();
+                            lon = lon.to_degrees();
                             info!(
                                 "position defined in dataset: {:?} [ECEF] (lat={:.5}°, lon={:.5}°",
                                 (x, y, z),
@@ -205,10 +275,32 @@ impl Context {
                     },
                 }
             },
+            stamp: match cli.matches.get_flag("no-stamp") {
+                true => Stamp::reproducible(),
+                false => Stamp::default(),
+            },
+            sinks: cli.report_sinks(),
         })
     }
 }
 
+/*
+ * Opmodes that depend on optional features
+ */
+fn optional_subcommands() -> Vec<Command> {
+    #[allow(unused_mut)]
+    let mut subcommands = Vec::new();
+    #[cfg(feature = "sql")]
+    subcommands.push(export::subcommand());
+    #[cfg(feature = "fetch")]
+    subcommands.push(fetch::subcommand());
+    #[cfg(feature = "plot")]
+    subcommands.push(graph::subcommand());
+    #[cfg(feature = "tui")]
+    subcommands.push(tui::subcommand());
+    subcommands
+}
+
 impl Cli {
     /// Build new command line interface
     pub fn new() -> Self {
@@ -219,6 +311,7 @@ impl Cli {
                     .version(env!("CARGO_PKG_VERSION"))
                     .about("RINEX post processing (command line)")
                     .arg_required_else_help(true)
+                    .subcommand_negates_reqs(true)
                     .color(ColorChoice::Always)
                     .arg(Arg::new("filepath")
                         .short('f')
@@ -242,11 +335,36 @@ Again any RINEX, and SP3 are accepted. You can load as many directories as you n
                         .required(false)
                         .value_parser(value_parser!(u8))
                         .help("Extend maximal recursive search depth of -d. The default is 5."))
+                    .arg(Arg::new("profile")
+                        .long("profile")
+                        .value_name("FILE")
+                        .help("Mapping profile (TOML) for receivers that do not comply with the standards:
+renames observation codes, fixes constellation letters and reorders columns of the input
+Observation RINEX, at parse time. Refer to doc/profile.md."))
+                    .arg(Arg::new("obs-cache")
+                        .long("obs-cache")
+                        .action(ArgAction::SetTrue)
+                        .help("Store parsed Observation RINEX in a binary cache next to each input file,
+and reuse it on the next run while the file remains unchanged."))
                     .arg(Arg::new("quiet")
                         .short('q')
                         .long("quiet")
                         .action(ArgAction::SetTrue)
-                        .help("Disable all terminal output. Also disables auto HTML reports opener."))
+                        .conflicts_with("verbose")
+                        .help("Disable all terminal output, only errors are logged. Also disables auto HTML reports opener."))
+                    .arg(Arg::new("verbose")
+                        .short('v')
+                        .long("verbose")
+                        .action(ArgAction::Count)
+                        .help("Increase verbosity of the diagnostics, library included:
+-v reports the processing steps, -vv debug information and -vvv everything.
+By default, only warnings and errors are logged (unless RUST_LOG says otherwise)."))
+                    .arg(Arg::new("log-file")
+                        .long("log-file")
+                        .value_name("FILE")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Redirect the diagnostics to given file, rather than the terminal.
+Typically for batch jobs. Results are still printed in the terminal."))
                     .arg(Arg::new("workspace")
                         .short('w')
                         .long("workspace")
@@ -254,6 +372,45 @@ Again any RINEX, and SP3 are accepted. You can load as many directories as you n
                         .value_parser(value_parser!(PathBuf))
                         .help("Define custom workspace location. The env. variable RINEX_WORKSPACE, if present, is prefered.
 If none of those exist, we will generate local \"WORKSPACE\" folder."))
+                    .arg(Arg::new("no-stamp")
+                        .long("no-stamp")
+                        .action(ArgAction::SetTrue)
+                        .help("Reproducible outputs: generated files are stamped with a fixed date,
+so they are byte identical from one run to another (CI artifacts).
+Otherwise, they are stamped at the current UTC time."))
+                    .arg(Arg::new("sink")
+                        .long("sink")
+                        .value_name("SINK")
+                        .action(ArgAction::Append)
+                        .help("Route analysis results (QC, statistics, differencing, positioning) to given sink:
+\"text\" (terminal), \"json:FILE\", \"html:FILE\" or \"sqlite:FILE\".
+You can define as many sinks as you need. Refer to doc/report-sinks.md."))
+        .next_help_heading("Companion files (completes the primary files, with an explicit role)")
+            .arg(Arg::new("nav")
+                .long("nav")
+                .value_name("FILE")
+                .action(ArgAction::Append)
+                .help("Broadcast Navigation RINEX. You can load as many as you need."))
+            .arg(Arg::new("sp3")
+                .long("sp3")
+                .value_name("FILE")
+                .action(ArgAction::Append)
+                .help("SP3 high precision orbits. You can load as many as you need."))
+            .arg(Arg::new("clk")
+                .long("clk")
+                .value_name("FILE")
+                .action(ArgAction::Append)
+                .help("Clock RINEX, high precision clock states. You can load as many as you need."))
+            .arg(Arg::new("atx")
+                .long("atx")
+                .value_name("FILE")
+                .action(ArgAction::Append)
+                .help("ANTEX antenna calibrations. You can load as many as you need."))
+            .arg(Arg::new("met")
+                .long("met")
+                .value_name("FILE")
+                .action(ArgAction::Append)
+                .help("Meteo observations RINEX. You can load as many as you need."))
         .next_help_heading("Preprocessing")
             .arg(Arg::new("gps-filter")
                 .short('G')
@@ -292,6 +449,47 @@ If none of those exist, we will generate local \"WORKSPACE\" folder."))
                 .long("lli-mask")
                 .help("Applies given LLI AND() mask. 
 Also drops observations that did not come with an LLI flag"))
+            .arg(Arg::new("time-shift")
+                .long("time-shift")
+                .value_name("OFFSET")
+                .allow_hyphen_values(true)
+                .value_parser(parse_time_shift)
+                .help("Shift all Observation and Meteo epochs by given offset, for example \"-18s\" or \"+1 h\".
+TIME OF FIRST / LAST OBS are shifted accordingly, the time scale is not modified.
+Use this to correct receivers that logged in the wrong time scale (UTC rather than GPST).
+Applied prior any other preprocessing."))
+            .arg(Arg::new("trim")
+                .long("trim")
+                .action(ArgAction::SetTrue)
+                .help("Trim all input products to the time window they have in common,
+prior any other processing. The time span of each product and their common window are always reported,
+inputs that do not overlap are reported as an error. Refer to doc/processing.md."))
+            .arg(Arg::new("write-approx-position")
+                .long("write-approx-position")
+                .action(ArgAction::SetTrue)
+                .help("When no header defines the receiver position, a coarse position is estimated from
+the pseudo ranges and the Navigation data, so elevation angles and QC remain feasible.
+This writes the estimate into the Observation header (APPROX POSITION XYZ), so generated files preserve it."))
+            .arg(Arg::new("max-sv-per-epoch")
+                .long("max-sv-per-epoch")
+                .value_name("N")
+                .value_parser(value_parser!(usize))
+                .help("Keep only the N highest satellites of each Observation epoch.
+Elevation angles are resolved from the Navigation RINEX (--nav), satellites being ranked
+by signal strength if none is provided, or when --thin-out-by-snr is set.
+Produces smaller files for bandwidth constrained relaying, while preserving the geometry."))
+            .arg(Arg::new("thin-out-by-snr")
+                .long("thin-out-by-snr")
+                .action(ArgAction::SetTrue)
+                .requires("max-sv-per-epoch")
+                .help("Rank satellites by signal strength (mean SSI observation or SNR flag)
+rather than elevation, when thinning out with --max-sv-per-epoch."))
+            .arg(Arg::new("dedup-nav")
+                .long("dedup-nav")
+                .action(ArgAction::SetTrue)
+                .help("Remove repeated ephemeris uploads from the Navigation RINEX:
+a single message is kept per satellite and issue of data.
+Shrinks merged broadcast files and speeds up the ephemeris selection."))
             .next_help_heading("Receiver Antenna")
                 .arg(Arg::new("rx-ecef")
                     .long("rx-ecef")
@@ -303,19 +501,143 @@ Otherwise it gets automatically picked up."))
                     .long("rx-geo")
                     .value_name("\"lat,lon,alt\" coordinates in ddeg [°]")
                     .help("Define the (RX) antenna position manualy, in decimal degrees."))
+                .arg(Arg::new("apply-antenna-offset")
+                    .long("apply-antenna-offset")
+                    .action(ArgAction::SetTrue)
+                    .help("Apply the antenna eccentricity (ANTENNA: DELTA H/E/N) to the marker position
+defined in the dataset, so positioning and elevation angles refer to the antenna reference point (ARP).
+Not applied to --rx-ecef nor --rx-geo, which define the antenna position itself."))
+                .arg(Arg::new("fix-antenna-name")
+                    .long("fix-antenna-name")
+                    .action(ArgAction::SetTrue)
+                    .help("Normalize the antenna type (ANT # / TYPE) to the IGS naming convention
+(upper case model in columns 1-15, radome code in columns 17-20, \"NONE\" if unknown).
+Applies to all files generated by this session. Archives usually reject non standard names."))
+                .arg(Arg::new("reorder-observables")
+                    .long("reorder-observables")
+                    .action(ArgAction::SetTrue)
+                    .help("Reorder the observables of each constellation (header and columns) in canonical order:
+Pseudo Range, Phase, Doppler then signal strength, each by ascending frequency band.
+Applies to all files generated by this session, so files from different receivers become comparable column-wise."))
                 .next_help_heading("Exclusive Opmodes: you can only run one at a time.")
-                .subcommand(graph::subcommand())
+                .subcommand(bench::subcommand())
+                .subcommand(campaign::subcommand())
+                .subcommand(compress::subcommand())
+                .subcommand(inventory::constellations_subcommand())
+                .subcommand(epochs::subcommand())
+                .subcommand(extract::subcommand())
+                .subcommands(optional_subcommands())
+                .subcommand(header_apply::subcommand())
                 .subcommand(identify::subcommand())
+                .subcommand(index::subcommand())
                 .subcommand(merge::subcommand())
+                .subcommand(merge_nav::subcommand())
+                .subcommand(inventory::observables_subcommand())
+                .subcommand(meta::subcommand())
                 .subcommand(positioning::subcommand())
+                .subcommand(ppp::subcommand())
                 .subcommand(qc::subcommand())
+                .subcommand(residuals::subcommand())
                 .subcommand(split::subcommand())
+                .subcommand(stack::subcommand())
                 .subcommand(substract::subcommand())
+                .subcommand(sv_health::subcommand())
+                .subcommand(tec::subcommand())
                 .subcommand(time_binning::subcommand())
+                .subcommand(verify_crx::subcommand())
+                .subcommand(integrity::subcommand())
+                .subcommand(visibility::subcommand())
                 .get_matches()
             },
         }
     }
+    /// Returns session workspace, named after given stem.
+    /// The env. variable RINEX_WORKSPACE is prefered, then -w,
+    /// otherwise we use a local "WORKSPACE" folder.
+    /// The workspace is created if it does not exist yet, panics on failure.
+    pub fn workspace(&self, stem: &str) -> PathBuf {
+        let path = match std::env::var("RINEX_WORKSPACE") {
+            Ok(path) => Path::new(&path).join(stem).to_path_buf(),
+            _ => match self.matches.get_one::<PathBuf>("workspace") {
+                Some(base_dir) => Path::new(base_dir).join(stem).to_path_buf(),
+                None => Path::new("WORKSPACE").join(stem).to_path_buf(),
+            },
+        };
+        create_dir_all(&path).unwrap_or_else(|e| {
+            panic!(
+                "failed to create session workspace \"{}\": {:?}",
+                path.display(),
+                e
+            )
+        });
+        info!("session workspace is \"{}\"", path.to_string_lossy());
+        path
+    }
+    /// Returns true if quiet mode is active
+    pub fn quiet(&self) -> bool {
+        self.matches.get_flag("quiet")
+    }
+    /// Returns true if the binary cache of Observation RINEX is enabled
+    pub fn cache(&self) -> bool {
+        self.matches.get_flag("obs-cache")
+    }
+    /// Returns the log level defined by -q/-v/-vv/-vvv, if any
+    pub fn log_level(&self) -> Option<LevelFilter> {
+        if self.quiet() {
+            return Some(LevelFilter::Error);
+        }
+        match self.matches.get_count("verbose") {
+            0 => None,
+            1 => Some(LevelFilter::Info),
+            2 => Some(LevelFilter::Debug),
+            _ => Some(LevelFilter::Trace),
+        }
+    }
+    /// Returns the log file (--log-file), if any
+    pub fn log_file(&self) -> Option<&PathBuf> {
+        self.matches.get_one::<PathBuf>("log-file")
+    }
+    /// Returns the report sinks descriptors (--sink)
+    pub fn report_sinks(&self) -> Vec<String> {
+        self.matches
+            .get_many::<String>("sink")
+            .map(|sinks| sinks.cloned().collect())
+            .unwrap_or_default()
+    }
+    /// Returns all input file paths: directories are walked recursively
+    /// (up to --depth), individual files come last.
+    pub fn input_paths(&self) -> Vec<PathBuf> {
+        let max_depth = match self.matches.get_one::<u8>("depth") {
+            Some(value) => *value as usize,
+            None => 5usize,
+        };
+        let mut paths = Vec::new();
+        for dir in self.input_directories() {
+            let walkdir = WalkDir::new(dir).max_depth(max_depth);
+            for entry in walkdir.into_iter().filter_map(|e| e.ok()) {
+                if !entry.path().is_dir() {
+                    paths.push(entry.path().to_path_buf());
+                }
+            }
+        }
+        for filepath in self.input_files() {
+            paths.push(Path::new(filepath).to_path_buf());
+        }
+        paths
+    }
+    /// Returns the mapping profile possibly defined by user
+    pub fn mapping_profile(&self) -> Result<Option<MappingProfile>, Error> {
+        let fp = match self.matches.get_one::<String>("profile") {
+            Some(fp) => fp,
+            None => return Ok(None),
+        };
+        let content = read_to_string(fp)
+            .map_err(|e| Error::CompanionFileError("profile", fp.to_string(), e.to_string()))?;
+        let profile: MappingProfile = toml::from_str(&content)
+            .map_err(|e| Error::CompanionFileError("profile", fp.to_string(), e.to_string()))?;
+        info!("using mapping profile: {:#?}", profile);
+        Ok(Some(profile))
+    }
     /// Returns list of input directories
     pub fn input_directories(&self) -> Vec<&String> {
         if let Some(fp) = self.matches.get_many::<String>("directory") {
@@ -332,6 +654,14 @@ Otherwise it gets automatically picked up."))
             Vec::new()
         }
     }
+    /// Returns companion filepaths, for given companion (--nav, --sp3..)
+    pub fn companion_files(&self, companion: &str) -> Vec<&String> {
+        if let Some(fp) = self.matches.get_many::<String>(companion) {
+            fp.collect()
+        } else {
+            Vec::new()
+        }
+    }
     pub fn preprocessing(&self) -> Vec<&String> {
         if let Some(filters) = self.matches.get_many::<String>("preprocessing") {
             filters.collect()
@@ -339,6 +669,24 @@ Otherwise it gets automatically picked up."))
             Vec::new()
         }
     }
+    pub fn time_shift(&self) -> Option<Duration> {
+        self.matches.get_one::<Duration>("time-shift").copied()
+    }
+    pub fn trim(&self) -> bool {
+        self.matches.get_flag("trim")
+    }
+    pub fn write_approx_position(&self) -> bool {
+        self.matches.get_flag("write-approx-position")
+    }
+    pub fn max_sv_per_epoch(&self) -> Option<usize> {
+        self.matches.get_one::<usize>("max-sv-per-epoch").copied()
+    }
+    pub fn thin_out_by_snr(&self) -> bool {
+        self.matches.get_flag("thin-out-by-snr")
+    }
+    pub fn dedup_nav(&self) -> bool {
+        self.matches.get_flag("dedup-nav")
+    }
     pub fn gps_filter(&self) -> bool {
         self.matches.get_flag("gps-filter")
     }
@@ -360,6 +708,12 @@ Otherwise it gets automatically picked up."))
     pub fn irnss_filter(&self) -> bool {
         self.matches.get_flag("irnss-filter")
     }
+    pub fn fix_antenna_name(&self) -> bool {
+        self.matches.get_flag("fix-antenna-name")
+    }
+    pub fn reorder_observables(&self) -> bool {
+        self.matches.get_flag("reorder-observables")
+    }
     /*
      * faillible 3D coordinates parsing
      * it's better to panic if the descriptor is badly format
@@ -389,6 +743,10 @@ Otherwise it gets automatically picked up."))
         let geo = Self::parse_3d_coordinates(desc);
         Some(geo)
     }
+    /// Returns true if antenna eccentricity should be applied to the marker position
+    pub fn apply_antenna_offset(&self) -> bool {
+        self.matches.get_flag("apply-antenna-offset")
+    }
     /// Returns RX Position possibly specified by user
     pub fn manual_position(&self) -> Option<(f64, f64, f64)> {
         if let Some(position) = self.manual_ecef() {
@@ -24,6 +24,10 @@ See [] for meaningful examples."))
             .help("Force resolution method to Single Point Positioning (SPP).
 Otherwise, the Default method is used.
 Refer to [https://docs.rs/gnss-rtk/latest/gnss_rtk/prelude/enum.Method.html]."))
+        .arg(Arg::new("pos")
+            .long("pos")
+            .action(ArgAction::SetTrue)
+            .help("Format PVT solutions as RTKLIB position file (.pos)."))
         .arg(Arg::new("gpx")
             .long("gpx")
             .action(ArgAction::SetTrue)
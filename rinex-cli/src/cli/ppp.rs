@@ -0,0 +1,45 @@
+// Static PPP opmode
+use clap::{Arg, ArgAction, Command};
+
+pub fn subcommand() -> Command {
+    Command::new("ppp")
+        .long_flag("ppp")
+        .arg_required_else_help(false)
+        .about(
+            "Static Precise Point Positioning (float solution).
+Resolves the position of a static receiver and the zenith tropospheric delay,
+from dual frequency code and phase observations, SP3 orbits (--sp3) and
+optional precise clocks (--clk). Generates the position and ZTD time series,
+and the convergence plots.",
+        )
+        .arg(
+            Arg::new("cfg")
+                .short('c')
+                .long("cfg")
+                .value_name("FILE")
+                .required(false)
+                .action(ArgAction::Set)
+                .help(
+                    "Pass a PPP configuration file (JSON).
+[https://docs.rs/rinex/latest/rinex/ppp/struct.Config.html] is the structure to represent in JSON.",
+                ),
+        )
+        .arg(
+            Arg::new("pos")
+                .long("pos")
+                .action(ArgAction::SetTrue)
+                .help("Format solutions as RTKLIB position file (.pos)."),
+        )
+        .arg(
+            Arg::new("gpx")
+                .long("gpx")
+                .action(ArgAction::SetTrue)
+                .help("Format solutions as GPX track."),
+        )
+        .arg(
+            Arg::new("kml")
+                .long("kml")
+                .action(ArgAction::SetTrue)
+                .help("Format solutions as KML track."),
+        )
+}
@@ -1,5 +1,6 @@
-// tbin opmode
-use clap::{Arg, ArgAction, Command};
+// QC opmode
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
 
 pub fn subcommand() -> Command {
     Command::new("quality-check")
@@ -26,4 +27,32 @@ See online documentations [https://docs.rs/gnss-rtk/latest/gnss_rtk/prelude/enum
                 .help("Pass a QC configuration file (JSON).
 [] is the structure to represent in JSON.
 See [] for meaningful examples."))
+        .arg(
+            Arg::new("reference")
+                .long("reference")
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+                .action(ArgAction::Set)
+                .help("Observation RINEX of a nearby reference station.
+Adds a common view comparison to the report: satellites tracked by both stations
+are compared side by side (completeness, lock losses, SNR), to tell
+satellite side anomalies from station side anomalies."))
+        .arg(
+            Arg::new("per-file")
+                .long("per-file")
+                .action(ArgAction::SetTrue)
+                .help("Batch mode: analyze each input file on its own, instead of a single dataset.
+Files are analyzed in parallel, one report is generated per file (\"$FILENAME.html\"),
+and a summary of each file is streamed to \"qc.jsonl\" as soon as it is analyzed.
+Companion files (--nav, --sp3..) are not used in this mode."))
+        .arg(
+            Arg::new("jobs")
+                .short('j')
+                .long("jobs")
+                .value_name("N")
+                .value_parser(value_parser!(usize))
+                .action(ArgAction::Set)
+                .help("Number of files analyzed in parallel, in --per-file mode.
+Each worker holds a single file in memory at a time.
+Defaults to the number of available CPUs."))
 }
@@ -0,0 +1,51 @@
+// Known position residual analysis opmode
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
+
+pub fn subcommand() -> Command {
+    Command::new("residuals")
+        .long_flag("residuals")
+        .arg_required_else_help(true)
+        .about(
+            "Pseudo range residuals of each satellite, observed from a surveyed (known) position.
+Residuals are plotted versus time and elevation: the fastest way to spot
+multipath and biases at a reference station.
+Does not require --fp nor --dir.",
+        )
+        .arg(
+            Arg::new("obs")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Set)
+                .required(true)
+                .help("Observation RINEX."),
+        )
+        .arg(
+            Arg::new("nav")
+                .long("nav")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Set)
+                .required(true)
+                .help("Navigation RINEX (broadcast ephemeris)."),
+        )
+        .arg(
+            Arg::new("pos")
+                .long("pos")
+                .value_name("LAT,LON,ALT")
+                .action(ArgAction::Set)
+                .required(true)
+                .allow_hyphen_values(true)
+                .help("Surveyed position, as geodetic coordinates (ddeg, ddeg, meters)."),
+        )
+        .arg(
+            Arg::new("mask")
+                .short('m')
+                .long("mask")
+                .value_parser(value_parser!(f64))
+                .value_name("DEGREES")
+                .action(ArgAction::Set)
+                .default_value("10.0")
+                .help("Elevation mask."),
+        )
+}
@@ -0,0 +1,44 @@
+// Sidereal stacking opmode
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
+
+pub fn subcommand() -> Command {
+    Command::new("stack")
+        .long_flag("stack")
+        .arg_required_else_help(true)
+        .about(
+            "Multipath characterization: aligns the observations of consecutive days
+by sidereal day and stacks the signal strength and code multipath of each satellite.
+Reports the day to day repeatability and plots the stacked series.
+Does not require --fp nor --dir.",
+        )
+        .arg(
+            Arg::new("files")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Append)
+                .num_args(1..)
+                .required(true)
+                .help("Observation RINEX files of the same site, usually consecutive daily files."),
+        )
+        .arg(
+            Arg::new("period")
+                .long("period")
+                .value_parser(value_parser!(f64))
+                .value_name("SECONDS")
+                .action(ArgAction::Set)
+                .default_value("86164.0905")
+                .help("Stacking period. Defaults to the sidereal day."),
+        )
+        .arg(
+            Arg::new("bin")
+                .long("bin")
+                .value_parser(value_parser!(f64))
+                .value_name("SECONDS")
+                .action(ArgAction::Set)
+                .help(
+                    "Samples of different days are compared within bins of that width.
+Defaults to the sampling interval.",
+                ),
+        )
+}
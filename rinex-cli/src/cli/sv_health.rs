@@ -0,0 +1,31 @@
+// Satellite health timeline opmode
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
+
+pub fn subcommand() -> Command {
+    Command::new("sv-health")
+        .long_flag("sv-health")
+        .arg_required_else_help(true)
+        .about(
+            "Satellite health timeline, from the health flags of broadcast ephemeris.
+Lists the unhealthy intervals of each satellite and renders the health timeline,
+to explain observation gaps or select the satellites to exclude from processing.
+Does not require --fp nor --dir.",
+        )
+        .arg(
+            Arg::new("nav")
+                .long("nav")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Set)
+                .required(true)
+                .help("Navigation RINEX (broadcast ephemeris)."),
+        )
+        .arg(
+            Arg::new("unhealthy")
+                .short('u')
+                .long("unhealthy")
+                .action(ArgAction::SetTrue)
+                .help("Only render the satellites that were unhealthy at some point."),
+        )
+}
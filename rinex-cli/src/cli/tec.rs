@@ -0,0 +1,62 @@
+// Observed TEC versus Global Ionosphere Map opmode
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
+
+pub fn subcommand() -> Command {
+    Command::new("tec")
+        .long_flag("tec")
+        .arg_required_else_help(true)
+        .about(
+            "Compares the slant TEC observed by a dual frequency receiver
+to the one predicted by a Global Ionosphere Map (2D IONEX), at the Ionosphere pierce points.
+Residuals are reported per satellite, and plotted versus time and elevation.
+Does not require --fp nor --dir.",
+        )
+        .arg(
+            Arg::new("obs")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Set)
+                .required(true)
+                .help("Observation RINEX."),
+        )
+        .arg(
+            Arg::new("nav")
+                .long("nav")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Set)
+                .required(true)
+                .help("Navigation RINEX (broadcast ephemeris)."),
+        )
+        .arg(
+            Arg::new("ionex")
+                .long("ionex")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Set)
+                .required(true)
+                .help("Global Ionosphere Map (2D IONEX)."),
+        )
+        .arg(
+            Arg::new("pos")
+                .long("pos")
+                .value_name("LAT,LON,ALT")
+                .action(ArgAction::Set)
+                .allow_hyphen_values(true)
+                .help(
+                    "Receiver position, as geodetic coordinates (ddeg, ddeg, meters).
+Defaults to the position described in the Observation RINEX header.",
+                ),
+        )
+        .arg(
+            Arg::new("mask")
+                .short('m')
+                .long("mask")
+                .value_parser(value_parser!(f64))
+                .value_name("DEGREES")
+                .action(ArgAction::Set)
+                .default_value("10.0")
+                .help("Elevation mask."),
+        )
+}
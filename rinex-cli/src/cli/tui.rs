@@ -0,0 +1,23 @@
+// Interactive terminal browser opmode
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
+
+pub fn subcommand() -> Command {
+    Command::new("tui")
+        .long_flag("tui")
+        .arg_required_else_help(true)
+        .about(
+            "Browses a parsed file in an interactive terminal interface:
+header, epoch list, per-satellite observations table and sparkline of the selected observable.
+Handy to inspect files over SSH, without generating any graph.
+Does not require --fp nor --dir.",
+        )
+        .arg(
+            Arg::new("file")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Set)
+                .required(true)
+                .help("RINEX file to browse."),
+        )
+}
@@ -0,0 +1,24 @@
+// CRINEX integrity verification opmode
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
+
+pub fn subcommand() -> Command {
+    Command::new("verify-crx")
+        .long_flag("verify-crx")
+        .arg_required_else_help(true)
+        .about(
+            "CRINEX archive integrity audit: each file is decompressed, recompressed
+and compared to the original (the CRINEX PROG / DATE stamp is ignored).
+Reports PASS or FAIL for each file, and fails if any file did not pass.
+Does not require --fp nor --dir.",
+        )
+        .arg(
+            Arg::new("crx")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Append)
+                .num_args(1..)
+                .required(true)
+                .help("CRINEX files, possibly gzip compressed."),
+        )
+}
@@ -0,0 +1,97 @@
+// Satellite visibility prediction opmode
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
+
+pub fn subcommand() -> Command {
+    Command::new("visibility")
+        .long_flag("visibility")
+        .arg_required_else_help(true)
+        .about(
+            "Predicts which satellites rise above the elevation mask, seen from a ground position,
+from broadcast ephemeris. Generates the rise/set tables and a visibility (Gantt) plot,
+for survey planning or to verify the completeness of observations.
+Does not require --fp nor --dir.",
+        )
+        .arg(
+            Arg::new("nav")
+                .long("nav")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("FILE")
+                .action(ArgAction::Set)
+                .required(true)
+                .help("Navigation RINEX (broadcast ephemeris)."),
+        )
+        .arg(
+            Arg::new("pos")
+                .long("pos")
+                .value_name("LAT,LON,ALT")
+                .action(ArgAction::Set)
+                .required(true)
+                .allow_hyphen_values(true)
+                .help("Ground position, as geodetic coordinates (ddeg, ddeg, meters)."),
+        )
+        .arg(
+            Arg::new("window")
+                .long("window")
+                .value_name("START END")
+                .num_args(2)
+                .action(ArgAction::Set)
+                .help(
+                    "Prediction window, for example \"2020-06-25T00:00:00 GPST\" \"2020-06-25T12:00:00 GPST\".
+Defaults to the time frame of the Navigation RINEX.",
+                ),
+        )
+        .arg(
+            Arg::new("mask")
+                .short('m')
+                .long("mask")
+                .value_parser(value_parser!(f64))
+                .value_name("DEGREES")
+                .action(ArgAction::Set)
+                .default_value("10.0")
+                .help("Elevation mask."),
+        )
+        .arg(
+            Arg::new("step")
+                .long("step")
+                .value_parser(positive_seconds)
+                .value_name("SECONDS")
+                .action(ArgAction::Set)
+                .default_value("60.0")
+                .help("Sampling interval of the prediction."),
+        )
+}
+
+/*
+ * Sampling interval, which must be positive
+ */
+fn positive_seconds(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(seconds) if seconds > 0.0 => Ok(seconds),
+        Ok(_) => Err("must be positive".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn step_parsing() {
+        assert_eq!(positive_seconds("30"), Ok(30.0));
+        assert!(positive_seconds("0").is_err());
+        assert!(positive_seconds("-30").is_err());
+        assert!(positive_seconds("thirty").is_err());
+        assert!(subcommand()
+            .try_get_matches_from([
+                "visibility",
+                "--nav",
+                "nav.rnx",
+                "--pos",
+                "0,0,0",
+                "--step",
+                "0"
+            ])
+            .is_err());
+    }
+}
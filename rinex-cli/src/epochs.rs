@@ -0,0 +1,84 @@
+//! Epochs listing and summaries
+use crate::Error;
+use clap::ArgMatches;
+use rinex::prelude::{Duration, Epoch, Rinex};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/*
+ * Number of epochs per hour, in the timescale of the file.
+ * Hours without any epoch are reported too.
+ */
+fn hourly(rinex: &Rinex, epochs: &[Epoch]) {
+    let hour = Duration::from_hours(1.0);
+    let mut counts: BTreeMap<Epoch, usize> = BTreeMap::new();
+    for epoch in epochs {
+        *counts.entry(epoch.floor(hour)).or_default() += 1;
+    }
+    let (first, last) = match (counts.keys().next(), counts.keys().last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return,
+    };
+    let expected = rinex
+        .dominant_sample_rate()
+        .filter(|dt| *dt > Duration::ZERO)
+        .map(|dt| format!("{}", (hour.to_seconds() / dt.to_seconds()).round() as usize))
+        .unwrap_or_else(|| "-".to_string());
+    println!("{:<16}{:>8}{:>10}", "hour", "epochs", "expected");
+    let mut t = first;
+    while t <= last {
+        // YYYY-MM-DDTHH
+        let label = format!("{:?}", t);
+        println!(
+            "{:<16}{:>8}{:>10}",
+            label.get(..13).unwrap_or_default(),
+            counts.get(&t).copied().unwrap_or(0),
+            expected
+        );
+        t = (t + hour).floor(hour);
+    }
+}
+
+/// Prints the epochs of a file, in the selected forms
+pub fn epochs(matches: &ArgMatches) -> Result<(), Error> {
+    let path = matches.get_one::<PathBuf>("file").unwrap();
+    let rinex = Rinex::from_path(path)?;
+    let epochs: Vec<Epoch> = rinex.epoch().collect();
+
+    let (count, first, last, gaps, hourly_counts) = (
+        matches.get_flag("count"),
+        matches.get_flag("first"),
+        matches.get_flag("last"),
+        matches.get_flag("gaps"),
+        matches.get_flag("hourly"),
+    );
+    let list = matches.get_flag("list") || !(count || first || last || gaps || hourly_counts);
+
+    if count {
+        println!("{}", epochs.len());
+    }
+    if first {
+        if let Some(epoch) = epochs.first() {
+            println!("{:?}", epoch);
+        }
+    }
+    if last {
+        if let Some(epoch) = epochs.last() {
+            println!("{:?}", epoch);
+        }
+    }
+    if list {
+        for epoch in &epochs {
+            println!("{:?}", epoch);
+        }
+    }
+    if gaps {
+        for (start, duration) in rinex.data_gaps(None) {
+            println!("{:?} {}", start, duration);
+        }
+    }
+    if hourly_counts {
+        hourly(&rinex, &epochs);
+    }
+    Ok(())
+}
@@ -0,0 +1,81 @@
+//! SQLite export of parsed records
+use crate::{cli::Cli, Error};
+use clap::ArgMatches;
+use rinex::prelude::Rinex;
+use rinex::sql::{Exporter, Value, SCHEMA};
+use rusqlite::{params_from_iter, types, Connection};
+use std::path::PathBuf;
+
+fn to_sql(value: Value) -> types::Value {
+    match value {
+        Value::Null => types::Value::Null,
+        Value::Integer(value) => types::Value::Integer(value),
+        Value::Real(value) => types::Value::Real(value),
+        Value::Text(text) => types::Value::Text(text),
+    }
+}
+
+/*
+ * Next identifier of given table, past the ones already in use
+ */
+fn next_id(conn: &Connection, table: &str) -> Result<i64, rusqlite::Error> {
+    conn.query_row(
+        &format!("SELECT COALESCE(MAX(id), 0) + 1 FROM {}", table),
+        [],
+        |row| row.get(0),
+    )
+}
+
+/*
+ * Export opmode: each file is parsed and inserted on its own,
+ * everything is committed in a single transaction
+ */
+pub fn export(cli: &Cli, matches: &ArgMatches) -> Result<(), Error> {
+    let path = matches.get_one::<PathBuf>("sqlite").unwrap();
+    let mut conn = Connection::open(path)?;
+    conn.execute_batch(SCHEMA)?;
+
+    let mut exporter = Exporter {
+        next_file_id: next_id(&conn, "files")?,
+        next_epoch_id: next_id(&conn, "epochs")?,
+        next_ephemeris_id: next_id(&conn, "ephemerides")?,
+    };
+
+    let mut paths = cli.input_paths();
+    for nav in cli.companion_files("nav") {
+        paths.push(PathBuf::from(nav));
+    }
+
+    let tx = conn.transaction()?;
+    let (mut exported, mut failures) = (0_usize, 0_usize);
+    for fp in paths {
+        let fp = fp.to_string_lossy().to_string();
+        let rinex = match Rinex::from_file(&fp) {
+            Ok(rinex) => rinex,
+            Err(e) => {
+                failures += 1;
+                error!("failed to parse \"{}\": {}", fp, e);
+                continue;
+            },
+        };
+        exporter.export(&fp, &rinex, |row| -> Result<(), rusqlite::Error> {
+            let mut statement = tx.prepare_cached(&row.statement())?;
+            statement.execute(params_from_iter(row.values.into_iter().map(to_sql)))?;
+            Ok(())
+        })?;
+        exported += 1;
+        info!("\"{}\" exported", fp);
+    }
+    if exported == 0 {
+        return Err(Error::ExportFailure(failures));
+    }
+    tx.commit()?;
+
+    info!(
+        "{} files exported to \"{}\" ({} failures)",
+        exported,
+        path.display(),
+        failures
+    );
+    Ok(())
+}
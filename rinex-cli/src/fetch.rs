@@ -0,0 +1,217 @@
+//! IGS products fetcher: downloads companion products into a local cache
+use crate::cli::Cli;
+use crate::Error;
+use clap::ArgMatches;
+use rinex::epoch::CalendarDate;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+const DEFAULT_MIRROR: &str = "https://cddis.nasa.gov/archive";
+
+/*
+ * Products we know how to locate
+ */
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Product {
+    /// Merged broadcast navigation
+    Nav,
+    /// Final orbits
+    Sp3,
+    /// Final clocks
+    Clk,
+    /// Differential code biases
+    Dcb,
+}
+
+impl FromStr for Product {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "nav" => Ok(Self::Nav),
+            "sp3" => Ok(Self::Sp3),
+            "clk" => Ok(Self::Clk),
+            "dcb" => Ok(Self::Dcb),
+            _ => Err(Error::InvalidProduct(s.to_string())),
+        }
+    }
+}
+
+impl Product {
+    /*
+     * Companion argument this product is loaded with
+     */
+    fn companion(&self) -> Option<&'static str> {
+        match self {
+            Self::Nav => Some("--nav"),
+            Self::Sp3 => Some("--sp3"),
+            Self::Clk => Some("--clk"),
+            Self::Dcb => None,
+        }
+    }
+    /*
+     * Location of this product (for given day), relative to the archive base,
+     * following the CDDIS layout and the IGS long product names
+     */
+    fn remote_path(&self, year: i32, doy: u32, week: u32) -> String {
+        match self {
+            Self::Nav => format!(
+                "gnss/data/daily/{}/brdc/BRDC00IGS_R_{}{:03}0000_01D_MN.rnx.gz",
+                year, year, doy
+            ),
+            Self::Sp3 => format!(
+                "gnss/products/{}/IGS0OPSFIN_{}{:03}0000_01D_15M_ORB.SP3.gz",
+                week, year, doy
+            ),
+            Self::Clk => format!(
+                "gnss/products/{}/IGS0OPSFIN_{}{:03}0000_01D_30S_CLK.CLK.gz",
+                week, year, doy
+            ),
+            Self::Dcb => format!(
+                "gnss/products/bias/{}/CAS0MGXRAP_{}{:03}0000_01D_01D_DCB.BSX.gz",
+                year, year, doy
+            ),
+        }
+    }
+}
+
+/*
+ * Parses "YYYY-MM-DD" into (year, day of year, GPS week)
+ */
+fn parse_date(date: &str) -> Result<(i32, u32, u32), Error> {
+    let date = CalendarDate::from_str(date).map_err(|_| Error::InvalidDate(date.to_string()))?;
+    let (week, _) = date
+        .gps_week()
+        .ok_or_else(|| Error::InvalidDate(date.to_string()))?;
+    Ok((date.year, date.day_of_year(), week))
+}
+
+/*
+ * Downloads given URL into given file, returns true on success.
+ * Content is downloaded into a temporary file, renamed once complete:
+ * interrupted or failed downloads never appear in the cache.
+ * The bearer token is passed through curl's stdin, so it does not
+ * appear in the process list.
+ */
+fn download(url: &str, path: &Path, token: Option<&str>) -> bool {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+    let partial = PathBuf::from(partial);
+
+    let mut cmd = Command::new("curl");
+    cmd.args(["--fail", "--silent", "--show-error", "--location"])
+        .args(["--netrc-optional", "--ssl-reqd"])
+        .arg("--output")
+        .arg(&partial)
+        .arg(url);
+    if token.is_some() {
+        cmd.args(["--header", "@-"]).stdin(Stdio::piped());
+    }
+    let status = cmd.spawn().and_then(|mut child| {
+        if let (Some(token), Some(mut stdin)) = (token, child.stdin.take()) {
+            // stdin is closed once the header is written
+            writeln!(stdin, "Authorization: Bearer {}", token)?;
+        }
+        child.wait()
+    });
+    match status {
+        Ok(status) if status.success() => match std::fs::rename(&partial, path) {
+            Ok(_) => true,
+            Err(e) => {
+                error!("failed to store \"{}\": {}", path.display(), e);
+                let _ = std::fs::remove_file(&partial);
+                false
+            },
+        },
+        Ok(_) => {
+            let _ = std::fs::remove_file(&partial);
+            false
+        },
+        Err(e) => {
+            error!("failed to run curl: {}", e);
+            let _ = std::fs::remove_file(&partial);
+            false
+        },
+    }
+}
+
+/*
+ * Fetch opmode: downloads requested products, unless already cached
+ */
+pub fn fetch(cli: &Cli, matches: &ArgMatches) -> Result<(), Error> {
+    let date = matches.get_one::<String>("date").unwrap();
+    let (year, doy, week) = parse_date(date)?;
+
+    let products = matches
+        .get_one::<String>("products")
+        .unwrap()
+        .split(',')
+        .map(Product::from_str)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mirrors: Vec<&str> = match matches.get_many::<String>("mirror") {
+        Some(mirrors) => mirrors.map(|m| m.trim_end_matches('/')).collect(),
+        None => vec![DEFAULT_MIRROR],
+    };
+
+    // the token is never passed on the command line, where it would be visible
+    let token = match matches.get_one::<PathBuf>("token-file") {
+        Some(path) => Some(
+            std::fs::read_to_string(path)
+                .map_err(|e| {
+                    Error::CompanionFileError(
+                        "token-file",
+                        path.display().to_string(),
+                        e.to_string(),
+                    )
+                })?
+                .trim()
+                .to_string(),
+        ),
+        None => std::env::var("CDDIS_TOKEN").ok(),
+    };
+
+    let cache: PathBuf = match matches.get_one::<PathBuf>("cache") {
+        Some(cache) => {
+            std::fs::create_dir_all(cache)?;
+            cache.clone()
+        },
+        None => cli.workspace("CACHE"),
+    };
+
+    let mut companions = Vec::<String>::new();
+    let mut missing = Vec::<String>::new();
+    for product in &products {
+        let remote_path = product.remote_path(year, doy, week);
+        let filename = remote_path.rsplit('/').next().unwrap();
+        let path = cache.join(filename);
+
+        let fetched = if path.exists() {
+            info!("{:?}: \"{}\" is cached", product, path.display());
+            true
+        } else {
+            mirrors.iter().any(|mirror| {
+                let url = format!("{}/{}", mirror, remote_path);
+                info!("{:?}: downloading \"{}\"", product, url);
+                download(&url, &path, token.as_deref())
+            })
+        };
+
+        if fetched {
+            if let Some(arg) = product.companion() {
+                companions.push(format!("{} {}", arg, path.display()));
+            }
+        } else {
+            error!("{:?}: not available on any mirror", product);
+            missing.push(format!("{:?}", product));
+        }
+    }
+    if missing.len() == products.len() {
+        return Err(Error::FetchFailure(missing.join(", ")));
+    }
+    if !companions.is_empty() {
+        println!("{}", companions.join(" "));
+    }
+    Ok(())
+}
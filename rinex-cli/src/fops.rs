@@ -1,9 +1,12 @@
 use crate::cli::Context;
 use crate::Error;
 use clap::ArgMatches;
-use rinex::prelude::{Duration, Epoch, Rinex, RinexType};
+use itertools::Itertools;
+use rinex::prelude::{Constellation, Duration, Epoch, Observable, Rinex, RinexType, SV};
 use rinex::preprocessing::*;
 use rinex::{Merge, Split};
+use rinex_qc_traits::{ReportSink, ReportTable};
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::process::Command;
 use std::str::FromStr;
@@ -38,12 +41,240 @@ pub fn merge(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
 
     let output_path = ctx.workspace.join(suffix).to_string_lossy().to_string();
 
-    rinex_c.to_file(&output_path)?;
+    rinex_c.to_file_with_stamp(&output_path, &ctx.stamp)?;
 
     info!("\"{}\" has been generated", output_path);
     Ok(())
 }
 
+/*
+ * Compresses Observation RINEX into the workspace,
+ * or only reports achievable compression in dry run mode
+ */
+pub fn compress(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
+    let rinex = ctx.data.obs_data().ok_or(Error::MissingObservationRinex)?;
+
+    if matches.get_flag("dry-run") {
+        let estimate = rinex.compression_estimate()?;
+        println!(
+            "compressed size: {} bytes ({} uncompressed, ratio {:.3})",
+            estimate.compressed_size,
+            estimate.input_size,
+            estimate.ratio()
+        );
+        println!("kernel resets: {}", estimate.kernel_resets());
+        println!(
+            "satellite resynchronizations: {}",
+            estimate.resynchronizations
+        );
+        println!(
+            "{:<8}{:<6}{:>10}{:>10}{:>10}{:>16}",
+            "GNSS", "OBS", "samples", "resets", "entropy", "max residual"
+        );
+        for ((constellation, observable), stats) in estimate
+            .observables
+            .iter()
+            .sorted_by_key(|((c, obs), _)| format!("{:X}{}", c, obs))
+        {
+            println!(
+                "{:<8}{:<6}{:>10}{:>10}{:>10.3}{:>16}",
+                format!("{:X}", constellation),
+                observable.to_string(),
+                stats.samples,
+                stats.kernel_resets,
+                stats.entropy(),
+                stats.max_residual().unwrap_or_default()
+            );
+        }
+        return Ok(());
+    }
+
+    let obs_path = ctx
+        .data
+        .obs_paths()
+        .expect("failed to determine output file name")
+        .get(0)
+        .unwrap();
+
+    let filename = obs_path
+        .file_name()
+        .expect("failed to determine output file name")
+        .to_string_lossy()
+        .to_string();
+
+    let output_path = ctx
+        .workspace
+        .join(crinex_filename(&filename))
+        .to_string_lossy()
+        .to_string();
+
+    rinex
+        .rnx2crnx()
+        .to_file_with_stamp(&output_path, &ctx.stamp)?;
+
+    info!("\"{}\" has been generated", output_path);
+    Ok(())
+}
+
+/*
+ * Converts a RINEX file name to its CRINEX counterpart:
+ * file compression is dropped, ".rnx" becomes ".crx"
+ * and V2 ".yyO" becomes ".yyD"
+ */
+fn crinex_filename(filename: &str) -> String {
+    let filename = filename
+        .strip_suffix(".gz")
+        .or_else(|| filename.strip_suffix(".Z"))
+        .unwrap_or(filename);
+    if let Some(stem) = filename.strip_suffix(".rnx") {
+        return format!("{}.crx", stem);
+    }
+    if let Some(stem) = filename.strip_suffix(".RNX") {
+        return format!("{}.CRX", stem);
+    }
+    if let Some((stem, ext)) = filename.rsplit_once('.') {
+        let (yy, kind) = ext.split_at(ext.len().saturating_sub(1));
+        if yy.len() == 2 && yy.chars().all(|c| c.is_ascii_digit()) {
+            match kind {
+                "o" => return format!("{}.{}d", stem, yy),
+                "O" => return format!("{}.{}D", stem, yy),
+                _ => {},
+            }
+        }
+    }
+    format!("{}.crx", filename)
+}
+
+/*
+ * Reduces the Observation header to what the record actually contains,
+ * so the extracted subset remains self consistent
+ */
+fn reduce_observation_header(rinex: &mut Rinex) {
+    let mut observed: HashMap<Constellation, Vec<Observable>> = HashMap::new();
+    for (_, (_, vehicles)) in rinex.observation() {
+        for (sv, observations) in vehicles {
+            let constellation = match sv.constellation.is_sbas() {
+                true => Constellation::SBAS,
+                false => sv.constellation,
+            };
+            let codes = observed.entry(constellation).or_default();
+            for observable in observations.keys() {
+                if !codes.contains(observable) {
+                    codes.push(observable.clone());
+                }
+            }
+        }
+    }
+    let first_epoch = rinex.first_epoch();
+    let last_epoch = rinex.last_epoch();
+    if let Some(obs) = &mut rinex.header.obs {
+        // preserve header order
+        obs.codes
+            .retain(|constellation, _| observed.contains_key(constellation));
+        for (constellation, codes) in obs.codes.iter_mut() {
+            codes.retain(|code| observed[constellation].contains(code));
+        }
+        if obs.time_of_first_obs.is_some() {
+            obs.time_of_first_obs = first_epoch;
+        }
+        if obs.time_of_last_obs.is_some() {
+            obs.time_of_last_obs = last_epoch;
+        }
+    }
+}
+
+/*
+ * Extracts a subset of the primary file into a reduced RINEX
+ */
+pub fn extract(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
+    let (rinex, input_path) = match (ctx.data.rinex_data(), ctx.data.rinex_path()) {
+        (Some(rinex), Some(path)) => (rinex, path),
+        _ => {
+            error!("extract: no RINEX file provided");
+            return Ok(());
+        },
+    };
+
+    let mut masks: Vec<MaskFilter> = Vec::new();
+
+    if let Some(items) = matches.get_many::<String>("sv") {
+        let mut svs: Vec<SV> = Vec::new();
+        for item in items {
+            match SV::from_str(item.trim()) {
+                Ok(sv) => svs.push(sv),
+                Err(_) => return Err(Error::InvalidSatellite(item.to_string())),
+            }
+        }
+        masks.push(MaskFilter {
+            operand: MaskOperand::Equals,
+            item: TargetItem::SvItem(svs),
+        });
+    }
+
+    if let Some(items) = matches.get_many::<String>("obs") {
+        let mut observables: Vec<Observable> = Vec::new();
+        for item in items {
+            match Observable::from_str(item.trim()) {
+                Ok(observable) => observables.push(observable),
+                Err(_) => return Err(Error::InvalidObservable(item.to_string())),
+            }
+        }
+        masks.push(MaskFilter {
+            operand: MaskOperand::Equals,
+            item: TargetItem::ObservableItem(observables),
+        });
+    }
+
+    if let Some(window) = matches.get_many::<Epoch>("window") {
+        let window: Vec<&Epoch> = window.collect();
+        masks.push(MaskFilter {
+            operand: MaskOperand::GreaterEquals,
+            item: TargetItem::EpochItem(*window[0]),
+        });
+        masks.push(MaskFilter {
+            operand: MaskOperand::LowerEquals,
+            item: TargetItem::EpochItem(*window[1]),
+        });
+    }
+
+    let mut subset = rinex.clone();
+    for mask in masks {
+        subset.filter_mut(mask.into());
+    }
+
+    if subset.is_observation_rinex() {
+        reduce_observation_header(&mut subset);
+    }
+
+    let output = match matches.get_one::<PathBuf>("output") {
+        Some(output) => ctx.workspace.join(output),
+        None => {
+            let filename = input_path
+                .file_name()
+                .expect("failed to determine output file name")
+                .to_string_lossy()
+                .to_string();
+            // never overwrite the input file
+            let filename = match filename.split_once('.') {
+                Some((stem, extension)) => format!("{}-extract.{}", stem, extension),
+                None => format!("{}-extract", filename),
+            };
+            ctx.workspace.join(filename)
+        },
+    };
+    let output = output.to_string_lossy().to_string();
+
+    subset.to_file_with_stamp(&output, &ctx.stamp)?;
+
+    info!(
+        "\"{}\" has been generated: {} epochs ({} in input file)",
+        output,
+        subset.epoch().count(),
+        rinex.epoch().count()
+    );
+    Ok(())
+}
+
 /*
  * Splits input files at specified Time Instant
  */
@@ -108,7 +339,7 @@ pub fn split(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
             .to_string_lossy()
             .to_string();
 
-        rinex_a.to_file(&output)?;
+        rinex_a.to_file_with_stamp(&output, &ctx.stamp)?;
         info!("\"{}\" has been generated", output);
 
         let first_epoch = rinex_b
@@ -140,7 +371,7 @@ pub fn split(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
             .to_string_lossy()
             .to_string();
 
-        rinex_b.to_file(&output)?;
+        rinex_b.to_file_with_stamp(&output, &ctx.stamp)?;
         info!("\"{}\" has been generated", output);
     }
     Ok(())
@@ -219,7 +450,7 @@ pub fn time_binning(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
                 .to_string_lossy()
                 .to_string();
 
-            rinex.to_file(&output)?;
+            rinex.to_file_with_stamp(&output, &ctx.stamp)?;
             info!("\"{}\" has been generated", output);
 
             first += *duration;
@@ -293,9 +524,45 @@ pub fn substract(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
         .to_string_lossy()
         .to_string();
 
-    rinex_c.to_file(&fullpath)?;
+    rinex_c.to_file_with_stamp(&fullpath, &ctx.stamp)?;
 
     info!("\"{}\" has been generated", fullpath);
+
+    /*
+     * report differenced observations: mean and rms, per signal
+     */
+    let mut sinks = ctx.report_sinks()?;
+    if !sinks.is_empty() {
+        let mut stats = BTreeMap::<(SV, Observable), (usize, f64, f64)>::new();
+        for (_, (_, svnn)) in rinex_c.observation() {
+            for (sv, observations) in svnn {
+                for (observable, data) in observations {
+                    let (n, sum, sum_sq) = stats
+                        .entry((*sv, observable.clone()))
+                        .or_insert((0, 0.0, 0.0));
+                    *n += 1;
+                    *sum += data.obs;
+                    *sum_sq += data.obs * data.obs;
+                }
+            }
+        }
+        sinks.section("Differencing")?;
+        sinks.value("reference", &path_b.as_str().into())?;
+        sinks.value("epochs", &rinex_c.epoch().count().into())?;
+        sinks.value("output", &fullpath.as_str().into())?;
+        let mut table = ReportTable::new("differences", &["sv", "observable", "n", "mean", "rms"]);
+        for ((sv, observable), (n, sum, sum_sq)) in stats {
+            table.push(vec![
+                sv.to_string().into(),
+                observable.to_string().into(),
+                n.into(),
+                (sum / n as f64).into(),
+                (sum_sq / n as f64).sqrt().into(),
+            ]);
+        }
+        sinks.table(&table)?;
+        sinks.finish()?;
+    }
     Ok(())
 }
 
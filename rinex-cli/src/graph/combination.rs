@@ -16,7 +16,10 @@ pub fn plot_gnss_combination(
     let markers = generate_markers(data.len());
 
     // plot all ops
-    for (op_index, ((lhs_observable, ref_observable), vehicles)) in data.iter().enumerate() {
+    // sorted, for reproducible legends
+    let mut ops: Vec<_> = data.iter().collect();
+    ops.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (op_index, ((lhs_observable, ref_observable), vehicles)) in ops.into_iter().enumerate() {
         for (sv, epochs) in vehicles {
             let data_x: Vec<Epoch> = epochs.iter().map(|((e, _flag), _v)| *e).collect();
             let data_y: Vec<f64> = epochs.iter().map(|(_, v)| *v).collect();
@@ -53,7 +56,10 @@ pub fn plot_gnss_dcb(
     // generate 1 marker per OP
     let markers = generate_markers(data.len());
     // plot all ops
-    for (op_index, (op, vehicles)) in data.iter().enumerate() {
+    // sorted, for reproducible legends
+    let mut ops: Vec<_> = data.iter().collect();
+    ops.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (op_index, (op, vehicles)) in ops.into_iter().enumerate() {
         for (_sv, epochs) in vehicles {
             let data_x: Vec<Epoch> = epochs.iter().map(|((e, _flag), _v)| *e).collect();
             let data_y: Vec<f64> = epochs.iter().map(|(_, v)| *v).collect();
@@ -85,7 +91,10 @@ pub fn plot_gnss_code_mp(
     // generate 1 marker per OP
     let markers = generate_markers(data.len());
     // plot all ops
-    for (op_index, (op, vehicles)) in data.iter().enumerate() {
+    // sorted, for reproducible legends
+    let mut ops: Vec<_> = data.iter().collect();
+    ops.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (op_index, (op, vehicles)) in ops.into_iter().enumerate() {
         for (_sv, epochs) in vehicles {
             let data_x: Vec<Epoch> = epochs.iter().map(|((e, _flag), _v)| *e).collect();
             let data_y: Vec<f64> = epochs.iter().map(|(_, v)| *v).collect();
@@ -1,6 +1,6 @@
 use super::{
-    build_default_3d_plot, build_default_polar_plot, build_timedomain_2y_plot,
-    build_timedomain_plot, build_world_map, Plot,
+    build_default_2d_plot, build_default_3d_plot, build_default_polar_plot,
+    build_timedomain_2y_plot, build_timedomain_plot, build_world_map, Plot,
 };
 //use log::trace;
 use plotly::{layout::MapboxStyle, Trace};
@@ -28,6 +28,10 @@ impl PlotContext {
         self.plots
             .push(build_timedomain_2y_plot(title, y1_label, y2_label));
     }
+    pub fn add_cartesian2d_plot(&mut self, title: &str, x_label: &str, y_label: &str) {
+        self.plots
+            .push(build_default_2d_plot(title, x_label, y_label));
+    }
     pub fn add_cartesian3d_plot(
         &mut self,
         title: &str,
@@ -25,7 +25,9 @@ use rinex::prelude::*;
 
 mod record;
 use record::{
-    plot_atmosphere_conditions, plot_residual_ephemeris, plot_sv_nav_clock, plot_sv_nav_orbits,
+    plot_atmosphere_conditions, plot_dop, plot_observation_noise, plot_residual_ephemeris,
+    plot_rx_clock_estimate, plot_rx_clock_offset, plot_snr_elevation, plot_ssi_indicators,
+    plot_sv_nav_clock, plot_sv_nav_orbits,
 };
 
 mod context;
@@ -257,6 +259,26 @@ pub fn build_timedomain_plot(title: &str, y_title: &str) -> Plot {
     )
 }
 
+/*
+ * builds a standard 2D plot single Y scale,
+ * to plot data against a physical quantity
+ */
+pub fn build_default_2d_plot(title: &str, x_title: &str, y_title: &str) -> Plot {
+    build_plot(
+        title,
+        Side::Top,
+        Font::default(),
+        x_title,
+        y_title,
+        (false, false), // y=0 lines
+        true,           // show legend
+        true,           // autosize
+        true,           // show tick labels
+        10.0,           // ticks dx
+        ".0f",          // ticks fmt
+    )
+}
+
 /*
  * builds a standard 3D plot
  */
@@ -631,6 +653,46 @@ pub fn graph_opmode(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
         /* save MP */
         ctx.render_html("MULTIPATH.html", plot_ctx.to_html());
     }
+    if matches.get_flag("noise") {
+        let data = ctx.data.obs_data().ok_or(Error::MissingObservationRinex)?;
+
+        let mut plot_ctx = PlotContext::new();
+        plot_observation_noise(data, &mut plot_ctx);
+
+        /* save noise */
+        ctx.render_html("NOISE.html", plot_ctx.to_html());
+    }
+    if matches.get_flag("ssi") {
+        let data = ctx.data.obs_data().ok_or(Error::MissingObservationRinex)?;
+
+        let mut plot_ctx = PlotContext::new();
+        plot_ssi_indicators(data, &mut plot_ctx);
+
+        /* save SSI */
+        ctx.render_html("SSI.html", plot_ctx.to_html());
+    }
+    if matches.get_flag("rx-clock-offset") {
+        let data = ctx.data.obs_data().ok_or(Error::MissingObservationRinex)?;
+
+        let mut plot_ctx = PlotContext::new();
+        plot_rx_clock_offset(data, &mut plot_ctx);
+
+        /* save RX CLK offsets */
+        ctx.render_html("RX-CLOCK-OFFSET.html", plot_ctx.to_html());
+    }
+    if matches.get_flag("snr-elev") {
+        if ctx.data.obs_data().is_none() || ctx.data.nav_data().is_none() {
+            panic!("--snr-elev requires both OBS and NAV RINEX.");
+        }
+        let rx_ecef = ctx
+            .rx_ecef
+            .expect("--snr-elev requires the receiver location to be defined.");
+        let mut plot_ctx = PlotContext::new();
+        plot_snr_elevation(&ctx.data, rx_ecef, &mut plot_ctx);
+
+        /* save SNR(elev) */
+        ctx.render_html("SNR-ELEVATION.html", plot_ctx.to_html());
+    }
     if navigation_plot(matches) {
         let mut plot_ctx = PlotContext::new();
 
@@ -655,6 +717,19 @@ pub fn graph_opmode(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
         /* save NAV */
         ctx.render_html("NAVIGATION.html", plot_ctx.to_html());
     }
+    if matches.get_flag("dop") {
+        if ctx.data.obs_data().is_none() || ctx.data.nav_data().is_none() {
+            panic!("--dop requires both OBS and NAV RINEX.");
+        }
+        let rx_ecef = ctx
+            .rx_ecef
+            .expect("--dop requires the receiver location to be defined.");
+        let mut plot_ctx = PlotContext::new();
+        plot_dop(ctx, rx_ecef, &mut plot_ctx, csv_export);
+
+        /* save DOP */
+        ctx.render_html("DOP.html", plot_ctx.to_html());
+    }
     if matches.get_flag("sv-clock") {
         let mut plot_ctx = PlotContext::new();
         plot_sv_nav_clock(&ctx.data, &mut plot_ctx);
@@ -662,6 +737,19 @@ pub fn graph_opmode(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
         /* save CLK */
         ctx.render_html("CLOCKS.html", plot_ctx.to_html());
     }
+    if matches.get_flag("rx-clock") {
+        if ctx.data.obs_data().is_none() {
+            panic!("--rx-clk requires OBS RINEX.");
+        }
+        if ctx.data.sp3_data().is_none() && ctx.data.nav_data().is_none() {
+            panic!("--rx-clk requires either BRDC or SP3.");
+        }
+        let mut plot_ctx = PlotContext::new();
+        plot_rx_clock_estimate(ctx, &mut plot_ctx, csv_export);
+
+        /* save RX CLK */
+        ctx.render_html("RX-CLOCK.html", plot_ctx.to_html());
+    }
     if atmosphere_plot(matches) {
         let mut plot_ctx = PlotContext::new();
         plot_atmosphere_conditions(ctx, &mut plot_ctx, matches);
@@ -6,9 +6,14 @@ mod observation;
 mod sp3_plot;
 
 pub use meteo::plot_meteo_observations;
+pub use navigation::plot_dop;
+pub use navigation::plot_rx_clock_estimate;
 pub use navigation::plot_sv_nav_clock;
 pub use navigation::plot_sv_nav_orbits;
-pub use observation::plot_observations;
+pub use observation::{
+    plot_observation_noise, plot_observations, plot_rx_clock_offset, plot_snr_elevation,
+    plot_ssi_indicators,
+};
 pub use sp3_plot::plot_residual_ephemeris;
 
 use crate::cli::Context;
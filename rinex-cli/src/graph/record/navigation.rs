@@ -1,7 +1,11 @@
-use crate::graph::{build_3d_chart_epoch_label, build_chart_epoch_axis, PlotContext};
+use crate::cli::Context;
+use crate::graph::{
+    build_3d_chart_epoch_label, build_chart_epoch_axis, csv_export_timedomain, PlotContext,
+};
 use plotly::common::{Mode, Visible};
 use rinex::navigation::Ephemeris;
 use rinex::prelude::*;
+use rinex::processing::dop;
 
 pub fn plot_sv_nav_clock(ctx: &RnxContext, plot_ctx: &mut PlotContext) {
     let mut clock_plot_created = false;
@@ -205,6 +209,92 @@ pub fn plot_sv_nav_clock(ctx: &RnxContext, plot_ctx: &mut PlotContext) {
     }
 }
 
+/*
+ * Plots the receiver clock offset, estimated at each Epoch
+ * from pseudo range observations
+ */
+pub fn plot_rx_clock_estimate(ctx: &Context, plot_ctx: &mut PlotContext, csv_export: bool) {
+    let estimates = ctx.data.rx_clock_estimate();
+    if estimates.is_empty() {
+        error!("failed to estimate the receiver clock offset");
+        return;
+    }
+    let data_x: Vec<Epoch> = estimates.keys().copied().collect();
+    let data_y: Vec<f64> = estimates.values().copied().collect();
+
+    plot_ctx.add_timedomain_plot("Receiver Clock Offset (estimate)", "Clock Offset [s]");
+    let trace = build_chart_epoch_axis("Clk Offset", Mode::Markers, data_x.clone(), data_y.clone());
+    plot_ctx.add_trace(trace);
+    trace!("receiver clock estimate");
+
+    if csv_export {
+        let fullpath = ctx.workspace.join("CSV").join("rx-clock-estimate.csv");
+        csv_export_timedomain(
+            &fullpath,
+            "Receiver Clock Offset (estimate)",
+            "Epoch, Clock Offset [s]",
+            &data_x,
+            &data_y,
+        )
+        .expect("failed to render data as CSV");
+    }
+}
+
+/*
+ * Plots the dilutions of precision, from the satellite geometry
+ * (broadcast ephemeris) of the observations
+ */
+pub fn plot_dop(
+    ctx: &Context,
+    rx_ecef: (f64, f64, f64),
+    plot_ctx: &mut PlotContext,
+    csv_export: bool,
+) {
+    let (obs, nav) = match (ctx.data.obs_data(), ctx.data.nav_data()) {
+        (Some(obs), Some(nav)) => (obs, nav),
+        _ => return,
+    };
+    let position = GroundPosition::from_ecef_wgs84(rx_ecef);
+    let dops = dop(nav, obs, position);
+    if dops.is_empty() {
+        error!("failed to determine the dilutions of precision");
+        return;
+    }
+    let data_x: Vec<Epoch> = dops.keys().copied().collect();
+
+    plot_ctx.add_timedomain_2y_plot("GDOP, PDOP", "GDOP", "PDOP");
+    let gdop: Vec<f64> = dops.values().map(|dop| dop.gdop).collect();
+    let trace = build_chart_epoch_axis("gdop", Mode::Markers, data_x.clone(), gdop.clone());
+    plot_ctx.add_trace(trace);
+    let pdop: Vec<f64> = dops.values().map(|dop| dop.pdop).collect();
+    let trace = build_chart_epoch_axis("pdop", Mode::Markers, data_x.clone(), pdop).y_axis("y2");
+    plot_ctx.add_trace(trace);
+
+    plot_ctx.add_timedomain_2y_plot("HDOP, VDOP", "HDOP", "VDOP");
+    let hdop: Vec<f64> = dops.values().map(|dop| dop.hdop).collect();
+    let trace = build_chart_epoch_axis("hdop", Mode::Markers, data_x.clone(), hdop);
+    plot_ctx.add_trace(trace);
+    let vdop: Vec<f64> = dops.values().map(|dop| dop.vdop).collect();
+    let trace = build_chart_epoch_axis("vdop", Mode::Markers, data_x.clone(), vdop).y_axis("y2");
+    plot_ctx.add_trace(trace);
+
+    plot_ctx.add_timedomain_2y_plot("TDOP, Satellites in sight", "TDOP", "Satellites");
+    let tdop: Vec<f64> = dops.values().map(|dop| dop.tdop).collect();
+    let trace = build_chart_epoch_axis("tdop", Mode::Markers, data_x.clone(), tdop);
+    plot_ctx.add_trace(trace);
+    let satellites: Vec<usize> = dops.values().map(|dop| dop.satellites).collect();
+    let trace = build_chart_epoch_axis("satellites", Mode::Markers, data_x.clone(), satellites)
+        .y_axis("y2");
+    plot_ctx.add_trace(trace);
+    trace!("dilution of precision");
+
+    if csv_export {
+        let fullpath = ctx.workspace.join("CSV").join("gdop.csv");
+        csv_export_timedomain(&fullpath, "GDOP", "Epoch, GDOP", &data_x, &gdop)
+            .expect("failed to render data as CSV");
+    }
+}
+
 pub fn plot_sv_nav_orbits(ctx: &RnxContext, plot_ctx: &mut PlotContext) {
     let mut pos_plot_created = false;
     /*
@@ -1,8 +1,9 @@
 use crate::cli::Context;
 use crate::graph::{build_chart_epoch_axis, csv_export_timedomain, generate_markers, PlotContext};
 use plotly::common::{Marker, MarkerSymbol, Mode, Visible};
+use plotly::Scatter;
 use rinex::{observation::*, prelude::*};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 fn observable_to_physics(observable: &Observable) -> String {
     if observable.is_phase_observable() {
@@ -34,8 +35,8 @@ pub fn plot_observations(ctx: &Context, plot_context: &mut PlotContext, csv_expo
     //      bool: loss of lock - CS emphasis
     //      x: sampling timestamp,
     //      y: observation (raw),
-    let mut dataset: HashMap<String, HashMap<String, HashMap<SV, Vec<(bool, Epoch, f64)>>>> =
-        HashMap::new();
+    let mut dataset: BTreeMap<String, BTreeMap<String, BTreeMap<SV, Vec<(bool, Epoch, f64)>>>> =
+        BTreeMap::new();
 
     for ((epoch, _flag), (clock_offset, vehicles)) in record {
         if let Some(value) = clock_offset {
@@ -60,15 +61,15 @@ pub fn plot_observations(ctx: &Context, plot_context: &mut PlotContext, csv_expo
                             data.insert(*sv, vec![(cycle_slip, *epoch, y)]);
                         }
                     } else {
-                        let mut map: HashMap<SV, Vec<(bool, Epoch, f64)>> = HashMap::new();
+                        let mut map: BTreeMap<SV, Vec<(bool, Epoch, f64)>> = BTreeMap::new();
                         map.insert(*sv, vec![(cycle_slip, *epoch, y)]);
                         data.insert(observable_code, map);
                     }
                 } else {
-                    let mut map: HashMap<SV, Vec<(bool, Epoch, f64)>> = HashMap::new();
+                    let mut map: BTreeMap<SV, Vec<(bool, Epoch, f64)>> = BTreeMap::new();
                     map.insert(*sv, vec![(cycle_slip, *epoch, y)]);
-                    let mut mmap: HashMap<String, HashMap<SV, Vec<(bool, Epoch, f64)>>> =
-                        HashMap::new();
+                    let mut mmap: BTreeMap<String, BTreeMap<SV, Vec<(bool, Epoch, f64)>>> =
+                        BTreeMap::new();
                     mmap.insert(observable_code, map);
                     dataset.insert(physics.to_string(), mmap);
                 }
@@ -201,3 +202,183 @@ pub fn plot_observations(ctx: &Context, plot_context: &mut PlotContext, csv_expo
         trace!("{} observations", y_label);
     }
 }
+
+/*
+ * Plots SNR versus elevation, with fitted template curve, per signal
+ */
+pub fn plot_snr_elevation(ctx: &RnxContext, rx_ecef: (f64, f64, f64), plot_ctx: &mut PlotContext) {
+    let obs = ctx.obs_data().unwrap(); // infaillible
+    let nav = ctx.nav_data().unwrap(); // infaillible
+
+    let model = obs.snr_elevation_model(nav, Some(GroundPosition::from_ecef_wgs84(rx_ecef)));
+    if model.samples.is_empty() {
+        error!("failed to gather SNR versus elevation samples");
+        return;
+    }
+    plot_ctx.add_cartesian2d_plot("SNR versus Elevation", "Elevation [°]", "SNR [dB.Hz]");
+
+    let mut signals: Vec<_> = model.samples.iter().collect();
+    signals.sort_by_key(|((c, obs), _)| format!("{:X}{}", c, obs));
+
+    for (index, ((constellation, observable), samples)) in signals.into_iter().enumerate() {
+        let visible = || {
+            if index == 0 {
+                Visible::True
+            } else {
+                Visible::LegendOnly
+            }
+        };
+        let elev: Vec<f64> = samples.iter().map(|(elev, _)| *elev).collect();
+        let snr: Vec<f64> = samples.iter().map(|(_, snr)| *snr).collect();
+        let trace = Scatter::new(elev, snr)
+            .mode(Mode::Markers)
+            .name(&format!("{:X}({})", constellation, observable))
+            .visible(visible());
+        plot_ctx.add_trace(trace);
+
+        if let Some(template) = model.template(*constellation, observable) {
+            let elev: Vec<f64> = (0..=90).map(|e| e as f64).collect();
+            let snr: Vec<f64> = elev.iter().map(|e| template.eval(*e)).collect();
+            let trace = Scatter::new(elev, snr)
+                .mode(Mode::Lines)
+                .name(&format!("{:X}({}) template", constellation, observable))
+                .visible(visible());
+            plot_ctx.add_trace(trace);
+        }
+    }
+    trace!("snr versus elevation");
+}
+
+/*
+ * Plots third difference measurement noise, per SV and signal
+ */
+pub fn plot_observation_noise(obs: &Rinex, plot_ctx: &mut PlotContext) {
+    let noise = obs.observation_noise();
+    if noise.series.is_empty() {
+        error!("failed to estimate measurement noise");
+        return;
+    }
+    let mut series: Vec<_> = noise.series.iter().collect();
+    series.sort_by_key(|((sv, obs), _)| format!("{}{}", sv, obs));
+
+    for (title, y_label, phase) in [
+        ("Pseudo Range noise", "Noise [m]", false),
+        ("Phase noise", "Noise [cycles]", true),
+    ] {
+        plot_ctx.add_timedomain_plot(title, y_label);
+        for (index, ((sv, observable), samples)) in series
+            .iter()
+            .filter(|((_, obs), _)| obs.is_phase_observable() == phase)
+            .enumerate()
+        {
+            let epochs: Vec<Epoch> = samples.iter().map(|(t, _)| *t).collect();
+            let values: Vec<f64> = samples.iter().map(|(_, noise)| *noise).collect();
+            let trace = build_chart_epoch_axis(
+                &format!("{}({})", sv, observable),
+                Mode::Markers,
+                epochs,
+                values,
+            )
+            .visible({
+                if index == 0 {
+                    Visible::True
+                } else {
+                    Visible::LegendOnly
+                }
+            });
+            plot_ctx.add_trace(trace);
+        }
+    }
+    trace!("observation noise");
+}
+
+/*
+ * Plots SSI indicators (approximated as dB-Hz), per SV and signal,
+ * along the signal strength observations when they exist
+ */
+pub fn plot_ssi_indicators(obs: &Rinex, plot_ctx: &mut PlotContext) {
+    let mut series = BTreeMap::<(SV, Observable), Vec<(Epoch, f64, Option<f64>)>>::new();
+    for ((epoch, _), sv, observable, dbhz, snr) in obs.ssi_indicator() {
+        if let Some(indicator) = snr.to_dbhz() {
+            series
+                .entry((sv, observable))
+                .or_default()
+                .push((epoch, indicator.0, dbhz));
+        }
+    }
+    if series.is_empty() {
+        error!("no SSI indicator to plot");
+        return;
+    }
+    plot_ctx.add_timedomain_plot("SSI indicators", "SSI [dB-Hz]");
+    for (index, ((sv, observable), samples)) in series.iter().enumerate() {
+        let visible = || {
+            if index == 0 {
+                Visible::True
+            } else {
+                Visible::LegendOnly
+            }
+        };
+        let epochs: Vec<Epoch> = samples.iter().map(|(t, _, _)| *t).collect();
+        let indicators: Vec<f64> = samples.iter().map(|(_, ssi, _)| *ssi).collect();
+        let trace = build_chart_epoch_axis(
+            &format!("{}({}) indicator", sv, observable),
+            Mode::Lines,
+            epochs,
+            indicators,
+        )
+        .visible(visible());
+        plot_ctx.add_trace(trace);
+
+        let (epochs, observations): (Vec<Epoch>, Vec<f64>) = samples
+            .iter()
+            .filter_map(|(t, _, dbhz)| dbhz.map(|dbhz| (*t, dbhz)))
+            .unzip();
+        if !epochs.is_empty() {
+            let trace = build_chart_epoch_axis(
+                &format!("{}({})", sv, observable),
+                Mode::Markers,
+                epochs,
+                observations,
+            )
+            .visible(visible());
+            plot_ctx.add_trace(trace);
+        }
+    }
+    trace!("ssi indicators");
+}
+
+/*
+ * Plots the receiver clock offsets reported in the epochs,
+ * along the fitted linear drift
+ */
+pub fn plot_rx_clock_offset(obs: &Rinex, plot_ctx: &mut PlotContext) {
+    let (epochs, offsets): (Vec<Epoch>, Vec<f64>) = obs
+        .recvr_clock()
+        .map(|((epoch, _), offset)| (epoch, offset))
+        .unzip();
+    if epochs.is_empty() {
+        error!("no receiver clock offset to plot");
+        return;
+    }
+    plot_ctx.add_timedomain_plot("Receiver Clock Offset", "Clock Offset [s]");
+    let trace = build_chart_epoch_axis("Clk Offset", Mode::Markers, epochs.clone(), offsets)
+        .marker(Marker::new().symbol(MarkerSymbol::TriangleUp));
+    plot_ctx.add_trace(trace);
+
+    if let Some((t0, offset, drift)) = obs.recvr_clock_fit() {
+        info!("receiver clock drift: {:.3} ppb", drift * 1.0E9);
+        let fit: Vec<f64> = epochs
+            .iter()
+            .map(|t| offset + drift * (*t - t0).to_seconds())
+            .collect();
+        let trace = build_chart_epoch_axis(
+            &format!("Drift: {:.3} ppb", drift * 1.0E9),
+            Mode::Lines,
+            epochs,
+            fit,
+        );
+        plot_ctx.add_trace(trace);
+    }
+    trace!("receiver clock offsets");
+}
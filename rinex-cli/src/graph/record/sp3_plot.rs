@@ -3,7 +3,7 @@ use plotly::common::{Mode, Visible}; //Marker, MarkerSymbol
 use rinex::prelude::Epoch;
 use rinex::prelude::RnxContext;
 use rinex::prelude::SV;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 /*
  * Advanced NAV feature
@@ -34,7 +34,7 @@ pub fn plot_residual_ephemeris(ctx: &RnxContext, plot_ctx: &mut PlotContext) {
         return;
     }
 
-    let mut residuals: HashMap<SV, (Vec<(f64, f64, f64)>, Vec<Epoch>)> = HashMap::new();
+    let mut residuals: BTreeMap<SV, (Vec<(f64, f64, f64)>, Vec<Epoch>)> = BTreeMap::new();
 
     for (t, nav_sv, (x_km, y_km, z_km)) in nav.sv_position() {
         if let Some((_, _, (sp3_x, sp3_y, sp3_z))) = sp3
@@ -0,0 +1,236 @@
+//! Header substitution from a sites database
+use crate::{cli::Cli, Error};
+use clap::ArgMatches;
+use rinex::hardware::{Antenna, Rcvr};
+use rinex::prelude::{GroundPosition, Header, Rinex, Stamp};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Station metadata, as described in the sites database.
+/// Undescribed fields are preserved in the rewritten headers.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Site {
+    /// MARKER NAME
+    pub marker: Option<String>,
+    /// MARKER NUMBER (DOMES number, like "97103M001")
+    pub domes: Option<String>,
+    /// OBSERVER / AGENCY
+    pub observer: Option<String>,
+    /// OBSERVER / AGENCY
+    pub agency: Option<String>,
+    /// REC # / TYPE / VERS
+    pub receiver: Option<Rcvr>,
+    /// ANT # / TYPE and ANTENNA: DELTA H/E/N
+    pub antenna: Option<SiteAntenna>,
+    /// APPROX POSITION XYZ: ECEF WGS84 coordinates, in meters
+    pub position: Option<(f64, f64, f64)>,
+}
+
+/// Antenna description, as described in the sites database.
+/// Fields are merged one by one into the existing antenna description,
+/// undescribed fields are preserved.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SiteAntenna {
+    /// Hardware model
+    pub model: Option<String>,
+    /// Serial number
+    pub sn: Option<String>,
+    /// Eccentricity height component, in meters
+    pub height: Option<f64>,
+    /// Eccentricity eastern component, in meters
+    pub eastern: Option<f64>,
+    /// Eccentricity northern component, in meters
+    pub northern: Option<f64>,
+}
+
+impl SiteAntenna {
+    /*
+     * Merges this description into given antenna
+     */
+    fn merge(&self, antenna: &Antenna) -> Antenna {
+        let mut merged = antenna.clone();
+        if let Some(model) = &self.model {
+            merged.model = model.clone();
+        }
+        if let Some(sn) = &self.sn {
+            merged.sn = sn.clone();
+        }
+        merged.height = self.height.or(antenna.height);
+        merged.eastern = self.eastern.or(antenna.eastern);
+        merged.northern = self.northern.or(antenna.northern);
+        merged
+    }
+}
+
+impl Site {
+    /*
+     * Rewrites given header to match this site,
+     * returns the substituted fields
+     */
+    fn apply(&self, header: &mut Header) -> Vec<&'static str> {
+        let mut changes = Vec::new();
+        if let Some(name) = &self.marker {
+            let marker = header.geodetic_marker.clone().unwrap_or_default();
+            if marker.name != *name {
+                header.geodetic_marker = Some(marker.with_name(name));
+                changes.push("MARKER NAME");
+            }
+        }
+        if let Some(domes) = &self.domes {
+            let marker = header.geodetic_marker.clone().unwrap_or_default();
+            let updated = marker.with_number(domes);
+            if updated.number().as_ref() != Some(domes) {
+                warn!("invalid DOMES number \"{}\"", domes);
+            } else if marker.number() != updated.number() {
+                header.geodetic_marker = Some(updated);
+                changes.push("MARKER NUMBER");
+            }
+        }
+        if let Some(observer) = &self.observer {
+            if header.observer != *observer {
+                header.observer = observer.clone();
+                changes.push("OBSERVER");
+            }
+        }
+        if let Some(agency) = &self.agency {
+            if header.agency != *agency {
+                header.agency = agency.clone();
+                changes.push("AGENCY");
+            }
+        }
+        if let Some(receiver) = &self.receiver {
+            if header.rcvr.as_ref() != Some(receiver) {
+                header.rcvr = Some(receiver.clone());
+                changes.push("REC # / TYPE / VERS");
+            }
+        }
+        if let Some(antenna) = &self.antenna {
+            let current = header.rcvr_antenna.clone().unwrap_or_default();
+            let merged = antenna.merge(&current);
+            if merged.model != current.model || merged.sn != current.sn {
+                changes.push("ANT # / TYPE");
+            }
+            if (merged.height, merged.eastern, merged.northern)
+                != (current.height, current.eastern, current.northern)
+            {
+                changes.push("ANTENNA: DELTA H/E/N");
+            }
+            if header.rcvr_antenna.as_ref() != Some(&merged) {
+                header.rcvr_antenna = Some(merged);
+            }
+        }
+        if let Some(position) = self.position {
+            let position = GroundPosition::from_ecef_wgs84(position);
+            if header.ground_position != Some(position) {
+                header.ground_position = Some(position);
+                changes.push("APPROX POSITION XYZ");
+            }
+        }
+        changes
+    }
+}
+
+/*
+ * Parses the sites database: TOML, or JSON when named "*.json"
+ */
+fn load_database(path: &Path) -> Result<BTreeMap<String, Site>, Error> {
+    let content = std::fs::read_to_string(path)?;
+    let sites = match path.extension() {
+        Some(ext) if ext == "json" => serde_json::from_str(&content).map_err(|e| e.to_string()),
+        _ => toml::from_str(&content).map_err(|e| e.to_string()),
+    };
+    sites.map_err(Error::InvalidSitesDatabase)
+}
+
+/*
+ * Header substitution opmode: each file is parsed and rewritten on its own
+ */
+pub fn header_apply(cli: &Cli, matches: &ArgMatches) -> Result<(), Error> {
+    let name = matches.get_one::<String>("site").unwrap();
+    let database = matches.get_one::<PathBuf>("database").unwrap();
+    let sites = load_database(database)?;
+    let site = sites
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, site)| site)
+        .ok_or_else(|| Error::UnknownSite(name.to_string()))?;
+
+    let workspace = cli.workspace(&name.to_uppercase());
+    let stamp = match cli.matches.get_flag("no-stamp") {
+        true => Stamp::reproducible(),
+        false => Stamp::default(),
+    };
+
+    let (mut rewritten, mut failures) = (0_usize, 0_usize);
+    for path in cli.input_paths() {
+        let fp = path.to_string_lossy().to_string();
+        let mut rinex = match Rinex::from_file(&fp) {
+            Ok(rinex) => rinex,
+            Err(e) => {
+                failures += 1;
+                error!("failed to parse \"{}\": {}", fp, e);
+                continue;
+            },
+        };
+        let changes = site.apply(&mut rinex.header);
+        if changes.is_empty() {
+            info!("\"{}\": header already matches \"{}\"", fp, name);
+        } else {
+            info!("\"{}\": substituted {}", fp, changes.join(", "));
+        }
+        let filename = path
+            .file_name()
+            .ok_or_else(|| Error::InvalidOutputPath(fp.clone()))?;
+        let output = workspace.join(filename).to_string_lossy().to_string();
+        rinex.to_file_with_stamp(&output, &stamp)?;
+        rewritten += 1;
+    }
+
+    info!(
+        "{} files rewritten into \"{}\" ({} failures)",
+        rewritten,
+        workspace.display(),
+        failures
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn header() -> Header {
+        Header::default().with_receiver_antenna(
+            Antenna::default()
+                .with_model("TRM57971.00     NONE")
+                .with_serial_number("1441112501")
+                .with_height(0.0508),
+        )
+    }
+    #[test]
+    fn antenna_merge() {
+        let mut header = header();
+        let site: Site =
+            toml::from_str("antenna = { sn = \"1441112502\", eastern = 0.001 }").unwrap();
+        let changes = site.apply(&mut header);
+        assert_eq!(changes, vec!["ANT # / TYPE", "ANTENNA: DELTA H/E/N"]);
+        let antenna = header.rcvr_antenna.as_ref().unwrap();
+        assert_eq!(antenna.model, "TRM57971.00     NONE");
+        assert_eq!(antenna.sn, "1441112502");
+        assert_eq!(antenna.height, Some(0.0508));
+        assert_eq!(antenna.eastern, Some(0.001));
+        assert_eq!(antenna.northern, None);
+        // applying twice does not change anything
+        assert!(site.apply(&mut header).is_empty());
+    }
+    #[test]
+    fn undescribed_fields() {
+        let mut header = header();
+        let site: Site = toml::from_str("marker = \"ABMF\"").unwrap();
+        assert_eq!(site.apply(&mut header), vec!["MARKER NAME"]);
+        assert_eq!(header.rcvr_antenna, self::header().rcvr_antenna);
+        assert!(header.rcvr.is_none());
+    }
+}
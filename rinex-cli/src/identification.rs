@@ -10,7 +10,7 @@ use std::str::FromStr;
 
 use itertools::Itertools;
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use map_3d::{ecef2geodetic, Ellipsoid};
 
@@ -212,7 +212,7 @@ struct EpochReport {
     pub first: String,
     pub last: String,
     pub system: String,
-    pub sampling: HashMap<String, usize>,
+    pub sampling: BTreeMap<String, usize>,
 }
 
 impl EpochReport {
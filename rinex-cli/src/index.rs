@@ -0,0 +1,71 @@
+//! Archive indexing: catalog of RINEX holdings, and queries
+use crate::Error;
+use clap::ArgMatches;
+use rinex::catalog::{Catalog, Query};
+use rinex::prelude::{Constellation, RinexType};
+use std::fs::File;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/*
+ * Index opmode: builds a new catalog, or queries an existing one
+ */
+pub fn index(matches: &ArgMatches) -> Result<(), Error> {
+    if let Some(("query", submatches)) = matches.subcommand() {
+        return query(submatches);
+    }
+    let archive = matches.get_one::<PathBuf>("archive").unwrap();
+    let path = matches.get_one::<PathBuf>("catalog").unwrap();
+
+    let catalog = Catalog::from_directory(archive)?;
+    let fd = File::create(path)?;
+    serde_json::to_writer_pretty(fd, &catalog)?;
+    info!(
+        "\"{}\": {} files cataloged in \"{}\"",
+        archive.display(),
+        catalog.entries.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+fn query(matches: &ArgMatches) -> Result<(), Error> {
+    let path = matches.get_one::<PathBuf>("catalog").unwrap();
+    let content = std::fs::read_to_string(path)?;
+    let catalog: Catalog = serde_json::from_str(&content)?;
+
+    let query = Query {
+        station: matches.get_one::<String>("station").cloned(),
+        year: matches.get_one::<i32>("year").copied(),
+        doy: matches.get_one::<u32>("doy").copied(),
+        constellation: match matches.get_one::<String>("constellation") {
+            Some(gnss) => Some(
+                Constellation::from_str(gnss)
+                    .map_err(|_| Error::InvalidConstellation(gnss.to_string()))?,
+            ),
+            None => None,
+        },
+        rinex_type: match matches.get_one::<String>("type") {
+            Some(rinex_type) => Some(
+                RinexType::from_str(rinex_type)
+                    .map_err(|_| Error::InvalidRinexType(rinex_type.to_string()))?,
+            ),
+            None => None,
+        },
+    };
+
+    for entry in catalog.query(&query) {
+        println!(
+            "{:<10}{:<18}{:>4} {:>10} {}",
+            entry.station.as_deref().unwrap_or("UNKNOWN"),
+            format!("{}", entry.rinex_type),
+            entry.year.map(|y| y.to_string()).unwrap_or_default(),
+            entry
+                .doy
+                .map(|doy| format!("{:03}", doy))
+                .unwrap_or_default(),
+            entry.path.display()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! Fast integrity check
+use crate::Error;
+use clap::ArgMatches;
+use rinex::integrity::{check_file, Issue};
+use std::path::PathBuf;
+
+/*
+ * Integrity opmode: scans each file without parsing it,
+ * and reports the findings with their byte offset
+ */
+pub fn integrity(matches: &ArgMatches) -> Result<(), Error> {
+    let mut failures = 0;
+    for path in matches.get_many::<PathBuf>("files").unwrap() {
+        match check_file(path.to_string_lossy().as_ref()) {
+            Ok(report) => {
+                if report.is_ok() {
+                    println!(
+                        "PASS {} ({} bytes, {} lines)",
+                        path.display(),
+                        report.bytes,
+                        report.lines
+                    );
+                } else {
+                    failures += 1;
+                    let status = match report.is_truncated() {
+                        true => "truncated",
+                        false => "corrupt",
+                    };
+                    println!("FAIL {}: {}", path.display(), status);
+                }
+                for finding in &report.findings {
+                    let severity = match finding.issue {
+                        Issue::NonAscii => "warning",
+                        _ => "error",
+                    };
+                    let lines = match finding.lines {
+                        1 => format!("line {}", finding.line),
+                        n => format!("lines {}-{}", finding.line, finding.line + n - 1),
+                    };
+                    println!(
+                        "  {}: offset {} ({}): {}",
+                        severity, finding.offset, lines, finding.issue
+                    );
+                }
+            },
+            Err(e) => {
+                failures += 1;
+                println!("FAIL {}: {}", path.display(), e);
+            },
+        }
+    }
+    if failures > 0 {
+        return Err(Error::IntegrityCheckFailure(failures));
+    }
+    Ok(())
+}
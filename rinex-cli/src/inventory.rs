@@ -0,0 +1,120 @@
+//! Observables and constellations enumeration, from the file body
+use crate::Error;
+use clap::ArgMatches;
+use rinex::prelude::{Constellation, Epoch, Observable, Rinex, SV};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+/*
+ * Parses all files passed on the command line
+ */
+fn parse(matches: &ArgMatches) -> Result<Vec<(PathBuf, Rinex)>, Error> {
+    let mut ret = Vec::new();
+    for path in matches.get_many::<PathBuf>("files").unwrap() {
+        let rinex = Rinex::from_path(path)?;
+        ret.push((path.clone(), rinex));
+    }
+    Ok(ret)
+}
+
+/// Lists observables present in each file body, per constellation
+pub fn observables(matches: &ArgMatches) -> Result<(), Error> {
+    for (path, rinex) in parse(matches)? {
+        println!("{}", path.display());
+        if rinex.is_observation_rinex() {
+            let mut counts: BTreeMap<Constellation, BTreeMap<Observable, usize>> = BTreeMap::new();
+            for (_, (_, vehicles)) in rinex.observation() {
+                for (sv, observations) in vehicles {
+                    for observable in observations.keys() {
+                        *counts
+                            .entry(sv.constellation)
+                            .or_default()
+                            .entry(observable.clone())
+                            .or_default() += 1;
+                    }
+                }
+            }
+            for (constellation, observables) in counts {
+                println!("  {}", constellation);
+                for (observable, count) in observables {
+                    println!("    {:<6}{:>10}", observable.to_string(), count);
+                }
+            }
+        } else if rinex.is_meteo_rinex() {
+            let mut counts: BTreeMap<Observable, usize> = BTreeMap::new();
+            for (_, observations) in rinex.meteo() {
+                for observable in observations.keys() {
+                    *counts.entry(observable.clone()).or_default() += 1;
+                }
+            }
+            for (observable, count) in counts {
+                println!("    {:<6}{:>10}", observable.to_string(), count);
+            }
+        } else {
+            error!(
+                "\"{}\": observables only apply to Observation and Meteo RINEX",
+                path.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+/*
+ * Per constellation content
+ */
+#[derive(Default)]
+struct Inventory {
+    sv: BTreeSet<SV>,
+    epochs: BTreeSet<Epoch>,
+    count: usize,
+}
+
+/// Lists constellations present in each file body
+pub fn constellations(matches: &ArgMatches) -> Result<(), Error> {
+    for (path, rinex) in parse(matches)? {
+        println!("{}", path.display());
+        let mut inventory: BTreeMap<Constellation, Inventory> = BTreeMap::new();
+        let label = if rinex.is_observation_rinex() {
+            for ((t, _), (_, vehicles)) in rinex.observation() {
+                for (sv, observations) in vehicles {
+                    let entry = inventory.entry(sv.constellation).or_default();
+                    entry.sv.insert(*sv);
+                    entry.epochs.insert(*t);
+                    entry.count += observations.len();
+                }
+            }
+            "observations"
+        } else if rinex.is_navigation_rinex() {
+            for (t, frames) in rinex.navigation() {
+                for (_, sv, _) in frames.iter().filter_map(|fr| fr.as_eph()) {
+                    let entry = inventory.entry(sv.constellation).or_default();
+                    entry.sv.insert(sv);
+                    entry.epochs.insert(*t);
+                    entry.count += 1;
+                }
+            }
+            "ephemerides"
+        } else {
+            error!(
+                "\"{}\": constellations only apply to Observation and Navigation RINEX",
+                path.display()
+            );
+            continue;
+        };
+        println!(
+            "  {:<14}{:>6}{:>10}{:>14}",
+            "constellation", "sv", "epochs", label
+        );
+        for (constellation, content) in inventory {
+            println!(
+                "  {:<14}{:>6}{:>10}{:>14}",
+                constellation.to_string(),
+                content.sv.len(),
+                content.epochs.len(),
+                content.count
+            );
+        }
+    }
+    Ok(())
+}
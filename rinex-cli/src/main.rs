@@ -2,13 +2,40 @@
 //! Refer to README for command line arguments.    
 //! Homepage: <https://github.com/georust/rinex-cli>
 
+#[cfg(feature = "plot")]
 mod analysis; // basic analysis
+mod bench; // throughput benchmarking
+mod campaign; // archive statistics
 mod cli; // command line interface
+mod epochs; // epochs listing
+#[cfg(feature = "sql")]
+mod export; // SQLite export
+#[cfg(feature = "fetch")]
+mod fetch; // products download
 mod fops;
+#[cfg(feature = "plot")]
 mod graph;
+mod header_apply; // header substitution from a sites database
 mod identification; // high level identification/macros
+mod index; // archive indexing
+mod integrity; // fast integrity check
+mod inventory; // observables and constellations enumeration
+mod merge_nav; // multi station navigation merge
+mod meta; // teqc like metadata dump
 mod positioning;
+mod ppp; // static PPP (float solution)
+#[cfg(feature = "progress")]
+mod progress; // progress bars
 mod qc; // QC report generator // plotting operations // file operation helpers // graphical analysis // positioning + CGGTTS opmode
+mod report; // report sinks
+mod residuals; // known position residual analysis
+mod stack; // sidereal stacking (multipath characterization)
+mod sv_health; // satellite health timeline
+mod tec; // observed TEC versus ionosphere maps
+#[cfg(feature = "tui")]
+mod tui; // interactive terminal browser
+mod verify_crx; // CRINEX integrity verification
+mod visibility; // satellite visibility prediction
 
 mod preprocessing;
 use preprocessing::preprocess;
@@ -18,7 +45,8 @@ extern crate gnss_rtk as rtk;
 
 use cli::{Cli, Context};
 
-use env_logger::{Builder, Target};
+use env_logger::{Builder, Env, Target};
+use std::fs::File;
 
 #[macro_use]
 extern crate log;
@@ -33,26 +61,153 @@ pub enum Error {
     MissingObservationRinex,
     #[error("missing (BRDC) NAV RINEX")]
     MissingNavigationRinex,
+    #[error("RINEX2 does not support multi constellation Navigation files")]
+    MixedNavigationV2,
+    #[error("missing 2D IONEX")]
+    MissingIonex,
+    #[error("missing receiver position")]
+    MissingPosition,
     #[error("merge ops failure")]
     MergeError(#[from] rinex::merge::Error),
     #[error("split ops failure")]
     SplitError(#[from] rinex::split::Error),
+    #[error("compression failure")]
+    CompressionError(#[from] rinex::hatanaka::Error),
     #[error("failed to create QC report: permission denied!")]
     QcReportCreationError,
     #[error("positioning solver error")]
     PositioningSolverError(#[from] positioning::Error),
+    #[error("ppp error")]
+    PPPError(#[from] rinex::ppp::Error),
+    #[error("failed to format solutions")]
+    FormatterError(#[from] positioning::solutions::formatter::Error),
+    #[error("campaign error")]
+    CampaignError(#[from] rinex::campaign::Error),
+    #[error("i/o error")]
+    IoError(#[from] std::io::Error),
+    #[error("json error")]
+    JsonError(#[from] serde_json::Error),
+    #[error("invalid satellite \"{0}\"")]
+    InvalidSatellite(String),
+    #[error("invalid observable \"{0}\"")]
+    InvalidObservable(String),
+    #[error("invalid constellation \"{0}\"")]
+    InvalidConstellation(String),
+    #[error("invalid rinex type \"{0}\"")]
+    InvalidRinexType(String),
+    #[cfg(feature = "fetch")]
+    #[error("invalid product \"{0}\"")]
+    InvalidProduct(String),
+    #[cfg(feature = "fetch")]
+    #[error("failed to download any product: {0}")]
+    FetchFailure(String),
+    #[error("invalid date \"{0}\"")]
+    InvalidDate(String),
+    #[error("invalid position \"{0}\"")]
+    InvalidPosition(String),
+    #[error("{0} file(s) failed the integrity check")]
+    IntegrityCheckFailure(usize),
+    #[error("invalid report sink \"{0}\"")]
+    InvalidReportSink(String),
+    #[cfg(feature = "sql")]
+    #[error("sqlite error")]
+    SqliteError(#[from] rusqlite::Error),
+    #[cfg(feature = "sql")]
+    #[error("none of the {0} input file(s) could be exported")]
+    ExportFailure(usize),
+    #[error("failed to load --{0} \"{1}\": {2}")]
+    CompanionFileError(&'static str, String, String),
+    #[error("invalid sites database: {0}")]
+    InvalidSitesDatabase(String),
+    #[error("site \"{0}\" not found in database")]
+    UnknownSite(String),
+    #[error("failed to determine output file name for \"{0}\"")]
+    InvalidOutputPath(String),
 }
 
 pub fn main() -> Result<(), Error> {
-    let mut builder = Builder::from_default_env();
+    // Build context defined by user
+    let cli = Cli::new();
+
+    /*
+     * Diagnostics (library included): -q/-v levels prevail over RUST_LOG,
+     * warnings and errors are reported by default
+     */
+    let mut builder = Builder::from_env(Env::default().default_filter_or("warn"));
+    if let Some(level) = cli.log_level() {
+        builder.filter_level(level);
+    }
+    match cli.log_file() {
+        Some(path) => {
+            let fd = File::create(path)?;
+            builder.target(Target::Pipe(Box::new(fd)));
+        },
+        None => {
+            builder.target(Target::Stdout);
+        },
+    }
     builder
-        .target(Target::Stdout)
         .format_timestamp_secs()
         .format_module_path(false)
         .init();
 
-    // Build context defined by user
-    let cli = Cli::new();
+    /*
+     * Campaign, epochs, fetch, index, integrity, inventory, merge-nav, residuals,
+     * stack, sv-health, tec, tui, verify-crx, visibility and bench opmodes
+     * do not rely on a data context
+     */
+    match cli.matches.subcommand() {
+        Some(("bench", submatches)) => return bench::bench(submatches),
+        Some(("campaign", submatches)) => return campaign::campaign(&cli, submatches),
+        Some(("epochs", submatches)) => return epochs::epochs(submatches),
+        #[cfg(feature = "fetch")]
+        Some(("fetch", submatches)) => return fetch::fetch(&cli, submatches),
+        Some(("index", submatches)) => return index::index(submatches),
+        Some(("integrity", submatches)) => return integrity::integrity(submatches),
+        Some(("merge-nav", submatches)) => return merge_nav::merge_nav(&cli, submatches),
+        Some(("observables", submatches)) => return inventory::observables(submatches),
+        Some(("constellations", submatches)) => return inventory::constellations(submatches),
+        Some(("residuals", submatches)) => return residuals::residuals(&cli, submatches),
+        Some(("stack", submatches)) => return stack::stack(&cli, submatches),
+        Some(("sv-health", submatches)) => return sv_health::sv_health(&cli, submatches),
+        Some(("tec", submatches)) => return tec::tec(&cli, submatches),
+        #[cfg(feature = "tui")]
+        Some(("tui", submatches)) => return tui::tui(submatches),
+        Some(("verify-crx", submatches)) => return verify_crx::verify_crx(submatches),
+        Some(("visibility", submatches)) => return visibility::visibility(&cli, submatches),
+        _ => {},
+    }
+    if cli.input_files().is_empty() && cli.input_directories().is_empty() {
+        error!("no input files: use --fp and/or --dir");
+        return Ok(());
+    }
+
+    /*
+     * SQLite export does not build a single data context:
+     * each file is exported on its own
+     */
+    #[cfg(feature = "sql")]
+    if let Some(("export", submatches)) = cli.matches.subcommand() {
+        return export::export(&cli, submatches);
+    }
+
+    /*
+     * Header substitution rewrites each file on its own
+     */
+    if let Some(("header-apply", submatches)) = cli.matches.subcommand() {
+        return header_apply::header_apply(&cli, submatches);
+    }
+
+    /*
+     * Batch QC does not build a single data context either:
+     * each file is analyzed on its own
+     */
+    if let Some(("quality-check", submatches)) = cli.matches.subcommand() {
+        if submatches.get_flag("per-file") {
+            return qc::qc_batch(&cli, submatches);
+        }
+    }
+
     let mut ctx = Context::from_cli(&cli)?;
 
     /*
@@ -64,12 +219,22 @@ pub fn main() -> Result<(), Error> {
      * Exclusive opmodes
      */
     match cli.matches.subcommand() {
+        #[cfg(feature = "plot")]
         Some(("graph", submatches)) => {
             graph::graph_opmode(&ctx, submatches)?;
         },
         Some(("identify", submatches)) => {
             identification::dataset_identification(&ctx.data, submatches);
         },
+        Some(("meta", _)) => {
+            meta::metadata(&ctx.data);
+        },
+        Some(("compress", submatches)) => {
+            fops::compress(&ctx, submatches)?;
+        },
+        Some(("extract", submatches)) => {
+            fops::extract(&ctx, submatches)?;
+        },
         Some(("merge", submatches)) => {
             fops::merge(&ctx, submatches)?;
         },
@@ -82,6 +247,9 @@ pub fn main() -> Result<(), Error> {
         Some(("positioning", submatches)) => {
             positioning::precise_positioning(&ctx, submatches)?;
         },
+        Some(("ppp", submatches)) => {
+            ppp::static_ppp(&ctx, submatches)?;
+        },
         Some(("sub", submatches)) => {
             fops::substract(&ctx, submatches)?;
         },
@@ -0,0 +1,67 @@
+//! Navigation merge (BRDC generation)
+use crate::{cli::Cli, Error};
+use clap::ArgMatches;
+use rinex::{
+    merge::Merge,
+    prelude::{Constellation, Rinex, Stamp},
+};
+use std::path::PathBuf;
+
+/*
+ * Number of messages in given Navigation file
+ */
+fn nb_frames(rinex: &Rinex) -> usize {
+    rinex
+        .record
+        .as_nav()
+        .map(|record| record.values().map(|frames| frames.len()).sum())
+        .unwrap_or(0)
+}
+
+/// Merges the Navigation files of several stations, in the order
+/// they were given, then drops the redundant ephemerides.
+pub fn merge_nav(cli: &Cli, matches: &ArgMatches) -> Result<(), Error> {
+    let mut brdc = Option::<Rinex>::None;
+    let mut total = 0;
+    for path in matches.get_many::<PathBuf>("files").unwrap() {
+        let rinex = Rinex::from_path(path)?;
+        if !rinex.is_navigation_rinex() {
+            return Err(Error::MissingNavigationRinex);
+        }
+        let frames = nb_frames(&rinex);
+        info!("{}: {} messages", path.display(), frames);
+        total += frames;
+        match &mut brdc {
+            Some(brdc) => brdc.merge_mut(&rinex)?,
+            None => brdc = Some(rinex),
+        }
+    }
+    let mut brdc = brdc.ok_or(Error::MissingNavigationRinex)?;
+    brdc.nav_dedup_mut();
+
+    if brdc.header.version.major < 3 && brdc.header.constellation == Some(Constellation::Mixed) {
+        return Err(Error::MixedNavigationV2);
+    }
+    if let Some(nav) = &brdc.header.nav {
+        info!(
+            "{} ionospheric model(s), {} time system correction(s)",
+            nav.ionod_corrections.len(),
+            nav.time_corrections.len()
+        );
+    }
+    let kept = nb_frames(&brdc);
+    info!(
+        "{} messages, {} redundant messages dropped",
+        kept,
+        total - kept
+    );
+
+    let stamp = match cli.matches.get_flag("no-stamp") {
+        true => Stamp::reproducible(),
+        false => Stamp::default(),
+    };
+    let output = matches.get_one::<PathBuf>("output").unwrap();
+    brdc.to_file_with_stamp(output.to_string_lossy().as_ref(), &stamp)?;
+    info!("\"{}\" has been generated", output.display());
+    Ok(())
+}
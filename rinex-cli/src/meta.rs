@@ -0,0 +1,119 @@
+//! teqc "+meta" compatible metadata dump
+use rinex::prelude::{Duration, Epoch, Rinex, RnxContext};
+use std::path::Path;
+
+use itertools::Itertools;
+
+/*
+ * Prints one teqc like "label: value" line
+ */
+fn meta_line(label: &str, value: &str) {
+    println!("{:<32}{}", format!("{}:", label), value);
+}
+
+/*
+ * Formats an Epoch the way teqc does
+ */
+fn meta_epoch(t: Epoch) -> String {
+    let (y, m, d, hh, mm, ss, ns) = t.to_gregorian_utc();
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
+        y,
+        m,
+        d,
+        hh,
+        mm,
+        ss,
+        ns / 1_000_000
+    )
+}
+
+/*
+ * Dumps metadata for a single file
+ */
+fn file_metadata(path: &Path) {
+    let rinex = match Rinex::from_path(&path.to_path_buf()) {
+        Ok(rinex) => rinex,
+        Err(e) => {
+            error!("failed to parse \"{}\": {:?}", path.display(), e);
+            return;
+        },
+    };
+    let header = &rinex.header;
+
+    meta_line("filename", &path.to_string_lossy());
+    meta_line("file format", "RINEX");
+    if let Ok(metadata) = std::fs::metadata(path) {
+        meta_line("file size (bytes)", &metadata.len().to_string());
+    }
+    if let Some(t) = rinex.first_epoch() {
+        meta_line("start date & time", &meta_epoch(t));
+    }
+    if let Some(t) = rinex.last_epoch() {
+        meta_line("final date & time", &meta_epoch(t));
+    }
+    let interval: Option<Duration> = match rinex.is_navigation_rinex() {
+        true => None, // not sampled
+        false => header
+            .sampling_interval
+            .map(|dt| dt.duration())
+            .or_else(|| rinex.dominant_sample_rate()),
+    };
+    if let Some(interval) = interval {
+        meta_line("sample interval", &format!("{:.4}", interval.to_seconds()));
+        let missing: i64 = rinex
+            .data_gaps(Some(interval))
+            .map(|(_, gap)| (gap.to_seconds() / interval.to_seconds()).round() as i64 - 1)
+            .sum();
+        meta_line("possible missing epochs", &missing.to_string());
+    }
+    if let Some(marker) = &header.geodetic_marker {
+        let code = marker.name.chars().take(4).collect::<String>();
+        meta_line("4-char station code", &code.to_uppercase());
+        meta_line("station name", &marker.name);
+        meta_line("station ID number", &marker.number().unwrap_or_default());
+        if let Some(monument) = marker.marker_type {
+            meta_line("monument type", &format!("{:?}", monument));
+        }
+    }
+    if let Some(antenna) = &header.rcvr_antenna {
+        meta_line("antenna ID number", &antenna.sn);
+        meta_line("antenna type", &antenna.model);
+    }
+    if let Some(position) = header.ground_position {
+        let (lat, lon, alt) = position.to_geodetic();
+        meta_line("antenna latitude (deg)", &format!("{:.6}", lat));
+        meta_line("antenna longitude (deg)", &format!("{:.6}", lon));
+        meta_line("antenna elevation (m)", &format!("{:.3}", alt));
+    }
+    if let Some(antenna) = &header.rcvr_antenna {
+        if let Some(height) = antenna.height {
+            meta_line("antenna height (m)", &format!("{:.4}", height));
+        }
+    }
+    if let Some(rcvr) = &header.rcvr {
+        meta_line("receiver ID number", &rcvr.sn);
+        meta_line("receiver type", &rcvr.model);
+        meta_line("receiver firmware", &rcvr.firmware);
+    }
+    let observables = rinex
+        .observable()
+        .map(|obs| obs.to_string())
+        .unique()
+        .sorted()
+        .join(" ");
+    if !observables.is_empty() {
+        meta_line("observation types", &observables);
+    }
+    println!();
+}
+
+/*
+ * teqc "+meta" opmode: dumps metadata of every input RINEX file
+ */
+pub fn metadata(ctx: &RnxContext) {
+    let paths = [ctx.obs_paths(), ctx.meteo_paths(), ctx.nav_paths()];
+    for path in paths.into_iter().flatten().flatten() {
+        file_metadata(path);
+    }
+}
@@ -95,7 +95,10 @@ where
     let mut trk_midpoint = Option::<Epoch>::None;
     let mut trackers = HashMap::<(SV, Observable), SVTracker>::new();
 
-    for ((t, flag), (_clk, vehicles)) in obs_data.observation() {
+    // pseudo ranges already corrected by the receiver
+    let clock_offset_applied = obs_data.recvr_clock_offset_applied();
+
+    for ((t, flag), (clk, vehicles)) in obs_data.observation() {
         /*
          * we only consider "OK" Epochs
          */
@@ -152,10 +155,11 @@ where
                         value: data.obs,
                     });
                 } else if observable.is_phase_observable() {
+                    // phase is expressed in cycles, the solver expects meters
                     phase = Some(Observation {
                         frequency,
                         snr: { data.snr.map(|snr| snr.into()) },
-                        value: data.obs,
+                        value: data.to_meters(carrier),
                     });
                 }
 
@@ -237,8 +241,12 @@ where
                         let azimuth = pvt_data.azimuth;
                         let elevation = pvt_data.elevation;
 
-                        let refsys = pvt_solution.dt;
-                        let refsv = pvt_solution.dt + clock_corr.to_seconds();
+                        let mut refsys = pvt_solution.dt;
+                        if clock_offset_applied {
+                            // actual receiver clock offset
+                            refsys += clk.unwrap_or(0.0);
+                        }
+                        let refsv = refsys + clock_corr.to_seconds();
 
                         /*
                          * TROPO : always present
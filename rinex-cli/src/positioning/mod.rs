@@ -5,6 +5,9 @@ mod ppp; // precise point positioning
 use ppp::post_process as ppp_post_process;
 use ppp::PostProcessingError as PPPPostProcessingError;
 
+pub mod solutions; // solutions formatting
+use solutions::formatter::SolverMode;
+
 mod cggtts; // CGGTTS special solver
 use cggtts::post_process as cggtts_post_process;
 use cggtts::PostProcessingError as CGGTTSPostProcessingError;
@@ -19,7 +22,7 @@ use rtk::prelude::{
     NgModel, Solver, Vector3,
 };
 
-use map_3d::{ecef2geodetic, rad2deg, Ellipsoid};
+use map_3d::{ecef2geodetic, Ellipsoid};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -47,7 +50,7 @@ pub fn tropo_components(meteo: Option<&Rinex>, t: Epoch, lat_ddeg: f64) -> Optio
             Observable::ZenithDryDelay => {
                 let (x, y, z, _) = s.position?;
                 let (lat, _, _) = ecef2geodetic(x, y, z, Ellipsoid::WGS84);
-                let lat = rad2deg(lat);
+                let lat = lat.to_degrees();
                 if (lat - lat_ddeg).abs() < MAX_LATDDEG_DELTA {
                     let value = rnx
                         .zenith_dry_delay()
@@ -63,7 +66,7 @@ pub fn tropo_components(meteo: Option<&Rinex>, t: Epoch, lat_ddeg: f64) -> Optio
             Observable::ZenithWetDelay => {
                 let (x, y, z, _) = s.position?;
                 let (mut lat, _, _) = ecef2geodetic(x, y, z, Ellipsoid::WGS84);
-                lat = rad2deg(lat);
+                lat = lat.to_degrees();
                 if (lat - lat_ddeg).abs() < MAX_LATDDEG_DELTA {
                     let value = rnx
                         .zenith_wet_delay()
@@ -134,7 +137,7 @@ pub fn kb_model(nav: &Rinex, t: Epoch) -> Option<KbModel> {
         })
     } else {
         /* RINEX 3 case */
-        let iono_corr = nav.header.ionod_correction?;
+        let iono_corr = nav.header.ionod_correction()?;
         iono_corr.as_klobuchar().map(|kb_model| KbModel {
             h_km: 350.0, //TODO improve this
             alpha: kb_model.alpha,
@@ -264,7 +267,11 @@ pub fn precise_positioning(ctx: &Context, matches: &ArgMatches) -> Result<(), Er
         /* PPP */
         let pvt_solutions = ppp::resolve(ctx, solver, rx_lat_ddeg);
         /* save solutions (graphs, reports..) */
-        ppp_post_process(ctx, pvt_solutions, matches)?;
+        let mode = match method {
+            Method::SPP => SolverMode::Single,
+            Method::PPP => SolverMode::PppKinematic,
+        };
+        ppp_post_process(ctx, pvt_solutions, mode, matches)?;
     }
     Ok(())
 }
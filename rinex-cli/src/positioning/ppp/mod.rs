@@ -2,7 +2,7 @@
 use crate::cli::Context;
 use crate::positioning::{bd_model, kb_model, ng_model, tropo_components};
 use rinex::carrier::Carrier;
-use rinex::navigation::Ephemeris;
+use rinex::navigation::{Ephemeris, TimeSystem};
 use rinex::prelude::SV;
 use std::collections::BTreeMap;
 
@@ -36,7 +36,10 @@ where
         None => false,
     };
 
-    for ((t, flag), (_clk, vehicles)) in obs_data.observation() {
+    // pseudo ranges already corrected by the receiver
+    let clock_offset_applied = obs_data.recvr_clock_offset_applied();
+
+    for ((t, flag), (clk, vehicles)) in obs_data.observation() {
         let mut candidates = Vec::<Candidate>::with_capacity(4);
 
         if !flag.is_ok() {
@@ -103,6 +106,21 @@ where
                 toe,
             );
 
+            /*
+             * Broadcast clocks are referenced to the system time of each constellation:
+             * align them to the system time of the observations
+             */
+            let clock_corr = match TimeSystem::from_timescale(t.time_scale) {
+                Some(reference) => {
+                    let system = TimeSystem::GNSS(sv.constellation);
+                    match nav_data.time_system_offset(system, reference, *t) {
+                        Some(offset) => clock_corr + offset,
+                        None => clock_corr,
+                    }
+                },
+                None => clock_corr,
+            };
+
             let mut codes = Vec::<Observation>::new();
             let mut phases = Vec::<Observation>::new();
             let mut dopplers = Vec::<Observation>::new();
@@ -118,10 +136,11 @@ where
                             value: data.obs,
                         });
                     } else if observable.is_phase_observable() {
+                        // phase is expressed in cycles, the solver expects meters
                         phases.push(Observation {
                             frequency,
                             snr: { data.snr.map(|snr| snr.into()) },
-                            value: data.obs,
+                            value: data.to_meters(carrier),
                         });
                     } else if observable.is_doppler_observable() {
                         dopplers.push(Observation {
@@ -170,7 +189,12 @@ where
             &iono_bias,
             &tropo_bias,
         ) {
-            Ok((t, pvt)) => {
+            Ok((t, mut pvt)) => {
+                if clock_offset_applied {
+                    // resolved offset is the residual offset:
+                    // report the actual receiver clock offset
+                    pvt.dt += clk.unwrap_or(0.0);
+                }
                 debug!("{:?} : {:?}", t, pvt);
                 solutions.insert(t, pvt);
             },
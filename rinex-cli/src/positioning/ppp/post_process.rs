@@ -1,57 +1,185 @@
 use crate::cli::Context;
 use clap::ArgMatches;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::Write;
 use thiserror::Error;
 
+use rinex_qc_traits::{ReportSink, ReportTable};
+
 use hifitime::Epoch;
 use rtk::prelude::PVTSolution;
 
-extern crate gpx;
-use gpx::{errors::GpxError, Gpx, GpxVersion, Waypoint};
-
-use kml::{
-    types::AltitudeMode, types::Coord as KmlCoord, types::Geometry as KmlGeometry,
-    types::KmlDocument, types::Placemark, types::Point as KmlPoint, Kml, KmlVersion, KmlWriter,
+use crate::positioning::solutions::{
+    formatter::{self, Error as FormatterError, Format, SolverMode},
+    Solution,
 };
 
-extern crate geo_types;
-use geo_types::Point as GeoPoint;
-
+#[cfg(feature = "plot")]
 use plotly::color::NamedColor;
+#[cfg(feature = "plot")]
 use plotly::common::Mode;
+#[cfg(feature = "plot")]
 use plotly::common::{Marker, MarkerSymbol};
+#[cfg(feature = "plot")]
 use plotly::layout::MapboxStyle;
+#[cfg(feature = "plot")]
 use plotly::ScatterMapbox;
 
+#[cfg(feature = "plot")]
 use crate::fops::open_with_web_browser;
+#[cfg(feature = "plot")]
 use crate::graph::{build_3d_chart_epoch_label, build_chart_epoch_axis, PlotContext};
-use map_3d::{ecef2geodetic, rad2deg, Ellipsoid};
+use map_3d::{ecef2geodetic, Ellipsoid};
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("std::io error")]
     IOError(#[from] std::io::Error),
-    #[error("failed to generate gpx track")]
-    GpxError(#[from] GpxError),
-    #[error("failed to generate kml track")]
-    KmlError(#[from] kml::Error),
+    #[error("failed to format solutions")]
+    FormatterError(#[from] FormatterError),
+    #[error("failed to open report sink: {0}")]
+    ReportSinkError(String),
 }
 
 pub fn post_process(
     ctx: &Context,
     results: BTreeMap<Epoch, PVTSolution>,
+    mode: SolverMode,
     matches: &ArgMatches,
 ) -> Result<(), Error> {
-    // create a dedicated plot context
-    let mut plot_ctx = PlotContext::new();
-
     let (x, y, z) = ctx.rx_ecef.unwrap(); // cannot fail at this point
 
     let (lat_rad, lon_rad, _) = ecef2geodetic(x, y, z, Ellipsoid::WGS84);
-    let lat_ddeg = rad2deg(lat_rad);
-    let lon_ddeg = rad2deg(lon_rad);
+    let lat_ddeg = lat_rad.to_degrees();
+    let lon_ddeg = lon_rad.to_degrees();
+
+    #[cfg(feature = "plot")]
+    plot_solutions(ctx, &results, (x, y, z), (lat_ddeg, lon_ddeg));
+
+    /*
+     * Generate txt
+     */
+    let txtpath = ctx.workspace.join("PVT.csv");
+    let txtfile = txtpath.to_string_lossy().to_string();
+    let mut fd = File::create(&txtfile)?;
+
+    let mut solutions = Vec::<Solution>::with_capacity(results.len());
+    let mut table = ReportTable::new(
+        "solutions",
+        &[
+            "epoch",
+            "x_ecef [m]",
+            "y_ecef [m]",
+            "z_ecef [m]",
+            "hdop",
+            "vdop",
+            "tdop",
+            "clock bias [s]",
+        ],
+    );
+
+    writeln!(
+        fd,
+        "Epoch, dx, dy, dz, x_ecef, y_ecef, z_ecef, speed_x, speed_y, speed_z, hdop, vdop, rcvr_clock_bias, tdop"
+    )?;
+
+    for (epoch, solution) in results {
+        let (px, py, pz) = (x + solution.pos.x, y + solution.pos.y, z + solution.pos.z);
+        let (hdop, vdop, tdop) = (
+            solution.hdop(lat_ddeg, lon_ddeg),
+            solution.vdop(lat_ddeg, lon_ddeg),
+            solution.tdop(),
+        );
+        writeln!(
+            fd,
+            "{:?}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}",
+            epoch,
+            solution.pos.x,
+            solution.pos.y,
+            solution.pos.z,
+            px,
+            py,
+            pz,
+            solution.vel.x,
+            solution.vel.y,
+            solution.vel.z,
+            hdop,
+            vdop,
+            solution.dt,
+            tdop
+        )?;
+        table.push(vec![
+            epoch.to_string().into(),
+            px.into(),
+            py.into(),
+            pz.into(),
+            hdop.into(),
+            vdop.into(),
+            tdop.into(),
+            solution.dt.into(),
+        ]);
+        solutions.push(Solution::from_ecef(epoch, (px, py, pz)).with_dop(hdop, vdop, tdop));
+    }
+    info!("\"{}\" generated", txtfile);
+
+    /*
+     * Report sinks
+     */
+    let mut sinks = ctx
+        .report_sinks()
+        .map_err(|e| Error::ReportSinkError(e.to_string()))?;
+    if !sinks.is_empty() {
+        sinks.section("Positioning")?;
+        sinks.value("apriori x_ecef [m]", &x.into())?;
+        sinks.value("apriori y_ecef [m]", &y.into())?;
+        sinks.value("apriori z_ecef [m]", &z.into())?;
+        sinks.value("solutions", &table.rows.len().into())?;
+        sinks.table(&table)?;
+        sinks.finish()?;
+    }
+
+    /*
+     * Solutions in standard formats
+     */
+    let prefix = Context::context_stem(&ctx.data);
+    for (flag, format) in [
+        ("pos", Format::Pos),
+        ("kml", Format::Kml),
+        ("gpx", Format::Gpx),
+    ] {
+        if matches.get_flag(flag) {
+            let path = ctx
+                .workspace
+                .join(format!("{}.{}", prefix, format.extension()));
+            let mut fd = File::create(&path)?;
+            formatter::write(&mut fd, format, &prefix, mode, &solutions)?;
+            info!("\"{}\" generated", path.display());
+        }
+    }
+
+    #[cfg(feature = "plot")]
+    if !ctx.quiet {
+        let graphs = ctx.workspace.join("PPP.html");
+        let graphs = graphs.to_string_lossy().to_string();
+        open_with_web_browser(&graphs);
+    }
+
+    Ok(())
+}
+
+/*
+ * Graphical visualization of the solutions, rendered in PPP.html
+ */
+#[cfg(feature = "plot")]
+fn plot_solutions(
+    ctx: &Context,
+    results: &BTreeMap<Epoch, PVTSolution>,
+    (x, y, z): (f64, f64, f64),
+    (lat_ddeg, lon_ddeg): (f64, f64),
+) {
+    // create a dedicated plot context
+    let mut plot_ctx = PlotContext::new();
 
     let epochs = results.keys().copied().collect::<Vec<Epoch>>();
 
@@ -60,9 +188,9 @@ pub fn post_process(
         let px = x + result.pos.x;
         let py = y + result.pos.y;
         let pz = z + result.pos.z;
-        let (lat_ddeg, lon_ddeg, _) = ecef2geodetic(px, py, pz, Ellipsoid::WGS84);
-        lat.push(rad2deg(lat_ddeg));
-        lon.push(rad2deg(lon_ddeg));
+        let (lat_rad, lon_rad, _) = ecef2geodetic(px, py, pz, Ellipsoid::WGS84);
+        lat.push(lat_rad.to_degrees());
+        lon.push(lon_rad.to_degrees());
     }
 
     plot_ctx.add_world_map(
@@ -201,137 +329,4 @@ pub fn post_process(
     let mut fd = File::create(&graphs).unwrap_or_else(|_| panic!("failed to crate \"{}\"", graphs));
     write!(fd, "{}", plot_ctx.to_html()).expect("failed to render rtk visualization");
     info!("\"{}\" solutions generated", graphs);
-
-    /*
-     * Generate txt, GPX, KML..
-     */
-    let txtpath = ctx.workspace.join("PVT.csv");
-    let txtfile = txtpath.to_string_lossy().to_string();
-    let mut fd = File::create(&txtfile)?;
-
-    let mut gpx_track = gpx::Track::default();
-    let mut kml_track = Vec::<Kml>::new();
-
-    writeln!(
-        fd,
-        "Epoch, dx, dy, dz, x_ecef, y_ecef, z_ecef, speed_x, speed_y, speed_z, hdop, vdop, rcvr_clock_bias, tdop"
-    )?;
-
-    for (epoch, solution) in results {
-        let (px, py, pz) = (x + solution.pos.x, y + solution.pos.y, z + solution.pos.z);
-        let (lat, lon, alt) = map_3d::ecef2geodetic(px, py, pz, Ellipsoid::WGS84);
-        let (hdop, vdop, tdop) = (
-            solution.hdop(lat_ddeg, lon_ddeg),
-            solution.vdop(lat_ddeg, lon_ddeg),
-            solution.tdop(),
-        );
-        writeln!(
-            fd,
-            "{:?}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}",
-            epoch,
-            solution.pos.x,
-            solution.pos.y,
-            solution.pos.z,
-            px,
-            py,
-            pz,
-            solution.vel.x,
-            solution.vel.y,
-            solution.vel.z,
-            hdop,
-            vdop,
-            solution.dt,
-            tdop
-        )?;
-        if matches.get_flag("gpx") {
-            let mut segment = gpx::TrackSegment::new();
-            let mut wp = Waypoint::new(GeoPoint::new(rad2deg(lat), rad2deg(lon)));
-            wp.elevation = Some(alt);
-            wp.speed = None; // TODO ?
-            wp.time = None; // TODO Gpx::Time
-            wp.name = Some(format!("{:?}", epoch));
-            wp.hdop = Some(hdop);
-            wp.vdop = Some(vdop);
-            wp.sat = None; //TODO: nb of contributing satellites
-            wp.dgps_age = None; //TODO: Number of seconds since last DGPS update, from the element.
-            wp.dgpsid = None; //TODO: ID of DGPS station used in differential correction, in the range [0, 1023].
-            segment.points.push(wp);
-            gpx_track.segments.push(segment);
-        }
-        if matches.get_flag("kml") {
-            kml_track.push(Kml::Placemark(Placemark {
-                name: Some(format!("{:?}", epoch)),
-                description: Some(String::from("\"Receiver Location\"")),
-                geometry: {
-                    Some(KmlGeometry::Point(KmlPoint {
-                        coord: {
-                            KmlCoord {
-                                x: rad2deg(lat),
-                                y: rad2deg(lon),
-                                z: Some(alt),
-                            }
-                        },
-                        extrude: false,
-                        altitude_mode: AltitudeMode::Absolute,
-                        attrs: HashMap::new(),
-                    }))
-                },
-                attrs: [(String::from("TDOP"), format!("{:.6E}", solution.tdop()))]
-                    .into_iter()
-                    .collect(),
-                children: vec![],
-            }));
-        }
-    }
-    info!("\"{}\" generated", txtfile);
-    if matches.get_flag("gpx") {
-        let prefix = Context::context_stem(&ctx.data);
-        let gpxpath = ctx.workspace.join(format!("{}.gpx", prefix));
-        let gpxfile = gpxpath.to_string_lossy().to_string();
-
-        let fd = File::create(&gpxfile)?;
-
-        let mut gpx = Gpx::default();
-        gpx.version = GpxVersion::Gpx11;
-        gpx_track.name = Some(prefix.clone());
-        // gpx_track.number = Some(1);
-        gpx.tracks.push(gpx_track);
-
-        gpx::write(&gpx, fd)?;
-        info!("{} gpx track generated", gpxfile);
-    }
-    if matches.get_flag("kml") {
-        let prefix = Context::context_stem(&ctx.data);
-        let kmlpath = ctx.workspace.join(format!("{}.kml", prefix));
-        let kmlfile = kmlpath.to_string_lossy().to_string();
-
-        let mut fd = File::create(&kmlfile)?;
-
-        let kmldoc = KmlDocument {
-            version: KmlVersion::V23,
-            attrs: [(
-                String::from("rtk-version"),
-                env!("CARGO_PKG_VERSION").to_string(),
-            )]
-            .into_iter()
-            .collect(),
-            elements: {
-                vec![Kml::Folder {
-                    attrs: HashMap::new(),
-                    elements: kml_track,
-                }]
-            },
-        };
-        let mut writer = KmlWriter::from_writer(&mut fd);
-        writer.write(&Kml::KmlDocument(kmldoc))?;
-        info!("{} kml track generated", kmlfile);
-    }
-
-    if !ctx.quiet {
-        let graphs = ctx.workspace.join("PPP.html");
-        let graphs = graphs.to_string_lossy().to_string();
-        open_with_web_browser(&graphs);
-    }
-
-    Ok(())
 }
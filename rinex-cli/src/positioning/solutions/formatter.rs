@@ -0,0 +1,276 @@
+//! Solutions formatting: RTKLIB .pos, KML and GPX tracks
+use super::Solution;
+use hifitime::{Duration, Epoch, TimeScale};
+use rinex::epoch::epoch_decompose;
+use std::collections::HashMap;
+use std::io::Write;
+use thiserror::Error;
+
+use gpx::{errors::GpxError, Gpx, GpxVersion, Waypoint};
+
+use kml::{
+    types::AltitudeMode, types::Coord as KmlCoord, types::Geometry as KmlGeometry,
+    types::KmlDocument, types::Placemark, types::Point as KmlPoint, Kml, KmlVersion, KmlWriter,
+};
+
+use geo_types::Point as GeoPoint;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("std::io error")]
+    IOError(#[from] std::io::Error),
+    #[error("failed to generate gpx track")]
+    GpxError(#[from] GpxError),
+    #[error("failed to generate kml track")]
+    KmlError(#[from] kml::Error),
+}
+
+/// Supported solutions formats
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// RTKLIB position file
+    Pos,
+    /// Keyhole Markup Language (Google Earth, QGIS..)
+    Kml,
+    /// GPS Exchange format
+    Gpx,
+}
+
+impl Format {
+    /// Standard file extension
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Pos => "pos",
+            Self::Kml => "kml",
+            Self::Gpx => "gpx",
+        }
+    }
+}
+
+/// Solver mode the solutions were resolved with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SolverMode {
+    /// Single point positioning, epoch by epoch
+    Single,
+    /// Precise point positioning, epoch by epoch
+    PppKinematic,
+    /// Precise point positioning, static receiver
+    PppStatic,
+}
+
+impl SolverMode {
+    /*
+     * RTKLIB "pos mode" designation
+     */
+    fn pos_mode(&self) -> &'static str {
+        match self {
+            Self::Single => "single",
+            Self::PppKinematic => "ppp-kinematic",
+            Self::PppStatic => "ppp-static",
+        }
+    }
+    /*
+     * RTKLIB quality flag (Q)
+     */
+    fn quality(&self) -> u8 {
+        match self {
+            Self::Single => 5,
+            Self::PppKinematic | Self::PppStatic => 6,
+        }
+    }
+}
+
+/*
+ * RTKLIB epoch format: "YYYY/MM/DD HH:MM:SS.SSS".
+ * Epoch is rounded to the millisecond first, so seconds never read "60.000".
+ */
+fn format_epoch(epoch: Epoch) -> String {
+    let (y, m, d, hh, mm, ss, nanos) =
+        epoch_decompose(epoch.round(Duration::from_milliseconds(1.0)));
+    format!(
+        "{:04}/{:02}/{:02} {:02}:{:02}:{:02}.{:03}",
+        y,
+        m,
+        d,
+        hh,
+        mm,
+        ss,
+        nanos / 1_000_000
+    )
+}
+
+/// Writes solutions as RTKLIB position file (.pos), in geodetic coordinates.
+/// Standard deviations are not estimated (null), the quality flag
+/// and the position mode follow given [SolverMode].
+pub fn write_pos<W: Write>(
+    w: &mut W,
+    program: &str,
+    mode: SolverMode,
+    solutions: &[Solution],
+) -> Result<(), Error> {
+    let timescale = solutions
+        .first()
+        .map(|s| s.epoch.time_scale)
+        .unwrap_or(TimeScale::GPST);
+    writeln!(w, "% program   : {}", program)?;
+    if let (Some(first), Some(last)) = (solutions.first(), solutions.last()) {
+        writeln!(
+            w,
+            "% obs start : {} {}",
+            format_epoch(first.epoch),
+            timescale
+        )?;
+        writeln!(
+            w,
+            "% obs end   : {} {}",
+            format_epoch(last.epoch),
+            timescale
+        )?;
+    }
+    writeln!(w, "% pos mode  : {}", mode.pos_mode())?;
+    writeln!(
+        w,
+        "% (lat/lon/height=WGS84/ellipsoidal,Q=1:fix,2:float,3:sbas,4:dgps,5:single,6:ppp,ns=# of satellites)"
+    )?;
+    writeln!(
+        w,
+        "%  {:<4}                  latitude(deg) longitude(deg)  height(m)   Q  ns   sdn(m)   sde(m)   sdu(m)  sdne(m)  sdeu(m)  sdun(m) age(s)  ratio",
+        timescale.to_string()
+    )?;
+    for solution in solutions {
+        let (lat, lon, alt) = solution.geodetic;
+        writeln!(
+            w,
+            "{} {:14.9} {:14.9} {:10.4} {:3} {:3} {:8.4} {:8.4} {:8.4} {:8.4} {:8.4} {:8.4} {:6.2} {:6.1}",
+            format_epoch(solution.epoch),
+            lat,
+            lon,
+            alt,
+            mode.quality(),
+            solution.satellites.unwrap_or(0),
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes solutions as a KML track: one placemark per solution
+pub fn write_kml<W: Write>(w: &mut W, program: &str, solutions: &[Solution]) -> Result<(), Error> {
+    let placemarks = solutions
+        .iter()
+        .map(|solution| {
+            let (lat, lon, alt) = solution.geodetic;
+            Kml::Placemark(Placemark {
+                name: Some(format!("{:?}", solution.epoch)),
+                description: Some(String::from("\"Receiver Location\"")),
+                geometry: Some(KmlGeometry::Point(KmlPoint {
+                    // KML coordinates are (longitude, latitude, altitude)
+                    coord: KmlCoord {
+                        x: lon,
+                        y: lat,
+                        z: Some(alt),
+                    },
+                    extrude: false,
+                    altitude_mode: AltitudeMode::Absolute,
+                    attrs: HashMap::new(),
+                })),
+                attrs: [(String::from("TDOP"), format!("{:.6E}", solution.tdop))]
+                    .into_iter()
+                    .collect(),
+                children: vec![],
+            })
+        })
+        .collect();
+    let kmldoc = KmlDocument {
+        version: KmlVersion::V23,
+        attrs: [(String::from("program"), program.to_string())]
+            .into_iter()
+            .collect(),
+        elements: vec![Kml::Folder {
+            attrs: HashMap::new(),
+            elements: placemarks,
+        }],
+    };
+    let mut writer = KmlWriter::from_writer(w);
+    writer.write(&Kml::KmlDocument(kmldoc))?;
+    Ok(())
+}
+
+/// Writes solutions as a GPX track, named after given name
+pub fn write_gpx<W: Write>(w: &mut W, name: &str, solutions: &[Solution]) -> Result<(), Error> {
+    let mut segment = gpx::TrackSegment::new();
+    for solution in solutions {
+        let (lat, lon, alt) = solution.geodetic;
+        // GPX points are (longitude, latitude)
+        let mut wp = Waypoint::new(GeoPoint::new(lon, lat));
+        wp.elevation = Some(alt);
+        wp.name = Some(format!("{:?}", solution.epoch));
+        wp.hdop = Some(solution.hdop);
+        wp.vdop = Some(solution.vdop);
+        wp.sat = solution.satellites.map(|n| n as u64);
+        segment.points.push(wp);
+    }
+    let mut track = gpx::Track::default();
+    track.name = Some(name.to_string());
+    track.segments.push(segment);
+
+    let mut gpx = Gpx::default();
+    gpx.version = GpxVersion::Gpx11;
+    gpx.tracks.push(track);
+    gpx::write(&gpx, w)?;
+    Ok(())
+}
+
+/// Writes solutions in given [Format]
+pub fn write<W: Write>(
+    w: &mut W,
+    format: Format,
+    name: &str,
+    mode: SolverMode,
+    solutions: &[Solution],
+) -> Result<(), Error> {
+    let program = format!("rinex-cli v{}", env!("CARGO_PKG_VERSION"));
+    match format {
+        Format::Pos => write_pos(w, &program, mode, solutions),
+        Format::Kml => write_kml(w, &program, solutions),
+        Format::Gpx => write_gpx(w, name, solutions),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+    #[test]
+    fn epoch_rounding() {
+        let t = Epoch::from_str("2022-03-04T00:00:59.9996 GPST").unwrap();
+        assert_eq!(format_epoch(t), "2022/03/04 00:01:00.000");
+        let t = Epoch::from_str("2022-03-04T00:00:30.1234 GPST").unwrap();
+        assert_eq!(format_epoch(t), "2022/03/04 00:00:30.123");
+    }
+    #[test]
+    fn pos_mode() {
+        let t = Epoch::from_str("2022-03-04T00:00:30 GPST").unwrap();
+        let solutions = [Solution::from_ecef(t, (4696989.0, 723994.0, 4239678.0))];
+        for (mode, header, quality) in [
+            (SolverMode::Single, "% pos mode  : single", 5),
+            (SolverMode::PppStatic, "% pos mode  : ppp-static", 6),
+        ] {
+            let mut content = Vec::new();
+            write_pos(&mut content, "test", mode, &solutions).unwrap();
+            let content = String::from_utf8(content).unwrap();
+            assert!(content.lines().any(|line| line == header));
+            let record = content.lines().last().unwrap();
+            assert!(record.starts_with("2022/03/04 00:00:30.000"));
+            let q = record.split_ascii_whitespace().nth(5).unwrap();
+            assert_eq!(q, quality.to_string());
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Position solutions, as shared by the positioning opmodes
+use hifitime::Epoch;
+use map_3d::{ecef2geodetic, Ellipsoid};
+
+pub mod formatter;
+
+/// Resolved position, at a given epoch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Solution {
+    /// Epoch of resolution
+    pub epoch: Epoch,
+    /// ECEF coordinates [m]
+    pub ecef: (f64, f64, f64),
+    /// Geodetic coordinates: latitude [ddeg], longitude [ddeg], altitude [m] (WGS84)
+    pub geodetic: (f64, f64, f64),
+    /// Horizontal dilution of precision
+    pub hdop: f64,
+    /// Vertical dilution of precision
+    pub vdop: f64,
+    /// Temporal dilution of precision
+    pub tdop: f64,
+    /// Number of contributing satellites, when known
+    pub satellites: Option<usize>,
+}
+
+impl Solution {
+    /// Builds a new [Solution] from ECEF coordinates [m]
+    pub fn from_ecef(epoch: Epoch, ecef: (f64, f64, f64)) -> Self {
+        let (lat, lon, alt) = ecef2geodetic(ecef.0, ecef.1, ecef.2, Ellipsoid::WGS84);
+        Self {
+            epoch,
+            ecef,
+            geodetic: (lat.to_degrees(), lon.to_degrees(), alt),
+            hdop: 0.0,
+            vdop: 0.0,
+            tdop: 0.0,
+            satellites: None,
+        }
+    }
+    /// Copies and defines dilutions of precision
+    pub fn with_dop(&self, hdop: f64, vdop: f64, tdop: f64) -> Self {
+        let mut s = *self;
+        s.hdop = hdop;
+        s.vdop = vdop;
+        s.tdop = tdop;
+        s
+    }
+}
@@ -0,0 +1,228 @@
+//! Static PPP opmode
+use crate::cli::Context;
+#[cfg(feature = "plot")]
+use crate::fops::open_with_web_browser;
+#[cfg(feature = "plot")]
+use crate::graph::{build_chart_epoch_axis, PlotContext};
+use crate::positioning::solutions::{
+    formatter::{self, Format, SolverMode},
+    Solution,
+};
+use crate::Error;
+use clap::ArgMatches;
+#[cfg(feature = "plot")]
+use map_3d::{ecef2enu, Ellipsoid};
+#[cfg(feature = "plot")]
+use plotly::common::Mode;
+#[cfg(feature = "plot")]
+use rinex::ppp::Solution as PPPSolution;
+use rinex::ppp::{resolve, Config};
+#[cfg(feature = "plot")]
+use rinex::prelude::Epoch;
+#[cfg(feature = "plot")]
+use std::collections::BTreeMap;
+use std::fs::{read_to_string, File};
+use std::io::Write;
+#[cfg(feature = "plot")]
+use std::path::PathBuf;
+
+/*
+ * Resolves the static PPP solution of the context,
+ * then generates the position / ZTD time series and the convergence plots.
+ */
+pub fn static_ppp(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
+    let cfg = match matches.get_one::<String>("cfg") {
+        Some(fp) => {
+            let content = read_to_string(fp)
+                .unwrap_or_else(|_| panic!("failed to read configuration: permission denied"));
+            let cfg = serde_json::from_str(&content)
+                .unwrap_or_else(|_| panic!("failed to parse configuration: invalid content"));
+            info!("using custom ppp configuration: {:#?}", cfg);
+            cfg
+        },
+        None => {
+            let cfg = Config::default();
+            info!("using default ppp configuration: {:#?}", cfg);
+            cfg
+        },
+    };
+
+    let results = resolve(&ctx.data, cfg)?;
+    let (last_epoch, last) = match results.iter().next_back() {
+        Some((t, solution)) => (*t, *solution),
+        None => {
+            error!("ppp: no solution could be resolved");
+            return Ok(());
+        },
+    };
+    let final_solution = Solution::from_ecef(last_epoch, last.position);
+    let (lat, lon, alt) = final_solution.geodetic;
+    info!(
+        "ppp: final position {:?} (lat={:.8}°, lon={:.8}°, alt={:.4}m), ztd={:.4}m",
+        last.position, lat, lon, alt, last.ztd
+    );
+
+    /*
+     * Position / ZTD time series
+     */
+    let txtpath = ctx.workspace.join("PPP.csv");
+    let mut fd = File::create(&txtpath)?;
+    writeln!(
+        fd,
+        "Epoch, x_ecef, y_ecef, z_ecef, sigma_x, sigma_y, sigma_z, rcvr_clock_bias, zwd, ztd, satellites"
+    )?;
+    let mut solutions = Vec::<Solution>::with_capacity(results.len());
+    for (epoch, solution) in &results {
+        writeln!(
+            fd,
+            "{:?}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {:.6E}, {}",
+            epoch,
+            solution.position.0,
+            solution.position.1,
+            solution.position.2,
+            solution.position_sigma.0,
+            solution.position_sigma.1,
+            solution.position_sigma.2,
+            solution.clock_offset,
+            solution.zwd,
+            solution.ztd,
+            solution.satellites,
+        )?;
+        let mut s = Solution::from_ecef(*epoch, solution.position);
+        s.satellites = Some(solution.satellites);
+        solutions.push(s);
+    }
+    info!("\"{}\" generated", txtpath.display());
+
+    #[cfg(feature = "plot")]
+    let graphs = plot_convergence(ctx, &results, (lat, lon, alt))?;
+
+    /*
+     * Solutions in standard formats
+     */
+    let prefix = Context::context_stem(&ctx.data);
+    for (flag, format) in [
+        ("pos", Format::Pos),
+        ("kml", Format::Kml),
+        ("gpx", Format::Gpx),
+    ] {
+        if matches.get_flag(flag) {
+            let path = ctx
+                .workspace
+                .join(format!("{}.{}", prefix, format.extension()));
+            let mut fd = File::create(&path)?;
+            formatter::write(&mut fd, format, &prefix, SolverMode::PppStatic, &solutions)?;
+            info!("\"{}\" generated", path.display());
+        }
+    }
+
+    #[cfg(feature = "plot")]
+    if !ctx.quiet {
+        open_with_web_browser(&graphs.to_string_lossy());
+    }
+    Ok(())
+}
+
+/*
+ * Convergence plots: ENU offsets to the final solution, ZTD
+ */
+#[cfg(feature = "plot")]
+fn plot_convergence(
+    ctx: &Context,
+    results: &BTreeMap<Epoch, PPPSolution>,
+    (lat, lon, alt): (f64, f64, f64),
+) -> Result<PathBuf, Error> {
+    let epochs = results.keys().copied().collect::<Vec<Epoch>>();
+    let enu: Vec<(f64, f64, f64)> = results
+        .values()
+        .map(|s| {
+            let (x, y, z) = s.position;
+            ecef2enu(
+                x,
+                y,
+                z,
+                lat.to_radians(),
+                lon.to_radians(),
+                alt,
+                Ellipsoid::WGS84,
+            )
+        })
+        .collect();
+
+    let mut plot_ctx = PlotContext::new();
+    plot_ctx.add_timedomain_2y_plot("Horizontal convergence", "East [m]", "North [m]");
+    let trace = build_chart_epoch_axis(
+        "east",
+        Mode::Markers,
+        epochs.clone(),
+        enu.iter().map(|p| p.0).collect::<Vec<f64>>(),
+    );
+    plot_ctx.add_trace(trace);
+    let trace = build_chart_epoch_axis(
+        "north",
+        Mode::Markers,
+        epochs.clone(),
+        enu.iter().map(|p| p.1).collect::<Vec<f64>>(),
+    )
+    .y_axis("y2");
+    plot_ctx.add_trace(trace);
+
+    plot_ctx.add_timedomain_plot("Vertical convergence", "Up [m]");
+    let trace = build_chart_epoch_axis(
+        "up",
+        Mode::Markers,
+        epochs.clone(),
+        enu.iter().map(|p| p.2).collect::<Vec<f64>>(),
+    );
+    plot_ctx.add_trace(trace);
+
+    plot_ctx.add_timedomain_plot("3D formal error", "sigma [m]");
+    let trace = build_chart_epoch_axis(
+        "sigma",
+        Mode::Markers,
+        epochs.clone(),
+        results
+            .values()
+            .map(|s| {
+                let (sx, sy, sz) = s.position_sigma;
+                (sx.powi(2) + sy.powi(2) + sz.powi(2)).sqrt()
+            })
+            .collect::<Vec<f64>>(),
+    );
+    plot_ctx.add_trace(trace);
+
+    plot_ctx.add_timedomain_2y_plot("Troposphere", "ZTD [m]", "ZWD [m]");
+    let trace = build_chart_epoch_axis(
+        "ztd",
+        Mode::Markers,
+        epochs.clone(),
+        results.values().map(|s| s.ztd).collect::<Vec<f64>>(),
+    );
+    plot_ctx.add_trace(trace);
+    let trace = build_chart_epoch_axis(
+        "zwd",
+        Mode::Markers,
+        epochs.clone(),
+        results.values().map(|s| s.zwd).collect::<Vec<f64>>(),
+    )
+    .y_axis("y2");
+    plot_ctx.add_trace(trace);
+
+    plot_ctx.add_timedomain_plot("Receiver clock offset", "dt [s]");
+    let trace = build_chart_epoch_axis(
+        "dt",
+        Mode::Markers,
+        epochs,
+        results
+            .values()
+            .map(|s| s.clock_offset)
+            .collect::<Vec<f64>>(),
+    );
+    plot_ctx.add_trace(trace);
+
+    let graphs = ctx.workspace.join("PPP-static.html");
+    let mut fd = File::create(&graphs)?;
+    write!(fd, "{}", plot_ctx.to_html())?;
+    info!("\"{}\" generated", graphs.display());
+    Ok(graphs)
+}
@@ -6,6 +6,83 @@ use rinex::prelude::RnxContext;
 use rinex::preprocessing::*;
 
 pub fn preprocess(ctx: &mut RnxContext, cli: &Cli) {
+    // time offset, prior anything else
+    if let Some(offset) = cli.time_shift() {
+        if let Some(obs) = ctx.obs_data_mut() {
+            obs.shift_epochs_mut(offset);
+        }
+        if let Some(meteo) = ctx.meteo_data_mut() {
+            meteo.shift_epochs_mut(offset);
+        }
+        info!("epochs shifted by {}", offset);
+    }
+
+    // products time frames
+    for (product, start, end) in ctx.time_spans() {
+        info!("{:?}: {} - {}", product, start, end);
+    }
+    if ctx.time_spans().len() > 1 {
+        match ctx.common_time_span() {
+            Some((start, end)) => {
+                info!("common time span: {} - {}", start, end);
+                if cli.trim() {
+                    ctx.trim_mut(start, end);
+                    info!("products trimmed to the common time span");
+                }
+            },
+            None => error!("input products do not overlap in time"),
+        }
+    }
+
+    // coarse position, when no header defines one
+    if ctx.ground_position().is_none() {
+        match ctx.bootstrap_ground_position_mut(cli.write_approx_position()) {
+            Some(position) => info!(
+                "no reference position: coarse estimate {:?}",
+                position.to_ecef_wgs84()
+            ),
+            None => warn!("no reference position: elevation dependent processing is not feasible"),
+        }
+    }
+
+    // IGS antenna naming
+    if let Some(obs) = ctx.obs_data_mut() {
+        if let Some(antenna) = &mut obs.header.rcvr_antenna {
+            if let Err(e) = antenna.validate_igs_name() {
+                if cli.fix_antenna_name() {
+                    let igs_name = antenna.igs_name();
+                    info!(
+                        "antenna type \"{}\" normalized to \"{}\"",
+                        antenna.model, igs_name
+                    );
+                    antenna.model = igs_name;
+                } else {
+                    warn!("non standard antenna type \"{}\": {}", antenna.model, e);
+                }
+            }
+        }
+    }
+
+    // canonical observables order
+    if cli.reorder_observables() {
+        if let Some(obs) = ctx.obs_data_mut() {
+            obs.reorder_observables_mut();
+            trace!("observables reordered");
+        }
+    }
+
+    // repeated ephemeris uploads
+    if cli.dedup_nav() {
+        if let Some(nav) = ctx.nav_data_mut() {
+            let count = nav.ephemeris().count();
+            nav.nav_dedup_mut();
+            info!(
+                "removed {} repeated ephemeris",
+                count - nav.ephemeris().count()
+            );
+        }
+    }
+
     // GNSS filters
     let mut gnss_filters: Vec<&str> = Vec::new();
 
@@ -73,4 +150,25 @@ pub fn preprocess(ctx: &mut RnxContext, cli: &Cli) {
             error!("invalid filter description \"{}\"", filt_str);
         }
     }
+
+    // thin out, once masks have been applied
+    if let Some(n) = cli.max_sv_per_epoch() {
+        let ref_position = ctx.ground_position();
+        let nav = match cli.thin_out_by_snr() {
+            true => None,
+            false => ctx.nav_data().cloned(),
+        };
+        if let Some(obs) = ctx.obs_data_mut() {
+            match nav {
+                Some(nav) if ref_position.is_some() => {
+                    obs.thin_out_by_elevation_mut(n, &nav, ref_position);
+                    info!("kept {} highest satellites per epoch", n);
+                },
+                _ => {
+                    obs.thin_out_by_snr_mut(n);
+                    info!("kept {} strongest satellites per epoch", n);
+                },
+            }
+        }
+    }
 }
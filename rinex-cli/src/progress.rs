@@ -0,0 +1,86 @@
+//! Progress bars, fed by the library progress reports
+use indicatif::{HumanBytes, ProgressBar, ProgressStyle};
+use std::path::Path;
+
+/*
+ * Progress bar for the parsing of given file.
+ * The file length is only known when the content is not gzip compressed,
+ * otherwise we use a spinner.
+ */
+pub fn parser_bar(path: &Path, quiet: bool) -> ProgressBar {
+    if quiet {
+        return ProgressBar::hidden();
+    }
+    let filename = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let length = match path.extension() {
+        Some(ext) if ext == "gz" => None,
+        _ => std::fs::metadata(path).ok().map(|meta| meta.len()),
+    };
+    let bar = match length {
+        Some(length) => {
+            let bar = ProgressBar::new(length);
+            bar.set_style(
+                ProgressStyle::with_template(
+                    "{prefix} {bar:40.cyan/blue} {bytes}/{total_bytes} ({eta}) {msg}",
+                )
+                .unwrap()
+                .progress_chars("=> "),
+            );
+            bar
+        },
+        None => {
+            let bar = ProgressBar::new_spinner();
+            bar.set_style(ProgressStyle::with_template("{prefix} {spinner} {msg}").unwrap());
+            bar
+        },
+    };
+    bar.set_prefix(filename);
+    bar
+}
+
+/*
+ * Maps a library progress report to given bar
+ */
+pub fn update(bar: &ProgressBar, bytes: u64, epochs: usize) {
+    if bar.length().is_some() {
+        bar.set_position(bytes);
+        bar.set_message(format!("{} epochs", epochs));
+    } else {
+        bar.set_message(format!("{} {} epochs", HumanBytes(bytes), epochs));
+        bar.tick();
+    }
+}
+
+/*
+ * Spinner for the operations that are not file based (QC)
+ */
+pub fn spinner(prefix: &str, quiet: bool) -> ProgressBar {
+    if quiet {
+        return ProgressBar::hidden();
+    }
+    let bar = ProgressBar::new_spinner();
+    bar.set_style(ProgressStyle::with_template("{prefix} {spinner} {msg}").unwrap());
+    bar.set_prefix(prefix.to_string());
+    bar
+}
+
+/*
+ * Progress bar for the batch operations, one step per file
+ */
+pub fn batch_bar(total: u64, quiet: bool) -> ProgressBar {
+    if quiet {
+        return ProgressBar::hidden();
+    }
+    let bar = ProgressBar::new(total);
+    bar.set_style(
+        ProgressStyle::with_template(
+            "[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} ({eta}) {msg}",
+        )
+        .unwrap()
+        .progress_chars("=> "),
+    );
+    bar
+}
@@ -1,15 +1,28 @@
 //! File Quality opmode
 use clap::ArgMatches;
 use log::info;
+use serde::Serialize;
 use std::fs::{read_to_string, File};
-use std::io::Write;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+use std::time::Instant;
 
-use crate::cli::Context;
+use crate::cli::{Cli, Context};
 use crate::fops::open_with_web_browser;
+use crate::preprocess;
+#[cfg(feature = "progress")]
+use crate::progress;
 use crate::Error;
+use rinex::campaign::SessionStatistics;
+use rinex::prelude::{Rinex, RnxContext};
 use rinex_qc::{QcOpts, QcReport};
+use rinex_qc_traits::{ReportSink, Reportable};
 
-pub fn qc_report(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
+/*
+ * QC configuration: either user defined or default
+ */
+fn qc_opts(matches: &ArgMatches) -> QcOpts {
     let cfg = match matches.get_one::<String>("cfg") {
         Some(fp) => {
             let content = read_to_string(fp)
@@ -25,6 +38,11 @@ pub fn qc_report(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
             cfg
         },
     };
+    cfg
+}
+
+pub fn qc_report(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
+    let cfg = qc_opts(matches);
 
     /*
      * print more infos
@@ -35,7 +53,41 @@ pub fn qc_report(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
     info!("Elevation mask        : {:?}", cfg.elev_mask);
     info!("Sampling gap tolerance: {:?}", cfg.gap_tolerance);
 
-    let html = QcReport::html(&ctx.data, cfg);
+    let reference = match matches.get_one::<PathBuf>("reference") {
+        Some(path) => {
+            let reference = Rinex::from_file(path.to_string_lossy().as_ref())?;
+            if !reference.is_observation_rinex() {
+                return Err(Error::MissingObservationRinex);
+            }
+            info!("common view comparison with \"{}\"", path.display());
+            Some(reference)
+        },
+        None => None,
+    };
+
+    let mut sinks = ctx.report_sinks()?;
+    if !sinks.is_empty() {
+        QcReport::report(&ctx.data, reference.as_ref(), cfg.clone(), &mut sinks)?;
+        sinks.finish()?;
+    }
+
+    #[cfg(feature = "progress")]
+    let html = {
+        let bar = progress::spinner("QC", ctx.quiet);
+        let html = QcReport::html_with_progress(
+            &ctx.data,
+            reference.as_ref(),
+            cfg,
+            &mut |_: u64, epochs: usize| {
+                bar.set_message(format!("{} epochs analyzed", epochs));
+                bar.tick();
+            },
+        );
+        bar.finish_and_clear();
+        html
+    };
+    #[cfg(not(feature = "progress"))]
+    let html = QcReport::html_with_progress(&ctx.data, reference.as_ref(), cfg, &mut |_, _| {});
     let report_path = ctx.workspace.join("QC.html");
 
     let mut fd = File::create(&report_path).map_err(|_| Error::QcReportCreationError)?;
@@ -50,3 +102,156 @@ pub fn qc_report(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
     }
     Ok(())
 }
+
+/// Per file summary, streamed (JSONL) in batch mode
+#[derive(Debug, Serialize)]
+struct FileSummary {
+    /// Analyzed file
+    path: PathBuf,
+    /// Generated report
+    report: Option<PathBuf>,
+    /// Session statistics, for Observation RINEX
+    session: Option<SessionStatistics>,
+    /// Processing time, in seconds
+    elapsed: f64,
+    /// Reason of failure, if any
+    error: Option<String>,
+}
+
+/*
+ * Analyzes a single file: loads it in its own context,
+ * applies the preprocessing pipeline and renders its report.
+ */
+fn qc_single_file(cli: &Cli, cfg: &QcOpts, workspace: &Path, path: &Path) -> FileSummary {
+    let t0 = Instant::now();
+    let mut summary = FileSummary {
+        path: path.to_path_buf(),
+        report: None,
+        session: None,
+        elapsed: 0.0,
+        error: None,
+    };
+    let mut data = RnxContext::default();
+    match data.load(&path.to_path_buf()) {
+        Ok(_) => {
+            preprocess(&mut data, cli);
+            if let Some(obs) = data.obs_data() {
+                summary.session = SessionStatistics::from_rinex(path, obs).ok();
+            }
+            let html = QcReport::html(&data, cfg.clone());
+            // name the report after the complete file name ($FILENAME.RNX.gz.html),
+            // files that only differ by their extension do not overwrite each other
+            let filename = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let report = workspace.join(format!("{}.html", filename));
+            match File::create(&report) {
+                Ok(mut fd) => match write!(fd, "{}", html) {
+                    Ok(_) => summary.report = Some(report),
+                    Err(e) => summary.error = Some(e.to_string()),
+                },
+                Err(e) => summary.error = Some(e.to_string()),
+            }
+        },
+        Err(e) => summary.error = Some(e.to_string()),
+    }
+    summary.elapsed = t0.elapsed().as_secs_f64();
+    summary
+}
+
+/*
+ * Batch (--per-file) QC: each file is analyzed on its own, by a pool of workers.
+ * A worker only holds one file at a time, and results are streamed
+ * to the JSONL summary as soon as they're available, so memory remains bounded
+ * whatever the archive size.
+ */
+pub fn qc_batch(cli: &Cli, matches: &ArgMatches) -> Result<(), Error> {
+    let cfg = qc_opts(matches);
+    let paths = cli.input_paths();
+    let total = paths.len();
+    let jobs = match matches.get_one::<usize>("jobs") {
+        Some(jobs) => (*jobs).max(1),
+        None => std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1),
+    }
+    .min(total.max(1));
+
+    let workspace = cli.workspace("QC");
+    let jsonl_path = workspace.join("qc.jsonl");
+    let mut jsonl =
+        BufWriter::new(File::create(&jsonl_path).map_err(|_| Error::QcReportCreationError)?);
+    info!("batch QC: {} files, {} workers", total, jobs);
+
+    #[cfg(feature = "progress")]
+    let progress = progress::batch_bar(total as u64, cli.quiet());
+
+    // session statistics are reported as they come
+    let mut sinks = crate::report::open(&cli.report_sinks())?;
+
+    let queue = Mutex::new(paths.into_iter());
+    let (mut analyzed, mut failures) = (0_usize, 0_usize);
+    std::thread::scope(|scope| {
+        // bounded: workers block until the summaries are consumed
+        let (tx, rx) = mpsc::sync_channel::<FileSummary>(jobs);
+        for _ in 0..jobs {
+            let tx = tx.clone();
+            let (queue, cfg, workspace) = (&queue, &cfg, &workspace);
+            scope.spawn(move || loop {
+                let path = match queue.lock().unwrap().next() {
+                    Some(path) => path,
+                    None => break,
+                };
+                let summary = qc_single_file(cli, cfg, workspace, &path);
+                if tx.send(summary).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        for summary in rx {
+            if let Some(e) = &summary.error {
+                failures += 1;
+                let message = format!("failed to analyze \"{}\": {}", summary.path.display(), e);
+                #[cfg(feature = "progress")]
+                progress.println(message);
+                #[cfg(not(feature = "progress"))]
+                error!("{}", message);
+            }
+            if let Some(session) = &summary.session {
+                session
+                    .report(&mut sinks)
+                    .expect("failed to report session statistics");
+            }
+            let line = serde_json::to_string(&summary).expect("failed to serialize qc summary");
+            writeln!(jsonl, "{}", line)
+                .and_then(|_| jsonl.flush())
+                .expect("failed to stream qc summary");
+            #[cfg(feature = "progress")]
+            {
+                progress.set_message(
+                    summary
+                        .path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                );
+                progress.inc(1);
+            }
+            analyzed += 1;
+        }
+    });
+    #[cfg(feature = "progress")]
+    progress.finish_and_clear();
+    sinks.finish()?;
+
+    info!(
+        "batch QC: {} files analyzed ({} failures), summary in \"{}\"",
+        analyzed,
+        failures,
+        jsonl_path.display()
+    );
+    Ok(())
+}
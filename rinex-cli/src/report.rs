@@ -0,0 +1,155 @@
+//! Report sinks: routes analysis results to the terminal, JSON, HTML or SQLite
+use crate::Error;
+use rinex_qc_traits::{HtmlSink, JsonSink, ReportSink, TextSink};
+#[cfg(feature = "sql")]
+use rinex_qc_traits::{ReportTable, ReportValue};
+#[cfg(feature = "sql")]
+use rusqlite::{params_from_iter, types::Value, Connection};
+use std::fs::File;
+use std::io::BufWriter;
+
+/*
+ * Opens the sinks described by the --sink options:
+ * "text" (stdout), "json:FILE", "html:FILE" or "sqlite:FILE"
+ */
+pub fn open(descriptors: &[String]) -> Result<Vec<Box<dyn ReportSink>>, Error> {
+    let mut sinks = Vec::<Box<dyn ReportSink>>::with_capacity(descriptors.len());
+    for descriptor in descriptors {
+        let (kind, path) = match descriptor.split_once(':') {
+            Some((kind, path)) => (kind, Some(path)),
+            None => (descriptor.as_str(), None),
+        };
+        match (kind.trim().to_lowercase().as_str(), path) {
+            ("text", None) => sinks.push(Box::new(TextSink::stdout())),
+            ("json", Some(path)) => {
+                let fd = File::create(path)?;
+                sinks.push(Box::new(JsonSink::new(BufWriter::new(fd))));
+            },
+            ("html", Some(path)) => {
+                let fd = File::create(path)?;
+                sinks.push(Box::new(HtmlSink::new(BufWriter::new(fd))));
+            },
+            #[cfg(feature = "sql")]
+            ("sqlite", Some(path)) => sinks.push(Box::new(SqliteSink::open(path)?)),
+            _ => return Err(Error::InvalidReportSink(descriptor.to_string())),
+        }
+        info!("reporting through \"{}\"", descriptor);
+    }
+    Ok(sinks)
+}
+
+/// [`SqliteSink`] writes the results into a SQLite database:
+/// named values go to the "report_values" table (section, key, value),
+/// each reported table goes to its own SQL table, named after
+/// the section and the table, created on first use.
+/// Values retain their type (INTEGER, REAL, TEXT, NULL when missing).
+/// Everything is written within a single transaction, committed on completion.
+#[cfg(feature = "sql")]
+pub struct SqliteSink {
+    conn: Connection,
+    section: String,
+}
+
+/*
+ * SQLite errors are reported as I/O errors, like any other sink
+ */
+#[cfg(feature = "sql")]
+fn sql_error(e: rusqlite::Error) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Other, e)
+}
+
+/*
+ * Quoted SQL identifier: lowercase alphanumeric characters and underscores
+ */
+#[cfg(feature = "sql")]
+pub(crate) fn identifier(name: &str) -> String {
+    let name: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("\"{}\"", name.trim_matches('_'))
+}
+
+#[cfg(feature = "sql")]
+fn to_sql(value: &ReportValue) -> Value {
+    match value {
+        ReportValue::Text(text) => Value::Text(text.clone()),
+        ReportValue::Integer(value) => Value::Integer(*value),
+        ReportValue::Float(value) if value.is_finite() => Value::Real(*value),
+        ReportValue::Bool(value) => Value::Integer(*value as i64),
+        ReportValue::Float(_) | ReportValue::Missing => Value::Null,
+    }
+}
+
+#[cfg(feature = "sql")]
+impl SqliteSink {
+    /// Opens (creates if need be) given database
+    pub fn open(path: &str) -> Result<Self, rusqlite::Error> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS report_values (section TEXT NOT NULL, key TEXT NOT NULL, value);
+            BEGIN;",
+        )?;
+        Ok(Self {
+            conn,
+            section: String::new(),
+        })
+    }
+}
+
+#[cfg(feature = "sql")]
+impl ReportSink for SqliteSink {
+    fn section(&mut self, title: &str) -> std::io::Result<()> {
+        self.section = title.to_string();
+        Ok(())
+    }
+    fn value(&mut self, key: &str, value: &ReportValue) -> std::io::Result<()> {
+        self.conn
+            .execute(
+                "INSERT INTO report_values (section, key, value) VALUES (?1, ?2, ?3)",
+                (&self.section, key, to_sql(value)),
+            )
+            .map_err(sql_error)?;
+        Ok(())
+    }
+    fn table(&mut self, table: &ReportTable) -> std::io::Result<()> {
+        let name = identifier(&format!("{}_{}", self.section, table.name));
+        let columns: Vec<String> = table
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| match identifier(column).as_str() {
+                "\"\"" => format!("\"column{}\"", i + 1),
+                column => column.to_string(),
+            })
+            .collect();
+        self.conn
+            .execute_batch(&format!(
+                "CREATE TABLE IF NOT EXISTS {} ({});",
+                name,
+                columns.join(", ")
+            ))
+            .map_err(sql_error)?;
+        let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("?{}", i)).collect();
+        let mut statement = self
+            .conn
+            .prepare(&format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                name,
+                columns.join(", "),
+                placeholders.join(", ")
+            ))
+            .map_err(sql_error)?;
+        for row in &table.rows {
+            statement
+                .execute(params_from_iter(row.iter().map(to_sql)))
+                .map_err(sql_error)?;
+        }
+        Ok(())
+    }
+    fn finish(&mut self) -> std::io::Result<()> {
+        self.conn.execute_batch("COMMIT;").map_err(sql_error)
+    }
+}
@@ -0,0 +1,176 @@
+//! Pseudo range residuals against a surveyed position
+use crate::cli::Cli;
+#[cfg(feature = "plot")]
+use crate::fops::open_with_web_browser;
+#[cfg(feature = "plot")]
+use crate::graph::{build_chart_epoch_axis, PlotContext};
+use crate::visibility::parse_position;
+use crate::Error;
+use clap::ArgMatches;
+#[cfg(feature = "plot")]
+use plotly::common::{Mode, Visible};
+#[cfg(feature = "plot")]
+use plotly::Scatter;
+use rinex::observation::PseudoRangeResidual;
+#[cfg(feature = "plot")]
+use rinex::prelude::Epoch;
+use rinex::prelude::{Observable, Rinex, SV};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+/*
+ * Residual analysis opmode: computes the pseudo range residuals,
+ * prints the statistics of each satellite and signal,
+ * plots residuals versus time and elevation
+ */
+pub fn residuals(cli: &Cli, matches: &ArgMatches) -> Result<(), Error> {
+    let obs_path = matches.get_one::<PathBuf>("obs").unwrap();
+    let obs = Rinex::from_file(obs_path.to_string_lossy().as_ref())?;
+    if !obs.is_observation_rinex() {
+        return Err(Error::MissingObservationRinex);
+    }
+    let nav_path = matches.get_one::<PathBuf>("nav").unwrap();
+    let nav = Rinex::from_file(nav_path.to_string_lossy().as_ref())?;
+    if !nav.is_navigation_rinex() {
+        return Err(Error::MissingNavigationRinex);
+    }
+    let position = parse_position(matches.get_one::<String>("pos").unwrap())?;
+    let mask = *matches.get_one::<f64>("mask").unwrap();
+
+    let residuals = obs.pseudo_range_residuals(&nav, position, mask);
+    if residuals.is_empty() {
+        error!("no residual could be computed: check the position and the ephemeris");
+        return Ok(());
+    }
+    let mut series = BTreeMap::<(SV, Observable), Vec<&PseudoRangeResidual>>::new();
+    for residual in &residuals {
+        series
+            .entry((residual.sv, residual.observable.clone()))
+            .or_default()
+            .push(residual);
+    }
+    info!(
+        "{} residuals, {} satellites and signals above {}°",
+        residuals.len(),
+        series.len(),
+        mask
+    );
+
+    let stem = obs_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .and_then(|name| name.split('.').next().map(|s| s.to_string()))
+        .unwrap_or_else(|| "OBS".to_string());
+    let workspace = cli.workspace(&format!("RESIDUALS-{}", stem));
+
+    /* statistics: the mean exhibits biases, the RMS multipath and noise */
+    println!(
+        "{:<5} {:<6} {:>8} {:>10} {:>10} {:>10}",
+        "SV", "signal", "samples", "mean [m]", "rms [m]", "max [m]"
+    );
+    for ((sv, observable), residuals) in &series {
+        let n = residuals.len() as f64;
+        let mean = residuals.iter().map(|r| r.residual).sum::<f64>() / n;
+        let rms = (residuals.iter().map(|r| r.residual.powi(2)).sum::<f64>() / n).sqrt();
+        let max = residuals
+            .iter()
+            .map(|r| r.residual.abs())
+            .fold(0.0_f64, f64::max);
+        println!(
+            "{:<5} {:<6} {:>8} {:>10.3} {:>10.3} {:>10.3}",
+            sv.to_string(),
+            observable.to_string(),
+            residuals.len(),
+            mean,
+            rms,
+            max
+        );
+    }
+
+    let csv_path = workspace.join("residuals.csv");
+    let mut fd = File::create(&csv_path)?;
+    writeln!(
+        fd,
+        "Epoch, SV, signal, elevation [°], azimuth [°], residual [m]"
+    )?;
+    for residual in &residuals {
+        writeln!(
+            fd,
+            "{}, {}, {}, {:.3}, {:.3}, {:.3}",
+            residual.epoch,
+            residual.sv,
+            residual.observable,
+            residual.elevation,
+            residual.azimuth,
+            residual.residual
+        )?;
+    }
+    info!("\"{}\" has been generated", csv_path.display());
+
+    #[cfg(feature = "plot")]
+    {
+        let mut plot_ctx = PlotContext::new();
+        plot_residuals(&mut plot_ctx, &series);
+
+        let html_path = workspace.join("residuals.html");
+        let mut fd = File::create(&html_path)
+            .unwrap_or_else(|e| panic!("failed to create {}: {:?}", html_path.display(), e));
+        write!(fd, "{}", plot_ctx.to_html()).unwrap_or_else(|e| {
+            panic!("failed to render HTML content: {:?}", e);
+        });
+        info!("html rendered in \"{}\"", html_path.display());
+        if !cli.quiet() {
+            open_with_web_browser(html_path.to_string_lossy().as_ref());
+        }
+    }
+    Ok(())
+}
+
+/*
+ * Residuals versus time, then versus elevation:
+ * one trace per satellite and signal, first one visible
+ */
+#[cfg(feature = "plot")]
+fn plot_residuals(
+    plot_ctx: &mut PlotContext,
+    series: &BTreeMap<(SV, Observable), Vec<&PseudoRangeResidual>>,
+) {
+    let visible = |index: usize| {
+        if index == 0 {
+            Visible::True
+        } else {
+            Visible::LegendOnly
+        }
+    };
+    plot_ctx.add_timedomain_plot("Pseudo range residuals", "Residual [m]");
+    for (index, ((sv, observable), residuals)) in series.iter().enumerate() {
+        let epochs: Vec<Epoch> = residuals.iter().map(|r| r.epoch).collect();
+        let values: Vec<f64> = residuals.iter().map(|r| r.residual).collect();
+        let trace = build_chart_epoch_axis(
+            &format!("{}({})", sv, observable),
+            Mode::Markers,
+            epochs,
+            values,
+        )
+        .visible(visible(index));
+        plot_ctx.add_trace(trace);
+    }
+
+    plot_ctx.add_cartesian2d_plot(
+        "Pseudo range residuals versus Elevation",
+        "Elevation [°]",
+        "Residual [m]",
+    );
+    for (index, ((sv, observable), residuals)) in series.iter().enumerate() {
+        let elevations: Vec<f64> = residuals.iter().map(|r| r.elevation).collect();
+        let values: Vec<f64> = residuals.iter().map(|r| r.residual).collect();
+        let trace = Scatter::new(elevations, values)
+            .mode(Mode::Markers)
+            .name(&format!("{}({})", sv, observable))
+            .visible(visible(index));
+        plot_ctx.add_trace(trace);
+    }
+    trace!("pseudo range residuals");
+}
@@ -0,0 +1,184 @@
+//! Sidereal stacking, for multipath characterization
+use crate::cli::Cli;
+#[cfg(feature = "plot")]
+use crate::fops::open_with_web_browser;
+#[cfg(feature = "plot")]
+use crate::graph::PlotContext;
+use crate::Error;
+use clap::ArgMatches;
+#[cfg(feature = "plot")]
+use plotly::common::{Mode, Visible};
+#[cfg(feature = "plot")]
+use plotly::Scatter;
+use rinex::merge::Merge;
+#[cfg(feature = "plot")]
+use rinex::observation::{SiderealStack, StackedSignal};
+use rinex::prelude::{Duration, Rinex};
+#[cfg(feature = "plot")]
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+/*
+ * Stacking opmode: merges the daily files, stacks them by sidereal day,
+ * prints the repeatability of each satellite and signal and plots the stacked series
+ */
+pub fn stack(cli: &Cli, matches: &ArgMatches) -> Result<(), Error> {
+    let paths: Vec<&PathBuf> = matches.get_many::<PathBuf>("files").unwrap().collect();
+    let mut obs = Option::<Rinex>::None;
+    for path in &paths {
+        let rinex = Rinex::from_path(path)?;
+        if !rinex.is_observation_rinex() {
+            return Err(Error::MissingObservationRinex);
+        }
+        match &mut obs {
+            Some(obs) => obs.merge_mut(&rinex)?,
+            None => obs = Some(rinex),
+        }
+    }
+    let obs = obs.ok_or(Error::MissingObservationRinex)?;
+
+    let period = Duration::from_seconds(*matches.get_one::<f64>("period").unwrap());
+    let bin = match matches.get_one::<f64>("bin") {
+        Some(bin) => Duration::from_seconds(*bin),
+        None => obs
+            .dominant_sample_rate()
+            .unwrap_or(Duration::from_seconds(30.0)),
+    };
+    let stack = match obs.sidereal_stack(period) {
+        Some(stack) => stack,
+        None => {
+            error!("stack: no observations");
+            return Ok(());
+        },
+    };
+    info!(
+        "{} days stacked with a {} period, {} bins",
+        stack.days(),
+        period,
+        bin
+    );
+    if stack.days() < 2 {
+        warn!("repeatability requires at least two consecutive days");
+    }
+
+    let stem = paths[0]
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .and_then(|name| name.split('.').next().map(|s| s.to_string()))
+        .unwrap_or_else(|| "OBS".to_string());
+    let workspace = cli.workspace(&format!("STACK-{}", stem));
+
+    /* repeatability: low rms and high correlation reveal a repeating multipath */
+    let repeatabilities = stack.repeatabilities(bin);
+    println!(
+        "{:<5} {:<9} {:>5} {:>8} {:>10} {:>12}",
+        "SV", "signal", "days", "samples", "rms", "correlation"
+    );
+    for (sv, signal, repeatability) in &repeatabilities {
+        println!(
+            "{:<5} {:<9} {:>5} {:>8} {:>10.3} {:>12}",
+            sv.to_string(),
+            signal.to_string(),
+            repeatability.days,
+            repeatability.samples,
+            repeatability.rms,
+            repeatability
+                .correlation
+                .map(|r| format!("{:.3}", r))
+                .unwrap_or_else(|| "-".to_string()),
+        );
+    }
+
+    let csv_path = workspace.join("repeatability.csv");
+    let mut fd = File::create(&csv_path)?;
+    writeln!(fd, "SV, signal, days, samples, rms, correlation")?;
+    for (sv, signal, repeatability) in &repeatabilities {
+        writeln!(
+            fd,
+            "{}, {}, {}, {}, {:.3}, {}",
+            sv,
+            signal,
+            repeatability.days,
+            repeatability.samples,
+            repeatability.rms,
+            repeatability
+                .correlation
+                .map(|r| format!("{:.3}", r))
+                .unwrap_or_default(),
+        )?;
+    }
+    info!("\"{}\" has been generated", csv_path.display());
+
+    #[cfg(feature = "plot")]
+    {
+        let mut plot_ctx = PlotContext::new();
+        plot_stack(&mut plot_ctx, &stack, bin);
+
+        let html_path = workspace.join("stack.html");
+        let mut fd = File::create(&html_path)?;
+        write!(fd, "{}", plot_ctx.to_html())?;
+        info!("html rendered in \"{}\"", html_path.display());
+        if !cli.quiet() {
+            open_with_web_browser(html_path.to_string_lossy().as_ref());
+        }
+    }
+    Ok(())
+}
+
+/*
+ * One plot per signal: each day of each satellite versus the offset
+ * within the day, followed by the stacked series. First satellite visible.
+ */
+#[cfg(feature = "plot")]
+fn plot_stack(plot_ctx: &mut PlotContext, stack: &SiderealStack, bin: Duration) {
+    let signals: BTreeSet<&StackedSignal> = stack.series.keys().map(|(_, signal)| signal).collect();
+    for signal in signals {
+        let y_label = match signal {
+            StackedSignal::Ssi(_) => "SSI [dB-Hz]",
+            StackedSignal::Multipath(_) => "Multipath [m]",
+        };
+        plot_ctx.add_cartesian2d_plot(
+            &format!("{} stacked by sidereal day", signal),
+            "Offset within the day [h]",
+            y_label,
+        );
+        let series = stack
+            .series
+            .iter()
+            .filter(|((_, rhs), _)| rhs == signal)
+            .enumerate();
+        for (index, ((sv, _), days)) in series {
+            let visible = if index == 0 {
+                Visible::True
+            } else {
+                Visible::LegendOnly
+            };
+            for (day, samples) in days {
+                let hours: Vec<f64> = samples
+                    .iter()
+                    .map(|(dt, _)| dt.to_seconds() / 3600.0)
+                    .collect();
+                let values: Vec<f64> = samples.iter().map(|(_, value)| *value).collect();
+                let trace = Scatter::new(hours, values)
+                    .mode(Mode::Markers)
+                    .name(&format!("{} day {}", sv, day))
+                    .visible(visible.clone());
+                plot_ctx.add_trace(trace);
+            }
+            let stacked = stack.stacked(*sv, signal, bin);
+            let hours: Vec<f64> = stacked
+                .iter()
+                .map(|(dt, _)| dt.to_seconds() / 3600.0)
+                .collect();
+            let values: Vec<f64> = stacked.iter().map(|(_, value)| *value).collect();
+            let trace = Scatter::new(hours, values)
+                .mode(Mode::Lines)
+                .name(&format!("{} stacked", sv))
+                .visible(visible);
+            plot_ctx.add_trace(trace);
+        }
+    }
+    trace!("sidereal stacking");
+}
@@ -0,0 +1,203 @@
+//! Satellite health timeline, from broadcast ephemeris
+use crate::cli::Cli;
+#[cfg(feature = "plot")]
+use crate::fops::open_with_web_browser;
+#[cfg(feature = "plot")]
+use crate::graph::{build_chart_epoch_axis, PlotContext};
+use crate::Error;
+use clap::ArgMatches;
+use itertools::Itertools;
+#[cfg(feature = "plot")]
+use plotly::color::NamedColor;
+#[cfg(feature = "plot")]
+use plotly::common::{Line, Mode};
+use rinex::prelude::{Duration, Epoch, Rinex, SV};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+/*
+ * Time interval during which a satellite kept the same health status
+ */
+struct Interval {
+    start: Epoch,
+    end: Epoch,
+    healthy: bool,
+}
+
+impl Interval {
+    fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+/*
+ * Builds the health timeline of each satellite: the status declared in a frame
+ * holds until the following frame, the last one until the end of the file.
+ * When several frames are published at once (I/NAV and F/NAV for example),
+ * the satellite is unhealthy if any of them says so.
+ */
+fn timeline(nav: &Rinex) -> BTreeMap<SV, Vec<Interval>> {
+    let mut status = BTreeMap::<SV, BTreeMap<Epoch, bool>>::new();
+    for (toc, (_, sv, ephemeris)) in nav.ephemeris() {
+        let healthy = status.entry(sv).or_default().entry(*toc).or_insert(true);
+        *healthy &= ephemeris.is_healthy();
+    }
+    let last_epoch = nav.last_epoch();
+    let mut timeline = BTreeMap::<SV, Vec<Interval>>::new();
+    for (sv, status) in status {
+        let mut intervals = Vec::<Interval>::new();
+        let mut status = status.into_iter().peekable();
+        while let Some((start, healthy)) = status.next() {
+            let end = match status.peek() {
+                Some((next, _)) => *next,
+                None => last_epoch.unwrap_or(start).max(start),
+            };
+            match intervals.last_mut() {
+                Some(last) if last.healthy == healthy => last.end = end,
+                _ => intervals.push(Interval {
+                    start,
+                    end,
+                    healthy,
+                }),
+            }
+        }
+        timeline.insert(sv, intervals);
+    }
+    timeline
+}
+
+/*
+ * SV health opmode: lists unhealthy intervals,
+ * generates the health timeline
+ */
+pub fn sv_health(cli: &Cli, matches: &ArgMatches) -> Result<(), Error> {
+    let path = matches.get_one::<PathBuf>("nav").unwrap();
+    let nav = Rinex::from_file(path.to_string_lossy().as_ref())?;
+    if !nav.is_navigation_rinex() {
+        return Err(Error::MissingNavigationRinex);
+    }
+    let mut timeline = timeline(&nav);
+    if timeline.is_empty() {
+        error!("\"{}\": no ephemeris frame", path.display());
+        return Ok(());
+    }
+    let unhealthy: Vec<SV> = timeline
+        .iter()
+        .filter_map(|(sv, intervals)| {
+            if intervals.iter().any(|interval| !interval.healthy) {
+                Some(*sv)
+            } else {
+                None
+            }
+        })
+        .collect();
+    info!(
+        "{} out of {} satellites were unhealthy at some point",
+        unhealthy.len(),
+        timeline.len()
+    );
+
+    let stem = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .and_then(|name| name.split('.').next().map(|s| s.to_string()))
+        .unwrap_or_else(|| "NAV".to_string());
+    let workspace = cli.workspace(&format!("SV-HEALTH-{}", stem));
+
+    /* unhealthy intervals */
+    println!(
+        "{:<5} {:<32} {:<32} {:>10}",
+        "SV", "start", "end", "duration"
+    );
+    for (sv, intervals) in &timeline {
+        for interval in intervals.iter().filter(|interval| !interval.healthy) {
+            println!(
+                "{:<5} {:<32} {:<32} {:>10}",
+                sv.to_string(),
+                interval.start.to_string(),
+                interval.end.to_string(),
+                interval
+                    .duration()
+                    .round(Duration::from_seconds(1.0))
+                    .to_string(),
+            );
+        }
+    }
+    if !unhealthy.is_empty() {
+        println!(
+            "unhealthy satellites: {}",
+            unhealthy.iter().map(|sv| sv.to_string()).join(",")
+        );
+    }
+
+    let csv_path = workspace.join("sv_health.csv");
+    let mut fd = File::create(&csv_path)?;
+    writeln!(fd, "SV, start, end, duration [s], healthy")?;
+    for (sv, intervals) in &timeline {
+        for interval in intervals {
+            writeln!(
+                fd,
+                "{}, {}, {}, {:.0}, {}",
+                sv,
+                interval.start,
+                interval.end,
+                interval.duration().to_seconds(),
+                interval.healthy
+            )?;
+        }
+    }
+    info!("\"{}\" has been generated", csv_path.display());
+
+    if matches.get_flag("unhealthy") {
+        timeline.retain(|sv, _| unhealthy.contains(sv));
+    }
+    #[cfg(feature = "plot")]
+    {
+        let mut plot_ctx = PlotContext::new();
+        plot_timeline(&mut plot_ctx, &timeline);
+
+        let html_path = workspace.join("sv_health.html");
+        let mut fd = File::create(&html_path)
+            .unwrap_or_else(|e| panic!("failed to create {}: {:?}", html_path.display(), e));
+        write!(fd, "{}", plot_ctx.to_html()).unwrap_or_else(|e| {
+            panic!("failed to render HTML content: {:?}", e);
+        });
+        info!("html rendered in \"{}\"", html_path.display());
+        if !cli.quiet() {
+            open_with_web_browser(html_path.to_string_lossy().as_ref());
+        }
+    }
+    Ok(())
+}
+
+/*
+ * Health timeline: one horizontal bar per satellite and interval,
+ * colored by health status
+ */
+#[cfg(feature = "plot")]
+fn plot_timeline(plot_ctx: &mut PlotContext, timeline: &BTreeMap<SV, Vec<Interval>>) {
+    plot_ctx.add_timedomain_plot("Satellite health", "SV");
+    let mut legend = (false, false);
+    for (sv, intervals) in timeline {
+        for interval in intervals {
+            let (name, color, shown) = if interval.healthy {
+                ("healthy", NamedColor::Green, &mut legend.0)
+            } else {
+                ("unhealthy", NamedColor::Red, &mut legend.1)
+            };
+            let trace = build_chart_epoch_axis(
+                name,
+                Mode::Lines,
+                vec![interval.start, interval.end],
+                vec![sv.to_string(), sv.to_string()],
+            )
+            .line(Line::new().width(8.0).color(color))
+            .legend_group(name)
+            .show_legend(!*shown);
+            *shown = true;
+            plot_ctx.add_trace(trace);
+        }
+    }
+}
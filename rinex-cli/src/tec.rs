@@ -0,0 +1,204 @@
+//! Observed slant TEC versus Global Ionosphere Map
+use crate::cli::Cli;
+#[cfg(feature = "plot")]
+use crate::fops::open_with_web_browser;
+#[cfg(feature = "plot")]
+use crate::graph::{build_chart_epoch_axis, PlotContext};
+use crate::visibility::parse_position;
+use crate::Error;
+use clap::ArgMatches;
+#[cfg(feature = "plot")]
+use plotly::common::{Mode, Visible};
+#[cfg(feature = "plot")]
+use plotly::Scatter;
+#[cfg(feature = "plot")]
+use rinex::prelude::Epoch;
+use rinex::prelude::{Rinex, SV};
+use rinex::processing::{gim_residuals, TecResidual};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+/*
+ * TEC opmode: compares the observed slant TEC to the map,
+ * prints the statistics of each satellite,
+ * plots TEC and residuals versus time and elevation
+ */
+pub fn tec(cli: &Cli, matches: &ArgMatches) -> Result<(), Error> {
+    let obs_path = matches.get_one::<PathBuf>("obs").unwrap();
+    let obs = Rinex::from_file(obs_path.to_string_lossy().as_ref())?;
+    if !obs.is_observation_rinex() {
+        return Err(Error::MissingObservationRinex);
+    }
+    let nav_path = matches.get_one::<PathBuf>("nav").unwrap();
+    let nav = Rinex::from_file(nav_path.to_string_lossy().as_ref())?;
+    if !nav.is_navigation_rinex() {
+        return Err(Error::MissingNavigationRinex);
+    }
+    let gim_path = matches.get_one::<PathBuf>("ionex").unwrap();
+    let gim = Rinex::from_file(gim_path.to_string_lossy().as_ref())?;
+    if !gim.is_ionex_2d() {
+        return Err(Error::MissingIonex);
+    }
+    let position = match matches.get_one::<String>("pos") {
+        Some(pos) => parse_position(pos)?,
+        None => match obs.header.ground_position {
+            Some(position) => position,
+            None => {
+                let position = obs.approx_position(&nav).ok_or(Error::MissingPosition)?;
+                info!("coarse position estimate {:?}", position.to_ecef_wgs84());
+                position
+            },
+        },
+    };
+    let mask = *matches.get_one::<f64>("mask").unwrap();
+
+    let residuals = gim_residuals(&obs, &nav, &gim, position, mask);
+    if residuals.is_empty() {
+        error!("no residual could be computed: check the time frames and the signals");
+        return Ok(());
+    }
+    let mut series = BTreeMap::<SV, Vec<&TecResidual>>::new();
+    for residual in &residuals {
+        series.entry(residual.sv).or_default().push(residual);
+    }
+    info!(
+        "{} residuals, {} satellites above {}°",
+        residuals.len(),
+        series.len(),
+        mask
+    );
+
+    let stem = obs_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .and_then(|name| name.split('.').next().map(|s| s.to_string()))
+        .unwrap_or_else(|| "OBS".to_string());
+    let workspace = cli.workspace(&format!("TEC-{}", stem));
+
+    /* statistics: the mean exhibits the (uncalibrated) code biases */
+    println!(
+        "{:<5} {:<8} {:>8} {:>12} {:>12} {:>12}",
+        "SV", "signals", "samples", "mean [TECu]", "std [TECu]", "rms [TECu]"
+    );
+    for (sv, residuals) in &series {
+        let n = residuals.len() as f64;
+        let mean = residuals.iter().map(|r| r.residual).sum::<f64>() / n;
+        let std = (residuals
+            .iter()
+            .map(|r| (r.residual - mean).powi(2))
+            .sum::<f64>()
+            / n)
+            .sqrt();
+        let rms = (residuals.iter().map(|r| r.residual.powi(2)).sum::<f64>() / n).sqrt();
+        let (obs1, obs2) = &residuals[0].observables;
+        println!(
+            "{:<5} {:<8} {:>8} {:>12.3} {:>12.3} {:>12.3}",
+            sv.to_string(),
+            format!("{}-{}", obs1, obs2),
+            residuals.len(),
+            mean,
+            std,
+            rms
+        );
+    }
+
+    let csv_path = workspace.join("tec.csv");
+    let mut fd = File::create(&csv_path)?;
+    writeln!(
+        fd,
+        "Epoch, SV, elevation [°], azimuth [°], IPP latitude [°], IPP longitude [°], observed [TECu], modeled [TECu], residual [TECu]"
+    )?;
+    for residual in &residuals {
+        writeln!(
+            fd,
+            "{}, {}, {:.3}, {:.3}, {:.3}, {:.3}, {:.3}, {:.3}, {:.3}",
+            residual.epoch,
+            residual.sv,
+            residual.elevation,
+            residual.azimuth,
+            residual.ipp.latitude,
+            residual.ipp.longitude,
+            residual.observed,
+            residual.modeled,
+            residual.residual
+        )?;
+    }
+    info!("\"{}\" has been generated", csv_path.display());
+
+    #[cfg(feature = "plot")]
+    {
+        let mut plot_ctx = PlotContext::new();
+        plot_tec(&mut plot_ctx, &series);
+
+        let html_path = workspace.join("tec.html");
+        let mut fd = File::create(&html_path)
+            .unwrap_or_else(|e| panic!("failed to create {}: {:?}", html_path.display(), e));
+        write!(fd, "{}", plot_ctx.to_html()).unwrap_or_else(|e| {
+            panic!("failed to render HTML content: {:?}", e);
+        });
+        info!("html rendered in \"{}\"", html_path.display());
+        if !cli.quiet() {
+            open_with_web_browser(html_path.to_string_lossy().as_ref());
+        }
+    }
+    Ok(())
+}
+
+/*
+ * Observed and modeled slant TEC versus time, then residuals
+ * versus time and elevation: one trace per satellite, first one visible
+ */
+#[cfg(feature = "plot")]
+fn plot_tec(plot_ctx: &mut PlotContext, series: &BTreeMap<SV, Vec<&TecResidual>>) {
+    let visible = |index: usize| {
+        if index == 0 {
+            Visible::True
+        } else {
+            Visible::LegendOnly
+        }
+    };
+    plot_ctx.add_timedomain_plot("Slant TEC", "TEC [TECu]");
+    for (index, (sv, residuals)) in series.iter().enumerate() {
+        let epochs: Vec<Epoch> = residuals.iter().map(|r| r.epoch).collect();
+        let observed: Vec<f64> = residuals.iter().map(|r| r.observed).collect();
+        let modeled: Vec<f64> = residuals.iter().map(|r| r.modeled).collect();
+        let trace = build_chart_epoch_axis(
+            &format!("{}(observed)", sv),
+            Mode::Markers,
+            epochs.clone(),
+            observed,
+        )
+        .visible(visible(index));
+        plot_ctx.add_trace(trace);
+        let trace = build_chart_epoch_axis(&format!("{}(map)", sv), Mode::Lines, epochs, modeled)
+            .visible(visible(index));
+        plot_ctx.add_trace(trace);
+    }
+
+    plot_ctx.add_timedomain_plot("Slant TEC residuals", "Residual [TECu]");
+    for (index, (sv, residuals)) in series.iter().enumerate() {
+        let epochs: Vec<Epoch> = residuals.iter().map(|r| r.epoch).collect();
+        let values: Vec<f64> = residuals.iter().map(|r| r.residual).collect();
+        let trace = build_chart_epoch_axis(&sv.to_string(), Mode::Markers, epochs, values)
+            .visible(visible(index));
+        plot_ctx.add_trace(trace);
+    }
+
+    plot_ctx.add_cartesian2d_plot(
+        "Slant TEC residuals versus Elevation",
+        "Elevation [°]",
+        "Residual [TECu]",
+    );
+    for (index, (sv, residuals)) in series.iter().enumerate() {
+        let elevations: Vec<f64> = residuals.iter().map(|r| r.elevation).collect();
+        let values: Vec<f64> = residuals.iter().map(|r| r.residual).collect();
+        let trace = Scatter::new(elevations, values)
+            .mode(Mode::Markers)
+            .name(&sv.to_string())
+            .visible(visible(index));
+        plot_ctx.add_trace(trace);
+    }
+    trace!("slant tec residuals");
+}
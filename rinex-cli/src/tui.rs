@@ -0,0 +1,358 @@
+//! Interactive terminal browser
+use crate::Error;
+use clap::ArgMatches;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{
+        Block, Borders, List, ListItem, ListState, Paragraph, Row, Sparkline, Table, TableState,
+    },
+    Frame, Terminal,
+};
+use rinex::observation::ObservationData;
+use rinex::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::io::stdout;
+use std::path::{Path, PathBuf};
+
+/// Width of an observation column
+const COLUMN_WIDTH: u16 = 14;
+
+/// Browsable panes
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Pane {
+    Epochs,
+    Header,
+    Table,
+}
+
+impl Pane {
+    fn next(self) -> Self {
+        match self {
+            Self::Epochs => Self::Header,
+            Self::Header => Self::Table,
+            Self::Table => Self::Epochs,
+        }
+    }
+    fn previous(self) -> Self {
+        match self {
+            Self::Epochs => Self::Table,
+            Self::Header => Self::Epochs,
+            Self::Table => Self::Header,
+        }
+    }
+}
+
+struct App {
+    rinex: Rinex,
+    title: String,
+    header: Vec<String>,
+    header_scroll: u16,
+    epochs: Vec<(Epoch, EpochFlag)>,
+    epoch_state: ListState,
+    table_state: TableState,
+    /// selected observable (column)
+    observable: usize,
+    focus: Pane,
+}
+
+impl App {
+    fn new(rinex: Rinex, path: &Path) -> Self {
+        let epochs: Vec<(Epoch, EpochFlag)> = match rinex.is_observation_rinex() {
+            true => rinex.observation().map(|(key, _)| *key).collect(),
+            false => rinex.epoch().map(|t| (t, EpochFlag::Ok)).collect(),
+        };
+        let title = format!(
+            " {} | {} V{} | {} epochs | Tab: pane, arrows: browse, q: quit",
+            path.file_name().unwrap_or_default().to_string_lossy(),
+            rinex.header.rinex_type,
+            rinex.header.version,
+            epochs.len(),
+        );
+        let header = rinex
+            .header
+            .to_string()
+            .lines()
+            .map(|line| line.to_string())
+            .collect();
+        let mut epoch_state = ListState::default();
+        if !epochs.is_empty() {
+            epoch_state.select(Some(0));
+        }
+        Self {
+            rinex,
+            title,
+            header,
+            header_scroll: 0,
+            epochs,
+            epoch_state,
+            table_state: TableState::default().with_selected(Some(0)),
+            observable: 0,
+            focus: Pane::Epochs,
+        }
+    }
+    fn epoch(&self) -> Option<(Epoch, EpochFlag)> {
+        self.epochs.get(self.epoch_state.selected()?).copied()
+    }
+    /// Observations of the selected epoch
+    fn vehicles(&self) -> Option<&BTreeMap<SV, HashMap<Observable, ObservationData>>> {
+        let key = self.epoch()?;
+        let (_, vehicles) = self.rinex.record.as_obs()?.get(&key)?;
+        Some(vehicles)
+    }
+    /// Observables of the selected epoch, sorted
+    fn observables(&self) -> Vec<Observable> {
+        let mut observables: Vec<Observable> = self
+            .vehicles()
+            .into_iter()
+            .flat_map(|vehicles| vehicles.values().flat_map(|obs| obs.keys().cloned()))
+            .collect();
+        observables.sort();
+        observables.dedup();
+        observables
+    }
+    /// Selected satellite and observable
+    fn selection(&self) -> Option<(SV, Observable)> {
+        let sv = *self.vehicles()?.keys().nth(self.table_state.selected()?)?;
+        let observable = self.observables().get(self.observable)?.clone();
+        Some((sv, observable))
+    }
+    /*
+     * Moves selection within the focused pane, by given offset
+     */
+    fn scroll(&mut self, offset: isize) {
+        match self.focus {
+            Pane::Header => {
+                let max = self.header.len().saturating_sub(1) as isize;
+                self.header_scroll = (self.header_scroll as isize + offset).clamp(0, max) as u16;
+            },
+            Pane::Epochs => {
+                let max = self.epochs.len().saturating_sub(1) as isize;
+                let index = self.epoch_state.selected().unwrap_or(0) as isize;
+                self.epoch_state
+                    .select(Some((index + offset).clamp(0, max) as usize));
+            },
+            Pane::Table => {
+                let max = self.vehicles().map(|v| v.len()).unwrap_or(0);
+                let max = max.saturating_sub(1) as isize;
+                let index = self.table_state.selected().unwrap_or(0) as isize;
+                self.table_state
+                    .select(Some((index + offset).clamp(0, max) as usize));
+            },
+        }
+    }
+    /*
+     * Handles a key press, returns false when exiting
+     */
+    fn on_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Tab => self.focus = self.focus.next(),
+            KeyCode::BackTab => self.focus = self.focus.previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll(1),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll(-1),
+            KeyCode::PageDown => self.scroll(10),
+            KeyCode::PageUp => self.scroll(-10),
+            KeyCode::Home => self.scroll(isize::MIN / 2),
+            KeyCode::End => self.scroll(isize::MAX / 2),
+            KeyCode::Right | KeyCode::Char('l') => {
+                let max = self.observables().len().saturating_sub(1);
+                self.observable = (self.observable + 1).min(max);
+            },
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.observable = self.observable.saturating_sub(1);
+            },
+            _ => {},
+        }
+        true
+    }
+    fn block(&self, pane: Pane, title: String) -> Block<'static> {
+        let style = match self.focus == pane {
+            true => Style::default().fg(Color::Yellow),
+            false => Style::default(),
+        };
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(style)
+            .title(title)
+    }
+}
+
+fn draw(frame: &mut Frame, app: &mut App) {
+    let [title, body] =
+        Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(frame.size());
+    let [epochs, data] =
+        Layout::horizontal([Constraint::Length(36), Constraint::Min(0)]).areas(body);
+    let [header, table, sparkline] = Layout::vertical([
+        Constraint::Percentage(35),
+        Constraint::Min(0),
+        Constraint::Length(7),
+    ])
+    .areas(data);
+
+    frame.render_widget(
+        Paragraph::new(app.title.as_str()).style(Style::default().add_modifier(Modifier::BOLD)),
+        title,
+    );
+
+    // header pane
+    let lines: Vec<Line> = app.header.iter().map(|l| Line::from(l.as_str())).collect();
+    frame.render_widget(
+        Paragraph::new(lines)
+            .scroll((app.header_scroll, 0))
+            .block(app.block(Pane::Header, " Header ".to_string())),
+        header,
+    );
+
+    // epoch list
+    let items: Vec<ListItem> = app
+        .epochs
+        .iter()
+        .map(|(t, flag)| ListItem::new(format!("{} {}", t, flag)))
+        .collect();
+    let list = List::new(items)
+        .block(app.block(Pane::Epochs, " Epochs ".to_string()))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, epochs, &mut app.epoch_state);
+
+    if !app.rinex.is_observation_rinex() {
+        frame.render_widget(
+            Paragraph::new("Per-satellite observations are only available for Observation RINEX")
+                .block(app.block(Pane::Table, " Observations ".to_string())),
+            table,
+        );
+        frame.render_widget(Block::default().borders(Borders::ALL), sparkline);
+        return;
+    }
+
+    // observations table: selected observable column remains visible
+    let observables = app.observables();
+    let visible = ((table.width.saturating_sub(7)) / (COLUMN_WIDTH + 1)).max(1) as usize;
+    let first = app.observable.saturating_sub(visible - 1);
+    let columns = observables.iter().enumerate().skip(first).take(visible);
+
+    let highlight = Style::default().fg(Color::Yellow);
+    let mut header_row = vec![Line::from("SV")];
+    for (index, observable) in columns.clone() {
+        let line = Line::from(format!("{:>14}", observable.to_string()));
+        header_row.push(match index == app.observable {
+            true => line.style(highlight),
+            false => line,
+        });
+    }
+    let rows: Vec<Row> = app
+        .vehicles()
+        .into_iter()
+        .flat_map(|vehicles| vehicles.iter())
+        .map(|(sv, observations)| {
+            let mut cells = vec![sv.to_string()];
+            for (_, observable) in columns.clone() {
+                cells.push(match observations.get(observable) {
+                    Some(data) => format!("{:14.3}", data.obs),
+                    None => String::new(),
+                });
+            }
+            Row::new(cells)
+        })
+        .collect();
+    let mut widths = vec![Constraint::Length(4)];
+    widths.extend(columns.map(|_| Constraint::Length(COLUMN_WIDTH)));
+
+    let title = match app.epoch() {
+        Some((t, flag)) => format!(" Observations {} ({}) ", t, flag),
+        None => " Observations ".to_string(),
+    };
+    let table_widget = Table::new(rows, widths)
+        .header(Row::new(header_row).style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(app.block(Pane::Table, title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(table_widget, table, &mut app.table_state);
+
+    // sparkline of the selected observable, up to the selected epoch
+    let (sv, observable) = match app.selection() {
+        Some(selection) => selection,
+        None => {
+            frame.render_widget(Block::default().borders(Borders::ALL), sparkline);
+            return;
+        },
+    };
+    let width = sparkline.width.saturating_sub(2) as usize;
+    let current = app.epoch_state.selected().unwrap_or(0);
+    let start = (current + 1).saturating_sub(width);
+    let values: Vec<Option<f64>> = app.epochs[start..=current]
+        .iter()
+        .map(|key| {
+            let (_, vehicles) = app.rinex.record.as_obs()?.get(key)?;
+            Some(vehicles.get(&sv)?.get(&observable)?.obs)
+        })
+        .collect();
+    let min = values
+        .iter()
+        .flatten()
+        .fold(f64::INFINITY, |a, b| a.min(*b));
+    let max = values
+        .iter()
+        .flatten()
+        .fold(f64::NEG_INFINITY, |a, b| a.max(*b));
+    // gaps are blank, the lowest value remains visible
+    let data: Vec<u64> = values
+        .iter()
+        .map(|value| match value {
+            Some(value) if max > min => 1 + ((value - min) / (max - min) * 99.0) as u64,
+            Some(_) => 50,
+            None => 0,
+        })
+        .collect();
+    let title = match min.is_finite() {
+        true => format!(" {} {}: {:.3} .. {:.3} ", sv, observable, min, max),
+        false => format!(" {} {}: no data ", sv, observable),
+    };
+    frame.render_widget(
+        Sparkline::default()
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .data(&data)
+            .max(100)
+            .style(Style::default().fg(Color::Cyan)),
+        sparkline,
+    );
+}
+
+/*
+ * Runs the event loop, until user exits
+ */
+fn run(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    app: &mut App,
+) -> Result<(), Error> {
+    loop {
+        terminal.draw(|frame| draw(frame, app))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !app.on_key(key.code) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+pub fn tui(matches: &ArgMatches) -> Result<(), Error> {
+    let path = matches.get_one::<PathBuf>("file").unwrap();
+    let rinex = Rinex::from_path(path)?;
+    let mut app = App::new(rinex, path);
+
+    enable_raw_mode()?;
+    execute!(stdout(), EnterAlternateScreen)?;
+    let result = Terminal::new(CrosstermBackend::new(stdout()))
+        .map_err(Error::from)
+        .and_then(|mut terminal| run(&mut terminal, &mut app));
+    // terminal is always restored
+    disable_raw_mode()?;
+    execute!(stdout(), LeaveAlternateScreen)?;
+    result
+}
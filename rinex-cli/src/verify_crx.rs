@@ -0,0 +1,40 @@
+//! CRINEX archive integrity verification
+use crate::Error;
+use clap::ArgMatches;
+use rinex::hatanaka::verify;
+use std::path::PathBuf;
+
+/*
+ * Verify CRX opmode: decompression / recompression cycle of each file,
+ * that must reproduce the original content
+ */
+pub fn verify_crx(matches: &ArgMatches) -> Result<(), Error> {
+    let mut failures = 0;
+    for path in matches.get_many::<PathBuf>("crx").unwrap() {
+        match verify(path.to_string_lossy().as_ref()) {
+            Ok(report) => match report.mismatch {
+                None => println!("PASS {} ({} lines)", path.display(), report.lines),
+                Some(mismatch) => {
+                    failures += 1;
+                    println!("FAIL {}: line {} differs", path.display(), mismatch.line);
+                    println!(
+                        "  original:     {}",
+                        mismatch.original.as_deref().unwrap_or("<end of file>")
+                    );
+                    println!(
+                        "  recompressed: {}",
+                        mismatch.recompressed.as_deref().unwrap_or("<end of file>")
+                    );
+                },
+            },
+            Err(e) => {
+                failures += 1;
+                println!("FAIL {}: {}", path.display(), e);
+            },
+        }
+    }
+    if failures > 0 {
+        return Err(Error::IntegrityCheckFailure(failures));
+    }
+    Ok(())
+}
@@ -0,0 +1,201 @@
+//! Satellite visibility prediction, for survey planning
+use crate::cli::Cli;
+#[cfg(feature = "plot")]
+use crate::fops::open_with_web_browser;
+#[cfg(feature = "plot")]
+use crate::graph::{build_chart_epoch_axis, PlotContext};
+use crate::Error;
+use clap::ArgMatches;
+use map_3d::{geodetic2ecef, Ellipsoid};
+#[cfg(feature = "plot")]
+use plotly::common::{Line, Mode};
+use rinex::navigation::visibility::predict;
+#[cfg(feature = "plot")]
+use rinex::navigation::visibility::Pass;
+#[cfg(feature = "plot")]
+use rinex::prelude::SV;
+use rinex::prelude::{Duration, Epoch, GroundPosition, Rinex};
+#[cfg(feature = "plot")]
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/*
+ * Parses "lat,lon,alt" (ddeg, ddeg, meters) into a ground position
+ */
+pub(crate) fn parse_position(pos: &str) -> Result<GroundPosition, Error> {
+    let items: Vec<f64> = pos
+        .split(',')
+        .map(|item| item.trim().parse::<f64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| Error::InvalidPosition(pos.to_string()))?;
+    if items.len() != 3 {
+        return Err(Error::InvalidPosition(pos.to_string()));
+    }
+    let (x, y, z) = geodetic2ecef(
+        items[0].to_radians(),
+        items[1].to_radians(),
+        items[2],
+        Ellipsoid::WGS84,
+    );
+    Ok(GroundPosition::from_ecef_wgs84((x, y, z)))
+}
+
+/*
+ * Visibility opmode: predicts the satellite passes,
+ * generates the rise/set table and the visibility plots
+ */
+pub fn visibility(cli: &Cli, matches: &ArgMatches) -> Result<(), Error> {
+    let path = matches.get_one::<PathBuf>("nav").unwrap();
+    let nav = Rinex::from_file(path.to_string_lossy().as_ref())?;
+    if !nav.is_navigation_rinex() {
+        return Err(Error::MissingNavigationRinex);
+    }
+    let position = parse_position(matches.get_one::<String>("pos").unwrap())?;
+    let mask = *matches.get_one::<f64>("mask").unwrap();
+    let step = Duration::from_seconds(*matches.get_one::<f64>("step").unwrap());
+
+    let (start, end) = match matches.get_many::<String>("window") {
+        Some(window) => {
+            let window: Vec<Epoch> = window
+                .map(|t| Epoch::from_str(t).map_err(|_| Error::InvalidDate(t.to_string())))
+                .collect::<Result<_, _>>()?;
+            (window[0], window[1])
+        },
+        None => match (nav.first_epoch(), nav.last_epoch()) {
+            (Some(start), Some(end)) => (start, end),
+            _ => {
+                error!("\"{}\": empty navigation record", path.display());
+                return Ok(());
+            },
+        },
+    };
+
+    let passes = predict(&nav, position, start, end, step, mask);
+    info!(
+        "{} satellites rise above {}° between {} and {}",
+        passes.len(),
+        mask,
+        start,
+        end
+    );
+
+    let stem = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .and_then(|name| name.split('.').next().map(|s| s.to_string()))
+        .unwrap_or_else(|| "NAV".to_string());
+    let workspace = cli.workspace(&format!("VISIBILITY-{}", stem));
+
+    /* rise/set table */
+    println!(
+        "{:<5} {:<32} {:<32} {:>10} {:>8}",
+        "SV", "rise", "set", "duration", "max el."
+    );
+    let csv_path = workspace.join("visibility.csv");
+    let mut fd = File::create(&csv_path)?;
+    writeln!(
+        fd,
+        "SV, rise, set, duration [s], culmination, max elevation [°]"
+    )?;
+    for (sv, passes) in &passes {
+        for pass in passes {
+            println!(
+                "{:<5} {:<32} {:<32} {:>10} {:>7.2}°",
+                sv.to_string(),
+                pass.rise.to_string(),
+                pass.set.to_string(),
+                pass.duration()
+                    .round(Duration::from_seconds(1.0))
+                    .to_string(),
+                pass.max_elevation
+            );
+            writeln!(
+                fd,
+                "{}, {}, {}, {:.0}, {}, {:.2}",
+                sv,
+                pass.rise,
+                pass.set,
+                pass.duration().to_seconds(),
+                pass.culmination,
+                pass.max_elevation
+            )?;
+        }
+    }
+    info!("\"{}\" has been generated", csv_path.display());
+
+    #[cfg(feature = "plot")]
+    {
+        let mut plot_ctx = PlotContext::new();
+        plot_gantt(&mut plot_ctx, &passes);
+        plot_in_sight(&mut plot_ctx, &passes, start, end, step);
+
+        let html_path = workspace.join("visibility.html");
+        let mut fd = File::create(&html_path)
+            .unwrap_or_else(|e| panic!("failed to create {}: {:?}", html_path.display(), e));
+        write!(fd, "{}", plot_ctx.to_html()).unwrap_or_else(|e| {
+            panic!("failed to render HTML content: {:?}", e);
+        });
+        info!("html rendered in \"{}\"", html_path.display());
+        if !cli.quiet() {
+            open_with_web_browser(html_path.to_string_lossy().as_ref());
+        }
+    }
+    Ok(())
+}
+
+/*
+ * Gantt chart: one horizontal bar per satellite pass
+ */
+#[cfg(feature = "plot")]
+fn plot_gantt(plot_ctx: &mut PlotContext, passes: &BTreeMap<SV, Vec<Pass>>) {
+    plot_ctx.add_timedomain_plot("Satellite visibility", "SV");
+    for (sv, passes) in passes {
+        for (index, pass) in passes.iter().enumerate() {
+            let trace = build_chart_epoch_axis(
+                &sv.to_string(),
+                Mode::Lines,
+                vec![pass.rise, pass.set],
+                vec![sv.to_string(), sv.to_string()],
+            )
+            .line(Line::new().width(8.0))
+            .legend_group(sv.to_string())
+            .show_legend(index == 0);
+            plot_ctx.add_trace(trace);
+        }
+    }
+}
+
+/*
+ * Number of satellites in sight, over time
+ */
+#[cfg(feature = "plot")]
+fn plot_in_sight(
+    plot_ctx: &mut PlotContext,
+    passes: &BTreeMap<SV, Vec<Pass>>,
+    start: Epoch,
+    end: Epoch,
+    step: Duration,
+) {
+    let mut epochs = Vec::<Epoch>::new();
+    let mut in_sight = Vec::<usize>::new();
+    if step <= Duration::ZERO {
+        return;
+    }
+    let mut t = start;
+    while t <= end {
+        epochs.push(t);
+        in_sight.push(
+            passes
+                .values()
+                .filter(|passes| passes.iter().any(|pass| pass.contains(t)))
+                .count(),
+        );
+        t += step;
+    }
+    plot_ctx.add_timedomain_plot("Satellites in sight", "Satellites");
+    let trace = build_chart_epoch_axis("in sight", Mode::Lines, epochs, in_sight);
+    plot_ctx.add_trace(trace);
+}
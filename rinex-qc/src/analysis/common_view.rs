@@ -0,0 +1,544 @@
+use crate::QcOpts;
+use horrorshow::{box_html, RenderBox};
+use itertools::Itertools;
+use rinex::observation::LliFlags;
+use rinex::prelude::{Epoch, Rinex, SV};
+use rinex_qc_traits::{HtmlReport, ReportSink, ReportTable, Reportable};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Satellites tracked less than this fraction of the epochs
+/// (within the common time frame) are reported as incomplete
+const MIN_COMPLETENESS: f64 = 0.9;
+
+/// Localization of an anomaly, deduced from common view
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Localization {
+    /// No anomaly
+    Nominal,
+    /// Both stations are affected: the satellite is most likely to blame
+    Satellite,
+    /// Only the station under test is affected
+    Station,
+    /// Only the reference station is affected
+    Reference,
+}
+
+impl Localization {
+    fn new(station: bool, reference: bool) -> Self {
+        match (station, reference) {
+            (true, true) => Self::Satellite,
+            (true, false) => Self::Station,
+            (false, true) => Self::Reference,
+            (false, false) => Self::Nominal,
+        }
+    }
+}
+
+impl std::fmt::Display for Localization {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Nominal => f.write_str("Nominal"),
+            Self::Satellite => f.write_str("Satellite side"),
+            Self::Station => f.write_str("Station side"),
+            Self::Reference => f.write_str("Reference side"),
+        }
+    }
+}
+
+/// Statistics of one satellite, at one station
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SvStatistics {
+    /// Fraction of the epochs where this satellite was observed
+    pub completeness: f64,
+    /// Number of epochs where a lock loss was declared
+    pub slips: usize,
+    /// Mean signal strength [dB-Hz]: signal strength observations,
+    /// or SSI indicators when the receiver does not produce them
+    pub mean_snr: Option<f64>,
+}
+
+/// Common view comparison of one satellite
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvComparison {
+    /// Station under test
+    pub station: SvStatistics,
+    /// Reference station
+    pub reference: SvStatistics,
+    /// Incomplete tracking
+    pub completeness: Localization,
+    /// Lock losses
+    pub slips: Localization,
+    /// Weak signal
+    pub snr: Localization,
+}
+
+#[derive(Debug, Clone)]
+/// Common view comparison between the station under test
+/// and a nearby reference station. Anomalies observed at both stations
+/// are most likely satellite side, whereas anomalies observed at a single station
+/// are most likely station side (antenna, environment, receiver).
+/// Comparison is restricted to the time frame common to both stations.
+pub struct QcCommonView {
+    /// Station under test
+    pub station: String,
+    /// Reference station
+    pub reference: String,
+    /// Common time frame
+    pub time_frame: Option<(Epoch, Epoch)>,
+    /// Satellites only observed by the station under test
+    pub station_only: Vec<SV>,
+    /// Satellites only observed by the reference station
+    pub reference_only: Vec<SV>,
+    /// Comparison of each satellite in common view
+    pub common: BTreeMap<SV, SvComparison>,
+}
+
+/*
+ * Station name, from header or fallback value
+ */
+fn station_name(rinex: &Rinex, default: &str) -> String {
+    rinex
+        .header
+        .geodetic_marker
+        .as_ref()
+        .map(|marker| marker.name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| default.to_string())
+}
+
+/*
+ * Per satellite statistics, within given time frame
+ */
+fn statistics(rinex: &Rinex, start: Epoch, end: Epoch) -> HashMap<SV, SvStatistics> {
+    let within = |t: Epoch| t >= start && t <= end;
+    let total = rinex.epoch().filter(|t| within(*t)).count();
+
+    let mut epochs = HashMap::<SV, usize>::new();
+    if let Some(record) = rinex.record.as_obs() {
+        for ((t, _), (_, vehicles)) in record {
+            if within(*t) {
+                for (sv, observations) in vehicles {
+                    if !observations.is_empty() {
+                        *epochs.entry(*sv).or_default() += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    // epochs where a lock loss was declared, per satellite
+    let mut slips = HashMap::<SV, HashSet<Epoch>>::new();
+    for ((t, _), sv, _, lli) in rinex.lli() {
+        if within(t) && lli.intersects(LliFlags::LOCK_LOSS) {
+            slips.entry(sv).or_default().insert(t);
+        }
+    }
+
+    let mut snr = HashMap::<SV, Vec<f64>>::new();
+    for ((t, _), sv, _, value) in rinex.ssi() {
+        if within(t) {
+            snr.entry(sv).or_default().push(value);
+        }
+    }
+    if snr.is_empty() {
+        for ((t, _), sv, _, _, indicator) in rinex.ssi_indicator() {
+            if let Some(dbhz) = indicator.to_dbhz() {
+                if within(t) {
+                    snr.entry(sv).or_default().push(dbhz.0);
+                }
+            }
+        }
+    }
+
+    epochs
+        .into_iter()
+        .map(|(sv, epochs)| {
+            let stats = SvStatistics {
+                completeness: epochs as f64 / total as f64,
+                slips: slips
+                    .get(&sv)
+                    .map(|epochs| epochs.len())
+                    .unwrap_or_default(),
+                mean_snr: snr
+                    .get(&sv)
+                    .map(|values| values.iter().sum::<f64>() / values.len() as f64),
+            };
+            (sv, stats)
+        })
+        .collect()
+}
+
+impl QcCommonView {
+    pub fn new(station: &Rinex, reference: &Rinex, opts: &QcOpts) -> Self {
+        let time_frame = match (
+            station.first_epoch(),
+            station.last_epoch(),
+            reference.first_epoch(),
+            reference.last_epoch(),
+        ) {
+            (Some(t0), Some(t1), Some(r0), Some(r1)) if t0.max(r0) <= t1.min(r1) => {
+                Some((t0.max(r0), t1.min(r1)))
+            },
+            _ => None,
+        };
+        let (stations, references) = match time_frame {
+            Some((start, end)) => (
+                statistics(station, start, end),
+                statistics(reference, start, end),
+            ),
+            None => (HashMap::new(), HashMap::new()),
+        };
+
+        let mut station_only: Vec<SV> = stations
+            .keys()
+            .filter(|sv| !references.contains_key(sv))
+            .copied()
+            .collect();
+        station_only.sort();
+        let mut reference_only: Vec<SV> = references
+            .keys()
+            .filter(|sv| !stations.contains_key(sv))
+            .copied()
+            .collect();
+        reference_only.sort();
+
+        let weak = |stats: &SvStatistics| {
+            stats
+                .mean_snr
+                .map(|snr| snr < opts.min_snr_db)
+                .unwrap_or(false)
+        };
+        let mut common = BTreeMap::<SV, SvComparison>::new();
+        for (sv, station) in stations {
+            if let Some(reference) = references.get(&sv) {
+                common.insert(
+                    sv,
+                    SvComparison {
+                        completeness: Localization::new(
+                            station.completeness < MIN_COMPLETENESS,
+                            reference.completeness < MIN_COMPLETENESS,
+                        ),
+                        slips: Localization::new(station.slips > 0, reference.slips > 0),
+                        snr: Localization::new(weak(&station), weak(reference)),
+                        reference: reference.clone(),
+                        station,
+                    },
+                );
+            }
+        }
+        Self {
+            station: station_name(station, "Station"),
+            reference: station_name(reference, "Reference"),
+            time_frame,
+            station_only,
+            reference_only,
+            common,
+        }
+    }
+}
+
+/*
+ * Reports one metric of both stations, and its localization
+ */
+fn report_metric(
+    station: String,
+    reference: String,
+    localization: Localization,
+) -> Box<dyn RenderBox> {
+    box_html! {
+        td {
+            : station
+        }
+        td {
+            : reference
+        }
+        @ if localization == Localization::Nominal {
+            td {
+                : localization.to_string()
+            }
+        } else {
+            td {
+                b {
+                    : localization.to_string()
+                }
+            }
+        }
+    }
+}
+
+fn format_sv_list(list: &[SV]) -> String {
+    list.iter()
+        .map(|sv| sv.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn format_snr(snr: Option<f64>) -> String {
+    match snr {
+        Some(snr) => format!("{:.1} dB-Hz", snr),
+        None => "Unknown".to_string(),
+    }
+}
+
+impl Reportable for QcCommonView {
+    fn report(&self, sink: &mut dyn ReportSink) -> std::io::Result<()> {
+        sink.section("Common view comparison")?;
+        sink.value("station", &self.station.as_str().into())?;
+        sink.value("reference", &self.reference.as_str().into())?;
+        if let Some((start, end)) = self.time_frame {
+            sink.value("start", &start.to_string().into())?;
+            sink.value("end", &end.to_string().into())?;
+        }
+        sink.value("station only", &self.station_only.iter().join(", ").into())?;
+        sink.value(
+            "reference only",
+            &self.reference_only.iter().join(", ").into(),
+        )?;
+        let mut table = ReportTable::new(
+            "common view",
+            &[
+                "sv",
+                "completeness",
+                "reference completeness",
+                "slips",
+                "reference slips",
+                "mean snr [dB-Hz]",
+                "reference mean snr [dB-Hz]",
+                "incomplete tracking",
+                "lock losses",
+                "weak signal",
+            ],
+        );
+        for (sv, cmp) in &self.common {
+            table.push(vec![
+                sv.to_string().into(),
+                cmp.station.completeness.into(),
+                cmp.reference.completeness.into(),
+                cmp.station.slips.into(),
+                cmp.reference.slips.into(),
+                cmp.station.mean_snr.into(),
+                cmp.reference.mean_snr.into(),
+                cmp.completeness.to_string().into(),
+                cmp.slips.to_string().into(),
+                cmp.snr.to_string().into(),
+            ]);
+        }
+        sink.table(&table)
+    }
+}
+
+impl HtmlReport for QcCommonView {
+    fn to_html(&self) -> String {
+        panic!("common view analysis cannot be rendered on its own")
+    }
+    fn to_inline_html(&self) -> Box<dyn RenderBox + '_> {
+        box_html! {
+            tr {
+                th {
+                    : "Stations"
+                }
+                td {
+                    : format!("{} (under test) / {} (reference)", self.station, self.reference)
+                }
+            }
+            tr {
+                th {
+                    : "Common time frame"
+                }
+                @ if let Some((start, end)) = self.time_frame {
+                    td {
+                        : format!("{} - {}", start, end)
+                    }
+                } else {
+                    td {
+                        : "None: stations cannot be compared"
+                    }
+                }
+            }
+            tr {
+                th {
+                    : format!("Only tracked by {}", self.station)
+                }
+                td {
+                    : format_sv_list(&self.station_only)
+                }
+            }
+            tr {
+                th {
+                    : format!("Only tracked by {}", self.reference)
+                }
+                td {
+                    : format_sv_list(&self.reference_only)
+                }
+            }
+            tr {
+                table(class="table is-bordered") {
+                    thead {
+                        tr {
+                            th {
+                                : "SV"
+                            }
+                            th(colspan="3") {
+                                : "Completeness"
+                            }
+                            th(colspan="3") {
+                                : "Lock losses"
+                            }
+                            th(colspan="3") {
+                                : "Mean SNR"
+                            }
+                        }
+                        tr {
+                            td {
+                                : ""
+                            }
+                            @ for _ in 0..3 {
+                                td {
+                                    : self.station.clone()
+                                }
+                                td {
+                                    : self.reference.clone()
+                                }
+                                td {
+                                    : "Diagnosis"
+                                }
+                            }
+                        }
+                    }
+                    tbody {
+                        @ for (sv, comparison) in &self.common {
+                            tr {
+                                th {
+                                    : sv.to_string()
+                                }
+                                : report_metric(
+                                    format!("{:.1} %", comparison.station.completeness * 100.0),
+                                    format!("{:.1} %", comparison.reference.completeness * 100.0),
+                                    comparison.completeness);
+                                : report_metric(
+                                    comparison.station.slips.to_string(),
+                                    comparison.reference.slips.to_string(),
+                                    comparison.slips);
+                                : report_metric(
+                                    format_snr(comparison.station.mean_snr),
+                                    format_snr(comparison.reference.mean_snr),
+                                    comparison.snr);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+    #[test]
+    fn common_view() {
+        let station = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let opts = QcOpts::default();
+
+        let view = QcCommonView::new(&station, &station, &opts);
+        assert!(view.time_frame.is_some());
+        assert!(view.station_only.is_empty());
+        assert!(view.reference_only.is_empty());
+        assert_eq!(view.common.len(), station.sv().count());
+        for (sv, comparison) in &view.common {
+            assert_eq!(comparison.station, comparison.reference, "{}", sv);
+            assert_ne!(comparison.completeness, Localization::Station);
+            assert_ne!(comparison.slips, Localization::Reference);
+        }
+
+        // reference station loses G01 after 00:28 and never tracks R23
+        let g01 = SV::from_str("G01").unwrap();
+        let r23 = SV::from_str("R23").unwrap();
+        let t = Epoch::from_str("2022-03-04T00:28:30 GPST").unwrap();
+        let mut reference = station.clone();
+        if let Some(record) = reference.record.as_mut_obs() {
+            for ((epoch, _), (_, vehicles)) in record.iter_mut() {
+                vehicles.remove(&r23);
+                if *epoch >= t {
+                    vehicles.remove(&g01);
+                }
+            }
+        }
+        let view = QcCommonView::new(&station, &reference, &opts);
+        assert_eq!(view.station_only, vec![r23]);
+        assert!(view.reference_only.is_empty());
+        let comparison = view.common.get(&g01).unwrap();
+        assert_eq!(comparison.station.completeness, 1.0);
+        assert!(comparison.reference.completeness < MIN_COMPLETENESS);
+        assert_eq!(comparison.completeness, Localization::Reference);
+    }
+    #[test]
+    fn localization() {
+        assert_eq!(Localization::new(false, false), Localization::Nominal);
+        assert_eq!(Localization::new(true, true), Localization::Satellite);
+        assert_eq!(Localization::new(true, false), Localization::Station);
+        assert_eq!(Localization::new(false, true), Localization::Reference);
+    }
+    #[test]
+    fn slips_and_snr() {
+        let station = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let g01 = SV::from_str("G01").unwrap();
+        let g03 = SV::from_str("G03").unwrap();
+
+        // station declares lock losses on G01, twice on the same epoch
+        // (two signals), and tracks G03 with weak signals
+        let mut tested = station.clone();
+        let mut slip_epochs = 0;
+        if let Some(record) = tested.record.as_mut_obs() {
+            for (index, (_, (_, vehicles))) in record.iter_mut().enumerate() {
+                if let Some(observations) = vehicles.get_mut(&g01) {
+                    if index % 2 == 0 {
+                        slip_epochs += 1;
+                        for data in observations.values_mut().take(2) {
+                            data.lli = Some(LliFlags::LOCK_LOSS);
+                        }
+                    }
+                }
+                if let Some(observations) = vehicles.get_mut(&g03) {
+                    for (observable, data) in observations.iter_mut() {
+                        if observable.is_ssi_observable() {
+                            data.obs = 10.0;
+                        }
+                    }
+                }
+            }
+        }
+        // remove the original lock losses of the reference
+        let mut reference = station.clone();
+        if let Some(record) = reference.record.as_mut_obs() {
+            for (_, (_, vehicles)) in record.iter_mut() {
+                for observations in vehicles.values_mut() {
+                    for data in observations.values_mut() {
+                        data.lli = None;
+                    }
+                }
+            }
+        }
+        let view = QcCommonView::new(&tested, &reference, &QcOpts::default());
+
+        let comparison = view.common.get(&g01).unwrap();
+        assert_eq!(comparison.station.slips, slip_epochs);
+        assert_eq!(comparison.reference.slips, 0);
+        assert_eq!(comparison.slips, Localization::Station);
+
+        let comparison = view.common.get(&g03).unwrap();
+        assert_eq!(comparison.station.mean_snr, Some(10.0));
+        assert_eq!(comparison.snr, Localization::Station);
+    }
+    #[test]
+    fn disjoint_time_frames() {
+        let station = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let reference = Rinex::from_file("../test_resources/OBS/V2/AJAC3550.21O").unwrap();
+        let view = QcCommonView::new(&station, &reference, &QcOpts::default());
+        assert!(view.time_frame.is_none());
+        assert!(view.common.is_empty());
+        assert!(view.station_only.is_empty());
+        assert!(view.reference_only.is_empty());
+    }
+}
@@ -0,0 +1,146 @@
+use crate::QcOpts;
+use horrorshow::{box_html, RenderBox};
+use rinex::prelude::{Epoch, Rinex};
+use rinex::processing::{dop, Dop};
+use rinex_qc_traits::{HtmlReport, ReportSink, ReportTable};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone)]
+pub struct QcDopAnalysis {
+    /// Dilutions of precision, per epoch
+    pub dop: BTreeMap<Epoch, Dop>,
+    /// GDOP threshold
+    pub max_gdop: f64,
+    /// PDOP threshold
+    pub max_pdop: f64,
+}
+
+impl QcDopAnalysis {
+    /// Dilutions of precision need the broadcast ephemeris
+    /// and the receiver position: this analysis is not feasible otherwise.
+    pub fn new(primary: &Rinex, nav: &Option<Rinex>, opts: &QcOpts) -> Option<Self> {
+        let nav = nav.as_ref()?;
+        let position = opts.ground_position.or(primary.header.ground_position)?;
+        let dop = dop(nav, primary, position);
+        if dop.is_empty() {
+            return None;
+        }
+        Some(Self {
+            dop,
+            max_gdop: opts.max_gdop,
+            max_pdop: opts.max_pdop,
+        })
+    }
+    /*
+     * (mean, max) of given DOP
+     */
+    fn statistics<F: Fn(&Dop) -> f64>(&self, f: F) -> (f64, f64) {
+        let values: Vec<f64> = self.dop.values().map(f).collect();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let max = values.iter().fold(f64::MIN, |max, v| max.max(*v));
+        (mean, max)
+    }
+    /*
+     * Epochs exceeding given threshold
+     */
+    fn exceeding<F: Fn(&Dop) -> f64>(&self, f: F, threshold: f64) -> Vec<Epoch> {
+        self.dop
+            .iter()
+            .filter_map(|(t, dop)| if f(dop) > threshold { Some(*t) } else { None })
+            .collect()
+    }
+    /// Writes the dilutions of precision statistics, within current section
+    pub fn report(&self, sink: &mut dyn ReportSink) -> std::io::Result<()> {
+        let mut table = ReportTable::new("dilution of precision", &["", "mean", "max"]);
+        for (name, (mean, max)) in [
+            ("GDOP", self.statistics(|dop| dop.gdop)),
+            ("PDOP", self.statistics(|dop| dop.pdop)),
+            ("HDOP", self.statistics(|dop| dop.hdop)),
+            ("VDOP", self.statistics(|dop| dop.vdop)),
+            ("TDOP", self.statistics(|dop| dop.tdop)),
+        ] {
+            table.push(vec![name.into(), mean.into(), max.into()]);
+        }
+        sink.table(&table)?;
+        let gdop = self.exceeding(|dop| dop.gdop, self.max_gdop);
+        let pdop = self.exceeding(|dop| dop.pdop, self.max_pdop);
+        sink.value("epochs exceeding GDOP threshold", &gdop.len().into())?;
+        sink.value("epochs exceeding PDOP threshold", &pdop.len().into())
+    }
+}
+
+fn report_exceeding(epochs: &[Epoch], total: usize, threshold: f64) -> Box<dyn RenderBox + '_> {
+    let rate = epochs.len() as f64 * 100.0 / total as f64;
+    box_html! {
+        @ if epochs.is_empty() {
+            td {
+                : format!("Never exceeds {}", threshold)
+            }
+        } else {
+            td {
+                p {
+                    : format!("Exceeds {} at {} epochs ({:.2} %)", threshold, epochs.len(), rate)
+                }
+                p {
+                    : format!("First: {}, Last: {}", epochs[0], epochs[epochs.len() - 1])
+                }
+            }
+        }
+    }
+}
+
+impl HtmlReport for QcDopAnalysis {
+    fn to_html(&self) -> String {
+        panic!("dop analysis cannot be rendered on its own")
+    }
+    fn to_inline_html(&self) -> Box<dyn RenderBox + '_> {
+        let rows = [
+            ("GDOP", self.statistics(|dop| dop.gdop)),
+            ("PDOP", self.statistics(|dop| dop.pdop)),
+            ("HDOP", self.statistics(|dop| dop.hdop)),
+            ("VDOP", self.statistics(|dop| dop.vdop)),
+            ("TDOP", self.statistics(|dop| dop.tdop)),
+        ];
+        let gdop = self.exceeding(|dop| dop.gdop, self.max_gdop);
+        let pdop = self.exceeding(|dop| dop.pdop, self.max_pdop);
+        let total = self.dop.len();
+        box_html! {
+            tr {
+                th {
+                    : "Dilution of precision"
+                }
+                th {
+                    : "Mean"
+                }
+                th {
+                    : "Max"
+                }
+            }
+            @ for (name, (mean, max)) in rows {
+                tr {
+                    td {
+                        : name
+                    }
+                    td {
+                        : format!("{:.3}", mean)
+                    }
+                    td {
+                        : format!("{:.3}", max)
+                    }
+                }
+            }
+            tr {
+                th {
+                    : "GDOP threshold"
+                }
+                : report_exceeding(&gdop, total, self.max_gdop)
+            }
+            tr {
+                th {
+                    : "PDOP threshold"
+                }
+                : report_exceeding(&pdop, total, self.max_pdop)
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use crate::QcOpts;
 use horrorshow::{box_html, helper::doctype, html, RenderBox};
 use rinex::prelude::*;
-use rinex_qc_traits::HtmlReport; //table_lengthy_td
+use rinex_qc_traits::{HtmlReport, ReportSink}; //table_lengthy_td
 
 mod sv;
 
@@ -10,6 +10,12 @@ use obs::QcObsAnalysis;
 
 mod sampling;
 
+mod dop;
+use dop::QcDopAnalysis;
+
+mod common_view;
+pub use common_view::QcCommonView;
+
 use sampling::QcSamplingAnalysis;
 use sv::QcSvAnalysis;
 
@@ -28,17 +34,31 @@ pub struct QcAnalysis {
     /// is truly complete when both "obs" and "processing"
     /// features are enabled
     observ: QcObsAnalysis,
+    /// Dilutions of precision, when NAV RINEX and
+    /// the receiver position are both known
+    dop: Option<QcDopAnalysis>,
 }
 
 impl QcAnalysis {
     /// Creates a new Analysis Report from given RINEX context.  
     /// primary : primary file
-    pub fn new(primary: &Rinex, _nav: &Option<Rinex>, opts: &QcOpts) -> Self {
+    pub fn new(primary: &Rinex, nav: &Option<Rinex>, opts: &QcOpts) -> Self {
         Self {
             sv: QcSvAnalysis::new(primary, opts),
             sampling: QcSamplingAnalysis::new(primary, opts),
             observ: QcObsAnalysis::new(primary, opts),
+            dop: QcDopAnalysis::new(primary, nav, opts),
+        }
+    }
+    /// Writes all results, within current section
+    pub fn report(&self, sink: &mut dyn ReportSink) -> std::io::Result<()> {
+        self.sampling.report(sink)?;
+        self.sv.report(sink)?;
+        self.observ.report(sink)?;
+        if let Some(dop) = &self.dop {
+            dop.report(sink)?;
         }
+        Ok(())
     }
 }
 
@@ -98,6 +118,15 @@ impl HtmlReport for QcAnalysis {
                         }
                     }
                 }
+                @ if let Some(dop) = &self.dop {
+                    div(id="dop") {
+                        table(class="table is-bordered; style=\"margin-bottom: 30px\"") {
+                            tbody {
+                                : dop.to_inline_html()
+                            }
+                        }
+                    }
+                }
             }
         }
     }
@@ -1,6 +1,6 @@
 use horrorshow::{box_html, RenderBox};
 use itertools::Itertools;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::str::FromStr;
 
 extern crate gnss_rs as gnss;
@@ -10,11 +10,11 @@ use gnss::prelude::SV;
 use crate::QcOpts;
 //use rinex::carrier;
 use rinex::carrier::Carrier;
-use rinex::observation::SNR;
-use rinex::prelude::{Epoch, EpochFlag, Observable, Rinex};
+use rinex::observation::{DbHz, NoiseEstimate, SNR};
+use rinex::prelude::{Constellation, Epoch, EpochFlag, Observable, Rinex};
 use rinex::preprocessing::Derivative;
 
-use rinex_qc_traits::HtmlReport;
+use rinex_qc_traits::{HtmlReport, ReportSink, ReportTable};
 use statrs::statistics::Statistics;
 
 /*
@@ -40,8 +40,38 @@ fn report_signals(list: &Vec<Carrier>) -> String {
 /*
  * Report RX Clock drift analysis
  */
-fn report_clock_drift(data: &Vec<(Epoch, f64)>) -> Box<dyn RenderBox + '_> {
+fn report_clock_drift(
+    data: &Vec<(Epoch, f64)>,
+    drift: Option<f64>,
+    offset_applied: bool,
+) -> Box<dyn RenderBox + '_> {
+    let offset_applied = match offset_applied {
+        true => "Yes: observations are corrected",
+        false => "No",
+    };
+    let drift = match drift {
+        Some(drift) => format!("{:.3} ppb", drift * 1.0E9),
+        None => "Unfeasible: missing data".to_string(),
+    };
     box_html! {
+        table(class="table is-bordered") {
+            tr {
+                th {
+                    : "Clock offsets applied"
+                }
+                td {
+                    : offset_applied
+                }
+            }
+            tr {
+                th {
+                    : "Clock drift estimate"
+                }
+                td {
+                    : drift
+                }
+            }
+        }
         @ if data.is_empty() {
             table(class="table is-bordered") {
                 tr {
@@ -78,6 +108,127 @@ fn report_clock_drift(data: &Vec<(Epoch, f64)>) -> Box<dyn RenderBox + '_> {
     }
 }
 
+/*
+ * Measurement noise (third difference) estimates
+ */
+fn report_noise(
+    noise: &Vec<(Constellation, Observable, NoiseEstimate)>,
+) -> Box<dyn RenderBox + '_> {
+    box_html! {
+        @ if noise.is_empty() {
+            tr {
+                th {
+                    : "Unfeasible"
+                }
+                td {
+                    : "Missing Data"
+                }
+            }
+        } else {
+            tr {
+                th {
+                    : "Signal"
+                }
+                th {
+                    : "Sigma"
+                }
+                th {
+                    : "Samples"
+                }
+            }
+            @ for (constellation, observable, estimate) in noise {
+                tr {
+                    td {
+                        : format!("{:X}({})", constellation, observable)
+                    }
+                    td {
+                        @ if observable.is_phase_observable() {
+                            : format!("{:.3E} cycles", estimate.sigma)
+                        } else {
+                            : format!("{:.3E} m", estimate.sigma)
+                        }
+                    }
+                    td {
+                        : estimate.samples.to_string()
+                    }
+                }
+            }
+        }
+    }
+}
+
+/*
+ * Declared receiver versus observations consistency
+ */
+fn report_receiver_consistency<'a>(
+    model: &'a Option<String>,
+    implausible: &'a Vec<(Constellation, Observable)>,
+    inactive: &'a Vec<(SV, Epoch)>,
+) -> Box<dyn RenderBox + 'a> {
+    box_html! {
+        tr {
+            th {
+                : "Receiver"
+            }
+            @ if let Some(model) = model {
+                td {
+                    : model.to_string()
+                }
+            } else {
+                td {
+                    : "Unknown"
+                }
+            }
+        }
+        tr {
+            th {
+                : "Consistency"
+            }
+            @ if implausible.is_empty() {
+                td {
+                    : "Consistent"
+                }
+            } else {
+                td {
+                    p {
+                        : "Observations not supported by declared receiver (mislabeled header?)"
+                    }
+                    @ for mut chunks in &implausible.iter().chunks(12) {
+                        p {
+                            @ while let Some((constellation, observable)) = chunks.next() {
+                                p {
+                                    : format!("{} {}, ", constellation, observable)
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        tr {
+            th {
+                : "Inactive PRNs"
+            }
+            @ if inactive.is_empty() {
+                td {
+                    : "None"
+                }
+            } else {
+                td {
+                    p {
+                        : "PRNs observed while not in use (first observation)"
+                    }
+                    @ for (sv, epoch) in inactive {
+                        p {
+                            : format!("{} ({})", sv, epoch)
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 /*
  * Epoch anomalies formatter
  */
@@ -335,6 +486,111 @@ fn report_ssi_statistics(ssi_stats: &HashMap<Observable, (f64, f64)>) -> Box<dyn
     }
 }
 
+/*
+ * Reports the missing data placeholders that were normalized at parse time
+ */
+fn report_normalized(normalized: &[(Observable, usize)]) -> Box<dyn RenderBox + '_> {
+    box_html! {
+        @ if normalized.is_empty() {
+            tr {
+                th {
+                    : "Normalized"
+                }
+                td {
+                    : "None"
+                }
+            }
+        } else {
+            @ for (observable, count) in normalized {
+                tr {
+                    th {
+                        : observable.to_string()
+                    }
+                    td {
+                        : count.to_string()
+                    }
+                }
+            }
+        }
+    }
+}
+
+/*
+ * Reports SSI indicators versus SSx observations consistency
+ */
+fn report_ssi_consistency(
+    ssi_consistency: &BTreeMap<Observable, SsiConsistency>,
+    tolerance: f64,
+) -> Box<dyn RenderBox + '_> {
+    box_html! {
+        table(class="table is-bordered") {
+            thead {
+                tr {
+                    td {
+                        : ""
+                    }
+                    @ for (signal, _) in ssi_consistency {
+                        th {
+                            : signal.to_string()
+                        }
+                    }
+                }
+            }
+            tbody {
+                tr {
+                    th {
+                        : "Indicators"
+                    }
+                    @ for (_, consistency) in ssi_consistency {
+                        td {
+                            : consistency.indicators
+                        }
+                    }
+                }
+                tr {
+                    th {
+                        : "Indicator only"
+                    }
+                    @ for (_, consistency) in ssi_consistency {
+                        td {
+                            : consistency.indicators - consistency.observed
+                        }
+                    }
+                }
+                tr {
+                    th {
+                        : format!("Inconsistent (> {} dB)", tolerance)
+                    }
+                    @ for (_, consistency) in ssi_consistency {
+                        @ if consistency.inconsistent == 0 {
+                            td {
+                                : 0
+                            }
+                        } else {
+                            td {
+                                : format!("{} ({:.1} %)", consistency.inconsistent, consistency.inconsistent as f64 * 100.0 / consistency.observed as f64)
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/*
+ * SSI indicators statistics, for a given signal
+ */
+#[derive(Debug, Default, Clone)]
+struct SsiConsistency {
+    /// Total number of indicators
+    indicators: usize,
+    /// Indicators that come with a SSx observation
+    observed: usize,
+    /// SSx observations that do not match their indicator
+    inconsistent: usize,
+}
+
 #[derive(Debug, Clone)]
 /// OBS RINEX specific QC analysis.  
 /// Full OBS RINEX analysis requires both the "obs" and "processing" features.
@@ -363,8 +619,26 @@ pub struct QcObsAnalysis {
     snr_stats: HashMap<Observable, ((Epoch, f64), (Epoch, f64))>,
     /// SSI statistical analysis (mean, stddev)
     ssi_stats: HashMap<Observable, (f64, f64)>,
+    /// SSI indicators versus SSx observations, per signal (sorted, for reproducible reports)
+    ssi_consistency: BTreeMap<Observable, SsiConsistency>,
+    /// SSI consistency tolerance, in dB
+    ssi_tolerance: f64,
     /// RX clock drift
     clock_drift: Vec<(Epoch, f64)>,
+    /// RX clock drift estimate [s/s], fitted over the whole time frame
+    clock_drift_estimate: Option<f64>,
+    /// True if RX clock offsets were applied to the observations
+    clock_offset_applied: bool,
+    /// Measurement noise estimates, per signal
+    noise: Vec<(Constellation, Observable, NoiseEstimate)>,
+    /// Declared receiver model
+    rcvr_model: Option<String>,
+    /// Observations that the declared receiver is not known to support
+    implausible_observables: Vec<(Constellation, Observable)>,
+    /// PRNs observed while not in use, and first epoch they were observed at
+    inactive_satellites: Vec<(SV, Epoch)>,
+    /// Missing data placeholders normalized at parse time, per observable
+    normalized: Vec<(Observable, usize)>,
 }
 
 impl QcObsAnalysis {
@@ -449,6 +723,21 @@ impl QcObsAnalysis {
             .iter()
             .map(|(obs, values)| (obs.clone(), (values.mean(), values.std_dev())))
             .collect();
+        /*
+         * SSI indicators versus SSx observations
+         * per signal: we do not differentiate vehicles
+         */
+        let mut ssi_consistency: BTreeMap<Observable, SsiConsistency> = BTreeMap::new();
+        for (_, _, obs, dbhz, snr) in rnx.ssi_indicator() {
+            let consistency = ssi_consistency.entry(obs).or_default();
+            consistency.indicators += 1;
+            if let Some(dbhz) = dbhz {
+                consistency.observed += 1;
+                if !snr.is_consistent(DbHz(dbhz), opts.ssi_tolerance_db) {
+                    consistency.inconsistent += 1;
+                }
+            }
+        }
         // append snr: drop vehicle differentiation
         let mut snr: HashMap<Observable, Vec<(Epoch, f64)>> = HashMap::new();
         for ((e, _), _, obs, snr_value) in rnx.snr() {
@@ -493,6 +782,8 @@ impl QcObsAnalysis {
             complete_epochs,
             snr_stats,
             ssi_stats,
+            ssi_consistency,
+            ssi_tolerance: opts.ssi_tolerance_db,
             clock_drift: {
                 let rx_clock: Vec<_> = rnx
                     .recvr_clock()
@@ -505,7 +796,71 @@ impl QcObsAnalysis {
                 //mov.eval(rx_clock_drift)
                 rx_clock_drift
             },
+            clock_drift_estimate: rnx.recvr_clock_drift(),
+            clock_offset_applied: rnx.recvr_clock_offset_applied(),
+            noise: rnx.observation_noise().estimates(),
+            rcvr_model: rnx.header.rcvr.as_ref().map(|rcvr| rcvr.model.clone()),
+            implausible_observables: rnx.implausible_observables(),
+            inactive_satellites: match &opts.satellites {
+                Some(db) => rnx.inactive_satellites_with(db),
+                None => rnx.inactive_satellites(),
+            },
+            normalized: rnx
+                .normalized
+                .iter()
+                .map(|(observable, count)| (observable.clone(), *count))
+                .collect(),
+        }
+    }
+    /// Writes the observation results, within current section
+    pub fn report(&self, sink: &mut dyn ReportSink) -> std::io::Result<()> {
+        sink.value("observables", &self.observables.join(", ").into())?;
+        sink.value("signals", &self.signals.iter().join(", ").into())?;
+        sink.value("doppler", &self.has_doppler.into())?;
+        sink.value("epochs", &self.total_epochs.into())?;
+        sink.value("epochs with observations", &self.total_with_obs.into())?;
+        sink.value("cycle slip anomalies", &self.cs_anomalies.len().into())?;
+        sink.value("power failures", &self.power_failures.len().into())?;
+        sink.value("receiver", &self.rcvr_model.clone().into())?;
+        sink.value("clock offset applied", &self.clock_offset_applied.into())?;
+        sink.value(
+            "clock drift [ppb]",
+            &self.clock_drift_estimate.map(|drift| drift * 1.0E9).into(),
+        )?;
+        let mut ssi = ReportTable::new("signal strength", &["observable", "mean", "stddev"]);
+        for (observable, (mean, stddev)) in
+            self.ssi_stats.iter().sorted_by(|(a, _), (b, _)| a.cmp(b))
+        {
+            ssi.push(vec![
+                observable.to_string().into(),
+                (*mean).into(),
+                (*stddev).into(),
+            ]);
+        }
+        sink.table(&ssi)?;
+        let mut noise = ReportTable::new(
+            "measurement noise",
+            &["constellation", "observable", "sigma", "samples"],
+        );
+        for (constellation, observable, estimate) in &self.noise {
+            noise.push(vec![
+                constellation.to_string().into(),
+                observable.to_string().into(),
+                estimate.sigma.into(),
+                estimate.samples.into(),
+            ]);
+        }
+        sink.table(&noise)?;
+        let mut normalized = ReportTable::new("normalized placeholders", &["observable", "count"]);
+        for (observable, count) in &self.normalized {
+            normalized.push(vec![observable.to_string().into(), (*count).into()]);
         }
+        sink.table(&normalized)?;
+        let mut inactive = ReportTable::new("inactive satellites", &["sv", "first epoch"]);
+        for (sv, epoch) in &self.inactive_satellites {
+            inactive.push(vec![sv.to_string().into(), epoch.to_string().into()]);
+        }
+        sink.table(&inactive)
     }
 }
 
@@ -617,6 +972,18 @@ impl HtmlReport for QcObsAnalysis {
                     }
                 }
             }
+            tr {
+                table(class="table is-bordered") {
+                    thead {
+                        th {
+                            : "SSI indicators"
+                        }
+                    }
+                    tbody {
+                        : report_ssi_consistency(&self.ssi_consistency, self.ssi_tolerance)
+                    }
+                }
+            }
             tr {
                 table(class="table is-bordered") {
                     thead {
@@ -625,7 +992,47 @@ impl HtmlReport for QcObsAnalysis {
                         }
                     }
                     tbody {
-                        : report_clock_drift(&self.clock_drift)
+                        : report_clock_drift(&self.clock_drift, self.clock_drift_estimate, self.clock_offset_applied)
+                    }
+                }
+            }
+            tr {
+                table(class="table is-bordered") {
+                    thead {
+                        th {
+                            : "Noise (third difference)"
+                        }
+                    }
+                    tbody {
+                        : report_noise(&self.noise)
+                    }
+                }
+            }
+            tr {
+                table(class="table is-bordered") {
+                    thead {
+                        th {
+                            : "Receiver consistency"
+                        }
+                    }
+                    tbody {
+                        : report_receiver_consistency(
+                            &self.rcvr_model,
+                            &self.implausible_observables,
+                            &self.inactive_satellites
+                        )
+                    }
+                }
+            }
+            tr {
+                table(class="table is-bordered") {
+                    thead {
+                        th {
+                            : "Missing data placeholders"
+                        }
+                    }
+                    tbody {
+                        : report_normalized(&self.normalized)
                     }
                 }
             }
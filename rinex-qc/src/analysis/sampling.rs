@@ -35,10 +35,37 @@ impl QcSamplingAnalysis {
             anomalies: rnx.epoch_anomalies().collect(),
         }
     }
+    /// Writes the sampling results, within current section
+    pub fn report(&self, sink: &mut dyn ReportSink) -> std::io::Result<()> {
+        sink.value(
+            "first epoch",
+            &self.first_epoch.map(|t| t.to_string()).into(),
+        )?;
+        sink.value("last epoch", &self.last_epoch.map(|t| t.to_string()).into())?;
+        sink.value("duration", &self.duration.map(|dt| dt.to_string()).into())?;
+        sink.value(
+            "sample rate",
+            &self.sample_rate.map(|dt| dt.to_string()).into(),
+        )?;
+        sink.value(
+            "dominant sample rate",
+            &self.dominant_sample_rate.map(|dt| dt.to_string()).into(),
+        )?;
+        let mut gaps = ReportTable::new("data gaps", &["start", "duration"]);
+        for (t, dt) in &self.gaps {
+            gaps.push(vec![t.to_string().into(), dt.to_string().into()]);
+        }
+        sink.table(&gaps)?;
+        let mut anomalies = ReportTable::new("epoch anomalies", &["epoch", "flag"]);
+        for (t, flag) in &self.anomalies {
+            anomalies.push(vec![t.to_string().into(), flag.to_string().into()]);
+        }
+        sink.table(&anomalies)
+    }
 }
 
 use horrorshow::RenderBox;
-use rinex_qc_traits::HtmlReport;
+use rinex_qc_traits::{HtmlReport, ReportSink, ReportTable};
 
 impl HtmlReport for QcSamplingAnalysis {
     fn to_html(&self) -> String {
@@ -2,7 +2,7 @@ use super::QcOpts;
 use rinex::prelude::{Rinex, SV};
 
 use horrorshow::{box_html, RenderBox};
-use rinex_qc_traits::HtmlReport;
+use rinex_qc_traits::{HtmlReport, ReportSink};
 
 #[derive(Debug, Clone)]
 pub struct QcSvAnalysis {
@@ -16,6 +16,11 @@ impl QcSvAnalysis {
         let sv: Vec<_> = primary.sv().collect();
         Self { sv }
     }
+    /// Writes the satellites identified, within current section
+    pub fn report(&self, sink: &mut dyn ReportSink) -> std::io::Result<()> {
+        sink.value("satellites", &self.sv.len().into())?;
+        sink.value("sv", &self.sv.iter().join(", ").into())
+    }
 }
 
 impl HtmlReport for QcSvAnalysis {
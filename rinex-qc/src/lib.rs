@@ -2,15 +2,16 @@
 //use strum_macros::EnumString;
 use horrorshow::helper::doctype;
 use horrorshow::html; // RenderBox};
-use rinex_qc_traits::HtmlReport;
+use rinex_qc_traits::{HtmlReport, ReportSink, Reportable};
 
 mod opts;
 pub use opts::{QcClassification, QcOpts};
 
 mod analysis;
-use analysis::QcAnalysis;
+use analysis::{QcAnalysis, QcCommonView};
 
-use rinex::prelude::RnxContext;
+use rinex::prelude::{Rinex, RnxContext};
+use rinex::progress::ProgressSink;
 
 /*
  * Methods used when reporting lenghty vectors or data subsets in a table.
@@ -47,16 +48,35 @@ use rinex::preprocessing::{MaskFilter, MaskOperand, Preprocessing, TargetItem};
 pub struct QcReport {}
 
 impl QcReport {
-    fn build_analysis(ctx: &RnxContext, opts: &QcOpts) -> Vec<QcAnalysis> {
+    /*
+     * Builds one analysis per classification target, along with its label.
+     * Progress is reported in epochs analyzed, once per target
+     */
+    fn build_analysis(
+        ctx: &RnxContext,
+        opts: &QcOpts,
+        progress: &mut dyn ProgressSink,
+    ) -> Vec<(String, QcAnalysis)> {
         /*
          * QC analysis not feasible when Observations not provided
          */
         if ctx.obs_data().is_none() {
             return Vec::new();
         }
+        /*
+         * Reference position: when neither the configuration nor the context
+         * define one, a coarse estimate still allows the position dependent analysis
+         */
+        let mut opts = opts.clone();
+        if opts.ground_position.is_none() {
+            opts.ground_position = ctx.ground_position().or_else(|| {
+                let nav = ctx.nav_data()?;
+                ctx.obs_data()?.approx_position(nav)
+            });
+        }
 
         // build analysis to perform
-        let mut analysis: Vec<QcAnalysis> = Vec::new();
+        let mut analysis: Vec<(String, QcAnalysis)> = Vec::new();
         /*
          * QC Classification:
          *    the end user has the ability to sort the generated report per physics,
@@ -65,17 +85,17 @@ impl QcReport {
          * first convert the classification method to a compatible object,
          * so we can apply a mask filter
          */
-        let mut filter_targets: Vec<TargetItem> = Vec::new();
+        let mut filter_targets: Vec<(String, TargetItem)> = Vec::new();
 
         match opts.classification {
             QcClassification::GNSS => {
                 for gnss in ctx.obs_data().unwrap().constellation() {
-                    filter_targets.push(TargetItem::from(gnss));
+                    filter_targets.push((format!("{:X} analysis", gnss), TargetItem::from(gnss)));
                 }
             },
             QcClassification::SV => {
                 for sv in ctx.obs_data().unwrap().sv() {
-                    filter_targets.push(TargetItem::from(sv));
+                    filter_targets.push((format!("{:X} analysis", sv), TargetItem::from(sv)));
                 }
             },
             QcClassification::Physics => {
@@ -83,12 +103,13 @@ impl QcReport {
                     ctx.obs_data().unwrap().observable().cloned().collect();
                 observables.sort(); // improves report rendering
                 for obsv in observables {
-                    filter_targets.push(TargetItem::from(obsv));
+                    filter_targets.push((format!("{} analysis", obsv), TargetItem::from(obsv)));
                 }
             },
         }
         // apply mask filters and generate an analysis on resulting data set
-        for target in filter_targets {
+        let mut epochs = 0;
+        for (label, target) in filter_targets {
             let mask = MaskFilter {
                 item: target,
                 operand: MaskOperand::Equals,
@@ -103,14 +124,65 @@ impl QcReport {
                 .map(|nav| nav.filter(mask.clone().into()));
 
             // perform analysis on these subsets
-            analysis.push(QcAnalysis::new(&subset, &nav_subset, opts));
+            analysis.push((label, QcAnalysis::new(&subset, &nav_subset, &opts)));
+            epochs += subset.epoch().count();
+            progress.progress(0, epochs);
         }
         analysis
     }
     /// Generates a Quality Check Report from provided Context and parametrization,
     /// in html format.
     pub fn html(context: &RnxContext, opts: QcOpts) -> String {
-        let analysis = Self::build_analysis(context, &opts);
+        Self::render(context, None, opts, &mut |_, _| {})
+    }
+    /// Generates a Quality Check Report from provided Context and parametrization,
+    /// in html format, with a common view comparison against the observations
+    /// of a nearby `reference` station. This helps telling whether anomalies are
+    /// satellite side or station side.
+    pub fn html_with_reference(context: &RnxContext, reference: &Rinex, opts: QcOpts) -> String {
+        Self::render(context, Some(reference), opts, &mut |_, _| {})
+    }
+    /// Same as [Self::html], with an optional common view `reference`
+    /// (see [Self::html_with_reference]), the progress being reported to given [ProgressSink].
+    /// The analysis is not file based: only the amount of epochs analyzed is reported,
+    /// cumulated over the classification targets, bytes remain null.
+    pub fn html_with_progress(
+        context: &RnxContext,
+        reference: Option<&Rinex>,
+        opts: QcOpts,
+        progress: &mut dyn ProgressSink,
+    ) -> String {
+        Self::render(context, reference, opts, progress)
+    }
+    /// Writes the Quality Check results through given [`ReportSink`]:
+    /// one section per analysis, following the classification method,
+    /// then the common view comparison against `reference`, if provided.
+    pub fn report(
+        context: &RnxContext,
+        reference: Option<&Rinex>,
+        opts: QcOpts,
+        sink: &mut dyn ReportSink,
+    ) -> std::io::Result<()> {
+        for (label, analysis) in Self::build_analysis(context, &opts, &mut |_, _| {}) {
+            sink.section(&label)?;
+            analysis.report(sink)?;
+        }
+        if let (Some(obs), Some(reference)) = (context.obs_data(), reference) {
+            QcCommonView::new(obs, reference, &opts).report(sink)?;
+        }
+        Ok(())
+    }
+    fn render(
+        context: &RnxContext,
+        reference: Option<&Rinex>,
+        opts: QcOpts,
+        progress: &mut dyn ProgressSink,
+    ) -> String {
+        let analysis = Self::build_analysis(context, &opts, progress);
+        let common_view = match (context.obs_data(), reference) {
+            (Some(obs), Some(reference)) => Some(QcCommonView::new(obs, reference, &opts)),
+            _ => None,
+        };
         format!(
             "{}",
             html! {
@@ -191,42 +263,70 @@ impl QcReport {
                              * Report all analysis
                              * and emphasize how they were sorted (self.opts.classfication)
                              */
-                            @ for i in 0..analysis.len() {
+                            @ for (label, analysis) in &analysis {
                                 table(class="table is-bordered; style=\"margin-bottom: 20px\"") {
                                     thead {
-                                        @ if opts.classification == QcClassification::GNSS {
-                                            th {
-                                                : format!("{:X} analysis", context
-                                                    .obs_data()
-                                                    .unwrap() // infaillible, until we only accept QC with OBS
-                                                    .constellation().nth(i).unwrap())
-                                            }
-                                        } else if opts.classification == QcClassification::SV {
-                                            th {
-                                                : format!("{:X} analysis", context
-                                                    .obs_data()
-                                                    .unwrap() // infaillible, until we only accept QC with OBS
-                                                    .sv().nth(i).unwrap())
-                                            }
-
-                                        } else if opts.classification == QcClassification::Physics {
-                                            th {
-                                                : format!("{} analysis", context
-                                                    .obs_data()
-                                                    .unwrap() // infaillible, until we only accept QC with OBS
-                                                    .observable().nth(i).unwrap())
-                                            }
+                                        th {
+                                            : label
                                         }
                                     }
                                     tbody {
-                                        : analysis[i].to_inline_html()
+                                        : analysis.to_inline_html()
                                     }
                                 }
                             }
                         }//div=analysis
+                        @ if let Some(common_view) = &common_view {
+                            div(id="common-view") {
+                                table(class="table is-bordered; style=\"margin-bottom: 20px\"") {
+                                    thead {
+                                        th {
+                                            : "Common view comparison"
+                                        }
+                                    }
+                                    tbody {
+                                        : common_view.to_inline_html()
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
             }
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rinex_qc_traits::JsonSink;
+    use std::path::PathBuf;
+    #[test]
+    fn qc_report_sink() {
+        let path = PathBuf::from("../test_resources/OBS/V3/DUTH0630.22O");
+        let mut context = RnxContext::default();
+        context.load(&path).unwrap();
+        let station = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+
+        let mut sink = JsonSink::new(Vec::<u8>::new());
+        QcReport::report(&context, Some(&station), QcOpts::default(), &mut sink).unwrap();
+        let json = sink.to_json();
+        let sections = json["sections"].as_array().unwrap();
+
+        // one section per constellation, then the common view
+        let constellations = station.constellation().count();
+        assert_eq!(sections.len(), constellations + 1);
+        for section in &sections[..constellations] {
+            assert!(section["title"].as_str().unwrap().ends_with(" analysis"));
+            assert!(section["values"]["satellites"].as_i64().unwrap() > 0);
+            assert!(section["values"]["epochs"].as_i64().unwrap() > 0);
+        }
+        let common_view = &sections[constellations];
+        assert_eq!(common_view["title"], "Common view comparison");
+        assert_eq!(
+            common_view["tables"][0]["rows"].as_array().unwrap().len(),
+            station.sv().count()
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use rinex::prelude::*;
-use rinex::{geodetic, wgs84};
+use rinex::{geodetic, satellite::SatelliteDatabase, wgs84};
 use rinex_qc_traits::HtmlReport;
 
 use horrorshow::{box_html, RenderBox};
@@ -97,6 +97,20 @@ pub struct QcOpts {
     /// Window duration to be used, during RX clock drift analysis
     #[cfg_attr(feature = "serde", serde(default = "default_drift_window"))]
     pub clock_drift_window: Duration,
+    /// GDOP threshold: epochs with a weaker geometry are reported
+    #[cfg_attr(feature = "serde", serde(default = "default_max_gdop"))]
+    pub max_gdop: f64,
+    /// PDOP threshold: epochs with a weaker geometry are reported
+    #[cfg_attr(feature = "serde", serde(default = "default_max_pdop"))]
+    pub max_pdop: f64,
+    /// Tolerance (in dB) when comparing signal strength observations
+    /// to their SSI indicator
+    #[cfg_attr(feature = "serde", serde(default = "default_ssi_tolerance"))]
+    pub ssi_tolerance_db: f64,
+    /// Satellite database, to report the satellites observed while their PRN
+    /// was not in use. Our bundled database is used when None.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub satellites: Option<SatelliteDatabase>,
 }
 
 impl QcOpts {
@@ -129,6 +143,18 @@ fn default_drift_window() -> Duration {
     Duration::from_seconds(3600.0)
 }
 
+fn default_max_gdop() -> f64 {
+    6.0
+}
+
+fn default_max_pdop() -> f64 {
+    5.0
+}
+
+fn default_ssi_tolerance() -> f64 {
+    1.0
+}
+
 impl Default for QcOpts {
     fn default() -> Self {
         Self {
@@ -138,6 +164,10 @@ impl Default for QcOpts {
             elev_mask: None,
             classification: QcClassification::default(),
             clock_drift_window: default_drift_window(),
+            max_gdop: default_max_gdop(),
+            max_pdop: default_max_pdop(),
+            ssi_tolerance_db: default_ssi_tolerance(),
+            satellites: None,
         }
     }
 }
@@ -200,6 +230,22 @@ impl HtmlReport for QcOpts {
                     : self.clock_drift_window.to_string()
                 }
             }
+            tr {
+                th {
+                    : "GDOP / PDOP thresholds"
+                }
+                td {
+                    : format!("{} / {}", self.max_gdop, self.max_pdop)
+                }
+            }
+            tr {
+                th {
+                    : "SSI tolerance"
+                }
+                td {
+                    : format!("{} dB", self.ssi_tolerance_db)
+                }
+            }
         }
     }
 }
@@ -0,0 +1,136 @@
+//! Throughput benchmarks: parsing, Hatanaka compression and decompression,
+//! and production (formatting + writing), on reference files of increasing size.
+use rinex::{
+    hatanaka::{Compressor, Decompressor},
+    prelude::*,
+};
+
+extern crate criterion;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use std::path::{Path, PathBuf};
+
+/*
+ * (RINEX, CRINEX) reference pairs, of increasing size
+ */
+const REFERENCES: [(&str, &str, &str); 3] = [
+    ("small", "OBS/V3/DUTH0630.22O", "CRNX/V3/DUTH0630.22D"),
+    ("medium", "OBS/V3/pdel0010.21o", "CRNX/V3/pdel0010.21d"),
+    (
+        "large",
+        "OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx",
+        "CRNX/V3/ACOR00ESP_R_20213550000_01D_30S_MO.crx",
+    ),
+];
+
+fn resource(path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("test_resources")
+        .join(path)
+}
+
+/*
+ * Returns the record section of given file, as readable text
+ */
+fn record_section(path: &Path) -> String {
+    let content = std::fs::read_to_string(path).unwrap();
+    let offset = content.find("END OF HEADER").unwrap();
+    let offset = offset + content[offset..].find('\n').unwrap() + 1;
+    content[offset..].to_string()
+}
+
+/*
+ * Hatanaka compression of the record section, line by line
+ * like our production process
+ */
+fn compression(header: &Header, content: &str) {
+    let obs = header.obs.as_ref().unwrap();
+    let constellation = header.constellation.as_ref().unwrap();
+    let mut compressor = Compressor::default();
+    for line in content.lines() {
+        let line = line.to_owned() + "\n";
+        let _ = compressor.compress(header.version.major, &obs.codes, constellation, &line);
+    }
+}
+
+/*
+ * Hatanaka decompression of the record section, line by line
+ * like our parsing process
+ */
+fn decompression(header: &Header, content: &str) {
+    let obs = header.obs.as_ref().unwrap();
+    let crinex = obs.crinex.as_ref().unwrap();
+    let constellation = header.constellation.as_ref().unwrap();
+    let mut decompressor = Decompressor::new();
+    for line in content.lines() {
+        let line = line.to_owned() + "\n";
+        let _ = decompressor.decompress(
+            crinex.version.major,
+            constellation,
+            header.version.major,
+            &obs.codes,
+            &line,
+        );
+    }
+}
+
+fn throughput_benchmark(c: &mut Criterion) {
+    let output = std::env::temp_dir().join("rinex-throughput-bench.rnx");
+    let output = output.to_string_lossy().to_string();
+
+    for (size, rnx_path, crx_path) in REFERENCES {
+        let rnx_path = resource(rnx_path);
+        let crx_path = resource(crx_path);
+        let rnx_bytes = std::fs::metadata(&rnx_path).unwrap().len();
+        let crx_bytes = std::fs::metadata(&crx_path).unwrap().len();
+
+        let rnx = Rinex::from_path(&rnx_path).unwrap();
+        let crx = Rinex::from_path(&crx_path).unwrap();
+        // compress our own production, like rnx2crx does
+        rnx.to_file(&output).unwrap();
+        let rnx_record = record_section(Path::new(&output));
+        let crx_record = record_section(&crx_path);
+
+        let mut group = c.benchmark_group("parsing");
+        group.throughput(Throughput::Bytes(rnx_bytes));
+        group.bench_with_input(BenchmarkId::new("rinex", size), &rnx_path, |b, path| {
+            b.iter(|| Rinex::from_path(path).unwrap())
+        });
+        group.throughput(Throughput::Bytes(crx_bytes));
+        group.bench_with_input(BenchmarkId::new("crinex", size), &crx_path, |b, path| {
+            b.iter(|| Rinex::from_path(path).unwrap())
+        });
+        group.finish();
+
+        let mut group = c.benchmark_group("hatanaka");
+        group.throughput(Throughput::Bytes(rnx_record.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("compression", size),
+            &rnx_record,
+            |b, content| b.iter(|| compression(&rnx.header, content)),
+        );
+        group.throughput(Throughput::Bytes(crx_record.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("decompression", size),
+            &crx_record,
+            |b, content| b.iter(|| decompression(&crx.header, content)),
+        );
+        group.finish();
+
+        let mut group = c.benchmark_group("production");
+        group.throughput(Throughput::Bytes(rnx_bytes));
+        group.bench_with_input(BenchmarkId::new("rinex", size), &rnx, |b, rnx| {
+            b.iter(|| rnx.to_file(&output).unwrap())
+        });
+        group.throughput(Throughput::Bytes(crx_bytes));
+        group.bench_with_input(BenchmarkId::new("crinex", size), &crx, |b, crx| {
+            b.iter(|| crx.to_file(&output).unwrap())
+        });
+        group.finish();
+    }
+    let _ = std::fs::remove_file(&output);
+}
+
+criterion_group!(benches, throughput_benchmark);
+criterion_main!(benches);
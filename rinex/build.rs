@@ -100,6 +100,172 @@ pub struct NavHelper<'a> {
         .unwrap();
 }
 
+fn build_rcvr_database() {
+    let outdir = env::var("OUT_DIR").unwrap();
+    let rcvr_path = Path::new(&outdir).join("rcvr_db.rs");
+    let mut rcvr_file = std::fs::File::create(rcvr_path).unwrap();
+
+    // read database
+    let rcvr_descriptor = std::fs::read_to_string("db/RCVR/receivers.json").unwrap();
+    // parse
+    let json: serde_json::Value = serde_json::from_str(&rcvr_descriptor).unwrap();
+
+    let receivers = json.as_array().unwrap();
+
+    rcvr_file
+        .write_all("// Known receivers and their capabilities\n".as_bytes())
+        .unwrap();
+
+    rcvr_file.write_all("lazy_static! {\n".as_bytes()).unwrap();
+
+    rcvr_file
+        .write_all(
+            "   pub(crate) static ref RCVR_DATABASE: Vec<RcvrCapabilities> = vec![ \n".as_bytes(),
+        )
+        .unwrap();
+
+    for receiver in receivers {
+        let model = receiver["model"].as_str().unwrap(); // mandatory
+        let bands = receiver["bands"].as_object().unwrap(); // mandatory
+
+        rcvr_file
+            .write_all("   RcvrCapabilities {\n".as_bytes())
+            .unwrap();
+        rcvr_file
+            .write_all(format!("      model: \"{}\",\n", model).as_bytes())
+            .unwrap();
+        rcvr_file
+            .write_all("      bands: vec![ \n".as_bytes())
+            .unwrap();
+        for (constellation, bands) in bands {
+            rcvr_file
+                .write_all(
+                    format!(
+                        "         (Constellation::from_str(\"{}\").unwrap(), {}),\n",
+                        constellation, bands
+                    )
+                    .as_bytes(),
+                )
+                .unwrap();
+        }
+        rcvr_file.write_all("      ],\n".as_bytes()).unwrap();
+        rcvr_file.write_all("   },\n".as_bytes()).unwrap();
+    }
+
+    rcvr_file
+        .write_all("   ];\n".as_bytes()) // RCVR_DATABASE vec![
+        .unwrap();
+
+    rcvr_file
+        .write_all("}\n".as_bytes()) // lazy_static!
+        .unwrap();
+}
+
+fn build_sv_database() {
+    let outdir = env::var("OUT_DIR").unwrap();
+    let sv_path = Path::new(&outdir).join("sv_db.rs");
+    let mut sv_file = std::fs::File::create(sv_path).unwrap();
+
+    // read database
+    let sv_descriptor = std::fs::read_to_string("db/SV/satellites.json").unwrap();
+    // parse
+    let json: serde_json::Value = serde_json::from_str(&sv_descriptor).unwrap();
+
+    let coverage = json["coverage"].as_object().unwrap(); // mandatory
+    let satellites = json["satellites"].as_array().unwrap(); // mandatory
+
+    sv_file
+        .write_all("// Satellite PRN assignments, from our satellite database\n".as_bytes())
+        .unwrap();
+
+    sv_file.write_all("lazy_static! {\n".as_bytes()).unwrap();
+
+    sv_file
+        .write_all(
+            "   pub(crate) static ref SV_DATABASE: SatelliteDatabase = SatelliteDatabase {\n"
+                .as_bytes(),
+        )
+        .unwrap();
+    sv_file
+        .write_all("      coverage: vec![ \n".as_bytes())
+        .unwrap();
+    for (constellation, since) in coverage {
+        sv_file
+            .write_all(
+                format!(
+                    "      (Constellation::from_str(\"{}\").unwrap(), Epoch::from_str(\"{}\").unwrap()),\n",
+                    constellation,
+                    since.as_str().unwrap()
+                )
+                .as_bytes(),
+            )
+            .unwrap();
+    }
+    sv_file
+        .write_all("      ].into_iter().collect(),\n".as_bytes()) // coverage vec![
+        .unwrap();
+
+    sv_file
+        .write_all("      satellites: vec![ \n".as_bytes())
+        .unwrap();
+
+    for satellite in satellites {
+        let sv = satellite["sv"].as_str().unwrap(); // mandatory
+        let svn = satellite["svn"].as_str().unwrap(); // mandatory
+        let block = satellite["block"].as_str().unwrap(); // mandatory
+        let from = satellite["from"].as_str().unwrap(); // mandatory
+        let until = satellite["until"].as_str(); // still active when omitted
+
+        sv_file.write_all("   SvAssignment {\n".as_bytes()).unwrap();
+        sv_file
+            .write_all(format!("      sv: SV::from_str(\"{}\").unwrap(),\n", sv).as_bytes())
+            .unwrap();
+        sv_file
+            .write_all(format!("      svn: \"{}\".to_string(),\n", svn).as_bytes())
+            .unwrap();
+        sv_file
+            .write_all(
+                format!(
+                    "      block: BlockType::from_str(\"{}\").unwrap(),\n",
+                    block
+                )
+                .as_bytes(),
+            )
+            .unwrap();
+        sv_file
+            .write_all(format!("      from: Epoch::from_str(\"{}\").unwrap(),\n", from).as_bytes())
+            .unwrap();
+        match until {
+            Some(until) => sv_file
+                .write_all(
+                    format!(
+                        "      until: Some(Epoch::from_str(\"{}\").unwrap()),\n",
+                        until
+                    )
+                    .as_bytes(),
+                )
+                .unwrap(),
+            None => sv_file
+                .write_all("      until: None,\n".as_bytes())
+                .unwrap(),
+        }
+        sv_file.write_all("   },\n".as_bytes()).unwrap();
+    }
+
+    sv_file
+        .write_all("      ],\n".as_bytes()) // satellites vec![
+        .unwrap();
+    sv_file
+        .write_all("   };\n".as_bytes()) // SatelliteDatabase
+        .unwrap();
+
+    sv_file
+        .write_all("}\n".as_bytes()) // lazy_static!
+        .unwrap();
+}
+
 fn main() {
     build_nav_database();
+    build_rcvr_database();
+    build_sv_database();
 }
@@ -1,4 +1,4 @@
-use crate::{preprocessing::TargetItem, Duration};
+use crate::{preprocessing::TargetItem, sampling::SamplingInterval, Duration};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -14,8 +14,8 @@ pub enum Error {
 pub enum DecimationType {
     /// Decimates Dataset by given factor.
     DecimByRatio(u32),
-    /// Decimates Dataset so sampling rate matches given duration
-    DecimByInterval(Duration),
+    /// Decimates Dataset so sampling rate matches given interval
+    DecimByInterval(SamplingInterval),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -70,6 +70,28 @@ pub trait Decimate {
     /// [Self::decimate_by_interval] mutable implementation
     fn decimate_by_interval_mut(&mut self, dt: Duration);
 
+    /// [Self::decimate_by_interval] with a validated [SamplingInterval],
+    /// as described in the header INTERVAL field or in decimation filters.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::preprocessing::Decimate;
+    /// let rinex = Rinex::from_file("../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx")
+    ///     .unwrap();
+    /// let decimated = rinex.decimate_by_sampling_interval("1 h".parse().unwrap());
+    /// assert_eq!(decimated.epoch().count(), rinex.epoch().count() - 2);
+    /// ```
+    fn decimate_by_sampling_interval(&self, interval: SamplingInterval) -> Self
+    where
+        Self: Sized,
+    {
+        self.decimate_by_interval(interval.duration())
+    }
+
+    /// [Self::decimate_by_sampling_interval] mutable implementation
+    fn decimate_by_sampling_interval_mut(&mut self, interval: SamplingInterval) {
+        self.decimate_by_interval_mut(interval.duration())
+    }
+
     /// Decimate Dataset so sampling matches given `rhs` sampling.
     /// Both types must match.
     fn decimate_match(&self, rhs: &Self) -> Self;
@@ -82,7 +104,14 @@ impl std::str::FromStr for DecimationFilter {
     type Err = Error;
     fn from_str(content: &str) -> Result<Self, Self::Err> {
         let items: Vec<&str> = content.trim().split(':').collect();
-        if let Ok(dt) = Duration::from_str(items[0].trim()) {
+        // "30 s", "1 min", "0.1 s".. or any duration description
+        let interval = items[0].parse::<SamplingInterval>().or_else(|_| {
+            Duration::from_str(items[0].trim())
+                .ok()
+                .and_then(|dt| SamplingInterval::new(dt).ok())
+                .ok_or(())
+        });
+        if let Ok(dt) = interval {
             Ok(Self {
                 target: {
                     if items.len() > 1 {
@@ -1,9 +1,94 @@
 use super::TargetItem;
-use crate::TimeSeries;
+use crate::{Duration, Epoch, TimeSeries};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum InterpMethod {
+    /// Linear interpolation between surrounding samples
     Linear,
+    /// Nearest sample is copied, for indicators and accumulations
+    Nearest,
+}
+
+impl InterpMethod {
+    /*
+     * Interpolates at t0 <= t <= t1
+     */
+    pub(crate) fn interpolate(
+        &self,
+        (t0, y0): (Epoch, f64),
+        (t1, y1): (Epoch, f64),
+        t: Epoch,
+    ) -> f64 {
+        if t1 == t0 {
+            return y0;
+        }
+        match self {
+            Self::Linear => y0 + (y1 - y0) * (t - t0).to_seconds() / (t1 - t0).to_seconds(),
+            Self::Nearest => {
+                if t - t0 <= t1 - t {
+                    y0
+                } else {
+                    y1
+                }
+            },
+        }
+    }
+}
+
+/*
+ * Latest sample at or before t and earliest sample at or after t,
+ * among epochs where `f` returns some data
+ */
+pub(crate) fn neighbours<'a, V, T, F>(
+    series: &'a BTreeMap<Epoch, V>,
+    t: Epoch,
+    f: F,
+) -> Option<((Epoch, T), (Epoch, T))>
+where
+    F: Fn(&'a V) -> Option<T>,
+{
+    let before = series
+        .range(..=t)
+        .rev()
+        .find_map(|(e, v)| Some((*e, f(v)?)))?;
+    let after = series.range(t..).find_map(|(e, v)| Some((*e, f(v)?)))?;
+    Some((before, after))
+}
+
+/*
+ * Epochs missing from a time series sampled at its dominant interval,
+ * within gaps that do not exceed `max_gap` (duration between the
+ * two samples surrounding the gap)
+ */
+pub(crate) fn gap_epochs<'a>(
+    epochs: impl Iterator<Item = &'a Epoch>,
+    max_gap: Duration,
+) -> Vec<Epoch> {
+    let epochs: Vec<Epoch> = epochs.copied().collect();
+    let mut histogram = BTreeMap::<Duration, usize>::new();
+    for pair in epochs.windows(2) {
+        *histogram.entry(pair[1] - pair[0]).or_default() += 1;
+    }
+    let interval = match histogram
+        .into_iter()
+        .max_by(|(dt_a, pop_a), (dt_b, pop_b)| pop_a.cmp(pop_b).then(dt_b.cmp(dt_a)))
+    {
+        Some((interval, _)) if interval > Duration::ZERO => interval,
+        _ => return Vec::new(),
+    };
+    let mut ret = Vec::<Epoch>::new();
+    for pair in epochs.windows(2) {
+        let dt = pair[1] - pair[0];
+        if dt > interval && dt <= max_gap {
+            let mut t = pair[0] + interval;
+            while t < pair[1] {
+                ret.push(t);
+                t += interval;
+            }
+        }
+    }
+    ret
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -5,6 +5,7 @@ mod smoothing;
 
 use super::TargetItem;
 pub use decim::{Decimate, DecimationFilter, DecimationType};
+pub(crate) use interp::{gap_epochs, neighbours};
 pub use interp::{InterpFilter, InterpMethod, Interpolate};
 pub use mask::{Mask, MaskFilter, MaskOperand};
 pub use smoothing::{Smooth, SmoothingFilter, SmoothingType};
@@ -10,5 +10,7 @@ pub use filters::{
     Mask, MaskFilter, MaskOperand, Preprocessing, Smooth, SmoothingFilter, SmoothingType,
 };
 
+pub(crate) use filters::{gap_epochs, neighbours};
+
 //pub use averaging::Averager;
 pub use derivative::Derivative;
@@ -7,7 +7,7 @@ use super::{
     antenna::SvAntennaParsingError, Antenna, AntennaSpecific, Calibration, CalibrationMethod,
     Cospar, RxAntenna, SvAntenna,
 };
-use crate::{carrier, linspace::Linspace, merge, merge::Merge, Carrier, Epoch};
+use crate::{carrier, fieldslice, linspace::Linspace, merge, merge::Merge, Carrier, Epoch};
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
@@ -48,6 +48,44 @@ pub struct FrequencyDependentData {
     pub phase_pattern: AntennaPhasePattern,
 }
 
+impl FrequencyDependentData {
+    /// Interpolates the Phase Center Variation [mm] at given zenith angle (or nadir angle
+    /// for an [`SvAntenna`]) expressed in degrees, on given zenith grid.
+    /// Returns None if the angle is out of the grid or the phase pattern is empty.
+    pub fn pcv(&self, grid: &Linspace, zenith: f64) -> Option<f64> {
+        let AntennaPhasePattern::AzimuthIndependentPattern(values) = &self.phase_pattern;
+        if values.is_empty() || zenith < grid.start || zenith > grid.end {
+            return None;
+        }
+        if grid.spacing <= 0.0 || values.len() == 1 {
+            return Some(values[0]);
+        }
+        let pos = (zenith - grid.start) / grid.spacing;
+        let index = pos.floor() as usize;
+        if index + 1 >= values.len() {
+            return values.last().copied();
+        }
+        let dx = pos - index as f64;
+        Some(values[index] * (1.0 - dx) + values[index + 1] * dx)
+    }
+    /// Returns the phase center correction [mm] for a signal received
+    /// under given elevation and azimuth angles (both in degrees), on given zenith grid.
+    /// This is the APC eccentricity projected onto the line of sight, minus the PCV.
+    /// Adding this correction to a phase range refers it to the antenna reference point.
+    pub fn phase_center_correction(
+        &self,
+        grid: &Linspace,
+        elevation: f64,
+        azimuth: f64,
+    ) -> Option<f64> {
+        let pcv = self.pcv(grid, 90.0 - elevation)?;
+        let (north, east, up) = self.apc_eccentricity;
+        let (el, az) = (elevation.to_radians(), azimuth.to_radians());
+        let los = north * el.cos() * az.cos() + east * el.cos() * az.sin() + up * el.sin();
+        Some(los - pcv)
+    }
+}
+
 /// ANTEX RINEX record content.
 /// Data is a list of Antenna containing several [Frequency] items.
 /// We do not parse RMS frequencies at the moment, but it will
@@ -100,6 +138,10 @@ pub enum Error {
     ParseCarrierError(#[from] carrier::Error),
     #[error("sv antenna parsing error")]
     SvAntennaParsing(#[from] SvAntennaParsingError),
+    #[error("failed to parse phase pattern")]
+    PhasePatternParsing,
+    #[error("malformed field")]
+    FieldError(#[from] fieldslice::Error),
     #[error("failed to parse APC/NEU northern coordinates")]
     APCNorthernCoordinatesParsing,
     #[error("failed to parse APC/NEU eastern coordinates")]
@@ -250,7 +292,20 @@ pub(crate) fn parse_antenna(
     let mut valid_from = Epoch::default();
 
     for line in lines {
-        let (content, marker) = line.split_at(60);
+        if let Some(pattern) = line.trim_start().strip_prefix("NOAZI") {
+            /*
+             * Azimuth independent phase pattern,
+             * one value per zenith grid point
+             */
+            let values = pattern
+                .split_ascii_whitespace()
+                .map(|value| value.parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| Error::PhasePatternParsing)?;
+            freq_data.phase_pattern = AntennaPhasePattern::AzimuthIndependentPattern(values);
+            continue;
+        }
+        let (content, marker) = fieldslice::split_at(line, 60)?;
         if marker.contains("TYPE / SERIAL NO") {
             let (ant_igs, rem) = content.split_at(16); // IGS V.1.4 does not follow the specs ?
             let (block1, rem) = rem.split_at(20 + 4);
@@ -0,0 +1,325 @@
+//! Binary cache (`.rnxcache`) of parsed Observation records.
+//!
+//! The cache is stored next to its source file and is only reused
+//! while the source file is unchanged (same size and modification date).
+//! The header is always parsed from the source file, which is fast.
+//! Layout (little endian):
+//!  - magic bytes, format revision and source fingerprint
+//!  - record comments
+//!  - vehicles and observables tables
+//!  - epoch index: epoch, flag, clock offset and observations range
+//!  - observations: 14 bytes each, see [`CompactObservation`]
+use crate::{
+    observation::{CompactEpoch, CompactObservation, CompactRecord},
+    prelude::*,
+    record::{Comments, Record},
+};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::UNIX_EPOCH;
+
+/// Cache file extension
+pub const EXTENSION: &str = "rnxcache";
+
+const MAGIC: &[u8; 8] = b"RNXCACHE";
+
+const REVISION: u8 = 1;
+
+/*
+ * Upper bound of the preallocations, whatever the lengths
+ * a (corrupt) cache declares
+ */
+const MAX_PREALLOC: usize = 4096;
+
+/*
+ * Distinguishes the temporary files of concurrent writers, within one process
+ */
+static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the cache file path of given source file
+pub fn cache_path(source: &Path) -> PathBuf {
+    let mut path = source.as_os_str().to_os_string();
+    path.push(".");
+    path.push(EXTENSION);
+    PathBuf::from(path)
+}
+
+/*
+ * Source file identification: size and modification date
+ */
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct Fingerprint {
+    size: u64,
+    secs: u64,
+    nanos: u32,
+}
+
+impl Fingerprint {
+    pub(crate) fn new(source: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(source).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            size: metadata.len(),
+            secs: modified.as_secs(),
+            nanos: modified.subsec_nanos(),
+        })
+    }
+}
+
+/*
+ * Cache content encoder
+ */
+#[derive(Default)]
+struct Encoder {
+    buf: Vec<u8>,
+}
+
+impl Encoder {
+    fn u8(&mut self, value: u8) {
+        self.buf.push(value);
+    }
+    fn u16(&mut self, value: u16) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+    fn u32(&mut self, value: u32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+    fn u64(&mut self, value: u64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+    fn f64(&mut self, value: f64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+    fn str(&mut self, value: &str) {
+        self.u32(value.len() as u32);
+        self.buf.extend_from_slice(value.as_bytes());
+    }
+    fn epoch(&mut self, epoch: &Epoch) {
+        let (centuries, nanos) = epoch.duration_since_j1900_tai.to_parts();
+        self.u8(epoch.time_scale.into());
+        self.u16(centuries as u16);
+        self.u64(nanos);
+    }
+}
+
+/*
+ * Cache content decoder, None on any inconsistency
+ */
+struct Decoder<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn bytes(&mut self, size: usize) -> Option<&'a [u8]> {
+        if self.buf.len() < size {
+            return None;
+        }
+        let (bytes, rem) = self.buf.split_at(size);
+        self.buf = rem;
+        Some(bytes)
+    }
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.bytes(2)?.try_into().ok()?))
+    }
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.bytes(8)?.try_into().ok()?))
+    }
+    fn f64(&mut self) -> Option<f64> {
+        Some(f64::from_le_bytes(self.bytes(8)?.try_into().ok()?))
+    }
+    fn str(&mut self) -> Option<&'a str> {
+        let size = self.u32()? as usize;
+        std::str::from_utf8(self.bytes(size)?).ok()
+    }
+    /*
+     * Table length, the preallocation remains bounded
+     */
+    fn len(&mut self) -> Option<(usize, usize)> {
+        let len = self.u32()? as usize;
+        Some((len, len.min(MAX_PREALLOC)))
+    }
+    fn epoch(&mut self) -> Option<Epoch> {
+        let time_scale = TimeScale::from(self.u8()?);
+        let centuries = self.u16()? as i16;
+        let nanos = self.u64()?;
+        Some(Epoch {
+            duration_since_j1900_tai: Duration::from_parts(centuries, nanos),
+            time_scale,
+        })
+    }
+}
+
+/*
+ * Writes the cache of given Observation record.
+ * Content is written to a temporary file first, so a concurrent reader
+ * never encounters a partial cache.
+ */
+pub(crate) fn write(
+    path: &Path,
+    fingerprint: &Fingerprint,
+    record: &Record,
+    comments: &Comments,
+) -> std::io::Result<()> {
+    let record = match record.as_obs() {
+        Some(record) => CompactRecord::from(record),
+        None => return Ok(()),
+    };
+    let mut enc = Encoder::default();
+    enc.buf.extend_from_slice(MAGIC);
+    enc.u8(REVISION);
+    enc.u64(fingerprint.size);
+    enc.u64(fingerprint.secs);
+    enc.u32(fingerprint.nanos);
+
+    enc.u32(comments.len() as u32);
+    for (epoch, comments) in comments {
+        enc.epoch(epoch);
+        enc.u32(comments.len() as u32);
+        for comment in comments {
+            enc.str(comment);
+        }
+    }
+    enc.u32(record.satellites.len() as u32);
+    for sv in &record.satellites {
+        enc.str(&sv.to_string());
+    }
+    enc.u32(record.observables.len() as u32);
+    for observable in &record.observables {
+        enc.str(&observable.to_string());
+    }
+    enc.u32(record.epochs.len() as u32);
+    for epoch in &record.epochs {
+        enc.epoch(&epoch.epoch);
+        enc.u8(epoch.flag.to_string().parse::<u8>().unwrap_or(0));
+        enc.f64(epoch.clock_offset);
+        enc.u32(epoch.end);
+    }
+    enc.u32(record.observations.len() as u32);
+    for observation in &record.observations {
+        enc.f64(observation.value);
+        enc.u16(observation.sv);
+        enc.u16(observation.observable);
+        enc.u8(observation.lli);
+        enc.u8(observation.snr);
+    }
+
+    let mut tmp_path = path.as_os_str().to_os_string();
+    tmp_path.push(format!(
+        ".{}.{}",
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let mut fd = File::create(&tmp_path)?;
+    fd.write_all(&enc.buf)?;
+    std::fs::rename(&tmp_path, path)
+}
+
+/*
+ * Reads the cached record. Fails if it does not exist, does not match
+ * the source fingerprint or is inconsistent (truncated, corrupt):
+ * the source file should then be parsed.
+ */
+pub(crate) fn read(path: &Path, fingerprint: &Fingerprint) -> std::io::Result<(Record, Comments)> {
+    let content = std::fs::read(path)?;
+    decode(&content, fingerprint).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("\"{}\": outdated or corrupt cache", path.display()),
+        )
+    })
+}
+
+fn decode(content: &[u8], fingerprint: &Fingerprint) -> Option<(Record, Comments)> {
+    let mut dec = Decoder { buf: content };
+    if dec.bytes(MAGIC.len())? != MAGIC || dec.u8()? != REVISION {
+        return None;
+    }
+    let cached = Fingerprint {
+        size: dec.u64()?,
+        secs: dec.u64()?,
+        nanos: dec.u32()?,
+    };
+    if cached != *fingerprint {
+        return None;
+    }
+
+    let mut comments = Comments::new();
+    for _ in 0..dec.u32()? {
+        let epoch = dec.epoch()?;
+        let (size, capacity) = dec.len()?;
+        let mut content = Vec::with_capacity(capacity);
+        for _ in 0..size {
+            content.push(dec.str()?.to_string());
+        }
+        comments.insert(epoch, content);
+    }
+    let (size, capacity) = dec.len()?;
+    let mut satellites = Vec::with_capacity(capacity);
+    for _ in 0..size {
+        satellites.push(SV::from_str(dec.str()?).ok()?);
+    }
+    let (size, capacity) = dec.len()?;
+    let mut observables = Vec::with_capacity(capacity);
+    for _ in 0..size {
+        observables.push(Observable::from_str(dec.str()?).ok()?);
+    }
+    let (size, capacity) = dec.len()?;
+    let mut epochs = Vec::<CompactEpoch>::with_capacity(capacity);
+    for _ in 0..size {
+        let epoch = CompactEpoch {
+            epoch: dec.epoch()?,
+            flag: EpochFlag::from_str(&dec.u8()?.to_string()).ok()?,
+            clock_offset: dec.f64()?,
+            end: dec.u32()?,
+        };
+        // observation ranges are contiguous
+        if epochs
+            .last()
+            .map(|last| epoch.end < last.end)
+            .unwrap_or(false)
+        {
+            return None;
+        }
+        epochs.push(epoch);
+    }
+    let (size, capacity) = dec.len()?;
+    if epochs
+        .last()
+        .map(|last| last.end as usize != size)
+        .unwrap_or(size != 0)
+    {
+        return None;
+    }
+    let mut observations = Vec::with_capacity(capacity);
+    for _ in 0..size {
+        let observation = CompactObservation {
+            value: dec.f64()?,
+            sv: dec.u16()?,
+            observable: dec.u16()?,
+            lli: dec.u8()?,
+            snr: dec.u8()?,
+        };
+        if observation.sv as usize >= satellites.len()
+            || observation.observable as usize >= observables.len()
+        {
+            return None;
+        }
+        observations.push(observation);
+    }
+    let record = CompactRecord {
+        satellites,
+        observables,
+        epochs,
+        observations,
+    };
+    Some((Record::ObsRecord(record.to_record()), comments))
+}
@@ -0,0 +1,489 @@
+//! Campaign: statistics of a single station, across an archive
+use crate::catalog::walk;
+use crate::epoch::epoch_decompose;
+use crate::observation::LliFlags;
+use crate::prelude::{Duration, Epoch, EpochFlag, Rinex};
+use hifitime::Unit;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Campaign related errors
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("not an observation rinex")]
+    NotObservationRinex,
+    #[error("empty session")]
+    EmptySession,
+    #[error("unknown aggregation period \"{0}\"")]
+    UnknownPeriod(String),
+    #[error("station \"{0}\" does not belong to this campaign")]
+    StationMismatch(String),
+    #[error("parsing error")]
+    ParsingError(#[from] crate::Error),
+    #[error("i/o error")]
+    IoError(#[from] std::io::Error),
+}
+
+/// Statistics of a single session (usually, a daily file)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SessionStatistics {
+    /// File this session was ingested from
+    pub path: PathBuf,
+    /// Station (marker) name, if known
+    pub station: Option<String>,
+    /// First epoch
+    pub first_epoch: Epoch,
+    /// Last epoch
+    pub last_epoch: Epoch,
+    /// Sampling interval
+    pub interval: Duration,
+    /// Epochs with valid observations
+    pub epochs: usize,
+    /// Epochs expected from the session time span and sampling interval
+    pub expected_epochs: usize,
+    /// Cycle slips: both epochs flagged as such and
+    /// phase observations flagged with a lock loss
+    pub cycle_slips: usize,
+    /// Sum of SSI observations [dB.Hz]
+    snr_sum: f64,
+    /// Number of SSI observations
+    snr_samples: usize,
+}
+
+impl SessionStatistics {
+    /// Builds session statistics from given Observation [`Rinex`]
+    pub fn from_rinex(path: &Path, rinex: &Rinex) -> Result<Self, Error> {
+        let record = rinex.record.as_obs().ok_or(Error::NotObservationRinex)?;
+        let first_epoch = rinex.first_epoch().ok_or(Error::EmptySession)?;
+        let last_epoch = rinex.last_epoch().ok_or(Error::EmptySession)?;
+        let interval = rinex
+            .header
+            .sampling_interval
+            .map(|dt| dt.duration())
+            .or_else(|| rinex.dominant_sample_rate())
+            .unwrap_or(Duration::from_seconds(30.0));
+
+        let (mut epochs, mut cycle_slips) = (0, 0);
+        let (mut snr_sum, mut snr_samples) = (0.0_f64, 0);
+        for ((_, flag), (_, vehicles)) in record {
+            match flag {
+                EpochFlag::Ok if !vehicles.is_empty() => epochs += 1,
+                EpochFlag::CycleSlip => cycle_slips += 1,
+                _ => {},
+            }
+            for observations in vehicles.values() {
+                for (observable, data) in observations {
+                    if observable.is_phase_observable() {
+                        if let Some(lli) = data.lli {
+                            if lli.intersects(LliFlags::LOCK_LOSS) {
+                                cycle_slips += 1;
+                            }
+                        }
+                    } else if observable.is_ssi_observable() {
+                        snr_sum += data.obs;
+                        snr_samples += 1;
+                    }
+                }
+            }
+        }
+        let expected_epochs =
+            ((last_epoch - first_epoch).to_seconds() / interval.to_seconds()).round() as usize + 1;
+        Ok(Self {
+            path: path.to_path_buf(),
+            station: rinex
+                .header
+                .geodetic_marker
+                .as_ref()
+                .map(|marker| marker.name.trim().to_uppercase()),
+            first_epoch,
+            last_epoch,
+            interval,
+            epochs,
+            expected_epochs,
+            cycle_slips,
+            snr_sum,
+            snr_samples,
+        })
+    }
+    /// Session duration
+    pub fn duration(&self) -> Duration {
+        self.interval * self.expected_epochs as f64
+    }
+    /// Completeness, in percent
+    pub fn completeness(&self) -> f64 {
+        self.epochs as f64 * 100.0 / self.expected_epochs as f64
+    }
+    /// Mean SNR [dB.Hz], if SSI observations were provided
+    pub fn mean_snr(&self) -> Option<f64> {
+        if self.snr_samples > 0 {
+            Some(self.snr_sum / self.snr_samples as f64)
+        } else {
+            None
+        }
+    }
+}
+
+/// Aggregation period
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Period {
+    /// Calendar days
+    Daily,
+    /// GPS weeks (starting on sundays)
+    #[default]
+    Weekly,
+    /// Calendar months
+    Monthly,
+}
+
+impl std::str::FromStr for Period {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "daily" | "day" => Ok(Self::Daily),
+            "weekly" | "week" => Ok(Self::Weekly),
+            "monthly" | "month" => Ok(Self::Monthly),
+            _ => Err(Error::UnknownPeriod(s.to_string())),
+        }
+    }
+}
+
+/*
+ * Calendar date of given Epoch, within its own timescale
+ */
+fn calendar_date(t: Epoch) -> (i32, u8, u8) {
+    let (y, m, d, _, _, _, _) = epoch_decompose(t);
+    (y, m, d)
+}
+
+impl Period {
+    /*
+     * Start of the period given Epoch belongs to,
+     * expressed in the same timescale
+     */
+    fn start(&self, t: Epoch) -> Epoch {
+        let ts = t.time_scale;
+        let (y, m, d) = calendar_date(t);
+        match self {
+            Self::Daily => Epoch::from_gregorian_at_midnight(y, m, d, ts),
+            Self::Weekly => {
+                // GPS weeks start on sundays, like 1980-01-06
+                let day = Epoch::from_gregorian_at_midnight(y, m, d, ts);
+                let origin = Epoch::from_gregorian_at_midnight(1980, 1, 6, ts);
+                let days = (day - origin).to_unit(Unit::Day).round() as i64;
+                let sunday = day - Duration::from_days(days.rem_euclid(7) as f64)
+                    + Duration::from_hours(12.0);
+                let (y, m, d) = calendar_date(sunday);
+                Epoch::from_gregorian_at_midnight(y, m, d, ts)
+            },
+            Self::Monthly => Epoch::from_gregorian_at_midnight(y, m, 1, ts),
+        }
+    }
+}
+
+/// Statistics aggregated over a [`Period`]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PeriodStatistics {
+    /// Start of this period
+    pub start: Epoch,
+    /// Number of sessions that contributed
+    pub sessions: usize,
+    /// Epochs with valid observations
+    pub epochs: usize,
+    /// Expected epochs
+    pub expected_epochs: usize,
+    /// Total number of cycle slips
+    pub cycle_slips: usize,
+    /// Observed duration
+    pub duration: Duration,
+    /// Mean SNR [dB.Hz], if SSI observations were provided
+    pub mean_snr: Option<f64>,
+}
+
+impl PeriodStatistics {
+    /// Completeness, in percent
+    pub fn completeness(&self) -> f64 {
+        self.epochs as f64 * 100.0 / self.expected_epochs as f64
+    }
+    /// Cycle slips per day of observation
+    pub fn slips_per_day(&self) -> f64 {
+        self.cycle_slips as f64 / self.duration.to_unit(Unit::Day)
+    }
+}
+
+/// [`Campaign`] gathers the statistics of a single station across
+/// an archive of (usually daily) Observation RINEX files,
+/// and aggregates them over weeks or months, for station health monitoring.
+/// Only statistics are retained, so very large archives can be ingested.
+/// ```
+/// use rinex::campaign::{Campaign, Period};
+/// let campaign = Campaign::from_directory("../test_resources/OBS/V3", Some("VLNS"))
+///     .unwrap();
+/// assert_eq!(campaign.station(), Some("VLNS"));
+/// assert_eq!(campaign.sessions.len(), 2);
+///
+/// // VLNS0010.22O and VLNS0630.22O are two months apart
+/// let monthly = campaign.aggregate(Period::Monthly);
+/// assert_eq!(monthly.len(), 2);
+///
+/// let mut csv = Vec::<u8>::new();
+/// campaign.to_csv(Period::Monthly, &mut csv)
+///     .unwrap();
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Campaign {
+    /// Station this campaign is dedicated to
+    station: Option<String>,
+    /// Ingested sessions, in chronological order
+    pub sessions: Vec<SessionStatistics>,
+}
+
+impl Campaign {
+    /// Creates a new [`Campaign`] dedicated to given station.
+    /// When not specified, the first ingested session defines the station.
+    pub fn new(station: Option<&str>) -> Self {
+        Self {
+            station: station.map(|s| s.trim().to_uppercase()),
+            sessions: Vec::new(),
+        }
+    }
+    /// Returns the station this campaign is dedicated to
+    pub fn station(&self) -> Option<&str> {
+        self.station.as_deref()
+    }
+    /// Ingests a new session. Sessions of other stations are rejected:
+    /// the marker name should start with the station name (usually, a 4 letter code).
+    pub fn add(&mut self, path: &Path, rinex: &Rinex) -> Result<(), Error> {
+        let session = SessionStatistics::from_rinex(path, rinex)?;
+        if let Some(name) = &session.station {
+            match &self.station {
+                Some(station) => {
+                    if !name.starts_with(station.as_str()) {
+                        return Err(Error::StationMismatch(name.clone()));
+                    }
+                },
+                None => self.station = Some(name.clone()),
+            }
+        }
+        let index = self
+            .sessions
+            .iter()
+            .position(|s| s.first_epoch > session.first_epoch)
+            .unwrap_or(self.sessions.len());
+        self.sessions.insert(index, session);
+        Ok(())
+    }
+    /// Ingests a directory tree of Observation RINEX files (recursively).
+    /// Files that are not Observation RINEX, or that belong to another station,
+    /// are ignored. Files are ingested in alphabetical order.
+    pub fn from_directory<P: AsRef<Path>>(path: P, station: Option<&str>) -> Result<Self, Error> {
+        let mut campaign = Self::new(station);
+        for path in walk(path.as_ref())? {
+            if let Ok(rinex) = Rinex::from_path(&path) {
+                let _ = campaign.add(&path, &rinex);
+            }
+        }
+        Ok(campaign)
+    }
+    /// Aggregates session statistics over given [`Period`], in chronological order.
+    pub fn aggregate(&self, period: Period) -> Vec<PeriodStatistics> {
+        let mut ret = Vec::<PeriodStatistics>::new();
+        let mut snr = Vec::<(f64, usize)>::new();
+        for session in &self.sessions {
+            let start = period.start(session.first_epoch);
+            if ret.last().map(|p| p.start) != Some(start) {
+                ret.push(PeriodStatistics {
+                    start,
+                    sessions: 0,
+                    epochs: 0,
+                    expected_epochs: 0,
+                    cycle_slips: 0,
+                    duration: Duration::default(),
+                    mean_snr: None,
+                });
+                snr.push((0.0, 0));
+            }
+            let (stats, snr) = (ret.last_mut().unwrap(), snr.last_mut().unwrap());
+            stats.sessions += 1;
+            stats.epochs += session.epochs;
+            stats.expected_epochs += session.expected_epochs;
+            stats.cycle_slips += session.cycle_slips;
+            stats.duration += session.duration();
+            snr.0 += session.snr_sum;
+            snr.1 += session.snr_samples;
+            if snr.1 > 0 {
+                stats.mean_snr = Some(snr.0 / snr.1 as f64);
+            }
+        }
+        ret
+    }
+    /// Exports statistics aggregated over given [`Period`] as CSV:
+    /// one line per period, suitable for station health monitoring.
+    pub fn to_csv<W: Write>(&self, period: Period, writer: &mut W) -> Result<(), Error> {
+        writeln!(
+            writer,
+            "station,start,sessions,epochs,expected_epochs,completeness_percent,cycle_slips,slips_per_day,mean_snr_dbhz"
+        )?;
+        let station = self.station().unwrap_or("UNKNOWN");
+        for stats in self.aggregate(period) {
+            let (y, m, d) = calendar_date(stats.start);
+            writeln!(
+                writer,
+                "{},{:04}-{:02}-{:02},{},{},{},{:.2},{},{:.2},{}",
+                station,
+                y,
+                m,
+                d,
+                stats.sessions,
+                stats.epochs,
+                stats.expected_epochs,
+                stats.completeness(),
+                stats.cycle_slips,
+                stats.slips_per_day(),
+                stats
+                    .mean_snr
+                    .map(|snr| format!("{:.2}", snr))
+                    .unwrap_or_default(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "qc")]
+use rinex_qc_traits::{ReportSink, ReportTable, Reportable};
+
+#[cfg(feature = "qc")]
+impl Reportable for SessionStatistics {
+    fn report(&self, sink: &mut dyn ReportSink) -> std::io::Result<()> {
+        sink.section(&format!("Session \"{}\"", self.path.display()))?;
+        sink.value("station", &self.station.clone().into())?;
+        sink.value("first epoch", &self.first_epoch.to_string().into())?;
+        sink.value("last epoch", &self.last_epoch.to_string().into())?;
+        sink.value("interval", &self.interval.to_string().into())?;
+        sink.value("epochs", &self.epochs.into())?;
+        sink.value("expected epochs", &self.expected_epochs.into())?;
+        sink.value("completeness [%]", &self.completeness().into())?;
+        sink.value("cycle slips", &self.cycle_slips.into())?;
+        sink.value("mean snr [dB.Hz]", &self.mean_snr().into())
+    }
+}
+
+#[cfg(feature = "qc")]
+impl Reportable for Campaign {
+    fn report(&self, sink: &mut dyn ReportSink) -> std::io::Result<()> {
+        sink.section("Campaign")?;
+        sink.value("station", &self.station().into())?;
+        sink.value("sessions", &self.sessions.len().into())?;
+        let mut table = ReportTable::new(
+            "sessions",
+            &[
+                "path",
+                "first epoch",
+                "last epoch",
+                "epochs",
+                "completeness [%]",
+                "cycle slips",
+                "mean snr [dB.Hz]",
+            ],
+        );
+        for session in &self.sessions {
+            table.push(vec![
+                session.path.display().to_string().into(),
+                session.first_epoch.to_string().into(),
+                session.last_epoch.to_string().into(),
+                session.epochs.into(),
+                session.completeness().into(),
+                session.cycle_slips.into(),
+                session.mean_snr().into(),
+            ]);
+        }
+        sink.table(&table)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prelude::TimeScale;
+    #[test]
+    fn campaign_aggregation() {
+        let mut campaign = Campaign::new(Some("VLNS"));
+        for file in ["VLNS0630.22O", "VLNS0010.22O", "LARM0010.22O"] {
+            let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("..")
+                .join("test_resources")
+                .join("OBS")
+                .join("V3")
+                .join(file);
+            let rinex = Rinex::from_path(&path).unwrap();
+            let ret = campaign.add(&path, &rinex);
+            if file.starts_with("LARM") {
+                assert!(matches!(ret, Err(Error::StationMismatch(_))));
+            } else {
+                assert!(ret.is_ok());
+            }
+        }
+        assert_eq!(campaign.sessions.len(), 2);
+        assert!(
+            campaign.sessions[0].first_epoch < campaign.sessions[1].first_epoch,
+            "sessions should be sorted"
+        );
+
+        let daily = campaign.aggregate(Period::Daily);
+        assert_eq!(daily.len(), 2);
+        assert_eq!(
+            daily[0].start,
+            Epoch::from_gregorian_at_midnight(2022, 1, 1, TimeScale::GPST)
+        );
+        assert_eq!(
+            daily[1].start,
+            Epoch::from_gregorian_at_midnight(2022, 3, 4, TimeScale::GPST)
+        );
+
+        let weekly = campaign.aggregate(Period::Weekly);
+        assert_eq!(weekly.len(), 2);
+        // GPS week 2190 starts on 2021-12-26
+        assert_eq!(
+            weekly[0].start,
+            Epoch::from_gregorian_at_midnight(2021, 12, 26, TimeScale::GPST)
+        );
+
+        let monthly = campaign.aggregate(Period::Monthly);
+        assert_eq!(monthly.len(), 2);
+        assert_eq!(
+            monthly[1].start,
+            Epoch::from_gregorian_at_midnight(2022, 3, 1, TimeScale::GPST)
+        );
+        for (stats, session) in monthly.iter().zip(campaign.sessions.iter()) {
+            assert_eq!(stats.sessions, 1);
+            assert_eq!(stats.epochs, session.epochs);
+            assert!(stats.completeness() > 0.0 && stats.completeness() <= 100.0);
+        }
+
+        let mut csv = Vec::<u8>::new();
+        campaign.to_csv(Period::Monthly, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("VLNS,2022-01-01,1,"));
+        assert!(lines[2].starts_with("VLNS,2022-03-01,1,"));
+
+        #[cfg(feature = "qc")]
+        {
+            use rinex_qc_traits::TextSink;
+            let mut sink = TextSink::new(Vec::<u8>::new());
+            campaign.report(&mut sink).unwrap();
+            campaign.sessions[0].report(&mut sink).unwrap();
+            let text = String::from_utf8(sink.into_inner()).unwrap();
+            assert!(text.contains("[Campaign]\nstation: VLNS\nsessions: 2\n"));
+            assert!(text.contains("VLNS0010.22O\"]\nstation: VLNS\n"));
+        }
+    }
+}
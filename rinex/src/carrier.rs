@@ -632,6 +632,57 @@ impl Carrier {
             _ => Err(Error::UnknownObservable(obs.to_string())),
         }
     }
+    /*
+     * Observation codes (2 and 3 characters) of given constellation, from our tables
+     */
+    pub(crate) fn constellation_codes(constellation: Constellation) -> Vec<&'static str> {
+        match constellation {
+            Constellation::GPS => [
+                &Self::gpsl1_codes()[..],
+                &Self::gpsl2_codes()[..],
+                &Self::gpsl5_codes()[..],
+            ]
+            .concat(),
+            Constellation::Glonass => [
+                &Self::g1_codes()[..],
+                &Self::g1a_codes()[..],
+                &Self::g2_codes()[..],
+                &Self::g2a_codes()[..],
+                &Self::g3_codes()[..],
+            ]
+            .concat(),
+            Constellation::Galileo => [
+                &Self::e1_codes()[..],
+                &Self::e5_codes()[..],
+                &Self::e5a_codes()[..],
+                &Self::e5b_codes()[..],
+                &Self::e6_codes()[..],
+            ]
+            .concat(),
+            Constellation::QZSS => [
+                &Self::qzl1_codes()[..],
+                &Self::qzl2_codes()[..],
+                &Self::qzl5_codes()[..],
+                &Self::qzl6_codes()[..],
+            ]
+            .concat(),
+            Constellation::BeiDou => [
+                &Self::b1a_codes()[..],
+                &Self::b1c_codes()[..],
+                &Self::b1i_codes()[..],
+                &Self::b2a_codes()[..],
+                &Self::b2i_codes()[..],
+                &Self::b2b_codes()[..],
+                &Self::b2_codes()[..],
+                &Self::b3_codes()[..],
+                &Self::b3a_codes()[..],
+            ]
+            .concat(),
+            Constellation::IRNSS => [&Self::irnl5_codes()[..], &Self::irn_s_codes()[..]].concat(),
+            c if c.is_sbas() => [&Self::geol1_codes()[..], &Self::geol5_codes()[..]].concat(),
+            _ => Vec::new(),
+        }
+    }
     /// Identifies Frequency channel, from given observable, related
     /// to given Constellation
     pub fn from_observable(
@@ -0,0 +1,212 @@
+//! Catalog: lightweight index of RINEX holdings, built from header sections only
+use crate::epoch::epoch_decompose;
+use crate::prelude::{Constellation, Duration, Epoch, Header, RinexType};
+use crate::production::ProductionAttributes;
+use crate::version::Version;
+use hifitime::Unit;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use walkdir::WalkDir;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Description of a single file, obtained from its header section
+/// (see [Header::quick_scan]) and its name.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CatalogEntry {
+    /// File path
+    pub path: PathBuf,
+    /// Station (marker) name, if known
+    pub station: Option<String>,
+    /// RINEX type
+    pub rinex_type: RinexType,
+    /// RINEX revision
+    pub version: Version,
+    /// True if file is Compact RINEX
+    pub crinex: bool,
+    /// Constellations described in the header
+    pub constellations: Vec<Constellation>,
+    /// First epoch, when described in the header
+    pub first_epoch: Option<Epoch>,
+    /// Last epoch, when described in the header
+    pub last_epoch: Option<Epoch>,
+    /// Sampling interval, when described in the header
+    pub interval: Option<Duration>,
+    /// Year of the first symbol, deduced from the first epoch,
+    /// or from the file name when the header does not describe it
+    pub year: Option<i32>,
+    /// Day of year of the first symbol, deduced like [Self::year]
+    pub doy: Option<u32>,
+}
+
+impl CatalogEntry {
+    /// Describes given file, without parsing its body
+    pub fn from_path(path: &Path) -> Result<Self, crate::Error> {
+        let header = Header::quick_scan(path.to_string_lossy().as_ref())?;
+        let attributes = path.file_name().and_then(|name| {
+            let name = name.to_string_lossy();
+            let name = name.strip_suffix(".gz").unwrap_or(&name);
+            ProductionAttributes::from_str(name).ok()
+        });
+
+        let (first_epoch, last_epoch) = match &header.obs {
+            Some(obs) => (obs.time_of_first_obs, obs.time_of_last_obs),
+            None => (None, None),
+        };
+
+        let mut constellations: Vec<Constellation> = match &header.obs {
+            Some(obs) if !obs.codes.is_empty() => obs.codes.keys().copied().collect(),
+            _ => header.constellation.into_iter().collect(),
+        };
+        constellations.sort();
+        constellations.dedup();
+
+        let (year, doy) = match first_epoch {
+            Some(epoch) => {
+                let (year, doy) = year_doy(epoch);
+                (Some(year), Some(doy))
+            },
+            None => match &attributes {
+                Some(attributes) => (Some(attributes.year as i32), Some(attributes.doy)),
+                None => (None, None),
+            },
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            station: match &header.geodetic_marker {
+                Some(marker) if !marker.name.trim().is_empty() => {
+                    Some(marker.name.trim().to_uppercase())
+                },
+                _ => attributes.map(|attributes| attributes.name),
+            },
+            rinex_type: header.rinex_type,
+            version: header.version,
+            crinex: header.is_crinex(),
+            constellations,
+            first_epoch,
+            last_epoch,
+            interval: header.sampling_interval.map(|dt| dt.duration()),
+            year,
+            doy,
+        })
+    }
+}
+
+/*
+ * Year and day of year (1 based) of given epoch, in its own timescale
+ */
+fn year_doy(epoch: Epoch) -> (i32, u32) {
+    let (year, _, _, _, _, _, _) = epoch_decompose(epoch);
+    let new_year = Epoch::from_gregorian(year, 1, 1, 0, 0, 0, 0, epoch.time_scale);
+    let days = (epoch - new_year).to_unit(Unit::Day).floor() as u32;
+    (year, days + 1)
+}
+
+/// [Catalog] search criteria. Unspecified criteria match any entry.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    /// Station (marker) name: entries whose station starts with this name
+    pub station: Option<String>,
+    /// RINEX type
+    pub rinex_type: Option<RinexType>,
+    /// Entries that describe this constellation
+    pub constellation: Option<Constellation>,
+    /// Year of the first symbol
+    pub year: Option<i32>,
+    /// Day of year of the first symbol
+    pub doy: Option<u32>,
+}
+
+impl Query {
+    /// Returns true if given entry matches all criteria
+    pub fn matches(&self, entry: &CatalogEntry) -> bool {
+        if let Some(station) = &self.station {
+            let station = station.trim().to_uppercase();
+            match &entry.station {
+                Some(name) if name.starts_with(&station) => {},
+                _ => return false,
+            }
+        }
+        if let Some(rinex_type) = self.rinex_type {
+            if entry.rinex_type != rinex_type {
+                return false;
+            }
+        }
+        if let Some(constellation) = self.constellation {
+            // mixed files may describe any constellation
+            if !entry
+                .constellations
+                .iter()
+                .any(|c| *c == constellation || *c == Constellation::Mixed)
+            {
+                return false;
+            }
+        }
+        if self.year.is_some() && entry.year != self.year {
+            return false;
+        }
+        if self.doy.is_some() && entry.doy != self.doy {
+            return false;
+        }
+        true
+    }
+}
+
+/// Catalog of RINEX holdings, built from header sections only,
+/// so thousands of files are classified in a few seconds.
+/// ```
+/// use rinex::catalog::{Catalog, Query};
+/// let catalog = Catalog::from_directory("../test_resources/OBS/V2")
+///     .unwrap();
+/// assert_eq!(catalog.entries.len(), 10);
+///
+/// let query = Query {
+///     station: Some("AJAC".to_string()),
+///     doy: Some(355),
+///     ..Default::default()
+/// };
+/// let found: Vec<_> = catalog.query(&query).collect();
+/// assert_eq!(found.len(), 1);
+/// assert!(found[0].path.ends_with("AJAC3550.21O"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Catalog {
+    /// Catalog entries, in alphabetical (path) order
+    pub entries: Vec<CatalogEntry>,
+}
+
+impl Catalog {
+    /// Builds a [Catalog] from given directory tree.
+    /// Files that are not RINEX (or cannot be parsed) are ignored.
+    pub fn from_directory<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let entries = walk(path.as_ref())?
+            .iter()
+            .filter_map(|path| CatalogEntry::from_path(path).ok())
+            .collect();
+        Ok(Self { entries })
+    }
+    /// Returns entries that match given [Query]
+    pub fn query<'a>(&'a self, query: &'a Query) -> impl Iterator<Item = &'a CatalogEntry> + 'a {
+        self.entries.iter().filter(|entry| query.matches(entry))
+    }
+}
+
+/*
+ * Lists all files contained in given directory tree, in alphabetical order.
+ * Shared by the catalog and the campaign ingestion.
+ */
+pub(crate) fn walk(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::<PathBuf>::new();
+    for entry in WalkDir::new(dir).follow_links(true) {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            paths.push(entry.into_path());
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
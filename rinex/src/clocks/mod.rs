@@ -1,7 +1,7 @@
 //! RINEX Clock files parser & analysis
 use hifitime::TimeScale;
 pub mod record;
-pub use record::{ClockData, ClockDataType, Error, Record, System};
+pub use record::{ClockAlignment, ClockData, ClockDataType, Error, Record, System};
 
 /// Clocks `RINEX` specific header fields
 #[derive(Clone, Debug, Default, PartialEq)]
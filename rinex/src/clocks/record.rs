@@ -1,4 +1,7 @@
-use crate::{epoch, merge, merge::Merge, prelude::*, split, split::Split, version::Version};
+use crate::{
+    epoch, fieldslice, formatter::FloatFormat, merge, merge::Merge, prelude::*, record, split,
+    split::Split, version::Version,
+};
 use hifitime::Duration;
 use std::collections::{BTreeMap, HashMap};
 use std::str::FromStr;
@@ -65,6 +68,10 @@ pub enum Error {
     ParseObservableError(#[from] strum::ParseError),
     #[error("failed to write data")]
     WriterIoError(#[from] std::io::Error),
+    #[error("missing data")]
+    MissingData,
+    #[error("malformed field")]
+    FieldError(#[from] fieldslice::Error),
 }
 
 /// Clocks file payload
@@ -86,7 +93,7 @@ pub struct ClockData {
 }
 
 /// Clock data observables
-#[derive(Debug, PartialEq, Eq, Hash, Clone, EnumString)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, PartialOrd, Ord, EnumString)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ClockDataType {
     /// Data analysis results for receiver clocks
@@ -136,10 +143,31 @@ impl std::fmt::Display for ClockDataType {
 */
 pub type Record = BTreeMap<Epoch, HashMap<ClockDataType, HashMap<System, ClockData>>>;
 
+/*
+ * Estimated memory footprint of a Clock record, in bytes
+ */
+pub(crate) fn memory_usage(rec: &Record) -> usize {
+    let mut size = record::btreemap_usage(rec);
+    for types in rec.values() {
+        size += record::hashmap_usage(types);
+        for systems in types.values() {
+            size += record::hashmap_usage(systems);
+            for system in systems.keys() {
+                if let System::Station(name) = system {
+                    size += name.capacity();
+                }
+            }
+        }
+    }
+    size
+}
+
 pub(crate) fn is_new_epoch(line: &str) -> bool {
     // first 2 bytes match a ClockDataType code
-    let content = line.split_at(2).0;
-    ClockDataType::from_str(content).is_ok()
+    match line.get(..2) {
+        Some(content) => ClockDataType::from_str(content).is_ok(),
+        None => false,
+    }
 }
 
 /// Builds `RINEX` record entry for `Clocks` data files.   
@@ -150,16 +178,24 @@ pub(crate) fn parse_epoch(
     content: &str,
 ) -> Result<(Epoch, ClockDataType, System, ClockData), Error> {
     let mut lines = content.lines();
-    let line = lines.next().unwrap();
+    let line = lines.next().ok_or(Error::MissingData)?;
+    let limit = Version { major: 3, minor: 4 };
+    // data type, system, epoch and nb of data fields, at the very least
+    let min_len = match version < limit {
+        true => 3 + 5 + 28 + 4,
+        false => 3 + 10 + 28 + 4,
+    };
+    if line.len() < min_len || !line.is_ascii() {
+        return Err(Error::MissingData);
+    }
     // Data type code
-    let (dtype, mut rem) = line.split_at(3);
+    let (dtype, mut rem) = fieldslice::split_at(line, 3)?;
     let data_type = ClockDataType::from_str(dtype.trim())?; // must pass
-    let limit = Version { major: 3, minor: 4 };
 
     let system: System = match version < limit {
         true => {
             // old fashion
-            let (system_str, r) = rem.split_at(5);
+            let (system_str, r) = fieldslice::split_at(rem, 5)?;
             rem = r;
             if let Ok(svnn) = SV::from_str(system_str.trim()) {
                 System::SV(svnn)
@@ -169,14 +205,14 @@ pub(crate) fn parse_epoch(
         },
         false => {
             // modern fashion
-            let (system_str, r) = rem.split_at(4);
+            let (system_str, r) = fieldslice::split_at(rem, 4)?;
             if let Ok(svnn) = SV::from_str(system_str.trim()) {
-                let (_, r) = r.split_at(6);
+                let (_, r) = fieldslice::split_at(r, 6)?;
                 rem = r;
                 System::SV(svnn)
             } else {
                 let mut content = system_str.to_owned();
-                let (remainder, r) = r.split_at(6);
+                let (remainder, r) = fieldslice::split_at(r, 6)?;
                 rem = r;
                 content.push_str(remainder);
                 System::Station(content.trim().to_string())
@@ -191,19 +227,20 @@ pub(crate) fn parse_epoch(
        +2+1  // h
        +2+1  // m
         +11; // s
-    let (epoch, rem) = rem.split_at(offset);
+    let (epoch, rem) = fieldslice::split_at(rem, offset)?;
     let (epoch, _) = epoch::parse_utc(epoch.trim())?;
 
     // nb of data fields
-    let (n, _) = rem.split_at(4);
+    let (n, _) = fieldslice::split_at(rem, 4)?;
     let n = n.trim().parse::<u8>()?;
 
     // data fields
     let mut data = ClockData::default();
     let items: Vec<&str> = line.split_ascii_whitespace().collect();
-    data.bias = items[9].trim().parse::<f64>()?; // bias must pass
+    let bias = items.get(9).ok_or(Error::MissingData)?;
+    data.bias = bias.trim().parse::<f64>()?; // bias must pass
     if n > 1 {
-        if let Ok(f) = items[10].trim().parse::<f64>() {
+        if let Some(Ok(f)) = items.get(10).map(|item| item.trim().parse::<f64>()) {
             data.bias_dev = Some(f)
         }
     }
@@ -236,24 +273,29 @@ pub(crate) fn fmt_epoch(
     data: &HashMap<ClockDataType, HashMap<System, ClockData>>,
 ) -> Result<String, Error> {
     let mut lines = String::with_capacity(128);
-    for (dtype, data) in data.iter() {
-        for (system, data) in data.iter() {
+    // sorted, for reproducible production
+    let mut dtypes: Vec<_> = data.iter().collect();
+    dtypes.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (dtype, data) in dtypes {
+        let mut systems: Vec<_> = data.iter().collect();
+        systems.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (system, data) in systems {
             lines.push_str(&format!("{} {} {} ", dtype, system, epoch));
-            lines.push_str(&format!("{:.13E} ", data.bias));
+            lines.push_str(&format!("{} ", FloatFormat::CLOCK.format(data.bias)));
             if let Some(sigma) = data.bias_dev {
-                lines.push_str(&format!("{:.13E} ", sigma));
+                lines.push_str(&format!("{} ", FloatFormat::CLOCK.format(sigma)));
             }
             if let Some(drift) = data.drift {
-                lines.push_str(&format!("{:.13E} ", drift));
+                lines.push_str(&format!("{} ", FloatFormat::CLOCK.format(drift)));
             }
             if let Some(sigma) = data.drift_dev {
-                lines.push_str(&format!("{:.13E} ", sigma));
+                lines.push_str(&format!("{} ", FloatFormat::CLOCK.format(sigma)));
             }
             if let Some(drift_change) = data.drift_change {
-                lines.push_str(&format!("{:.13E} ", drift_change));
+                lines.push_str(&format!("{} ", FloatFormat::CLOCK.format(drift_change)));
             }
             if let Some(sigma) = data.drift_change_dev {
-                lines.push_str(&format!("{:.13E} ", sigma));
+                lines.push_str(&format!("{} ", FloatFormat::CLOCK.format(sigma)));
             }
             lines.push('\n');
         }
@@ -284,6 +326,200 @@ mod test {
         let c = "A  G16  1994 07 14 20 59  0.000000  2   -0.123456789012E+00 -0.123456789012E+01";
         assert!(!is_new_epoch(c));
     }
+    #[test]
+    fn test_fmt_epoch_order() {
+        let epoch = Epoch::from_gregorian_utc_at_midnight(2022, 1, 1);
+        let mut data = HashMap::<ClockDataType, HashMap<System, ClockData>>::new();
+        for dtype in [ClockDataType::AS, ClockDataType::AR] {
+            let mut systems = HashMap::new();
+            for station in ["ZIMM", "AREQ", "PIE1"] {
+                systems.insert(System::Station(station.to_string()), ClockData::default());
+            }
+            data.insert(dtype, systems);
+        }
+        let formatted = fmt_epoch(&epoch, &data).unwrap();
+        let keys: Vec<_> = formatted
+            .lines()
+            .map(|line| {
+                line.split_whitespace()
+                    .take(2)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect();
+        assert_eq!(
+            keys,
+            vec!["AR AREQ", "AR PIE1", "AR ZIMM", "AS AREQ", "AS PIE1", "AS ZIMM"]
+        );
+    }
+}
+
+/// Clock products alignment, typically applied before comparing
+/// clock products from different analysis centers.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::clocks::{ClockAlignment, ClockDataType, System};
+/// let rinex = Rinex::from_file("../test_resources/CLK/V2/COD20352.CLK")
+///     .unwrap();
+/// let record = rinex.record.as_clock()
+///     .unwrap();
+/// let pie1 = System::Station("PIE1".to_string());
+/// let aligned = record.rereference(&pie1);
+/// for (_, dtypes) in aligned.iter() {
+///     let pie1 = &dtypes[&ClockDataType::AR][&pie1];
+///     assert_eq!(pie1.bias, 0.0);
+/// }
+/// ```
+pub trait ClockAlignment {
+    /// Re-references all clocks to given reference clock (station or satellite),
+    /// by subtracting its bias (and drift) from all other clocks, epoch wise.
+    /// Epochs where the reference clock is missing are dropped.
+    fn rereference(&self, reference: &System) -> Self;
+    /// Re-references all clocks to given reference clock, see [Self::rereference].
+    fn rereference_mut(&mut self, reference: &System);
+    /// Removes the mean bias of each clock.
+    fn demean(&self) -> Self;
+    /// Removes the mean bias of each clock.
+    fn demean_mut(&mut self);
+    /// Removes the linear trend (offset and rate, least squares) of each clock.
+    /// Fitted rates are also removed from the drift estimates.
+    fn detrend(&self) -> Self;
+    /// Removes the linear trend of each clock, see [Self::detrend].
+    fn detrend_mut(&mut self);
+}
+
+/*
+ * Least squares linear fit (offset @ t0, rate) of each clock
+ */
+fn linear_fit(
+    rec: &Record,
+    with_rate: bool,
+) -> HashMap<(ClockDataType, System), (Epoch, f64, f64)> {
+    let mut series = HashMap::<(ClockDataType, System), Vec<(Epoch, f64)>>::new();
+    for (epoch, dtypes) in rec {
+        for (dtype, systems) in dtypes {
+            for (system, data) in systems {
+                series
+                    .entry((dtype.clone(), system.clone()))
+                    .or_default()
+                    .push((*epoch, data.bias));
+            }
+        }
+    }
+    series
+        .into_iter()
+        .map(|(key, samples)| {
+            let t0 = samples[0].0;
+            let n = samples.len() as f64;
+            let x_mean = samples
+                .iter()
+                .map(|(t, _)| (*t - t0).to_seconds())
+                .sum::<f64>()
+                / n;
+            let y_mean = samples.iter().map(|(_, y)| y).sum::<f64>() / n;
+            let (mut sxy, mut sxx) = (0.0_f64, 0.0_f64);
+            for (t, y) in &samples {
+                let dx = (*t - t0).to_seconds() - x_mean;
+                sxy += dx * (y - y_mean);
+                sxx += dx * dx;
+            }
+            let rate = if with_rate && sxx > 0.0 {
+                sxy / sxx
+            } else {
+                0.0
+            };
+            (key, (t0, y_mean - rate * x_mean, rate))
+        })
+        .collect()
+}
+
+/*
+ * Removes the fitted model of each clock
+ */
+fn remove_fit(rec: &mut Record, with_rate: bool) {
+    let fit = linear_fit(rec, with_rate);
+    for (epoch, dtypes) in rec.iter_mut() {
+        for (dtype, systems) in dtypes.iter_mut() {
+            for (system, data) in systems.iter_mut() {
+                if let Some((t0, offset, rate)) = fit.get(&(dtype.clone(), system.clone())) {
+                    data.bias -= offset + rate * (*epoch - *t0).to_seconds();
+                    if let Some(drift) = data.drift.as_mut() {
+                        *drift -= rate;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl ClockAlignment for Record {
+    fn rereference(&self, reference: &System) -> Self {
+        let mut s = self.clone();
+        s.rereference_mut(reference);
+        s
+    }
+    fn rereference_mut(&mut self, reference: &System) {
+        self.retain(|_, dtypes| {
+            // analysis results are preferred
+            let refclock = match [
+                ClockDataType::AR,
+                ClockDataType::AS,
+                ClockDataType::CR,
+                ClockDataType::DR,
+                ClockDataType::MS,
+            ]
+            .iter()
+            .find_map(|dtype| dtypes.get(dtype)?.get(reference))
+            .cloned()
+            {
+                Some(refclock) => refclock,
+                None => return false,
+            };
+            for systems in dtypes.values_mut() {
+                for (system, data) in systems.iter_mut() {
+                    if system == reference {
+                        *data = ClockData {
+                            bias: 0.0,
+                            drift: data.drift.map(|_| 0.0),
+                            drift_change: data.drift_change.map(|_| 0.0),
+                            ..Default::default()
+                        };
+                        continue;
+                    }
+                    data.bias -= refclock.bias;
+                    data.drift = match (data.drift, refclock.drift) {
+                        (Some(drift), Some(refdrift)) => Some(drift - refdrift),
+                        _ => None,
+                    };
+                    data.drift_change = match (data.drift_change, refclock.drift_change) {
+                        (Some(change), Some(refchange)) => Some(change - refchange),
+                        _ => None,
+                    };
+                    // reference clock uncertainty adds up
+                    if let (Some(dev), Some(refdev)) = (data.bias_dev, refclock.bias_dev) {
+                        data.bias_dev = Some((dev.powi(2) + refdev.powi(2)).sqrt());
+                    }
+                }
+            }
+            true
+        });
+    }
+    fn demean(&self) -> Self {
+        let mut s = self.clone();
+        s.demean_mut();
+        s
+    }
+    fn demean_mut(&mut self) {
+        remove_fit(self, false);
+    }
+    fn detrend(&self) -> Self {
+        let mut s = self.clone();
+        s.detrend_mut();
+        s
+    }
+    fn detrend_mut(&mut self) {
+        remove_fit(self, true);
+    }
 }
 
 impl Merge for Record {
@@ -346,6 +582,51 @@ impl Merge for Record {
     }
 }
 
+/*
+ * Merges `rhs` into `lhs`, resolving overlapping epochs with given policy
+ */
+pub(crate) fn merge_with_mut(
+    lhs: &mut Record,
+    rhs: &Record,
+    policy: merge::MergePolicy,
+) -> Result<(), merge::Error> {
+    if policy == merge::MergePolicy::Error {
+        if let Some(epoch) = merge::first_conflict(lhs, rhs) {
+            return Err(merge::Error::EpochConflict(*epoch));
+        }
+    }
+    for (epoch, dtypes) in rhs.iter() {
+        if let Some(ddtypes) = lhs.get_mut(epoch) {
+            for (dtype, systems) in dtypes.iter() {
+                let ssystems = ddtypes.entry(dtype.clone()).or_default();
+                for (system, data) in systems.iter() {
+                    if let Some(ddata) = ssystems.get_mut(system) {
+                        merge::resolve_f64(&mut ddata.bias, data.bias, policy);
+                        merge::resolve_option_f64(&mut ddata.bias_dev, data.bias_dev, policy);
+                        merge::resolve_option_f64(&mut ddata.drift, data.drift, policy);
+                        merge::resolve_option_f64(&mut ddata.drift_dev, data.drift_dev, policy);
+                        merge::resolve_option_f64(
+                            &mut ddata.drift_change,
+                            data.drift_change,
+                            policy,
+                        );
+                        merge::resolve_option_f64(
+                            &mut ddata.drift_change_dev,
+                            data.drift_change_dev,
+                            policy,
+                        );
+                    } else {
+                        ssystems.insert(system.clone(), data.clone());
+                    }
+                }
+            }
+        } else {
+            lhs.insert(*epoch, dtypes.clone());
+        }
+    }
+    Ok(())
+}
+
 impl Split for Record {
     fn split(&self, epoch: Epoch) -> Result<(Self, Self), split::Error> {
         let r0 = self
@@ -454,7 +735,93 @@ impl Interpolate for Record {
         s.interpolate_mut(series);
         s
     }
-    fn interpolate_mut(&mut self, _series: TimeSeries) {
-        unimplemented!("clocks:record:interpolate_mut()");
+    fn interpolate_mut(&mut self, series: TimeSeries) {
+        *self = series
+            .filter_map(|t| Some((t, interpolate_at(self, t, &InterpMethod::Linear, None)?)))
+            .collect();
+    }
+}
+
+/*
+ * Interpolates clock data between two samples, at t0 <= t <= t1.
+ * Optional fields are only interpolated when both samples provide them.
+ */
+#[cfg(feature = "processing")]
+fn interpolate_data(
+    method: &InterpMethod,
+    (t0, d0): (Epoch, &ClockData),
+    (t1, d1): (Epoch, &ClockData),
+    t: Epoch,
+) -> ClockData {
+    let interp = |y0: f64, y1: f64| method.interpolate((t0, y0), (t1, y1), t);
+    let interp_opt = |y0: Option<f64>, y1: Option<f64>| Some(interp(y0?, y1?));
+    ClockData {
+        bias: interp(d0.bias, d1.bias),
+        bias_dev: interp_opt(d0.bias_dev, d1.bias_dev),
+        drift: interp_opt(d0.drift, d1.drift),
+        drift_dev: interp_opt(d0.drift_dev, d1.drift_dev),
+        drift_change: interp_opt(d0.drift_change, d1.drift_change),
+        drift_change_dev: interp_opt(d0.drift_change_dev, d1.drift_change_dev),
+    }
+}
+
+/*
+ * Interpolates clock data at given epoch, from surrounding samples
+ * that are no further than `max_span` apart
+ */
+#[cfg(feature = "processing")]
+pub(crate) fn interpolate_at(
+    rec: &Record,
+    t: Epoch,
+    method: &InterpMethod,
+    max_span: Option<Duration>,
+) -> Option<HashMap<ClockDataType, HashMap<System, ClockData>>> {
+    if let Some(dtypes) = rec.get(&t) {
+        return Some(dtypes.clone());
+    }
+    let (_, before) = rec.range(..t).next_back()?;
+    let (_, after) = rec.range(t..).next()?;
+    let mut ret = HashMap::<ClockDataType, HashMap<System, ClockData>>::new();
+    for (dtype, systems) in before.iter().chain(after.iter()) {
+        for system in systems.keys() {
+            if ret
+                .get(dtype)
+                .map(|systems| systems.contains_key(system))
+                .unwrap_or(false)
+            {
+                continue;
+            }
+            let (before, after) = match crate::algorithm::neighbours(rec, t, |dtypes| {
+                dtypes.get(dtype).and_then(|systems| systems.get(system))
+            }) {
+                Some(neighbours) => neighbours,
+                None => continue,
+            };
+            if max_span
+                .map(|span| after.0 - before.0 <= span)
+                .unwrap_or(true)
+            {
+                ret.entry(dtype.clone())
+                    .or_default()
+                    .insert(system.clone(), interpolate_data(method, before, after, t));
+            }
+        }
+    }
+    if ret.is_empty() {
+        None
+    } else {
+        Some(ret)
     }
 }
+
+/*
+ * Fills data gaps that do not exceed `max_gap`, at the dominant sampling interval
+ */
+#[cfg(feature = "processing")]
+pub(crate) fn fill_gaps_mut(rec: &mut Record, max_gap: Duration, method: &InterpMethod) {
+    let filled: Vec<_> = crate::algorithm::gap_epochs(rec.keys(), max_gap)
+        .into_iter()
+        .filter_map(|t| Some((t, interpolate_at(rec, t, method, Some(max_gap))?)))
+        .collect();
+    rec.extend(filled);
+}
@@ -0,0 +1,8 @@
+//! Physical constants shared by the positioning, clock estimation
+//! and processing toolkits.
+
+/// Speed of light in vacuum [m.s⁻¹]
+pub const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+
+/// Earth rotation rate in WGS84 frame [rad.s⁻¹]
+pub const EARTH_OMEGA_E_WGS84: f64 = 7.2921151467E-5;
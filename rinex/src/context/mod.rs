@@ -1,5 +1,5 @@
 //! RINEX post processing context
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 use walkdir::WalkDir;
 
@@ -8,12 +8,34 @@ use crate::{merge, merge::Merge};
 use sp3::Merge as SP3Merge;
 
 // use crate::observation::Snr;
-// use crate::prelude::Epoch;
-use crate::prelude::{GroundPosition, Rinex};
+use crate::observation::MappingProfile;
+use crate::prelude::{Duration, Epoch, GroundPosition, Rinex};
+use crate::progress::ProgressSink;
+use crate::reader::ParserSettings;
 // use gnss::prelude::SV;
 
 use sp3::prelude::SP3;
 
+#[cfg(all(feature = "obs", feature = "nav"))]
+use crate::{
+    constants::SPEED_OF_LIGHT,
+    navigation::{index::EphemerisIndex, Ephemeris},
+    prelude::Constellation,
+    propagation::transmission,
+};
+
+#[cfg(feature = "obs")]
+use crate::{
+    clocks::{ClockDataType, System},
+    prelude::SV,
+};
+
+#[cfg(feature = "processing")]
+use crate::preprocessing::{MaskFilter, MaskOperand, Preprocessing, TargetItem};
+
+#[cfg(feature = "obs")]
+use std::collections::{BTreeMap, HashMap};
+
 use log::{error, trace};
 
 #[cfg(feature = "qc")]
@@ -36,6 +58,89 @@ pub enum Error {
     RinexMergeError(#[from] merge::Error),
     #[error("failed to extend sp3 context")]
     SP3MergeError(#[from] sp3::MergeError),
+    #[error("sp3 parsing error")]
+    SP3Parsing(#[from] sp3::Errors),
+    #[error("file content does not match {0:?} product")]
+    ProductTypeMismatch(ProductType),
+}
+
+/*
+ * Linear interpolation of a time series, at t
+ */
+#[cfg(feature = "obs")]
+pub(crate) fn interpolate(series: &BTreeMap<Epoch, f64>, t: Epoch) -> Option<f64> {
+    let (t0, v0) = series.range(..=t).next_back()?;
+    let (t1, v1) = series.range(t..).next()?;
+    let dt = (*t1 - *t0).to_seconds();
+    if dt == 0.0 {
+        return Some(*v0);
+    }
+    Some(v0 + (v1 - v0) * (t - *t0).to_seconds() / dt)
+}
+
+/// [ProductType] describes the role of a file within a [RnxContext]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ProductType {
+    /// Observation RINEX
+    Observation,
+    /// Broadcast Navigation RINEX
+    BroadcastNavigation,
+    /// Meteo observations RINEX
+    MeteoObservation,
+    /// SP3 high precision orbits
+    HighPrecisionOrbit,
+    /// Clock RINEX, high precision clock states
+    HighPrecisionClock,
+    /// ANTEX, antenna calibration
+    Antex,
+    /// IONEX, ionosphere maps
+    Ionex,
+}
+
+/// Broadcast ephemerides remain valid past their last publication,
+/// and interpolated products need samples on both sides of the time of interest:
+/// those products are given this margin (in hours) when evaluating and trimming time spans.
+const PRODUCT_MARGIN_HOURS: f64 = 4.0;
+
+/*
+ * Time span of a RINEX file (first and last epoch)
+ */
+fn rinex_time_span(rinex: &Rinex) -> Option<(Epoch, Epoch)> {
+    Some((rinex.first_epoch()?, rinex.last_epoch()?))
+}
+
+/*
+ * Retains the RINEX data within [start, end]
+ */
+#[cfg(feature = "processing")]
+fn rinex_trim_mut(rinex: &mut Rinex, start: Epoch, end: Epoch) {
+    rinex.filter_mut(
+        MaskFilter {
+            operand: MaskOperand::GreaterEquals,
+            item: TargetItem::EpochItem(start),
+        }
+        .into(),
+    );
+    rinex.filter_mut(
+        MaskFilter {
+            operand: MaskOperand::LowerEquals,
+            item: TargetItem::EpochItem(end),
+        }
+        .into(),
+    );
+}
+
+/*
+ * Retains the SP3 data within [start, end]
+ */
+#[cfg(feature = "processing")]
+fn sp3_trim_mut(sp3: &mut SP3, start: Epoch, end: Epoch) {
+    let within = |t: &Epoch| *t >= start && *t <= end;
+    sp3.epoch.retain(within);
+    sp3.position.retain(|t, _| within(t));
+    sp3.clock.retain(|t, _| within(t));
+    sp3.velocities.retain(|t, _| within(t));
+    sp3.clock_rate.retain(|t, _| within(t));
 }
 
 #[derive(Default, Debug, Clone)]
@@ -84,6 +189,15 @@ pub struct RnxContext {
     /// Optional IONEX file for accurate ionospheric
     /// delay modeling
     pub ionex: Option<ProvidedData<Rinex>>,
+    /// Optional Clock RINEX, high precision clock states
+    /// that complete SP3 orbits
+    pub clk: Option<ProvidedData<Rinex>>,
+    /// Coarse reference position, estimated when no header defines one,
+    /// see [Self::bootstrap_ground_position_mut]
+    pub approx_position: Option<GroundPosition>,
+    /// [ParserSettings] applied when loading files, for example
+    /// to reuse the binary cache of Observation RINEX, see [ParserSettings::cache]
+    pub parser_settings: ParserSettings,
 }
 
 impl RnxContext {
@@ -130,13 +244,41 @@ impl RnxContext {
     }
     /// Load individual file into Context
     pub fn load(&mut self, path: &PathBuf) -> Result<(), Error> {
+        self.load_rinex(path, None, &mut |_, _| {})
+    }
+    /// Same as [Self::load], Observation RINEX being fixed
+    /// by given [MappingProfile] at parse time, see [Rinex::from_file_with_profile].
+    pub fn load_with_profile(
+        &mut self,
+        path: &PathBuf,
+        profile: &MappingProfile,
+    ) -> Result<(), Error> {
+        self.load_rinex(path, Some(profile), &mut |_, _| {})
+    }
+    /// Same as [Self::load_with_profile], with an optional [MappingProfile],
+    /// the RINEX parser progress being reported to given [ProgressSink].
+    pub fn load_with_progress(
+        &mut self,
+        path: &PathBuf,
+        profile: Option<&MappingProfile>,
+        progress: &mut dyn ProgressSink,
+    ) -> Result<(), Error> {
+        self.load_rinex(path, profile, progress)
+    }
+    fn load_rinex(
+        &mut self,
+        path: &PathBuf,
+        profile: Option<&MappingProfile>,
+        progress: &mut dyn ProgressSink,
+    ) -> Result<(), Error> {
         let fullpath = path.to_string_lossy().to_string();
         let filename = path
             .file_name()
             .ok_or(Error::FileNameDetermination)?
             .to_string_lossy()
             .to_string();
-        if let Ok(rnx) = Rinex::from_path(path) {
+        let rinex = Rinex::parse_path(path, profile, &self.parser_settings, progress);
+        if let Ok(rnx) = rinex {
             if rnx.is_observation_rinex() {
                 self.load_obs(path, &rnx)?;
                 trace!("loaded observations \"{}\"", filename);
@@ -152,6 +294,9 @@ impl RnxContext {
             } else if rnx.is_antex() {
                 self.load_antex(path, &rnx)?;
                 trace!("loaded antex dataset \"{}\"", filename);
+            } else if rnx.is_clocks_rinex() {
+                self.load_clock(path, &rnx)?;
+                trace!("loaded clock states \"{}\"", filename);
             } else {
                 return Err(Error::NonSupportedType);
             }
@@ -161,12 +306,34 @@ impl RnxContext {
         }
         Ok(())
     }
+    /// Loads individual file into Context, with an explicit role.
+    /// Unlike [Self::load], this fails if the file content
+    /// does not match the expected [ProductType].
+    pub fn load_product(&mut self, path: &PathBuf, product: ProductType) -> Result<(), Error> {
+        if product == ProductType::HighPrecisionOrbit {
+            let sp3 = SP3::from_file(&path.to_string_lossy())?;
+            return self.load_sp3(path, &sp3);
+        }
+        let rnx = Rinex::parse_path(path, None, &self.parser_settings, &mut |_, _| {})?;
+        match product {
+            ProductType::Observation if rnx.is_observation_rinex() => self.load_obs(path, &rnx),
+            ProductType::BroadcastNavigation if rnx.is_navigation_rinex() => {
+                self.load_nav(path, &rnx)
+            },
+            ProductType::MeteoObservation if rnx.is_meteo_rinex() => self.load_meteo(path, &rnx),
+            ProductType::HighPrecisionClock if rnx.is_clocks_rinex() => self.load_clock(path, &rnx),
+            ProductType::Antex if rnx.is_antex() => self.load_antex(path, &rnx),
+            ProductType::Ionex if rnx.is_ionex() => self.load_ionex(path, &rnx),
+            _ => Err(Error::ProductTypeMismatch(product)),
+        }
+    }
     /// Unwraps inner RINEX data, by preference order:
     /// 1. Observation Data if provided
     /// 2. Navigation Data if provided
     /// 3. Meteo Data if provided
     /// 4. Ionex Data if provided
     /// 5. ATX Data if provided
+    /// 6. Clock Data if provided
     fn provided_rinex(&self) -> Option<&ProvidedData<Rinex>> {
         if let Some(data) = &self.obs {
             Some(data)
@@ -178,6 +345,8 @@ impl RnxContext {
             Some(data)
         } else if let Some(data) = &self.atx {
             Some(data)
+        } else if let Some(data) = &self.clk {
+            Some(data)
         } else {
             None
         }
@@ -188,6 +357,7 @@ impl RnxContext {
     /// 3. Meteo Data if provided
     /// 4. Ionex Data if provided
     /// 5. ATX Data if provided
+    /// 6. Clock Data if provided
     pub fn rinex_data(&self) -> Option<&Rinex> {
         let rinex_source = self.provided_rinex()?;
         Some(&rinex_source.data)
@@ -198,6 +368,7 @@ impl RnxContext {
     /// 3. Meteo Data if provided
     /// 4. Ionex Data if provided
     /// 5. ATX Data if provided
+    /// 6. Clock Data if provided
     pub fn rinex_path(&self) -> Option<&PathBuf> {
         let rinex_source = self.provided_rinex()?;
         let paths = &rinex_source.paths;
@@ -214,6 +385,7 @@ impl RnxContext {
     /// 3. Meteo Data if provided
     /// 4. Ionex Data if provided
     /// 5. ATX Data if provided
+    /// 6. Clock Data if provided
     pub fn rinex_name(&self) -> Option<String> {
         let path = self.rinex_path()?;
         Some(path.file_name().unwrap().to_string_lossy().to_string())
@@ -367,6 +539,102 @@ impl RnxContext {
             None
         }
     }
+    /// Returns true if provided context contains Clock RINEX data
+    pub fn has_clock_data(&self) -> bool {
+        self.clk.is_some()
+    }
+    /// Returns Clock RINEX files source paths
+    pub fn clk_paths(&self) -> Option<&[PathBuf]> {
+        if let Some(ref clk) = self.clk {
+            Some(clk.paths())
+        } else {
+            None
+        }
+    }
+    /// Returns reference to Clock RINEX data specifically
+    pub fn clk_data(&self) -> Option<&Rinex> {
+        if let Some(ref clk) = self.clk {
+            Some(&clk.data)
+        } else {
+            None
+        }
+    }
+    /// Returns mutable reference to Clock RINEX data specifically
+    pub fn clk_data_mut(&mut self) -> Option<&mut Rinex> {
+        if let Some(ref mut clk) = self.clk {
+            Some(&mut clk.data)
+        } else {
+            None
+        }
+    }
+    /// Returns the time span (first and last epoch) of each product of this context.
+    /// ANTEX has no time span. Broadcast ephemerides remain valid past their last
+    /// publication: the span of Navigation data is extended by a few hours.
+    pub fn time_spans(&self) -> Vec<(ProductType, Epoch, Epoch)> {
+        let margin = Duration::from_hours(PRODUCT_MARGIN_HOURS);
+        let mut spans = Vec::new();
+        for (product, data) in [
+            (ProductType::Observation, self.obs_data()),
+            (ProductType::BroadcastNavigation, self.nav_data()),
+            (ProductType::MeteoObservation, self.meteo_data()),
+            (ProductType::HighPrecisionClock, self.clk_data()),
+            (ProductType::Ionex, self.ionex_data()),
+        ] {
+            if let Some((start, end)) = data.and_then(rinex_time_span) {
+                if product == ProductType::BroadcastNavigation {
+                    spans.push((product, start, end + margin));
+                } else {
+                    spans.push((product, start, end));
+                }
+            }
+        }
+        if let Some(sp3) = self.sp3_data() {
+            if let (Some(start), Some(end)) = (sp3.first_epoch(), sp3.last_epoch()) {
+                spans.push((ProductType::HighPrecisionOrbit, start, end));
+            }
+        }
+        spans
+    }
+    /// Returns the time window that all products of this context have in common,
+    /// see [Self::time_spans]. Returns None if they do not overlap,
+    /// in which case most processing would fail.
+    pub fn common_time_span(&self) -> Option<(Epoch, Epoch)> {
+        let spans = self.time_spans();
+        let start = spans.iter().map(|(_, start, _)| *start).max()?;
+        let end = spans.iter().map(|(_, _, end)| *end).min()?;
+        if start <= end {
+            Some((start, end))
+        } else {
+            None
+        }
+    }
+    /// Retains the data within [start, end], typically the [Self::common_time_span].
+    /// Observation and Meteo data are trimmed exactly. Broadcast ephemerides, precise
+    /// orbits and clocks and IONEX are trimmed with a margin of a few hours, so ephemeris
+    /// selection and interpolation remain possible at the edges of the window.
+    #[cfg(feature = "processing")]
+    #[cfg_attr(docrs, doc(cfg(feature = "processing")))]
+    pub fn trim_mut(&mut self, start: Epoch, end: Epoch) {
+        let margin = Duration::from_hours(PRODUCT_MARGIN_HOURS);
+        if let Some(obs) = self.obs_data_mut() {
+            rinex_trim_mut(obs, start, end);
+        }
+        if let Some(meteo) = self.meteo_data_mut() {
+            rinex_trim_mut(meteo, start, end);
+        }
+        if let Some(nav) = self.nav_data_mut() {
+            rinex_trim_mut(nav, start - margin, end + margin);
+        }
+        if let Some(clk) = self.clk_data_mut() {
+            rinex_trim_mut(clk, start - margin, end + margin);
+        }
+        if let Some(ionex) = self.ionex_data_mut() {
+            rinex_trim_mut(ionex, start - margin, end + margin);
+        }
+        if let Some(sp3) = &mut self.sp3 {
+            sp3_trim_mut(&mut sp3.data, start - margin, end + margin);
+        }
+    }
     /// Returns possible Reference position defined in this context.
     /// Usually the Receiver location in the laboratory.
     pub fn ground_position(&self) -> Option<GroundPosition> {
@@ -380,7 +648,61 @@ impl RnxContext {
                 return Some(pos);
             }
         }
-        None
+        self.approx_position
+    }
+    /// When no header defines the reference position, estimates a coarse position
+    /// from the Observation and Navigation data (see [Rinex::approx_position]),
+    /// which then serves as [Self::ground_position]: elevation angles, masks and QC
+    /// become feasible. When `update_header` is set, the estimate is also written
+    /// into the Observation header (APPROX POSITION XYZ), so generated files preserve it.
+    /// Returns the reference position, either defined or estimated.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::path::PathBuf;
+    /// let mut ctx = RnxContext::new(&PathBuf::from(
+    ///     "../test_resources/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz",
+    /// )).unwrap();
+    /// ctx.load(&PathBuf::from(
+    ///     "../test_resources/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz",
+    /// )).unwrap();
+    /// // raw converted file
+    /// ctx.obs_data_mut().unwrap().header.ground_position = None;
+    /// assert!(ctx.ground_position().is_none());
+    ///
+    /// let position = ctx.bootstrap_ground_position_mut(true)
+    ///     .unwrap();
+    /// assert_eq!(ctx.ground_position(), Some(position));
+    /// assert_eq!(ctx.obs_data().unwrap().header.ground_position, Some(position));
+    /// ```
+    #[cfg(all(feature = "obs", feature = "nav"))]
+    #[cfg_attr(docrs, doc(cfg(all(feature = "obs", feature = "nav"))))]
+    pub fn bootstrap_ground_position_mut(&mut self, update_header: bool) -> Option<GroundPosition> {
+        if self.ground_position().is_none() {
+            let obs = self.obs_data()?;
+            let nav = self.nav_data()?;
+            let position = obs.approx_position(nav)?;
+            trace!("coarse position estimate: {:?}", position);
+            self.approx_position = Some(position);
+        }
+        if update_header {
+            if let Some(position) = self.approx_position {
+                if let Some(obs) = self.obs_data_mut() {
+                    obs.header.ground_position.get_or_insert(position);
+                }
+            }
+        }
+        self.ground_position()
+    }
+    /// Returns possible receiver antenna position defined in this context.
+    /// Refer to [crate::header::Header::antenna_position]: when `apply_antenna_offset` is set,
+    /// the antenna eccentricity of the Observation RINEX is applied to [Self::ground_position].
+    pub fn antenna_position(&self, apply_antenna_offset: bool) -> Option<GroundPosition> {
+        if let Some(data) = self.obs_data() {
+            if let Some(pos) = data.header.antenna_position(apply_antenna_offset) {
+                return Some(pos);
+            }
+        }
+        self.ground_position()
     }
     fn load_obs(&mut self, path: &PathBuf, rnx: &Rinex) -> Result<(), Error> {
         if let Some(obs) = &mut self.obs {
@@ -442,6 +764,18 @@ impl RnxContext {
         }
         Ok(())
     }
+    fn load_clock(&mut self, path: &Path, rnx: &Rinex) -> Result<(), Error> {
+        if let Some(clk) = &mut self.clk {
+            clk.data.merge_mut(rnx)?;
+            clk.paths.push(path.to_path_buf());
+        } else {
+            self.clk = Some(ProvidedData {
+                data: rnx.clone(),
+                paths: vec![path.to_path_buf()],
+            });
+        }
+        Ok(())
+    }
     fn load_sp3(&mut self, path: &PathBuf, sp3: &SP3) -> Result<(), Error> {
         if let Some(data) = &mut self.sp3 {
             /* extend existing context */
@@ -455,6 +789,144 @@ impl RnxContext {
         }
         Ok(())
     }
+    /*
+     * Precise SV clock offsets [s], per SV: from the Clock RINEX
+     * when provided, otherwise from the SP3 file
+     */
+    #[cfg(feature = "obs")]
+    pub(crate) fn precise_clocks(&self) -> HashMap<SV, BTreeMap<Epoch, f64>> {
+        let mut clocks = HashMap::<SV, BTreeMap<Epoch, f64>>::new();
+        if let Some(rec) = self.clk_data().and_then(|clk| clk.record.as_clock()) {
+            for (t, types) in rec {
+                if let Some(systems) = types.get(&ClockDataType::AS) {
+                    for (system, data) in systems {
+                        if let System::SV(sv) = system {
+                            clocks.entry(*sv).or_default().insert(*t, data.bias);
+                        }
+                    }
+                }
+            }
+        }
+        if clocks.is_empty() {
+            if let Some(sp3) = self.sp3_data() {
+                for (t, sv, clk) in sp3.sv_clock() {
+                    clocks.entry(sv).or_default().insert(t, clk * 1.0E-6);
+                }
+            }
+        }
+        clocks
+    }
+    /// Estimates the receiver clock offset (in seconds) for every valid
+    /// Epoch of the Observation data set, from pseudo range observations.
+    /// This is a single epoch estimate, without any filtering:
+    /// each SV in sight contributes dt_rx = (PR - rho) / c + dt_sv,
+    /// and the median of these contributions is retained,
+    /// which makes it robust against a few outliers.
+    /// SV clock offsets are picked from the Clock RINEX when provided,
+    /// otherwise from SP3, otherwise from the broadcast ephemeris.
+    /// SV positions are picked from SP3 when available,
+    /// otherwise from the broadcast ephemeris.
+    /// Atmospheric delays and relativistic effects are not compensated for,
+    /// so this is only as accurate as a few tens of nanoseconds.
+    /// When receiver clock offsets were applied (RCV CLOCK OFFS APPL),
+    /// pseudo ranges were already corrected, so the reported
+    /// offset is added back: this always estimates the actual offset.
+    /// Requires Observation data, a reference ground position and either
+    /// SP3 or Navigation data. Epochs where less than 3 SV could be used
+    /// are not reported.
+    #[cfg(all(feature = "obs", feature = "nav"))]
+    #[cfg_attr(docrs, doc(cfg(all(feature = "obs", feature = "nav"))))]
+    pub fn rx_clock_estimate(&self) -> BTreeMap<Epoch, f64> {
+        let mut ret = BTreeMap::<Epoch, f64>::new();
+        let obs = match self.obs_data() {
+            Some(obs) => obs,
+            None => return ret,
+        };
+        let (rx_x, rx_y, rx_z) = match self.ground_position() {
+            Some(pos) => pos.to_ecef_wgs84(),
+            None => return ret,
+        };
+        let sp3 = self.sp3_data();
+        let nav = self.nav_data();
+        if sp3.is_none() && nav.is_none() {
+            return ret;
+        }
+        /* gather precise clocks once, index broadcast ephemerides once */
+        let precise_clocks = self.precise_clocks();
+        let ephemerides = nav.map(EphemerisIndex::new);
+        /* SV clock offset at t, in seconds */
+        let sv_clock = |sv: SV, t: Epoch| -> Option<f64> {
+            if let Some(dt) = precise_clocks
+                .get(&sv)
+                .and_then(|clocks| interpolate(clocks, t))
+            {
+                return Some(dt);
+            }
+            if sv.constellation == Constellation::Glonass {
+                /* broadcast clock model not supported yet */
+                return None;
+            }
+            let (toe, eph) = ephemerides.as_ref()?.select(sv, t)?;
+            let dt = Ephemeris::sv_clock_corr(sv, eph.sv_clock(), t, toe);
+            Some(dt.to_seconds())
+        };
+        /* SV position at t, in meters ECEF */
+        let sv_position = |sv: SV, t: Epoch| -> Option<(f64, f64, f64)> {
+            if let Some(sp3) = sp3 {
+                if let Some((x, y, z)) = sp3.sv_position_interpolate(sv, t, 11) {
+                    return Some((x * 1.0E3, y * 1.0E3, z * 1.0E3));
+                }
+            }
+            let (toc, eph) = ephemerides.as_ref()?.select(sv, t)?;
+            let (x, y, z) = eph.sv_position_at(sv, toc, t)?;
+            Some((x * 1.0E3, y * 1.0E3, z * 1.0E3))
+        };
+
+        let offset_applied = obs.recvr_clock_offset_applied();
+        for ((t, flag), (clk, vehicles)) in obs.observation() {
+            if !flag.is_ok() {
+                continue;
+            }
+            let mut estimates = Vec::<f64>::with_capacity(vehicles.len());
+            for (sv, observations) in vehicles {
+                /* single pseudo range per SV, always the same one */
+                let pr = observations
+                    .iter()
+                    .filter(|(observable, _)| observable.is_pseudorange_observable())
+                    .min_by_key(|(observable, _)| observable.to_string())
+                    .map(|(_, data)| data.obs);
+                let pr = match pr {
+                    Some(pr) => pr,
+                    None => continue,
+                };
+                let (dt_sv, (x, y, z)) =
+                    match transmission(*t, pr, |t| sv_clock(*sv, t), |t| sv_position(*sv, t)) {
+                        Some(state) => state,
+                        None => continue,
+                    };
+                let rho = ((x - rx_x).powi(2) + (y - rx_y).powi(2) + (z - rx_z).powi(2)).sqrt();
+                let estimate = (pr - rho) / SPEED_OF_LIGHT + dt_sv;
+                if estimate.is_finite() {
+                    estimates.push(estimate);
+                }
+            }
+            if estimates.len() < 3 {
+                continue;
+            }
+            estimates.sort_by(|a, b| a.total_cmp(b));
+            let n = estimates.len();
+            let mut median = if n % 2 == 0 {
+                (estimates[n / 2 - 1] + estimates[n / 2]) / 2.0
+            } else {
+                estimates[n / 2]
+            };
+            if offset_applied {
+                median += clk.unwrap_or(0.0);
+            }
+            ret.insert(*t, median);
+        }
+        ret
+    }
     // /// Removes "incomplete" Epochs from OBS Data
     // pub fn complete_epoch_filter(&mut self, min_snr: Option<Snr>) {
     //     let total = self.primary_data().epoch().count();
@@ -647,6 +1119,22 @@ impl HtmlReport for RnxContext {
                     }
                 }
             }
+            tr {
+                td {
+                    : "Clock RINEX"
+                }
+                td {
+                    @ if self.clk_paths().is_none() {
+                        : "None"
+                    } else {
+                        @ for path in self.clk_paths().unwrap() {
+                            br {
+                                : format!("{}", path.file_name().unwrap().to_string_lossy())
+                            }
+                        }
+                    }
+                }
+            }
         }
     }
 }
@@ -0,0 +1,194 @@
+//! Earth Orientation Parameters.
+//! EOP are either parsed from IERS `finals2000A` files,
+//! or gathered from RINEX4 Navigation EOP messages.
+use crate::prelude::Epoch;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[cfg(feature = "nav")]
+use crate::navigation::EopMessage;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// EOP parsing errors
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("i/o error")]
+    IoError(#[from] std::io::Error),
+    #[error("failed to parse mjd \"{0}\"")]
+    MjdParsing(String),
+    #[error("failed to parse \"{0}\"")]
+    FieldParsing(String),
+}
+
+/// Earth Orientation Parameters, at a single Epoch
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct EopData {
+    /// Pole X coordinate (arc-sec)
+    pub x: f64,
+    /// Pole Y coordinate (arc-sec)
+    pub y: f64,
+    /// UT1 - UTC (s)
+    pub dut1: f64,
+    /// Excess of length of day (s), when known
+    pub lod: Option<f64>,
+}
+
+/// Earth Orientation Parameters time series
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Eop {
+    /// EOP, sorted by Epoch
+    pub data: BTreeMap<Epoch, EopData>,
+}
+
+/*
+ * Parses fixed width finals2000A field, None if blank
+ */
+fn finals_field(line: &str, start: usize, end: usize) -> Result<Option<f64>, Error> {
+    match line.get(start..end.min(line.len())).map(|s| s.trim()) {
+        Some(field) if !field.is_empty() => f64::from_str(field)
+            .map(Some)
+            .map_err(|_| Error::FieldParsing(field.to_string())),
+        _ => Ok(None),
+    }
+}
+
+impl Eop {
+    /// Parses IERS `finals2000A` (or `finals`) daily EOP file.
+    pub fn from_finals_file(path: &str) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(path)?;
+        Self::from_finals(&content)
+    }
+    /// Parses IERS `finals2000A` content. Bulletin A values are used.
+    /// Entries that do not have polar motion or UT1-UTC
+    /// (end of prediction) are dropped.
+    pub fn from_finals(content: &str) -> Result<Self, Error> {
+        let mut data = BTreeMap::<Epoch, EopData>::new();
+        for line in content.lines() {
+            let mjd = match line.get(7..15).map(|s| s.trim()) {
+                Some(mjd) if !mjd.is_empty() => {
+                    f64::from_str(mjd).map_err(|_| Error::MjdParsing(mjd.to_string()))?
+                },
+                _ => continue,
+            };
+            let x = finals_field(line, 18, 27)?;
+            let y = finals_field(line, 37, 46)?;
+            let dut1 = finals_field(line, 58, 68)?;
+            let lod = finals_field(line, 79, 86)?;
+            if let (Some(x), Some(y), Some(dut1)) = (x, y, dut1) {
+                data.insert(
+                    Epoch::from_mjd_utc(mjd),
+                    EopData {
+                        x,
+                        y,
+                        dut1,
+                        lod: lod.map(|ms| ms * 1.0E-3),
+                    },
+                );
+            }
+        }
+        Ok(Self { data })
+    }
+    /// Returns first Epoch described
+    pub fn first_epoch(&self) -> Option<Epoch> {
+        self.data.keys().next().copied()
+    }
+    /// Returns last Epoch described
+    pub fn last_epoch(&self) -> Option<Epoch> {
+        self.data.keys().next_back().copied()
+    }
+    /// Linear interpolation of EOP at desired Epoch.
+    /// Returns None if `t` is not within the described time frame.
+    /// LOD is only interpolated if known on both sides.
+    pub fn interpolate(&self, t: Epoch) -> Option<EopData> {
+        let (t0, before) = self.data.range(..=t).next_back()?;
+        if *t0 == t {
+            return Some(*before);
+        }
+        let (t1, after) = self.data.range(t..).next()?;
+        let dt = (t - *t0).to_seconds() / (*t1 - *t0).to_seconds();
+        let lerp = |a: f64, b: f64| a + (b - a) * dt;
+        Some(EopData {
+            x: lerp(before.x, after.x),
+            y: lerp(before.y, after.y),
+            dut1: lerp(before.dut1, after.dut1),
+            lod: match (before.lod, after.lod) {
+                (Some(lod0), Some(lod1)) => Some(lerp(lod0, lod1)),
+                _ => None,
+            },
+        })
+    }
+}
+
+#[cfg(feature = "nav")]
+#[cfg_attr(docrs, doc(cfg(feature = "nav")))]
+impl EopMessage {
+    /// Evaluates this message at given Epoch, `t0` being the message reference Epoch.
+    pub fn eop_data(&self, t0: Epoch, t: Epoch) -> EopData {
+        let dt = (t - t0).to_unit(hifitime::Unit::Day);
+        let eval = |(p, dp, ddp): (f64, f64, f64)| p + dp * dt + ddp * dt.powi(2) / 2.0;
+        EopData {
+            x: eval(self.x),
+            y: eval(self.y),
+            dut1: eval(self.delta_ut1),
+            lod: Some(-self.delta_ut1.1),
+        }
+    }
+}
+
+#[cfg(feature = "nav")]
+#[cfg_attr(docrs, doc(cfg(feature = "nav")))]
+impl<'a> FromIterator<(&'a Epoch, &'a EopMessage)> for Eop {
+    fn from_iter<I: IntoIterator<Item = (&'a Epoch, &'a EopMessage)>>(iter: I) -> Self {
+        Self {
+            data: iter
+                .into_iter()
+                .map(|(t, msg)| (*t, msg.eop_data(*t, *t)))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    const FINALS: &str = "22 6 8 59738.00 I  0.128930 0.000091  0.445690 0.000091  I-0.0339520 0.0000105 -0.1862 0.0100  I
+22 6 9 59739.00 I  0.131350 0.000091  0.445180 0.000091  I-0.0337940 0.0000105 -0.1306 0.0100  I
+22 610 59740.00 I  0.133820 0.000091  0.444610 0.000091  I-0.0336910 0.0000105 -0.0846 0.0100  I
+22 611 59741.00 P  0.136113 0.002705  0.444207 0.004134  P-0.0336337 0.0034218
+22 612 59742.00";
+    #[test]
+    fn finals2000a() {
+        let eop = Eop::from_finals(FINALS).unwrap();
+        assert_eq!(eop.data.len(), 4, "last entry has no data");
+        let t0 = Epoch::from_mjd_utc(59738.0);
+        assert_eq!(eop.first_epoch(), Some(t0));
+        assert_eq!(eop.last_epoch(), Some(Epoch::from_mjd_utc(59741.0)));
+        assert_eq!(
+            eop.data.get(&t0),
+            Some(&EopData {
+                x: 0.128930,
+                y: 0.445690,
+                dut1: -0.0339520,
+                lod: Some(-0.1862E-3),
+            })
+        );
+        let predicted = eop.data.get(&Epoch::from_mjd_utc(59741.0)).unwrap();
+        assert!(predicted.lod.is_none());
+
+        let t = Epoch::from_mjd_utc(59738.5);
+        let data = eop.interpolate(t).unwrap();
+        assert!((data.x - (0.128930 + 0.131350) / 2.0).abs() < 1.0E-9);
+        assert!((data.y - (0.445690 + 0.445180) / 2.0).abs() < 1.0E-9);
+        assert!((data.dut1 - (-0.0339520 - 0.0337940) / 2.0).abs() < 1.0E-9);
+        assert!((data.lod.unwrap() - (-0.1862E-3 - 0.1306E-3) / 2.0).abs() < 1.0E-12);
+
+        assert_eq!(eop.interpolate(t0), eop.data.get(&t0).copied());
+        assert!(eop.interpolate(Epoch::from_mjd_utc(59737.0)).is_none());
+        assert!(eop.interpolate(Epoch::from_mjd_utc(59742.0)).is_none());
+    }
+}
@@ -0,0 +1,216 @@
+//! GNSS calendar: conversions between calendar date, day of year,
+//! GPS week and day of week, and Modified Julian Day (MJD)
+use super::epoch_decompose;
+use hifitime::{Epoch, TimeScale};
+use std::str::FromStr;
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// MJD of 1970-01-01
+const MJD_UNIX: i64 = 40_587;
+
+/// MJD of the GPS week 0 origin (1980-01-06, a Sunday)
+const MJD_GPS: i64 = 44_244;
+
+#[derive(Error, Clone, Debug, PartialEq)]
+pub enum Error {
+    #[error("expecting \"YYYY-MM-DD\" date, got \"{0}\"")]
+    InvalidDate(String),
+}
+
+/// Returns true if given year is a leap year (gregorian calendar)
+pub fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+/// Number of days in given month of given year
+pub fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// [`CalendarDate`] is a day of the gregorian calendar,
+/// as used by GNSS products naming conventions: day of year,
+/// GPS week and day of week, or Modified Julian Day.
+/// ```
+/// use rinex::epoch::calendar::CalendarDate;
+/// use std::str::FromStr;
+/// let date = CalendarDate::from_str("2022-03-04")
+///     .unwrap();
+/// assert_eq!(date.day_of_year(), 63);
+/// assert_eq!(date.gps_week(), Some((2199, 5)));
+/// assert_eq!(date.mjd(), 59642);
+/// assert_eq!(CalendarDate::from_gps_week(2199, 5), Some(date));
+/// assert_eq!(CalendarDate::from_day_of_year(2022, 63), Some(date));
+/// assert_eq!(CalendarDate::from_mjd(59642), date);
+/// assert_eq!(date.to_string(), "2022-03-04");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CalendarDate {
+    /// Year
+    pub year: i32,
+    /// Month (1-12)
+    pub month: u8,
+    /// Day of month (1-31)
+    pub day: u8,
+}
+
+impl CalendarDate {
+    /// Builds a [`CalendarDate`], returns None if it does not exist
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        if (1..=12).contains(&month) && day > 0 && day <= days_in_month(year, month) {
+            Some(Self { year, month, day })
+        } else {
+            None
+        }
+    }
+    /// Calendar day of given [`Epoch`], expressed in its own timescale
+    pub fn from_epoch(epoch: Epoch) -> Self {
+        let (year, month, day, _, _, _, _) = epoch_decompose(epoch);
+        Self { year, month, day }
+    }
+    /// Returns midnight of this day, in given timescale
+    pub fn to_epoch(&self, ts: TimeScale) -> Epoch {
+        Epoch::from_gregorian(self.year, self.month, self.day, 0, 0, 0, 0, ts)
+    }
+    /// Day of year (1-366)
+    pub fn day_of_year(&self) -> u32 {
+        (1..self.month)
+            .map(|month| days_in_month(self.year, month) as u32)
+            .sum::<u32>()
+            + self.day as u32
+    }
+    /// Builds a [`CalendarDate`] from a day of year (1-366)
+    pub fn from_day_of_year(year: i32, doy: u32) -> Option<Self> {
+        let mut day = doy;
+        for month in 1..=12 {
+            let days = days_in_month(year, month) as u32;
+            if day == 0 {
+                return None;
+            } else if day <= days {
+                return Some(Self {
+                    year,
+                    month,
+                    day: day as u8,
+                });
+            }
+            day -= days;
+        }
+        None
+    }
+    /// Modified Julian Day
+    pub fn mjd(&self) -> i64 {
+        // days from civil, shifted to the march based year
+        let (month, day) = (self.month as i64, self.day as i64);
+        let year = self.year as i64 - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year - era * 400;
+        let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468 + MJD_UNIX
+    }
+    /// Builds a [`CalendarDate`] from a Modified Julian Day
+    pub fn from_mjd(mjd: i64) -> Self {
+        // civil from days, march based year
+        let days = mjd - MJD_UNIX + 719_468;
+        let era = days.div_euclid(146_097);
+        let doe = days - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+        Self { year, month, day }
+    }
+    /// GPS week and day of week (0: Sunday, 6: Saturday).
+    /// Returns None prior the GPS week 0 origin (1980-01-06).
+    pub fn gps_week(&self) -> Option<(u32, u8)> {
+        let days = self.mjd() - MJD_GPS;
+        if days < 0 {
+            None
+        } else {
+            Some(((days / 7) as u32, (days % 7) as u8))
+        }
+    }
+    /// Builds a [`CalendarDate`] from a GPS week and day of week (0: Sunday, 6: Saturday)
+    pub fn from_gps_week(week: u32, day: u8) -> Option<Self> {
+        if day < 7 {
+            Some(Self::from_mjd(MJD_GPS + week as i64 * 7 + day as i64))
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for CalendarDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for CalendarDate {
+    type Err = Error;
+    /// Parses "YYYY-MM-DD"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidDate(s.to_string());
+        let items: Vec<&str> = s.trim().split('-').collect();
+        if items.len() != 3 {
+            return Err(invalid());
+        }
+        let year = i32::from_str(items[0]).map_err(|_| invalid())?;
+        let month = u8::from_str(items[1]).map_err(|_| invalid())?;
+        let day = u8::from_str(items[2]).map_err(|_| invalid())?;
+        Self::new(year, month, day).ok_or_else(invalid)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn calendar_conversions() {
+        for (date, doy, week, mjd) in [
+            ("1980-01-06", 6, Some((0, 0)), 44244),
+            ("1999-08-21", 233, Some((1023, 6)), 51411),
+            ("1999-08-22", 234, Some((1024, 0)), 51412),
+            ("2000-02-29", 60, Some((1051, 2)), 51603),
+            ("2000-12-31", 366, Some((1095, 0)), 51909),
+            ("2019-04-07", 97, Some((2048, 0)), 58580),
+            ("2021-12-21", 355, Some((2189, 2)), 59569),
+            ("1979-12-31", 365, None, 44238),
+        ] {
+            let date = CalendarDate::from_str(date).unwrap();
+            assert_eq!(date.day_of_year(), doy, "{}", date);
+            assert_eq!(date.gps_week(), week, "{}", date);
+            assert_eq!(date.mjd(), mjd, "{}", date);
+            assert_eq!(CalendarDate::from_day_of_year(date.year, doy), Some(date));
+            assert_eq!(CalendarDate::from_mjd(mjd), date);
+            if let Some((week, day)) = week {
+                assert_eq!(CalendarDate::from_gps_week(week, day), Some(date));
+            }
+            for ts in [TimeScale::UTC, TimeScale::GPST, TimeScale::GST] {
+                let t = date.to_epoch(ts);
+                assert_eq!(t.time_scale, ts);
+                assert_eq!(CalendarDate::from_epoch(t), date, "{}", ts);
+            }
+        }
+        assert!(CalendarDate::from_day_of_year(2021, 366).is_none());
+        assert!(CalendarDate::from_day_of_year(2021, 0).is_none());
+        assert!(CalendarDate::from_gps_week(2000, 7).is_none());
+        assert!(CalendarDate::from_str("2021-02-29").is_err());
+        assert!(CalendarDate::from_str("2021-13-01").is_err());
+        assert!(CalendarDate::from_str("2021/01/01").is_err());
+
+        // late in the day: still the same day of year
+        let t = Epoch::from_str("2022-03-04T23:59:30 GPST").unwrap();
+        assert_eq!(CalendarDate::from_epoch(t).day_of_year(), 63);
+    }
+}
@@ -42,6 +42,17 @@ impl EpochFlag {
     pub fn is_ok(self) -> bool {
         self == Self::Ok
     }
+    /// Returns true if this flag marks an event epoch (flags 2 to 5),
+    /// that is followed by special records instead of observations
+    pub fn is_event(self) -> bool {
+        matches!(
+            self,
+            Self::AntennaBeingMoved
+                | Self::NewSiteOccupation
+                | Self::HeaderInformationFollows
+                | Self::ExternalEvent
+        )
+    }
 }
 
 impl FromStr for EpochFlag {
@@ -111,4 +122,14 @@ mod test {
         assert_eq!(format!("{}", EpochFlag::ExternalEvent), "5");
         assert_eq!(format!("{}", EpochFlag::CycleSlip), "6");
     }
+    #[test]
+    fn events() {
+        assert!(!EpochFlag::Ok.is_event());
+        assert!(!EpochFlag::PowerFailure.is_event());
+        assert!(EpochFlag::AntennaBeingMoved.is_event());
+        assert!(EpochFlag::NewSiteOccupation.is_event());
+        assert!(EpochFlag::HeaderInformationFollows.is_event());
+        assert!(EpochFlag::ExternalEvent.is_event());
+        assert!(!EpochFlag::CycleSlip.is_event());
+    }
 }
@@ -1,11 +1,14 @@
 use crate::types::Type;
-use hifitime::{Duration, Epoch, TimeScale, Unit};
+use hifitime::{Duration, Epoch, TimeScale};
 use std::str::FromStr;
 use thiserror::Error;
 
 pub mod flag;
 pub use flag::EpochFlag;
 
+pub mod calendar;
+pub use calendar::CalendarDate;
+
 #[derive(Error, Debug)]
 pub enum ParsingError {
     #[error("failed to parse epoch flag")]
@@ -43,13 +46,7 @@ pub(crate) fn now() -> Epoch {
  * Formats given epoch to string, matching standard specifications
  */
 pub(crate) fn format(epoch: Epoch, flag: Option<EpochFlag>, t: Type, revision: u8) -> String {
-    // Hifitime V3 does not have a gregorian decomposition method
-    let (y, m, d, hh, mm, ss, nanos) = match epoch.time_scale {
-        TimeScale::GPST => (epoch + Duration::from_seconds(37.0)).to_gregorian_utc(),
-        TimeScale::GST => (epoch + Duration::from_seconds(19.0)).to_gregorian_utc(),
-        TimeScale::BDT => (epoch + Duration::from_seconds(19.0)).to_gregorian_utc(),
-        _ => epoch.to_gregorian_utc(),
-    };
+    let (y, m, d, hh, mm, ss, nanos) = epoch_decompose(epoch);
 
     match t {
         Type::ObservationData => {
@@ -181,24 +178,20 @@ pub(crate) fn parse_in_timescale(
             },
             5 => {
                 if let Some(dot) = item.find('.') {
-                    let is_nav = item.trim().len() < 7;
-
                     ss = item[..dot]
                         .trim()
                         .parse::<u8>()
                         .map_err(|_| ParsingError::SecondsField(item.to_string()))?;
 
-                    ns = item[dot + 1..]
-                        .trim()
-                        .parse::<u32>()
-                        .map_err(|_| ParsingError::NanosecondsField(item.to_string()))?;
-
-                    if is_nav {
-                        // NAV RINEX : 100ms precision
-                        ns *= 100_000_000;
-                    } else {
-                        // OBS RINEX : 100ns precision
-                        ns *= 100;
+                    // NAV RINEX : 100ms precision, OBS RINEX : 100ns precision,
+                    // sub-nanosecond digits are dropped
+                    let digits = item[dot + 1..].trim();
+                    let digits = &digits[..std::cmp::min(digits.len(), 9)];
+                    if !digits.is_empty() {
+                        ns = digits
+                            .parse::<u32>()
+                            .map_err(|_| ParsingError::NanosecondsField(item.to_string()))?;
+                        ns *= 10_u32.pow(9 - digits.len() as u32);
                     }
                 } else {
                     ss = item
@@ -225,7 +218,8 @@ pub(crate) fn parse_in_timescale(
                 return Err(ParsingError::FormatError);
             }
 
-            let epoch = Epoch::from_gregorian_utc(y, m, d, hh, mm, ss, ns);
+            let epoch = Epoch::maybe_from_gregorian_utc(y, m, d, hh, mm, ss, ns)
+                .map_err(|_| ParsingError::FormatError)?;
             Ok((epoch, flag))
         },
         _ => {
@@ -236,14 +230,7 @@ pub(crate) fn parse_in_timescale(
             }
             let epoch = Epoch::from_str(&format!(
                 "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:09} {}",
-                y,
-                m,
-                d,
-                hh,
-                mm,
-                ss,
-                ns / 100_000_000,
-                ts
+                y, m, d, hh, mm, ss, ns, ts
             ))?;
             Ok((epoch, flag))
         },
@@ -254,18 +241,43 @@ pub(crate) fn parse_utc(s: &str) -> Result<(Epoch, EpochFlag), ParsingError> {
     parse_in_timescale(s, TimeScale::UTC)
 }
 
+/// Gregorian decomposition of given epoch, in its own timescale:
+/// (year, month, day, hours, minutes, seconds, nanoseconds)
+pub fn epoch_decompose(e: Epoch) -> (i32, u8, u8, u8, u8, u8, u32) {
+    // Hifitime V3 does not have a gregorian decomposition method,
+    // but it describes GNSS timescales on the TAI gregorian calendar
+    if e.time_scale.is_gnss() {
+        e.to_gregorian_tai()
+    } else {
+        e.to_gregorian_utc()
+    }
+}
+
 /*
- * Until Hifitime provides a decomposition method in timescale other than UTC
- * we have this tweak to decompose %Y %M %D %HH %MM %SS %NS
+ * Aligns two sorted epoch series: returns (lhs, rhs) pairs of epochs
+ * that are within tolerance of one another. Each epoch is matched at most once,
+ * to its nearest counterpart, so slightly offset samplings do not get paired twice.
  */
-pub(crate) fn epoch_decompose(e: Epoch) -> (i32, u8, u8, u8, u8, u8, u32) {
-    let ts = e.time_scale;
-    let offset = if ts.is_gnss() {
-        37 * Unit::Second
-    } else {
-        Duration::ZERO
-    };
-    (e + offset).to_gregorian_utc()
+pub(crate) fn intersect(lhs: &[Epoch], rhs: &[Epoch], tolerance: Duration) -> Vec<(Epoch, Epoch)> {
+    let mut ret = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < lhs.len() && j < rhs.len() {
+        let dt = lhs[i] - rhs[j];
+        if dt < -tolerance {
+            i += 1;
+        } else if dt > tolerance {
+            j += 1;
+        } else if i + 1 < lhs.len() && (lhs[i + 1] - rhs[j]).abs() < dt.abs() {
+            i += 1; // next lhs is a better match
+        } else if j + 1 < rhs.len() && (lhs[i] - rhs[j + 1]).abs() < dt.abs() {
+            j += 1; // next rhs is a better match
+        } else {
+            ret.push((lhs[i], rhs[j]));
+            i += 1;
+            j += 1;
+        }
+    }
+    ret
 }
 
 #[cfg(test)]
@@ -275,6 +287,35 @@ mod test {
     use hifitime::TimeScale;
     use std::str::FromStr;
     #[test]
+    fn epoch_intersection() {
+        let t0 = Epoch::from_str("2023-01-01T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(30.0);
+        let lhs: Vec<Epoch> = (0..10).map(|i| t0 + dt * i as f64).collect();
+        // offset sampling, missing epoch, and a foreign one
+        let offset = Duration::from_milliseconds(20.0);
+        let mut rhs: Vec<Epoch> = (0..10)
+            .filter(|i| *i != 5)
+            .map(|i| t0 + dt * i as f64 + offset)
+            .collect();
+        rhs.insert(3, t0 + dt * 2.0 + Duration::from_seconds(10.0));
+
+        let aligned = intersect(&lhs, &rhs, Duration::ZERO);
+        assert!(aligned.is_empty());
+
+        let aligned = intersect(&lhs, &rhs, Duration::from_milliseconds(50.0));
+        assert_eq!(aligned.len(), 9);
+        for (lhs, rhs) in &aligned {
+            assert_eq!(*rhs - *lhs, offset);
+        }
+        assert!(!aligned.iter().any(|(lhs, _)| *lhs == t0 + dt * 5.0));
+
+        // loose tolerance: nearest match wins
+        let aligned = intersect(&lhs, &rhs, Duration::from_seconds(12.0));
+        assert_eq!(aligned.len(), 9);
+        assert_eq!(aligned[2], (t0 + dt * 2.0, t0 + dt * 2.0 + offset));
+        assert_eq!(aligned[3], (t0 + dt * 3.0, t0 + dt * 3.0 + offset));
+    }
+    #[test]
     fn epoch_parse_nav_v2() {
         let e = parse_utc("20 12 31 23 45  0.0");
         assert!(e.is_ok());
@@ -582,6 +623,34 @@ mod test {
         //assert_eq!(format!("{}", e), "2022 01 09 00 00  8.7654321  0");
     }
     #[test]
+    fn epoch_high_rate() {
+        // 10 Hz sampling, in GNSS timescales
+        for ts in [TimeScale::UTC, TimeScale::GPST, TimeScale::GST] {
+            let t0 = parse_in_timescale("2022 01 09 00 00  0.0000000  0", ts)
+                .unwrap()
+                .0;
+            for k in 1..20 {
+                let content = format!(
+                    "2022 01 09 00 00 {:>2}.{:07}  0",
+                    k / 10,
+                    (k % 10) * 1_000_000
+                );
+                let (e, _) = parse_in_timescale(&content, ts).unwrap();
+                assert_eq!(e.time_scale, ts);
+                assert_eq!(e - t0, Duration::from_milliseconds(100.0 * k as f64));
+                assert_eq!(format(e, None, Type::ObservationData, 3), content);
+            }
+        }
+        let (e, _) = parse_in_timescale(" 22  1  9  0  0  0.0500000  0", TimeScale::GPST).unwrap();
+        assert_eq!(
+            format(e, None, Type::ObservationData, 2),
+            "22  1  9  0  0  0.0500000  0"
+        );
+        // digits beyond the nanosecond are dropped
+        let (e, _) = parse_utc("2022 01 09 00 00  0.1234567891  0").unwrap();
+        assert_eq!(e.to_gregorian_utc().6, 123_456_789);
+    }
+    #[test]
     fn epoch_parse_meteo_v2() {
         let e = parse_utc(" 22  1  4  0  0  0  ");
         assert!(e.is_ok());
@@ -0,0 +1,118 @@
+//! (Extended) Kalman filter
+use super::{Error, Matrix};
+
+/// Sequential (extended) Kalman filter, for static or slowly varying states:
+/// the transition is the identity, and each state follows a random walk
+/// of given process noise. States may be added or removed between epochs,
+/// which is typical of carrier phase ambiguities.
+/// ```
+/// use rinex::estimation::{Kalman, Matrix};
+/// // constant, measured 4 times
+/// let mut kf = Kalman::new(vec![0.0], &[100.0], vec![0.0])
+///     .unwrap();
+/// for y in [10.2, 9.8, 10.1, 9.9] {
+///     let h = Matrix::from_rows(&[vec![1.0]])
+///         .unwrap();
+///     let innovation = y - kf.x[0];
+///     kf.update(&h, &[innovation], &Matrix::from_diagonal(&[0.01])).unwrap();
+/// }
+/// assert!((kf.x[0] - 10.0).abs() < 1.0E-2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Kalman {
+    /// State vector
+    pub x: Vec<f64>,
+    /// State covariance
+    pub p: Matrix,
+    /// Process noise, per state [unit²/s]
+    pub q: Vec<f64>,
+}
+
+impl Kalman {
+    /// Creates a new filter, from initial states, their (uncorrelated)
+    /// initial variances and their process noise
+    pub fn new(x: Vec<f64>, variances: &[f64], q: Vec<f64>) -> Result<Self, Error> {
+        if x.len() != variances.len() || x.len() != q.len() {
+            return Err(Error::DimensionMismatch);
+        }
+        Ok(Self {
+            x,
+            p: Matrix::from_diagonal(variances),
+            q,
+        })
+    }
+    /// Number of states
+    pub fn len(&self) -> usize {
+        self.x.len()
+    }
+    /// Returns true if this filter has no state
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty()
+    }
+    /// Time update: propagates the covariance over dt [s]
+    pub fn predict(&mut self, dt: f64) {
+        for (i, q) in self.q.iter().enumerate() {
+            self.p[(i, i)] += q * dt.abs();
+        }
+    }
+    /// Appends a new state, uncorrelated to others
+    pub fn push_state(&mut self, x: f64, variance: f64, q: f64) {
+        self.x.push(x);
+        self.q.push(q);
+        self.p.push_state(variance);
+    }
+    /// Removes given state
+    pub fn remove_state(&mut self, k: usize) -> Result<(), Error> {
+        self.p.remove_state(k)?;
+        self.x.remove(k);
+        self.q.remove(k);
+        Ok(())
+    }
+    /// Resets given state: new value, uncorrelated to others, with given variance
+    pub fn reset_state(&mut self, k: usize, x: f64, variance: f64) {
+        self.x[k] = x;
+        self.p.reset_state(k, variance);
+    }
+    /// Kalman gain, for given design matrix H and measurement covariance R
+    pub fn gain(&self, h: &Matrix, r: &Matrix) -> Result<Matrix, Error> {
+        if h.cols() != self.len() || r.rows() != h.rows() || r.cols() != h.rows() {
+            return Err(Error::DimensionMismatch);
+        }
+        let pht = self.p.mul(&h.transpose())?;
+        let s = h.mul(&pht)?.add(r)?;
+        pht.mul(&s.inverse().ok_or(Error::SingularMatrix)?)
+    }
+    /// Commits a new state estimate, obtained with given gain,
+    /// and updates the covariance (Joseph form, which preserves symmetry
+    /// and positiveness). This is used by iterated filters, which
+    /// relinearize H around the current estimate.
+    pub fn correct(
+        &mut self,
+        x: Vec<f64>,
+        gain: &Matrix,
+        h: &Matrix,
+        r: &Matrix,
+    ) -> Result<(), Error> {
+        if x.len() != self.len() {
+            return Err(Error::DimensionMismatch);
+        }
+        let ikh = Matrix::identity(self.len()).sub(&gain.mul(h)?)?;
+        self.p = ikh
+            .mul(&self.p)?
+            .mul(&ikh.transpose())?
+            .add(&gain.mul(r)?.mul(&gain.transpose())?)?;
+        self.x = x;
+        Ok(())
+    }
+    /// Measurement update, from innovations y - h(x),
+    /// design matrix H and measurement covariance R
+    pub fn update(&mut self, h: &Matrix, innovations: &[f64], r: &Matrix) -> Result<(), Error> {
+        if innovations.len() != h.rows() {
+            return Err(Error::DimensionMismatch);
+        }
+        let gain = self.gain(h, r)?;
+        let dx = gain.mul_vec(innovations)?;
+        let x = self.x.iter().zip(dx.iter()).map(|(x, dx)| x + dx).collect();
+        self.correct(x, &gain, h, r)
+    }
+}
@@ -0,0 +1,61 @@
+//! Weighted least squares
+use super::{Error, Matrix};
+
+/// [weighted_least_squares] solution
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeastSquares {
+    /// Estimated parameters
+    pub x: Vec<f64>,
+    /// Parameters covariance: (Hᵀ W H)⁻¹
+    pub covariance: Matrix,
+    /// Post fit residuals: y - H x
+    pub residuals: Vec<f64>,
+}
+
+/// Solves y = H x in the weighted least squares sense:
+/// x = (Hᵀ W H)⁻¹ Hᵀ W y, where W = diag(weights).
+/// Weights are typically the inverse of the measurement variances.
+/// ```
+/// use rinex::estimation::{weighted_least_squares, Matrix};
+/// // y = 1 + 2t
+/// let h = Matrix::from_rows(&[
+///     vec![1.0, 0.0],
+///     vec![1.0, 1.0],
+///     vec![1.0, 2.0],
+/// ])
+/// .unwrap();
+/// let solution = weighted_least_squares(&h, &[1.0, 3.0, 5.0], &[1.0, 1.0, 1.0])
+///     .unwrap();
+/// assert!((solution.x[0] - 1.0).abs() < 1.0E-9);
+/// assert!((solution.x[1] - 2.0).abs() < 1.0E-9);
+/// ```
+pub fn weighted_least_squares(
+    h: &Matrix,
+    y: &[f64],
+    weights: &[f64],
+) -> Result<LeastSquares, Error> {
+    if y.len() != h.rows() || weights.len() != h.rows() {
+        return Err(Error::DimensionMismatch);
+    }
+    if h.rows() < h.cols() {
+        return Err(Error::NotEnoughMeasurements);
+    }
+    let mut htw = h.transpose();
+    for i in 0..htw.rows() {
+        for (j, w) in weights.iter().enumerate() {
+            htw[(i, j)] *= w;
+        }
+    }
+    let covariance = htw.mul(h)?.inverse().ok_or(Error::SingularMatrix)?;
+    let x = covariance.mul_vec(&htw.mul_vec(y)?)?;
+    let residuals = y
+        .iter()
+        .zip(h.mul_vec(&x)?)
+        .map(|(y, fit)| y - fit)
+        .collect();
+    Ok(LeastSquares {
+        x,
+        covariance,
+        residuals,
+    })
+}
@@ -0,0 +1,256 @@
+//! Dense linear algebra, dimensioned for our estimators (a few tens of states)
+use super::Error;
+use std::ops::{Index, IndexMut};
+
+/// Row major, dense matrix
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<f64>,
+}
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = f64;
+    fn index(&self, (i, j): (usize, usize)) -> &f64 {
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut f64 {
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+impl Matrix {
+    /// Null matrix
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            data: vec![0.0; rows * cols],
+        }
+    }
+    /// Identity matrix
+    pub fn identity(n: usize) -> Self {
+        Self::from_diagonal(&vec![1.0; n])
+    }
+    /// Square, diagonal matrix
+    pub fn from_diagonal(diagonal: &[f64]) -> Self {
+        let n = diagonal.len();
+        let mut m = Self::zeros(n, n);
+        for (i, value) in diagonal.iter().enumerate() {
+            m[(i, i)] = *value;
+        }
+        m
+    }
+    /// Builds a matrix from its rows, which must have the same length
+    pub fn from_rows(rows: &[Vec<f64>]) -> Result<Self, Error> {
+        let cols = rows.first().map(|row| row.len()).unwrap_or(0);
+        let mut data = Vec::with_capacity(rows.len() * cols);
+        for row in rows {
+            if row.len() != cols {
+                return Err(Error::DimensionMismatch);
+            }
+            data.extend_from_slice(row);
+        }
+        Ok(Self {
+            rows: rows.len(),
+            cols,
+            data,
+        })
+    }
+    /// Number of rows
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+    /// Number of columns
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+    /// Diagonal terms
+    pub fn diagonal(&self) -> Vec<f64> {
+        (0..self.rows.min(self.cols))
+            .map(|i| self[(i, i)])
+            .collect()
+    }
+    /// Transposed matrix
+    pub fn transpose(&self) -> Self {
+        let mut t = Self::zeros(self.cols, self.rows);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                t[(j, i)] = self[(i, j)];
+            }
+        }
+        t
+    }
+    /// Matrix product
+    pub fn mul(&self, rhs: &Self) -> Result<Self, Error> {
+        if self.cols != rhs.rows {
+            return Err(Error::DimensionMismatch);
+        }
+        let mut m = Self::zeros(self.rows, rhs.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = self[(i, k)];
+                if a == 0.0 {
+                    continue;
+                }
+                for j in 0..rhs.cols {
+                    m[(i, j)] += a * rhs[(k, j)];
+                }
+            }
+        }
+        Ok(m)
+    }
+    /// Matrix sum
+    pub fn add(&self, rhs: &Self) -> Result<Self, Error> {
+        if (self.rows, self.cols) != (rhs.rows, rhs.cols) {
+            return Err(Error::DimensionMismatch);
+        }
+        let mut m = self.clone();
+        for (a, b) in m.data.iter_mut().zip(rhs.data.iter()) {
+            *a += b;
+        }
+        Ok(m)
+    }
+    /// Matrix difference
+    pub fn sub(&self, rhs: &Self) -> Result<Self, Error> {
+        if (self.rows, self.cols) != (rhs.rows, rhs.cols) {
+            return Err(Error::DimensionMismatch);
+        }
+        let mut m = self.clone();
+        for (a, b) in m.data.iter_mut().zip(rhs.data.iter()) {
+            *a -= b;
+        }
+        Ok(m)
+    }
+    /// Matrix vector product
+    pub fn mul_vec(&self, v: &[f64]) -> Result<Vec<f64>, Error> {
+        if self.cols != v.len() {
+            return Err(Error::DimensionMismatch);
+        }
+        Ok((0..self.rows)
+            .map(|i| (0..self.cols).map(|j| self[(i, j)] * v[j]).sum())
+            .collect())
+    }
+    /// Inverse of a square matrix, by Gauss-Jordan elimination with partial pivoting.
+    /// Returns None for singular (or numerically singular) matrices.
+    #[cfg(not(feature = "nalgebra"))]
+    pub fn inverse(&self) -> Option<Self> {
+        let n = self.rows;
+        if n != self.cols {
+            return None;
+        }
+        let scale = self.scale();
+        let mut a = self.clone();
+        let mut inv = Self::identity(n);
+        for col in 0..n {
+            let pivot =
+                (col..n).max_by(|i, j| a[(*i, col)].abs().total_cmp(&a[(*j, col)].abs()))?;
+            if a[(pivot, col)].abs() <= 1.0E-12 * scale {
+                return None;
+            }
+            if pivot != col {
+                for j in 0..n {
+                    a.data.swap(pivot * n + j, col * n + j);
+                    inv.data.swap(pivot * n + j, col * n + j);
+                }
+            }
+            let d = a[(col, col)];
+            for j in 0..n {
+                a[(col, j)] /= d;
+                inv[(col, j)] /= d;
+            }
+            for i in 0..n {
+                if i == col {
+                    continue;
+                }
+                let f = a[(i, col)];
+                if f == 0.0 {
+                    continue;
+                }
+                for j in 0..n {
+                    a[(i, j)] -= f * a[(col, j)];
+                    inv[(i, j)] -= f * inv[(col, j)];
+                }
+            }
+        }
+        Some(inv)
+    }
+    /// Inverse of a square matrix (LU decomposition, by nalgebra).
+    /// Returns None for singular matrices.
+    #[cfg(feature = "nalgebra")]
+    pub fn inverse(&self) -> Option<Self> {
+        if self.rows != self.cols {
+            return None;
+        }
+        let scale = self.scale();
+        let lu = nalgebra::DMatrix::<f64>::from(self.clone()).lu();
+        if lu.u().diagonal().iter().any(|d| d.abs() <= 1.0E-12 * scale) {
+            return None;
+        }
+        lu.try_inverse().map(Self::from)
+    }
+    /*
+     * Largest absolute value, to detect numerically singular matrices
+     */
+    fn scale(&self) -> f64 {
+        self.data.iter().fold(0.0_f64, |max, a| max.max(a.abs()))
+    }
+    /// Appends a new (square matrix) state, uncorrelated to others
+    pub fn push_state(&mut self, variance: f64) {
+        let n = self.rows;
+        let mut m = Self::zeros(n + 1, n + 1);
+        for i in 0..n {
+            for j in 0..n {
+                m[(i, j)] = self[(i, j)];
+            }
+        }
+        m[(n, n)] = variance;
+        *self = m;
+    }
+    /// Removes given (square matrix) state
+    pub fn remove_state(&mut self, k: usize) -> Result<(), Error> {
+        let n = self.rows;
+        if k >= n {
+            return Err(Error::InvalidState(k));
+        }
+        let mut m = Self::zeros(n - 1, n - 1);
+        for (ii, i) in (0..n).filter(|i| *i != k).enumerate() {
+            for (jj, j) in (0..n).filter(|j| *j != k).enumerate() {
+                m[(ii, jj)] = self[(i, j)];
+            }
+        }
+        *self = m;
+        Ok(())
+    }
+    /// Resets given (square matrix) state: uncorrelated, with given variance
+    pub fn reset_state(&mut self, k: usize, variance: f64) {
+        for i in 0..self.rows {
+            self[(i, k)] = 0.0;
+            self[(k, i)] = 0.0;
+        }
+        self[(k, k)] = variance;
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<Matrix> for nalgebra::DMatrix<f64> {
+    fn from(m: Matrix) -> Self {
+        Self::from_row_slice(m.rows, m.cols, &m.data)
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<nalgebra::DMatrix<f64>> for Matrix {
+    fn from(m: nalgebra::DMatrix<f64>) -> Self {
+        let (rows, cols) = m.shape();
+        Self {
+            rows,
+            cols,
+            data: m.transpose().as_slice().to_vec(),
+        }
+    }
+}
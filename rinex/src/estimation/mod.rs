@@ -0,0 +1,27 @@
+//! Estimation utilities: weighted least squares and (extended) Kalman filter,
+//! shared by the positioning and clock estimation features.
+//! Linear algebra is implemented internally, for the small dimensions we deal with.
+//! Enable the `nalgebra` feature to convert [Matrix] from/to `nalgebra::DMatrix`,
+//! and to rely on nalgebra for matrix inversion.
+use thiserror::Error;
+
+mod kalman;
+mod lsq;
+mod matrix;
+
+pub use kalman::Kalman;
+pub use lsq::{weighted_least_squares, LeastSquares};
+pub use matrix::Matrix;
+
+/// Estimation errors
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum Error {
+    #[error("singular matrix")]
+    SingularMatrix,
+    #[error("not enough measurements")]
+    NotEnoughMeasurements,
+    #[error("dimensions mismatch")]
+    DimensionMismatch,
+    #[error("state #{0} does not exist")]
+    InvalidState(usize),
+}
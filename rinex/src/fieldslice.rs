@@ -0,0 +1,119 @@
+//! Fixed width field access, following RINEX (FORTRAN) conventions.
+//!
+//! RINEX content is described in fixed width columns, but producers
+//! usually omit trailing blanks and some files contain non ASCII characters.
+//! Direct slicing (`&line[a..b]`) panics in both cases.
+//! These helpers never panic: columns that lie beyond the end of a line
+//! are considered blank, and columns that would split a multibyte character
+//! are reported as an [Error]:
+//! ```
+//! use rinex::fieldslice;
+//!
+//! let line = "G01  20243517.560 6";
+//! assert_eq!(fieldslice::field(line, 0, 3), Ok("G01"));
+//! // trimmed line: missing columns are blank
+//! assert_eq!(fieldslice::field(line, 17, 19), Ok(" 6"));
+//! assert_eq!(fieldslice::field(line, 40, 56), Ok(""));
+//! assert_eq!(fieldslice::split_at(line, 3), Ok(("G01", "  20243517.560 6")));
+//! // multibyte characters
+//! assert!(fieldslice::field("é", 0, 1).is_err());
+//! // field must be fully described
+//! assert!(fieldslice::exact(line, 17, 20).is_err());
+//! ```
+use thiserror::Error;
+
+/// Fixed width field access error
+#[derive(Error, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// Field boundaries split a multibyte character
+    #[error("columns {start}..{end} split a multibyte character")]
+    CharBoundary { start: usize, end: usize },
+    /// Line does not describe the entire field
+    #[error("columns {start}..{end} exceed line length ({len})")]
+    Truncated {
+        start: usize,
+        end: usize,
+        len: usize,
+    },
+}
+
+/// Returns columns `start..end` of given line.
+/// Columns beyond the end of the line are considered blank,
+/// the returned field is then shorter than requested (possibly empty).
+pub fn field(line: &str, start: usize, end: usize) -> Result<&str, Error> {
+    let len = line.len();
+    let (a, b) = (start.min(len), end.max(start).min(len));
+    line.get(a..b).ok_or(Error::CharBoundary { start, end })
+}
+
+/// Returns columns `start..end` of given line,
+/// which must be entirely described.
+pub fn exact(line: &str, start: usize, end: usize) -> Result<&str, Error> {
+    if end > line.len() {
+        return Err(Error::Truncated {
+            start,
+            end,
+            len: line.len(),
+        });
+    }
+    field(line, start, end)
+}
+
+/// Returns columns following `start` of given line,
+/// empty if line is shorter.
+pub fn remainder(line: &str, start: usize) -> Result<&str, Error> {
+    field(line, start, line.len())
+}
+
+/// Splits given line at column `offset`.
+/// If the line is shorter, the second part is empty.
+pub fn split_at(line: &str, offset: usize) -> Result<(&str, &str), Error> {
+    let offset = offset.min(line.len());
+    if line.is_char_boundary(offset) {
+        Ok(line.split_at(offset))
+    } else {
+        Err(Error::CharBoundary {
+            start: offset,
+            end: offset,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn fields() {
+        let line = "  20243517.560 6  21208865.370 7";
+        assert_eq!(field(line, 0, 14), Ok("  20243517.560"));
+        assert_eq!(field(line, 14, 16), Ok(" 6"));
+        assert_eq!(field(line, 30, 48), Ok(" 7"));
+        assert_eq!(field(line, 32, 48), Ok(""));
+        assert_eq!(field(line, 48, 64), Ok(""));
+        assert_eq!(field(line, 10, 2), Ok(""));
+        assert_eq!(exact(line, 16, 32), Ok("  21208865.370 7"));
+        assert_eq!(
+            exact(line, 16, 33),
+            Err(Error::Truncated {
+                start: 16,
+                end: 33,
+                len: 32
+            })
+        );
+        assert_eq!(remainder(line, 30), Ok(" 7"));
+        assert_eq!(remainder(line, 40), Ok(""));
+    }
+    #[test]
+    fn multibyte() {
+        let line = "Zürich";
+        assert_eq!(field(line, 0, 1), Ok("Z"));
+        assert_eq!(
+            field(line, 0, 2),
+            Err(Error::CharBoundary { start: 0, end: 2 })
+        );
+        assert_eq!(field(line, 0, 3), Ok("Zü"));
+        assert!(split_at(line, 2).is_err());
+        assert_eq!(split_at(line, 3), Ok(("Zü", "rich")));
+        assert_eq!(split_at(line, 10), Ok(("Zürich", "")));
+    }
+}
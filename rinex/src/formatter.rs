@@ -0,0 +1,558 @@
+//! Numeric formatting, following RINEX (FORTRAN) conventions.
+//!
+//! RINEX producers are expected to format floating point numbers
+//! on fixed width fields: F14.3 for observations, D19.12 for
+//! Navigation and Clock data. The FORTRAN D exponent is used by
+//! old revisions, where modern revisions use E:
+//! ```
+//! use rinex::formatter::{Exponent, FloatFormat};
+//!
+//! let fmt = FloatFormat::scientific(19, 12, Exponent::D);
+//! assert_eq!(fmt.format(-1488.79980469), "-1.488799804690D+03");
+//! assert_eq!(fmt.format(3.72529029846E-9), " 3.725290298460D-09");
+//!
+//! let fmt = FloatFormat::fortran(19, 12, Exponent::D);
+//! assert_eq!(fmt.format(1234.56789012), " 0.123456789012D+04");
+//!
+//! let fmt = FloatFormat::OBSERVATION;
+//! assert_eq!(fmt.format(20243517.560), "  20243517.560");
+//! ```
+//! Observation resolution is described by the [Precision] policy.
+use crate::Observable;
+use std::num::ParseFloatError;
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Exponent marker, in scientific notation
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Exponent {
+    /// Modern notation (1.0E+00)
+    #[default]
+    E,
+    /// FORTRAN double precision notation (1.0D+00),
+    /// used by RINEX2 Navigation files
+    D,
+}
+
+impl std::fmt::Display for Exponent {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::E => f.write_str("E"),
+            Self::D => f.write_str("D"),
+        }
+    }
+}
+
+/// Float notations
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Notation {
+    /// Fixed point (F) notation
+    Fixed,
+    /// Scientific notation, with normalized mantissa in [1, 10[:
+    /// -1.488799804690D+03
+    Scientific(Exponent),
+    /// FORTRAN scientific notation, with mantissa in [0.1, 1[:
+    /// -0.148879980469D+04
+    Fortran(Exponent),
+}
+
+/// [FloatFormat] describes how a number should be formatted
+/// on a fixed width field. Numbers are right aligned.
+/// Exponents are always expressed with a sign and at least two digits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FloatFormat {
+    /// Minimal field width
+    pub width: usize,
+    /// Number of digits after the decimal point
+    pub precision: usize,
+    /// Notation
+    pub notation: Notation,
+}
+
+impl FloatFormat {
+    /// Observation RINEX measurements (F14.3)
+    pub const OBSERVATION: Self = Self::fixed(14, 3);
+    /// Meteo RINEX measurements (F7.1)
+    pub const METEO: Self = Self::fixed(7, 1);
+    /// Clock RINEX data (E19.12)
+    pub const CLOCK: Self = Self::scientific(19, 12, Exponent::E);
+    /// Builds a fixed point format
+    pub const fn fixed(width: usize, precision: usize) -> Self {
+        Self {
+            width,
+            precision,
+            notation: Notation::Fixed,
+        }
+    }
+    /// Builds a scientific format, with normalized mantissa
+    pub const fn scientific(width: usize, precision: usize, exponent: Exponent) -> Self {
+        Self {
+            width,
+            precision,
+            notation: Notation::Scientific(exponent),
+        }
+    }
+    /// Builds a FORTRAN scientific format, with mantissa in [0.1, 1[
+    pub const fn fortran(width: usize, precision: usize, exponent: Exponent) -> Self {
+        Self {
+            width,
+            precision,
+            notation: Notation::Fortran(exponent),
+        }
+    }
+    /// Navigation RINEX data format (D19.12), for given revision:
+    /// D exponent is used prior V3.
+    pub const fn navigation(major: u8) -> Self {
+        if major < 3 {
+            Self::scientific(19, 12, Exponent::D)
+        } else {
+            Self::scientific(19, 12, Exponent::E)
+        }
+    }
+    /// Formats given value
+    pub fn format(&self, value: f64) -> String {
+        let formatted = match self.notation {
+            Notation::Fixed => format!("{:.*}", self.precision, value),
+            Notation::Scientific(exponent) => {
+                // rust normalizes the mantissa, even when rounding overflows
+                let formatted = format!("{:.*e}", self.precision, value);
+                let (mantissa, exp) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+                let exp = exp.parse::<i32>().unwrap_or(0);
+                format!("{}{}", mantissa, fmt_exponent(exponent, exp))
+            },
+            Notation::Fortran(exponent) => {
+                if value == 0.0 {
+                    format!(
+                        "0.{:0<width$}{}",
+                        "",
+                        fmt_exponent(exponent, 0),
+                        width = self.precision
+                    )
+                } else {
+                    // d.ddd..E(n) => 0.dddd..E(n+1), same number of significant digits
+                    let formatted = format!("{:.*e}", self.precision.saturating_sub(1), value);
+                    let (mantissa, exp) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+                    let exp = exp.parse::<i32>().unwrap_or(0);
+                    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+                        Some(mantissa) => ("-", mantissa),
+                        None => ("", mantissa),
+                    };
+                    let digits = mantissa.replace('.', "");
+                    format!("{}0.{}{}", sign, digits, fmt_exponent(exponent, exp + 1))
+                }
+            },
+        };
+        format!("{:>width$}", formatted, width = self.width)
+    }
+}
+
+/// [Precision] policy errors
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum PrecisionError {
+    #[error("{0} decimal digits do not fit the observation field")]
+    TooManyDecimals(u8),
+}
+
+/// [Precision] is the resolution policy of Observation RINEX measurements,
+/// expressed in number of decimal digits, per kind of observable.
+/// It is shared by the formatter, the Hatanaka compressor (which
+/// differentiates integer quantized values) and the decompressor.
+/// RINEX uses 3 decimal digits (F14.3): millimeter for Pseudo Range,
+/// 1E-3 cycle for Phase, 1E-3 Hz for Doppler and 1E-3 dBHz for SSI.
+/// Increasing the resolution reduces the number of integer digits
+/// that fit in the 14 characters field: at most [Precision::MAX_DECIMALS].
+///
+/// The policy is not described in the header section: CRINEX files compressed
+/// with a non standard policy are not correctly decompressed by other tools,
+/// nor by this library. Plain RINEX files remain readable by any tool.
+/// Opt-in with [crate::observation::HeaderFields::precision_comment] to describe it
+/// with an `OBS PRECISION (DECIMALS)` header COMMENT (private extension of this library),
+/// so our decompressor and formatter restore the same resolution.
+/// ```
+/// use rinex::prelude::Observable;
+/// use rinex::formatter::Precision;
+/// use std::str::FromStr;
+///
+/// let l1c = Observable::from_str("L1C").unwrap();
+/// let c1c = Observable::from_str("C1C").unwrap();
+///
+/// let precision = Precision::default()
+///     .with_phase(5)
+///     .unwrap();
+/// assert_eq!(precision.decimals(&c1c), 3);
+/// assert_eq!(precision.quantize(&l1c, 1.234567891), 123457);
+/// assert_eq!(precision.dequantize(&l1c, 123457), 1.23457);
+/// assert_eq!(precision.float_format(&l1c).format(-0.5), "      -0.50000");
+///
+/// // does not fit the F14 field
+/// assert!(Precision::default().with_phase(12).is_err());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Precision {
+    /// Pseudo Range resolution (meters)
+    pub pseudo_range: u8,
+    /// Phase resolution (cycles)
+    pub phase: u8,
+    /// Doppler resolution (Hz)
+    pub doppler: u8,
+    /// Signal strength resolution (dBHz)
+    pub ssi: u8,
+    /// Resolution of other observables, like channel numbers
+    pub other: u8,
+}
+
+impl Default for Precision {
+    fn default() -> Self {
+        Self::RINEX
+    }
+}
+
+impl Precision {
+    /// Standard RINEX resolution (F14.3)
+    pub const RINEX: Self = Self {
+        pseudo_range: 3,
+        phase: 3,
+        doppler: 3,
+        ssi: 3,
+        other: 3,
+    };
+    /* header COMMENT describing a non standard policy */
+    const COMMENT_MARKER: &'static str = "OBS PRECISION (DECIMALS)";
+    /// Maximal number of decimal digits: a negative value
+    /// ("-0." prefix) must fit the 14 characters field
+    pub const MAX_DECIMALS: u8 = 11;
+    /*
+     * Verifies that given resolution fits the observation field
+     */
+    fn checked(decimals: u8) -> Result<u8, PrecisionError> {
+        if decimals > Self::MAX_DECIMALS {
+            Err(PrecisionError::TooManyDecimals(decimals))
+        } else {
+            Ok(decimals)
+        }
+    }
+    /// Copies and sets Pseudo Range resolution
+    pub fn with_pseudo_range(&self, decimals: u8) -> Result<Self, PrecisionError> {
+        let mut s = *self;
+        s.pseudo_range = Self::checked(decimals)?;
+        Ok(s)
+    }
+    /// Copies and sets Phase resolution
+    pub fn with_phase(&self, decimals: u8) -> Result<Self, PrecisionError> {
+        let mut s = *self;
+        s.phase = Self::checked(decimals)?;
+        Ok(s)
+    }
+    /// Copies and sets Doppler resolution
+    pub fn with_doppler(&self, decimals: u8) -> Result<Self, PrecisionError> {
+        let mut s = *self;
+        s.doppler = Self::checked(decimals)?;
+        Ok(s)
+    }
+    /// Copies and sets signal strength resolution
+    pub fn with_ssi(&self, decimals: u8) -> Result<Self, PrecisionError> {
+        let mut s = *self;
+        s.ssi = Self::checked(decimals)?;
+        Ok(s)
+    }
+    /// Number of decimal digits for given observable
+    pub fn decimals(&self, observable: &Observable) -> u8 {
+        match observable {
+            Observable::PseudoRange(_) => self.pseudo_range,
+            Observable::Phase(_) => self.phase,
+            Observable::Doppler(_) => self.doppler,
+            Observable::SSI(_) => self.ssi,
+            _ => self.other,
+        }
+    }
+    /// Scaling applied to given observable, to obtain integer quantized values
+    pub fn scaling(&self, observable: &Observable) -> f64 {
+        10.0_f64.powi(self.decimals(observable) as i32)
+    }
+    /// Quantizes given observation
+    pub fn quantize(&self, observable: &Observable, value: f64) -> i64 {
+        (value * self.scaling(observable)).round() as i64
+    }
+    /// Quantizes given observation field (like F14.3) directly from its decimal digits,
+    /// see [parse_fixed_point]. Unlike [Self::quantize], this mapping is exact.
+    /// Returns None on empty (omitted observation) fields.
+    pub fn quantize_str(&self, observable: &Observable, field: &str) -> Option<i64> {
+        parse_fixed_point(field, self.decimals(observable)).or_else(|| {
+            // scientific notation: not expected in observation fields
+            let value = parse_float(field).ok()?;
+            Some(self.quantize(observable, value))
+        })
+    }
+    /// Recovers observation from quantized value
+    pub fn dequantize(&self, observable: &Observable, value: i64) -> f64 {
+        value as f64 / self.scaling(observable)
+    }
+    /// Observation field format (F14.n) for given observable
+    pub fn float_format(&self, observable: &Observable) -> FloatFormat {
+        FloatFormat::fixed(14, self.decimals(observable) as usize)
+    }
+    /*
+     * Header COMMENT that describes a non standard policy,
+     * so the file is decompressed and formatted at the same resolution
+     */
+    pub(crate) fn to_comment(self) -> String {
+        format!(
+            "{} C{} L{} D{} S{} X{}",
+            Self::COMMENT_MARKER,
+            self.pseudo_range,
+            self.phase,
+            self.doppler,
+            self.ssi,
+            self.other
+        )
+    }
+    /*
+     * Recovers the policy described by a header COMMENT, if it is one
+     */
+    pub(crate) fn from_comment(content: &str) -> Option<Self> {
+        let items = content.trim().strip_prefix(Self::COMMENT_MARKER)?;
+        let mut s = Self::RINEX;
+        for item in items.split_ascii_whitespace() {
+            let mut chars = item.chars();
+            let kind = chars.next()?;
+            let decimals = chars.as_str().parse::<u8>().ok()?;
+            let decimals = Self::checked(decimals).ok()?;
+            match kind {
+                'C' => s.pseudo_range = decimals,
+                'L' => s.phase = decimals,
+                'D' => s.doppler = decimals,
+                'S' => s.ssi = decimals,
+                'X' => s.other = decimals,
+                _ => return None,
+            }
+        }
+        Some(s)
+    }
+}
+
+/*
+ * Formats exponent with sign, on (at least) two digits
+ */
+fn fmt_exponent(exponent: Exponent, exp: i32) -> String {
+    if exp < 0 {
+        format!("{}-{:02}", exponent, -exp)
+    } else {
+        format!("{}+{:02}", exponent, exp)
+    }
+}
+
+/// Parses a floating point number, tolerating the FORTRAN notations
+/// encountered in RINEX: D exponent, lower case exponent and omitted
+/// leading zero (.123456789012D+04).
+/// ```
+/// use rinex::formatter::parse_float;
+/// assert_eq!(parse_float(" -0.148879980469D+04").unwrap(), -1488.79980469);
+/// assert_eq!(parse_float(".5d-01").unwrap(), 0.05);
+/// assert_eq!(parse_float("  20243517.560").unwrap(), 20243517.560);
+/// ```
+pub fn parse_float(content: &str) -> Result<f64, ParseFloatError> {
+    content.trim().replace(['D', 'd'], "E").parse::<f64>()
+}
+
+/// Parses a decimal field (for example F14.3) into an integer number
+/// of 10^-`decimals` units, directly from its digits: the mapping is exact,
+/// whereas binary floating point rounding (half away from zero, on an inexact value)
+/// may differ by one unit on halfway values.
+/// Extra digits are rounded half to even, missing digits are zero padded.
+/// Returns None on empty or non decimal content (like scientific notation).
+/// ```
+/// use rinex::formatter::parse_fixed_point;
+/// assert_eq!(parse_fixed_point("  20243517.560", 3), Some(20243517560));
+/// assert_eq!(parse_fixed_point("     -1234.5", 3), Some(-1234500));
+/// assert_eq!(parse_fixed_point("1.0005", 3), Some(1000));
+/// assert_eq!(parse_fixed_point("1.0015", 3), Some(1002));
+/// assert_eq!(parse_fixed_point("              ", 3), None);
+/// ```
+pub fn parse_fixed_point(content: &str, decimals: u8) -> Option<i64> {
+    let content = content.trim();
+    let (negative, digits) = match content.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, content.strip_prefix('+').unwrap_or(content)),
+    };
+    let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    if integer.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    if !integer
+        .bytes()
+        .chain(fraction.bytes())
+        .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let decimals = decimals as usize;
+    let mut value = 0_i64;
+    for digit in integer.bytes().chain(
+        fraction
+            .bytes()
+            .chain(std::iter::repeat(b'0'))
+            .take(decimals),
+    ) {
+        value = value.checked_mul(10)?.checked_add((digit - b'0') as i64)?;
+    }
+    if let Some(discarded) = fraction.get(decimals..).filter(|d| !d.is_empty()) {
+        let (first, rest) = discarded.split_at(1);
+        let halfway = first == "5" && rest.bytes().all(|b| b == b'0');
+        if first > "5" || (first == "5" && !halfway) || (halfway && value % 2 == 1) {
+            value = value.checked_add(1)?;
+        }
+    }
+    Some(if negative { -value } else { value })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn precision_comment() {
+        let precision = Precision::default()
+            .with_phase(5)
+            .unwrap()
+            .with_ssi(0)
+            .unwrap();
+        let comment = precision.to_comment();
+        assert_eq!(comment, "OBS PRECISION (DECIMALS) C3 L5 D3 S0 X3");
+        assert_eq!(Precision::from_comment(&comment), Some(precision));
+        assert!(Precision::from_comment("OBS PRECISION (DECIMALS) Z2").is_none());
+        assert!(Precision::from_comment("OBS PRECISION (DECIMALS) L12").is_none());
+        assert!(Precision::from_comment("some comment").is_none());
+    }
+    #[test]
+    fn scientific_notation() {
+        let fmt = FloatFormat::navigation(2);
+        assert_eq!(fmt.format(0.0), " 0.000000000000D+00");
+        assert_eq!(fmt.format(-12312.3), "-1.231230000000D+04");
+        assert_eq!(fmt.format(-1.23123123), "-1.231231230000D+00");
+        assert_eq!(fmt.format(-0.123123E-4), "-1.231230000000D-05");
+        assert_eq!(fmt.format(9.9999999999999), " 1.000000000000D+01");
+        // three digits exponent overflows the field
+        assert_eq!(fmt.format(1.0E-100), "1.000000000000D-100");
+
+        let fmt = FloatFormat::navigation(3);
+        assert_eq!(fmt.format(-12312.3), "-1.231230000000E+04");
+        assert_eq!(fmt.format(7.282570004460E-05), " 7.282570004460E-05");
+        assert_eq!(FloatFormat::CLOCK.format(-1.0E-9), "-1.000000000000E-09");
+    }
+    #[test]
+    fn fortran_notation() {
+        let fmt = FloatFormat::fortran(19, 12, Exponent::D);
+        assert_eq!(fmt.format(0.0), " 0.000000000000D+00");
+        assert_eq!(fmt.format(-0.123456789012E-4), "-0.123456789012D-04");
+        assert_eq!(fmt.format(0.214479208984E+05), " 0.214479208984D+05");
+        assert_eq!(fmt.format(0.99999999999999), " 0.100000000000D+01");
+        assert_eq!(fmt.format(1.0), " 0.100000000000D+01");
+    }
+    #[test]
+    fn fixed_notation() {
+        assert_eq!(FloatFormat::OBSERVATION.format(-0.5), "        -0.500");
+        assert_eq!(FloatFormat::OBSERVATION.format(123.4567), "       123.457");
+        assert_eq!(FloatFormat::METEO.format(987.12), "  987.1");
+    }
+    #[test]
+    fn round_trip() {
+        // 12 decimal digits on a normalized mantissa: 13 significant digits
+        let values = [
+            -1.488799804690E+03,
+            -2.196182250980E+00,
+            3.725290298460E-09,
+            1.292880712890E+04,
+            -9.313225746150E-10,
+            2.193169775390E+04,
+            299792458.0,
+            -1.0E-20,
+        ];
+        for major in [2, 3] {
+            let fmt = FloatFormat::navigation(major);
+            for value in values {
+                let formatted = fmt.format(value);
+                assert_eq!(formatted.len(), 19, "\"{}\"", formatted);
+                let parsed = parse_float(&formatted).unwrap();
+                assert_eq!(parsed, value, "\"{}\"", formatted);
+            }
+        }
+        let fmt = FloatFormat::fortran(19, 12, Exponent::D);
+        for value in [-0.148879980469E+04, 0.372529029846E-08, 0.214479208984E+05] {
+            let formatted = fmt.format(value);
+            assert_eq!(parse_float(&formatted).unwrap(), value, "\"{}\"", formatted);
+        }
+        // observations: 14.3 is exact to the millimeter / millicycle
+        for value in [20243517.560, -1234.567, 0.001, 129472453.612] {
+            let formatted = FloatFormat::OBSERVATION.format(value);
+            assert_eq!(formatted.len(), 14);
+            assert_eq!(parse_float(&formatted).unwrap(), value);
+        }
+    }
+    #[test]
+    fn precision() {
+        use std::str::FromStr;
+        let c1c = Observable::from_str("C1C").unwrap();
+        let d1c = Observable::from_str("D1C").unwrap();
+        let s1c = Observable::from_str("S1C").unwrap();
+
+        let precision = Precision::default();
+        assert_eq!(precision, Precision::RINEX);
+        for observable in [&c1c, &d1c, &s1c] {
+            assert_eq!(precision.float_format(observable), FloatFormat::OBSERVATION);
+            assert_eq!(precision.quantize(observable, 20243517.5604), 20243517560);
+            assert_eq!(precision.dequantize(observable, -1234567), -1234.567);
+        }
+
+        let precision = precision.with_doppler(4).unwrap().with_ssi(1).unwrap();
+        assert_eq!(
+            precision.with_pseudo_range(Precision::MAX_DECIMALS + 1),
+            Err(PrecisionError::TooManyDecimals(12))
+        );
+        assert_eq!(precision.decimals(&c1c), 3);
+        assert_eq!(precision.quantize(&d1c, -1234.56789), -12345679);
+        assert_eq!(precision.dequantize(&d1c, -12345679), -1234.5679);
+        assert_eq!(precision.quantize(&s1c, 45.26), 453);
+        assert_eq!(precision.float_format(&s1c).format(45.26), "          45.3");
+
+        // fields are quantized from their digits
+        for (field, expected) in [
+            ("  20243517.560", 20243517560),
+            (" 129472453.612", 129472453612),
+            ("        -0.005", -5),
+            ("         0.000", 0),
+            ("      -123.4  ", -123400),
+            ("           .25", 250),
+        ] {
+            assert_eq!(precision.quantize_str(&c1c, field), Some(expected));
+        }
+        assert_eq!(precision.quantize_str(&c1c, "              "), None);
+        assert_eq!(
+            precision.quantize_str(&c1c, "  1.234567E+03"),
+            Some(1234567)
+        );
+        // rounding half to even, on the decimal representation
+        assert_eq!(parse_fixed_point("1.0005", 3), Some(1000));
+        assert_eq!(parse_fixed_point("1.0015", 3), Some(1002));
+        assert_eq!(parse_fixed_point("-1.00150", 3), Some(-1002));
+        assert_eq!(parse_fixed_point("1.00051", 3), Some(1001));
+        assert_eq!(parse_fixed_point("1.0004999", 3), Some(1000));
+        assert_eq!(parse_fixed_point("1.5", 0), Some(2));
+        assert_eq!(parse_fixed_point("2.5", 0), Some(2));
+        assert!(parse_fixed_point("1.2.3", 3).is_none());
+        assert!(parse_fixed_point("-", 3).is_none());
+        // f64 rounding moves halfway values away from zero
+        assert_eq!((20243517.5605_f64 * 1000.0).round() as i64, 20243517561);
+        assert_eq!(parse_fixed_point("20243517.5605", 3), Some(20243517560));
+
+        // quantization is exact at the nominal resolution
+        for value in [20243517.560, -1234.567, 0.001, 129472453.612] {
+            let q = Precision::RINEX.quantize(&c1c, value);
+            assert_eq!(Precision::RINEX.dequantize(&c1c, q), value);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use map_3d::{deg2rad, ecef2geodetic, geodetic2ecef, rad2deg, Ellipsoid};
+use map_3d::{ecef2enu, ecef2geodetic, enu2ecef, geodetic2ecef, Ellipsoid};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -27,7 +27,12 @@ impl GroundPosition {
     /// Builds Self from Geodetic coordinates in ddeg
     pub fn from_geodetic(pos: (f64, f64, f64)) -> Self {
         let (x, y, z) = pos;
-        let (x, y, z) = geodetic2ecef(deg2rad(x), deg2rad(y), deg2rad(z), Ellipsoid::WGS84);
+        let (x, y, z) = geodetic2ecef(
+            x.to_radians(),
+            y.to_radians(),
+            z.to_radians(),
+            Ellipsoid::WGS84,
+        );
         Self(x, y, z)
     }
     /// Converts Self to ECEF WGS84
@@ -38,12 +43,24 @@ impl GroundPosition {
     pub fn to_geodetic(&self) -> (f64, f64, f64) {
         let (x, y, z) = (self.0, self.1, self.2);
         let (lat, lon, alt) = ecef2geodetic(x, y, z, Ellipsoid::WGS84);
-        (rad2deg(lat), rad2deg(lon), alt)
+        (lat.to_degrees(), lon.to_degrees(), alt)
     }
     /// Returns position altitude
     pub fn altitude(&self) -> f64 {
         self.to_geodetic().2
     }
+    /// Expresses Self in the local East North Up frame of `origin`, in meters
+    pub fn to_enu(&self, origin: &Self) -> (f64, f64, f64) {
+        let (lat0, lon0, alt0) = ecef2geodetic(origin.0, origin.1, origin.2, Ellipsoid::WGS84);
+        ecef2enu(self.0, self.1, self.2, lat0, lon0, alt0, Ellipsoid::WGS84)
+    }
+    /// Builds Self from East North Up coordinates, in meters,
+    /// expressed in the local frame of `origin`
+    pub fn from_enu(enu: (f64, f64, f64), origin: &Self) -> Self {
+        let (lat0, lon0, alt0) = ecef2geodetic(origin.0, origin.1, origin.2, Ellipsoid::WGS84);
+        let (x, y, z) = enu2ecef(enu.0, enu.1, enu.2, lat0, lon0, alt0, Ellipsoid::WGS84);
+        Self(x, y, z)
+    }
 }
 
 impl std::fmt::Display for GroundPosition {
@@ -1,5 +1,10 @@
 //! Hardware: receiver, antenna informations
+use crate::prelude::{Constellation, GroundPosition, Observable};
 use gnss::prelude::SV;
+use std::str::FromStr;
+use thiserror::Error;
+
+include!(concat!(env!("OUT_DIR"), "/rcvr_db.rs"));
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -16,6 +21,77 @@ pub struct Rcvr {
     pub firmware: String, // firmware #
 }
 
+impl Rcvr {
+    /// Returns the capabilities of this receiver model,
+    /// if it is known in our receiver database.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::hardware::Rcvr;
+    /// use std::str::FromStr;
+    /// let rcvr = Rcvr::from_str("2090088             LEICA GR50          4.51                ")
+    ///     .unwrap();
+    /// let capabilities = rcvr.capabilities()
+    ///     .unwrap();
+    /// let l5 = Observable::from_str("L5Q").unwrap();
+    /// assert!(capabilities.supports(Constellation::GPS, &l5));
+    /// let l6 = Observable::from_str("L6X").unwrap();
+    /// assert!(!capabilities.supports(Constellation::GPS, &l6));
+    /// ```
+    pub fn capabilities(&self) -> Option<&'static RcvrCapabilities> {
+        RCVR_DATABASE
+            .iter()
+            .find(|rcvr| rcvr.model.eq_ignore_ascii_case(self.model.trim()))
+    }
+}
+
+/// Known receiver model capabilities, from our receiver database.
+/// This is used to verify that observations are plausible for the
+/// declared receiver, and catch mislabeled headers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RcvrCapabilities {
+    /// Receiver model, as described in `REC # / TYPE / VERS`
+    pub model: &'static str,
+    /// Supported frequency bands, per constellation.
+    /// Bands are described by their RINEX digit, for example "125" for L1, L2 and L5.
+    pub bands: Vec<(Constellation, &'static str)>,
+}
+
+impl RcvrCapabilities {
+    /*
+     * Supported bands for this constellation.
+     * All SBAS systems are described as SBAS.
+     */
+    fn constellation_bands(&self, constellation: Constellation) -> Option<&'static str> {
+        let constellation = if constellation.is_sbas() {
+            Constellation::SBAS
+        } else {
+            constellation
+        };
+        self.bands
+            .iter()
+            .find(|(c, _)| *c == constellation)
+            .map(|(_, bands)| *bands)
+    }
+    /// Returns true if this receiver can track given constellation
+    pub fn supports_constellation(&self, constellation: Constellation) -> bool {
+        self.constellation_bands(constellation).is_some()
+    }
+    /// Returns true if this receiver can track given observable,
+    /// for given constellation. Only the frequency band is verified.
+    pub fn supports(&self, constellation: Constellation, observable: &Observable) -> bool {
+        match self.constellation_bands(constellation) {
+            Some(bands) => {
+                let code = observable.to_string();
+                match code.chars().nth(1) {
+                    Some(band) => bands.contains(band),
+                    None => false,
+                }
+            },
+            None => false,
+        }
+    }
+}
+
 impl std::str::FromStr for Rcvr {
     type Err = std::io::Error;
     fn from_str(line: &str) -> Result<Self, Self::Err> {
@@ -30,6 +106,56 @@ impl std::str::FromStr for Rcvr {
     }
 }
 
+/// IGS antenna naming convention (rcvr_ant.tab) violations
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum AntennaNameError {
+    #[error("antenna type exceeds 20 characters")]
+    TooLong,
+    #[error("antenna model exceeds 15 characters")]
+    ModelTooLong,
+    #[error("missing antenna model")]
+    MissingModel,
+    #[error("missing radome code (should be \"NONE\")")]
+    MissingRadome,
+    #[error("radome code should be 4 characters, in columns 17-20")]
+    MisalignedRadome,
+    #[error("non standard character '{0}'")]
+    InvalidCharacter(char),
+}
+
+/*
+ * Characters allowed in IGS antenna models & radome codes
+ */
+fn is_igs_antenna_char(c: char) -> bool {
+    c.is_ascii_uppercase() || c.is_ascii_digit() || "-_+./".contains(c)
+}
+
+/// Antenna eccentricity (ANTENNA: DELTA H/E/N): offset of the
+/// antenna reference point (ARP) relative to the marker, in meters
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AntennaOffset {
+    /// Height of the ARP above the marker
+    pub height: f64,
+    /// Eastern eccentricity
+    pub eastern: f64,
+    /// Northern eccentricity
+    pub northern: f64,
+}
+
+impl AntennaOffset {
+    /// Returns the antenna reference point position,
+    /// from the `marker` position this offset is referenced to
+    pub fn apply(&self, marker: GroundPosition) -> GroundPosition {
+        GroundPosition::from_enu((self.eastern, self.northern, self.height), &marker)
+    }
+    /// Returns the marker position, from the antenna reference point
+    /// position this offset was applied to
+    pub fn remove(&self, arp: GroundPosition) -> GroundPosition {
+        GroundPosition::from_enu((-self.eastern, -self.northern, -self.height), &arp)
+    }
+}
+
 /// Antenna description
 #[derive(Debug, Clone, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -76,6 +202,19 @@ impl Antenna {
         s.height = Some(h);
         s
     }
+    /// Returns the antenna eccentricity, if at least one of its components
+    /// is defined. Undefined components are null.
+    pub fn offset(&self) -> Option<AntennaOffset> {
+        if self.height.is_none() && self.eastern.is_none() && self.northern.is_none() {
+            None
+        } else {
+            Some(AntennaOffset {
+                height: self.height.unwrap_or(0.0),
+                eastern: self.eastern.unwrap_or(0.0),
+                northern: self.northern.unwrap_or(0.0),
+            })
+        }
+    }
     /// Sets antenna `eastern` coordinates component
     pub fn with_eastern_component(&self, e: f64) -> Self {
         let mut s = self.clone();
@@ -88,6 +227,93 @@ impl Antenna {
         s.northern = Some(n);
         s
     }
+    /// Returns the radome code, if the antenna type follows
+    /// the IGS naming convention: 4 characters in columns 17-20.
+    pub fn radome(&self) -> Option<&str> {
+        if self.model.len() == 20 && self.model.is_char_boundary(16) {
+            let (_, radome) = self.model.split_at(16);
+            Some(radome)
+        } else {
+            None
+        }
+    }
+    /// Validates the antenna type against the IGS naming convention
+    /// (rcvr_ant.tab): upper case model, left justified in columns 1-15,
+    /// followed by the radome code in columns 17-20 ("NONE" if no radome).
+    /// Archives usually reject files that do not follow this convention.
+    /// ```
+    /// use rinex::hardware::{Antenna, AntennaNameError};
+    /// let antenna = Antenna::default()
+    ///     .with_model("TRM115000.00    NONE");
+    /// assert!(antenna.validate_igs_name().is_ok());
+    ///
+    /// let antenna = Antenna::default()
+    ///     .with_model("TRM115000.00");
+    /// assert_eq!(antenna.validate_igs_name(), Err(AntennaNameError::MissingRadome));
+    ///
+    /// let antenna = Antenna::default()
+    ///     .with_model("trm115000.00 none");
+    /// assert_eq!(antenna.validate_igs_name(), Err(AntennaNameError::InvalidCharacter('t')));
+    /// ```
+    pub fn validate_igs_name(&self) -> Result<(), AntennaNameError> {
+        if let Some(c) = self
+            .model
+            .chars()
+            .find(|c| *c != ' ' && !is_igs_antenna_char(*c))
+        {
+            return Err(AntennaNameError::InvalidCharacter(c));
+        }
+        if self.model.len() > 20 {
+            return Err(AntennaNameError::TooLong);
+        }
+        if self.model.starts_with(' ') || self.model.trim().is_empty() {
+            return Err(AntennaNameError::MissingModel);
+        }
+        if self.model.len() < 20 {
+            return match self.model.split_whitespace().count() {
+                1 => Err(AntennaNameError::MissingRadome),
+                _ => Err(AntennaNameError::MisalignedRadome),
+            };
+        }
+        let (model, radome) = self.model.split_at(16);
+        if model.trim_end().len() > 15 {
+            return Err(AntennaNameError::ModelTooLong);
+        }
+        if radome.contains(' ') {
+            return Err(AntennaNameError::MisalignedRadome);
+        }
+        Ok(())
+    }
+    /// Returns the antenna type, normalized to the IGS naming convention:
+    /// upper case, model left justified in columns 1-15, radome code in
+    /// columns 17-20. When no radome code can be identified, "NONE" is used.
+    /// Model and radome are preserved: a model that is too long
+    /// will still not comply after normalization.
+    /// ```
+    /// use rinex::hardware::Antenna;
+    /// let antenna = Antenna::default()
+    ///     .with_model("trm115000.00 none");
+    /// assert_eq!(antenna.igs_name(), "TRM115000.00    NONE");
+    ///
+    /// let antenna = Antenna::default()
+    ///     .with_model("ASH701945E_M");
+    /// assert_eq!(antenna.igs_name(), "ASH701945E_M    NONE");
+    /// ```
+    pub fn igs_name(&self) -> String {
+        let upper = self.model.to_uppercase();
+        let mut items: Vec<&str> = upper.split_whitespace().collect();
+        let radome = match items.len() {
+            0 | 1 => "NONE",
+            _ => {
+                if items[items.len() - 1].len() == 4 {
+                    items.pop().unwrap()
+                } else {
+                    "NONE"
+                }
+            },
+        };
+        format!("{:<15} {}", items.join(" "), radome)
+    }
 }
 
 #[cfg(feature = "qc")]
@@ -120,6 +346,9 @@ impl HtmlReport for Antenna {
                     th {
                         : "Eccentricity"
                     }
+                    th {
+                        : "IGS naming"
+                    }
                 }
                 tr {
                     td {
@@ -154,6 +383,13 @@ impl HtmlReport for Antenna {
                             : "Unknown"
                         }
                     }
+                    td {
+                        @ if let Err(e) = self.validate_igs_name() {
+                            : format!("Non standard: {}", e)
+                        } else {
+                            : "Compliant"
+                        }
+                    }
                 }
             }
         }
@@ -242,4 +478,116 @@ mod test {
         assert_eq!(rcvr.sn, "2090088");
         assert_eq!(rcvr.firmware, "4.51");
     }
+    #[test]
+    fn rcvr_database() {
+        for (model, constellation, observable, supported) in [
+            ("SEPT POLARX5", Constellation::Galileo, "C6C", true),
+            ("SEPT POLARX5", Constellation::IRNSS, "L5A", true),
+            ("SEPT POLARX4", Constellation::Galileo, "C6C", false),
+            ("TRIMBLE NETR9", Constellation::BeiDou, "C7I", true),
+            ("TRIMBLE NETR5", Constellation::Galileo, "C1C", false),
+            ("TRIMBLE NETR5", Constellation::EGNOS, "C1C", true),
+            ("TRIMBLE NETR5", Constellation::EGNOS, "C5I", false),
+            ("ROGUE SNR-8", Constellation::GPS, "P2", true),
+            ("ROGUE SNR-8", Constellation::GPS, "C5", false),
+            ("ROGUE SNR-8", Constellation::Glonass, "C1", false),
+        ] {
+            let rcvr = Rcvr {
+                model: model.to_string(),
+                ..Default::default()
+            };
+            let capabilities = rcvr
+                .capabilities()
+                .unwrap_or_else(|| panic!("{} should be known", model));
+            let observable = Observable::from_str(observable).unwrap();
+            assert_eq!(
+                capabilities.supports(constellation, &observable),
+                supported,
+                "{} {} {}",
+                model,
+                constellation,
+                observable
+            );
+        }
+        let rcvr = Rcvr {
+            model: "UNKNOWN RCVR".to_string(),
+            ..Default::default()
+        };
+        assert!(rcvr.capabilities().is_none());
+    }
+    #[test]
+    fn antenna_igs_naming() {
+        for (model, igs_name, error) in [
+            ("LEIAR25.R4      LEIT", "LEIAR25.R4      LEIT", None),
+            ("AOAD/M_T        NONE", "AOAD/M_T        NONE", None),
+            (
+                "LEIAS10",
+                "LEIAS10         NONE",
+                Some(AntennaNameError::MissingRadome),
+            ),
+            (
+                "LEIAR25.R4 LEIT",
+                "LEIAR25.R4      LEIT",
+                Some(AntennaNameError::MisalignedRadome),
+            ),
+            (
+                "leiar25.r4      leit",
+                "LEIAR25.R4      LEIT",
+                Some(AntennaNameError::InvalidCharacter('l')),
+            ),
+            (
+                "-Unknown-",
+                "-UNKNOWN-       NONE",
+                Some(AntennaNameError::InvalidCharacter('n')),
+            ),
+            (
+                "SEPCHOKE_B3E6_LONG  SPKE",
+                "SEPCHOKE_B3E6_LONG SPKE",
+                Some(AntennaNameError::TooLong),
+            ),
+            (
+                "SEPCHOKE_B3E6_LONGSPKE",
+                "SEPCHOKE_B3E6_LONGSPKE NONE",
+                Some(AntennaNameError::TooLong),
+            ),
+        ] {
+            let antenna = Antenna::default().with_model(model);
+            assert_eq!(antenna.validate_igs_name().err(), error, "{}", model);
+            assert_eq!(antenna.igs_name(), igs_name, "{}", model);
+            let normalized = Antenna::default().with_model(&antenna.igs_name());
+            if normalized.model.len() == 20 {
+                assert!(normalized.validate_igs_name().is_ok(), "{}", model);
+                assert_eq!(normalized.radome(), Some(&igs_name[16..]));
+            }
+        }
+    }
+    #[test]
+    fn antenna_offset() {
+        assert!(Antenna::default().offset().is_none());
+        let antenna = Antenna::default().with_height(1.891);
+        let offset = antenna.offset().unwrap();
+        assert_eq!(
+            offset,
+            AntennaOffset {
+                height: 1.891,
+                eastern: 0.0,
+                northern: 0.0,
+            }
+        );
+        let antenna = antenna
+            .with_eastern_component(0.25)
+            .with_northern_component(-0.5);
+        let offset = antenna.offset().unwrap();
+        // NOA1 marker
+        let marker = GroundPosition::from_ecef_wgs84((4594489.6, 2039447.8, 3912626.8));
+        let arp = offset.apply(marker);
+        let (e, n, u) = arp.to_enu(&marker);
+        assert!((e - 0.25).abs() < 1.0E-6, "east: {}", e);
+        assert!((n + 0.5).abs() < 1.0E-6, "north: {}", n);
+        assert!((u - 1.891).abs() < 1.0E-6, "up: {}", u);
+        assert!((arp.altitude() - marker.altitude() - 1.891).abs() < 1.0E-3);
+        let (x, y, z) = offset.remove(arp).to_ecef_wgs84();
+        let (x0, y0, z0) = marker.to_ecef_wgs84();
+        assert!((x - x0).abs() < 1.0E-6 && (y - y0).abs() < 1.0E-6 && (z - z0).abs() < 1.0E-6);
+    }
 }
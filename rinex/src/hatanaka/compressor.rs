@@ -1,11 +1,17 @@
 //! RINEX compression module
-use super::{numdiff::NumDiff, textdiff::TextDiff, Error};
+use super::{
+    event_records, numdiff::NumDiff, textdiff::TextDiff, EpochBlock, Error, HatanakaCodec,
+};
+use crate::fieldslice;
+use crate::formatter::{parse_fixed_point, Precision};
 use crate::is_rinex_comment;
-use crate::{Constellation, Observable, SV};
-use std::collections::HashMap;
+use crate::observation;
+use crate::writer::LineEnding;
+use crate::{Constellation, Header, Observable, SV};
+use std::collections::{hash_map::Entry, HashMap};
 use std::str::FromStr;
 
-#[derive(Default, PartialEq)]
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
 pub enum State {
     #[default]
     EpochDescriptor,
@@ -27,6 +33,8 @@ pub struct Compressor {
     first_epoch: bool,
     /// epoch line ptr
     epoch_ptr: u8,
+    /// special records of the current event epoch, passed as is
+    event_records: usize,
     /// epoch descriptor
     epoch_descriptor: String,
     /// flags descriptor being constructed
@@ -41,10 +49,103 @@ pub struct Compressor {
     epoch_diff: TextDiff,
     /// Clock offset differentiator
     clock_diff: NumDiff,
+    /// True when clock offset differentiator is initialized
+    clock_init: bool,
+    /// Receiver clock offset of the epoch being described,
+    /// in nanoseconds
+    clock_offset: Option<i64>,
     /// Vehicle differentiators
     sv_diff: HashMap<SV, HashMap<usize, (NumDiff, TextDiff, TextDiff)>>,
+    /// RINEX3 flags differentiators
+    sv_flags: HashMap<SV, TextDiff>,
     /// Pending kernel re-initialization
     forced_init: HashMap<SV, Vec<usize>>,
+    /// RINEX3 epoch body, held until the descriptor is complete
+    body: String,
+    /// RINEX2 vehicle content, held until the vehicle is concluded
+    pending: String,
+    /// Optional statistics, only gathered when estimating
+    stats: Option<CompressionEstimate>,
+    /// Observations quantization policy
+    precision: Precision,
+    /// Line terminator of the compressed content
+    line_ending: LineEnding,
+}
+
+/// Compression statistics of a given observable
+#[derive(Debug, Default, Clone)]
+pub struct ObservableStats {
+    /// Number of compressed samples
+    pub samples: usize,
+    /// Number of kernel initializations,
+    /// including the forced resets
+    pub kernel_inits: usize,
+    /// Number of kernel resets, forced by missing observations
+    pub kernel_resets: usize,
+    /// Histogram of compressed residuals
+    residuals: HashMap<i64, usize>,
+}
+
+impl ObservableStats {
+    /// Shannon entropy of the compressed residuals, in bits per sample.
+    /// The higher, the less compressible this observable is.
+    pub fn entropy(&self) -> f64 {
+        let total: usize = self.residuals.values().sum();
+        if total == 0 {
+            return 0.0;
+        }
+        self.residuals
+            .values()
+            .map(|count| {
+                let p = *count as f64 / total as f64;
+                -p * p.log2()
+            })
+            .sum()
+    }
+    /// Largest compressed residual (absolute value)
+    pub fn max_residual(&self) -> Option<i64> {
+        self.residuals.keys().map(|r| r.abs()).max()
+    }
+}
+
+/// [`CompressionEstimate`] is returned by [`Compressor::estimate`]
+#[derive(Debug, Default, Clone)]
+pub struct CompressionEstimate {
+    /// Uncompressed (RINEX) size, in bytes
+    pub input_size: usize,
+    /// Achievable compressed (CRINEX) size, in bytes
+    pub compressed_size: usize,
+    /// Statistics per observable
+    pub observables: HashMap<(Constellation, Observable), ObservableStats>,
+    /// Number of satellite re-synchronizations: a satellite went missing
+    /// for at least one epoch, its kernels were re-initialized when it came back.
+    pub resynchronizations: usize,
+}
+
+impl CompressionEstimate {
+    /// Compression ratio: compressed size / input size
+    pub fn ratio(&self) -> f64 {
+        if self.input_size == 0 {
+            return 1.0;
+        }
+        self.compressed_size as f64 / self.input_size as f64
+    }
+    /// Total number of kernel resets, forced by missing observations
+    pub fn kernel_resets(&self) -> usize {
+        self.observables
+            .values()
+            .map(|stats| stats.kernel_resets)
+            .sum()
+    }
+}
+
+enum KernelEvent {
+    /// Kernel initialization
+    Init,
+    /// Forced kernel reinitialization
+    Reset,
+    /// Compressed residual
+    Residual(i64),
 }
 
 fn format_epoch_descriptor(content: &str) -> String {
@@ -57,11 +158,58 @@ fn format_epoch_descriptor(content: &str) -> String {
     result
 }
 
+/*
+ * Returns the observables of given vehicle. SBAS vehicles are identified
+ * as the augmentation system they belong to, but described by the SBAS entry.
+ */
+fn sv_observables(
+    observables: &HashMap<Constellation, Vec<Observable>>,
+    sv: SV,
+) -> Result<&Vec<Observable>, Error> {
+    observables
+        .get(&sv.constellation)
+        .or_else(|| match sv.constellation.is_sbas() {
+            true => observables.get(&Constellation::SBAS),
+            false => None,
+        })
+        .ok_or(Error::UndefinedObservables(sv.constellation))
+}
+
+/*
+ * Compresses one observation. Like RNX2CRX, the kernel is re-initialized
+ * when the residual overflows (beyond 1E10 quantization steps),
+ * which typically happens on large cycle slips
+ */
+fn compress_observation(
+    kernels: &mut (NumDiff, TextDiff, TextDiff),
+    value: i64,
+) -> Result<(String, KernelEvent), Error> {
+    let residual = kernels.0.compress(value);
+    if (residual / 100_000).abs() > 100_000 {
+        reset_kernels(kernels, value)?;
+        Ok((format!("3&{} ", value), KernelEvent::Reset))
+    } else {
+        Ok((format!("{} ", residual), KernelEvent::Residual(residual)))
+    }
+}
+
+/*
+ * (Re)initializes the kernels of one observation:
+ * flags are then described as is, like the decompressor expects
+ */
+fn reset_kernels(kernels: &mut (NumDiff, TextDiff, TextDiff), value: i64) -> Result<(), Error> {
+    kernels.0.init(3, value)?;
+    kernels.1.init(" ");
+    kernels.2.init(" ");
+    Ok(())
+}
+
 impl Default for Compressor {
     fn default() -> Self {
         Self {
             first_epoch: true,
             epoch_ptr: 0,
+            event_records: 0,
             epoch_descriptor: String::new(),
             flags_descriptor: String::new(),
             state: State::default(),
@@ -70,37 +218,209 @@ impl Default for Compressor {
             obs_ptr: 0,
             epoch_diff: TextDiff::new(),
             clock_diff: NumDiff::new(NumDiff::MAX_COMPRESSION_ORDER).unwrap(),
+            clock_init: false,
+            clock_offset: None,
             sv_diff: HashMap::new(),
+            sv_flags: HashMap::new(),
             forced_init: HashMap::new(),
+            body: String::new(),
+            pending: String::new(),
+            stats: None,
+            precision: Precision::default(),
+            line_ending: LineEnding::default(),
         }
     }
 }
 
 impl Compressor {
+    /// Builds a [Compressor] that quantizes observations
+    /// with given [Precision] policy. It must match the resolution
+    /// of the content to compress, otherwise digits are lost.
+    pub fn with_precision(precision: Precision) -> Self {
+        Self {
+            precision,
+            ..Default::default()
+        }
+    }
+    /// Copies and returns this [Compressor] with given [LineEnding]:
+    /// the compressed content is terminated accordingly.
+    /// ```
+    /// use rinex::hatanaka::Compressor;
+    /// use rinex::writer::LineEnding;
+    /// let compressor = Compressor::default()
+    ///     .with_line_ending(LineEnding::CrLf);
+    /// ```
+    pub fn with_line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+    /// Compresses exactly one epoch, following given [Header] definitions.
+    /// This is the safe boundary for real-time applications:
+    /// they do not have to buffer and feed partial text content.
+    /// Successive epochs must be compressed with the same [Compressor].
+    pub fn compress_epoch(&mut self, header: &Header, block: &EpochBlock) -> Result<String, Error> {
+        let obs = header.obs.as_ref().ok_or(Error::NotObsRinexData)?;
+        let constellation = header
+            .constellation
+            .as_ref()
+            .ok_or(Error::UndefinedConstellation)?;
+        let content = observation::record::fmt_epoch(
+            block.epoch,
+            block.flag,
+            &block.clock_offset,
+            &block.vehicles,
+            header,
+        );
+        let mut result = String::new();
+        for line in content.lines() {
+            // one line at a time, like production does
+            let line = line.to_owned() + "\n";
+            result.push_str(&self.compress(
+                header.version.major,
+                &obs.codes,
+                constellation,
+                &line,
+            )?);
+        }
+        if self.state != State::EpochDescriptor {
+            return Err(Error::IncompleteEpoch);
+        }
+        Ok(result)
+    }
+    /// Estimates the achievable compression of given RINEX content (epochs),
+    /// without producing any output. This is the same algorithm as [`Self::compress`],
+    /// but it also reports per observable statistics, like entropy of
+    /// the compressed residuals and number of kernel resets.
+    /// This helps choosing the compression order, or identifying
+    /// receivers producing poorly compressible data.
+    pub fn estimate(
+        rnx_major: u8,
+        observables: &HashMap<Constellation, Vec<Observable>>,
+        constellation: &Constellation,
+        content: &str,
+    ) -> Result<CompressionEstimate, Error> {
+        let mut compressor = Self {
+            stats: Some(CompressionEstimate::default()),
+            ..Default::default()
+        };
+        let mut compressed_size = 0;
+        for line in content.lines() {
+            let line = line.to_owned() + "\n";
+            let compressed = compressor.compress(rnx_major, observables, constellation, &line)?;
+            compressed_size += compressed.len();
+        }
+        let mut estimate = compressor.stats.unwrap_or_default();
+        estimate.input_size = content.len();
+        estimate.compressed_size = compressed_size;
+        Ok(estimate)
+    }
     /// Identifies amount of vehicles to be provided in next iterations
     /// by analyzing epoch descriptor
-    fn determine_nb_vehicles(&self, content: &str) -> Result<usize, Error> {
-        if content.len() < 33 {
-            Err(Error::MalformedEpochDescriptor)
-        } else {
-            let nb = &content[30..32];
+    fn determine_nb_vehicles(&self, rnx_major: u8, content: &str) -> Result<usize, Error> {
+        let nb = match rnx_major {
+            1 | 2 => content.get(30..32),
+            _ => content.get(32..35),
+        };
+        if let Some(nb) = nb {
             if let Ok(u) = nb.trim().parse::<u16>() {
                 //println!("Identified {} vehicles", u); //DEBUG
                 Ok(u.into())
             } else {
                 Err(Error::MalformedEpochDescriptor)
             }
+        } else {
+            Err(Error::MalformedEpochDescriptor)
+        }
+    }
+
+    /// Stores given epoch descriptor line. The receiver clock offset,
+    /// if any, is not part of the CRINEX epoch descriptor:
+    /// it is latched, to be compressed on its own line.
+    /// In RINEX2, it is described in columns 69-80 (F12.9), we latch it in 1E-9 s.
+    /// In RINEX3, it is described in columns 42-56 (F15.12), we latch it in 1E-12 s,
+    /// and the satellites will be appended from the following lines.
+    fn push_epoch_descriptor(&mut self, rnx_major: u8, line: &str) -> Result<(), Error> {
+        match rnx_major {
+            1 | 2 => {
+                let (descriptor, clock_offset) = fieldslice::split_at(line, 68)?;
+                if let Some(offset) = parse_fixed_point(clock_offset, 9) {
+                    self.clock_offset = Some(offset);
+                }
+                self.epoch_descriptor.push_str(descriptor);
+                self.epoch_descriptor.push('\n');
+            },
+            _ => {
+                let (descriptor, clock_offset) = fieldslice::split_at(line, 41)?;
+                if let Some(offset) = parse_fixed_point(clock_offset, 12) {
+                    self.clock_offset = Some(offset);
+                }
+                self.epoch_descriptor
+                    .push_str(&format!("{:<41}", descriptor.trim_end()));
+            },
+        }
+        Ok(())
+    }
+
+    /// Emits the CRINEX epoch descriptor (compressed, after the first epoch),
+    /// followed by the clock offset line
+    fn emit_epoch_descriptor(&mut self) -> String {
+        let mut result = String::new();
+        let descriptor = self.epoch_descriptor.trim_end_matches('\n');
+        if self.first_epoch {
+            self.epoch_diff.init(descriptor);
+            result.push_str(descriptor);
+            self.first_epoch = false;
+        } else {
+            result.push_str(self.epoch_diff.compress(descriptor).trim_end());
+        }
+        result.push('\n');
+        result.push_str(&self.compress_clock_offset());
+        result.push('\n');
+        result
+    }
+
+    /// Compresses latched receiver clock offset.
+    /// Returns an empty line when the epoch has no clock offset,
+    /// in which case the kernel will be reinitialized on the next one.
+    fn compress_clock_offset(&mut self) -> String {
+        match self.clock_offset.take() {
+            Some(offset) => {
+                if self.clock_init {
+                    self.clock_diff.compress(offset).to_string()
+                } else {
+                    self.clock_diff.init(3, offset).unwrap();
+                    self.clock_init = true;
+                    format!("3&{}", offset)
+                }
+            },
+            None => {
+                self.clock_init = false;
+                String::new()
+            },
         }
     }
 
     /// Identifies vehicle from previously stored epoch descriptor
     fn current_vehicle(&self, constellation: &Constellation) -> Result<SV, Error> {
+        self.vehicle(self.vehicle_ptr, constellation)
+    }
+
+    /// Identifies vehicle at given position in previously stored epoch descriptor
+    fn vehicle(&self, index: usize, constellation: &Constellation) -> Result<SV, Error> {
         let sv_size = 3;
-        let epoch_size = 32;
-        let vehicle_offset = self.vehicle_ptr * sv_size;
+        let epoch_size = match self.epoch_descriptor.starts_with('>') {
+            true => 41, // CRINEX3
+            false => 32,
+        };
+        let vehicle_offset = index * sv_size;
         let min = epoch_size + vehicle_offset;
         let max = min + sv_size;
-        let vehicle = &mut self.epoch_descriptor[min..max].trim().to_string();
+        let vehicle = &mut self
+            .epoch_descriptor
+            .get(min..max)
+            .ok_or(Error::VehicleIdentificationError)?
+            .trim()
+            .to_string();
         if let Some(constell_id) = vehicle.chars().nth(0) {
             if constell_id.is_ascii_digit() {
                 // in old RINEX + mono constell context
@@ -119,11 +439,10 @@ impl Compressor {
     fn conclude_vehicle(&mut self, content: &str) -> String {
         let mut result = content.to_string();
         //println!(">>> VEHICULE CONCLUDED"); //DEBUG
-        // conclude line with lli/ssi flags
-        let flags = self.flags_descriptor.trim_end();
-        if !flags.is_empty() {
-            result.push_str(flags);
-        }
+        // conclude line with lli/ssi flags,
+        // trailing blanks are not significant: RNX2CRX strips them
+        result.push_str(&self.flags_descriptor);
+        result.truncate(result.trim_end_matches(' ').len());
         result.push('\n');
         self.flags_descriptor.clear();
         // move to next vehicle
@@ -141,10 +460,72 @@ impl Compressor {
         //println!(">>> EPOCH CONCLUDED \n");
         self.epoch_ptr = 0;
         self.vehicle_ptr = 0;
-        self.epoch_descriptor.clear();
         self.state.reset();
     }
 
+    /// Epoch continuity check, per satellite: kernels of satellites
+    /// missing in the new epoch are dropped, so they are re-initialized ('&')
+    /// when the satellite comes back, instead of differencing across the gap.
+    /// This is how RNX2CRX behaves: CRX2RNX expects an initialization
+    /// for every satellite that was not part of the previous epoch.
+    fn resynchronize(&mut self, constellation: &Constellation) {
+        let mut vehicles = Vec::with_capacity(self.nb_vehicles);
+        for index in 0..self.nb_vehicles {
+            match self.vehicle(index, constellation) {
+                Ok(sv) => vehicles.push(sv),
+                Err(_) => return, // special events: not a list of vehicles
+            }
+        }
+        let missing: Vec<SV> = self
+            .sv_diff
+            .keys()
+            .filter(|sv| !vehicles.contains(sv))
+            .copied()
+            .collect();
+        self.sv_flags.retain(|sv, _| vehicles.contains(sv));
+        for sv in missing {
+            self.sv_diff.remove(&sv);
+            self.forced_init.remove(&sv);
+            if let Some(stats) = &mut self.stats {
+                stats.resynchronizations += 1;
+            }
+        }
+    }
+
+    /// Omitted observation: flags are blank,
+    /// its kernel will be re-initialized when it comes back
+    fn omit_observation(&mut self, sv: SV, index: usize) {
+        self.flags_descriptor.push_str("  ");
+        self.schedule_kernel_init(sv, index);
+    }
+
+    /// Compresses the RINEX3 flags of given vehicle. Like RNX2CRX, flags are
+    /// differenced as a whole, and blank flags are explicit ('&')
+    /// when the vehicle was not observed in the previous epoch
+    fn compress_flags(&mut self, sv: SV) {
+        let flags = std::mem::take(&mut self.flags_descriptor);
+        let kernel = self.sv_flags.entry(sv).or_insert_with(|| {
+            let mut kernel = TextDiff::new();
+            kernel.init(&"&".repeat(flags.len()));
+            kernel
+        });
+        self.flags_descriptor = kernel.compress(&flags);
+    }
+
+    /// Consumes the pending reinitialization of given kernel, if any
+    fn take_forced_init(&mut self, sv: SV, index: usize) -> bool {
+        if let Some(indexes) = self.forced_init.get_mut(&sv) {
+            if let Some(pos) = indexes.iter().position(|i| *i == index) {
+                indexes.remove(pos);
+                if indexes.is_empty() {
+                    self.forced_init.remove(&sv);
+                }
+                return true;
+            }
+        }
+        false
+    }
+
     /// Schedule given kernel for reinitizalition
     /// due to omitted data field.
     /// We only do so if kernel was previously initialized
@@ -163,60 +544,92 @@ impl Compressor {
             }
         }
     }
+    /*
+     * Tracks a kernel event, only if statistics are being gathered
+     */
+    fn track(&mut self, sv: SV, observable: Option<&Observable>, event: KernelEvent) {
+        if let (Some(stats), Some(observable)) = (&mut self.stats, observable) {
+            let stats = stats
+                .observables
+                .entry((sv.constellation, observable.clone()))
+                .or_default();
+            stats.samples += 1;
+            match event {
+                KernelEvent::Init => stats.kernel_inits += 1,
+                KernelEvent::Reset => {
+                    stats.kernel_inits += 1;
+                    stats.kernel_resets += 1;
+                },
+                KernelEvent::Residual(r) => *stats.residuals.entry(r).or_insert(0) += 1,
+            }
+        }
+    }
 
     /// Compresses given RINEX data to CRINEX
     pub fn compress(
         &mut self,
-        _rnx_major: u8,
+        rnx_major: u8,
         observables: &HashMap<Constellation, Vec<Observable>>,
         constellation: &Constellation,
         content: &str,
     ) -> Result<String, Error> {
-        let mut result: String = String::new();
+        let mut result: String = std::mem::take(&mut self.pending);
         let mut lines = content.lines();
+        // line to process again, once the previous vehicle is concluded
+        let mut retry: Option<&str> = None;
 
         loop {
-            let line: &str = match lines.next() {
-                Some(l) => {
-                    if l.trim().is_empty() {
-                        // line completely empty
-                        // ==> determine if we were expecting content
-                        if self.state == State::Body {
-                            // previously active
-                            if self.obs_ptr > 0 {
+            let line: &str = match retry.take() {
+                Some(l) => l,
+                None => match lines.next() {
+                    Some(l) => {
+                        if l.trim().is_empty() {
+                            // line completely empty
+                            // ==> determine if we were expecting content
+                            if self.state == State::Body {
                                 // previously active
-                                // identify current SV
-                                if let Ok(sv) = self.current_vehicle(constellation) {
-                                    // nb of obs for this constellation
-                                    let sv_nb_obs = observables[&sv.constellation].len();
-                                    let nb_missing = std::cmp::min(5, sv_nb_obs - self.obs_ptr);
-                                    //println!("Early empty line - missing {} field(s)", nb_missing); //DEBUG
-                                    for i in 0..nb_missing {
-                                        result.push(' '); // empty whitespace, on each missing observable
-                                                          // to remain retro compatible with official tools
-                                        self.flags_descriptor.push_str("  "); // both missing
-                                        self.schedule_kernel_init(sv, self.obs_ptr + i);
-                                    }
-                                    self.obs_ptr += nb_missing;
-                                    if self.obs_ptr == sv_nb_obs {
-                                        // vehicle completion
-                                        result = self.conclude_vehicle(&result);
-                                    }
+                                if self.obs_ptr > 0 {
+                                    // previously active
+                                    // identify current SV
+                                    if let Ok(sv) = self.current_vehicle(constellation) {
+                                        // nb of obs for this constellation
+                                        let sv_nb_obs = sv_observables(observables, sv)?.len();
+                                        let nb_missing = std::cmp::min(5, sv_nb_obs - self.obs_ptr);
+                                        //println!("Early empty line - missing {} field(s)", nb_missing); //DEBUG
+                                        for i in 0..nb_missing {
+                                            result.push(' '); // empty whitespace, on each missing observable
+                                                              // to remain retro compatible with official tools
+                                            self.omit_observation(sv, self.obs_ptr + i);
+                                        }
+                                        self.obs_ptr += nb_missing;
+                                        if self.obs_ptr == sv_nb_obs {
+                                            // vehicle completion
+                                            result = self.conclude_vehicle(&result);
+                                        }
 
-                                    if nb_missing > 0 {
-                                        continue;
+                                        if nb_missing > 0 {
+                                            continue;
+                                        }
                                     }
                                 }
                             }
                         }
-                    }
-                    l
+                        l
+                    },
+                    None => break, // done iterating
                 },
-                None => break, // done iterating
             };
 
             // println!("\nWorking from LINE : \"{}\"", line); //DEBUG
 
+            // [0*] : special records of an event epoch, passed as is
+            if self.event_records > 0 {
+                self.event_records -= 1;
+                result.push_str(line);
+                result.push('\n');
+                continue;
+            }
+
             // [0] : COMMENTS (special case)
             if is_rinex_comment(line) {
                 if line.contains("RINEX FILE SPLICE") {
@@ -235,271 +648,166 @@ impl Compressor {
             match self.state {
                 State::EpochDescriptor => {
                     if self.epoch_ptr == 0 {
+                        if let Some(n) = event_records(rnx_major, line) {
+                            // event epoch: descriptor is not compressed
+                            // and kernels are left untouched
+                            if rnx_major < 3 {
+                                result.push('&');
+                                result.push_str(line.get(1..).unwrap_or_default());
+                            } else {
+                                result.push_str(line);
+                            }
+                            result.push('\n');
+                            self.event_records = n;
+                            continue;
+                        }
                         // 1st line
                         // identify #systems
-                        self.nb_vehicles = self.determine_nb_vehicles(line)?;
+                        self.nb_vehicles = self.determine_nb_vehicles(rnx_major, line)?;
+                        self.epoch_descriptor.clear();
                     }
                     self.epoch_ptr += 1;
-                    self.epoch_descriptor.push_str(line);
+                    self.push_epoch_descriptor(rnx_major, line)?;
 
-                    //TODO
-                    //pour clock offsets
-                    /*if line.len() > 60-12 {
-                        Some(line.split_at(60-12).1.trim())
-                    } else {
-                        None*/
-                    //TODO
-                    // if we did have clock offset,
-                    //  append in a new line
-                    //  otherwise append a BLANK
-                    self.epoch_descriptor.push('\n');
+                    if self.nb_vehicles == 0 {
+                        // empty epoch (receiver reset..): descriptor is all we have,
+                        // it is emitted right away and there is no body to expect
+                        self.epoch_descriptor = match rnx_major {
+                            1 | 2 => format_epoch_descriptor(&self.epoch_descriptor),
+                            _ => self.epoch_descriptor.clone() + "\n",
+                        };
+                        result.push_str(&self.emit_epoch_descriptor());
+                        self.resynchronize(constellation);
+                        self.conclude_epoch();
+                        continue;
+                    }
+
+                    if rnx_major > 2 {
+                        // RINEX3: satellites are described by the following lines,
+                        // CRINEX descriptor is emitted once the epoch is concluded
+                        self.obs_ptr = 0;
+                        self.vehicle_ptr = 0;
+                        self.flags_descriptor.clear();
+                        self.body.clear();
+                        self.state = State::Body;
+                        continue;
+                    }
 
                     let nb_lines = num_integer::div_ceil(self.nb_vehicles, 12) as u8;
                     if self.epoch_ptr == nb_lines {
                         // end of descriptor
                         // format to CRINEX
                         self.epoch_descriptor = format_epoch_descriptor(&self.epoch_descriptor);
-                        if self.first_epoch {
-                            //println!("INIT EPOCH with \"{}\"", self.epoch_descriptor); //DEBUG
-                            self.epoch_diff.init(&self.epoch_descriptor);
-                            result.push_str(&self.epoch_descriptor);
-                            /////////////////////////////////////
-                            //TODO
-                            //missing clock offset field here
-                            //next line should not always be empty
-                            /////////////////////////////////////
-                            result.push('\n');
-                            self.first_epoch = false;
-                        } else {
-                            result.push_str(
-                                self.epoch_diff.compress(&self.epoch_descriptor).trim_end(),
-                            );
-                            result.push('\n');
-                            /////////////////////////////////////
-                            //TODO
-                            //missing clock offset field here
-                            //next line should not always be empty
-                            /////////////////////////////////////
-                            result.push('\n');
-                        }
+                        result.push_str(&self.emit_epoch_descriptor());
 
                         self.obs_ptr = 0;
                         self.vehicle_ptr = 0;
                         self.flags_descriptor.clear();
+                        self.resynchronize(constellation);
                         self.state = State::Body;
                     }
                 },
                 State::Body => {
+                    let start = result.len();
+                    // identify current satellite: using stored epoch description in RINEX2,
+                    // RINEX3 lines start with the satellite, which completes the descriptor
+                    let (vehicle, line) = match rnx_major {
+                        1 | 2 => (self.current_vehicle(constellation), line),
+                        _ => match (line.get(..3), line.get(3..)) {
+                            (Some(vehicle), Some(rem)) => {
+                                self.epoch_descriptor.push_str(vehicle);
+                                // trailing blanks are not significant
+                                (
+                                    SV::from_str(vehicle.trim()).map_err(Error::from),
+                                    rem.trim_end(),
+                                )
+                            },
+                            _ => return Err(Error::VehicleIdentificationError),
+                        },
+                    };
                     // nb of obs in this line
-                    let nb_obs_line = num_integer::div_ceil(line.len(), 17);
-                    // identify current satellite using stored epoch description
-                    if let Ok(sv) = self.current_vehicle(constellation) {
+                    let nb_obs_line = match rnx_major {
+                        1 | 2 => num_integer::div_ceil(line.len(), 17),
+                        _ => num_integer::div_ceil(line.len(), 16),
+                    };
+                    if let Ok(sv) = vehicle {
                         // nb of obs for this constellation
-                        let sv_nb_obs = observables[&sv.constellation].len();
-                        if self.obs_ptr + nb_obs_line > sv_nb_obs {
+                        let sv_nb_obs = sv_observables(observables, sv)?.len();
+                        if rnx_major < 3 && self.obs_ptr + nb_obs_line > sv_nb_obs {
                             // facing an overflow
                             // this means all final fields were omitted,
                             // ==> handle this case
                             //println!("SV {} final fields were omitted", sv); //DEBUG
-                            for index in self.obs_ptr..sv_nb_obs + 1 {
-                                self.schedule_kernel_init(sv, index);
+                            for index in self.obs_ptr..sv_nb_obs {
                                 result.push(' '); // put an empty space on missing observables
                                                   // this is how RNX2CRX (official) behaves,
                                                   // if we don't do this we break retro compatibility
-                                self.flags_descriptor.push_str("  ");
+                                self.omit_observation(sv, index);
                             }
                             result = self.conclude_vehicle(&result);
-                            if self.state == State::EpochDescriptor {
-                                // epoch got also concluded
-                                // --> rewind fsm
-                                self.nb_vehicles = self.determine_nb_vehicles(line)?;
-                                self.epoch_ptr = 1; // we already have a new descriptor
-                                self.epoch_descriptor.push_str(line);
-                                self.epoch_descriptor.push('\n');
-                                continue; // avoid end of this loop,
-                                          // as this vehicle is now concluded
-                            }
+                            // this line describes the following vehicle,
+                            // or the following epoch if this one got concluded
+                            retry = Some(line);
+                            continue;
                         }
 
                         // compress all observables
                         // and store flags for line completion
+                        let sv_observables = sv_observables(observables, sv)?;
                         let mut observables = line;
                         for _ in 0..nb_obs_line {
-                            let index = std::cmp::min(16, observables.len()); // avoid overflow
-                                                                              // as some data flags might be omitted
-                            let (data, rem) = observables.split_at(index);
-                            let (obsdata, flags) = data.split_at(14);
+                            // some data flags might be omitted
+                            let (data, rem) = fieldslice::split_at(observables, 16)?;
+                            let (obsdata, flags) = fieldslice::split_at(data, 14)?;
                             observables = rem;
-                            if let Ok(obsdata) = obsdata.trim().parse::<f64>() {
-                                let obsdata = f64::round(obsdata * 1000.0) as i64;
-                                if flags.trim().is_empty() {
-                                    // Both Flags ommited
-                                    //println!("OBS \"{}\" LLI \"X\" SSI \"X\"", obsdata); //DEBUG
-                                    // data compression
-                                    if let Some(sv_diffs) = self.sv_diff.get_mut(&sv) {
-                                        // retrieve observable state
-                                        if let Some(diffs) = sv_diffs.get_mut(&self.obs_ptr) {
-                                            let compressed: i64;
-                                            // forced re/init is pending
-                                            if let Some(indexes) = self.forced_init.get_mut(&sv) {
-                                                if indexes.contains(&self.obs_ptr) {
-                                                    // forced reinit pending
-                                                    compressed = obsdata;
-                                                    diffs.0.init(3, obsdata).unwrap();
-                                                    diffs.1.init(" ");
-                                                    diffs.2.init(" ");
-                                                    //println!("FORCED REINIT WITH FLAGS \"{}\"", self.flags_descriptor); //DEBUG
-                                                    result.push_str(&format!("3&{} ", compressed)); //append obs
-                                                                                                    // remove from pending list,
-                                                                                                    // so we only force it once
-                                                    for i in 0..indexes.len() {
-                                                        if indexes[i] == self.obs_ptr {
-                                                            indexes.remove(i);
-                                                            break;
-                                                        }
-                                                    }
-                                                    if indexes.is_empty() {
-                                                        self.forced_init.remove(&sv);
-                                                    }
-                                                } else {
-                                                    // compress data
-                                                    compressed = diffs.0.compress(obsdata);
-                                                    result.push_str(&format!("{} ", compressed));
-                                                    //append obs
-                                                }
-                                            } else {
-                                                // compress data
-                                                compressed = diffs.0.compress(obsdata);
-                                                result.push_str(&format!("{} ", compressed));
-                                                //append obs
-                                            }
-
-                                            let _ = diffs.1.compress(" ");
-                                            let _ = diffs.2.compress(" ");
-                                            // ==> empty flags fields
-                                            self.flags_descriptor.push_str("  ");
-                                        } else {
-                                            // first time dealing with this observable
-                                            let mut diff: (NumDiff, TextDiff, TextDiff) = (
+                            // fixed point value, extracted from the field digits
+                            let quantized = match sv_observables.get(self.obs_ptr) {
+                                Some(observable) => {
+                                    self.precision.quantize_str(observable, obsdata)
+                                },
+                                None if obsdata.trim().is_empty() => None,
+                                None => return Err(Error::MalformedEpochBody), // too many observables
+                            };
+                            if let Some(obsdata) = quantized {
+                                // omitted flags are blank
+                                let lli = flags.get(..1).unwrap_or(" ");
+                                let ssi = flags.get(1..2).unwrap_or(" ");
+                                let ptr = self.obs_ptr;
+                                let forced = self.take_forced_init(sv, ptr);
+                                let (kernels, fresh) =
+                                    match self.sv_diff.entry(sv).or_default().entry(ptr) {
+                                        Entry::Occupied(entry) => (entry.into_mut(), false),
+                                        Entry::Vacant(entry) => (
+                                            entry.insert((
                                                 NumDiff::new(NumDiff::MAX_COMPRESSION_ORDER)?,
                                                 TextDiff::new(),
                                                 TextDiff::new(),
-                                            );
-                                            diff.0.init(3, obsdata).unwrap();
-                                            result.push_str(&format!("3&{} ", obsdata)); //append obs
-                                            diff.1.init(" "); // BLANK
-                                            diff.2.init(" "); // BLANK
-                                            self.flags_descriptor.push_str("  ");
-                                            sv_diffs.insert(self.obs_ptr, diff);
-                                        }
-                                    } else {
-                                        // first time dealing with this vehicle
-                                        let mut diff: (NumDiff, TextDiff, TextDiff) = (
-                                            NumDiff::new(NumDiff::MAX_COMPRESSION_ORDER)?,
-                                            TextDiff::new(),
-                                            TextDiff::new(),
-                                        );
-                                        diff.0.init(3, obsdata).unwrap();
-                                        result.push_str(&format!("3&{} ", obsdata)); //append obs
-                                        diff.1.init(" "); // BLANK
-                                        diff.2.init(" "); // BLANK
-                                        self.flags_descriptor.push_str("  ");
-                                        let mut map: HashMap<usize, (NumDiff, TextDiff, TextDiff)> =
-                                            HashMap::new();
-                                        map.insert(self.obs_ptr, diff);
-                                        self.sv_diff.insert(sv, map);
-                                    }
+                                            )),
+                                            true,
+                                        ),
+                                    };
+                                let (compressed, event) = if fresh {
+                                    // first time dealing with this observable
+                                    reset_kernels(kernels, obsdata)?;
+                                    (format!("3&{} ", obsdata), KernelEvent::Init)
+                                } else if forced {
+                                    reset_kernels(kernels, obsdata)?;
+                                    (format!("3&{} ", obsdata), KernelEvent::Reset)
                                 } else {
-                                    //flags.len() >=1 : Not all Flags ommited
-                                    let (lli, ssi) = flags.split_at(1);
-                                    //println!("OBS \"{}\" - LLI \"{}\" - SSI \"{}\"", obsdata, lli, ssi); //DEBUG
-                                    if let Some(sv_diffs) = self.sv_diff.get_mut(&sv) {
-                                        // retrieve observable state
-                                        if let Some(diffs) = sv_diffs.get_mut(&self.obs_ptr) {
-                                            // compress data
-                                            let compressed: i64;
-                                            // forced re/init is pending
-                                            if let Some(indexes) = self.forced_init.get_mut(&sv) {
-                                                if indexes.contains(&self.obs_ptr) {
-                                                    // forced init pending
-                                                    compressed = obsdata;
-                                                    result.push_str(&format!("3&{} ", compressed));
-                                                    diffs.0.init(3, obsdata).unwrap();
-                                                    // remove from pending list,
-                                                    // so we only force it once
-                                                    for i in 0..indexes.len() {
-                                                        if indexes[i] == self.obs_ptr {
-                                                            indexes.remove(i);
-                                                            break;
-                                                        }
-                                                    }
-                                                    if indexes.is_empty() {
-                                                        self.forced_init.remove(&sv);
-                                                    }
-                                                } else {
-                                                    compressed = diffs.0.compress(obsdata);
-                                                    result.push_str(&format!("{} ", compressed));
-                                                }
-                                            } else {
-                                                compressed = diffs.0.compress(obsdata);
-                                                result.push_str(&format!("{} ", compressed));
-                                            }
-
-                                            let lli = diffs.1.compress(lli);
-                                            self.flags_descriptor.push_str(&lli);
-
-                                            let ssi = diffs.2.compress(ssi);
-                                            self.flags_descriptor.push_str(&ssi);
-                                        } else {
-                                            // first time dealing with this observable
-                                            let mut diff: (NumDiff, TextDiff, TextDiff) = (
-                                                NumDiff::new(NumDiff::MAX_COMPRESSION_ORDER)?,
-                                                TextDiff::new(),
-                                                TextDiff::new(),
-                                            );
-                                            diff.0.init(3, obsdata).unwrap();
-                                            diff.1.init(lli);
-                                            diff.2.init(ssi);
-                                            result.push_str(&format!("3&{} ", obsdata)); //append obs
-                                            if !lli.is_empty() {
-                                                self.flags_descriptor.push_str(lli);
-                                            } else {
-                                                self.flags_descriptor.push(' ');
-                                            }
-
-                                            if !ssi.is_empty() {
-                                                self.flags_descriptor.push_str(ssi);
-                                            } else {
-                                                // SSI omitted
-                                                self.flags_descriptor.push(' ');
-                                            }
-                                            sv_diffs.insert(self.obs_ptr, diff);
-                                        }
-                                    } else {
-                                        // first time dealing with this vehicle
-                                        let mut diff: (NumDiff, TextDiff, TextDiff) = (
-                                            NumDiff::new(NumDiff::MAX_COMPRESSION_ORDER)?,
-                                            TextDiff::new(),
-                                            TextDiff::new(),
-                                        );
-                                        diff.0.init(3, obsdata).unwrap();
-                                        result.push_str(&format!("3&{} ", obsdata)); //append obs
-                                        diff.1.init(lli);
-                                        diff.2.init(ssi);
-                                        self.flags_descriptor.push_str(lli);
-                                        if !ssi.is_empty() {
-                                            self.flags_descriptor.push_str(ssi);
-                                        } else {
-                                            // SSI omitted
-                                            diff.2.init(" "); // BLANK
-                                            self.flags_descriptor.push(' ');
-                                        }
-                                        let mut map: HashMap<usize, (NumDiff, TextDiff, TextDiff)> =
-                                            HashMap::new();
-                                        map.insert(self.obs_ptr, diff);
-                                        self.sv_diff.insert(sv, map);
-                                    }
+                                    compress_observation(kernels, obsdata)?
+                                };
+                                result.push_str(&compressed);
+                                if rnx_major > 2 {
+                                    // compressed once the vehicle is concluded
+                                    self.flags_descriptor.push_str(lli);
+                                    self.flags_descriptor.push_str(ssi);
+                                } else {
+                                    let lli = kernels.1.compress(lli);
+                                    self.flags_descriptor.push_str(&lli);
+                                    let ssi = kernels.2.compress(ssi);
+                                    self.flags_descriptor.push_str(&ssi);
                                 }
+                                self.track(sv, sv_observables.get(ptr), event);
                             } else {
                                 //obsdata::f64::from_str()
                                 // when floating point parsing is in failure,
@@ -507,8 +815,7 @@ impl Compressor {
                                 result.push(' '); // put an empty space on missing observables
                                                   // this is how RNX2CRX (official) behaves,
                                                   // if we don't do this we break retro compatibility
-                                self.flags_descriptor.push_str("  ");
-                                self.schedule_kernel_init(sv, self.obs_ptr);
+                                self.omit_observation(sv, self.obs_ptr);
                             }
                             self.obs_ptr += 1;
                             //println!("OBS {}/{}", self.obs_ptr, sv_nb_obs); //DEBUG
@@ -519,6 +826,17 @@ impl Compressor {
                             }
                         } //for i..nb_obs in this line
 
+                        if rnx_major > 2 {
+                            // RINEX3: vehicle is described in a single line,
+                            // final fields might be omitted
+                            for index in self.obs_ptr..sv_nb_obs {
+                                result.push(' ');
+                                self.omit_observation(sv, index);
+                            }
+                            self.obs_ptr = sv_nb_obs;
+                            self.compress_flags(sv);
+                        }
+
                         if self.obs_ptr == sv_nb_obs {
                             // vehicle completion
                             result = self.conclude_vehicle(&result);
@@ -528,12 +846,47 @@ impl Compressor {
                         // failed to identify which vehicle we're dealing with
                         return Err(Error::VehicleIdentificationError);
                     }
+
+                    if rnx_major > 2 {
+                        // RINEX3: body is held until the epoch is concluded,
+                        // because the descriptor lists all satellites
+                        let body = result.split_off(start);
+                        self.body.push_str(&body);
+                        if self.state == State::EpochDescriptor {
+                            self.epoch_descriptor.push('\n');
+                            result.push_str(&self.emit_epoch_descriptor());
+                            result.push_str(&self.body);
+                            self.body.clear();
+                            self.resynchronize(constellation);
+                        }
+                    }
                 },
             } //match(state)
         } //main loop
-        Ok(result)
+        if rnx_major < 3 && self.state == State::Body && self.obs_ptr > 0 {
+            // RINEX2 vehicle continues on the next line: its trailing blanks
+            // can only be stripped once concluded
+            let offset = result.rfind('\n').map(|i| i + 1).unwrap_or(0);
+            self.pending = result.split_off(offset);
+        }
+        Ok(self.line_ending.apply(&result))
     }
     //notes:
     //si le flag est absent: "&" pour insérer un espace
     //tous les flags sont foutus a la fin en guise de dernier mot
 }
+
+impl HatanakaCodec for Compressor {
+    type State = State;
+    fn process(&mut self, header: &Header, content: &str) -> Result<String, Error> {
+        let obs = header.obs.as_ref().ok_or(Error::NotObsRinexData)?;
+        let constellation = header.constellation.unwrap_or(Constellation::Mixed);
+        self.compress(header.version.major, &obs.codes, &constellation, content)
+    }
+    fn reset(&mut self) {
+        *self = Self::with_precision(self.precision).with_line_ending(self.line_ending);
+    }
+    fn state(&self) -> State {
+        self.state
+    }
+}
@@ -1,8 +1,11 @@
 //! RINEX decompression module
-use super::{numdiff::NumDiff, textdiff::TextDiff, Error};
-use crate::{is_rinex_comment, prelude::*};
+use super::{
+    event_records, numdiff::NumDiff, textdiff::TextDiff, EpochBlock, Error, HatanakaCodec,
+};
+use crate::{fieldslice, formatter::Precision, is_rinex_comment, observation, prelude::*};
 
 use std::collections::HashMap;
+use std::fmt::Write;
 use std::str::FromStr;
 
 #[derive(Default, Debug, Clone)]
@@ -14,11 +17,14 @@ pub enum State {
 }
 
 /// Structure to decompress CRINEX data
+#[derive(Clone)]
 pub struct Decompressor {
     /// finite state machine
     state: State,
     /// True only for first epoch ever processed
     first_epoch: bool,
+    /// special records of the current event epoch, passed as is
+    event_records: usize,
     /// Epoch differentiator
     epoch_diff: TextDiff,
     /// recovered but unformatted CRINEX is stored
@@ -32,6 +38,8 @@ pub struct Decompressor {
     nb_sv: usize, // sv_ptr range
     /// Vehicle differentiators
     sv_diff: HashMap<SV, Vec<(NumDiff, TextDiff, TextDiff)>>,
+    /// Observations quantization policy
+    precision: Precision,
 }
 
 /// Reworks given content to match RINEX specifications
@@ -48,13 +56,15 @@ fn format_epoch(
             // old RINEX
             // append Systems #ID,
             //  on as many lines as needed
-            let min_size = 32 + 3; // epoch descriptor + at least one vehicle
+            // epoch descriptor, possibly followed by vehicles:
+            // empty epochs do exist (receiver resets..)
+            let min_size = 32;
             if content.len() < min_size {
                 // parsing would fail
                 return Err(Error::FaultyRecoveredEpoch);
             }
 
-            let (epoch, systems) = content.split_at(32); // grab epoch
+            let (epoch, systems) = fieldslice::split_at(content, min_size)?; // grab epoch
             result.push_str(&epoch.replace('&', " ")); // rework
 
             //CRINEX has systems squashed in a single line
@@ -64,7 +74,13 @@ fn format_epoch(
                 // fits in a single line
                 result.push_str(systems);
                 if let Some(value) = clock_offset {
-                    result.push_str(&format!("  {:3.9}", (value as f64) / 1000.0_f64))
+                    // F12.9 in columns 69-80
+                    result.push_str(&format!(
+                        "{:>width$}",
+                        "",
+                        width = 68_usize.saturating_sub(result.len())
+                    ));
+                    result.push_str(&format!("{:12.9}", value as f64 * 1.0E-9));
                 }
             } else {
                 // does not fit in a single line
@@ -75,7 +91,7 @@ fn format_epoch(
                         if i == 12 {
                             // first line,
                             if let Some(value) = clock_offset {
-                                result.push_str(&format!("  {:3.9}", (value as f64) / 1000.0_f64))
+                                result.push_str(&format!("{:12.9}", value as f64 * 1.0E-9));
                             }
                         }
                         // tab indent
@@ -84,9 +100,9 @@ fn format_epoch(
                     /*
                      * avoids overflowing
                      */
-                    let min_offset = i * 3;
+                    let min_offset = std::cmp::min(i * 3, systems.len());
                     let max_offset = std::cmp::min(min_offset + 3, systems.len());
-                    result.push_str(&systems[min_offset..max_offset]);
+                    result.push_str(systems.get(min_offset..max_offset).unwrap_or_default());
                     index += 1;
                 }
             }
@@ -98,11 +114,11 @@ fn format_epoch(
                 // parsing would fail
                 return Err(Error::FaultyRecoveredEpoch);
             }
-            let (epoch, _) = content.split_at(35);
+            let epoch = fieldslice::exact(content, 0, 35)?;
             result.push_str(&epoch.replace('&', " "));
-            //TODO clock offset
             if let Some(value) = clock_offset {
-                result.push_str(&format!("         {:3.12}", (value as f64) / 1000.0_f64))
+                // F15.12 in columns 42-56
+                result.push_str(&format!("      {:15.12}", value as f64 * 1.0E-12));
             }
         },
     }
@@ -120,6 +136,7 @@ impl Decompressor {
     pub fn new() -> Self {
         Self {
             first_epoch: true,
+            event_records: 0,
             state: State::default(),
             epoch_diff: TextDiff::new(),
             epoch_descriptor: String::with_capacity(128),
@@ -128,16 +145,92 @@ impl Decompressor {
             nb_sv: 0,
             sv_ptr: 0,
             sv_diff: HashMap::new(), // init. later
+            precision: Precision::default(),
         }
     }
+    /// Builds a [Decompressor] that recovers observations
+    /// with given [Precision] policy, which must match the compression policy.
+    pub fn with_precision(precision: Precision) -> Self {
+        let mut s = Self::new();
+        s.precision = precision;
+        s
+    }
+    /// Decompresses exactly one epoch, following given CRINEX [Header] definitions.
+    /// `content` is the complete compressed epoch: epoch descriptor,
+    /// clock offset line and observations. This is the safe boundary
+    /// for real-time applications, that do not have to deal with partial content.
+    /// Successive epochs must be decompressed with the same [Decompressor].
+    pub fn decompress_epoch(
+        &mut self,
+        header: &Header,
+        content: &str,
+    ) -> Result<EpochBlock, Error> {
+        let obs = header.obs.as_ref().ok_or(Error::NotObsRinexData)?;
+        let crinex = obs.crinex.as_ref().ok_or(Error::NotACrinex)?;
+        let constellation = header
+            .constellation
+            .as_ref()
+            .ok_or(Error::UndefinedConstellation)?;
+        let mut recovered = String::new();
+        for line in content.lines() {
+            if is_rinex_comment(line) {
+                continue;
+            }
+            // one line at a time: empty lines (missing clock offset) are meaningful
+            let line = line.to_owned() + "\n";
+            let content = self.decompress(
+                crinex.version.major,
+                constellation,
+                header.version.major,
+                &obs.codes,
+                &line,
+            )?;
+            if !content.is_empty() {
+                recovered.push_str(content.trim_end_matches('\n'));
+                recovered.push('\n');
+            }
+        }
+        if !matches!(self.state, State::EpochDescriptor) {
+            return Err(Error::IncompleteEpoch);
+        }
+        let ts = obs
+            .time_of_first_obs
+            .map(|t| t.time_scale)
+            .or_else(|| constellation.timescale())
+            .unwrap_or_default();
+        let ((epoch, flag), clock_offset, vehicles) =
+            observation::record::parse_epoch(header, &recovered, ts)?;
+        Ok(EpochBlock {
+            epoch,
+            flag,
+            clock_offset,
+            vehicles,
+        })
+    }
+    /*
+     * Returns true if given compressed line starts a new epoch:
+     * decompression may resume right before this line
+     */
+    pub(crate) fn is_epoch_start(&self, line: &str) -> bool {
+        matches!(self.state, State::EpochDescriptor)
+            && self.event_records == 0
+            && !is_rinex_comment(line)
+            && (self.first_epoch || !line.starts_with("> "))
+    }
     /*
-        fn reset(&mut self) {
-            // are we sure this is enough ?
-            // special comment markers, like "MERGE" and "SPLICE"
-            //  are they to be encountered inside an epoch ?
-            self.state = State::default();
+     * Returns the number of special records that follow given compressed line,
+     * when it is the (uncompressed) descriptor of an event epoch
+     */
+    pub(crate) fn event_start(&self, rnx_major: u8, line: &str) -> Option<usize> {
+        if matches!(self.state, State::EpochDescriptor)
+            && self.event_records == 0
+            && (line.starts_with('&') || line.starts_with('>'))
+        {
+            event_records(rnx_major, line)
+        } else {
+            None
         }
-    */
+    }
     fn parse_nb_sv(content: &str, crx_major: u8) -> Option<usize> {
         let mut offset: usize = 2    // Y
             +2+1 // m
@@ -155,8 +248,7 @@ impl Decompressor {
             offset += 2; // YYYY on 4 digits
         }
 
-        let (_, rem) = content.split_at(offset);
-        let (n, _) = rem.split_at(3);
+        let n = content.get(offset..offset + 3)?;
         if let Ok(n) = u16::from_str_radix(n.trim(), 10) {
             Some(n.into())
         } else {
@@ -169,12 +261,15 @@ impl Decompressor {
         if let Some(sv_diff) = self.sv_diff.get_mut(&sv) {
             for index in 0..content.len() {
                 if let Some(sv_obs) = sv_diff.get_mut(index / 2) {
-                    if index % 2 == 0 {
-                        // LLI
-                        let _ = sv_obs.1.decompress(&content[index..index + 1]);
-                    } else {
-                        //SSI
-                        let _ = sv_obs.2.decompress(&content[index..index + 1]);
+                    // non ASCII flags are dropped
+                    if let Ok(flag) = fieldslice::field(content, index, index + 1) {
+                        if index % 2 == 0 {
+                            // LLI
+                            let _ = sv_obs.1.decompress(flag);
+                        } else {
+                            //SSI
+                            let _ = sv_obs.2.decompress(flag);
+                        }
                     }
                 }
             }
@@ -192,9 +287,10 @@ impl Decompressor {
             1 => std::cmp::min(32 + 3 * (sv_ptr + 1), epoch.len()), // overflow protection
             _ => std::cmp::min(41 + 3 * (sv_ptr + 1), epoch.len()), // overflow protection
         };
-        let system = epoch.split_at(offset).0;
-        let (_, svnn) = system.split_at(system.len() - 3); // last 3 XXX
-        let svnn = svnn.trim();
+        // last 3 XXX
+        let svnn = fieldslice::field(epoch, offset.saturating_sub(3), offset)
+            .ok()?
+            .trim();
         match crx_major > 2 {
             false => {
                 // OLD
@@ -209,7 +305,8 @@ impl Decompressor {
                     },
                     constellation => {
                         // OLD + FIXED: constellation might be omitted.......
-                        if let Ok(prn) = u8::from_str_radix(svnn[1..].trim(), 10) {
+                        let prn = fieldslice::remainder(svnn, 1).ok()?;
+                        if let Ok(prn) = u8::from_str_radix(prn.trim(), 10) {
                             Some(SV {
                                 prn,
                                 constellation: *constellation,
@@ -242,8 +339,31 @@ impl Decompressor {
         observables: &HashMap<Constellation, Vec<Observable>>,
         content: &str,
     ) -> Result<String, Error> {
+        let mut result = String::new();
+        self.decompress_into(
+            crx_major,
+            crx_constell,
+            rnx_major,
+            observables,
+            content,
+            &mut result,
+        )?;
+        Ok(result)
+    }
+    /*
+     * Decompresses given CRINEX content, appending the recovered
+     * RINEX content to `result`, so the caller can reuse its buffer.
+     */
+    pub(crate) fn decompress_into(
+        &mut self,
+        crx_major: u8,
+        crx_constell: &Constellation,
+        rnx_major: u8,
+        observables: &HashMap<Constellation, Vec<Observable>>,
+        content: &str,
+        result: &mut String,
+    ) -> Result<(), Error> {
         // content browser
-        let mut result: String = String::new();
         let mut lines = content.lines();
         loop {
             // browse all provided lines
@@ -255,6 +375,14 @@ impl Decompressor {
             //println!("DECOMPRESSING - \"{}\"", line); //DEBUG
             //println!("state: {:?}", self.state);
 
+            // [0*] : special records of an event epoch, passed as is
+            if self.event_records > 0 {
+                self.event_records -= 1;
+                result.push_str(line);
+                result.push('\n');
+                continue;
+            }
+
             // [0] : COMMENTS (special case)
             if is_rinex_comment(line) {
                 //if line.contains("RINEX FILE SPLICE") {
@@ -262,19 +390,24 @@ impl Decompressor {
                 //      merged RINEX Files
                 //    self.reset();
                 //}
-                result // feed content as is
-                    .push_str(line);
+                result.push_str(line); // feed content as is
                 result.push('\n');
                 continue; // move to next line
             }
 
             // [0*]: special epoch events
-            //       with uncompressed descriptor
-            //       (CRNX3)
-            if line.starts_with("> ") && !self.first_epoch {
-                result // feed content as is
-                    .push_str(line);
+            //       with uncompressed descriptor,
+            //       kernels are left untouched
+            if let Some(n) = self.event_start(rnx_major, line) {
+                if crx_major < 3 {
+                    // CRNX1 marker
+                    result.push(' ');
+                    result.push_str(line.get(1..).unwrap_or_default());
+                } else {
+                    result.push_str(line);
+                }
                 result.push('\n');
+                self.event_records = n;
                 continue; // move to next line
             }
 
@@ -300,6 +433,9 @@ impl Decompressor {
                         // from this entire line
                         self.epoch_diff.init(line.trim_end());
                         self.first_epoch = false;
+                    } else if line.starts_with('>') || line.starts_with('&') {
+                        // kernel re-initialization
+                        self.epoch_diff.init(line.trim_end());
                     } else {
                         /*
                          * this latches the current line content
@@ -318,11 +454,12 @@ impl Decompressor {
                     let mut clock_offset: Option<i64> = None;
                     if line.contains('&') {
                         // clock offset kernel (re)init
-                        let (n, rem) = line.split_at(1);
+                        let (n, rem) = fieldslice::split_at(line, 1)?;
                         if let Ok(order) = u8::from_str_radix(n, 10) {
-                            let (_, value) = rem.split_at(1);
+                            let (_, value) = fieldslice::split_at(rem, 1)?;
                             if let Ok(value) = i64::from_str_radix(value, 10) {
                                 self.clock_diff.init(order.into(), value)?;
+                                clock_offset = Some(value);
                             } else {
                                 return Err(Error::ClockOffsetValueError);
                             }
@@ -332,7 +469,8 @@ impl Decompressor {
                     } else {
                         // --> nominal clock offset line
                         if let Ok(value) = i64::from_str_radix(line.trim(), 10) {
-                            clock_offset = Some(value); // latch for later
+                            // latch for later
+                            clock_offset = Some(self.clock_diff.decompress(value));
                         }
                     }
 
@@ -358,12 +496,18 @@ impl Decompressor {
                         format_epoch(rnx_major, self.nb_sv, recovered, clock_offset)
                     {
                         //println!("--- EPOCH --- \n{}[STOP]", descriptor.trim_end()); //DEBUG
-                        result.push_str(&format!("{}\n", descriptor.trim_end()));
+                        result.push_str(descriptor.trim_end());
+                        result.push('\n');
                     } else {
                         return Err(Error::EpochConstruct);
                     }
 
-                    self.state = State::Body;
+                    if self.nb_sv == 0 {
+                        // empty epoch: concluded right away
+                        self.state = State::EpochDescriptor;
+                    } else {
+                        self.state = State::Body;
+                    }
                 }, // state::ClockOffsetDescriptor
 
                 State::Body => {
@@ -378,7 +522,7 @@ impl Decompressor {
                                           // vehicles are always described in a single line
                         if rnx_major > 2 {
                             // RNX3 needs SVNN on every line
-                            result.push_str(&format!("{} ", sv));
+                            let _ = write!(result, "{} ", sv);
                         }
                         /*
                          * Build compress tools in case this vehicle is new
@@ -440,6 +584,11 @@ impl Decompressor {
                                                     sv_diff[obs_ptr]
                                                         .0 // observations only, at this point
                                                         .init(order.into(), data)?;
+                                                    if crx_major < 3 {
+                                                        // CRINEX1: flags are described as is
+                                                        sv_diff[obs_ptr].1.init(" ");
+                                                        sv_diff[obs_ptr].2.init(" ");
+                                                    }
                                                     observations.push(Some(data));
                                                 }
                                             } else {
@@ -473,6 +622,10 @@ impl Decompressor {
                                                 sv_diff[obs_ptr]
                                                     .0 // observations only, at this point
                                                     .init(order.into(), data)?;
+                                                if crx_major < 3 {
+                                                    sv_diff[obs_ptr].1.init(" ");
+                                                    sv_diff[obs_ptr].2.init(" ");
+                                                }
                                                 observations.push(Some(data));
                                             }
                                         } else {
@@ -502,8 +655,20 @@ impl Decompressor {
                          *   into a single formatted line
                          *   or into several in case of OLD RINEX
                          */
+                        let codes = match sv.constellation.is_sbas() {
+                            true => observables.get(&Constellation::SBAS),
+                            false => observables.get(&sv.constellation),
+                        };
+                        if rnx_major < 3 {
+                            // old RINEX: omitted trailing observations
+                            // still occupy their (blank) lines
+                            if let Some(codes) = codes {
+                                observations.resize(codes.len().max(observations.len()), None);
+                            }
+                        }
                         for (index, data) in observations.iter().enumerate() {
                             if let Some(data) = data {
+                                let observable = codes.and_then(|codes| codes.get(index));
                                 let sv_diff = self.sv_diff.get_mut(&sv).unwrap(); //cant fail at this point
                                 let lli = sv_diff[index]
                                     .1 // LLI
@@ -519,12 +684,22 @@ impl Decompressor {
                                     // Another option would be to have an array to
                                     // store them
                                     .to_string();
-                                result.push_str(&format!(
-                                    "{:13.3}{}{} ",
-                                    *data as f64 / 1000_f64,
-                                    lli,
-                                    ssi
-                                )); //F14.3
+                                let (value, decimals) = match observable {
+                                    Some(observable) => (
+                                        self.precision.dequantize(observable, *data),
+                                        self.precision.decimals(observable) as usize,
+                                    ),
+                                    None => (*data as f64 / 1000_f64, 3),
+                                };
+                                if rnx_major < 3 {
+                                    // old RINEX lines are not prefixed by SVNN:
+                                    // F14.3 must be fully described here
+                                    let _ =
+                                        write!(result, "{:14.*}{}{}", decimals, value, lli, ssi);
+                                } else {
+                                    let _ =
+                                        write!(result, "{:13.*}{}{} ", decimals, value, lli, ssi);
+                                }
                             } else {
                                 result.push_str("                "); // BLANK
                             }
@@ -537,7 +712,10 @@ impl Decompressor {
                                 }
                             }
                         }
-                        //result.push_str("\n");
+                        if rnx_major > 2 || observations.len().rem_euclid(5) != 0 {
+                            // conclude (last) line of this vehicle
+                            result.push('\n');
+                        }
                     }
                     // end of line parsing
                     //  if sv_ptr has reached the expected amount of vehicles
@@ -549,6 +727,28 @@ impl Decompressor {
             } //match(state)
         } //loop
           //println!("--- TOTAL DECOMPRESSED --- \n\"{}\"", result); //DEBUG
-        Ok(result)
+        Ok(())
+    }
+}
+
+impl HatanakaCodec for Decompressor {
+    type State = State;
+    fn process(&mut self, header: &Header, content: &str) -> Result<String, Error> {
+        let obs = header.obs.as_ref().ok_or(Error::NotObsRinexData)?;
+        let crinex = obs.crinex.as_ref().ok_or(Error::NotACrinex)?;
+        let constellation = header.constellation.unwrap_or(Constellation::Mixed);
+        self.decompress(
+            crinex.version.major,
+            &constellation,
+            header.version.major,
+            &obs.codes,
+            content,
+        )
+    }
+    fn reset(&mut self) {
+        *self = Self::with_precision(self.precision);
+    }
+    fn state(&self) -> State {
+        self.state.clone()
     }
 }
@@ -0,0 +1,275 @@
+//! CRINEX random access
+use super::{Decompressor, Error};
+use crate::{is_rinex_comment, observation, prelude::*, reader::BufferedReader, record::Record};
+use std::collections::{BTreeMap, HashMap};
+use std::io::BufRead;
+
+/// [`Checkpoint`] is a location where CRINEX decompression may resume:
+/// byte offset of an epoch and state of the decompression kernels at that point.
+#[derive(Clone)]
+pub struct Checkpoint {
+    /// First epoch recovered from this location
+    pub epoch: Epoch,
+    /// Byte offset of this location, counted from the end of the header
+    /// (in the decompressed stream, for gzip compressed files)
+    pub offset: u64,
+    decompressor: Decompressor,
+}
+
+impl std::fmt::Debug for Checkpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Checkpoint")
+            .field("epoch", &self.epoch)
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+/// [`CrinexIndex`] is built by decompressing a CRINEX file once,
+/// and records a [`Checkpoint`] every few epochs.
+/// Any time window can then be recovered by resuming decompression
+/// at the closest preceding [`Checkpoint`], rather than from the beginning of the file.
+/// gzip compressed files are supported, but the gzip layer is not seekable:
+/// it still needs to be decoded up to the [`Checkpoint`], which is much faster
+/// than CRINEX decompression.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::hatanaka::CrinexIndex;
+/// use std::str::FromStr;
+/// let path = "../test_resources/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz";
+/// let index = CrinexIndex::build(path, 100)
+///     .unwrap();
+/// assert_eq!(index.len(), 29);
+/// let start = Epoch::from_str("2020-06-25T12:00:00 GPST").unwrap();
+/// let end = Epoch::from_str("2020-06-25T12:10:00 GPST").unwrap();
+/// let window = index.decompress_window(start, end)
+///     .unwrap();
+/// assert_eq!(window.epoch().count(), 21);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CrinexIndex {
+    path: String,
+    checkpoints: Vec<Checkpoint>,
+}
+
+/*
+ * Decompression resumption point, epoch to be determined
+ */
+type Candidate = (u64, Decompressor);
+
+type ObsEpoch = (
+    Option<f64>,
+    BTreeMap<SV, HashMap<Observable, observation::ObservationData>>,
+);
+
+/*
+ * Observations recovered from a CRINEX stream, one epoch at a time
+ */
+struct EpochRecovery {
+    header: Header,
+    ts: TimeScale,
+    crx_major: u8,
+    constellation: Constellation,
+    codes: HashMap<Constellation, Vec<Observable>>,
+    decompressor: Decompressor,
+    content: String,
+    /// special records of an event epoch, still to be skipped
+    event_records: usize,
+    /// resumption point preceding the next epoch
+    candidate: Option<Candidate>,
+    /// resumption point preceding the pending epoch
+    current: Option<Candidate>,
+}
+
+impl EpochRecovery {
+    fn new(header: Header, decompressor: Decompressor) -> Result<Self, Error> {
+        let obs = header.obs.as_ref().ok_or(Error::NotObsRinexData)?;
+        let crinex = obs.crinex.as_ref().ok_or(Error::NotACrinex)?;
+        let crx_major = crinex.version.major;
+        let codes = obs.codes.clone();
+        let constellation = header.constellation.unwrap_or(Constellation::Mixed);
+        let ts = observation::record::timescale(&header).ok_or(Error::UndefinedTimescale)?;
+        Ok(Self {
+            header,
+            ts,
+            crx_major,
+            constellation,
+            codes,
+            decompressor,
+            content: String::with_capacity(6 * 64),
+            event_records: 0,
+            candidate: None,
+            current: None,
+        })
+    }
+    /*
+     * Decompresses given line, returns epochs that were completed,
+     * with the resumption point that preceded them, if any.
+     * Event epochs are passed as is and are not observations: they are skipped.
+     */
+    fn recover(
+        &mut self,
+        line: &str,
+    ) -> Result<Vec<(Epoch, EpochFlag, ObsEpoch, Option<Candidate>)>, Error> {
+        let mut epochs = Vec::new();
+        let event = self
+            .decompressor
+            .event_start(self.header.version.major, line);
+        let recovered = self.decompressor.decompress(
+            self.crx_major,
+            &self.constellation,
+            self.header.version.major,
+            &self.codes,
+            line,
+        )?;
+        if let Some(records) = event {
+            epochs.extend(self.flush()?);
+            self.event_records = records;
+            return Ok(epochs);
+        }
+        if self.event_records > 0 {
+            self.event_records -= 1;
+            return Ok(epochs);
+        }
+        for line in recovered.lines() {
+            if is_rinex_comment(line) {
+                continue;
+            }
+            if observation::record::is_new_epoch(line, self.header.version) {
+                epochs.extend(self.flush()?);
+                self.current = self.candidate.take();
+            }
+            self.content.push_str(line);
+            self.content.push('\n');
+        }
+        Ok(epochs)
+    }
+    /*
+     * Parses pending epoch content, if any
+     */
+    fn flush(&mut self) -> Result<Option<(Epoch, EpochFlag, ObsEpoch, Option<Candidate>)>, Error> {
+        if self.content.is_empty() {
+            return Ok(None);
+        }
+        let parsed = observation::record::parse_epoch(&self.header, &self.content, self.ts);
+        self.content.clear();
+        let current = self.current.take();
+        let ((epoch, flag), clock_offset, vehicles) = parsed?;
+        Ok(Some((epoch, flag, (clock_offset, vehicles), current)))
+    }
+}
+
+impl CrinexIndex {
+    /// Builds a [`CrinexIndex`] by decompressing given CRINEX file,
+    /// with one [`Checkpoint`] every `spacing` epochs.
+    /// Each [`Checkpoint`] stores the decompression kernels,
+    /// so memory usage grows as `spacing` is reduced.
+    /// Corrupt CRINEX content is reported as an [`Error`].
+    pub fn build(path: &str, spacing: usize) -> Result<Self, Error> {
+        let mut reader = BufferedReader::new(path)?;
+        let header = Header::new(&mut reader)?;
+        let obs = header.obs.as_ref().ok_or(Error::NotObsRinexData)?;
+        if obs.crinex.is_none() {
+            return Err(Error::NotACrinex);
+        }
+        let decompressor = Decompressor::with_precision(obs.precision);
+        let mut recovery = EpochRecovery::new(header, decompressor)?;
+
+        let spacing = spacing.max(1);
+        let mut checkpoints = Vec::<Checkpoint>::new();
+        let mut nb_epochs = 0;
+        let mut offset = 0_u64;
+        let mut line = String::with_capacity(128);
+        loop {
+            line.clear();
+            let size = reader.read_line(&mut line)?;
+            if size == 0 {
+                break;
+            }
+            if recovery.decompressor.is_epoch_start(&line) {
+                if nb_epochs % spacing == 0 {
+                    recovery.candidate = Some((offset, recovery.decompressor.clone()));
+                }
+                nb_epochs += 1;
+            }
+            offset += size as u64;
+            for (epoch, _, _, candidate) in recovery.recover(&line)? {
+                if let Some((offset, decompressor)) = candidate {
+                    checkpoints.push(Checkpoint {
+                        epoch,
+                        offset,
+                        decompressor,
+                    });
+                }
+            }
+        }
+        if let Some((epoch, _, _, Some((offset, decompressor)))) = recovery.flush()? {
+            checkpoints.push(Checkpoint {
+                epoch,
+                offset,
+                decompressor,
+            });
+        }
+        Ok(Self {
+            path: path.to_string(),
+            checkpoints,
+        })
+    }
+    /// Number of [`Checkpoint`]s
+    pub fn len(&self) -> usize {
+        self.checkpoints.len()
+    }
+    /// Returns true if this index has no [`Checkpoint`]: file contains no epoch
+    pub fn is_empty(&self) -> bool {
+        self.checkpoints.is_empty()
+    }
+    /// Returns all [`Checkpoint`]s, in chronological order
+    pub fn checkpoints(&self) -> &[Checkpoint] {
+        &self.checkpoints
+    }
+    /// Recovers the Observation [`Rinex`] restricted to `start` <= epoch <= `end`,
+    /// resuming decompression at the closest preceding [`Checkpoint`].
+    /// Fails if the file is no longer a valid CRINEX, for example
+    /// when it was modified since this index was built.
+    pub fn decompress_window(&self, start: Epoch, end: Epoch) -> Result<Rinex, Error> {
+        let mut reader = BufferedReader::new(&self.path)?;
+        let header = Header::new(&mut reader)?;
+        let mut record = observation::Record::new();
+
+        let checkpoint = self
+            .checkpoints
+            .iter()
+            .rev()
+            .find(|checkpoint| checkpoint.epoch <= start)
+            .or_else(|| self.checkpoints.first());
+        let checkpoint = match checkpoint {
+            Some(checkpoint) => checkpoint,
+            None => return Ok(Rinex::new(header, Record::ObsRecord(record))),
+        };
+
+        reader.skip(checkpoint.offset)?;
+        let mut recovery = EpochRecovery::new(header, checkpoint.decompressor.clone())?;
+        let mut line = String::with_capacity(128);
+        'recovery: loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                // last epoch is only complete at the end of the file
+                if let Some((epoch, flag, data, _)) = recovery.flush()? {
+                    if epoch >= start && epoch <= end {
+                        record.insert((epoch, flag), data);
+                    }
+                }
+                break;
+            }
+            for (epoch, flag, data, _) in recovery.recover(&line)? {
+                if epoch > end {
+                    break 'recovery;
+                }
+                if epoch >= start {
+                    record.insert((epoch, flag), data);
+                }
+            }
+        }
+        Ok(Rinex::new(recovery.header, Record::ObsRecord(record)))
+    }
+}
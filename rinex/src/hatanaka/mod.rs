@@ -1,14 +1,24 @@
 //! RINEX compression / decompression module
+use crate::observation::ObservationData;
+use crate::prelude::{Constellation, Epoch, EpochFlag, Header, Observable, SV};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
 use thiserror::Error;
 
 pub mod compressor;
 pub mod numdiff;
 pub mod textdiff;
-pub use compressor::Compressor;
+pub use compressor::{CompressionEstimate, Compressor, ObservableStats};
 
 pub mod decompressor;
 pub use decompressor::Decompressor;
 
+pub mod index;
+pub use index::{Checkpoint, CrinexIndex};
+
+pub mod verify;
+pub use verify::{verify, Mismatch, Verification};
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("I/O error")]
@@ -41,6 +51,101 @@ pub enum Error {
     NumDiffError(#[from] numdiff::Error),
     #[error("sv parsing error")]
     SvParsing(#[from] gnss::sv::ParsingError),
+    #[error("malformed field")]
+    FieldError(#[from] crate::fieldslice::Error),
     #[error("failed to parse integer number")]
     ParseIntError(#[from] std::num::ParseIntError),
+    #[error("undefined constellation")]
+    UndefinedConstellation,
+    #[error("no observables defined for {0}")]
+    UndefinedObservables(Constellation),
+    #[error("epoch content is incomplete")]
+    IncompleteEpoch,
+    #[error("failed to parse recovered epoch")]
+    RecoveredEpochParsing(#[from] crate::observation::record::Error),
+    #[error("header parsing error")]
+    HeaderParsing(#[from] crate::header::ParsingError),
+    #[error("undefined observations timescale")]
+    UndefinedTimescale,
+}
+
+/*
+ * Returns the number of special records that follow given epoch descriptor,
+ * when it describes an event epoch (flags 2 to 5). Event epochs are not compressed:
+ * their descriptor is passed as is (CRINEX1 marks it with '&' in column 1),
+ * and so are their special records.
+ */
+pub(crate) fn event_records(rnx_major: u8, line: &str) -> Option<usize> {
+    let (flag, n) = match rnx_major {
+        1 | 2 => (line.get(28..29)?, line.get(29..32)?),
+        _ => (line.get(31..32)?, line.get(32..35)?),
+    };
+    let flag = EpochFlag::from_str(flag).ok()?;
+    if flag.is_event() {
+        n.trim().parse::<usize>().ok()
+    } else {
+        None
+    }
+}
+
+/// [EpochBlock] is a single and complete Observation epoch.
+/// It is the granularity of [Compressor::compress_epoch]
+/// and [Decompressor::decompress_epoch], that real-time applications
+/// should prefer over line oriented (de)compression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpochBlock {
+    /// Sampling instant
+    pub epoch: Epoch,
+    /// Epoch flag
+    pub flag: EpochFlag,
+    /// Possible receiver clock offset, in seconds
+    pub clock_offset: Option<f64>,
+    /// Observations, per vehicle
+    pub vehicles: BTreeMap<SV, HashMap<Observable, ObservationData>>,
+}
+
+/// [HatanakaCodec] is the API shared by [Compressor] and [Decompressor],
+/// for generic processing pipelines. Both process complete lines,
+/// following the definitions of a CRINEX [Header]: chaining a [Decompressor]
+/// and a [Compressor] is a pass-through, that reproduces the original content.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::hatanaka::{Compressor, Decompressor, HatanakaCodec};
+/// use rinex::reader::BufferedReader;
+/// use std::io::BufRead;
+///
+/// fn process<C: HatanakaCodec>(codec: &mut C, header: &Header, content: &str) -> String {
+///     content
+///         .lines()
+///         .map(|line| codec.process(header, &format!("{}\n", line)).unwrap())
+///         .collect()
+/// }
+///
+/// let mut reader = BufferedReader::new("../test_resources/CRNX/V3/DUTH0630.22D")
+///     .unwrap();
+/// let header = Header::new(&mut reader)
+///     .unwrap();
+/// let content: String = reader
+///     .lines()
+///     .map(|line| line.unwrap() + "\n")
+///     .collect();
+///
+/// let precision = header.obs.as_ref().unwrap().precision;
+/// let mut decompressor = Decompressor::with_precision(precision);
+/// let mut compressor = Compressor::with_precision(precision);
+/// let recovered = process(&mut decompressor, &header, &content);
+/// assert_eq!(process(&mut compressor, &header, &recovered), content);
+/// ```
+pub trait HatanakaCodec {
+    /// Finite state machine
+    type State;
+    /// Processes given content, made of complete lines.
+    /// Content may be held until enough lines were provided,
+    /// the output then lags the input.
+    fn process(&mut self, header: &Header, content: &str) -> Result<String, Error>;
+    /// Resets this codec, as if no content had ever been processed.
+    /// Quantization policy (and line ending of the [Compressor]) is preserved.
+    fn reset(&mut self);
+    /// Returns the current state of the finite state machine
+    fn state(&self) -> Self::State;
 }
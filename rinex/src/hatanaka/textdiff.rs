@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TextDiff {
     pub buffer: String,
 }
@@ -25,6 +25,10 @@ impl TextDiff {
 
     /// Decompresses given data
     pub fn decompress(&mut self, data: &str) -> &str {
+        if !data.is_ascii() || !self.buffer.is_ascii() {
+            // non ASCII content cannot be patched byte per byte
+            return self.decompress_chars(data);
+        }
         let s0_len = self.buffer.len();
         let s0 = unsafe { self.buffer.as_bytes_mut() };
         let s1_len = data.len();
@@ -56,37 +60,58 @@ impl TextDiff {
         &self.buffer
     }
 
-    /// Compresses given data
-    pub fn compress(&mut self, data: &str) -> String {
-        let mut result = String::new();
-        let inner: Vec<_> = self.buffer.chars().collect();
-        self.buffer.clear();
-        let to_compress: Vec<_> = data.chars().collect();
-
-        for i in 0..inner.len() {
-            if let Some(c) = to_compress.get(i) {
-                self.buffer.push_str(&c.to_string());
-                if c != &inner[i] {
-                    result.push_str(&c.to_string());
-                } else {
-                    result.push(' ');
-                }
+    /*
+     * Character based decompression, for non ASCII content
+     */
+    fn decompress_chars(&mut self, data: &str) -> &str {
+        let mut inner: Vec<char> = self.buffer.chars().collect();
+        for (index, c) in data.chars().enumerate() {
+            match inner.get_mut(index) {
+                Some(previous) => match c {
+                    ' ' => {}, // differenced out
+                    '&' => *previous = ' ',
+                    c => *previous = c,
+                },
+                None => inner.push(if c == '&' { ' ' } else { c }),
             }
         }
+        self.buffer = inner.into_iter().collect();
+        &self.buffer
+    }
 
-        for i in inner.len()..data.len() {
-            if let Some(c) = to_compress.get(i) {
-                if c.is_ascii_whitespace() {
-                    self.buffer.push('&');
-                    result.push('&');
-                } else {
-                    self.buffer.push_str(&c.to_string());
-                    result.push_str(&c.to_string());
-                }
+    /// Compresses given data. Like RNX2CRX, the previous content is
+    /// considered blank beyond its length, and is explicitly blanked out
+    /// beyond the new length.
+    pub fn compress(&mut self, data: &str) -> String {
+        let mut result = String::with_capacity(data.len());
+        let mut inner: Vec<char> = self.buffer.chars().collect();
+        for (index, c) in data.chars().enumerate() {
+            let c = if c.is_ascii_whitespace() { ' ' } else { c };
+            let previous = inner.get(index).copied().unwrap_or(' ');
+            if c == previous {
+                result.push(' ');
+            } else if c == ' ' {
+                // differenced out whitespace must be explicit
+                result.push('&');
+            } else {
+                result.push(c);
+            }
+            match inner.get_mut(index) {
+                Some(previous) => *previous = c,
+                None => inner.push(c),
             }
         }
-
-        result.to_string()
+        // previous content is blanked out, beyond the new length
+        for previous in inner.iter_mut().skip(data.chars().count()) {
+            if *previous == ' ' {
+                result.push(' ');
+            } else {
+                result.push('&');
+                *previous = ' ';
+            }
+        }
+        self.buffer = inner.into_iter().collect();
+        result
     }
 }
 
@@ -146,6 +171,14 @@ mod test {
         }
     }
     #[test]
+    fn non_ascii_decompression() {
+        let mut diff = TextDiff::new();
+        diff.init("Zürich 01");
+        assert_eq!(diff.decompress("       &2"), "Zürich  2");
+        assert_eq!(diff.decompress(" o"), "Zorich  2");
+        assert_eq!(diff.decompress("         é"), "Zorich  2é");
+    }
+    #[test]
     fn test_compression() {
         let mut diff = TextDiff::new();
 
@@ -156,10 +189,10 @@ mod test {
         assert_eq!(compressed, "4");
 
         let compressed = diff.compress("4  ");
-        assert_eq!(compressed, " &&");
+        assert_eq!(compressed, "   ");
 
         let compressed = diff.compress("0");
-        assert_eq!(compressed, "0");
+        assert_eq!(compressed, "0  ");
 
         // test re-init
         diff.init("Default Phrase 1234");
@@ -177,15 +210,15 @@ mod test {
 
         let to_compress = "DEFault Phrase 1234  ";
         let result = diff.compress(to_compress);
-        assert_eq!(result, "                   &&");
+        assert_eq!(result, "                     ");
 
         let to_compress = " EFault Phrase 1234  ";
         let result = diff.compress(to_compress);
-        assert_eq!(result, "                     ");
+        assert_eq!(result, "&                    ");
 
         let to_compress = "__ abcd Phrase 1222    ";
         let result = diff.compress(to_compress);
-        assert_eq!(result, "__  bcd          22  &&");
+        assert_eq!(result, "__& bcd          22    ");
 
         diff.init(" ");
         assert_eq!(diff.compress("3"), "3");
@@ -0,0 +1,107 @@
+//! CRINEX archive integrity verification
+use super::{Compressor, Decompressor, Error, HatanakaCodec};
+use crate::{prelude::*, reader::BufferedReader};
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+/// First line that differs, once recompressed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    /// Line number, in the CRINEX file
+    pub line: usize,
+    /// Original content, None past the end of file
+    pub original: Option<String>,
+    /// Recompressed content, None if recompression produced fewer lines
+    pub recompressed: Option<String>,
+}
+
+/// [`Verification`] report of a CRINEX file, see [`verify`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Verification {
+    /// Number of body lines that were verified
+    pub lines: usize,
+    /// First mismatch, if any
+    pub mismatch: Option<Mismatch>,
+}
+
+impl Verification {
+    /// Returns true if the recompressed content is identical to the original
+    pub fn is_ok(&self) -> bool {
+        self.mismatch.is_none()
+    }
+}
+
+/// Verifies the integrity of a CRINEX archive: the file is decompressed,
+/// recompressed, and compared to the original, line by line.
+/// Recompression follows RNX2CRX byte for byte, so any difference
+/// points to a corrupt (or non standard) archive. Alterations that still
+/// describe valid data (a modified residual for example) remain undetectable.
+/// The header is carried as is by a decompression / recompression cycle,
+/// only the "CRINEX PROG / DATE" stamp would be updated: it is parsed, not compared.
+/// gzip compressed files are supported.
+/// ```
+/// use rinex::hatanaka::verify;
+/// let report = verify("../test_resources/CRNX/V3/DUTH0630.22D")
+///     .unwrap();
+/// assert!(report.is_ok());
+/// assert_eq!(report.lines, 58);
+/// ```
+pub fn verify(path: &str) -> Result<Verification, Error> {
+    let mut reader = BufferedReader::new(path)?;
+    let header = Header::new(&mut reader)?;
+    let obs = header.obs.as_ref().ok_or(Error::NotObsRinexData)?;
+    if obs.crinex.is_none() {
+        return Err(Error::NotACrinex);
+    }
+
+    // body line numbers are reported within the file
+    let header_size = BufferedReader::new(path)?
+        .lines()
+        .take_while(|line| {
+            line.as_ref()
+                .map(|line| !line.contains("END OF HEADER"))
+                .unwrap_or(false)
+        })
+        .count()
+        + 1;
+
+    let mut decompressor = Decompressor::with_precision(obs.precision);
+    let mut compressor = Compressor::with_precision(obs.precision);
+    let mut report = Verification::default();
+    // original lines, awaiting their recompressed counterpart
+    let mut originals = VecDeque::<String>::new();
+    let mut recompressed = String::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        let recovered = decompressor.process(&header, &format!("{}\n", line))?;
+        originals.push_back(line);
+        for line in recovered.lines() {
+            recompressed.push_str(&compressor.process(&header, &format!("{}\n", line))?);
+        }
+        // compare completed lines
+        while let Some(end) = recompressed.find('\n') {
+            let line: String = recompressed.drain(..=end).collect();
+            let line = line.trim_end_matches('\n');
+            let original = originals.pop_front();
+            if original.as_deref() != Some(line) {
+                report.mismatch = Some(Mismatch {
+                    line: header_size + report.lines + 1,
+                    original,
+                    recompressed: Some(line.to_string()),
+                });
+                return Ok(report);
+            }
+            report.lines += 1;
+        }
+    }
+    // content that was never concluded
+    if !originals.is_empty() || !recompressed.is_empty() {
+        report.mismatch = Some(Mismatch {
+            line: header_size + report.lines + 1,
+            original: originals.pop_front(),
+            recompressed: Some(recompressed).filter(|content| !content.is_empty()),
+        });
+    }
+    Ok(report)
+}
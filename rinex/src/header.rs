@@ -4,15 +4,17 @@ use super::*;
 use crate::{
     antex, clocks,
     clocks::{ClockAnalysisAgency, ClockDataType},
+    fieldslice,
     ground_position::GroundPosition,
     hardware::{Antenna, Rcvr, SvAntenna},
     ionex, leap,
     linspace::Linspace,
-    meteo,
-    navigation::{IonMessage, KbModel},
+    meteo, navigation,
+    navigation::{IonMessage, TimeSystemCorr},
     observation,
-    observation::Crinex,
-    reader::BufferedReader,
+    observation::{Crinex, MappingProfile},
+    reader::{BufferedReader, Format, ParserSettings},
+    sampling::SamplingInterval,
     types::Type,
     version::Version,
     Observable,
@@ -25,7 +27,10 @@ use thiserror::Error;
 
 use crate::marker::{GeodeticMarker, MarkerType};
 
-use crate::{fmt_comment, fmt_rinex};
+use crate::{
+    fmt_comment, fmt_rinex,
+    formatter::{Exponent, FloatFormat, Precision},
+};
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
@@ -65,7 +70,9 @@ pub struct Header {
     /// `GNSS` constellation system encountered in this file,
     /// or reference GNSS constellation for the following data.
     pub constellation: Option<Constellation>,
-    /// comments extracted from `header` section
+    /// comments extracted from `header` section, one per COMMENT line.
+    /// Comments longer than 60 characters are wrapped over several lines
+    /// on production, and are therefore read back as several comments.
     pub comments: Vec<String>,
     /// program name
     pub program: String,
@@ -91,8 +98,8 @@ pub struct Header {
     pub ground_position: Option<GroundPosition>,
     /// Optionnal observation wavelengths
     pub wavelengths: Option<(u32, u32)>,
-    /// Optionnal sampling interval (s)
-    pub sampling_interval: Option<Duration>,
+    /// Optionnal sampling interval
+    pub sampling_interval: Option<SamplingInterval>,
     /// Optionnal file license
     pub license: Option<String>,
     /// Optionnal Object Identifier (IoT)
@@ -114,6 +121,9 @@ pub struct Header {
     /// Possible Ionospheric Delay correction model.
     /// Only exists in NAV V3 headers. In modern NAV, this
     /// is regularly updated in the file's body.
+    #[deprecated(
+        note = "use Header::ionod_correction(), or navigation::HeaderFields for all models"
+    )]
     pub ionod_correction: Option<IonMessage>,
     /// Possible DCBs compensation information
     pub dcb_compensations: Vec<DcbCompensation>,
@@ -125,6 +135,9 @@ pub struct Header {
     /// Meteo record specific fields
     #[cfg_attr(feature = "serde", serde(default))]
     pub meteo: Option<meteo::HeaderFields>,
+    /// Navigation record specific fields
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub nav: Option<navigation::HeaderFields>,
     /// Clocks record specific fields
     #[cfg_attr(feature = "serde", serde(default))]
     pub clocks: Option<clocks::HeaderFields>,
@@ -174,6 +187,12 @@ pub enum ParsingError {
     InvalidIonexGrid(String, String),
     #[error("invalid ionex grid definition")]
     InvalidIonexGridDefinition(#[from] linspace::Error),
+    #[error("\"TYPES OF OBS\" while GNSS definition is missing")]
+    UndefinedConstellation,
+    #[error("failed to read header: {0}")]
+    ReadError(String),
+    #[error("malformed header field")]
+    FieldError(#[from] fieldslice::Error),
 }
 
 fn parse_formatted_month(content: &str) -> Result<u8, ParsingError> {
@@ -197,6 +216,20 @@ fn parse_formatted_month(content: &str) -> Result<u8, ParsingError> {
     }
 }
 
+/*
+ * Constellation described by a RINEX3 "IONOSPHERIC CORR" type
+ */
+fn ionod_constellation(correction_type: &str) -> Option<Constellation> {
+    match correction_type.get(..3)? {
+        "GPS" => Some(Constellation::GPS),
+        "GAL" => Some(Constellation::Galileo),
+        "QZS" => Some(Constellation::QZSS),
+        "BDS" => Some(Constellation::BeiDou),
+        "IRN" => Some(Constellation::IRNSS),
+        _ => None,
+    }
+}
+
 /*
  * Generates a ParsingError::ParseIntError(x, y)
  */
@@ -227,6 +260,16 @@ macro_rules! grid_format_error {
 impl Header {
     /// Builds a `Header` from stream reader
     pub fn new(reader: &mut BufferedReader) -> Result<Header, ParsingError> {
+        Self::parse(reader, None)
+    }
+    /*
+     * Parses header fields, observation codes and constellation letters
+     * being possibly fixed by a mapping profile
+     */
+    pub(crate) fn parse(
+        reader: &mut BufferedReader,
+        profile: Option<&MappingProfile>,
+    ) -> Result<Header, ParsingError> {
         let mut rinex_type = Type::default();
         let mut constellation: Option<Constellation> = None;
         let mut version = Version::default();
@@ -245,16 +288,18 @@ impl Header {
         let mut rcvr_antenna: Option<Antenna> = None;
         let mut sv_antenna: Option<SvAntenna> = None;
         let mut leap: Option<leap::Leap> = None;
-        let mut sampling_interval: Option<Duration> = None;
+        let mut sampling_interval: Option<SamplingInterval> = None;
         let mut ground_position: Option<GroundPosition> = None;
         let mut dcb_compensations: Vec<DcbCompensation> = Vec::new();
-        let mut ionod_correction = Option::<IonMessage>::None;
         let mut pcv_compensations: Vec<PcvCompensation> = Vec::new();
         let mut scaling_count = 0_u16;
         // RINEX specific fields
         let mut current_constell: Option<Constellation> = None;
+        // (declared, parsed) observables, across continuation lines
+        let mut obs_codes = (0_usize, 0_usize);
         let mut observation = observation::HeaderFields::default();
         let mut meteo = meteo::HeaderFields::default();
+        let mut nav = navigation::HeaderFields::default();
         let mut clocks = clocks::HeaderFields::default();
         let mut antex = antex::HeaderFields::default();
         let mut ionex = ionex::HeaderFields::default();
@@ -262,11 +307,11 @@ impl Header {
         // iterate on a line basis
         let lines = reader.lines();
         for l in lines {
-            let line = l.unwrap();
+            let line = l.map_err(|e| ParsingError::ReadError(e.to_string()))?;
             if line.len() < 60 {
                 continue; // --> invalid header content
             }
-            let (content, marker) = line.split_at(60);
+            let (content, marker) = fieldslice::split_at(&line, 60)?;
             ///////////////////////////////
             // [0] END OF HEADER
             //     --> done parsing
@@ -278,7 +323,15 @@ impl Header {
             // [0*] COMMENTS
             ///////////////////////////////
             if marker.trim().eq("COMMENT") {
-                // --> storing might be useful
+                if let Some(precision) = Precision::from_comment(content) {
+                    // non standard observation resolution
+                    observation.precision = precision;
+                    observation.precision_comment = true;
+                    continue;
+                }
+                // --> storing might be useful.
+                // Each line is a comment on its own: nothing tells a sentence
+                // carried over the next line from two distinct comments.
                 comments.push(content.trim().to_string());
                 continue;
 
@@ -439,6 +492,9 @@ impl Header {
                 if type_str.contains("GLONASS") {
                     // old GLONASS NAV : no constellation field
                     constellation = Some(Constellation::Glonass);
+                } else if type_str.contains("GEO NAV MSG DATA") {
+                    // old GEO (SBAS) NAV : no constellation field
+                    constellation = Some(Constellation::SBAS);
                 } else if type_str.contains("GPS NAV DATA") {
                     constellation = Some(Constellation::GPS);
                 } else if type_str.contains("IRNSS NAV DATA") {
@@ -672,8 +728,8 @@ impl Header {
 
                 ground_position = Some(GroundPosition::from_ecef_wgs84((x, y, z)));
             } else if marker.contains("ANT # / TYPE") {
-                let (model, rem) = content.split_at(20);
-                let (sn, _) = rem.split_at(20);
+                let (sn, rem) = content.split_at(20);
+                let (model, _) = rem.split_at(20);
                 if let Some(a) = &mut rcvr_antenna {
                     *a = a.with_model(model.trim()).with_serial_number(sn.trim());
                 } else {
@@ -791,10 +847,23 @@ impl Header {
                 observation = observation.with_time_of_last_obs(time_of_last_obs);
             } else if marker.contains("TYPES OF OBS") {
                 // these observations can serve both Observation & Meteo RINEX
-                let (_, content) = content.split_at(6);
+                let (counter, content) = content.split_at(6);
+                if !counter.trim().is_empty() {
+                    // first line: continuation lines have a blank counter
+                    let count = counter.trim().parse::<usize>().unwrap_or(usize::MAX);
+                    obs_codes = (count, 0);
+                }
                 for i in 0..content.len() / 6 {
                     let obscode = &content[i * 6..std::cmp::min((i + 1) * 6, content.len())].trim();
+                    if obscode.is_empty() || obs_codes.1 == obs_codes.0 {
+                        continue; // trailing blanks or beyond declared count
+                    }
+                    let obscode = match profile {
+                        Some(profile) => profile.observable(obscode),
+                        None => *obscode,
+                    };
                     if let Ok(observable) = Observable::from_str(obscode) {
+                        obs_codes.1 += 1;
                         match constellation {
                             Some(Constellation::Mixed) => {
                                 lazy_static! {
@@ -826,7 +895,7 @@ impl Header {
                                 if rinex_type == Type::MeteoData {
                                     meteo.codes.push(observable);
                                 } else {
-                                    panic!("can't have \"TYPES OF OBS\" when GNSS definition is missing");
+                                    return Err(ParsingError::UndefinedConstellation);
                                 }
                             },
                         }
@@ -834,11 +903,18 @@ impl Header {
                 }
             } else if marker.contains("SYS / # / OBS TYPES") {
                 let (possible_counter, content) = content.split_at(6);
-                if !possible_counter.is_empty() {
-                    let code = &possible_counter[..1];
-                    if let Ok(c) = Constellation::from_str(code) {
-                        current_constell = Some(c);
-                    }
+                let (code, counter) = possible_counter.split_at(1);
+                if !code.trim().is_empty() {
+                    // first line: continuation lines have blank system and counter.
+                    // Unknown systems are dropped along with their continuations.
+                    current_constell = match (profile, code.chars().next()) {
+                        (Some(profile), Some(letter)) => {
+                            Constellation::from_str(&profile.constellation(letter).to_string()).ok()
+                        },
+                        _ => Constellation::from_str(code).ok(),
+                    };
+                    let count = counter.trim().parse::<usize>().unwrap_or(usize::MAX);
+                    obs_codes = (count, 0);
                 }
 
                 if let Some(constell) = current_constell {
@@ -846,13 +922,19 @@ impl Header {
                     for i in 0..content.len() / 4 {
                         let obscode =
                             &content[i * 4..std::cmp::min((i + 1) * 4, content.len())].trim();
+                        if obscode.is_empty() || obs_codes.1 == obs_codes.0 {
+                            continue; // trailing blanks or beyond declared count
+                        }
+                        let obscode = match profile {
+                            Some(profile) => profile.observable(obscode),
+                            None => *obscode,
+                        };
                         if let Ok(observable) = Observable::from_str(obscode) {
-                            if !obscode.is_empty() {
-                                if let Some(codes) = observation.codes.get_mut(&constell) {
-                                    codes.push(observable);
-                                } else {
-                                    observation.codes.insert(constell, vec![observable]);
-                                }
+                            obs_codes.1 += 1;
+                            if let Some(codes) = observation.codes.get_mut(&constell) {
+                                codes.push(observable);
+                            } else {
+                                observation.codes.insert(constell, vec![observable]);
                             }
                         }
                     }
@@ -890,14 +972,9 @@ impl Header {
                 //TODO
             } else if marker.contains("INTERVAL") {
                 let intv_str = content.split_at(20).0.trim();
-                if let Ok(interval) = f64::from_str(intv_str) {
-                    if interval > 0.0 {
-                        // INTERVAL = '0' may exist, in case
-                        // of Varying TEC map intervals
-                        sampling_interval =
-                            Some(Duration::from_f64(interval, hifitime::Unit::Second));
-                    }
-                }
+                // INTERVAL = '0' may exist, in case
+                // of Varying TEC map intervals
+                sampling_interval = SamplingInterval::from_header_field(intv_str);
             } else if marker.contains("GLONASS SLOT / FRQ #") {
                 //TODO
                 // This should be used when dealing with Glonass carriers
@@ -928,24 +1005,22 @@ impl Header {
                  * the record will just contain a single model for the entire day course
                  */
                 if let Ok(model) = IonMessage::from_rinex3_header(content) {
-                    // The Klobuchar model needs two lines to be entirely described.
-                    if let Some(kb_model) = model.as_klobuchar() {
-                        let correction_type = content.split_at(5).0.trim();
-                        if correction_type.ends_with('B') {
-                            let alpha = ionod_correction.unwrap().as_klobuchar().unwrap().alpha;
-                            let (beta, region) = (kb_model.beta, kb_model.region);
-                            ionod_correction = Some(IonMessage::KlobucharModel(KbModel {
-                                alpha,
-                                beta,
-                                region,
-                            }));
-                        } else {
-                            ionod_correction = Some(IonMessage::KlobucharModel(*kb_model));
+                    // per constellation description
+                    let correction_type = content.split_at(5).0.trim();
+                    if let Some(constellation) = ionod_constellation(correction_type) {
+                        let stored = nav.ionod_corrections.entry(constellation).or_insert(model);
+                        // The Klobuchar model is described by two lines (alpha, beta)
+                        if let (
+                            IonMessage::KlobucharModel(stored),
+                            IonMessage::KlobucharModel(kb_model),
+                        ) = (stored, model)
+                        {
+                            if correction_type.ends_with('B') {
+                                stored.beta = kb_model.beta;
+                            } else {
+                                stored.alpha = kb_model.alpha;
+                            }
                         }
-                    } else {
-                        // The NequickG model fits on a single line.
-                        // The BDGIM does not exist until RINEX4
-                        ionod_correction = Some(model);
                     }
                 }
             } else if marker.contains("TIME SYSTEM CORR") {
@@ -953,9 +1028,9 @@ impl Header {
                 /*
                  * V3 Time System correction description
                  */
-                //if let Ok((ts, ts, corr)) = gnss_time::decode_time_system_corr(content) {
-                //    time_corrections.insert(ts, (ts, corr));
-                //}
+                if let Ok(corr) = TimeSystemCorr::from_rinex3_header(content) {
+                    nav.time_corrections.push(corr);
+                }
             } else if marker.contains("TIME SYSTEM ID") {
                 let timescale = content.trim();
                 let ts = TimeScale::from_str(timescale)
@@ -1100,7 +1175,7 @@ impl Header {
             }
         }
 
-        Ok(Header {
+        let mut header = Header {
             version,
             rinex_type,
             constellation,
@@ -1118,7 +1193,6 @@ impl Header {
             glo_channels,
             leap,
             ground_position,
-            ionod_correction,
             dcb_compensations,
             pcv_compensations,
             wavelengths: None,
@@ -1142,6 +1216,13 @@ impl Header {
                     None
                 }
             },
+            nav: {
+                if rinex_type == Type::NavigationData {
+                    Some(nav)
+                } else {
+                    None
+                }
+            },
             clocks: {
                 if rinex_type == Type::ClockData {
                     Some(clocks)
@@ -1163,7 +1244,36 @@ impl Header {
                     None
                 }
             },
-        })
+            ..Default::default()
+        };
+        header.sync_ionod_correction();
+        Ok(header)
+    }
+
+    /// Parses the header section of given file only, stopping at "END OF HEADER".
+    /// Gzip compressed and Compact RINEX files are transparently supported.
+    /// This is much faster than [crate::Rinex::from_file] and is intended
+    /// to classify large amount of files (archive indexing).
+    /// ```
+    /// use rinex::prelude::*;
+    /// let header = Header::quick_scan("../test_resources/CRNX/V3/DUTH0630.22D")
+    ///     .unwrap();
+    /// assert!(header.is_crinex());
+    /// assert_eq!(header.rinex_type, RinexType::ObservationData);
+    /// assert_eq!(header.version.major, 3);
+    /// ```
+    pub fn quick_scan(path: &str) -> Result<Self, crate::Error> {
+        // reject content we would not decode
+        let mut fd = std::io::BufReader::new(std::fs::File::open(path)?);
+        match crate::reader::detect_format(&mut fd)? {
+            Some(Format::UnixCompress) => return Err(crate::Error::UnknownFormat),
+            Some(format) if format.is_gzip() && !cfg!(feature = "flate2") => {
+                return Err(crate::Error::UnknownFormat)
+            },
+            _ => {},
+        }
+        let mut reader = BufferedReader::with_settings(path, &ParserSettings::default())?;
+        Ok(Self::new(&mut reader)?)
     }
 
     /// Returns true if self is a `Compressed RINEX`
@@ -1175,6 +1285,56 @@ impl Header {
         }
     }
 
+    /// Returns the receiver antenna position. [Self::ground_position]
+    /// (APPROX POSITION XYZ) is the position of the marker: when `apply_antenna_offset`
+    /// is set, the antenna eccentricity (ANTENNA: DELTA H/E/N) is applied
+    /// and the antenna reference point (ARP) position is returned.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let marker = rnx.header.antenna_position(false).unwrap();
+    /// assert_eq!(Some(marker), rnx.header.ground_position);
+    /// // ARP is 18.3 cm above the marker
+    /// let arp = rnx.header.antenna_position(true).unwrap();
+    /// let (e, n, u) = arp.to_enu(&marker);
+    /// assert!(e.abs() < 1.0E-6);
+    /// assert!(n.abs() < 1.0E-6);
+    /// assert!((u - 0.183).abs() < 1.0E-6);
+    /// ```
+    pub fn antenna_position(&self, apply_antenna_offset: bool) -> Option<GroundPosition> {
+        let marker = self.ground_position?;
+        let offset = self
+            .rcvr_antenna
+            .as_ref()
+            .and_then(|antenna| antenna.offset());
+        match offset {
+            Some(offset) if apply_antenna_offset => Some(offset.apply(marker)),
+            _ => Some(marker),
+        }
+    }
+
+    /// Returns the Ionospheric Delay correction model described in this
+    /// NAV V3 header, if any: the GPS model is preferred, otherwise the
+    /// first model described. In modern NAV, models are regularly updated
+    /// in the file's body. Use [navigation::HeaderFields] for all models.
+    pub fn ionod_correction(&self) -> Option<IonMessage> {
+        let models = &self.nav.as_ref()?.ionod_corrections;
+        models
+            .get(&Constellation::GPS)
+            .or_else(|| models.values().next())
+            .copied()
+    }
+
+    /*
+     * Keeps the deprecated single model field consistent
+     * with the models stored per constellation
+     */
+    #[allow(deprecated)]
+    fn sync_ionod_correction(&mut self) {
+        self.ionod_correction = self.ionod_correction();
+    }
+
     /// Creates a Basic Header structure
     /// for Mixed Constellation Navigation RINEX
     pub fn basic_nav() -> Self {
@@ -1233,6 +1393,37 @@ impl Header {
         s
     }
 
+    /// Returns the header of the Compact RINEX (CRINEX) counterpart of this
+    /// Observation RINEX: the "CRINEX VERS / TYPE" and "CRINEX PROG / DATE" lines
+    /// are defined (replacing existing ones), the RINEX header itself is preserved.
+    /// CRINEX1 is intended to RINEX2 and CRINEX3 to RINEX3 and newer, but we let
+    /// the user force another revision. The program name is limited to 20 characters.
+    /// Has no effect if this is not an Observation Data header.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::version::Version;
+    /// let rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let header = rinex.header.to_crinex_header(Version::new(3, 0), "rnx2crx");
+    /// assert!(header.is_crinex());
+    ///
+    /// let content = header.to_string();
+    /// let lines: Vec<&str> = content.lines().collect();
+    /// assert!(lines[0].starts_with("3.0                 COMPACT RINEX FORMAT"));
+    /// assert!(lines[0].ends_with("CRINEX VERS   / TYPE"));
+    /// assert!(lines[1].starts_with("rnx2crx"));
+    /// assert!(lines[1].ends_with("CRINEX PROG / DATE"));
+    /// assert!(lines[2].ends_with("RINEX VERSION / TYPE"));
+    /// ```
+    pub fn to_crinex_header(&self, version: Version, prog: &str) -> Self {
+        let prog: String = prog.trim().chars().take(20).collect();
+        self.with_crinex(Crinex {
+            version,
+            prog,
+            date: epoch::now(),
+        })
+    }
+
     /// Adds receiver information to self
     pub fn with_receiver(&self, r: Rcvr) -> Self {
         let mut s = self.clone();
@@ -1350,6 +1541,26 @@ impl Header {
                         )
                     )
                 },
+                Some(c) if major < 3 && c.is_sbas() => {
+                    writeln!(
+                        f,
+                        "{}",
+                        fmt_rinex(
+                            &format!("{:6}.{:02}           H: GEO NAV MSG DATA", major, minor),
+                            "RINEX VERSION / TYPE"
+                        )
+                    )
+                },
+                Some(Constellation::GPS) if major < 3 => {
+                    writeln!(
+                        f,
+                        "{}",
+                        fmt_rinex(
+                            &format!("{:6}.{:02}           N: GPS NAV DATA", major, minor),
+                            "RINEX VERSION / TYPE"
+                        )
+                    )
+                },
                 Some(c) => {
                     writeln!(
                         f,
@@ -1412,12 +1623,60 @@ impl Header {
         match self.rinex_type {
             Type::ObservationData => self.fmt_observation_rinex(f),
             Type::MeteoData => self.fmt_meteo_rinex(f),
-            Type::NavigationData => Ok(()),
+            Type::NavigationData => self.fmt_navigation_rinex(f),
             Type::ClockData => self.fmt_clock_rinex(f),
             Type::IonosphereMaps => self.fmt_ionex(f),
             Type::AntennaData => Ok(()),
         }
     }
+    /*
+     * Navigation Data fields formatting.
+     * Ionospheric and Time System corrections only exist in RINEX3 headers.
+     */
+    fn fmt_navigation_rinex(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let nav = match &self.nav {
+            Some(nav) if self.version.major == 3 => nav,
+            _ => return Ok(()),
+        };
+        let fmt = FloatFormat::scientific(12, 4, Exponent::E);
+        for (constellation, model) in &nav.ionod_corrections {
+            let prefix = match constellation {
+                Constellation::Galileo => "GAL",
+                Constellation::QZSS => "QZS",
+                Constellation::BeiDou => "BDS",
+                Constellation::IRNSS => "IRN",
+                _ => "GPS",
+            };
+            let lines = match model {
+                IonMessage::KlobucharModel(kb) => {
+                    let (a, b) = (kb.alpha, kb.beta);
+                    vec![
+                        (format!("{}A", prefix), [a.0, a.1, a.2, a.3]),
+                        (format!("{}B", prefix), [b.0, b.1, b.2, b.3]),
+                    ]
+                },
+                IonMessage::NequickGModel(ng) => {
+                    vec![(prefix.to_string(), [ng.a.0, ng.a.1, ng.a.2, 0.0])]
+                },
+                IonMessage::BdgimModel(_) => vec![], // does not exist in RINEX3
+            };
+            for (correction_type, coefs) in lines {
+                let mut content = format!("{:<4} ", correction_type);
+                for coef in coefs {
+                    content.push_str(&fmt.format(coef));
+                }
+                writeln!(f, "{}", fmt_rinex(&content, "IONOSPHERIC CORR"))?;
+            }
+        }
+        for corr in &nav.time_corrections {
+            writeln!(
+                f,
+                "{}",
+                fmt_rinex(&corr.to_rinex3_header(), "TIME SYSTEM CORR")
+            )?;
+        }
+        Ok(())
+    }
     /*
      * Clock Data fields formatting
      */
@@ -1540,15 +1799,17 @@ impl Header {
             /*
              * List of observables
              */
-            let mut descriptor = String::new();
-            descriptor.push_str(&format!("{:6}", meteo.codes.len()));
-            for (i, observable) in meteo.codes.iter().enumerate() {
-                if (i % 9) == 0 && i > 0 {
-                    descriptor.push_str("      "); // TAB
-                }
-                descriptor.push_str(&format!("    {}", observable));
-            }
-            writeln!(f, "{}", fmt_rinex(&descriptor, "# / TYPES OF OBSERV"))?;
+            writeln!(
+                f,
+                "{}",
+                fmt_code_list(
+                    &format!("{:6}", meteo.codes.len()),
+                    &meteo.codes,
+                    6,
+                    9,
+                    "# / TYPES OF OBSERV"
+                )
+            )?;
             for sensor in &meteo.sensors {
                 write!(f, "{}", sensor)?;
             }
@@ -1561,28 +1822,42 @@ impl Header {
     fn fmt_observation_rinex(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         if let Some(obs) = &self.obs {
             if let Some(e) = obs.time_of_first_obs {
-                let (y, m, d, hh, mm, ss, nanos) = e.to_gregorian_utc();
+                let (y, m, d, hh, mm, ss, nanos) = epoch::epoch_decompose(e);
                 writeln!(
                     f,
                     "{}",
                     fmt_rinex(
                         &format!(
                             "  {:04}    {:02}    {:02}    {:02}    {:02}   {:02}.{:07}     {:x}",
-                            y, m, d, hh, mm, ss, nanos, e.time_scale
+                            y,
+                            m,
+                            d,
+                            hh,
+                            mm,
+                            ss,
+                            nanos / 100,
+                            e.time_scale
                         ),
                         "TIME OF FIRST OBS"
                     )
                 )?;
             }
             if let Some(e) = obs.time_of_last_obs {
-                let (y, m, d, hh, mm, ss, nanos) = e.to_gregorian_utc();
+                let (y, m, d, hh, mm, ss, nanos) = epoch::epoch_decompose(e);
                 writeln!(
                     f,
                     "{}",
                     fmt_rinex(
                         &format!(
                             "  {:04}    {:02}    {:02}    {:02}    {:02}   {:02}.{:07}     {:x}",
-                            y, m, d, hh, mm, ss, nanos, e.time_scale
+                            y,
+                            m,
+                            d,
+                            hh,
+                            mm,
+                            ss,
+                            nanos / 100,
+                            e.time_scale
                         ),
                         "TIME OF LAST OBS"
                     )
@@ -1596,32 +1871,51 @@ impl Header {
                     /*
                      * List of observables
                      */
-                    let mut descriptor = String::new();
-                    if let Some((_constell, observables)) = obs.codes.iter().next() {
-                        descriptor.push_str(&format!("{:6}", observables.len()));
-                        for (i, observable) in observables.iter().enumerate() {
-                            if (i % 9) == 0 && i > 0 {
-                                descriptor.push_str("      "); // TAB
-                            }
-                            descriptor.push_str(&format!("    {}", observable));
-                        }
-                        writeln!(f, "{}", fmt_rinex(&descriptor, "# / TYPES OF OBSERV"))?;
+                    // first constellation, for reproducible production
+                    if let Some((_constell, observables)) =
+                        obs.codes.iter().min_by_key(|(constell, _)| **constell)
+                    {
+                        writeln!(
+                            f,
+                            "{}",
+                            fmt_code_list(
+                                &format!("{:6}", observables.len()),
+                                observables,
+                                6,
+                                9,
+                                "# / TYPES OF OBSERV"
+                            )
+                        )?;
                     }
                 },
                 _ => {
                     /*
                      * List of observables
                      */
-                    for (constell, observables) in &obs.codes {
-                        let mut descriptor = String::new();
-                        descriptor.push_str(&format!("{:x}{:5}", constell, observables.len()));
-                        for observable in observables {
-                            descriptor.push_str(&format!(" {}", observable));
-                        }
-                        writeln!(f, "{}", fmt_rinex(&descriptor, "SYS / # / OBS TYPES"))?;
+                    // sorted, for reproducible production
+                    let mut codes: Vec<_> = obs.codes.iter().collect();
+                    codes.sort_by_key(|(constell, _)| **constell);
+                    for (constell, observables) in codes {
+                        writeln!(
+                            f,
+                            "{}",
+                            fmt_code_list(
+                                &format!("{:x}{:5}", constell, observables.len()),
+                                observables,
+                                4,
+                                13,
+                                "SYS / # / OBS TYPES"
+                            )
+                        )?;
                     }
                 },
             }
+            if obs.clock_offset_applied {
+                writeln!(f, "{}", fmt_rinex("     1", "RCV CLOCK OFFS APPL"))?;
+            }
+            if obs.precision_comment && obs.precision != Precision::RINEX {
+                writeln!(f, "{}", fmt_comment(&obs.precision.to_comment()))?;
+            }
             // must take place after list of observables:
             //  TODO scaling factor
             //  TODO DCBS compensations
@@ -1640,6 +1934,36 @@ impl Header {
     }
 }
 
+/*
+ * Formats a list of codes, right aligned on `width` characters,
+ * `per_line` codes per header line: continuation lines
+ * have a blank leading field, in place of `first`
+ */
+fn fmt_code_list<T: std::fmt::Display>(
+    first: &str,
+    codes: &[T],
+    width: usize,
+    per_line: usize,
+    marker: &str,
+) -> String {
+    let mut lines = Vec::<String>::new();
+    for (i, chunk) in codes.chunks(per_line).enumerate() {
+        let mut descriptor = if i == 0 {
+            first.to_string()
+        } else {
+            " ".repeat(first.len())
+        };
+        for code in chunk {
+            descriptor.push_str(&format!("{:>width$}", code.to_string(), width = width));
+        }
+        lines.push(fmt_rinex(&descriptor, marker));
+    }
+    if lines.is_empty() {
+        lines.push(fmt_rinex(first, marker));
+    }
+    lines.join("\n")
+}
+
 impl std::fmt::Display for Header {
     /// `Header` formatter, mainly for RINEX file production purposes
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -1669,7 +1993,7 @@ impl std::fmt::Display for Header {
             "{}",
             fmt_rinex(
                 &format!("{:<20}{}", self.observer, self.agency),
-                "OBSERVER /AGENCY"
+                "OBSERVER / AGENCY"
             )
         )?;
 
@@ -1695,7 +2019,7 @@ impl std::fmt::Display for Header {
                 f,
                 "{}",
                 fmt_rinex(
-                    &format!("{:<20}{}", antenna.model, antenna.sn),
+                    &format!("{:<20}{}", antenna.sn, antenna.model),
                     "ANT # / TYPE"
                 )
             )?;
@@ -1735,12 +2059,8 @@ impl std::fmt::Display for Header {
             )?;
         }
         // INTERVAL
-        if let Some(interval) = &self.sampling_interval {
-            writeln!(
-                f,
-                "{}",
-                fmt_rinex(&format!("{:6}", interval.to_seconds()), "INTERVAL")
-            )?;
+        if let Some(interval) = self.sampling_interval {
+            writeln!(f, "{}", fmt_rinex(&interval.to_header_field(), "INTERVAL"))?;
         }
 
         // LEAP
@@ -1914,6 +2234,12 @@ impl Merge for Header {
                 lhs.clock_offset_applied |= rhs.clock_offset_applied;
             }
         }
+        match (&mut self.nav, &rhs.nav) {
+            (Some(lhs), Some(rhs)) => lhs.merge_mut(rhs),
+            (None, Some(rhs)) => self.nav = Some(rhs.clone()),
+            _ => {},
+        }
+        self.sync_ionod_correction();
         if let Some(lhs) = &mut self.meteo {
             if let Some(rhs) = &rhs.meteo {
                 merge::merge_mut_unique_vec(&mut lhs.codes, &rhs.codes);
@@ -0,0 +1,399 @@
+//! Fast integrity check, prior parsing.
+//!
+//! [check] scans the raw content of a file, line by line, without parsing it.
+//! It detects the defects of corrupt downloads: truncated content
+//! (incomplete final epoch, missing line ending, truncated gzip stream),
+//! binary garbage, mixed line endings and non ASCII bytes.
+//! Each [Finding] is reported with its byte offset, so ingestion pipelines
+//! can quarantine such files before parsing them:
+//! ```
+//! use rinex::integrity;
+//!
+//! let report = integrity::check_file("../test_resources/OBS/V3/DUTH0630.22O")
+//!     .unwrap();
+//! assert!(report.is_ok());
+//!
+//! // truncated download
+//! let content = std::fs::read("../test_resources/OBS/V3/DUTH0630.22O")
+//!     .unwrap();
+//! let report = integrity::check(&content[..content.len() - 200]);
+//! assert!(!report.is_ok());
+//! for finding in report.findings {
+//!     println!("offset {}: {}", finding.offset, finding.issue);
+//! }
+//! ```
+use crate::{fieldslice, observation, reader::BufferedReader, version::Version};
+use std::io::BufRead;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Integrity [Issue]s
+#[derive(Error, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Issue {
+    /// Content could not be read until its end,
+    /// typically a truncated gzip stream
+    #[error("read error: {0}")]
+    ReadError(String),
+    /// Header is not terminated
+    #[error("missing END OF HEADER")]
+    MissingEndOfHeader,
+    /// Epoch is not complete: fewer lines than announced
+    #[error("incomplete epoch: {found} lines out of {expected}")]
+    IncompleteEpoch { expected: usize, found: usize },
+    /// Final line is not terminated
+    #[error("missing final line ending")]
+    MissingFinalNewline,
+    /// Control characters (binary garbage)
+    #[error("binary content")]
+    Binary,
+    /// Line ending differs from the first line ending of the file
+    #[error("mixed line endings")]
+    MixedLineEndings,
+    /// Non ASCII bytes
+    #[error("non ASCII content")]
+    NonAscii,
+}
+
+/// [Finding] of an integrity check
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Finding {
+    /// Byte offset (of decompressed content, for gzip files)
+    pub offset: u64,
+    /// Line number, starting at 1
+    pub line: usize,
+    /// Number of consecutive lines affected
+    pub lines: usize,
+    /// [Issue] encountered
+    pub issue: Issue,
+}
+
+/// Integrity check report, see [check]
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct IntegrityReport {
+    /// Total number of bytes scanned
+    pub bytes: u64,
+    /// Total number of lines scanned
+    pub lines: usize,
+    /// Findings, in order of appearance
+    pub findings: Vec<Finding>,
+}
+
+impl IntegrityReport {
+    /// Returns true if no issue was found.
+    /// Non ASCII content, which is tolerated by most parsers, is not considered.
+    pub fn is_ok(&self) -> bool {
+        self.findings
+            .iter()
+            .all(|finding| finding.issue == Issue::NonAscii)
+    }
+    /// Returns true if content is truncated
+    pub fn is_truncated(&self) -> bool {
+        self.findings.iter().any(|finding| {
+            matches!(
+                finding.issue,
+                Issue::ReadError(_)
+                    | Issue::MissingEndOfHeader
+                    | Issue::IncompleteEpoch { .. }
+                    | Issue::MissingFinalNewline
+            )
+        })
+    }
+    /*
+     * Stores a new finding, consecutive lines affected
+     * by the same issue are reported once
+     */
+    fn push(&mut self, offset: u64, line: usize, issue: Issue) {
+        if let Some(last) = self.findings.last_mut() {
+            if last.issue == issue && last.line + last.lines == line {
+                last.lines += 1;
+                return;
+            }
+        }
+        self.findings.push(Finding {
+            offset,
+            line,
+            lines: 1,
+            issue,
+        });
+    }
+}
+
+/*
+ * Header attributes, needed to verify the completeness of each epoch.
+ * Only readable Observation RINEX are verified.
+ */
+#[derive(Default)]
+struct Layout {
+    version: Version,
+    observation: bool,
+    crinex: bool,
+    /// (V2) number of observables
+    observables: usize,
+}
+
+impl Layout {
+    fn parse(&mut self, line: &str) {
+        let (content, marker) = match fieldslice::split_at(line, 60) {
+            Ok(split) => split,
+            Err(_) => return,
+        };
+        let marker = marker.trim();
+        if marker == "RINEX VERSION / TYPE" {
+            let version = fieldslice::field(content, 0, 9).unwrap_or("");
+            self.version = Version::from_str(version.trim()).unwrap_or_default();
+            self.observation = fieldslice::field(content, 20, 21) == Ok("O");
+        } else if marker == "CRINEX VERS   / TYPE" {
+            self.crinex = true;
+        } else if marker == "# / TYPES OF OBSERV" {
+            let count = fieldslice::field(content, 0, 6).unwrap_or("").trim();
+            if let Ok(count) = count.parse::<usize>() {
+                self.observables = count;
+            }
+        }
+    }
+    /*
+     * Returns the number of lines that follow given epoch descriptor,
+     * if it is one
+     */
+    fn epoch_lines(&self, line: &str) -> Option<usize> {
+        if !self.observation || self.crinex || !line.is_ascii() {
+            return None;
+        }
+        if !observation::record::is_new_epoch(line, self.version) {
+            return None;
+        }
+        let (flag, count) = match self.version.major {
+            1 | 2 => (
+                fieldslice::field(line, 28, 29).ok()?,
+                fieldslice::field(line, 29, 32).ok()?,
+            ),
+            _ => (
+                fieldslice::field(line, 31, 32).ok()?,
+                fieldslice::field(line, 32, 35).ok()?,
+            ),
+        };
+        let count = count.trim().parse::<usize>().ok()?;
+        if matches!(flag.trim(), "2" | "3" | "4" | "5") || self.version.major > 2 {
+            // special records, or one line per satellite
+            return Some(count);
+        }
+        let continuations = count.saturating_sub(1) / 12;
+        let per_sv = (self.observables + 4) / 5;
+        Some(continuations + count * per_sv.max(1))
+    }
+}
+
+/// Checks the integrity of given content, see [check_reader]
+pub fn check(content: &[u8]) -> IntegrityReport {
+    check_reader(content)
+}
+
+/// Checks the integrity of the content of given file, see [check_reader].
+/// gzip compressed files are supported, byte offsets then refer to the decompressed content.
+pub fn check_file(path: &str) -> std::io::Result<IntegrityReport> {
+    let reader = BufferedReader::new(path)?;
+    Ok(check_reader(reader))
+}
+
+/// Checks the integrity of the content of given reader, without parsing it:
+///  - read errors, typically a truncated gzip stream
+///  - missing END OF HEADER
+///  - incomplete epochs, in readable Observation RINEX:
+///    fewer lines than the epoch descriptor announced
+///  - missing final line ending, which indicates a truncated file
+///  - binary content (control characters)
+///  - mixed line endings (LF and CRLF)
+///  - non ASCII bytes
+pub fn check_reader<R: BufRead>(mut reader: R) -> IntegrityReport {
+    let mut report = IntegrityReport::default();
+    let mut buffer = Vec::<u8>::with_capacity(128);
+    let mut layout = Layout::default();
+    let mut end_of_header = false;
+    let mut crlf: Option<bool> = None;
+    // (offset, line, expected, found) of the current epoch
+    let mut epoch: Option<(u64, usize, usize, usize)> = None;
+    let mut terminated = true;
+
+    loop {
+        buffer.clear();
+        let size = match reader.read_until(b'\n', &mut buffer) {
+            Ok(0) => break,
+            Ok(size) => size,
+            Err(e) => {
+                report.push(
+                    report.bytes,
+                    report.lines + 1,
+                    Issue::ReadError(e.to_string()),
+                );
+                return report;
+            },
+        };
+        let offset = report.bytes;
+        report.bytes += size as u64;
+        report.lines += 1;
+        let line_number = report.lines;
+
+        // line ending
+        terminated = buffer.ends_with(b"\n");
+        let content = match (terminated, buffer.ends_with(b"\r\n")) {
+            (true, true) => &buffer[..size - 2],
+            (true, false) => &buffer[..size - 1],
+            _ => &buffer[..],
+        };
+        if terminated {
+            let is_crlf = buffer.ends_with(b"\r\n");
+            match crlf {
+                None => crlf = Some(is_crlf),
+                Some(crlf) if crlf != is_crlf => {
+                    report.push(
+                        offset + content.len() as u64,
+                        line_number,
+                        Issue::MixedLineEndings,
+                    );
+                },
+                _ => {},
+            }
+        }
+
+        // control characters, then non ASCII bytes
+        let binary = content
+            .iter()
+            .position(|b| (*b < 0x20 && *b != b'\t') || *b == 0x7f);
+        if let Some(index) = binary {
+            report.push(offset + index as u64, line_number, Issue::Binary);
+        } else if let Some(index) = content.iter().position(|b| !b.is_ascii()) {
+            report.push(offset + index as u64, line_number, Issue::NonAscii);
+        }
+
+        let line = String::from_utf8_lossy(content);
+        if !end_of_header {
+            if line.trim_end().ends_with("END OF HEADER") {
+                end_of_header = true;
+            } else {
+                layout.parse(&line);
+            }
+            continue;
+        }
+
+        // epochs completeness
+        if let Some(expected) = layout.epoch_lines(&line) {
+            if let Some((offset, line, expected, found)) = epoch {
+                if found < expected {
+                    report.push(offset, line, Issue::IncompleteEpoch { expected, found });
+                }
+            }
+            epoch = Some((offset, line_number, expected, 0));
+        } else if let Some((_, _, _, found)) = &mut epoch {
+            *found += 1;
+        }
+    }
+
+    if !end_of_header {
+        report.push(report.bytes, report.lines, Issue::MissingEndOfHeader);
+    }
+    if let Some((offset, line, expected, found)) = epoch {
+        if found < expected {
+            report.push(offset, line, Issue::IncompleteEpoch { expected, found });
+        }
+    }
+    if !terminated {
+        report.push(report.bytes, report.lines, Issue::MissingFinalNewline);
+    }
+    report
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    const V3: &str =
+        "     3.04           OBSERVATION DATA    M                   RINEX VERSION / TYPE
+G    2 C1C L1C                                              SYS / # / OBS TYPES
+                                                            END OF HEADER
+> 2022 03 04 00 00  0.0000000  0  2
+G01  20243517.560   106380411.41808
+G03  20619020.680   108353702.79708
+> 2022 03 04 00 00 30.0000000  0  2
+G01  20243517.560   106380411.41808
+G03  20619020.680   108353702.79708
+";
+    const V2: &str =
+        "     2.11           OBSERVATION DATA    G (GPS)             RINEX VERSION / TYPE
+     6    C1    L1    L2    P2    S1    S2                  # / TYPES OF OBSERV
+                                                            END OF HEADER
+ 21 12 21  0  0  0.0000000  0  2G07G08
+  20243517.560   106380411.41808    82893846.80009    20243518.680          51.250
+        54.750
+  21208865.370   111454536.50108    86847701.08508    21208866.000          48.000
+        45.250
+";
+    #[test]
+    fn valid() {
+        for content in [V3, V2] {
+            let report = check(content.as_bytes());
+            assert!(report.is_ok(), "{:?}", report.findings);
+            assert_eq!(report.bytes, content.len() as u64);
+            assert_eq!(report.lines, content.lines().count());
+        }
+    }
+    #[test]
+    fn truncated() {
+        for content in [V3, V2] {
+            // final line is lost
+            let end = content.trim_end().rfind('\n').unwrap() + 1;
+            let report = check(&content.as_bytes()[..end]);
+            assert!(report.is_truncated());
+            assert!(matches!(
+                report.findings[0].issue,
+                Issue::IncompleteEpoch { .. }
+            ));
+            // cut in the middle of a line
+            let report = check(&content.as_bytes()[..content.len() - 5]);
+            assert!(report.is_truncated());
+            assert_eq!(
+                report.findings.last().unwrap().issue,
+                Issue::MissingFinalNewline
+            );
+        }
+        // truncated header
+        let report = check(&V3.as_bytes()[..100]);
+        assert!(report
+            .findings
+            .iter()
+            .any(|finding| finding.issue == Issue::MissingEndOfHeader));
+    }
+    #[test]
+    fn garbage() {
+        let mut content = V3.as_bytes().to_vec();
+        let offset = V3.find("G03").unwrap();
+        content[offset + 5] = 0x00;
+        content[offset + 6] = 0x1b;
+        let report = check(&content);
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].offset, offset as u64 + 5);
+        assert_eq!(report.findings[0].issue, Issue::Binary);
+        assert_eq!(report.findings[0].line, 6);
+    }
+    #[test]
+    fn line_endings() {
+        let mut lines: Vec<String> = V3.lines().map(|l| l.to_string()).collect();
+        lines[4].push('\r');
+        lines[5].push('\r');
+        let content = lines.join("\n") + "\n";
+        let report = check(content.as_bytes());
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].issue, Issue::MixedLineEndings);
+        assert_eq!(report.findings[0].line, 5);
+        assert_eq!(report.findings[0].lines, 2);
+        // non ASCII bytes are tolerated
+        let content = V3.replace("G    2", "G    2 Zürich");
+        let report = check(content.as_bytes());
+        assert!(report.is_ok());
+        assert_eq!(report.findings[0].issue, Issue::NonAscii);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{merge, merge::Merge, prelude::*, split, split::Split};
 
-use crate::epoch;
+use crate::{epoch, fieldslice};
 use hifitime::Duration;
 use std::collections::{BTreeMap, HashMap};
 use std::str::FromStr;
@@ -32,6 +32,59 @@ pub struct TEC {
 
 pub type TECPlane = HashMap<(i32, i32), TEC>;
 
+/*
+ * Grid nodes are indexed by their rounded coordinates,
+ * as they are stored when parsing
+ */
+#[cfg(feature = "ionex")]
+fn plane_key(latitude: f64, longitude: f64) -> (i32, i32) {
+    (
+        (latitude.round() * 1000.0_f64) as i32,
+        (longitude.round() * 1000.0_f64) as i32,
+    )
+}
+
+/*
+ * Bilinear interpolation of the TEC value at given coordinates (ddeg),
+ * between the four surrounding nodes of the grid
+ */
+#[cfg(feature = "ionex")]
+pub(crate) fn interpolate_plane(
+    plane: &TECPlane,
+    grid: &super::Grid,
+    latitude: f64,
+    longitude: f64,
+) -> Option<f64> {
+    let (lat, lon) = (&grid.latitude, &grid.longitude);
+    if lat.spacing == 0.0 || lon.spacing == 0.0 {
+        return None;
+    }
+    let (lat_pos, lon_pos) = (
+        (latitude - lat.start) / lat.spacing,
+        (longitude - lon.start) / lon.spacing,
+    );
+    let (lat_last, lon_last) = (
+        ((lat.end - lat.start) / lat.spacing).round(),
+        ((lon.end - lon.start) / lon.spacing).round(),
+    );
+    if lat_pos < 0.0 || lat_pos > lat_last || lon_pos < 0.0 || lon_pos > lon_last {
+        return None; // outside the grid
+    }
+    // lower node, so the upper one remains within the grid
+    let (i, j) = (
+        lat_pos.floor().min(lat_last - 1.0).max(0.0),
+        lon_pos.floor().min(lon_last - 1.0).max(0.0),
+    );
+    let (u, v) = (lat_pos - i, lon_pos - j);
+    let node = |i: f64, j: f64| -> Option<f64> {
+        let key = plane_key(lat.start + i * lat.spacing, lon.start + j * lon.spacing);
+        plane.get(&key).map(|tec| tec.tec)
+    };
+    let (t00, t01) = (node(i, j)?, node(i, j + 1.0)?);
+    let (t10, t11) = (node(i + 1.0, j)?, node(i + 1.0, j + 1.0)?);
+    Some((1.0 - u) * (1.0 - v) * t00 + (1.0 - u) * v * t01 + u * (1.0 - v) * t10 + u * v * t11)
+}
+
 /// IONEX contains 2D (fixed altitude) or 3D Ionosphere Maps.
 /// See [Rinex::ionex] and related feature for more information.
 /// ```
@@ -70,6 +123,8 @@ pub enum Error {
     CoordinatesParsing(String, String),
     #[error("failed to parse epoch")]
     EpochParsing(#[from] epoch::ParsingError),
+    #[error("malformed field")]
+    FieldError(#[from] fieldslice::Error),
 }
 
 /*
@@ -113,7 +168,7 @@ pub(crate) fn parse_plane(
 
     for line in lines {
         if line.len() > 60 {
-            let (content, marker) = line.split_at(60);
+            let (content, marker) = fieldslice::split_at(line, 60)?;
             if marker.contains("START OF") {
                 continue; // skip that one
             } else if marker.contains("END OF") && marker.contains("MAP") {
@@ -6,20 +6,35 @@
 extern crate gnss_rs as gnss;
 
 pub mod antex;
+pub mod cache;
 pub mod carrier;
+#[cfg(feature = "catalog")]
+#[cfg_attr(docrs, doc(cfg(feature = "catalog")))]
+pub mod catalog;
 pub mod clocks;
+pub mod constants;
+pub mod eop;
 pub mod epoch;
+pub mod estimation;
+pub mod fieldslice;
+pub mod formatter;
 pub mod gnss_time;
 pub mod hardware;
 pub mod hatanaka;
 pub mod header;
+pub mod integrity;
 pub mod ionex;
 pub mod marker;
 pub mod merge;
 pub mod meteo;
 pub mod navigation;
 pub mod observation;
+pub mod progress;
 pub mod record;
+pub mod sampling;
+pub mod satellite;
+pub mod shared;
+pub mod site_displacement;
 pub mod split;
 pub mod types;
 pub mod version;
@@ -31,6 +46,9 @@ mod linspace; // grid and linear spacing
 mod observable;
 mod production; // RINEX production infrastructure // physical observations
 
+#[cfg(all(feature = "obs", any(feature = "nav", feature = "sp3")))]
+mod propagation; // light time and earth rotation
+
 #[cfg(test)]
 mod tests;
 
@@ -46,10 +64,10 @@ extern crate num_derive;
 extern crate lazy_static;
 
 pub mod reader;
-use reader::BufferedReader;
+use reader::{BufferedReader, ParserSettings};
 
 pub mod writer;
-use writer::BufferedWriter;
+use writer::{BufferedWriter, WriterSettings};
 
 use std::collections::{BTreeMap, HashMap};
 use std::io::Write; //, Read};
@@ -59,10 +77,9 @@ use std::str::FromStr;
 use thiserror::Error;
 
 use antex::{Antenna, AntennaSpecific, FrequencyDependentData};
-use epoch::epoch_decompose;
+use epoch::{epoch_decompose, CalendarDate};
 use ionex::TECPlane;
 use observable::Observable;
-use observation::Crinex;
 use version::Version;
 
 use production::{ProductionAttributes, FFU, PPU};
@@ -75,11 +92,13 @@ pub mod prelude {
     #[cfg(feature = "antex")]
     pub use crate::antex::AntennaMatcher;
     #[cfg(feature = "sp3")]
-    pub use crate::context::RnxContext;
+    pub use crate::context::{ProductType, RnxContext};
     pub use crate::epoch::EpochFlag;
     pub use crate::ground_position::GroundPosition;
     pub use crate::header::Header;
-    pub use crate::observable::Observable;
+    pub use crate::observable::{Observable, ObservationCodes};
+    pub use crate::production::Stamp;
+    pub use crate::sampling::SamplingInterval;
     pub use crate::types::Type as RinexType;
     pub use crate::Rinex;
     pub use gnss::prelude::Constellation;
@@ -105,6 +124,22 @@ extern crate horrorshow;
 #[cfg(feature = "sp3")]
 mod context;
 
+#[cfg(all(feature = "obs", feature = "catalog"))]
+#[cfg_attr(docrs, doc(cfg(all(feature = "obs", feature = "catalog"))))]
+pub mod campaign;
+
+#[cfg(feature = "obs")]
+#[cfg_attr(docrs, doc(cfg(feature = "obs")))]
+pub mod sql;
+
+#[cfg(all(feature = "sp3", feature = "obs"))]
+#[cfg_attr(docrs, doc(cfg(all(feature = "sp3", feature = "obs"))))]
+pub mod ppp;
+
+#[cfg(feature = "processing")]
+#[cfg_attr(docrs, doc(cfg(feature = "processing")))]
+pub mod processing;
+
 use carrier::Carrier;
 use prelude::*;
 
@@ -152,10 +187,30 @@ pub(crate) fn fmt_rinex(content: &str, marker: &str) -> String {
 }
 
 /*
- * macro to generate comments with standardized formatting
+ * macro to generate comments with standardized formatting:
+ * long comments are wrapped on word boundaries
  */
 pub(crate) fn fmt_comment(content: &str) -> String {
-    fmt_rinex(content, "COMMENT")
+    let mut lines = Vec::<String>::new();
+    let mut rem = content;
+    while rem.len() > 60 {
+        let mut cut = 60;
+        while !rem.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        // break on last blank that fits, words longer than a line are split
+        let cut = match rem[..cut].rfind(' ') {
+            _ if rem[cut..].starts_with(' ') => cut,
+            Some(blank) if blank > 0 => blank,
+            _ => cut,
+        };
+        lines.push(fmt_rinex(rem[..cut].trim_end(), "COMMENT"));
+        rem = rem[cut..].trim_start();
+    }
+    if lines.is_empty() || !rem.is_empty() {
+        lines.push(fmt_rinex(rem, "COMMENT"));
+    }
+    lines.join("\n")
 }
 
 #[derive(Clone, Default, Debug, PartialEq)]
@@ -203,7 +258,7 @@ pub(crate) fn fmt_comment(content: &str) -> String {
 /// // comments encountered in the Header section
 /// println!("{:#?}", rnx.header.comments);
 /// // sampling interval was set
-/// assert_eq!(rnx.header.sampling_interval, Some(Duration::from_seconds(30.0))); // 30s sample rate
+/// assert_eq!(rnx.sample_rate(), Some(Duration::from_seconds(30.0))); // 30s sample rate
 /// // record content is RINEX format dependent.
 /// // This one is Observation RINEX.
 /// // Refer to [record::Record] definitions, to understand
@@ -214,12 +269,13 @@ pub(crate) fn fmt_comment(content: &str) -> String {
 ///     // Do something
 /// }
 /// // comments encountered in file body
-/// // are currently stored like this and indexed by epoch of "appearance"
-/// // they are currently not really exploited
+/// // are stored like this and indexed by epoch of "appearance".
+/// // They are written back in place when producing a file
 /// for (epoch, comment) in rnx.comments {
 ///     println!("{:?}: \"{:?}\"", epoch, comment);
 /// }
 /// ```
+#[non_exhaustive]
 pub struct Rinex {
     /// `header` field contains general information
     pub header: Header,
@@ -227,9 +283,15 @@ pub struct Rinex {
     /// found in `record` section exclusively.    
     /// Comments extracted from `header` sections are exposed in `header.comments`
     pub comments: record::Comments,
+    /// Observation `events` (antenna swap, new site occupation..),
+    /// with their special records, see [record::Events]
+    pub events: record::Events,
     /// `record` contains `RINEX` file body
     /// and is type and constellation dependent
     pub record: record::Record,
+    /// Missing data placeholders that were normalized to absent data at parse time,
+    /// per [Observable], see [observation::MissingDataRules]
+    pub normalized: BTreeMap<Observable, usize>,
     /*
      * File Production attributes, attached to Self
      * parsed from files that follow stadard naming conventions
@@ -246,6 +308,8 @@ pub enum Error {
     RecordError(#[from] record::Error),
     #[error("file i/o error")]
     IoError(#[from] std::io::Error),
+    #[error("unknown file format")]
+    UnknownFormat,
 }
 
 impl Rinex {
@@ -255,6 +319,8 @@ impl Rinex {
             header,
             record,
             comments: record::Comments::new(),
+            events: record::Events::new(),
+            normalized: BTreeMap::new(),
             prod_attr: None,
         }
     }
@@ -264,9 +330,38 @@ impl Rinex {
             header,
             record: self.record.clone(),
             comments: self.comments.clone(),
+            events: self.events.clone(),
+            normalized: self.normalized.clone(),
             prod_attr: self.prod_attr.clone(),
         }
     }
+    /// Returns estimated memory footprint of self, in bytes,
+    /// mostly driven by the record. See [observation::CompactRecord]
+    /// for a lighter Observation storage.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rinex = Rinex::from_file("../test_resources/MET/V2/abvi0010.15m")
+    ///     .unwrap();
+    /// assert!(rinex.memory_usage() > rinex.record.memory_usage());
+    /// ```
+    pub fn memory_usage(&self) -> usize {
+        std::mem::size_of::<Self>() - std::mem::size_of::<record::Record>()
+            + self.record.memory_usage()
+            + record::btreemap_usage(&self.comments)
+            + self
+                .comments
+                .values()
+                .flatten()
+                .map(|comment| comment.capacity() + std::mem::size_of::<String>())
+                .sum::<usize>()
+            + record::btreemap_usage(&self.events)
+            + self
+                .events
+                .values()
+                .flatten()
+                .map(|line| line.capacity() + std::mem::size_of::<String>())
+                .sum::<usize>()
+    }
     /// Replaces header section.
     pub fn replace_header(&mut self, header: Header) {
         self.header = header.clone();
@@ -276,10 +371,18 @@ impl Rinex {
         Rinex {
             header: self.header.clone(),
             comments: self.comments.clone(),
+            events: self.events.clone(),
             record,
+            normalized: self.normalized.clone(),
             prod_attr: self.prod_attr.clone(),
         }
     }
+    /// Returns a copy of self with given Observation [record::Events].
+    pub fn with_events(&self, events: record::Events) -> Self {
+        let mut s = self.clone();
+        s.events = events;
+        s
+    }
     /// Replaces internal record.
     pub fn replace_record(&mut self, record: record::Record) {
         self.record = record.clone();
@@ -305,14 +408,14 @@ impl Rinex {
     }
     /// [`Self::rnx2crnx`] mutable implementation
     pub fn rnx2crnx_mut(&mut self) {
-        if self.is_observation_rinex() {
-            let mut crinex = Crinex::default();
-            crinex.version.major = match self.header.version.major {
-                1 | 2 => 1,
-                _ => 3,
-            };
-            self.header = self.header.with_crinex(crinex);
-        }
+        let major = match self.header.version.major {
+            1 | 2 => 1,
+            _ => 3,
+        };
+        self.header = self.header.to_crinex_header(
+            Version { major, minor: 0 },
+            &format!("rust-rinex-{}", env!("CARGO_PKG_VERSION")),
+        );
     }
 
     /// Converts self to CRINEX1 compressed format,
@@ -326,13 +429,10 @@ impl Rinex {
 
     /// [`Self::rnx2crnx1`] mutable implementation.
     pub fn rnx2crnx1_mut(&mut self) {
-        if self.is_observation_rinex() {
-            self.header = self.header.with_crinex(Crinex {
-                version: Version { major: 1, minor: 0 },
-                date: epoch::now(),
-                prog: format!("rust-rinex-{}", env!("CARGO_PKG_VERSION")),
-            });
-        }
+        self.header = self.header.to_crinex_header(
+            Version { major: 1, minor: 0 },
+            &format!("rust-rinex-{}", env!("CARGO_PKG_VERSION")),
+        );
     }
 
     /// Converts self to CRINEX3 compressed format,
@@ -340,19 +440,16 @@ impl Rinex {
     /// This can be used to "force" compression of a RINEX1 into CRINEX3
     pub fn rnx2crnx3(&self) -> Self {
         let mut s = self.clone();
-        s.rnx2crnx1_mut();
+        s.rnx2crnx3_mut();
         s
     }
 
     /// [`Self::rnx2crnx3`] mutable implementation.
     pub fn rnx2crnx3_mut(&mut self) {
-        if self.is_observation_rinex() {
-            self.header = self.header.with_crinex(Crinex {
-                date: epoch::now(),
-                version: Version { major: 3, minor: 0 },
-                prog: "rust-crinex".to_string(),
-            });
-        }
+        self.header = self.header.to_crinex_header(
+            Version { major: 3, minor: 0 },
+            &format!("rust-rinex-{}", env!("CARGO_PKG_VERSION")),
+        );
     }
 
     /// Converts a CRINEX (compressed RINEX) into readable RINEX.
@@ -376,9 +473,99 @@ impl Rinex {
                     scalings: params.scalings.clone(),
                     time_of_first_obs: params.time_of_first_obs,
                     time_of_last_obs: params.time_of_last_obs,
+                    precision: params.precision,
+                    precision_comment: params.precision_comment,
                 });
         }
     }
+    /// Reorders the observables of each constellation in canonical order
+    /// (Pseudo Range, then Phase, Doppler and signal strength, each by ascending band),
+    /// see [Observable::canonical_cmp]. Columns follow the header definitions
+    /// when formatting, so files from different receivers become comparable
+    /// column-wise. This has no effect if self is not an Observation RINEX.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap()
+    ///     .reorder_observables();
+    /// let codes = &rinex.header.obs.as_ref().unwrap().codes;
+    /// let gps: Vec<String> = codes[&Constellation::GPS]
+    ///     .iter()
+    ///     .map(|obs| obs.to_string())
+    ///     .collect();
+    /// assert_eq!(gps, vec!["C1C", "C2W", "L1C", "L2W", "D1C", "D2W", "S1C", "S2W"]);
+    /// ```
+    pub fn reorder_observables(&self) -> Self {
+        let mut s = self.clone();
+        s.reorder_observables_mut();
+        s
+    }
+    /// [Rinex::reorder_observables] mutable implementation
+    pub fn reorder_observables_mut(&mut self) {
+        if let Some(obs) = &mut self.header.obs {
+            for observables in obs.codes.values_mut() {
+                observables.sort_by(|a, b| a.canonical_cmp(b));
+            }
+        }
+    }
+    /// Inserts an event epoch into this Observation RINEX. `flag` must describe
+    /// an event (flags 2 to 5, see [EpochFlag::is_event]), otherwise this is a no-op.
+    /// `records` are the special records that follow the event descriptor:
+    /// complete header lines (content and label). They are appended to
+    /// the records of a previous event at the same epoch, if any.
+    /// Events are preserved when writing, either in RINEX or CRINEX.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let mut rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let t = Epoch::from_gregorian_utc_hms(2022, 3, 4, 0, 0, 0);
+    /// rinex.insert_event(t, EpochFlag::ExternalEvent, vec![
+    ///     "Receiver power cycled                                       COMMENT".to_string(),
+    /// ]);
+    /// assert_eq!(rinex.events.len(), 1);
+    /// ```
+    pub fn insert_event(&mut self, epoch: Epoch, flag: EpochFlag, records: Vec<String>) {
+        if self.is_observation_rinex() && flag.is_event() {
+            self.events
+                .entry((epoch, flag))
+                .or_default()
+                .extend(records);
+        }
+    }
+    /// Inserts an antenna swap event into this Observation RINEX:
+    /// new `antenna` is in use from `epoch` onwards.
+    /// It is described by `ANT # / TYPE` and `ANTENNA: DELTA H/E/N`
+    /// special records, following a "header information follows" event.
+    pub fn insert_antenna_swap(&mut self, epoch: Epoch, antenna: &hardware::Antenna) {
+        let records = vec![
+            fmt_rinex(
+                &format!("{:<20}{}", antenna.sn, antenna.model),
+                "ANT # / TYPE",
+            ),
+            fmt_rinex(
+                &format!(
+                    "{:14.4}{:14.4}{:14.4}",
+                    antenna.height.unwrap_or(0.0),
+                    antenna.eastern.unwrap_or(0.0),
+                    antenna.northern.unwrap_or(0.0)
+                ),
+                "ANTENNA: DELTA H/E/N",
+            ),
+        ];
+        self.insert_event(epoch, EpochFlag::HeaderInformationFollows, records);
+    }
+    /// Inserts a marker change event into this Observation RINEX:
+    /// receiver occupies the new `marker` from `epoch` onwards.
+    /// It is described by `MARKER NAME` (and `MARKER NUMBER`) special records,
+    /// following a "new site occupation" event.
+    pub fn insert_marker_change(&mut self, epoch: Epoch, marker: &marker::GeodeticMarker) {
+        let mut records = vec![fmt_rinex(&marker.name, "MARKER NAME")];
+        if let Some(number) = marker.number() {
+            records.push(fmt_rinex(&number, "MARKER NUMBER"));
+        }
+        self.insert_event(epoch, EpochFlag::NewSiteOccupation, records);
+    }
     /// Returns a filename that would describe Self according to naming conventions.
     /// For this information to be 100% complete, Self must come from a file
     /// that follows these conventions itself.
@@ -427,7 +614,7 @@ impl Rinex {
                     Some(ref custom) => format!("{:03}", custom.doy),
                     None => {
                         if let Some(epoch) = self.first_epoch() {
-                            let ddd = epoch.day_of_year().round() as u32;
+                            let ddd = CalendarDate::from_epoch(epoch).day_of_year();
                             format!("{:03}", ddd)
                         } else {
                             "DDD".to_string()
@@ -462,7 +649,7 @@ impl Rinex {
                     Some(ref custom) => format!("{:03}", custom.doy),
                     None => {
                         if let Some(epoch) = self.first_epoch() {
-                            let ddd = epoch.day_of_year().round() as u32;
+                            let ddd = CalendarDate::from_epoch(epoch).day_of_year();
                             format!("{:03}", ddd)
                         } else {
                             "DDD".to_string()
@@ -492,6 +679,7 @@ impl Rinex {
                         RinexType::MeteoData => 'M',
                         RinexType::NavigationData => match constellation {
                             Some(Constellation::Glonass) => 'G',
+                            Some(c) if c.is_sbas() => 'H',
                             _ => 'N',
                         },
                         _ => unreachable!("unreachable"),
@@ -641,42 +829,201 @@ impl Rinex {
         Self::from_path(&Path::new(fullpath).to_path_buf())
     }
 
+    /// Identifies the [reader::Format] of given content, from its magic bytes
+    /// and first header label, regardless of the file name.
+    /// [Self::from_file] relies on this to route to the appropriate decoding chain,
+    /// so mislabeled files (wrong extension) are still correctly parsed.
+    /// The reader is only peeked at, not consumed.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::reader::Format;
+    /// use std::fs::File;
+    /// use std::io::BufReader;
+    ///
+    /// let fd = File::open("../test_resources/CRNX/V1/AJAC3550.21D")
+    ///     .unwrap();
+    /// let mut reader = BufReader::new(fd);
+    /// let format = Rinex::detect_format(&mut reader)
+    ///     .unwrap();
+    /// assert_eq!(format, Format::Crinex);
+    /// assert!(!format.is_gzip());
+    /// ```
+    pub fn detect_format<R: std::io::BufRead>(reader: &mut R) -> Result<reader::Format, Error> {
+        reader::detect_format(reader)?.ok_or(Error::UnknownFormat)
+    }
+
     /// See [Self::from_file]
     pub fn from_path(path: &PathBuf) -> Result<Rinex, Error> {
+        Self::parse_path(path, None, &ParserSettings::default(), &mut |_, _| {})
+    }
+
+    /// Same as [Self::from_file], the parser progress being reported
+    /// to given [progress::ProgressSink]: amount of bytes of the file body read so far
+    /// (after .gz decompression), and epochs parsed so far. Large files are then
+    /// not mistaken for a hung process.
+    pub fn from_file_with_progress(
+        fullpath: &str,
+        progress: &mut dyn progress::ProgressSink,
+    ) -> Result<Rinex, Error> {
+        Self::parse_path(
+            Path::new(fullpath),
+            None,
+            &ParserSettings::default(),
+            progress,
+        )
+    }
+
+    /// Same as [Self::from_file], with custom [ParserSettings].
+    /// Content is always normalized (line endings, byte order mark),
+    /// the settings define how non ASCII characters are handled.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::reader::{NonAsciiPolicy, ParserSettings};
+    /// // strict parsing
+    /// let settings = ParserSettings::default()
+    ///     .with_non_ascii_policy(NonAsciiPolicy::Error);
+    /// let rinex = Rinex::from_file_with_settings("../test_resources/OBS/V2/AJAC3550.21O", &settings);
+    /// assert!(rinex.is_ok());
+    /// ```
+    pub fn from_file_with_settings(
+        fullpath: &str,
+        settings: &ParserSettings,
+    ) -> Result<Rinex, Error> {
+        Self::parse_path(Path::new(fullpath), None, settings, &mut |_, _| {})
+    }
+
+    /// Same as [Self::from_file], for Observation RINEX produced by receivers
+    /// that do not comply with the standards: the [observation::MappingProfile] renames
+    /// observation codes, fixes constellation letters and reorders the columns at parse time.
+    /// Constellation letters of compressed (CRINEX) records are not fixed.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::observation::MappingProfile;
+    /// // this receiver is known to use Q for QZSS
+    /// let mut profile = MappingProfile::default();
+    /// profile.constellations.insert("Q".to_string(), "J".to_string());
+    /// profile.order.insert("G".to_string(), vec!["L1C".to_string(), "C1C".to_string()]);
+    /// let rinex = Rinex::from_file_with_profile("../test_resources/OBS/V3/DUTH0630.22O", &profile)
+    ///     .unwrap();
+    /// let codes = &rinex.header.obs.as_ref().unwrap().codes;
+    /// assert_eq!(codes[&Constellation::GPS][0].to_string(), "L1C");
+    /// assert_eq!(codes[&Constellation::GPS][1].to_string(), "C1C");
+    /// ```
+    pub fn from_file_with_profile(
+        fullpath: &str,
+        profile: &observation::MappingProfile,
+    ) -> Result<Rinex, Error> {
+        Self::parse_path(
+            Path::new(fullpath),
+            Some(profile),
+            &ParserSettings::default(),
+            &mut |_, _| {},
+        )
+    }
+
+    fn parse_path(
+        path: &Path,
+        profile: Option<&observation::MappingProfile>,
+        settings: &ParserSettings,
+        progress: &mut dyn progress::ProgressSink,
+    ) -> Result<Rinex, Error> {
         let fullpath = path.to_string_lossy().to_string();
 
+        // binary cache: mapping profiles are not part of it
+        let cache = match profile {
+            None if settings.cache => cache::Fingerprint::new(path)
+                .map(|fingerprint| (cache::cache_path(path), fingerprint)),
+            _ => None,
+        };
+        if let Some((cache_path, fingerprint)) = &cache {
+            if let Ok((record, comments)) = cache::read(cache_path, fingerprint) {
+                let mut reader = BufferedReader::with_settings(&fullpath, settings)?;
+                let header = Header::new(&mut reader)?;
+                if header.rinex_type == types::Type::ObservationData {
+                    return Ok(Rinex {
+                        header,
+                        record,
+                        comments,
+                        events: record::Events::new(),
+                        normalized: BTreeMap::new(),
+                        prod_attr: Self::production_attributes(path),
+                    });
+                }
+            }
+        }
+
         // create buffered reader
-        let mut reader = BufferedReader::new(&fullpath)?;
+        let mut reader = BufferedReader::with_settings(&fullpath, settings)?;
 
         // Parse header fields
-        let mut header = Header::new(&mut reader)?;
+        let mut header = Header::parse(&mut reader, profile)?;
 
         // Parse file body (record content)
         // Comments might serve some fileops like "splice".
-        let (record, comments) = record::parse_record(&mut reader, &mut header)?;
-
-        // Parse / identify production attributes
-        // that only exist in the filename.
-        let prod_attr = match path.file_name() {
-            Some(filename) => {
-                let filename = filename.to_string_lossy().to_string();
-                if let Ok(attrs) = ProductionAttributes::from_str(&filename) {
-                    Some(attrs)
-                } else {
-                    None
-                }
-            },
-            _ => None,
+        let (mut record, comments, events) =
+            record::parse_record_with_profile(&mut reader, &mut header, profile, progress)?;
+
+        // missing data placeholders
+        let normalized = match profile {
+            Some(profile) => record.normalize_missing(&profile.missing),
+            None => BTreeMap::new(),
         };
 
+        // preferred columns order
+        if let (Some(profile), Some(obs)) = (profile, &mut header.obs) {
+            profile.reorder(obs);
+        }
+
+        if let Some((cache_path, fingerprint)) = &cache {
+            // events are not part of the cache
+            if events.is_empty() {
+                let _ = cache::write(cache_path, fingerprint, &record, &comments);
+            }
+        }
+
         Ok(Rinex {
             header,
             record,
             comments,
-            prod_attr,
+            events,
+            normalized,
+            prod_attr: Self::production_attributes(path),
         })
     }
 
+    /// Parses / identifies production attributes
+    /// that only exist in the filename.
+    fn production_attributes(path: &Path) -> Option<ProductionAttributes> {
+        let filename = path.file_name()?.to_string_lossy().to_string();
+        ProductionAttributes::from_str(&filename).ok()
+    }
+
+    /// Same as [Self::from_file], but Observation records are also stored
+    /// in a binary cache next to the source file (see [cache]),
+    /// which is reused on following calls as long as the source file is unchanged.
+    /// Failure to read or write the cache is not an error:
+    /// the source file is parsed instead. See [ParserSettings::cache]
+    /// to enable the cache with other settings.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let source = std::env::temp_dir().join("rinex-from-file-cached.22O");
+    /// std::fs::copy("../test_resources/OBS/V3/DUTH0630.22O", &source)
+    ///     .unwrap();
+    /// let source = source.to_string_lossy().to_string();
+    /// // first call produces the cache
+    /// let rinex = Rinex::from_file_cached(&source)
+    ///     .unwrap();
+    /// // following calls reuse it
+    /// let cached = Rinex::from_file_cached(&source)
+    ///     .unwrap();
+    /// assert_eq!(rinex, cached);
+    /// # std::fs::remove_file(rinex::cache::cache_path(source.as_ref())).unwrap();
+    /// # std::fs::remove_file(&source).unwrap();
+    /// ```
+    pub fn from_file_cached(fullpath: &str) -> Result<Rinex, Error> {
+        Self::from_file_with_settings(fullpath, &ParserSettings::default().with_cache(true))
+    }
+
     /// Returns true if this is an ATX RINEX
     pub fn is_antex(&self) -> bool {
         self.header.rinex_type == types::Type::AntennaData
@@ -951,16 +1298,16 @@ impl Rinex {
         s.observation_phase_align_origin_mut();
         s
     }
-    /// Converts all Phase Data to Carrier Cycles by multiplying all phase points
-    /// by the carrier signal wavelength.
-    pub fn observation_phase_carrier_cycles_mut(&mut self) {
+    /// Converts all Phase Data from carrier cycles to meters, by multiplying all phase points
+    /// by the carrier signal wavelength. See [observation::ObservationData::to_meters].
+    pub fn observation_phase_to_meters_mut(&mut self) {
         if let Some(r) = self.record.as_mut_obs() {
             for (_, (_, vehicles)) in r.iter_mut() {
                 for (sv, observations) in vehicles.iter_mut() {
                     for (observable, data) in observations.iter_mut() {
                         if observable.is_phase_observable() {
                             if let Ok(carrier) = observable.carrier(sv.constellation) {
-                                data.obs *= carrier.wavelength();
+                                data.obs = data.to_meters(carrier);
                             }
                         }
                     }
@@ -969,14 +1316,32 @@ impl Rinex {
         }
     }
 
-    /// Converts all Phase Data to Carrier Cycles by multiplying all phase points
-    /// by the carrier signal wavelength.
-    pub fn observation_phase_carrier_cycles(&self) -> Self {
+    /// Converts all Phase Data from carrier cycles to meters,
+    /// immutable implementation. See [Self::observation_phase_to_meters_mut].
+    pub fn observation_phase_to_meters(&self) -> Self {
         let mut s = self.clone();
-        s.observation_phase_carrier_cycles_mut();
+        s.observation_phase_to_meters_mut();
         s
     }
 
+    /// Converts all Phase Data from carrier cycles to meters.
+    #[deprecated(
+        since = "0.15.5",
+        note = "misleading name: use observation_phase_to_meters_mut"
+    )]
+    pub fn observation_phase_carrier_cycles_mut(&mut self) {
+        self.observation_phase_to_meters_mut()
+    }
+
+    /// Converts all Phase Data from carrier cycles to meters.
+    #[deprecated(
+        since = "0.15.5",
+        note = "misleading name: use observation_phase_to_meters"
+    )]
+    pub fn observation_phase_carrier_cycles(&self) -> Self {
+        self.observation_phase_to_meters()
+    }
+
     /*
         /// Single step /stage, in high order phase differencing
         /// algorithm, which we use in case of old receiver data / old RINEX
@@ -1285,6 +1650,8 @@ impl Rinex {
     /// Writes self into given file.   
     /// Both header + record will strictly follow RINEX standards.   
     /// Record: refer to supported RINEX types.
+    /// The header is written as is, `PGM / RUN BY / DATE` included:
+    /// use [Self::to_file_with_stamp] to stamp the production.
     /// ```
     /// // Read a RINEX and dump it without any modifications
     /// use rinex::prelude::*;
@@ -1295,9 +1662,100 @@ impl Rinex {
     /// Other useful links are:
     ///   * our Production settings customization infrastructure [Self::
     pub fn to_file(&self, path: &str) -> Result<(), Error> {
-        let mut writer = BufferedWriter::new(path)?;
-        write!(writer, "{}", self.header)?;
-        self.record.to_file(&self.header, &mut writer)?;
+        self.to_file_with_settings(path, &WriterSettings::default())
+    }
+    /// Same as [Self::to_file], with custom [WriterSettings]: line endings
+    /// and end of file convention, for downstream tools that require them.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::writer::{EndOfFile, LineEnding, WriterSettings};
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///   .unwrap();
+    /// let settings = WriterSettings::default()
+    ///     .with_line_ending(LineEnding::CrLf)
+    ///     .with_end_of_file(EndOfFile::BlankLine);
+    /// assert!(rnx.to_file_with_settings("test-crlf.rnx", &settings).is_ok());
+    /// let content = std::fs::read_to_string("test-crlf.rnx")
+    ///     .unwrap();
+    /// assert!(content.ends_with("\r\n\r\n"));
+    /// assert_eq!(content.matches('\n').count(), content.matches("\r\n").count());
+    /// ```
+    pub fn to_file_with_settings(
+        &self,
+        path: &str,
+        settings: &WriterSettings,
+    ) -> Result<(), Error> {
+        self.to_file_with_progress(path, settings, &mut |_, _| {})
+    }
+    /// Same as [Self::to_file_with_settings], the amount of bytes produced and epochs written
+    /// being reported to given [progress::ProgressSink]. This is where CRINEX
+    /// compression happens, for compressed Observation RINEX.
+    /// The last report matches the size of the file, terminators included.
+    pub fn to_file_with_progress(
+        &self,
+        path: &str,
+        settings: &WriterSettings,
+        progress: &mut dyn progress::ProgressSink,
+    ) -> Result<(), Error> {
+        let mut header = self.header.clone();
+        if let Some(stamp) = &settings.stamp {
+            stamp.apply(&mut header);
+        }
+        let mut writer = BufferedWriter::with_settings(path, settings)?;
+        write!(writer, "{}", header)?;
+        self.record.to_file_with_progress(
+            &header,
+            &self.comments,
+            &self.events,
+            &mut writer,
+            progress,
+        )?;
+        writer.finish()?;
+        Ok(())
+    }
+    /// Writes self into given file, with custom `PGM / RUN BY / DATE` [Stamp].
+    /// Use [Stamp::reproducible] to generate byte identical files.
+    /// This is [Self::to_file_with_settings] with [WriterSettings::with_stamp].
+    pub fn to_file_with_stamp(&self, path: &str, stamp: &Stamp) -> Result<(), Error> {
+        self.to_file_with_settings(path, &WriterSettings::default().with_stamp(stamp))
+    }
+    /// Merges `rhs` into `Self` like [Merge::merge], but epochs that
+    /// exist in both files are resolved with given [merge::MergePolicy],
+    /// see [record::Record::merge_with].
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::merge::MergePolicy;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V2/AJAC3550.21O")
+    ///     .unwrap();
+    /// // redundant logger of the same station
+    /// let backup = rnx.clone();
+    /// let merged = rnx.merge_with(&backup, MergePolicy::Average)
+    ///     .unwrap();
+    /// assert_eq!(merged.record, rnx.record);
+    /// // overlapping epochs are not tolerated
+    /// assert!(rnx.merge_with(&backup, MergePolicy::Error).is_err());
+    /// ```
+    pub fn merge_with(&self, rhs: &Self, policy: merge::MergePolicy) -> Result<Self, merge::Error> {
+        let mut lhs = self.clone();
+        lhs.merge_with_mut(rhs, policy)?;
+        Ok(lhs)
+    }
+    /// [Self::merge_with] in place
+    pub fn merge_with_mut(
+        &mut self,
+        rhs: &Self,
+        policy: merge::MergePolicy,
+    ) -> Result<(), merge::Error> {
+        // Self is only modified once both header and record merged
+        let mut header = self.header.clone();
+        header.merge_mut(&rhs.header)?;
+        if !self.is_antex() && self.epoch().count() == 0 {
+            // lhs is empty : overwrite
+            self.record = rhs.record.clone();
+        } else {
+            self.record.merge_with_mut(&rhs.record, policy)?;
+        }
+        self.header = header;
         Ok(())
     }
 }
@@ -1323,6 +1781,52 @@ impl Rinex {
         Some(end - start)
     }
 
+    /// Shifts all epochs by given `offset`, which may be negative:
+    /// the record, the comments and events, and the header TIME OF FIRST / LAST OBS
+    /// (or epochs of first and last IONEX maps). This is typically needed when
+    /// a receiver logged in the wrong time scale (for example UTC rather than GPST).
+    /// The time scale itself is not modified. Comments that precede the first epoch remain in place.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let shifted = rinex.shift_epochs(Duration::from_seconds(-18.0));
+    /// assert_eq!(
+    ///     shifted.first_epoch(),
+    ///     Some(rinex.first_epoch().unwrap() - Duration::from_seconds(18.0)),
+    /// );
+    /// assert_eq!(shifted.epoch().count(), rinex.epoch().count());
+    /// ```
+    pub fn shift_epochs(&self, offset: Duration) -> Self {
+        let mut s = self.clone();
+        s.shift_epochs_mut(offset);
+        s
+    }
+
+    /// [Rinex::shift_epochs] mutable implementation
+    pub fn shift_epochs_mut(&mut self, offset: Duration) {
+        self.record.shift_epochs(offset);
+        self.comments = std::mem::take(&mut self.comments)
+            .into_iter()
+            .map(|(t, comments)| match t == Epoch::default() {
+                true => (t, comments),
+                false => (t + offset, comments),
+            })
+            .collect();
+        self.events = std::mem::take(&mut self.events)
+            .into_iter()
+            .map(|((t, flag), records)| ((t + offset, flag), records))
+            .collect();
+        if let Some(obs) = &mut self.header.obs {
+            obs.time_of_first_obs = obs.time_of_first_obs.map(|t| t + offset);
+            obs.time_of_last_obs = obs.time_of_last_obs.map(|t| t + offset);
+        }
+        if let Some(ionex) = &mut self.header.ionex {
+            ionex.epoch_of_first_map += offset;
+            ionex.epoch_of_last_map += offset;
+        }
+    }
+
     /// Form a [`Timeseries`] iterator spanning [Self::duration]
     /// with [Self::dominant_sample_rate] spacing
     pub fn timeseries(&self) -> Option<TimeSeries> {
@@ -1334,7 +1838,7 @@ impl Rinex {
 
     /// Returns sample rate used by the data receiver.
     pub fn sample_rate(&self) -> Option<Duration> {
-        self.header.sampling_interval
+        self.header.sampling_interval.map(|dt| dt.duration())
     }
 
     /// Returns dominant sample rate
@@ -1498,6 +2002,87 @@ impl Rinex {
         }
     }
 
+    /// Returns the epochs present in both self and `rhs`, as aligned (self, rhs) pairs,
+    /// in chronological order. Epochs match when they are within `tolerance` of one another,
+    /// which accommodates receivers with slightly offset sampling. Each epoch is matched
+    /// at most once, to its nearest counterpart.
+    /// Use [`Duration::ZERO`] to only retain identical timestamps.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let duth = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let noa1 = Rinex::from_file("../test_resources/OBS/V3/NOA10630.22O")
+    ///     .unwrap();
+    /// for (lhs, rhs) in duth.intersect_epochs(&noa1, Duration::from_milliseconds(100.0)) {
+    ///     assert!((lhs - rhs).abs() <= Duration::from_milliseconds(100.0));
+    /// }
+    /// ```
+    pub fn intersect_epochs(
+        &self,
+        rhs: &Self,
+        tolerance: Duration,
+    ) -> Box<dyn Iterator<Item = (Epoch, Epoch)> + '_> {
+        let lhs: Vec<Epoch> = self.epoch().unique().collect();
+        let rhs: Vec<Epoch> = rhs.epoch().unique().collect();
+        Box::new(epoch::intersect(&lhs, &rhs, tolerance).into_iter())
+    }
+
+    /// Returns the Observation data present in both self and `rhs`, aligned in time,
+    /// see [`Self::intersect_epochs`]. Entries are only paired when their [`EpochFlag`]s match.
+    /// Both self and `rhs` should be Observation RINEX, otherwise this is empty.
+    /// This is what differential processing needs.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let duth = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// for ((lhs, _), (rhs, _)) in duth.intersect_observation(&duth, Duration::ZERO) {
+    ///     assert_eq!(lhs, rhs);
+    /// }
+    /// ```
+    pub fn intersect_observation<'a>(
+        &'a self,
+        rhs: &'a Self,
+        tolerance: Duration,
+    ) -> Box<
+        dyn Iterator<
+                Item = (
+                    (
+                        &'a (Epoch, EpochFlag),
+                        &'a (
+                            Option<f64>,
+                            BTreeMap<SV, HashMap<Observable, ObservationData>>,
+                        ),
+                    ),
+                    (
+                        &'a (Epoch, EpochFlag),
+                        &'a (
+                            Option<f64>,
+                            BTreeMap<SV, HashMap<Observable, ObservationData>>,
+                        ),
+                    ),
+                ),
+            > + 'a,
+    > {
+        match (self.record.as_obs(), rhs.record.as_obs()) {
+            (Some(lhs_rec), Some(rhs_rec)) => Box::new(
+                self.intersect_epochs(rhs, tolerance)
+                    .flat_map(move |(lhs_t, rhs_t)| {
+                        lhs_rec
+                            .range((lhs_t, EpochFlag::Ok)..)
+                            .take_while(move |((t, _), _)| *t == lhs_t)
+                            .filter_map(move |lhs_entry| {
+                                let flag = lhs_entry.0 .1;
+                                let rhs_entry = rhs_rec.get_key_value(&(rhs_t, flag))?;
+                                Some((lhs_entry, rhs_entry))
+                            })
+                    })
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            ),
+            _ => Box::new(Vec::new().into_iter()),
+        }
+    }
+
     /// Returns a unique [`SV`] iterator, to navigate
     /// all Satellite Vehicles encountered and identified.
     /// This will panic if invoked on ATX, Meteo or IONEX records.
@@ -2018,6 +2603,61 @@ impl Rinex {
                 .filter_map(|(e, (clk, _))| clk.as_ref().map(|clk| (*e, *clk))),
         )
     }
+    /// Returns true if receiver clock offsets were applied to this record
+    /// (RCV CLOCK OFFS APPL): epochs, pseudo ranges and phases are then
+    /// already corrected, and the reported offsets ([Self::recvr_clock])
+    /// should not be compensated for another time.
+    /// ```
+    /// use rinex::prelude::Rinex;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/VLNS0630.22O")
+    ///     .unwrap();
+    /// assert!(!rnx.recvr_clock_offset_applied());
+    /// // yet, offsets are reported
+    /// assert!(rnx.recvr_clock().count() > 0);
+    /// ```
+    pub fn recvr_clock_offset_applied(&self) -> bool {
+        self.header
+            .obs
+            .as_ref()
+            .map(|obs| obs.clock_offset_applied)
+            .unwrap_or(false)
+    }
+    /// Fits a line to the receiver clock offsets ([Self::recvr_clock]), in the
+    /// least squares sense: returns the first epoch, the fitted offset at that epoch (s)
+    /// and the receiver clock drift, in seconds per second.
+    /// Returns None when less than two offsets are reported.
+    pub fn recvr_clock_fit(&self) -> Option<(Epoch, f64, f64)> {
+        let offsets: Vec<(Epoch, f64)> = self.recvr_clock().map(|((e, _), clk)| (e, clk)).collect();
+        let (t0, _) = offsets.first()?;
+        let rows: Vec<Vec<f64>> = offsets
+            .iter()
+            .map(|(t, _)| vec![1.0, (*t - *t0).to_seconds()])
+            .collect();
+        let y: Vec<f64> = offsets.iter().map(|(_, clk)| *clk).collect();
+        let weights = vec![1.0; y.len()];
+        let h = estimation::Matrix::from_rows(&rows).ok()?;
+        let fit = estimation::weighted_least_squares(&h, &y, &weights).ok()?;
+        Some((*t0, fit.x[0], fit.x[1]))
+    }
+    /// Estimates the receiver clock drift, in seconds per second,
+    /// as the slope of the least squares line fitted to the receiver
+    /// clock offsets (see [Self::recvr_clock_fit]). Multiply by 1.0E9 to express it
+    /// in parts per billion (ppb), i.e. nanoseconds per second.
+    /// Returns None when less than two offsets are reported.
+    /// ```
+    /// use rinex::prelude::Rinex;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/VLNS0630.22O")
+    ///     .unwrap();
+    /// // null offsets: free of any drift
+    /// assert_eq!(rnx.recvr_clock_drift(), Some(0.0));
+    /// // offsets are not reported in this file
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// assert!(rnx.recvr_clock_drift().is_none());
+    /// ```
+    pub fn recvr_clock_drift(&self) -> Option<f64> {
+        self.recvr_clock_fit().map(|(_, _, drift)| drift)
+    }
     /// Returns an iterator over phase data, expressed in (whole) carrier cycles.
     /// If Self is a High Precision RINEX (scaled RINEX), data is correctly scaled.
     /// High precision RINEX allows up to 100 pico carrier cycle precision.
@@ -2226,6 +2866,66 @@ impl Rinex {
             })
         }))
     }
+    /// Returns an Iterator over signal strength indicators (RINEX SSI digit),
+    /// each one alongside the signal strength observation of the same signal, if any.
+    /// Some receivers only populate the indicator, see [SNR::to_dbhz] to approximate
+    /// the signal strength in that case.
+    /// The indicator is identified by the matching signal strength [Observable],
+    /// for example "S1C" for an indicator attached to "L1C", whether "S1C" was observed or not.
+    /// When several observations of the same signal come with an indicator,
+    /// the one attached to the phase observation prevails.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::observable;
+    /// use rinex::observation::{DbHz, SNR};
+    /// use std::str::FromStr;
+    /// let rinex = Rinex::from_file("../test_resources/OBS/V2/AJAC3550.21O")
+    ///     .unwrap();
+    /// let g07 = SV::from_str("G07").unwrap();
+    /// let ((epoch, _), _, _, dbhz, snr) = rinex.ssi_indicator()
+    ///     .find(|(_, sv, observable, _, _)| *sv == g07 && *observable == observable!("S1"))
+    ///     .unwrap();
+    /// assert_eq!(epoch, Epoch::from_str("2021-12-21T00:00:00 GPST").unwrap());
+    /// assert_eq!(snr, SNR::DbHz36_41);
+    /// assert_eq!(dbhz, Some(37.35));
+    /// assert!(snr.is_consistent(DbHz(37.35), 0.0));
+    /// ```
+    pub fn ssi_indicator(
+        &self,
+    ) -> Box<dyn Iterator<Item = ((Epoch, EpochFlag), SV, Observable, Option<f64>, SNR)> + '_> {
+        Box::new(self.observation().flat_map(|(e, (_, vehicles))| {
+            vehicles.iter().flat_map(move |(sv, observations)| {
+                // per signal: (priority, indicator)
+                let mut indicators = BTreeMap::<String, (u8, SNR)>::new();
+                for (observable, data) in observations {
+                    let snr = match data.snr {
+                        Some(snr) => snr,
+                        None => continue,
+                    };
+                    let priority = match observable {
+                        Observable::Phase(_) => 0,
+                        Observable::PseudoRange(_) => 1,
+                        Observable::Doppler(_) => 2,
+                        Observable::SSI(_) => 3,
+                        _ => continue,
+                    };
+                    let signal = observable.to_string()[1..].to_string();
+                    let indicator = indicators.entry(signal).or_insert((priority, snr));
+                    if priority < indicator.0 {
+                        *indicator = (priority, snr);
+                    }
+                }
+                indicators
+                    .into_iter()
+                    .map(move |(signal, (_, snr))| {
+                        let observable = Observable::SSI(format!("S{}", signal));
+                        let dbhz = observations.get(&observable).map(|data| data.obs);
+                        (*e, *sv, observable, dbhz, snr)
+                    })
+                    .collect::<Vec<_>>()
+            })
+        }))
+    }
     /// Returns an Iterator over LLI flags that might be associated to an Observation.
     /// ```
     /// use rinex::*;
@@ -2253,6 +2953,46 @@ impl Rinex {
             })
         }))
     }
+    /// Returns a flat Iterator over all observations, whatever their kind.
+    /// Epoch flags are dropped: use [Self::observation] if you need them.
+    /// Combine it with [observation::ObservationGrouping] to group
+    /// observations per satellite or per observable.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::observable;
+    /// use std::str::FromStr;
+    ///
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    ///
+    /// // Glonass L1C phase observations, with a valid lock
+    /// let r_l1c = rnx.observations()
+    ///     .filter(|(_, sv, observable, data)| {
+    ///         sv.constellation == Constellation::Glonass
+    ///             && **observable == observable!("L1C")
+    ///             && data.lli.map(|lli| lli.is_empty()).unwrap_or(true)
+    ///     })
+    ///     .count();
+    /// assert!(r_l1c > 0);
+    ///
+    /// // max observed signal strength
+    /// let max = rnx.observations()
+    ///     .filter(|(_, _, observable, _)| observable.is_ssi_observable())
+    ///     .map(|(_, _, _, data)| data.obs)
+    ///     .fold(0.0_f64, f64::max);
+    /// assert!(max > 40.0);
+    /// ```
+    pub fn observations(
+        &self,
+    ) -> Box<dyn Iterator<Item = (Epoch, SV, &Observable, &ObservationData)> + '_> {
+        Box::new(self.observation().flat_map(|((e, _), (_, vehicles))| {
+            vehicles.iter().flat_map(|(sv, observations)| {
+                observations
+                    .iter()
+                    .map(|(observable, data)| (*e, *sv, observable, data))
+            })
+        }))
+    }
     /// Returns an Iterator over "complete" Epochs.
     /// "Complete" Epochs are Epochs were both Phase and Pseudo Range
     /// observations are present on two carriers, sane sampling conditions are met
@@ -2330,31 +3070,305 @@ impl Rinex {
             HashMap::new()
         }
     }
-}
-
-#[cfg(feature = "nav")]
-use crate::navigation::{
-    BdModel, EopMessage, Ephemeris, IonMessage, KbModel, NavMsgType, NgModel, StoMessage,
-};
-
-//#[cfg(feature = "nav")]
-//use hifitime::Unit;
-//.sv_speed()
-
-#[cfg(feature = "nav")]
-use map_3d::ecef2geodetic;
-
-/*
- * NAV RINEX specific methods: only available on crate feature.
- * Either specific Iterators, or meaningful data we can extract.
- */
-#[cfg(feature = "nav")]
-#[cfg_attr(docrs, doc(cfg(feature = "nav")))]
-impl Rinex {
-    /// Returns a Unique Iterator over [`NavMsgType`]s that were identified
+    /// Estimates the achievable Hatanaka compression of this Observation RINEX,
+    /// without producing any output. Refer to [`hatanaka::Compressor::estimate`].
     /// ```
     /// use rinex::prelude::*;
-    /// use rinex::navigation::NavMsgType;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V2/AJAC3550.21O")
+    ///     .unwrap();
+    /// let estimate = rnx.compression_estimate()
+    ///     .unwrap();
+    /// assert!(estimate.ratio() < 1.0);
+    /// for ((constellation, observable), stats) in &estimate.observables {
+    ///     println!("{:X}({}): {:.2} bits/sample", constellation, observable, stats.entropy());
+    /// }
+    /// ```
+    pub fn compression_estimate(&self) -> Result<hatanaka::CompressionEstimate, hatanaka::Error> {
+        let record = self
+            .record
+            .as_obs()
+            .ok_or(hatanaka::Error::NotObsRinexData)?;
+        let obs = self
+            .header
+            .obs
+            .as_ref()
+            .ok_or(hatanaka::Error::NotObsRinexData)?;
+        let constellation = self
+            .header
+            .constellation
+            .as_ref()
+            .ok_or(hatanaka::Error::NotObsRinexData)?;
+        let mut content = String::new();
+        for ((epoch, flag), (clock_offset, data)) in record.iter() {
+            content.push_str(&observation::record::fmt_epoch(
+                *epoch,
+                *flag,
+                clock_offset,
+                data,
+                &self.header,
+            ));
+        }
+        hatanaka::Compressor::estimate(
+            self.header.version.major,
+            &obs.codes,
+            constellation,
+            &content,
+        )
+    }
+    /// Returns observations that are implausible for the receiver
+    /// declared in `REC # / TYPE / VERS`, according to our receiver database:
+    /// either the constellation or the frequency band is not supported by this model.
+    /// This usually indicates a mislabeled header.
+    /// Returns an empty list if the receiver is not declared, or not known.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// // declared receiver is consistent with the observations
+    /// assert!(rnx.implausible_observables().is_empty());
+    /// ```
+    pub fn implausible_observables(&self) -> Vec<(Constellation, Observable)> {
+        let capabilities = match self
+            .header
+            .rcvr
+            .as_ref()
+            .and_then(|rcvr| rcvr.capabilities())
+        {
+            Some(capabilities) => capabilities,
+            None => return Vec::new(),
+        };
+        let mut ret: Vec<(Constellation, Observable)> = Vec::new();
+        for (_, (_, vehicles)) in self.observation() {
+            for (sv, observations) in vehicles {
+                for observable in observations.keys() {
+                    if !capabilities.supports(sv.constellation, observable)
+                        && !ret.contains(&(sv.constellation, observable.clone()))
+                    {
+                        ret.push((sv.constellation, observable.clone()));
+                    }
+                }
+            }
+        }
+        ret.sort();
+        ret
+    }
+    /// Returns the satellites that are observed while their PRN was not in use,
+    /// according to our bundled satellite database (see [satellite::SatelliteDatabase]),
+    /// with the first epoch they were observed at.
+    /// This usually indicates a receiver or conversion issue.
+    /// Constellations that the database does not describe are not reported.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// assert!(rnx.inactive_satellites().is_empty());
+    /// ```
+    pub fn inactive_satellites(&self) -> Vec<(SV, Epoch)> {
+        self.inactive_satellites_with(satellite::SatelliteDatabase::bundled())
+    }
+    /// [Rinex::inactive_satellites] against given satellite database,
+    /// for example an updated one loaded at runtime.
+    pub fn inactive_satellites_with(&self, db: &satellite::SatelliteDatabase) -> Vec<(SV, Epoch)> {
+        let mut ret: Vec<(SV, Epoch)> = Vec::new();
+        for ((epoch, _), (_, vehicles)) in self.observation() {
+            for sv in vehicles.keys() {
+                if db.is_active(*sv, *epoch) == Some(false)
+                    && !ret.iter().any(|(inactive, _)| inactive == sv)
+                {
+                    ret.push((*sv, *epoch));
+                }
+            }
+        }
+        ret.sort();
+        ret
+    }
+    /// Estimates the Pseudo Range and Phase measurement noise, per satellite and signal,
+    /// from third order time differences of consecutive observations.
+    /// Refer to [`ObservationNoise`] for more information.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::observable;
+    /// use std::str::FromStr;
+    /// let rnx = Rinex::from_file("../test_resources/CRNX/V3/ACOR00ESP_R_20213550000_01D_30S_MO.crx")
+    ///     .unwrap();
+    /// let noise = rnx.observation_noise();
+    /// let c1c = noise.estimate(Constellation::GPS, &observable!("C1C"))
+    ///     .unwrap();
+    /// assert!(c1c.samples > 100);
+    /// assert!(c1c.sigma < 1.0); // [m]
+    /// ```
+    pub fn observation_noise(&self) -> ObservationNoise {
+        match self.record.as_obs() {
+            Some(record) => ObservationNoise::from_record(record),
+            None => ObservationNoise::default(),
+        }
+    }
+    /// Stacks the signal strength observations and the code multipath
+    /// (see [Rinex::code_multipath]) of each satellite by sidereal day,
+    /// usually [observation::SIDEREAL_DAY_SECONDS]: this Observation RINEX should span
+    /// consecutive days of a static site, typically several merged daily files.
+    /// The site multipath repeats from one day to the next, refer to [SiderealStack].
+    /// The code multipath is made zero mean per day and per arc: phase lock losses
+    /// (cycle slips) start a new arc.
+    /// Returns None if this is not an Observation RINEX, or it is empty.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::observation::{SIDEREAL_DAY_SECONDS, StackedSignal};
+    /// use std::str::FromStr;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let stack = rnx.sidereal_stack(Duration::from_seconds(SIDEREAL_DAY_SECONDS))
+    ///     .unwrap();
+    /// // single day: no repeatability to be measured
+    /// assert_eq!(stack.days(), 1);
+    /// assert!(stack.repeatabilities(Duration::from_seconds(30.0)).is_empty());
+    /// ```
+    pub fn sidereal_stack(&self, period: Duration) -> Option<SiderealStack> {
+        let start = self.first_epoch()?;
+        let mut stack = SiderealStack::new(start, period);
+        // phase lock losses, that start new multipath arcs
+        let mut slips = HashMap::<SV, Vec<Epoch>>::new();
+        for ((t, flag), (_, vehicles)) in self.observation() {
+            if !flag.is_ok() {
+                continue;
+            }
+            for (sv, observations) in vehicles {
+                for (observable, data) in observations {
+                    if observable.is_ssi_observable() {
+                        let signal = StackedSignal::Ssi(observable.clone());
+                        stack.push(*sv, signal, *t, data.obs);
+                    }
+                    let lock_loss = data
+                        .lli
+                        .map(|lli| lli.intersects(observation::LliFlags::LOCK_LOSS))
+                        .unwrap_or(false);
+                    if observable.is_phase_observable() && lock_loss {
+                        let slips = slips.entry(*sv).or_default();
+                        if slips.last() != Some(t) {
+                            slips.push(*t);
+                        }
+                    }
+                }
+            }
+        }
+        for (observable, vehicles) in self.code_multipath() {
+            for (sv, values) in vehicles {
+                for ((t, flag), value) in values {
+                    if flag.is_ok() {
+                        let signal = StackedSignal::Multipath(observable.clone());
+                        stack.push(sv, signal, t, value);
+                    }
+                }
+            }
+        }
+        stack.center_multipath(&slips);
+        Some(stack)
+    }
+    /// Thins out this Observation RINEX: keeps at most `n` satellites per epoch,
+    /// those with the highest `score` (for example their elevation angle
+    /// or their signal strength). Satellites for which `score` returns None
+    /// are dropped first, ties are resolved in [SV] order.
+    /// This produces smaller files, for bandwidth constrained relaying.
+    /// See [Rinex::thin_out_by_snr_mut] and [Rinex::thin_out_by_elevation_mut].
+    /// This has no effect on non observation records.
+    pub fn thin_out_mut<F>(&mut self, n: usize, score: F)
+    where
+        F: Fn(Epoch, SV, &HashMap<Observable, ObservationData>) -> Option<f64>,
+    {
+        if let Some(record) = self.record.as_mut_obs() {
+            for ((t, _), (_, vehicles)) in record.iter_mut() {
+                if vehicles.len() <= n {
+                    continue;
+                }
+                let mut ranked: Vec<(SV, Option<f64>)> = vehicles
+                    .iter()
+                    .map(|(sv, observations)| (*sv, score(*t, *sv, observations)))
+                    .collect();
+                ranked.sort_by(|(_, a), (_, b)| match (a, b) {
+                    (Some(a), Some(b)) => b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal),
+                    (Some(_), None) => std::cmp::Ordering::Less,
+                    (None, Some(_)) => std::cmp::Ordering::Greater,
+                    (None, None) => std::cmp::Ordering::Equal,
+                });
+                let kept: Vec<SV> = ranked.iter().take(n).map(|(sv, _)| *sv).collect();
+                vehicles.retain(|sv, _| kept.contains(sv));
+            }
+        }
+    }
+    /// [Rinex::thin_out_mut] immutable implementation.
+    pub fn thin_out<F>(&self, n: usize, score: F) -> Self
+    where
+        F: Fn(Epoch, SV, &HashMap<Observable, ObservationData>) -> Option<f64>,
+    {
+        let mut s = self.clone();
+        s.thin_out_mut(n, score);
+        s
+    }
+    /// Keeps the `n` satellites with the strongest signals, at every epoch.
+    /// Satellites are ranked by the mean value of their SSI observations [dB-Hz],
+    /// or by the mean of their SNR flags if none were observed.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap()
+    ///     .thin_out_by_snr(4);
+    /// for (_, (_, vehicles)) in rnx.observation() {
+    ///     assert!(vehicles.len() <= 4);
+    /// }
+    /// ```
+    pub fn thin_out_by_snr(&self, n: usize) -> Self {
+        let mut s = self.clone();
+        s.thin_out_by_snr_mut(n);
+        s
+    }
+    /// [Rinex::thin_out_by_snr] mutable implementation
+    pub fn thin_out_by_snr_mut(&mut self, n: usize) {
+        self.thin_out_mut(n, |_, _, observations| {
+            let mean = |values: Vec<f64>| match values.is_empty() {
+                true => None,
+                false => Some(values.iter().sum::<f64>() / values.len() as f64),
+            };
+            let ssi: Vec<f64> = observations
+                .iter()
+                .filter(|(observable, _)| observable.is_ssi_observable())
+                .map(|(_, data)| data.obs)
+                .collect();
+            mean(ssi).or_else(|| {
+                mean(
+                    observations
+                        .values()
+                        .filter_map(|data| Some(f64::from(data.snr?)))
+                        .collect(),
+                )
+            })
+        })
+    }
+}
+
+#[cfg(feature = "nav")]
+use crate::navigation::{
+    BdModel, EopMessage, Ephemeris, EphemerisSelection, GloTimeParameters, IonMessage, KbModel,
+    NavMsgType, NgModel, RetainMsgType, StoMessage, TimeSystem,
+};
+
+//#[cfg(feature = "nav")]
+//use hifitime::Unit;
+//.sv_speed()
+
+#[cfg(feature = "nav")]
+use map_3d::ecef2geodetic;
+
+/*
+ * NAV RINEX specific methods: only available on crate feature.
+ * Either specific Iterators, or meaningful data we can extract.
+ */
+#[cfg(feature = "nav")]
+#[cfg_attr(docrs, doc(cfg(feature = "nav")))]
+impl Rinex {
+    /// Returns a Unique Iterator over [`NavMsgType`]s that were identified
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::navigation::NavMsgType;
     /// let rinex = Rinex::from_file("../test_resources/NAV/V2/amel0010.21g")
     ///     .unwrap();
     /// assert!(
@@ -2424,8 +3438,76 @@ impl Rinex {
             })
         }))
     }
+    /// Retains frames published with given [`NavMsgType`] only,
+    /// refer to [`RetainMsgType`] to operate on the record directly.
+    /// Galileo Ephemeris described as legacy frames (RINEX3) are resolved
+    /// to either I/NAV or F/NAV from their data source bits, so both can be
+    /// explicitly selected, whatever the file revision.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::navigation::NavMsgType;
+    /// let mut rinex = Rinex::from_file("../test_resources/NAV/V3/CBW100NLD_R_20210010000_01D_MN.rnx")
+    ///     .unwrap();
+    /// rinex.nav_msg_type_retain_mut(NavMsgType::FNAV);
+    /// assert!(rinex.ephemeris().count() > 0);
+    /// for (_, (_, sv, _)) in rinex.ephemeris() {
+    ///     assert_eq!(sv.constellation, Constellation::Galileo);
+    /// }
+    /// ```
+    pub fn nav_msg_type_retain_mut(&mut self, msg: NavMsgType) {
+        if let Some(record) = self.record.as_mut_nav() {
+            record.retain_msg_type(msg);
+        }
+    }
+    /// Immutable implementation of [Self::nav_msg_type_retain_mut].
+    pub fn nav_msg_type_retain(&self, msg: NavMsgType) -> Self {
+        let mut s = self.clone();
+        s.nav_msg_type_retain_mut(msg);
+        s
+    }
+    /// Removes the repeated ephemeris uploads, refer to [EphemerisSelection::dedup]
+    /// to operate on the record directly. Merged broadcast Navigation files
+    /// shrink considerably, which also speeds up the ephemeris selection.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let mut rinex = Rinex::from_file("../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx")
+    ///     .unwrap();
+    /// let count = rinex.ephemeris().count();
+    /// // each ephemeris uploaded twice
+    /// for frames in rinex.record.as_mut_nav().unwrap().values_mut() {
+    ///     let uploads = frames.clone();
+    ///     frames.extend(uploads);
+    /// }
+    /// assert_eq!(rinex.nav_dedup().ephemeris().count(), count);
+    /// ```
+    pub fn nav_dedup_mut(&mut self) {
+        if let Some(record) = self.record.as_mut_nav() {
+            record.dedup();
+        }
+    }
+    /// Immutable implementation of [Self::nav_dedup_mut].
+    pub fn nav_dedup(&self) -> Self {
+        let mut s = self.clone();
+        s.nav_dedup_mut();
+        s
+    }
     /// Ephemeris selection method. Use this method to select Ephemeris
     /// to be used in "sv" navigation at "t" instant. Returns (toe and ephemeris frame).
+    /// SBAS messages do not have a toe: their reference epoch (toc) is returned instead,
+    /// from which the state vector may be propagated:
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rinex = Rinex::from_file("../test_resources/NAV/V3/MOJN00DNK_R_20201770000_01D_MN.rnx.gz")
+    ///     .unwrap();
+    /// let sv = SV::from_str("S23").unwrap();
+    /// let t = Epoch::from_str("2020-06-25T00:01:00 GPST").unwrap();
+    /// let (toc, eph) = rinex.sv_ephemeris(sv, t)
+    ///     .unwrap();
+    /// let sbas = eph.sbas()
+    ///     .unwrap();
+    /// let (x_km, y_km, z_km) = sbas.position_at((t - toc).to_seconds());
+    /// ```
     pub fn sv_ephemeris(&self, sv: SV, t: Epoch) -> Option<(Epoch, &Ephemeris)> {
         /*
          * minimize self.ephemeris with closest toe to t
@@ -2436,24 +3518,12 @@ impl Rinex {
          *      health, iode should also be taken into account
          */
         self.ephemeris()
-            .filter_map(|(_toc, (msg, svnn, eph))| {
+            .filter_map(|(toc, (msg, svnn, eph))| {
                 if svnn == sv {
-                    let ts = svnn.timescale()?;
-                    let toe: Option<Epoch> = match msg {
-                        NavMsgType::CNAV => {
-                            /* in CNAV : specs says toc is toe actually */
-                            // TODO Some(toc.in_time_scale(ts))
-                            None
-                        },
-                        _ => {
-                            /* determine toe */
-                            eph.toe(ts)
-                        },
-                    };
                     //TODO : this fails at this point
-                    //       on both GLONASS and SBAS
-                    //       therfore, kills rtk with these two constellations
-                    let toe = toe?;
+                    //       on GLONASS
+                    //       therfore, kills rtk with this constellation
+                    let toe = navigation::index::ephemeris_toe(msg, svnn, *toc, eph)?;
                     let dt = t - toe;
                     let max_dtoe = Ephemeris::max_dtoe(svnn.constellation)?;
                     // <o
@@ -2796,7 +3866,7 @@ impl Rinex {
             Some((t, (model_sv, model))) => (*t, (model_sv, *model)),
             None => {
                 // RINEX3 possible case: depicted in the header
-                let ionod_corr = self.header.ionod_correction?;
+                let ionod_corr = self.header.ionod_correction()?;
                 /*
                  * only valid for 24 hours, at publication time
                  */
@@ -2858,6 +3928,78 @@ impl Rinex {
             })
         }))
     }
+    /// Returns the Glonass-M time parameters (string 5) broadcast in
+    /// System Time Offset frames, per epoch: TauC from "GLUT" and
+    /// TauGPS from "GLGP" messages.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/NAV/V4/BRD400DLR_S_20230710000_01D_MN.rnx.gz")
+    ///     .unwrap();
+    /// for (epoch, params) in rnx.glonass_time_parameters() {
+    ///     if let Some(tau_gps) = params.tau_gps {
+    ///         assert!(tau_gps.abs() < 1.0E-6);
+    ///     }
+    ///     // Glonass system time (Moscow time) to UTC(SU)
+    ///     let utc = params.to_utc(epoch);
+    /// }
+    /// ```
+    pub fn glonass_time_parameters(
+        &self,
+    ) -> Box<dyn Iterator<Item = (Epoch, GloTimeParameters)> + '_> {
+        let mut ret: BTreeMap<Epoch, GloTimeParameters> = BTreeMap::new();
+        for (t, (_, _, sto)) in self.system_time_offset() {
+            match sto.system.as_str() {
+                "GLUT" => ret.entry(*t).or_default().tau_c = Some(sto.a.0),
+                "GLGP" => ret.entry(*t).or_default().tau_gps = Some(sto.a.0),
+                _ => {},
+            }
+        }
+        Box::new(ret.into_iter())
+    }
+    /// Evaluates the offset between two [TimeSystem]s at `t` (lhs - rhs),
+    /// from the broadcast parameters: the System Time Offset message the nearest in time (RINEX4),
+    /// otherwise the header Time System corrections (RINEX3), possibly combined through UTC.
+    /// Leap seconds are excluded: this is the offset between the physical realizations of
+    /// the two time systems, on top of their nominal offset (that [Epoch] already manages).
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::navigation::TimeSystem;
+    /// use std::str::FromStr;
+    /// let rnx = Rinex::from_file("../test_resources/NAV/V4/KMS300DNK_R_20221591000_01H_MN.rnx.gz")
+    ///     .unwrap();
+    /// let (gst, gpst) = (
+    ///     TimeSystem::GNSS(Constellation::Galileo),
+    ///     TimeSystem::GNSS(Constellation::GPS),
+    /// );
+    /// let t = Epoch::from_str("2022-06-08T10:00:00 GPST").unwrap();
+    /// let ggto = rnx.time_system_offset(gst, gpst, t).unwrap();
+    /// assert!(ggto.abs() < Duration::from_nanoseconds(10.0));
+    /// // reciprocal
+    /// assert_eq!(rnx.time_system_offset(gpst, gst, t), Some(-ggto));
+    /// ```
+    pub fn time_system_offset(
+        &self,
+        lhs: TimeSystem,
+        rhs: TimeSystem,
+        t: Epoch,
+    ) -> Option<Duration> {
+        if lhs == rhs {
+            return Some(Duration::ZERO);
+        }
+        let nearest = self
+            .system_time_offset()
+            .filter_map(|(t_i, (_, _, sto))| match sto.time_systems()? {
+                systems if systems == (lhs, rhs) => Some((*t_i, sto, false)),
+                systems if systems == (rhs, lhs) => Some((*t_i, sto, true)),
+                _ => None,
+            })
+            .min_by_key(|(t_i, _, _)| (t - *t_i).abs());
+        match nearest {
+            Some((t_i, sto, false)) => Some(sto.offset(t_i, t)),
+            Some((t_i, sto, true)) => Some(-sto.offset(t_i, t)),
+            None => self.header.nav.as_ref()?.system_offset(lhs, rhs, t),
+        }
+    }
     /// Returns [`EopMessage`] frames Iterator
     /// ```
     /// use rinex::prelude::*;
@@ -2883,6 +4025,23 @@ impl Rinex {
             })
         }))
     }
+    /// Gathers all [`EopMessage`]s into an [`eop::Eop`] time series,
+    /// which can then be interpolated at any Epoch within that time frame.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/NAV/V4/KMS300DNK_R_20221591000_01H_MN.rnx.gz")
+    ///     .unwrap();
+    /// let eop = rnx.eop();
+    /// if let Some(t0) = eop.first_epoch() {
+    ///     let data = eop.interpolate(t0)
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn eop(&self) -> eop::Eop {
+        self.earth_orientation()
+            .map(|(e, (_, _, msg))| (e, msg))
+            .collect()
+    }
 }
 
 /*
@@ -3171,6 +4330,9 @@ impl Merge for Rinex {
             // real merge
             self.record.merge_mut(&rhs.record)?;
         }
+        for (observable, count) in &rhs.normalized {
+            *self.normalized.entry(observable.clone()).or_default() += count;
+        }
         Ok(())
     }
 }
@@ -3179,17 +4341,23 @@ impl Split for Rinex {
     /// Splits `Self` at desired epoch
     fn split(&self, epoch: Epoch) -> Result<(Self, Self), split::Error> {
         let (r0, r1) = self.record.split(epoch)?;
+        let mut e0 = self.events.clone();
+        let e1 = e0.split_off(&(epoch, EpochFlag::default()));
         Ok((
             Self {
                 header: self.header.clone(),
                 comments: self.comments.clone(),
+                events: e0,
                 record: r0,
+                normalized: self.normalized.clone(),
                 prod_attr: self.prod_attr.clone(),
             },
             Self {
                 header: self.header.clone(),
                 comments: self.comments.clone(),
+                events: e1,
                 record: r1,
+                normalized: self.normalized.clone(),
                 prod_attr: self.prod_attr.clone(),
             },
         ))
@@ -3227,7 +4395,7 @@ impl Smooth for Rinex {
 }
 
 #[cfg(feature = "processing")]
-use crate::algorithm::{Filter, Preprocessing};
+use crate::algorithm::{DecimationFilter, DecimationType, Filter, Preprocessing};
 
 #[cfg(feature = "processing")]
 impl Preprocessing for Rinex {
@@ -3237,7 +4405,17 @@ impl Preprocessing for Rinex {
         s
     }
     fn filter_mut(&mut self, f: Filter) {
-        self.record.filter_mut(f);
+        match f {
+            // decimation of the whole dataset: header sampling is updated
+            Filter::Decimation(DecimationFilter {
+                target: None,
+                dtype,
+            }) => match dtype {
+                DecimationType::DecimByRatio(r) => self.decimate_by_ratio_mut(r),
+                DecimationType::DecimByInterval(dt) => self.decimate_by_sampling_interval_mut(dt),
+            },
+            f => self.record.filter_mut(f),
+        }
     }
 }
 
@@ -3253,6 +4431,10 @@ impl Decimate for Rinex {
     }
     fn decimate_by_ratio_mut(&mut self, r: u32) {
         self.record.decimate_by_ratio_mut(r);
+        if let Some(interval) = self.header.sampling_interval {
+            self.header.sampling_interval =
+                SamplingInterval::new(interval.duration() * r as i64).ok();
+        }
     }
     fn decimate_by_interval(&self, dt: Duration) -> Self {
         let mut s = self.clone();
@@ -3261,6 +4443,13 @@ impl Decimate for Rinex {
     }
     fn decimate_by_interval_mut(&mut self, dt: Duration) {
         self.record.decimate_by_interval_mut(dt);
+        if let Some(interval) = self.header.sampling_interval.as_mut() {
+            if let Ok(dt) = SamplingInterval::new(dt) {
+                if dt > *interval {
+                    *interval = dt;
+                }
+            }
+        }
     }
     fn decimate_match_mut(&mut self, rhs: &Self) {
         self.record.decimate_match_mut(&rhs.record);
@@ -3275,6 +4464,12 @@ impl Decimate for Rinex {
 #[cfg(feature = "obs")]
 use observation::Dcb;
 
+#[cfg(feature = "obs")]
+use observation::ObservationNoise;
+
+#[cfg(feature = "obs")]
+use observation::{SiderealStack, StackedSignal};
+
 #[cfg(feature = "obs")]
 impl Dcb for Rinex {
     fn dcb(&self) -> HashMap<String, BTreeMap<SV, BTreeMap<(Epoch, EpochFlag), f64>>> {
@@ -3372,6 +4567,68 @@ impl Dcb for Rinex {
 #[cfg(feature = "obs")]
 use observation::{Combination, Combine};
 
+#[cfg(feature = "obs")]
+use observation::{Availability, AvailabilityMatrix};
+
+/// Returns the observation [`AvailabilityMatrix`]: which signals
+/// were actually observed, per satellite and epoch. Empty when
+/// self is not an Observation RINEX.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::observable;
+/// use rinex::observation::Availability;
+/// use std::str::FromStr;
+/// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+///     .unwrap();
+/// let matrix = rnx.availability();
+/// let g01 = SV::from_str("G01").unwrap();
+/// let epochs = matrix.common_epochs(&[(g01, &observable!("C1C")), (g01, &observable!("C2W"))]);
+/// assert!(!epochs.is_empty());
+/// ```
+#[cfg(feature = "obs")]
+#[cfg_attr(docrs, doc(cfg(feature = "obs")))]
+impl Availability for Rinex {
+    fn availability(&self) -> AvailabilityMatrix {
+        match self.record.as_obs() {
+            Some(r) => r.availability(),
+            None => AvailabilityMatrix::default(),
+        }
+    }
+}
+
+#[cfg(all(feature = "obs", feature = "ndarray"))]
+use observation::{ObservationMatrix, ToNdArray};
+
+/// Returns the (epochs × satellites) [`ObservationMatrix`] of given observable,
+/// with NaN for missing data. Empty when self is not an Observation RINEX.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::observable;
+/// use rinex::observation::ToNdArray;
+/// use std::str::FromStr;
+/// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+///     .unwrap();
+/// let matrix = rnx.to_ndarray(&observable!("C1C"));
+/// assert_eq!(matrix.data.nrows(), matrix.epochs.len());
+/// assert_eq!(matrix.data.ncols(), matrix.satellites.len());
+/// // epoch-wise mean pseudo range
+/// for row in matrix.data.rows() {
+///     let valid: Vec<f64> = row.iter().copied().filter(|v| !v.is_nan()).collect();
+///     let mean = valid.iter().sum::<f64>() / valid.len() as f64;
+///     assert!(mean > 19.0E6 && mean < 26.0E6);
+/// }
+/// ```
+#[cfg(all(feature = "obs", feature = "ndarray"))]
+#[cfg_attr(docrs, doc(cfg(all(feature = "obs", feature = "ndarray"))))]
+impl ToNdArray for Rinex {
+    fn to_ndarray(&self, observable: &Observable) -> ObservationMatrix {
+        match self.record.as_obs() {
+            Some(r) => r.to_ndarray(observable),
+            None => ObservationMatrix::default(),
+        }
+    }
+}
+
 #[cfg(feature = "obs")]
 #[cfg_attr(docrs, doc(cfg(feature = "obs")))]
 impl Combine for Rinex {
@@ -3501,6 +4758,46 @@ impl Rinex {
             })
             .reduce(|plane, _| plane) // is unique, in a normal IONEX
     }
+    /// Interpolates the vertical TEC (TECu) at given time and coordinates (ddeg),
+    /// from this 2D IONEX: bilinear interpolation within the two maps that surround `t`,
+    /// then linear interpolation in time (maps are not rotated with the Sun).
+    /// Returns None outside the time frame or the grid of this IONEX,
+    /// and for 3D IONEX.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rnx = Rinex::from_file("../test_resources/IONEX/V1/CKMG0020.22I.gz")
+    ///     .unwrap();
+    /// let t = Epoch::from_str("2022-01-02T00:30:00 UTC")
+    ///     .unwrap();
+    /// let tec = rnx.tec_at(t, 30.0, 20.0)
+    ///     .unwrap();
+    /// assert!(tec > 0.0);
+    /// ```
+    pub fn tec_at(&self, t: Epoch, latitude: f64, longitude: f64) -> Option<f64> {
+        if !self.is_ionex_2d() {
+            return None;
+        }
+        let grid = &self.header.ionex.as_ref()?.grid;
+        let (mut before, mut after) = (None, None);
+        for ((e, _), plane) in self.ionex() {
+            if *e <= t {
+                before = Some((*e, plane));
+            }
+            if *e >= t {
+                after = Some((*e, plane));
+                break;
+            }
+        }
+        let ((t0, p0), (t1, p1)) = (before?, after?);
+        let tec0 = ionex::record::interpolate_plane(p0, grid, latitude, longitude)?;
+        if t0 == t1 {
+            return Some(tec0);
+        }
+        let tec1 = ionex::record::interpolate_plane(p1, grid, latitude, longitude)?;
+        let dt = (t - t0).to_seconds() / (t1 - t0).to_seconds();
+        Some(tec0 + (tec1 - tec0) * dt)
+    }
     /// Returns IONEX map borders, expressed as North Eastern
     /// and South Western (latitude; longitude) coordinates,
     /// both expressed in ddeg.
@@ -3555,6 +4852,26 @@ impl Rinex {
             })
             .reduce(|k, _| k) // we're expecting a single match here
     }
+    /// Returns calibration data for given RX Antenna model (ground station model),
+    /// per frequency. "now" is used to determine calibration validity (in time).
+    pub fn rx_antenna_calibration(
+        &self,
+        now: Epoch,
+        matcher: AntennaMatcher,
+    ) -> Option<(&Antenna, &HashMap<Carrier, FrequencyDependentData>)> {
+        let to_match = matcher.to_lowercase();
+        self.antex_valid_calibrations(now)
+            .find(|(ant, _)| match &ant.specific {
+                AntennaSpecific::RxAntenna(rx_ant) => match &to_match {
+                    AntennaMatcher::IGSCode(code) => rx_ant.igs_type.to_lowercase().eq(code),
+                    AntennaMatcher::SerialNumber(sn) => match &rx_ant.serial_number {
+                        Some(serial) => serial.to_lowercase().eq(sn),
+                        None => false,
+                    },
+                },
+                _ => false,
+            })
+    }
     /// Returns APC offset for given RX Antenna model (ground station model).
     /// Model name is the IGS code, which has to match exactly but we're case insensitive.
     /// The APC offset is expressed in NEU coordinates
@@ -3594,6 +4911,428 @@ impl Rinex {
     }
 }
 
+/*
+ * OBS RINEX + ANTEX: antenna corrections
+ */
+#[cfg(all(feature = "obs", feature = "antex"))]
+#[cfg_attr(docrs, doc(cfg(all(feature = "obs", feature = "antex"))))]
+impl Rinex {
+    /// Applies receiver antenna corrections (PCO + PCV) to all phase observations,
+    /// so they refer to the Antenna Reference Point.
+    /// `antex` is the ANTEX calibration to use and `site_antenna` identifies
+    /// the receiver antenna in it.
+    /// `elev_azim` returns the elevation and azimuth angles (in degrees)
+    /// of given vehicle at given Epoch, seen from the site: for example
+    /// resolved from broadcast ephemeris, assuming nominal attitude.
+    /// Observations for which no geometry or no calibration
+    /// (for this carrier) is available, are left untouched.
+    /// This has no effect on non observation records.
+    pub fn apply_antenna_corrections_mut<F>(
+        &mut self,
+        antex: &Rinex,
+        site_antenna: AntennaMatcher,
+        elev_azim: F,
+    ) where
+        F: Fn(Epoch, SV) -> Option<(f64, f64)>,
+    {
+        let t0 = match self.first_epoch() {
+            Some(t0) => t0,
+            None => return,
+        };
+        let (antenna, calibrations) = match antex.rx_antenna_calibration(t0, site_antenna) {
+            Some(calibration) => calibration,
+            None => return,
+        };
+        if let Some(record) = self.record.as_mut_obs() {
+            for ((t, _), (_, vehicles)) in record.iter_mut() {
+                for (sv, observations) in vehicles.iter_mut() {
+                    let (elev, azim) = match elev_azim(*t, *sv) {
+                        Some(angles) => angles,
+                        None => continue,
+                    };
+                    for (observable, data) in observations.iter_mut() {
+                        if !observable.is_phase_observable() {
+                            continue;
+                        }
+                        if let Ok(carrier) = observable.carrier(sv.constellation) {
+                            let correction = calibrations.get(&carrier).and_then(|calib| {
+                                calib.phase_center_correction(&antenna.zenith_grid, elev, azim)
+                            });
+                            if let Some(correction) = correction {
+                                data.obs += correction * 1.0E-3 / carrier.wavelength();
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    /// [`Rinex::apply_antenna_corrections_mut`] immutable implementation.
+    pub fn apply_antenna_corrections<F>(
+        &self,
+        antex: &Rinex,
+        site_antenna: AntennaMatcher,
+        elev_azim: F,
+    ) -> Self
+    where
+        F: Fn(Epoch, SV) -> Option<(f64, f64)>,
+    {
+        let mut s = self.clone();
+        s.apply_antenna_corrections_mut(antex, site_antenna, elev_azim);
+        s
+    }
+}
+
+#[cfg(all(feature = "obs", feature = "nav"))]
+use observation::{PseudoRangeResidual, SnrElevationModel};
+
+#[cfg(all(feature = "obs", feature = "nav"))]
+use crate::{constants::SPEED_OF_LIGHT, propagation::broadcast_transmission};
+
+/*
+ * Single epoch position solver (Gauss-Newton iterations from the center of the Earth):
+ * (x, y, z) and one clock offset per constellation, all in meters.
+ * Measurements are (constellation, pseudo range, satellite position).
+ * At least one redundant measurement is required to verify the solution.
+ */
+#[cfg(all(feature = "obs", feature = "nav"))]
+fn approx_position_solver(
+    measurements: &[(Constellation, f64, (f64, f64, f64))],
+) -> Option<GroundPosition> {
+    /* maximal post fit residuals (rms) */
+    const MAX_RMS: f64 = 100.0;
+    /* earth surface: the tropospheric delay is modeled from then on */
+    const MIN_RADIUS: f64 = 6.0E6;
+    let mut systems: Vec<Constellation> = measurements.iter().map(|(c, _, _)| *c).collect();
+    systems.sort();
+    systems.dedup();
+    let unknowns = 3 + systems.len();
+    if measurements.len() <= unknowns {
+        return None;
+    }
+    let mut x = vec![0.0_f64; unknowns];
+    for _ in 0..10 {
+        let radius = (x[0].powi(2) + x[1].powi(2) + x[2].powi(2)).sqrt();
+        let mut rows = Vec::<Vec<f64>>::with_capacity(measurements.len());
+        let mut y = Vec::<f64>::with_capacity(measurements.len());
+        for (constellation, pr, (sv_x, sv_y, sv_z)) in measurements {
+            let (dx, dy, dz) = (sv_x - x[0], sv_y - x[1], sv_z - x[2]);
+            let rho = (dx.powi(2) + dy.powi(2) + dz.powi(2)).sqrt();
+            let tropo = if radius > MIN_RADIUS {
+                let (elevation, _) =
+                    Ephemeris::elevation_azimuth((*sv_x, *sv_y, *sv_z), (x[0], x[1], x[2]));
+                observation::tropo_delay(elevation.max(5.0))
+            } else {
+                0.0
+            };
+            let system = systems.iter().position(|c| c == constellation)?;
+            let mut row = vec![0.0_f64; unknowns];
+            row[0] = -dx / rho;
+            row[1] = -dy / rho;
+            row[2] = -dz / rho;
+            row[3 + system] = 1.0;
+            rows.push(row);
+            y.push(pr - rho - x[3 + system] - tropo);
+        }
+        let weights = vec![1.0_f64; y.len()];
+        let h = estimation::Matrix::from_rows(&rows).ok()?;
+        let solution = estimation::weighted_least_squares(&h, &y, &weights).ok()?;
+        for (x, dx) in x.iter_mut().zip(&solution.x) {
+            *x += dx;
+        }
+        let correction = solution.x[..3]
+            .iter()
+            .map(|dx| dx.powi(2))
+            .sum::<f64>()
+            .sqrt();
+        if correction < 1.0 {
+            let rms = (solution.residuals.iter().map(|r| r.powi(2)).sum::<f64>()
+                / solution.residuals.len() as f64)
+                .sqrt();
+            if rms < MAX_RMS && radius > MIN_RADIUS {
+                return Some(GroundPosition::from_ecef_wgs84((x[0], x[1], x[2])));
+            }
+            return None;
+        }
+    }
+    None
+}
+
+#[cfg(all(feature = "obs", feature = "nav"))]
+#[cfg_attr(docrs, doc(cfg(all(feature = "obs", feature = "nav"))))]
+impl Rinex {
+    /// Builds a [`SnrElevationModel`] from all SSI observations contained
+    /// in this Observation RINEX, SV elevation angles being
+    /// evaluated from the provided Navigation RINEX.
+    /// A reference ground position must be known: either passed
+    /// or defined in the header, otherwise the model remains empty.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::observable;
+    /// use std::str::FromStr;
+    ///
+    /// let obs = Rinex::from_file("../test_resources/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+    ///     .unwrap();
+    /// let nav = Rinex::from_file("../test_resources/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+    ///     .unwrap();
+    ///
+    /// let model = obs.snr_elevation_model(&nav, None);
+    /// let s1c = observable!("S1C");
+    /// let template = model.template(Constellation::GPS, &s1c)
+    ///     .unwrap();
+    /// // SNR increases with elevation
+    /// assert!(template.eval(60.0) > template.eval(10.0));
+    /// ```
+    pub fn snr_elevation_model(
+        &self,
+        nav: &Rinex,
+        ref_position: Option<GroundPosition>,
+    ) -> SnrElevationModel {
+        let mut model = SnrElevationModel::default();
+        let ref_position = match ref_position.or(self.header.ground_position) {
+            Some(pos) => pos,
+            None => return model,
+        };
+        for ((t, flag), (_, vehicles)) in self.observation() {
+            if !flag.is_ok() {
+                continue;
+            }
+            for (sv, observations) in vehicles {
+                let mut ssi = observations
+                    .iter()
+                    .filter(|(observable, _)| observable.is_ssi_observable())
+                    .peekable();
+                if ssi.peek().is_none() {
+                    continue;
+                }
+                let elevation = nav
+                    .sv_ephemeris(*sv, *t)
+                    .and_then(|(_, eph)| eph.sv_elev_azim(*sv, *t, ref_position))
+                    .map(|(elev, _)| elev);
+                if let Some(elevation) = elevation {
+                    for (observable, data) in ssi {
+                        model.add_sample(sv.constellation, observable.clone(), elevation, data.obs);
+                    }
+                }
+            }
+        }
+        model.fit();
+        model
+    }
+    /// Keeps the `n` highest satellites at every epoch, which preserves
+    /// the geometry of the constellation while reducing the file size.
+    /// Elevation angles are evaluated from the provided Navigation RINEX.
+    /// Satellites for which no ephemeris is available are dropped first.
+    /// A reference ground position must be known: either passed or defined
+    /// in the header, otherwise satellites are ranked by signal strength,
+    /// see [Rinex::thin_out_by_snr].
+    /// ```
+    /// use rinex::prelude::*;
+    /// let obs = Rinex::from_file("../test_resources/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+    ///     .unwrap();
+    /// let nav = Rinex::from_file("../test_resources/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+    ///     .unwrap();
+    /// let thinned = obs.thin_out_by_elevation(8, &nav, None);
+    /// for (_, (_, vehicles)) in thinned.observation() {
+    ///     assert!(vehicles.len() <= 8);
+    /// }
+    /// ```
+    pub fn thin_out_by_elevation(
+        &self,
+        n: usize,
+        nav: &Rinex,
+        ref_position: Option<GroundPosition>,
+    ) -> Self {
+        let mut s = self.clone();
+        s.thin_out_by_elevation_mut(n, nav, ref_position);
+        s
+    }
+    /// [Rinex::thin_out_by_elevation] mutable implementation
+    pub fn thin_out_by_elevation_mut(
+        &mut self,
+        n: usize,
+        nav: &Rinex,
+        ref_position: Option<GroundPosition>,
+    ) {
+        let ref_position = match ref_position.or(self.header.ground_position) {
+            Some(pos) => pos,
+            None => {
+                self.thin_out_by_snr_mut(n);
+                return;
+            },
+        };
+        self.thin_out_mut(n, |t, sv, _| {
+            nav.sv_ephemeris(sv, t)
+                .and_then(|(_, eph)| eph.sv_elev_azim(sv, t, ref_position))
+                .map(|(elev, _)| elev)
+        })
+    }
+    /// Computes the pseudo range residuals of this Observation RINEX against
+    /// a surveyed `position` (for example a reference station), satellite positions
+    /// and clock offsets being resolved from the provided Navigation RINEX.
+    /// Measurements below the elevation `mask` [°] are discarded.
+    /// Signal propagation time, earth rotation and satellite clock offsets are
+    /// compensated for, the tropospheric delay is modeled with a simple nominal model,
+    /// while the ionospheric delay is not modeled. The receiver clock offset is estimated
+    /// at each epoch, per constellation and signal, see [PseudoRangeResidual].
+    /// Signals tracked by less than 3 satellites at a given epoch are not reported.
+    /// Residuals are sorted chronologically.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let mut obs = Rinex::from_file("../test_resources/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+    ///     .unwrap();
+    /// let nav = Rinex::from_file("../test_resources/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+    ///     .unwrap();
+    /// let position = obs.header.ground_position
+    ///     .unwrap();
+    /// // first hour
+    /// let end = Epoch::from_str("2020-06-25T01:00:00 GPST").unwrap();
+    /// obs.record.as_mut_obs()
+    ///     .unwrap()
+    ///     .retain(|(t, _), _| *t < end);
+    /// let residuals = obs.pseudo_range_residuals(&nav, position, 10.0);
+    /// for residual in residuals {
+    ///     assert!(residual.elevation >= 10.0);
+    ///     // residual.residual: [m]
+    /// }
+    /// ```
+    pub fn pseudo_range_residuals(
+        &self,
+        nav: &Rinex,
+        position: GroundPosition,
+        mask: f64,
+    ) -> Vec<PseudoRangeResidual> {
+        let mut ret = Vec::<PseudoRangeResidual>::new();
+        let (rx_x, rx_y, rx_z) = position.to_ecef_wgs84();
+        for ((t, flag), (_, vehicles)) in self.observation() {
+            if !flag.is_ok() {
+                continue;
+            }
+            /* raw residuals, per constellation and signal */
+            let mut raw = HashMap::<(Constellation, &Observable), Vec<PseudoRangeResidual>>::new();
+            for (sv, observations) in vehicles {
+                if sv.constellation == Constellation::Glonass {
+                    /* broadcast clock model not supported yet */
+                    continue;
+                }
+                let (toe, eph) = match nav.sv_ephemeris(*sv, *t) {
+                    Some(ephemeris) => ephemeris,
+                    None => continue,
+                };
+                for (observable, data) in observations {
+                    if !observable.is_pseudorange_observable() {
+                        continue;
+                    }
+                    let pr = data.obs;
+                    let (dt_sv, (x, y, z)) = match broadcast_transmission(*sv, toe, eph, *t, pr) {
+                        Some(state) => state,
+                        None => continue,
+                    };
+                    let (elevation, azimuth) =
+                        Ephemeris::elevation_azimuth((x, y, z), (rx_x, rx_y, rx_z));
+                    if elevation < mask {
+                        continue;
+                    }
+                    let rho = ((x - rx_x).powi(2) + (y - rx_y).powi(2) + (z - rx_z).powi(2)).sqrt();
+                    let residual =
+                        pr - rho + dt_sv * SPEED_OF_LIGHT - observation::tropo_delay(elevation);
+                    if residual.is_finite() {
+                        raw.entry((sv.constellation, observable)).or_default().push(
+                            PseudoRangeResidual {
+                                epoch: *t,
+                                sv: *sv,
+                                observable: observable.clone(),
+                                elevation,
+                                azimuth,
+                                residual,
+                            },
+                        );
+                    }
+                }
+            }
+            /* remove receiver clock offset */
+            let mut residuals = Vec::<PseudoRangeResidual>::new();
+            for (_, mut signal) in raw {
+                if signal.len() < 3 {
+                    continue;
+                }
+                let mut values: Vec<f64> = signal.iter().map(|r| r.residual).collect();
+                let clock = observation::median(&mut values);
+                for residual in signal.iter_mut() {
+                    residual.residual -= clock;
+                }
+                residuals.extend(signal);
+            }
+            residuals.sort_by(|a, b| (a.sv, &a.observable).cmp(&(b.sv, &b.observable)));
+            ret.extend(residuals);
+        }
+        ret
+    }
+    /// Estimates a coarse receiver position from the pseudo ranges of a single epoch,
+    /// satellite positions and clock offsets being resolved from the provided Navigation RINEX.
+    /// This is meant to bootstrap the processing of files that do not define
+    /// an APPROX POSITION XYZ (typically converted from raw receiver data),
+    /// for example to evaluate elevation angles: expect an accuracy of a few tens of meters.
+    /// One receiver clock offset is estimated per constellation, GLONASS is not used.
+    /// Epochs are tried in chronological order, the first one that provides
+    /// a consistent solution (post fit residuals below 100 m) is retained.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let mut obs = Rinex::from_file("../test_resources/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+    ///     .unwrap();
+    /// let nav = Rinex::from_file("../test_resources/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+    ///     .unwrap();
+    /// let surveyed = obs.header.ground_position
+    ///     .take()
+    ///     .unwrap();
+    /// let estimated = obs.approx_position(&nav)
+    ///     .unwrap();
+    /// let (e, n, u) = estimated.to_enu(&surveyed);
+    /// assert!((e.powi(2) + n.powi(2) + u.powi(2)).sqrt() < 100.0);
+    /// ```
+    pub fn approx_position(&self, nav: &Rinex) -> Option<GroundPosition> {
+        for ((t, flag), (_, vehicles)) in self.observation() {
+            if !flag.is_ok() {
+                continue;
+            }
+            /* (constellation, pseudo range corrected for satellite clock, satellite position) */
+            let mut measurements = Vec::<(Constellation, f64, (f64, f64, f64))>::new();
+            for (sv, observations) in vehicles {
+                if sv.constellation == Constellation::Glonass {
+                    /* broadcast clock model not supported yet */
+                    continue;
+                }
+                let (toe, eph) = match nav.sv_ephemeris(*sv, *t) {
+                    Some((toe, eph)) if eph.is_healthy() => (toe, eph),
+                    _ => continue,
+                };
+                /* one pseudo range per satellite, in canonical order */
+                let pr = observations
+                    .iter()
+                    .filter(|(observable, data)| {
+                        observable.is_pseudorange_observable() && data.obs > 0.0
+                    })
+                    .min_by(|(lhs, _), (rhs, _)| lhs.canonical_cmp(rhs))
+                    .map(|(_, data)| data.obs);
+                let pr = match pr {
+                    Some(pr) => pr,
+                    None => continue,
+                };
+                let (dt_sv, position) = match broadcast_transmission(*sv, toe, eph, *t, pr) {
+                    Some(state) => state,
+                    None => continue,
+                };
+                measurements.push((sv.constellation, pr + dt_sv * SPEED_OF_LIGHT, position));
+            }
+            if let Some(position) = approx_position_solver(&measurements) {
+                return Some(position);
+            }
+        }
+        None
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
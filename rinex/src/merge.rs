@@ -1,9 +1,11 @@
 //! RINEX File merging (combination)
 use std::cmp::{Eq, PartialEq};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::hash::Hash;
 use thiserror::Error;
 
+use hifitime::Epoch;
+
 /// Merge operation related error(s)
 #[derive(Error, Debug)]
 pub enum Error {
@@ -21,6 +23,63 @@ pub enum Error {
     IonexBaseRadiusMismatch,
     #[error("failed to retrieve system time for merge ops date")]
     HifitimeError(#[from] hifitime::Errors),
+    #[error("conflicting data at epoch {0}")]
+    EpochConflict(Epoch),
+    #[error("{0:?} merge policy is not supported for this record type")]
+    UnsupportedPolicy(MergePolicy),
+}
+
+/// [MergePolicy] describes how overlapping epochs are resolved,
+/// when both records provide data for the same epoch,
+/// see [crate::record::Record::merge_with].
+/// Conflicts are resolved per observation: data only provided by
+/// one of the two records is always preserved.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Self's data is preserved, `rhs` only introduces new data
+    PreferSelf,
+    /// `rhs` data overwrites Self's data
+    #[default]
+    PreferOther,
+    /// Numeric observations are averaged (Observation, Meteo and Clock records).
+    /// Flags are preserved and only completed by `rhs`.
+    /// Other record types do not support this policy.
+    Average,
+    /// Overlapping epochs are considered an error
+    Error,
+}
+
+/*
+ * Returns the first key both records share, so overlapping epochs
+ * are reported prior any modification
+ */
+pub(crate) fn first_conflict<'a, K: Ord, V>(
+    lhs: &BTreeMap<K, V>,
+    rhs: &'a BTreeMap<K, V>,
+) -> Option<&'a K> {
+    rhs.keys().find(|k| lhs.contains_key(k))
+}
+
+/*
+ * Resolves a conflict between two numeric values, following given policy
+ */
+pub(crate) fn resolve_f64(lhs: &mut f64, rhs: f64, policy: MergePolicy) {
+    match policy {
+        MergePolicy::PreferOther => *lhs = rhs,
+        MergePolicy::Average => *lhs = (*lhs + rhs) / 2.0,
+        MergePolicy::PreferSelf | MergePolicy::Error => {},
+    }
+}
+
+/*
+ * Resolves a conflict between two optional numeric values, following given policy
+ */
+pub(crate) fn resolve_option_f64(lhs: &mut Option<f64>, rhs: Option<f64>, policy: MergePolicy) {
+    match (lhs.as_mut(), rhs) {
+        (Some(lhs), Some(rhs)) => resolve_f64(lhs, rhs, policy),
+        (None, Some(rhs)) => *lhs = Some(rhs),
+        _ => {},
+    }
 }
 
 /*
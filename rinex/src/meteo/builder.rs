@@ -0,0 +1,130 @@
+//! Meteo RINEX production from sensor data
+use super::{sensor::Sensor, HeaderFields, Record};
+use crate::{
+    marker::GeodeticMarker, prelude::*, record, types::Type, version::Version, Observable,
+};
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+/// Meteo RINEX production errors
+pub enum Error {
+    #[error("\"{0}\" is not a meteo observable")]
+    NonMeteoObservable(Observable),
+    #[error("{0} already described by another sensor")]
+    DuplicateSensor(Observable),
+    #[error("no observable has been declared")]
+    NoObservables,
+}
+
+/// [`MeteoBuilder`] gathers weather station measurements
+/// and produces a valid Meteo [`Rinex`].
+/// Observables are declared by their [`Sensor`], or on first measurement.
+/// Epochs are expressed in UTC, as the standards require.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::meteo::{builder::MeteoBuilder, sensor::Sensor};
+/// let mut builder = MeteoBuilder::new("STATION")
+///     .with_general_infos("operator", "agency")
+///     .with_sensor(Sensor::new(Observable::Pressure)
+///         .with_model("PTU200")
+///         .with_accuracy(0.1))
+///     .unwrap();
+/// let t0 = Epoch::from_gregorian_utc_at_midnight(2023, 9, 11);
+/// builder.push(t0, Observable::Pressure, 1005.8).unwrap();
+/// builder.push(t0, Observable::Temperature, 19.8).unwrap();
+/// assert!(builder.push(t0, Observable::SSI("S1C".to_string()), 45.0).is_err());
+/// let rinex = builder.build().unwrap();
+/// assert!(rinex.is_meteo_rinex());
+/// let path = std::env::temp_dir().join("met.rnx");
+/// assert!(rinex.to_file(&path.to_string_lossy()).is_ok());
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct MeteoBuilder {
+    header: Header,
+    record: Record,
+}
+
+impl MeteoBuilder {
+    /// Builds a new [`MeteoBuilder`] for given station (`MARKER NAME`),
+    /// producing latest RINEX revision.
+    pub fn new(station: &str) -> Self {
+        let mut header = Header::default().with_type(Type::MeteoData);
+        header.geodetic_marker = Some(GeodeticMarker::default().with_name(station));
+        header.meteo = Some(HeaderFields::default());
+        Self {
+            header,
+            record: Record::new(),
+        }
+    }
+    /// Returns [`MeteoBuilder`] producing desired RINEX revision
+    pub fn with_version(&self, version: Version) -> Self {
+        let mut s = self.clone();
+        s.header.version = version;
+        s
+    }
+    /// Returns [`MeteoBuilder`] with operator and agency information
+    pub fn with_general_infos(&self, run_by: &str, agency: &str) -> Self {
+        let mut s = self.clone();
+        s.header.run_by = run_by.to_string();
+        s.header.agency = agency.to_string();
+        s
+    }
+    /// Returns [`MeteoBuilder`] with additional header comments
+    pub fn with_comments(&self, comments: Vec<String>) -> Self {
+        let mut s = self.clone();
+        s.header.comments = comments;
+        s
+    }
+    /// Declares a new [`Sensor`] and the observable it measures
+    pub fn with_sensor(&self, sensor: Sensor) -> Result<Self, Error> {
+        let mut s = self.clone();
+        s.declare(&sensor.observable)?;
+        let meteo = s.header.meteo.as_mut().unwrap();
+        if meteo
+            .sensors
+            .iter()
+            .any(|s| s.observable == sensor.observable)
+        {
+            return Err(Error::DuplicateSensor(sensor.observable));
+        }
+        meteo.sensors.push(sensor);
+        Ok(s)
+    }
+    /*
+     * Adds observable to the header definitions, if need be
+     */
+    fn declare(&mut self, observable: &Observable) -> Result<(), Error> {
+        if !observable.is_meteo_observable() {
+            return Err(Error::NonMeteoObservable(observable.clone()));
+        }
+        let codes = &mut self.header.meteo.as_mut().unwrap().codes;
+        if !codes.contains(observable) {
+            codes.push(observable.clone());
+        }
+        Ok(())
+    }
+    /// Stores a new measurement. A previous measurement of this observable,
+    /// at the same epoch, is replaced.
+    pub fn push(&mut self, epoch: Epoch, observable: Observable, value: f64) -> Result<(), Error> {
+        self.declare(&observable)?;
+        self.record
+            .entry(epoch.in_time_scale(TimeScale::UTC))
+            .or_insert_with(|| HashMap::with_capacity(4))
+            .insert(observable, value);
+        Ok(())
+    }
+    /// Builds the Meteo [`Rinex`], stamped with the current date.
+    pub fn build(&self) -> Result<Rinex, Error> {
+        if self.header.meteo.as_ref().unwrap().codes.is_empty() {
+            return Err(Error::NoObservables);
+        }
+        let mut header = self.header.clone();
+        Stamp::default().apply(&mut header);
+        Ok(Rinex::new(
+            header,
+            record::Record::MeteoRecord(self.record.clone()),
+        ))
+    }
+}
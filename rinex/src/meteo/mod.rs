@@ -1,6 +1,8 @@
 //! Meteo RINEX module
+pub mod builder;
 pub mod record;
 pub mod sensor;
+pub use builder::MeteoBuilder;
 pub use record::Record;
 
 use crate::Observable;
@@ -1,5 +1,6 @@
 use crate::{
-    epoch, merge, merge::Merge, prelude::*, split, split::Split, types::Type, version, Observable,
+    epoch, fieldslice, formatter::FloatFormat, merge, merge::Merge, prelude::*, record, split,
+    split::Split, types::Type, version, Observable,
 };
 
 use hifitime::Duration;
@@ -12,6 +13,23 @@ use thiserror::Error;
  */
 pub type Record = BTreeMap<Epoch, HashMap<Observable, f64>>;
 
+/*
+ * Estimated memory footprint of a Meteo record, in bytes
+ */
+pub(crate) fn memory_usage(rec: &Record) -> usize {
+    record::btreemap_usage(rec)
+        + rec
+            .values()
+            .map(|observations| {
+                record::hashmap_usage(observations)
+                    + observations
+                        .keys()
+                        .map(|observable| observable.heap_usage())
+                        .sum::<usize>()
+            })
+            .sum::<usize>()
+}
+
 /*
  * Returns true if given line matches a new Meteo Record Epoch.
  * We use this when browsing a RINEX file, to determine whether
@@ -24,16 +42,20 @@ pub(crate) fn is_new_epoch(line: &str, v: version::Version) -> bool {
             // minimum epoch descriptor
             return false;
         }
-        let datestr = &line[1..min_len.len()];
-        epoch::parse_utc(datestr).is_ok() // valid epoch descriptor
+        match fieldslice::field(line, 1, min_len.len()) {
+            Ok(datestr) => epoch::parse_utc(datestr).is_ok(), // valid epoch descriptor
+            Err(_) => false,
+        }
     } else {
         let min_len = " 2021  1  7  0  0  0";
         if line.len() < min_len.len() {
             // minimum epoch descriptor
             return false;
         }
-        let datestr = &line[1..min_len.len()];
-        epoch::parse_utc(datestr).is_ok() // valid epoch descriptor
+        match fieldslice::field(line, 1, min_len.len()) {
+            Ok(datestr) => epoch::parse_utc(datestr).is_ok(), // valid epoch descriptor
+            Err(_) => false,
+        }
     }
 }
 
@@ -56,7 +78,7 @@ pub(crate) fn parse_epoch(
     content: &str,
 ) -> Result<(Epoch, HashMap<Observable, f64>), Error> {
     let mut lines = content.lines();
-    let mut line = lines.next().unwrap();
+    let mut line = lines.next().unwrap_or_default();
 
     let mut map: HashMap<Observable, f64> = HashMap::with_capacity(3);
 
@@ -65,43 +87,42 @@ pub(crate) fn parse_epoch(
         offset += 2; // YYYY
     }
 
-    let (epoch, _) = epoch::parse_utc(&line[0..offset])?;
+    let (epoch, _) = epoch::parse_utc(line.get(0..offset).unwrap_or(line))?;
 
+    /*
+     * first line: up to 8 observations,
+     * continuation lines: 4X then up to 10 observations
+     */
     let codes = &header.meteo.as_ref().unwrap().codes;
-    let nb_codes = codes.len();
-    let nb_lines: usize = num_integer::div_ceil(nb_codes, 8);
+    let mut per_line: usize = 8;
     let mut code_index: usize = 0;
 
-    for i in 0..nb_lines {
-        for _ in 0..8 {
-            let code = &codes[code_index];
-            let obs: Option<f64> = match f64::from_str(line[offset..offset + 7].trim()) {
-                Ok(f) => Some(f),
-                Err(_) => None,
+    while code_index < codes.len() {
+        for _ in 0..per_line {
+            if code_index >= codes.len() {
+                break;
+            }
+            let end = std::cmp::min(offset + 7, line.len());
+            let obs: Option<f64> = match line.get(offset..end) {
+                Some(content) => f64::from_str(content.trim()).ok(),
+                None => None,
             };
-
             if let Some(obs) = obs {
-                map.insert(code.clone(), obs);
+                map.insert(codes[code_index].clone(), obs);
             }
             code_index += 1;
-            if code_index >= nb_codes {
-                break;
-            }
-
             offset += 7;
-            if offset >= line.len() {
-                break;
-            }
-        } // 1:8
-
-        if i < nb_lines - 1 {
+        }
+        if code_index < codes.len() {
             if let Some(l) = lines.next() {
                 line = l;
+                offset = 4;
+                per_line = 10;
             } else {
                 break;
             }
         }
-    } // nb lines
+    }
     Ok((epoch, map))
 }
 
@@ -120,17 +141,16 @@ pub(crate) fn fmt_epoch(
         epoch::format(*epoch, None, Type::MeteoData, header.version.major)
     ));
     let observables = &header.meteo.as_ref().unwrap().codes;
-    let mut index = 0;
-    for obscode in observables {
-        index += 1;
+    for (index, obscode) in observables.iter().enumerate() {
+        // 8 observations on first line, then 4X,10F7.1 continuations
+        if index == 8 || (index > 8 && (index - 8) % 10 == 0) {
+            lines.push_str("\n    ");
+        }
         if let Some(data) = data.get(obscode) {
-            lines.push_str(&format!("{:7.1}", data));
+            lines.push_str(&FloatFormat::METEO.format(*data));
         } else {
             lines.push_str("       ");
         }
-        if (index % 8) == 0 {
-            lines.push('\n');
-        }
     }
     lines.push('\n');
     Ok(lines)
@@ -198,6 +218,51 @@ impl Merge for Record {
     }
 }
 
+/*
+ * Applies `f` to all values of given observable
+ */
+pub(crate) fn apply<F>(rec: &mut Record, observable: &Observable, mut f: F)
+where
+    F: FnMut(f64) -> f64,
+{
+    for observations in rec.values_mut() {
+        if let Some(value) = observations.get_mut(observable) {
+            *value = f(*value);
+        }
+    }
+}
+
+/*
+ * Merges `rhs` into `lhs`, resolving overlapping epochs with given policy
+ */
+pub(crate) fn merge_with_mut(
+    lhs: &mut Record,
+    rhs: &Record,
+    policy: merge::MergePolicy,
+) -> Result<(), merge::Error> {
+    if policy == merge::MergePolicy::Error {
+        if let Some(epoch) = merge::first_conflict(lhs, rhs) {
+            return Err(merge::Error::EpochConflict(*epoch));
+        }
+    }
+    for (epoch, observations) in rhs.iter() {
+        if let Some(oobservations) = lhs.get_mut(epoch) {
+            for (observation, data) in observations.iter() {
+                if let Some(ddata) = oobservations.get_mut(observation) {
+                    merge::resolve_f64(ddata, *data, policy);
+                } else {
+                    // new observation
+                    oobservations.insert(observation.clone(), *data);
+                }
+            }
+        } else {
+            // new epoch
+            lhs.insert(*epoch, observations.clone());
+        }
+    }
+    Ok(())
+}
+
 impl Split for Record {
     fn split(&self, epoch: Epoch) -> Result<(Self, Self), split::Error> {
         let r0 = self
@@ -318,10 +383,14 @@ impl Decimate for Record {
     fn decimate_by_interval_mut(&mut self, interval: Duration) {
         let mut last_retained: Option<Epoch> = None;
         self.retain(|e, _| {
-            if last_retained.is_some() {
-                let dt = *e - last_retained.unwrap();
-                last_retained = Some(*e);
-                dt > interval
+            if let Some(last) = last_retained {
+                // compared to last retained epoch, so high rate
+                // data can be decimated to any larger interval
+                let retained = *e - last >= interval;
+                if retained {
+                    last_retained = Some(*e);
+                }
+                retained
             } else {
                 last_retained = Some(*e);
                 true // always retain 1st epoch
@@ -376,7 +445,7 @@ impl Preprocessing for Record {
                 },
                 DecimationType::DecimByInterval(dt) => {
                     if filter.target.is_none() {
-                        self.decimate_by_interval_mut(dt);
+                        self.decimate_by_sampling_interval_mut(dt);
                         return; // no need to proceed further
                     }
 
@@ -389,7 +458,7 @@ impl Preprocessing for Record {
                     };
 
                     // and decimate
-                    let subset = self.mask(mask).decimate_by_interval(dt);
+                    let subset = self.mask(mask).decimate_by_sampling_interval(dt);
 
                     // adapt self's subset to new data rates
                     decimate_data_subset(self, &subset, &item);
@@ -408,7 +477,61 @@ impl Interpolate for Record {
         s.interpolate_mut(series);
         s
     }
-    fn interpolate_mut(&mut self, _series: TimeSeries) {
-        unimplemented!("meteo:record:interpolate_mut()")
+    fn interpolate_mut(&mut self, series: TimeSeries) {
+        *self = series
+            .filter_map(|t| Some((t, interpolate_at(self, t, &InterpMethod::Linear, None)?)))
+            .collect();
     }
 }
+
+/*
+ * Interpolates observations at given epoch, from surrounding samples
+ * that are no further than `max_span` apart
+ */
+#[cfg(feature = "processing")]
+pub(crate) fn interpolate_at(
+    rec: &Record,
+    t: Epoch,
+    method: &InterpMethod,
+    max_span: Option<Duration>,
+) -> Option<HashMap<Observable, f64>> {
+    if let Some(observations) = rec.get(&t) {
+        return Some(observations.clone());
+    }
+    let (_, before) = rec.range(..t).next_back()?;
+    let (_, after) = rec.range(t..).next()?;
+    let mut ret = HashMap::<Observable, f64>::new();
+    for observable in before.keys().chain(after.keys()) {
+        if ret.contains_key(observable) {
+            continue;
+        }
+        let ((t0, y0), (t1, y1)) =
+            match crate::algorithm::neighbours(rec, t, |obs| obs.get(observable).copied()) {
+                Some(neighbours) => neighbours,
+                None => continue,
+            };
+        if max_span.map(|span| t1 - t0 <= span).unwrap_or(true) {
+            ret.insert(
+                observable.clone(),
+                method.interpolate((t0, y0), (t1, y1), t),
+            );
+        }
+    }
+    if ret.is_empty() {
+        None
+    } else {
+        Some(ret)
+    }
+}
+
+/*
+ * Fills data gaps that do not exceed `max_gap`, at the dominant sampling interval
+ */
+#[cfg(feature = "processing")]
+pub(crate) fn fill_gaps_mut(rec: &mut Record, max_gap: Duration, method: &InterpMethod) {
+    let filled: Vec<_> = crate::algorithm::gap_epochs(rec.keys(), max_gap)
+        .into_iter()
+        .filter_map(|t| Some((t, interpolate_at(rec, t, method, Some(max_gap))?)))
+        .collect();
+    rec.extend(filled);
+}
@@ -1,6 +1,6 @@
 //! Meteo sensor
-use crate::observable;
 use crate::Observable;
+use crate::{fieldslice, observable};
 use thiserror::Error;
 
 /// Meteo Observation Sensor
@@ -26,15 +26,17 @@ pub enum ParseSensorError {
     ObservableParsingErro(#[from] observable::ParsingError),
     #[error("failed to parse accuracy field")]
     ParseFloatError(#[from] std::num::ParseFloatError),
+    #[error("malformed field")]
+    FieldError(#[from] fieldslice::Error),
 }
 
 impl std::str::FromStr for Sensor {
     type Err = ParseSensorError;
     fn from_str(content: &str) -> Result<Self, Self::Err> {
-        let (model, rem) = content.split_at(20);
-        let (s_type, rem) = rem.split_at(20 + 6);
-        let (accuracy, rem) = rem.split_at(7 + 4);
-        let (observable, _) = rem.split_at(2);
+        let (model, rem) = fieldslice::split_at(content, 20)?;
+        let (s_type, rem) = fieldslice::split_at(rem, 20 + 6)?;
+        let (accuracy, rem) = fieldslice::split_at(rem, 7 + 4)?;
+        let (observable, _) = fieldslice::split_at(rem, 2)?;
         Ok(Self {
             model: {
                 if !model.trim().is_empty() {
@@ -78,7 +80,7 @@ impl std::fmt::Display for Sensor {
         }
 
         if let Some(accuracy) = self.accuracy {
-            write!(f, "{:^11.1}", accuracy)?
+            write!(f, "{:7.1}    ", accuracy)?
         } else {
             write!(f, "{:11}", "")?
         }
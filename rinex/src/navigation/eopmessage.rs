@@ -1,6 +1,6 @@
 //! `Navigation` new EOP Earth Orientation messages
-use crate::epoch;
 use crate::prelude::*;
+use crate::{epoch, fieldslice};
 use std::str::FromStr;
 use thiserror::Error;
 
@@ -15,6 +15,8 @@ pub enum Error {
     EopMissing2ndLine,
     #[error("eop message missing 3rd line")]
     EopMissing3rdLine,
+    #[error("malformed field")]
+    FieldError(#[from] fieldslice::Error),
 }
 
 /// Earth Orientation Message
@@ -40,25 +42,25 @@ impl EopMessage {
             Some(l) => l,
             _ => return Err(Error::EopMissing1stLine),
         };
-        let (epoch, rem) = line.split_at(23);
-        let (xp, rem) = rem.split_at(19);
-        let (dxp, ddxp) = rem.split_at(19);
+        let (epoch, rem) = fieldslice::split_at(line, 23)?;
+        let (xp, rem) = fieldslice::split_at(rem, 19)?;
+        let (dxp, ddxp) = fieldslice::split_at(rem, 19)?;
 
         let line = match lines.next() {
             Some(l) => l,
             _ => return Err(Error::EopMissing2ndLine),
         };
-        let (_, rem) = line.split_at(23);
-        let (yp, rem) = rem.split_at(19);
-        let (dyp, ddyp) = rem.split_at(19);
+        let (_, rem) = fieldslice::split_at(line, 23)?;
+        let (yp, rem) = fieldslice::split_at(rem, 19)?;
+        let (dyp, ddyp) = fieldslice::split_at(rem, 19)?;
 
         let line = match lines.next() {
             Some(l) => l,
             _ => return Err(Error::EopMissing3rdLine),
         };
-        let (t_tm, rem) = line.split_at(23);
-        let (dut, rem) = rem.split_at(19);
-        let (ddut, dddut) = rem.split_at(19);
+        let (t_tm, rem) = fieldslice::split_at(line, 23)?;
+        let (dut, rem) = fieldslice::split_at(rem, 19)?;
+        let (ddut, dddut) = fieldslice::split_at(rem, 19)?;
 
         let (epoch, _) = epoch::parse_in_timescale(epoch.trim(), ts)?;
         let x = (
@@ -0,0 +1,64 @@
+//! Broadcast ephemerides indexed per SV, for repeated selections
+use crate::navigation::{Ephemeris, NavMsgType};
+use crate::prelude::{Epoch, SV};
+
+#[cfg(any(feature = "processing", all(feature = "obs", feature = "sp3")))]
+use crate::prelude::Rinex;
+#[cfg(any(feature = "processing", all(feature = "obs", feature = "sp3")))]
+use std::collections::HashMap;
+
+/*
+ * Time of ephemeris of given frame: SBAS state vectors are referenced to toc.
+ * CNAV is not supported yet: toc should be the toe.
+ */
+pub(crate) fn ephemeris_toe(msg: NavMsgType, sv: SV, toc: Epoch, eph: &Ephemeris) -> Option<Epoch> {
+    let ts = sv.timescale()?;
+    match msg {
+        _ if sv.constellation.is_sbas() => Some(toc),
+        NavMsgType::CNAV => None,
+        _ => eph.toe(ts),
+    }
+}
+
+/*
+ * Ephemerides sorted by time of ephemeris, per SV.
+ * Selection rules are those of [Rinex::sv_ephemeris], the record is only scanned once.
+ */
+#[cfg(any(feature = "processing", all(feature = "obs", feature = "sp3")))]
+pub(crate) struct EphemerisIndex<'a> {
+    ephemerides: HashMap<SV, Vec<(Epoch, &'a Ephemeris)>>,
+}
+
+#[cfg(any(feature = "processing", all(feature = "obs", feature = "sp3")))]
+impl<'a> EphemerisIndex<'a> {
+    pub(crate) fn new(nav: &'a Rinex) -> Self {
+        let mut ephemerides = HashMap::<SV, Vec<(Epoch, &Ephemeris)>>::new();
+        for (toc, (msg, sv, eph)) in nav.ephemeris() {
+            if let Some(toe) = ephemeris_toe(msg, sv, *toc, eph) {
+                ephemerides.entry(sv).or_default().push((toe, eph));
+            }
+        }
+        for ephemerides in ephemerides.values_mut() {
+            // stable: first published ephemeris is preferred, on identical toe
+            ephemerides.sort_by_key(|(toe, _)| *toe);
+        }
+        Self { ephemerides }
+    }
+    /*
+     * Latest ephemeris of given SV, published at or before t (toe <= t),
+     * and still valid at t. Returns (toe, ephemeris).
+     */
+    pub(crate) fn select(&self, sv: SV, t: Epoch) -> Option<(Epoch, &'a Ephemeris)> {
+        let ephemerides = self.ephemerides.get(&sv)?;
+        let index = ephemerides.partition_point(|(toe, _)| *toe <= t);
+        let (toe, _) = ephemerides.get(index.checked_sub(1)?)?;
+        let first = ephemerides.partition_point(|(toe_i, _)| toe_i < toe);
+        let (toe, eph) = ephemerides[first];
+        let max_dtoe = Ephemeris::max_dtoe(sv.constellation)?;
+        if t - toe < max_dtoe {
+            Some((toe, eph))
+        } else {
+            None
+        }
+    }
+}
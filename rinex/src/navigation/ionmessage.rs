@@ -1,6 +1,6 @@
 use crate::{
-    carrier::Carrier,
     epoch::{parse_in_timescale, ParsingError as EpochParsingError},
+    fieldslice,
     prelude::{
         Epoch,
         TimeScale,
@@ -11,7 +11,9 @@ use bitflags::bitflags;
 use std::str::FromStr;
 use thiserror::Error;
 
-use map_3d::deg2rad;
+#[cfg(feature = "nav")]
+use crate::carrier::Carrier;
+#[cfg(feature = "nav")]
 use std::f64::consts::PI;
 
 /// Model parsing error
@@ -43,6 +45,8 @@ pub enum Error {
     MissingData,
     #[error("failed to parse bgdim parameter")]
     BdValueError,
+    #[error("malformed field")]
+    FieldError(#[from] fieldslice::Error),
     #[error("failed to parse epoch")]
     EpochParsingError(#[from] EpochParsingError),
 }
@@ -90,23 +94,23 @@ impl KbModel {
             Some(l) => l,
             _ => return Err(Error::NgModelMissing1stLine),
         };
-        let (epoch, rem) = line.split_at(23);
-        let (a0, rem) = rem.split_at(19);
-        let (a1, a2) = rem.split_at(19);
+        let (epoch, rem) = fieldslice::split_at(line, 23)?;
+        let (a0, rem) = fieldslice::split_at(rem, 19)?;
+        let (a1, a2) = fieldslice::split_at(rem, 19)?;
 
         let line = match lines.next() {
             Some(l) => l,
             _ => return Err(Error::KbModelMissing2ndLine),
         };
-        let (a3, rem) = line.split_at(23);
-        let (b0, rem) = rem.split_at(19);
-        let (b1, b2) = rem.split_at(19);
+        let (a3, rem) = fieldslice::split_at(line, 23)?;
+        let (b0, rem) = fieldslice::split_at(rem, 19)?;
+        let (b1, b2) = fieldslice::split_at(rem, 19)?;
 
         let line = match lines.next() {
             Some(l) => l,
             _ => return Err(Error::KbModelMissing3rdLine),
         };
-        let (b3, region) = line.split_at(23);
+        let (b3, region) = fieldslice::split_at(line, 23)?;
 
         let region: KbRegionCode = match region.trim().len() {
             0 => KbRegionCode::WideArea,
@@ -148,6 +152,7 @@ impl KbModel {
         ))
     }
     /* converts self to meters of delay */
+    #[cfg(feature = "nav")]
     pub(crate) fn meters_delay(
         &self,
         t: Epoch,
@@ -164,8 +169,8 @@ impl KbModel {
         const L1_F: f64 = 1575.42E6;
 
         let fract = R_EARTH / (R_EARTH + h_km);
-        let phi_u = deg2rad(user_lat_ddeg);
-        let lambda_u = deg2rad(user_lon_ddeg);
+        let phi_u = user_lat_ddeg.to_radians();
+        let lambda_u = user_lon_ddeg.to_radians();
 
         let t_gps = t.to_duration_in_time_scale(TimeScale::GPST).to_seconds();
         let psi = PI / 2.0 - e - (fract * e.cos()).asin();
@@ -248,9 +253,9 @@ impl NgModel {
             Some(l) => l,
             _ => return Err(Error::NgModelMissing1stLine),
         };
-        let (epoch, rem) = line.split_at(23);
-        let (a0, rem) = rem.split_at(19);
-        let (a1, rem) = rem.split_at(19);
+        let (epoch, rem) = fieldslice::split_at(line, 23)?;
+        let (a0, rem) = fieldslice::split_at(rem, 19)?;
+        let (a1, rem) = fieldslice::split_at(rem, 19)?;
 
         let line = match lines.next() {
             Some(l) => l,
@@ -298,23 +303,23 @@ impl BdModel {
             Some(l) => l,
             _ => return Err(Error::BdModelMissing1stLine),
         };
-        let (epoch, rem) = line.split_at(23);
-        let (a0, rem) = rem.split_at(19);
-        let (a1, a2) = rem.split_at(19);
+        let (epoch, rem) = fieldslice::split_at(line, 23)?;
+        let (a0, rem) = fieldslice::split_at(rem, 19)?;
+        let (a1, a2) = fieldslice::split_at(rem, 19)?;
 
         let line = match lines.next() {
             Some(l) => l,
             _ => return Err(Error::KbModelMissing2ndLine),
         };
-        let (a3, rem) = line.split_at(23);
-        let (a4, rem) = rem.split_at(19);
-        let (a5, a6) = rem.split_at(19);
+        let (a3, rem) = fieldslice::split_at(line, 23)?;
+        let (a4, rem) = fieldslice::split_at(rem, 19)?;
+        let (a5, a6) = fieldslice::split_at(rem, 19)?;
 
         let line = match lines.next() {
             Some(l) => l,
             _ => return Err(Error::KbModelMissing3rdLine),
         };
-        let (a7, a8) = line.split_at(23);
+        let (a7, a8) = fieldslice::split_at(line, 23)?;
 
         let (epoch, _) = parse_in_timescale(epoch.trim(), ts)?;
         let alpha = (
@@ -361,15 +366,15 @@ impl IonMessage {
      * It's just unique and applies to the entire daycourse in RINEX3.
      * The API remains totally coherent. */
     pub(crate) fn from_rinex3_header(header: &str) -> Result<Self, Error> {
-        let (corr_type, rem) = header.split_at(5);
+        let (corr_type, rem) = fieldslice::split_at(header, 5)?;
         match corr_type.trim() {
             /*
              * Models that only needs 3 fields
              */
             "GAL" => {
-                let (a0, rem) = rem.split_at(12);
-                let (a1, rem) = rem.split_at(12);
-                let (a2, _) = rem.split_at(12);
+                let (a0, rem) = fieldslice::split_at(rem, 12)?;
+                let (a1, rem) = fieldslice::split_at(rem, 12)?;
+                let (a2, _) = fieldslice::split_at(rem, 12)?;
                 let a0 = f64::from_str(a0.trim()).map_err(|_| Error::NgValueError)?;
                 let a1 = f64::from_str(a1.trim()).map_err(|_| Error::NgValueError)?;
                 let a2 = f64::from_str(a2.trim()).map_err(|_| Error::NgValueError)?;
@@ -382,10 +387,10 @@ impl IonMessage {
                 /*
                  * Model has 4 fields
                  */
-                let (a0, rem) = rem.split_at(12);
-                let (a1, rem) = rem.split_at(12);
-                let (a2, rem) = rem.split_at(12);
-                let (a3, _) = rem.split_at(12);
+                let (a0, rem) = fieldslice::split_at(rem, 12)?;
+                let (a1, rem) = fieldslice::split_at(rem, 12)?;
+                let (a2, rem) = fieldslice::split_at(rem, 12)?;
+                let (a3, _) = fieldslice::split_at(rem, 12)?;
                 // World or QZSS special orbital plan
                 let region = match corr_type.contains("QZS") {
                     true => KbRegionCode::JapanArea,
@@ -4,23 +4,46 @@ mod ephemeris;
 mod health;
 mod ionmessage;
 mod stomessage;
+mod typed;
 
 pub mod orbits;
 pub mod record;
 
+#[cfg(feature = "nav")]
+pub(crate) mod index;
+
+#[cfg(feature = "nav")]
+#[cfg_attr(docrs, doc(cfg(feature = "nav")))]
+pub mod visibility;
+
 pub use eopmessage::EopMessage;
 pub use ephemeris::Ephemeris;
-pub use health::{GeoHealth, GloHealth, Health, IrnssHealth};
+
+#[cfg(feature = "nav")]
+pub use ephemeris::{Kepler, Perturbations};
+
+pub use health::{GalHealth, GeoHealth, GloHealth, Health, IrnssHealth};
 pub use ionmessage::{BdModel, IonMessage, KbModel, KbRegionCode, NgModel, NgRegionFlags};
 pub use orbits::OrbitItem;
-pub use record::{NavFrame, NavMsgType, Record};
-pub use stomessage::StoMessage;
+pub use record::{EphemerisSelection, NavFrame, NavMsgType, Record, RetainMsgType};
+pub use stomessage::{StoMessage, TimeSystem, TimeSystemCorr};
+pub use typed::{
+    BdsEphemeris, BdsOrbitClass, GalDataSource, GalEphemeris, GloEphemeris, GloTimeParameters,
+    GpsEphemeris, SbasEphemeris,
+};
 
-use crate::epoch;
+use crate::{
+    epoch,
+    prelude::{Constellation, Duration, Epoch},
+};
+use std::collections::BTreeMap;
 use thiserror::Error;
 
 use gnss::prelude::SV;
 
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
 /// Navigation Record Parsing Error
 #[derive(Error, Debug)]
 pub enum Error {
@@ -56,6 +79,62 @@ pub enum Error {
     StoMessageError(#[from] stomessage::Error),
     #[error("failed to identify timescale for {0}")]
     TimescaleIdentification(SV),
+    #[error("malformed field")]
+    FieldError(#[from] crate::fieldslice::Error),
+}
+
+/// Navigation specific header fields
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct HeaderFields {
+    /// Ionospheric correction models, per constellation (RINEX3).
+    /// Multi station (BRDC) files describe one model per constellation.
+    pub ionod_corrections: BTreeMap<Constellation, IonMessage>,
+    /// Time System corrections (RINEX3)
+    pub time_corrections: Vec<TimeSystemCorr>,
+}
+
+impl HeaderFields {
+    /// Returns the [TimeSystemCorr] from `lhs` to `rhs`, if described
+    pub fn time_correction(&self, lhs: TimeSystem, rhs: TimeSystem) -> Option<&TimeSystemCorr> {
+        self.time_corrections
+            .iter()
+            .find(|corr| corr.lhs == lhs && corr.rhs == rhs)
+    }
+    /// Evaluates lhs - rhs at `t`, from the described corrections:
+    /// either directly, or through their respective UTC corrections.
+    /// Leap seconds are excluded: this is the offset between the physical
+    /// realizations of the two time systems, that [Epoch] cannot describe.
+    pub fn system_offset(&self, lhs: TimeSystem, rhs: TimeSystem, t: Epoch) -> Option<Duration> {
+        if lhs == rhs {
+            return Some(Duration::ZERO);
+        }
+        if let Some(corr) = self.time_correction(lhs, rhs) {
+            return Some(corr.offset(t));
+        }
+        if let Some(corr) = self.time_correction(rhs, lhs) {
+            return Some(-corr.offset(t));
+        }
+        let lhs = self.time_correction(lhs, TimeSystem::UTC)?;
+        let rhs = self.time_correction(rhs, TimeSystem::UTC)?;
+        Some(lhs.offset(t) - rhs.offset(t))
+    }
+    /*
+     * Merges rhs into self: models and corrections we do not have are added,
+     * ours are preserved
+     */
+    pub(crate) fn merge_mut(&mut self, rhs: &Self) {
+        for (constellation, model) in &rhs.ionod_corrections {
+            self.ionod_corrections
+                .entry(*constellation)
+                .or_insert(*model);
+        }
+        for corr in &rhs.time_corrections {
+            if self.time_correction(corr.lhs, corr.rhs).is_none() {
+                self.time_corrections.push(corr.clone());
+            }
+        }
+    }
 }
 
 /*
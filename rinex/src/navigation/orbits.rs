@@ -1,5 +1,6 @@
 //! NAV Orbits description, spanning all revisions and constellations
 use super::health;
+use crate::formatter::FloatFormat;
 use crate::version;
 use bitflags::bitflags;
 use std::str::FromStr;
@@ -151,17 +152,26 @@ impl OrbitItem {
     /// Formats self following RINEX standards,
     /// mainly used when producing a file
     pub fn to_string(&self) -> String {
+        self.format(&FloatFormat::navigation(3))
+    }
+    /// Formats self with given [FloatFormat]
+    pub fn format(&self, fmt: &FloatFormat) -> String {
+        fmt.format(self.to_f64())
+    }
+    /// Numerical value of self, as encoded in RINEX:
+    /// health indications are expressed by their integer value
+    pub fn to_f64(&self) -> f64 {
         match self {
-            OrbitItem::U8(n) => format!("{:14.11E}", *n as f64),
-            OrbitItem::I8(n) => format!("{:14.11E}", *n as f64),
-            OrbitItem::U32(n) => format!("{:14.11E}", *n as f64),
-            OrbitItem::F64(f) => format!("{:14.11E}", f),
-            OrbitItem::Health(h) => format!("{:14.11E}", h),
-            OrbitItem::GloHealth(h) => format!("{:14.11E}", h),
-            OrbitItem::GeoHealth(h) => format!("{:14.11E}", h),
-            OrbitItem::IrnssHealth(h) => format!("{:14.11E}", h),
-            OrbitItem::GalHealth(h) => format!("{:14.11E}", h.bits() as f64),
-            OrbitItem::GloStatus(h) => format!("{:14.11E}", h.bits() as f64),
+            OrbitItem::U8(n) => *n as f64,
+            OrbitItem::I8(n) => *n as f64,
+            OrbitItem::U32(n) => *n as f64,
+            OrbitItem::F64(f) => *f,
+            OrbitItem::Health(h) => h.clone() as u32 as f64,
+            OrbitItem::GloHealth(h) => h.clone() as u32 as f64,
+            OrbitItem::GeoHealth(h) => h.clone() as u32 as f64,
+            OrbitItem::IrnssHealth(h) => h.clone() as u32 as f64,
+            OrbitItem::GalHealth(h) => h.bits() as f64,
+            OrbitItem::GloStatus(h) => h.bits() as f64,
         }
     }
     /// Unwraps OrbitItem as f64
@@ -231,7 +241,8 @@ impl OrbitItem {
 
 /*
  * Identifies closest (but older) revision contained in NAV database.
- * Closest content (in time) is used during record parsing to identify and sort data.
+ * Closest content (in time) is used during record parsing and formatting to identify and sort data.
+ * SBAS augmentations (EGNOS, WAAS..) share the general SBAS definitions.
  * Returns None
  *   - if no database entries were found for requested constellation.
  *   - or only newer revision exist : we prefer matching on older revisions
@@ -242,6 +253,10 @@ pub(crate) fn closest_nav_standards(
     msg: NavMsgType,
 ) -> Option<&'static NavHelper<'static>> {
     let database = &NAV_ORBITS;
+    let constellation = match constellation.is_sbas() {
+        true => Constellation::SBAS,
+        false => constellation,
+    };
     // start by trying to locate desired revision.
     // On each mismatch, we decrement and move on to next major/minor combination.
     let (mut major, mut minor): (u8, u8) = revision.into();
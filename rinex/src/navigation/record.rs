@@ -1,40 +1,14 @@
 //! NAV frames parser
 use super::{Error, FrameClass};
-use regex::{Captures, Regex};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::str::FromStr;
 
 #[cfg(docrs)]
 use crate::Bibliography;
 
-/*
- * When formatting floating point number in Navigation RINEX,
- * exponent are expected to be in the %02d form,
- * but Rust is only capable of formating %d (AFAIK).
- * With this macro, we simply rework all exponents encountered in a string
- */
-fn double_exponent_digits(content: &str) -> String {
-    // replace "eN " with "E+0N"
-    let re = Regex::new(r"e\d{1} ").unwrap();
-    let lines = re.replace_all(content, |caps: &Captures| format!("E+0{}", &caps[0][1..]));
-
-    // replace "eN" with "E+0N"
-    let re = Regex::new(r"e\d{1}").unwrap();
-    let lines = re.replace_all(&lines, |caps: &Captures| format!("E+0{}", &caps[0][1..]));
-
-    // replace "e-N " with "E-0N"
-    let re = Regex::new(r"e-\d{1} ").unwrap();
-    let lines = re.replace_all(&lines, |caps: &Captures| format!("E-0{}", &caps[0][2..]));
-
-    // replace "e-N" with "e-0N"
-    let re = Regex::new(r"e-\d{1}").unwrap();
-    let lines = re.replace_all(&lines, |caps: &Captures| format!("E-0{}", &caps[0][2..]));
-
-    lines.to_string()
-}
-
 use crate::{
-    epoch, merge, merge::Merge, prelude::*, split, split::Split, types::Type, version::Version,
+    epoch, fieldslice, formatter::FloatFormat, merge, merge::Merge, prelude::*, record, split,
+    split::Split, types::Type, version::Version,
 };
 
 use super::{
@@ -147,6 +121,21 @@ impl Default for NavFrame {
 }
 
 impl NavFrame {
+    /// Returns the [NavMsgType] this frame was published with.
+    /// Galileo Ephemeris described as legacy frames (RINEX3) are resolved
+    /// to either I/NAV or F/NAV, from their data source bits.
+    pub fn msg_type(&self) -> NavMsgType {
+        match self {
+            Self::Eph(msg, sv, eph) => {
+                if *msg == NavMsgType::LNAV && sv.constellation == Constellation::Galileo {
+                    eph.gal_data_source().msg_type().unwrap_or(*msg)
+                } else {
+                    *msg
+                }
+            },
+            Self::Eop(msg, _, _) | Self::Ion(msg, _, _) | Self::Sto(msg, _, _) => *msg,
+        }
+    }
     /// Unwraps self, if possible, as ([`NavMsgType`], [`SV`], [`Ephemeris`])
     pub fn as_eph(&self) -> Option<(NavMsgType, SV, &Ephemeris)> {
         match self {
@@ -209,6 +198,141 @@ impl NavFrame {
 /// data is sorted by [`Epoch`] and wrapped in a [`NavFrame`]
 pub type Record = BTreeMap<Epoch, Vec<NavFrame>>;
 
+/// Message type selection, on Navigation [Record]s
+pub trait RetainMsgType {
+    /// Retains only frames published with given [NavMsgType].
+    /// Galileo frames are resolved to either I/NAV or F/NAV,
+    /// refer to [NavFrame::msg_type].
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::navigation::{NavMsgType, RetainMsgType};
+    /// let mut rinex = Rinex::from_file("../test_resources/NAV/V3/CBW100NLD_R_20210010000_01D_MN.rnx")
+    ///     .unwrap();
+    /// let record = rinex.record.as_mut_nav().unwrap();
+    /// record.retain_msg_type(NavMsgType::FNAV);
+    /// assert!(record.len() > 0);
+    /// ```
+    fn retain_msg_type(&mut self, msg: NavMsgType);
+}
+
+impl RetainMsgType for Record {
+    fn retain_msg_type(&mut self, msg: NavMsgType) {
+        self.retain(|_, frames| {
+            frames.retain(|fr| fr.msg_type() == msg);
+            !frames.is_empty()
+        });
+    }
+}
+
+/*
+ * Identifies an ephemeris data set: issue of data and time of ephemeris.
+ * Messages without issue of data or time of ephemeris (Glonass, SBAS)
+ * are identified by their epoch.
+ */
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum DataSet {
+    Issue(u32, u64),
+    Epoch(Epoch),
+}
+
+impl DataSet {
+    fn new(epoch: Epoch, eph: &Ephemeris) -> Self {
+        let iod = ["iode", "iodnav", "aode"]
+            .iter()
+            .find_map(|field| eph.get_orbit_f64(field));
+        match (iod, eph.get_orbit_f64("toe")) {
+            (Some(iod), Some(toe)) => Self::Issue(iod as u32, toe.to_bits()),
+            _ => Self::Epoch(epoch),
+        }
+    }
+}
+
+/// Ephemeris deduplication and selection, on Navigation [Record]s
+pub trait EphemerisSelection {
+    /// Broadcast Navigation files, especially merged ones, contain the same
+    /// ephemeris many times, as it is repeatedly uploaded. This only retains
+    /// the first message of each (SV, issue of data) data set. Issue of data is
+    /// IODE (GPS, QZSS, IRNSS), IODnav (Galileo) or AODE (BeiDou), along with the time of ephemeris.
+    /// Messages without issue of data (Glonass, SBAS) are deduplicated per epoch.
+    /// Other frames are left untouched.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::navigation::EphemerisSelection;
+    /// let mut rinex = Rinex::from_file("../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx")
+    ///     .unwrap();
+    /// let count = rinex.ephemeris().count();
+    /// let record = rinex.record.as_mut_nav()
+    ///     .unwrap();
+    /// // each ephemeris uploaded twice
+    /// for frames in record.values_mut() {
+    ///     let uploads = frames.clone();
+    ///     frames.extend(uploads);
+    /// }
+    /// record.dedup();
+    /// assert_eq!(rinex.ephemeris().count(), count);
+    /// ```
+    fn dedup(&mut self);
+    /// Returns the latest ephemeris of each SV, published at or before `t`,
+    /// in the form (epoch (toc), ephemeris).
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::navigation::EphemerisSelection;
+    /// use std::str::FromStr;
+    /// let rinex = Rinex::from_file("../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx")
+    ///     .unwrap();
+    /// let t = Epoch::from_str("2021-01-01T12:00:00 GPST")
+    ///     .unwrap();
+    /// let record = rinex.record.as_nav()
+    ///     .unwrap();
+    /// for (sv, (toc, _)) in record.latest_before(t) {
+    ///     assert!(toc <= t);
+    /// }
+    /// ```
+    fn latest_before(&self, t: Epoch) -> BTreeMap<SV, (Epoch, &Ephemeris)>;
+}
+
+impl EphemerisSelection for Record {
+    fn dedup(&mut self) {
+        let mut sets = BTreeSet::<(SV, NavMsgType, DataSet)>::new();
+        self.retain(|epoch, frames| {
+            frames.retain(|fr| match fr {
+                NavFrame::Eph(_, sv, eph) => {
+                    // Galileo I/NAV and F/NAV are distinct messages
+                    sets.insert((*sv, fr.msg_type(), DataSet::new(*epoch, eph)))
+                },
+                _ => true,
+            });
+            !frames.is_empty()
+        });
+    }
+    fn latest_before(&self, t: Epoch) -> BTreeMap<SV, (Epoch, &Ephemeris)> {
+        let mut ret = BTreeMap::new();
+        for (epoch, frames) in self.range(..=t) {
+            for (_, sv, eph) in frames.iter().filter_map(|fr| fr.as_eph()) {
+                ret.insert(sv, (*epoch, eph));
+            }
+        }
+        ret
+    }
+}
+
+/*
+ * Estimated memory footprint of a Navigation record, in bytes
+ */
+pub(crate) fn memory_usage(rec: &Record) -> usize {
+    let mut size = record::btreemap_usage(rec);
+    for frames in rec.values() {
+        size += frames.capacity() * std::mem::size_of::<NavFrame>();
+        for frame in frames {
+            if let Some((_, _, eph)) = frame.as_eph() {
+                size += record::hashmap_usage(&eph.orbits);
+                size += eph.orbits.keys().map(|key| key.capacity()).sum::<usize>();
+            }
+        }
+    }
+    size
+}
+
 /// Returns true if given content matches the beginning of a
 /// Navigation record epoch
 pub(crate) fn is_new_epoch(line: &str, v: Version) -> bool {
@@ -218,14 +342,16 @@ pub(crate) fn is_new_epoch(line: &str, v: Version) -> bool {
             return false; // not enough bytes
                           // to describe a PRN and an Epoch
         }
-        let (prn, _) = line.split_at(2);
         // 1st entry is a valid integer number
+        let prn = fieldslice::field(line, 0, 2).unwrap_or_default();
         if u8::from_str_radix(prn.trim(), 10).is_err() {
             return false;
         }
         // rest matches a valid epoch descriptor
-        let datestr = &line[3..22];
-        epoch::parse_utc(datestr).is_ok()
+        match fieldslice::field(line, 3, 22) {
+            Ok(datestr) => epoch::parse_utc(datestr).is_ok(),
+            Err(_) => false,
+        }
     } else if v.major == 3 {
         // RINEX V3
         if line.len() < 24 {
@@ -233,13 +359,15 @@ pub(crate) fn is_new_epoch(line: &str, v: Version) -> bool {
                           // to describe an SV and an Epoch
         }
         // 1st entry matches a valid SV description
-        let (sv, _) = line.split_at(4);
+        let sv = fieldslice::field(line, 0, 4).unwrap_or_default();
         if SV::from_str(sv).is_err() {
             return false;
         }
         // rest matches a valid epoch descriptor
-        let datestr = &line[4..23];
-        epoch::parse_utc(datestr).is_ok()
+        match fieldslice::field(line, 4, 23) {
+            Ok(datestr) => epoch::parse_utc(datestr).is_ok(),
+            Err(_) => false,
+        }
     } else {
         // Modern --> easy
         if let Some(c) = line.chars().next() {
@@ -271,9 +399,9 @@ fn parse_v4_record_entry(content: &str) -> Result<(Epoch, NavFrame), Error> {
         _ => return Err(Error::MissingData),
     };
 
-    let (_, rem) = line.split_at(2);
-    let (frame_class, rem) = rem.split_at(4);
-    let (svnn, rem) = rem.split_at(4);
+    let (_, rem) = fieldslice::split_at(line, 2)?;
+    let (frame_class, rem) = fieldslice::split_at(rem, 4)?;
+    let (svnn, rem) = fieldslice::split_at(rem, 4)?;
 
     // parse marker: defines which frame type will follow
     let frame_class = FrameClass::from_str(frame_class.trim())?;
@@ -337,28 +465,6 @@ fn parse_v2_v3_record_entry(
     Ok((epoch, fr))
 }
 
-/*
- * Reworks generated/formatted line to match standards
- */
-fn fmt_rework(major: u8, lines: &str) -> String {
-    /*
-     * There's an issue when formatting the exponent 00 in XXXXX.E00
-     * Rust does not know how to format an exponent on multiples digits,
-     * and RINEX expects two.
-     * If we try to rework this line, it may corrupt some SVNN fields.
-     */
-    let mut lines = double_exponent_digits(lines);
-
-    if major < 3 {
-        /*
-         * In old RINEX, D+00 D-01 is used instead of E+00 E-01
-         */
-        lines = lines.replace("E-", "D-");
-        lines = lines.replace("E+", "D+");
-    }
-    lines.to_string()
-}
-
 /*
  * Writes given epoch into stream
  */
@@ -376,6 +482,13 @@ pub(crate) fn fmt_epoch(
 
 fn fmt_epoch_v2v3(epoch: &Epoch, data: &Vec<NavFrame>, header: &Header) -> Result<String, Error> {
     let mut lines = String::with_capacity(128);
+    let fmt = FloatFormat::navigation(header.version.major);
+    // continuation lines are indented (3X or 4X)
+    let indent = if header.version.major < 3 {
+        "   "
+    } else {
+        "    "
+    };
     for fr in data.iter() {
         if let Some(fr) = fr.as_eph() {
             let (_, sv, ephemeris) = fr;
@@ -387,24 +500,29 @@ fn fmt_epoch_v2v3(epoch: &Epoch, data: &Vec<NavFrame>, header: &Header) -> Resul
                 },
                 Some(_) => {
                     // Unique constellation context:
-                    // in V2 format, only PRN is shown
-                    lines.push_str(&format!("{:2} ", sv.prn));
+                    // in V2 format, only PRN is shown (PRN - 100 for GEO vehicles)
+                    let prn = if sv.constellation.is_sbas() && sv.prn >= 100 {
+                        sv.prn - 100
+                    } else {
+                        sv.prn
+                    };
+                    lines.push_str(&format!("{:2} ", prn));
                 },
                 None => {
                     panic!("can't generate data without predefined constellations");
                 },
             }
-            lines.push_str(&format!(
-                "{} ",
-                epoch::format(*epoch, None, Type::NavigationData, header.version.major)
-            ));
-            lines.push_str(&format!(
-                "{:14.11E} {:14.11E} {:14.11E}\n   ",
-                ephemeris.clock_bias, ephemeris.clock_drift, ephemeris.clock_drift_rate
+            lines.push_str(&epoch::format(
+                *epoch,
+                None,
+                Type::NavigationData,
+                header.version.major,
             ));
-            if header.version.major == 3 {
-                lines.push_str("  ");
-            }
+            lines.push_str(&fmt.format(ephemeris.clock_bias));
+            lines.push_str(&fmt.format(ephemeris.clock_drift));
+            lines.push_str(&fmt.format(ephemeris.clock_drift_rate));
+            lines.push('\n');
+            lines.push_str(indent);
 
             // locate closest standards in DB
             let closest_orbits_definition =
@@ -416,26 +534,27 @@ fn fmt_epoch_v2v3(epoch: &Epoch, data: &Vec<NavFrame>, header: &Header) -> Resul
             let nb_items_per_line = 4;
             let mut chunks = closest_orbits_definition
                 .items
-                .chunks_exact(nb_items_per_line)
+                .chunks(nb_items_per_line)
                 .peekable();
 
             while let Some(chunk) = chunks.next() {
                 if chunks.peek().is_some() {
                     for (key, _) in chunk {
                         if let Some(data) = ephemeris.orbits.get(*key) {
-                            lines.push_str(&format!("{} ", data.to_string()));
+                            lines.push_str(&data.format(&fmt));
                         } else {
-                            lines.push_str("                   ");
+                            lines.push_str(&" ".repeat(fmt.width));
                         }
                     }
-                    lines.push_str("\n     ");
+                    lines.push('\n');
+                    lines.push_str(indent);
                 } else {
                     // last row
                     for (key, _) in chunk {
                         if let Some(data) = ephemeris.orbits.get(*key) {
-                            lines.push_str(&data.to_string());
+                            lines.push_str(&data.format(&fmt));
                         } else {
-                            lines.push_str("                   ");
+                            lines.push_str(&" ".repeat(fmt.width));
                         }
                     }
                     lines.push('\n');
@@ -443,12 +562,12 @@ fn fmt_epoch_v2v3(epoch: &Epoch, data: &Vec<NavFrame>, header: &Header) -> Resul
             }
         }
     }
-    lines = fmt_rework(header.version.major, &lines);
     Ok(lines)
 }
 
 fn fmt_epoch_v4(epoch: &Epoch, data: &Vec<NavFrame>, header: &Header) -> Result<String, Error> {
     let mut lines = String::with_capacity(128);
+    let fmt = FloatFormat::navigation(4);
     for fr in data.iter() {
         if let Some(fr) = fr.as_eph() {
             let (msgtype, sv, ephemeris) = fr;
@@ -467,14 +586,16 @@ fn fmt_epoch_v4(epoch: &Epoch, data: &Vec<NavFrame>, header: &Header) -> Result<
                 },
                 None => panic!("producing data with no constellation previously defined"),
             }
-            lines.push_str(&format!(
-                "{} ",
-                epoch::format(*epoch, None, Type::NavigationData, header.version.major)
-            ));
-            lines.push_str(&format!(
-                "{:14.13E} {:14.13E} {:14.13E}\n",
-                ephemeris.clock_bias, ephemeris.clock_drift, ephemeris.clock_drift_rate
+            lines.push_str(&epoch::format(
+                *epoch,
+                None,
+                Type::NavigationData,
+                header.version.major,
             ));
+            lines.push_str(&fmt.format(ephemeris.clock_bias));
+            lines.push_str(&fmt.format(ephemeris.clock_drift));
+            lines.push_str(&fmt.format(ephemeris.clock_drift_rate));
+            lines.push('\n');
 
             // locate closest revision in DB
             let closest_orbits_definition =
@@ -483,17 +604,19 @@ fn fmt_epoch_v4(epoch: &Epoch, data: &Vec<NavFrame>, header: &Header) -> Result<
                     _ => return Err(Error::OrbitRevision),
                 };
 
-            let mut index = 0;
-            for (key, _) in closest_orbits_definition.items.iter() {
-                index += 1;
+            let nb_items = closest_orbits_definition.items.len();
+            for (index, (key, _)) in closest_orbits_definition.items.iter().enumerate() {
+                if (index % 4) == 0 {
+                    lines.push_str("    ");
+                }
                 if let Some(data) = ephemeris.orbits.get(*key) {
-                    lines.push_str(&format!(" {}", data.to_string()));
+                    lines.push_str(&data.format(&fmt));
                 } else {
                     // data is missing: either not parsed or not provided
-                    lines.push_str("              ");
+                    lines.push_str(&" ".repeat(fmt.width));
                 }
-                if (index % 4) == 0 {
-                    lines.push_str("\n   "); //TODO: do not TAB when writing last line of grouping
+                if (index % 4) == 3 || index == nb_items - 1 {
+                    lines.push('\n');
                 }
             }
         } else if let Some(fr) = fr.as_sto() {
@@ -510,10 +633,12 @@ fn fmt_epoch_v4(epoch: &Epoch, data: &Vec<NavFrame>, header: &Header) -> Result<
                 sto.system,
                 sto.utc
             ));
-            lines.push_str(&format!(
-                "   {:14.13E} {:14.13E} {:14.13E} {:14.13E}\n",
-                sto.t_tm as f64, sto.a.0, sto.a.1, sto.a.2
-            ));
+            lines.push_str("    ");
+            lines.push_str(&fmt.format(sto.t_tm as f64));
+            lines.push_str(&fmt.format(sto.a.0));
+            lines.push_str(&fmt.format(sto.a.1));
+            lines.push_str(&fmt.format(sto.a.2));
+            lines.push('\n');
         } else if let Some(_fr) = fr.as_eop() {
             todo!("NAV V4: EOP: we have no example as of today");
             //(x, xr, xrr), (y, yr, yrr), t_tm, (dut, dutr, dutrr)) = frame.as_eop()
@@ -534,7 +659,6 @@ fn fmt_epoch_v4(epoch: &Epoch, data: &Vec<NavFrame>, header: &Header) -> Result<
             }
         } // ION
     }
-    lines = fmt_rework(4, &lines);
     Ok(lines)
 }
 
@@ -870,6 +994,7 @@ mod test {
 
         let (msg_type, sv, ephemeris) = fr.unwrap();
         assert_eq!(msg_type, NavMsgType::LNAV);
+        assert_eq!(frame.msg_type(), NavMsgType::FNAV);
         assert_eq!(
             sv,
             SV {
@@ -1104,16 +1229,16 @@ mod test {
         }
     }
     #[test]
-    fn format_rework() {
-        let content = "1000123  -123123e-1 -1.23123123e0 -0.123123e-4";
-        assert_eq!(
-            fmt_rework(2, content),
-            "1000123  -123123D-01 -1.23123123D+00 -0.123123D-04"
-        );
-        assert_eq!(
-            fmt_rework(3, content),
-            "1000123  -123123E-01 -1.23123123E+00 -0.123123E-04"
-        );
+    fn format_nav_fields() {
+        // former "E" reworking: exponents on two digits, D prior V3
+        let v2 = FloatFormat::navigation(2);
+        assert_eq!(v2.format(-12312.3), "-1.231230000000D+04");
+        assert_eq!(v2.format(-1.23123123), "-1.231231230000D+00");
+        assert_eq!(v2.format(-0.123123E-4), "-1.231230000000D-05");
+        let v3 = FloatFormat::navigation(3);
+        assert_eq!(v3.format(-12312.3), "-1.231230000000E+04");
+        assert_eq!(v3.format(-1.23123123), "-1.231231230000E+00");
+        assert_eq!(v3.format(-0.123123E-4), "-1.231230000000E-05");
     }
 }
 
@@ -1143,6 +1268,58 @@ impl Merge for Record {
     }
 }
 
+/*
+ * Two frames conflict when they describe the same message of the same vehicle
+ */
+fn conflicting_frames(lhs: &NavFrame, rhs: &NavFrame) -> bool {
+    match (lhs, rhs) {
+        (NavFrame::Eph(msg, sv, _), NavFrame::Eph(rhs_msg, rhs_sv, _))
+        | (NavFrame::Eop(msg, sv, _), NavFrame::Eop(rhs_msg, rhs_sv, _))
+        | (NavFrame::Ion(msg, sv, _), NavFrame::Ion(rhs_msg, rhs_sv, _))
+        | (NavFrame::Sto(msg, sv, _), NavFrame::Sto(rhs_msg, rhs_sv, _)) => {
+            msg == rhs_msg && sv == rhs_sv
+        },
+        _ => false,
+    }
+}
+
+/*
+ * Merges `rhs` into `lhs`, resolving conflicting frames with given policy
+ */
+pub(crate) fn merge_with_mut(
+    lhs: &mut Record,
+    rhs: &Record,
+    policy: merge::MergePolicy,
+) -> Result<(), merge::Error> {
+    match policy {
+        merge::MergePolicy::Average => return Err(merge::Error::UnsupportedPolicy(policy)),
+        merge::MergePolicy::Error => {
+            if let Some(epoch) = merge::first_conflict(lhs, rhs) {
+                return Err(merge::Error::EpochConflict(*epoch));
+            }
+        },
+        _ => {},
+    }
+    for (rhs_epoch, rhs_frames) in rhs {
+        if let Some(frames) = lhs.get_mut(rhs_epoch) {
+            for rhs_fr in rhs_frames {
+                match frames.iter_mut().find(|fr| conflicting_frames(fr, rhs_fr)) {
+                    Some(fr) => {
+                        if policy == merge::MergePolicy::PreferOther {
+                            *fr = rhs_fr.clone();
+                        }
+                    },
+                    None => frames.push(rhs_fr.clone()), // insert new NavFrame
+                }
+            }
+        } else {
+            // insert new epoch
+            lhs.insert(*rhs_epoch, rhs_frames.clone());
+        }
+    }
+    Ok(())
+}
+
 impl Split for Record {
     fn split(&self, epoch: Epoch) -> Result<(Self, Self), split::Error> {
         let r0 = self
@@ -1253,7 +1430,7 @@ fn mask_mut_equal(rec: &mut Record, target: TargetItem) {
             rec.retain(|_, frames| {
                 frames.retain(|fr| {
                     if let Some((msg, _, _)) = fr.as_eph() {
-                        filter.contains(&msg)
+                        filter.contains(&msg) || filter.contains(&fr.msg_type())
                     } else if let Some((msg, _, _)) = fr.as_ion() {
                         filter.contains(&msg)
                     } else if let Some((msg, _, _)) = fr.as_eop() {
@@ -1350,7 +1527,7 @@ fn mask_mut_ineq(rec: &mut Record, target: TargetItem) {
             rec.retain(|_, frames| {
                 frames.retain(|fr| {
                     if let Some((msg, _, _)) = fr.as_eph() {
-                        !filter.contains(&msg)
+                        !filter.contains(&msg) && !filter.contains(&fr.msg_type())
                     } else if let Some((msg, _, _)) = fr.as_ion() {
                         !filter.contains(&msg)
                     } else if let Some((msg, _, _)) = fr.as_eop() {
@@ -1710,7 +1887,7 @@ impl Preprocessing for Record {
                 },
                 DecimationType::DecimByInterval(dt) => {
                     if filter.target.is_none() {
-                        self.decimate_by_interval_mut(dt);
+                        self.decimate_by_sampling_interval_mut(dt);
                         return; // no need to proceed further
                     }
 
@@ -1723,7 +1900,7 @@ impl Preprocessing for Record {
                     };
 
                     // decimate
-                    let subset = self.mask(mask).decimate_by_interval(dt);
+                    let subset = self.mask(mask).decimate_by_sampling_interval(dt);
                     // adapt self's subset to new data rate
                     decimate_data_subset(self, &subset, &item);
                 },
@@ -1766,10 +1943,14 @@ impl Decimate for Record {
     fn decimate_by_interval_mut(&mut self, interval: Duration) {
         let mut last_retained: Option<Epoch> = None;
         self.retain(|e, _| {
-            if last_retained.is_some() {
-                let dt = *e - last_retained.unwrap();
-                last_retained = Some(*e);
-                dt > interval
+            if let Some(last) = last_retained {
+                // compared to last retained epoch, so high rate
+                // data can be decimated to any larger interval
+                let retained = *e - last >= interval;
+                if retained {
+                    last_retained = Some(*e);
+                }
+                retained
             } else {
                 last_retained = Some(*e);
                 true // always retain 1st epoch
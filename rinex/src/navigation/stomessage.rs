@@ -1,5 +1,9 @@
-use crate::epoch;
-use hifitime::{Epoch, TimeScale};
+use crate::{
+    epoch, fieldslice,
+    formatter::{parse_float, Exponent, FloatFormat},
+    prelude::Constellation,
+};
+use hifitime::{Duration, Epoch, TimeScale, Unit};
 use std::str::FromStr;
 use thiserror::Error;
 
@@ -12,6 +16,89 @@ pub enum Error {
     EpochParsingError(#[from] epoch::ParsingError),
     #[error("failed to parse data")]
     ParseFloatError(#[from] std::num::ParseFloatError),
+    #[error("failed to parse reference time")]
+    ParseIntError(#[from] std::num::ParseIntError),
+    #[error("invalid time system correction")]
+    TimeSystemCorr(#[from] fieldslice::Error),
+    #[error("unknown time systems \"{0}\"")]
+    UnknownTimeSystem(String),
+}
+
+/// Time System, related to another one by time system corrections
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum TimeSystem {
+    /// System time of given constellation
+    GNSS(Constellation),
+    /// Universal Coordinated Time
+    UTC,
+}
+
+impl TimeSystem {
+    /// Returns the [TimeSystem] in which the Epochs of given [TimeScale] are expressed
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::navigation::TimeSystem;
+    /// assert_eq!(
+    ///     TimeSystem::from_timescale(TimeScale::GST),
+    ///     Some(TimeSystem::GNSS(Constellation::Galileo))
+    /// );
+    /// assert_eq!(TimeSystem::from_timescale(TimeScale::TAI), None);
+    /// ```
+    pub fn from_timescale(ts: TimeScale) -> Option<Self> {
+        match ts {
+            TimeScale::GPST => Some(Self::GNSS(Constellation::GPS)),
+            TimeScale::GST => Some(Self::GNSS(Constellation::Galileo)),
+            TimeScale::BDT => Some(Self::GNSS(Constellation::BeiDou)),
+            TimeScale::UTC => Some(Self::UTC),
+            _ => None,
+        }
+    }
+    /*
+     * Two letters code, as used in correction types
+     */
+    fn code(&self) -> &str {
+        match self {
+            Self::UTC => "UT",
+            Self::GNSS(Constellation::GPS) => "GP",
+            Self::GNSS(Constellation::Galileo) => "GA",
+            Self::GNSS(Constellation::BeiDou) => "BD",
+            Self::GNSS(Constellation::QZSS) => "QZ",
+            Self::GNSS(Constellation::IRNSS) => "IR",
+            Self::GNSS(Constellation::Glonass) => "GL",
+            Self::GNSS(_) => "SB",
+        }
+    }
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "UT" => Some(Self::UTC),
+            "GP" => Some(Self::GNSS(Constellation::GPS)),
+            "GA" => Some(Self::GNSS(Constellation::Galileo)),
+            "BD" => Some(Self::GNSS(Constellation::BeiDou)),
+            "QZ" => Some(Self::GNSS(Constellation::QZSS)),
+            "IR" => Some(Self::GNSS(Constellation::IRNSS)),
+            "GL" => Some(Self::GNSS(Constellation::Glonass)),
+            "SB" => Some(Self::GNSS(Constellation::SBAS)),
+            _ => None,
+        }
+    }
+    /*
+     * Decodes a correction type, like GPUT or GAGP
+     */
+    pub(crate) fn decode(correction_type: &str) -> Option<(Self, Self)> {
+        let lhs = Self::from_code(correction_type.get(..2)?)?;
+        let rhs = Self::from_code(correction_type.get(2..4)?)?;
+        Some((lhs, rhs))
+    }
+}
+
+impl std::fmt::Display for TimeSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::UTC => f.write_str("UTC"),
+            Self::GNSS(constellation) => write!(f, "{}", constellation),
+        }
+    }
 }
 
 /// System Time Offset Message
@@ -35,18 +122,18 @@ impl StoMessage {
             _ => return Err(Error::MissingData),
         };
 
-        let (epoch, rem) = line.split_at(23);
-        let (system, _) = rem.split_at(5);
+        let (epoch, rem) = fieldslice::split_at(line, 23)?;
+        let (system, _) = fieldslice::split_at(rem, 5)?;
         let (epoch, _) = epoch::parse_in_timescale(epoch.trim(), ts)?;
 
         let line = match lines.next() {
             Some(l) => l,
             _ => return Err(Error::MissingData),
         };
-        let (time, rem) = line.split_at(23);
-        let (a0, rem) = rem.split_at(19);
-        let (a1, rem) = rem.split_at(19);
-        let (a2, rem) = rem.split_at(19);
+        let (time, rem) = fieldslice::split_at(line, 23)?;
+        let (a0, rem) = fieldslice::split_at(rem, 19)?;
+        let (a1, rem) = fieldslice::split_at(rem, 19)?;
+        let (a2, rem) = fieldslice::split_at(rem, 19)?;
 
         let t_tm = f64::from_str(time.trim())?;
         Ok((
@@ -63,4 +150,220 @@ impl StoMessage {
             },
         ))
     }
+    /// Returns the (lhs, rhs) [TimeSystem]s related by this message
+    pub fn time_systems(&self) -> Option<(TimeSystem, TimeSystem)> {
+        TimeSystem::decode(&self.system)
+    }
+    /// Evaluates lhs - rhs at `t`, where `reference` is the Epoch of this message
+    pub fn offset(&self, reference: Epoch, t: Epoch) -> Duration {
+        let dt = (t - reference).to_seconds();
+        let (a0, a1, a2) = self.a;
+        Duration::from_seconds(a0 + a1 * dt + a2 * dt.powi(2))
+    }
+}
+
+/// Time System Correction, as described in RINEX3 Navigation headers.
+/// This is the ancestor of the [StoMessage]: the two time systems
+/// are related by lhs - rhs = `a0 + a1 * (t - t_ref)`, leap seconds excluded.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::navigation::TimeSystem;
+/// use std::str::FromStr;
+/// let rnx = Rinex::from_file("../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx")
+///     .unwrap();
+/// let nav = rnx.header.nav.as_ref().unwrap();
+/// let (gst, gpst) = (
+///     TimeSystem::GNSS(Constellation::Galileo),
+///     TimeSystem::GNSS(Constellation::GPS),
+/// );
+/// // GAGP: GST - GPST
+/// let ggto = nav.time_correction(gst, gpst).unwrap();
+/// assert_eq!(ggto.code(), "GAGP");
+///
+/// let t = Epoch::from_str("2021-01-01T00:00:00 GPST").unwrap();
+/// let offset = ggto.offset(t);
+/// assert!(offset.abs() < Duration::from_nanoseconds(5.0));
+/// ```
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TimeSystemCorr {
+    /// Time system being corrected
+    pub lhs: TimeSystem,
+    /// Reference time system
+    pub rhs: TimeSystem,
+    /// Offset (s)
+    pub a0: f64,
+    /// Drift (s.s⁻¹)
+    pub a1: f64,
+    /// Reference time, in seconds of week
+    pub t_ref: u32,
+    /// Reference week
+    pub week: u32,
+    /// Augmentation system that provided this correction, if any
+    pub source: String,
+    /// UTC identifier, if any
+    pub utc: String,
+}
+
+impl TimeSystemCorr {
+    /// Returns the correction type, like GPUT (GPST to UTC) or GAGP (GST to GPST)
+    pub fn code(&self) -> String {
+        format!("{}{}", self.lhs.code(), self.rhs.code())
+    }
+    /// Returns the reference Epoch of this correction.
+    /// BeiDou corrections are referenced to the BDT week,
+    /// the other ones to the GPST week.
+    pub fn reference(&self) -> Epoch {
+        let (ts, origin) = match self.lhs {
+            TimeSystem::GNSS(Constellation::BeiDou) => (
+                TimeScale::BDT,
+                Epoch::from_gregorian_at_midnight(2006, 1, 1, TimeScale::BDT),
+            ),
+            _ => (
+                TimeScale::GPST,
+                Epoch::from_gregorian_at_midnight(1980, 1, 6, TimeScale::GPST),
+            ),
+        };
+        /*
+         * week origin and epochs are both expressed in the gregorian calendar,
+         * like the epochs we parse, so they remain comparable
+         */
+        let week = (self.week * 7) as f64 * Unit::Day;
+        (origin + week + self.t_ref as f64 * Unit::Second).in_time_scale(ts)
+    }
+    /// Evaluates lhs - rhs at `t`
+    pub fn offset(&self, t: Epoch) -> Duration {
+        let dt = (t - self.reference()).to_seconds();
+        Duration::from_seconds(self.a0 + self.a1 * dt)
+    }
+    /*
+     * Parses Self from RINEX3 header "TIME SYSTEM CORR" content
+     */
+    pub(crate) fn from_rinex3_header(content: &str) -> Result<Self, Error> {
+        let system = fieldslice::exact(content, 0, 4)?.trim();
+        let (lhs, rhs) =
+            TimeSystem::decode(system).ok_or(Error::UnknownTimeSystem(system.to_string()))?;
+        let a0 = fieldslice::exact(content, 5, 22)?;
+        let a1 = fieldslice::exact(content, 22, 38)?;
+        let t_ref = fieldslice::exact(content, 38, 45)?;
+        let week = fieldslice::exact(content, 45, 50)?;
+        Ok(Self {
+            lhs,
+            rhs,
+            a0: parse_float(a0)?,
+            a1: parse_float(a1)?,
+            t_ref: t_ref.trim().parse::<u32>()?,
+            week: week.trim().parse::<u32>()?,
+            source: fieldslice::field(content, 51, 56)?.trim().to_string(),
+            utc: fieldslice::field(content, 57, 59)?.trim().to_string(),
+        })
+    }
+    /*
+     * Formats Self as RINEX3 header "TIME SYSTEM CORR" content
+     */
+    pub(crate) fn to_rinex3_header(&self) -> String {
+        format!(
+            "{:<4} {}{} {:6} {:4} {:<5} {:>2}",
+            self.code(),
+            FloatFormat::scientific(17, 10, Exponent::E).format(self.a0),
+            FloatFormat::scientific(16, 9, Exponent::E).format(self.a1),
+            self.t_ref,
+            self.week,
+            self.source,
+            self.utc,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn time_system_corr() {
+        for (content, system, a0, a1, t_ref, week) in [
+            (
+                "GAUT  1.8626451492E-09-8.881784197E-16 432000 2138",
+                "GAUT",
+                1.8626451492E-09,
+                -8.881784197E-16,
+                432000,
+                2138,
+            ),
+            (
+                "GPUT  -.3725290298E-08 -.106581410E-13  61440 2139",
+                "GPUT",
+                -0.3725290298E-08,
+                -0.106581410E-13,
+                61440,
+                2139,
+            ),
+            (
+                "BDUT  0.0000000000E+00-3.019806627E-14 444534  782",
+                "BDUT",
+                0.0,
+                -3.019806627E-14,
+                444534,
+                782,
+            ),
+        ] {
+            let corr = TimeSystemCorr::from_rinex3_header(content).unwrap();
+            assert_eq!(corr.code(), system);
+            assert_eq!(corr.a0, a0);
+            assert_eq!(corr.a1, a1);
+            assert_eq!(corr.t_ref, t_ref);
+            assert_eq!(corr.week, week);
+            // formatting is lossless
+            let formatted = corr.to_rinex3_header();
+            assert_eq!(
+                TimeSystemCorr::from_rinex3_header(&formatted).unwrap(),
+                corr
+            );
+        }
+        assert!(TimeSystemCorr::from_rinex3_header("GPUT  1.8626451492E-09").is_err());
+        assert!(TimeSystemCorr::from_rinex3_header(
+            "XXUT  1.8626451492E-09-8.881784197E-16 432000 2138"
+        )
+        .is_err());
+    }
+    #[test]
+    fn time_systems() {
+        let (gpst, gst) = (
+            TimeSystem::GNSS(Constellation::GPS),
+            TimeSystem::GNSS(Constellation::Galileo),
+        );
+        assert_eq!(TimeSystem::decode("GPUT"), Some((gpst, TimeSystem::UTC)));
+        assert_eq!(TimeSystem::decode("GPGA"), Some((gpst, gst)));
+        assert_eq!(TimeSystem::decode("GAGP"), Some((gst, gpst)));
+        assert_eq!(TimeSystem::decode("GP"), None);
+        assert_eq!(TimeSystem::decode("GPXX"), None);
+        let sto = StoMessage {
+            system: "GAGP".to_string(),
+            a: (1.0E-9, 1.0E-12, 0.0),
+            ..Default::default()
+        };
+        assert_eq!(sto.time_systems(), Some((gst, gpst)));
+        let t0 = Epoch::from_str("2022-06-08T00:00:00 GST").unwrap();
+        let offset = sto.offset(t0, t0 + 1000.0 * Unit::Second);
+        assert!((offset.to_seconds() - 2.0E-9).abs() < 1.0E-9);
+    }
+    #[test]
+    fn time_system_corr_evaluation() {
+        let corr = TimeSystemCorr::from_rinex3_header(
+            "GPUT -3.7252902985E-09-1.065814104E-14  61440 2139",
+        )
+        .unwrap();
+        let t_ref = Epoch::from_str("2021-01-03T17:04:00 GPST").unwrap();
+        assert_eq!(corr.reference(), t_ref);
+        // nanosecond resolution
+        let offset = corr.offset(t_ref).to_seconds();
+        assert!((offset - -3.7252902985E-09).abs() < 1.0E-9);
+        let offset = corr.offset(t_ref + 1.0E6 * Unit::Second).to_seconds();
+        assert!((offset - (-3.7252902985E-09 - 1.065814104E-08)).abs() < 1.0E-9);
+
+        let corr = TimeSystemCorr::from_rinex3_header(
+            "BDUT  0.0000000000E+00-3.019806627E-14 444534  782",
+        )
+        .unwrap();
+        assert_eq!(corr.reference().time_scale, TimeScale::BDT);
+    }
 }
@@ -0,0 +1,976 @@
+//! Constellation specific (typed) Ephemeris views.
+//! The generic [Ephemeris] stores the orbit fields described in the
+//! database, indexed by name. The structures defined here expose
+//! the same content with named and typed fields, per constellation.
+use super::{
+    health::{GalHealth, GeoHealth, GloHealth, Health},
+    orbits::GloStatus,
+    Ephemeris, NavMsgType, OrbitItem,
+};
+
+use bitflags::bitflags;
+use gnss::prelude::{Constellation, SV};
+use hifitime::{Duration, Epoch, Unit};
+
+#[cfg(feature = "nav")]
+use super::ephemeris::{Kepler, Perturbations};
+
+bitflags! {
+    /// Galileo Ephemeris data sources
+    #[derive(Debug, Default, Copy, Clone)]
+    #[derive(PartialEq, PartialOrd)]
+    #[cfg_attr(feature = "serde", derive(Serialize))]
+    pub struct GalDataSource: u32 {
+        /// I/NAV E1-B
+        const INAV_E1B = 0x01;
+        /// F/NAV E5a-I
+        const FNAV_E5A = 0x02;
+        /// I/NAV E5b-I
+        const INAV_E5B = 0x04;
+        /// Clock corrections apply to E5a/E1
+        const CLOCK_E5A_E1 = 0x100;
+        /// Clock corrections apply to E5b/E1
+        const CLOCK_E5B_E1 = 0x200;
+    }
+}
+
+impl GalDataSource {
+    /// Returns the [NavMsgType] these data sources describe:
+    /// either I/NAV or F/NAV.
+    pub fn msg_type(&self) -> Option<NavMsgType> {
+        if self.contains(Self::FNAV_E5A) {
+            Some(NavMsgType::FNAV)
+        } else if self.intersects(Self::INAV_E1B | Self::INAV_E5B) {
+            Some(NavMsgType::INAV)
+        } else {
+            None
+        }
+    }
+}
+
+/// GPS (and QZSS) Legacy Ephemeris
+#[derive(Default, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct GpsEphemeris {
+    /// Clock bias (s)
+    pub clock_bias: f64,
+    /// Clock drift (s.s⁻¹)
+    pub clock_drift: f64,
+    /// Clock drift rate (s.s⁻²)
+    pub clock_drift_rate: f64,
+    /// Issue of Data (Ephemeris)
+    pub iode: f64,
+    /// Amplitude of sine harmonic correction term of the orbit radius (m)
+    pub crs: f64,
+    /// Mean motion difference from computed value (rad.s⁻¹)
+    pub dn: f64,
+    /// Mean anomaly at reference time (rad)
+    pub m0: f64,
+    /// Amplitude of cosine harmonic correction term of the argument of latitude (rad)
+    pub cuc: f64,
+    /// Eccentricity
+    pub e: f64,
+    /// Amplitude of sine harmonic correction term of the argument of latitude (rad)
+    pub cus: f64,
+    /// Square root of the semi major axis (m^1/2)
+    pub sqrt_a: f64,
+    /// Time of Ephemeris (seconds of GPS week)
+    pub toe: f64,
+    /// Amplitude of cosine harmonic correction term of the inclination angle (rad)
+    pub cic: f64,
+    /// Longitude of ascending node at reference time (rad)
+    pub omega0: f64,
+    /// Amplitude of sine harmonic correction term of the inclination angle (rad)
+    pub cis: f64,
+    /// Inclination angle at reference time (rad)
+    pub i0: f64,
+    /// Amplitude of cosine harmonic correction term of the orbit radius (m)
+    pub crc: f64,
+    /// Argument of perigee (rad)
+    pub omega: f64,
+    /// Rate of right ascension (rad.s⁻¹)
+    pub omega_dot: f64,
+    /// Inclination rate of change (rad.s⁻¹)
+    pub idot: f64,
+    /// Codes on L2 channel
+    pub l2_codes: f64,
+    /// GPS week counter (continuous)
+    pub week: u32,
+    /// L2P data flag
+    pub l2p_data_flag: f64,
+    /// SV accuracy (m)
+    pub sv_accuracy: f64,
+    /// SV Health
+    pub health: Health,
+    /// Total group delay (s)
+    pub tgd: f64,
+    /// Issue of Data (Clock)
+    pub iodc: f64,
+    /// Transmission time of message (seconds of GPS week)
+    pub t_tm: f64,
+    /// Fit interval (hours)
+    pub fit_interval: f64,
+}
+
+/// Galileo (I/NAV or F/NAV) Ephemeris
+#[derive(Default, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct GalEphemeris {
+    /// Clock bias (s)
+    pub clock_bias: f64,
+    /// Clock drift (s.s⁻¹)
+    pub clock_drift: f64,
+    /// Clock drift rate (s.s⁻²)
+    pub clock_drift_rate: f64,
+    /// Issue of Data of the nav batch
+    pub iodnav: f64,
+    /// Amplitude of sine harmonic correction term of the orbit radius (m)
+    pub crs: f64,
+    /// Mean motion difference from computed value (rad.s⁻¹)
+    pub dn: f64,
+    /// Mean anomaly at reference time (rad)
+    pub m0: f64,
+    /// Amplitude of cosine harmonic correction term of the argument of latitude (rad)
+    pub cuc: f64,
+    /// Eccentricity
+    pub e: f64,
+    /// Amplitude of sine harmonic correction term of the argument of latitude (rad)
+    pub cus: f64,
+    /// Square root of the semi major axis (m^1/2)
+    pub sqrt_a: f64,
+    /// Time of Ephemeris (seconds of GAL week)
+    pub toe: f64,
+    /// Amplitude of cosine harmonic correction term of the inclination angle (rad)
+    pub cic: f64,
+    /// Longitude of ascending node at reference time (rad)
+    pub omega0: f64,
+    /// Amplitude of sine harmonic correction term of the inclination angle (rad)
+    pub cis: f64,
+    /// Inclination angle at reference time (rad)
+    pub i0: f64,
+    /// Amplitude of cosine harmonic correction term of the orbit radius (m)
+    pub crc: f64,
+    /// Argument of perigee (rad)
+    pub omega: f64,
+    /// Rate of right ascension (rad.s⁻¹)
+    pub omega_dot: f64,
+    /// Inclination rate of change (rad.s⁻¹)
+    pub idot: f64,
+    /// Data sources
+    pub data_source: GalDataSource,
+    /// GAL week counter, aligned to GPS week
+    pub week: u32,
+    /// Signal in space accuracy (m)
+    pub sisa: f64,
+    /// SV Health
+    pub health: GalHealth,
+    /// E5a/E1 Broadcast Group Delay (s)
+    pub bgd_e5a_e1: f64,
+    /// E5b/E1 Broadcast Group Delay (s)
+    pub bgd_e5b_e1: f64,
+    /// Transmission time of message (seconds of GAL week)
+    pub t_tm: f64,
+}
+
+/// BeiDou (D1/D2) Ephemeris
+#[derive(Default, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct BdsEphemeris {
+    /// Clock bias (s)
+    pub clock_bias: f64,
+    /// Clock drift (s.s⁻¹)
+    pub clock_drift: f64,
+    /// Clock drift rate (s.s⁻²)
+    pub clock_drift_rate: f64,
+    /// Age of Data (Ephemeris)
+    pub aode: f64,
+    /// Amplitude of sine harmonic correction term of the orbit radius (m)
+    pub crs: f64,
+    /// Mean motion difference from computed value (rad.s⁻¹)
+    pub dn: f64,
+    /// Mean anomaly at reference time (rad)
+    pub m0: f64,
+    /// Amplitude of cosine harmonic correction term of the argument of latitude (rad)
+    pub cuc: f64,
+    /// Eccentricity
+    pub e: f64,
+    /// Amplitude of sine harmonic correction term of the argument of latitude (rad)
+    pub cus: f64,
+    /// Square root of the semi major axis (m^1/2)
+    pub sqrt_a: f64,
+    /// Time of Ephemeris (seconds of BDT week)
+    pub toe: f64,
+    /// Amplitude of cosine harmonic correction term of the inclination angle (rad)
+    pub cic: f64,
+    /// Longitude of ascending node at reference time (rad)
+    pub omega0: f64,
+    /// Amplitude of sine harmonic correction term of the inclination angle (rad)
+    pub cis: f64,
+    /// Inclination angle at reference time (rad)
+    pub i0: f64,
+    /// Amplitude of cosine harmonic correction term of the orbit radius (m)
+    pub crc: f64,
+    /// Argument of perigee (rad)
+    pub omega: f64,
+    /// Rate of right ascension (rad.s⁻¹)
+    pub omega_dot: f64,
+    /// Inclination rate of change (rad.s⁻¹)
+    pub idot: f64,
+    /// BDT week counter
+    pub week: u32,
+    /// SV accuracy (m)
+    pub sv_accuracy: f64,
+    /// Autonomous satellite health flag (SatH1)
+    pub sat_h1: f64,
+    /// B1/B3 Total Group Delay (s)
+    pub tgd1_b1_b3: f64,
+    /// B2/B3 Total Group Delay (s)
+    pub tgd2_b2_b3: f64,
+    /// Transmission time of message (seconds of BDT week)
+    pub t_tm: f64,
+    /// Age of Data (Clock)
+    pub aodc: f64,
+}
+
+/// BeiDou satellites orbit class
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum BdsOrbitClass {
+    /// Geostationary Earth Orbit
+    GEO,
+    /// Inclined Geosynchronous Orbit
+    IGSO,
+    /// Medium Earth Orbit
+    MEO,
+}
+
+impl BdsOrbitClass {
+    /// Semi major axis threshold (m), between MEO and geosynchronous orbits
+    const GEOSYNC_MIN_A: f64 = 35.0E6;
+    /// Inclination threshold (rad), between GEO and IGSO orbits
+    const IGSO_MIN_I: f64 = 0.5;
+    /// Identifies the orbit class of given BeiDou vehicle, from its PRN.
+    /// Returns None if `sv` is not a BeiDou vehicle.
+    pub fn from_sv(sv: SV) -> Option<Self> {
+        if sv.constellation != Constellation::BeiDou {
+            return None;
+        }
+        match sv.prn {
+            1..=5 | 59..=62 => Some(Self::GEO),
+            6..=10 | 13 | 16 | 31 | 38..=40 | 56 => Some(Self::IGSO),
+            _ => Some(Self::MEO),
+        }
+    }
+}
+
+/// Glonass (FDMA) Ephemeris: state vector in PZ90 ECEF.
+/// Reference epoch (toc) of Glonass messages is expressed in UTC,
+/// while the system time is Moscow time, see [GloEphemeris::moscow_to_utc].
+#[derive(Default, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct GloEphemeris {
+    /// Clock bias (-TauN) (s), see [GloEphemeris::tau_n]
+    pub clock_bias: f64,
+    /// Relative frequency bias (+GammaN), see [GloEphemeris::gamma_n]
+    pub clock_drift: f64,
+    /// Message frame time (tk) in seconds of the UTC week,
+    /// see [GloEphemeris::frame_time]
+    pub message_frame_time: f64,
+    /// Position vector (km)
+    pub position: (f64, f64, f64),
+    /// Velocity vector (km.s⁻¹)
+    pub velocity: (f64, f64, f64),
+    /// Acceleration vector (km.s⁻²)
+    pub acceleration: (f64, f64, f64),
+    /// SV Health
+    pub health: GloHealth,
+    /// Frequency channel number
+    pub channel: i8,
+    /// Age of operation information (days)
+    pub age_op: f64,
+    /// Status flags, only in RINEX4
+    pub status: Option<GloStatus>,
+    /// L1/L2 group delay difference (s), only in RINEX4
+    pub l1_l2_group_delay: Option<f64>,
+    /// User range accuracy index, only in RINEX4
+    pub urai: Option<f64>,
+}
+
+/// Glonass-M time parameters (string 5), broadcast
+/// in System Time Offset messages.
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct GloTimeParameters {
+    /// Correction from Glonass system time to UTC(SU), TauC (s)
+    pub tau_c: Option<f64>,
+    /// Fractional part of the offset between Glonass system time and GPST, TauGPS (s)
+    pub tau_gps: Option<f64>,
+}
+
+impl GloTimeParameters {
+    /// Converts a datetime expressed in Glonass system time to UTC(SU),
+    /// applying TauC when known: t(UTC(SU)) = t(GLONASST) + TauC - 3h.
+    pub fn to_utc(&self, t: Epoch) -> Epoch {
+        GloEphemeris::moscow_to_utc(t) + Duration::from_seconds(self.tau_c.unwrap_or(0.0))
+    }
+}
+
+/// SBAS (GEO) Ephemeris: state vector in ECEF.
+#[derive(Default, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SbasEphemeris {
+    /// Clock bias (aGf0) (s)
+    pub clock_bias: f64,
+    /// Relative frequency bias (aGf1)
+    pub clock_drift: f64,
+    /// Transmission time of message (seconds of GPS week)
+    pub t_tm: f64,
+    /// Position vector (km)
+    pub position: (f64, f64, f64),
+    /// Velocity vector (km.s⁻¹)
+    pub velocity: (f64, f64, f64),
+    /// Acceleration vector (km.s⁻²)
+    pub acceleration: (f64, f64, f64),
+    /// SV Health
+    pub health: GeoHealth,
+    /// User range accuracy (m)
+    pub ura: f64,
+    /// Issue of Data Navigation
+    pub iodn: f64,
+}
+
+impl Ephemeris {
+    /*
+     * Zero valued fields are not stored when parsing,
+     * so a missing field is interpreted as null.
+     */
+    fn orbit_f64_or_zero(&self, field: &str) -> f64 {
+        self.get_orbit_f64(field).unwrap_or(0.0)
+    }
+    /*
+     * Returns first non empty field, from a list of possible keys
+     * (keys vary accross revisions)
+     */
+    fn orbit_f64_any(&self, fields: &[&str]) -> f64 {
+        fields
+            .iter()
+            .find_map(|field| self.get_orbit_f64(field))
+            .unwrap_or(0.0)
+    }
+    /*
+     * Position, Velocity and Acceleration vectors.
+     * Null coordinates are omitted when parsing (GEO vehicles
+     * usually have a null Z coordinate), so we only require
+     * one coordinate to be present.
+     */
+    pub(crate) fn state_vector(
+        &self,
+    ) -> Option<((f64, f64, f64), (f64, f64, f64), (f64, f64, f64))> {
+        let (x, y, z) = (
+            self.get_orbit_f64("satPosX"),
+            self.get_orbit_f64("satPosY"),
+            self.get_orbit_f64("satPosZ"),
+        );
+        if x.is_none() && y.is_none() && z.is_none() {
+            return None;
+        }
+        let position = (x.unwrap_or(0.0), y.unwrap_or(0.0), z.unwrap_or(0.0));
+        let velocity = (
+            self.orbit_f64_or_zero("velX"),
+            self.orbit_f64_or_zero("velY"),
+            self.orbit_f64_or_zero("velZ"),
+        );
+        let acceleration = (
+            self.orbit_f64_or_zero("accelX"),
+            self.orbit_f64_or_zero("accelY"),
+            self.orbit_f64_or_zero("accelZ"),
+        );
+        Some((position, velocity, acceleration))
+    }
+    /// Interprets Self as [GpsEphemeris]. Also applies to QZSS.
+    /// Returns None if Keplerian parameters are missing,
+    /// or if Self is a RINEX4 CNAV/CNV2 frame, which does not describe
+    /// the legacy parameters (no toe, ΔN0 and its rate instead of ΔN).
+    pub fn gps(&self) -> Option<GpsEphemeris> {
+        if self.orbits.contains_key("deltaN0") {
+            return None;
+        }
+        Some(GpsEphemeris {
+            clock_bias: self.clock_bias,
+            clock_drift: self.clock_drift,
+            clock_drift_rate: self.clock_drift_rate,
+            iode: self.orbit_f64_or_zero("iode"),
+            crs: self.orbit_f64_or_zero("crs"),
+            dn: self.orbit_f64_or_zero("deltaN"),
+            m0: self.orbit_f64_or_zero("m0"),
+            cuc: self.orbit_f64_or_zero("cuc"),
+            e: self.orbit_f64_or_zero("e"),
+            cus: self.orbit_f64_or_zero("cus"),
+            sqrt_a: self.get_orbit_f64("sqrta")?,
+            toe: self.orbit_f64_or_zero("toe"),
+            cic: self.orbit_f64_or_zero("cic"),
+            omega0: self.orbit_f64_or_zero("omega0"),
+            cis: self.orbit_f64_or_zero("cis"),
+            i0: self.orbit_f64_or_zero("i0"),
+            crc: self.orbit_f64_or_zero("crc"),
+            omega: self.orbit_f64_or_zero("omega"),
+            omega_dot: self.orbit_f64_or_zero("omegaDot"),
+            idot: self.orbit_f64_or_zero("idot"),
+            l2_codes: self.orbit_f64_or_zero("l2Codes"),
+            week: self.get_week()?,
+            l2p_data_flag: self.orbit_f64_or_zero("l2pDataFlag"),
+            sv_accuracy: self.orbit_f64_or_zero("svAccuracy"),
+            health: match self.orbits.get("health") {
+                Some(OrbitItem::Health(h)) => h.clone(),
+                Some(OrbitItem::F64(f)) => {
+                    num::FromPrimitive::from_u32(*f as u32).unwrap_or_default()
+                },
+                _ => num::FromPrimitive::from_u32(0).unwrap_or_default(),
+            },
+            tgd: self.orbit_f64_or_zero("tgd"),
+            iodc: self.orbit_f64_or_zero("iodc"),
+            t_tm: self.orbit_f64_or_zero("t_tm"),
+            fit_interval: self.orbit_f64_or_zero("fitInt"),
+        })
+    }
+    /// Returns Galileo [GalDataSource]s, which is empty for other constellations.
+    pub fn gal_data_source(&self) -> GalDataSource {
+        GalDataSource::from_bits_truncate(self.orbit_f64_or_zero("dataSrc") as u32)
+    }
+    /// Interprets Self as [GalEphemeris].
+    /// Returns None if Keplerian parameters are missing.
+    pub fn galileo(&self) -> Option<GalEphemeris> {
+        Some(GalEphemeris {
+            clock_bias: self.clock_bias,
+            clock_drift: self.clock_drift,
+            clock_drift_rate: self.clock_drift_rate,
+            iodnav: self.orbit_f64_or_zero("iodnav"),
+            crs: self.orbit_f64_or_zero("crs"),
+            dn: self.orbit_f64_or_zero("deltaN"),
+            m0: self.orbit_f64_or_zero("m0"),
+            cuc: self.orbit_f64_or_zero("cuc"),
+            e: self.orbit_f64_or_zero("e"),
+            cus: self.orbit_f64_or_zero("cus"),
+            sqrt_a: self.get_orbit_f64("sqrta")?,
+            toe: self.orbit_f64_or_zero("toe"),
+            cic: self.orbit_f64_or_zero("cic"),
+            omega0: self.orbit_f64_or_zero("omega0"),
+            cis: self.orbit_f64_or_zero("cis"),
+            i0: self.orbit_f64_or_zero("i0"),
+            crc: self.orbit_f64_or_zero("crc"),
+            omega: self.orbit_f64_or_zero("omega"),
+            omega_dot: self.orbit_f64_or_zero("omegaDot"),
+            idot: self.orbit_f64_or_zero("idot"),
+            data_source: self.gal_data_source(),
+            week: self.get_week()?,
+            sisa: self.orbit_f64_or_zero("sisa"),
+            health: self
+                .orbits
+                .get("health")
+                .and_then(|item| item.as_gal_health())
+                .unwrap_or_default(),
+            bgd_e5a_e1: self.orbit_f64_or_zero("bgdE5aE1"),
+            bgd_e5b_e1: self.orbit_f64_or_zero("bgdE5bE1"),
+            t_tm: self.orbit_f64_or_zero("t_tm"),
+        })
+    }
+    /// Interprets Self as [BdsEphemeris].
+    /// Returns None if Keplerian parameters are missing.
+    pub fn beidou(&self) -> Option<BdsEphemeris> {
+        Some(BdsEphemeris {
+            clock_bias: self.clock_bias,
+            clock_drift: self.clock_drift,
+            clock_drift_rate: self.clock_drift_rate,
+            aode: self.orbit_f64_or_zero("aode"),
+            crs: self.orbit_f64_or_zero("crs"),
+            dn: self.orbit_f64_or_zero("deltaN"),
+            m0: self.orbit_f64_or_zero("m0"),
+            cuc: self.orbit_f64_or_zero("cuc"),
+            e: self.orbit_f64_or_zero("e"),
+            cus: self.orbit_f64_or_zero("cus"),
+            sqrt_a: self.get_orbit_f64("sqrta")?,
+            toe: self.orbit_f64_or_zero("toe"),
+            cic: self.orbit_f64_or_zero("cic"),
+            omega0: self.orbit_f64_or_zero("omega0"),
+            cis: self.orbit_f64_or_zero("cis"),
+            i0: self.orbit_f64_or_zero("i0"),
+            crc: self.orbit_f64_or_zero("crc"),
+            omega: self.orbit_f64_or_zero("omega"),
+            omega_dot: self.orbit_f64_or_zero("omegaDot"),
+            idot: self.orbit_f64_or_zero("idot"),
+            week: self.get_week()?,
+            sv_accuracy: self.orbit_f64_or_zero("svAccuracy"),
+            sat_h1: self.orbit_f64_or_zero("satH1"),
+            tgd1_b1_b3: self.orbit_f64_any(&["tgd1b1b3", "tgdb1b3"]),
+            tgd2_b2_b3: self.orbit_f64_any(&["tgd2b2b3", "tgdb2b3"]),
+            t_tm: self.orbit_f64_or_zero("t_tm"),
+            aodc: self.orbit_f64_or_zero("aodc"),
+        })
+    }
+    /// Interprets Self as [GloEphemeris].
+    /// Returns None if the position vector is missing.
+    pub fn glonass(&self) -> Option<GloEphemeris> {
+        let (position, velocity, acceleration) = self.state_vector()?;
+        Some(GloEphemeris {
+            clock_bias: self.clock_bias,
+            clock_drift: self.clock_drift,
+            message_frame_time: self.clock_drift_rate,
+            position,
+            velocity,
+            acceleration,
+            health: self
+                .orbits
+                .get("health")
+                .and_then(|item| item.as_glo_health())
+                .unwrap_or(GloHealth::Healthy),
+            channel: self
+                .orbits
+                .get("channel")
+                .and_then(|item| item.as_i8())
+                .unwrap_or(0),
+            age_op: self.orbit_f64_or_zero("ageOp"),
+            status: match self.orbits.get("status") {
+                Some(OrbitItem::GloStatus(status)) => Some(status.clone()),
+                _ => None,
+            },
+            l1_l2_group_delay: self.get_orbit_f64("L1L2GrpDelay"),
+            urai: self.get_orbit_f64("urai"),
+        })
+    }
+    /// Interprets Self as [SbasEphemeris].
+    /// Returns None if the position vector is missing.
+    pub fn sbas(&self) -> Option<SbasEphemeris> {
+        let (position, velocity, acceleration) = self.state_vector()?;
+        Some(SbasEphemeris {
+            clock_bias: self.clock_bias,
+            clock_drift: self.clock_drift,
+            t_tm: self.clock_drift_rate,
+            position,
+            velocity,
+            acceleration,
+            health: self
+                .orbits
+                .get("health")
+                .and_then(|item| item.as_geo_health())
+                .unwrap_or_default(),
+            ura: self.orbit_f64_or_zero("accuracyCode"),
+            iodn: self.orbit_f64_or_zero("iodn"),
+        })
+    }
+}
+
+/*
+ * Keplerian constellations share the same orbit description
+ */
+macro_rules! keplerian_ephemeris {
+    ($t: ty) => {
+        #[cfg(feature = "nav")]
+        #[cfg_attr(docrs, doc(cfg(feature = "nav")))]
+        impl $t {
+            /// Returns Orbit [Kepler] parameters
+            pub fn kepler(&self) -> Kepler {
+                Kepler {
+                    a: self.sqrt_a.powf(2.0),
+                    e: self.e,
+                    i_0: self.i0,
+                    omega: self.omega,
+                    omega_0: self.omega0,
+                    m_0: self.m0,
+                    toe: self.toe,
+                }
+            }
+            /// Returns Orbit [Perturbations] parameters
+            pub fn perturbations(&self) -> Perturbations {
+                Perturbations {
+                    cuc: self.cuc,
+                    cus: self.cus,
+                    cic: self.cic,
+                    cis: self.cis,
+                    crc: self.crc,
+                    crs: self.crs,
+                    dn: self.dn,
+                    i_dot: self.idot,
+                    omega_dot: self.omega_dot,
+                }
+            }
+        }
+    };
+}
+
+impl SbasEphemeris {
+    /// Propagates the position vector, expressed in km ECEF,
+    /// `dt` seconds after the reference epoch (toc) of this message,
+    /// using the broadcast velocity and acceleration vectors.
+    pub fn position_at(&self, dt: f64) -> (f64, f64, f64) {
+        let (x, y, z) = self.position;
+        let (vx, vy, vz) = self.velocity;
+        let (ax, ay, az) = self.acceleration;
+        (
+            x + vx * dt + ax * dt.powi(2) / 2.0,
+            y + vy * dt + ay * dt.powi(2) / 2.0,
+            z + vz * dt + az * dt.powi(2) / 2.0,
+        )
+    }
+}
+
+impl GloEphemeris {
+    /// Glonass system time (Moscow time) is 3 hours ahead of UTC(SU)
+    pub const MOSCOW_UTC_OFFSET_HOURS: f64 = 3.0;
+    /* PZ90 constants, see Glonass ICD */
+    const GM_KM: f64 = 398600.4418;
+    const EARTH_RADIUS_KM: f64 = 6378.136;
+    const J2: f64 = 1082625.75E-9;
+    const EARTH_OMEGA: f64 = 7.292115E-5;
+    /* Runge Kutta integration step (s) */
+    const INTEGRATION_STEP: f64 = 30.0;
+
+    /// Returns the SV clock bias relative to Glonass system time, TauN (s).
+    /// RINEX stores -TauN.
+    pub fn tau_n(&self) -> f64 {
+        -self.clock_bias
+    }
+    /// Returns the relative frequency bias, GammaN.
+    pub fn gamma_n(&self) -> f64 {
+        self.clock_drift
+    }
+    /// Returns the SV clock offset (s), `dt` seconds after the reference epoch
+    /// (toc) of this message: -TauN + GammaN * dt.
+    pub fn clock_offset(&self, dt: f64) -> f64 {
+        -self.tau_n() + self.gamma_n() * dt
+    }
+    /// Converts a datetime expressed in Glonass system time (Moscow time) to UTC.
+    pub fn moscow_to_utc(t: Epoch) -> Epoch {
+        t - Self::MOSCOW_UTC_OFFSET_HOURS * Unit::Hour
+    }
+    /// Converts a UTC datetime to Glonass system time (Moscow time).
+    pub fn utc_to_moscow(t: Epoch) -> Epoch {
+        t + Self::MOSCOW_UTC_OFFSET_HOURS * Unit::Hour
+    }
+    /// Returns the broadcast reference time tb, in minutes of the Moscow day,
+    /// from the reference epoch (toc) of this message, which is expressed in UTC.
+    pub fn tb(toc: Epoch) -> u32 {
+        let (_, _, _, hh, mm, _, _) = Self::utc_to_moscow(toc).to_gregorian_utc();
+        hh as u32 * 60 + mm as u32
+    }
+    /// Returns the message frame time (tk) as an [Epoch], from the reference epoch (toc)
+    /// of this message. Old files only express tk in seconds of the UTC day.
+    pub fn frame_time(&self, toc: Epoch) -> Epoch {
+        let (y, m, d, _, _, _, _) = toc.to_gregorian_utc();
+        let midnight = Epoch::from_gregorian_utc_at_midnight(y, m, d);
+        let tk = Duration::from_seconds(self.message_frame_time);
+        if self.message_frame_time < 86400.0 {
+            let t = midnight + tk;
+            if t > toc + 12.0 * Unit::Hour {
+                t - 1.0 * Unit::Day // previous day
+            } else {
+                t
+            }
+        } else {
+            let days_since_sunday = (u8::from(toc.weekday_utc()) + 1) % 7;
+            midnight - (days_since_sunday as f64) * Unit::Day + tk
+        }
+    }
+    /*
+     * PZ90 equations of motion, see Glonass ICD A.3.1.2
+     */
+    fn derivatives(&self, state: [f64; 6]) -> [f64; 6] {
+        let [x, y, z, vx, vy, vz] = state;
+        let (ax, ay, az) = self.acceleration;
+        let r2 = x.powi(2) + y.powi(2) + z.powi(2);
+        let r = r2.sqrt();
+        let mu = Self::GM_KM / r.powi(3);
+        let c = 1.5 * Self::J2 * Self::GM_KM * Self::EARTH_RADIUS_KM.powi(2) / r.powi(5);
+        let z2 = 5.0 * z.powi(2) / r2;
+        let w2 = Self::EARTH_OMEGA.powi(2);
+        [
+            vx,
+            vy,
+            vz,
+            -mu * x - c * x * (1.0 - z2) + w2 * x + 2.0 * Self::EARTH_OMEGA * vy + ax,
+            -mu * y - c * y * (1.0 - z2) + w2 * y - 2.0 * Self::EARTH_OMEGA * vx + ay,
+            -mu * z - c * z * (3.0 - z2) + az,
+        ]
+    }
+    /// Propagates the state vector `dt` seconds after the reference epoch (toc)
+    /// of this message, by numerical integration (4th order Runge Kutta) of the
+    /// PZ90 equations of motion. Returns position (km) and velocity (km.s⁻¹) vectors,
+    /// in PZ90 ECEF. Glonass ICD recommends |dt| < 15 minutes.
+    pub fn state_at(&self, dt: f64) -> ((f64, f64, f64), (f64, f64, f64)) {
+        let (x, y, z) = self.position;
+        let (vx, vy, vz) = self.velocity;
+        let mut state = [x, y, z, vx, vy, vz];
+        let steps = (dt.abs() / Self::INTEGRATION_STEP).ceil().max(1.0);
+        let h = dt / steps;
+        let add = |state: [f64; 6], k: [f64; 6], gain: f64| {
+            let mut ret = state;
+            for i in 0..6 {
+                ret[i] += k[i] * gain;
+            }
+            ret
+        };
+        for _ in 0..steps as usize {
+            let k1 = self.derivatives(state);
+            let k2 = self.derivatives(add(state, k1, h / 2.0));
+            let k3 = self.derivatives(add(state, k2, h / 2.0));
+            let k4 = self.derivatives(add(state, k3, h));
+            for i in 0..6 {
+                state[i] += h / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]);
+            }
+        }
+        (
+            (state[0], state[1], state[2]),
+            (state[3], state[4], state[5]),
+        )
+    }
+    /// Propagates the position vector, expressed in km PZ90 ECEF,
+    /// `dt` seconds after the reference epoch (toc) of this message.
+    /// See [GloEphemeris::state_at].
+    pub fn position_at(&self, dt: f64) -> (f64, f64, f64) {
+        self.state_at(dt).0
+    }
+}
+
+impl BdsEphemeris {
+    /// Identifies the orbit class, from the orbital parameters.
+    /// Unlike [BdsOrbitClass::from_sv], this does not depend on
+    /// the PRN allocation.
+    pub fn orbit_class(&self) -> BdsOrbitClass {
+        if self.sqrt_a.powi(2) < BdsOrbitClass::GEOSYNC_MIN_A {
+            BdsOrbitClass::MEO
+        } else if self.i0.abs() < BdsOrbitClass::IGSO_MIN_I {
+            BdsOrbitClass::GEO
+        } else {
+            BdsOrbitClass::IGSO
+        }
+    }
+}
+
+keplerian_ephemeris!(GpsEphemeris);
+keplerian_ephemeris!(GalEphemeris);
+keplerian_ephemeris!(BdsEphemeris);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
+    use std::str::FromStr;
+    fn ephemeris(descriptor: Vec<(&str, OrbitItem)>) -> Ephemeris {
+        let mut orbits: HashMap<String, OrbitItem> = HashMap::new();
+        for (key, item) in descriptor {
+            orbits.insert(key.to_string(), item);
+        }
+        Ephemeris {
+            clock_bias: 1.0E-4,
+            clock_drift: 2.0E-12,
+            clock_drift_rate: 0.0,
+            orbits,
+        }
+    }
+    #[test]
+    fn typed_gps() {
+        let eph = ephemeris(vec![
+            ("iode", OrbitItem::F64(10.0)),
+            ("crs", OrbitItem::F64(-15.09375)),
+            ("sqrta", OrbitItem::F64(5153.65)),
+            ("toe", OrbitItem::F64(431984.0)),
+            ("week", OrbitItem::U32(2138)),
+            ("health", OrbitItem::Health(Health::L1L2Healthy)),
+        ]);
+        let gps = eph.gps().unwrap();
+        assert_eq!(gps.iode, 10.0);
+        assert_eq!(gps.crs, -15.09375);
+        assert_eq!(gps.sqrt_a, 5153.65);
+        assert_eq!(gps.toe, 431984.0);
+        assert_eq!(gps.week, 2138);
+        assert_eq!(gps.health, Health::L1L2Healthy);
+        assert_eq!(gps.cuc, 0.0, "omitted fields are null");
+        assert_eq!(gps.clock_bias, 1.0E-4);
+
+        let eph = ephemeris(vec![("crs", OrbitItem::F64(-15.09375))]);
+        assert!(eph.gps().is_none(), "keplerian parameters are missing");
+
+        let eph = ephemeris(vec![
+            ("sqrta", OrbitItem::F64(5153.65)),
+            ("deltaN0", OrbitItem::F64(4.5E-9)),
+            ("t_op", OrbitItem::F64(431984.0)),
+            ("wn_op", OrbitItem::F64(2138.0)),
+        ]);
+        assert!(eph.gps().is_none(), "CNAV frames are not legacy frames");
+    }
+    #[test]
+    fn typed_glonass() {
+        let eph = ephemeris(vec![
+            ("satPosX", OrbitItem::F64(-1.488799804690E3)),
+            ("satPosY", OrbitItem::F64(1.292880712890E4)),
+            ("satPosZ", OrbitItem::F64(2.193169775390E4)),
+            ("velY", OrbitItem::F64(-2.049269676210)),
+            ("channel", OrbitItem::I8(1)),
+        ]);
+        let glo = eph.glonass().unwrap();
+        assert_eq!(
+            glo.position,
+            (-1.488799804690E3, 1.292880712890E4, 2.193169775390E4)
+        );
+        assert_eq!(glo.velocity, (0.0, -2.049269676210, 0.0));
+        assert_eq!(glo.channel, 1);
+        assert_eq!(glo.health, GloHealth::Healthy);
+        assert!(glo.status.is_none());
+        assert!(eph.gps().is_none());
+        assert_eq!(glo.tau_n(), -1.0E-4);
+        assert_eq!(glo.gamma_n(), 2.0E-12);
+        assert_eq!(glo.clock_offset(10.0), 1.0E-4 + 2.0E-11);
+    }
+    #[test]
+    fn typed_sbas() {
+        let mut eph = ephemeris(vec![
+            ("satPosX", OrbitItem::F64(3.594460E4)),
+            ("satPosY", OrbitItem::F64(2.204414E4)),
+            ("velX", OrbitItem::F64(1.0E-3)),
+            ("velY", OrbitItem::F64(-2.0E-3)),
+            ("velZ", OrbitItem::F64(5.0E-4)),
+            ("accelX", OrbitItem::F64(1.0E-6)),
+            ("accelY", OrbitItem::F64(2.0E-6)),
+            ("accelZ", OrbitItem::F64(-4.0E-6)),
+        ]);
+        eph.clock_drift = 1.0E-9;
+        eph.clock_drift_rate = 3.4563E5;
+        let sbas = eph.sbas().unwrap();
+        assert_eq!(sbas.t_tm, 3.4563E5);
+        assert_eq!(sbas.velocity, (1.0E-3, -2.0E-3, 5.0E-4));
+        assert_eq!(sbas.acceleration, (1.0E-6, 2.0E-6, -4.0E-6));
+
+        // x + v.dt + a.dt²/2, dt = 60s
+        let expected = (35944.6618, 22044.0236, 0.0228);
+        let (x, y, z) = sbas.position_at(60.0);
+        assert!((x - expected.0).abs() < 1.0E-9);
+        assert!((y - expected.1).abs() < 1.0E-9);
+        assert!((z - expected.2).abs() < 1.0E-9);
+
+        let sv = SV::from_str("S23").unwrap();
+        let toc = Epoch::from_str("2020-06-25T00:00:16 GPST").unwrap();
+        let t = toc + Duration::from_seconds(60.0);
+        assert_eq!(eph.sv_position(sv, t), Some((3.594460E4, 2.204414E4, 0.0)));
+        assert_eq!(eph.sv_position_at(sv, toc, t), Some((x, y, z)));
+
+        // a0 + a1.dt + a2.dt²: transmission time is not a drift rate
+        let dt = Ephemeris::sv_clock_corr(sv, eph.sv_clock(), t, toc);
+        assert!((dt.to_seconds() - (1.0E-4 + 6.0E-8)).abs() < 1.0E-9);
+    }
+    #[test]
+    fn glonass_time() {
+        let toc = Epoch::from_gregorian_utc(2022, 6, 8, 9, 45, 0, 0);
+        assert_eq!(GloEphemeris::tb(toc), 12 * 60 + 45);
+        let moscow = GloEphemeris::utc_to_moscow(toc);
+        assert_eq!(moscow, Epoch::from_gregorian_utc(2022, 6, 8, 12, 45, 0, 0));
+        assert_eq!(GloEphemeris::moscow_to_utc(moscow), toc);
+
+        // seconds of UTC week (wednesday)
+        let glo = GloEphemeris {
+            message_frame_time: 2.934E5,
+            ..Default::default()
+        };
+        assert_eq!(
+            glo.frame_time(toc),
+            Epoch::from_gregorian_utc(2022, 6, 8, 9, 30, 0, 0)
+        );
+        // seconds of UTC day
+        let toc = Epoch::from_gregorian_utc(2020, 12, 31, 23, 45, 0, 0);
+        let glo = GloEphemeris {
+            message_frame_time: 7.38E4,
+            ..Default::default()
+        };
+        assert_eq!(
+            glo.frame_time(toc),
+            Epoch::from_gregorian_utc(2020, 12, 31, 20, 30, 0, 0)
+        );
+        let toc = Epoch::from_gregorian_utc(2021, 1, 1, 0, 15, 0, 0);
+        let glo = GloEphemeris {
+            message_frame_time: 8.55E4,
+            ..Default::default()
+        };
+        assert_eq!(
+            glo.frame_time(toc),
+            Epoch::from_gregorian_utc(2020, 12, 31, 23, 45, 0, 0)
+        );
+
+        let params = GloTimeParameters {
+            tau_c: Some(1.0E-3),
+            tau_gps: None,
+        };
+        assert_eq!(
+            params.to_utc(moscow),
+            Epoch::from_gregorian_utc(2022, 6, 8, 9, 45, 0, 1_000_000)
+        );
+    }
+    #[test]
+    fn glonass_propagation() {
+        // R05 2023 03 12 00:15:00 (BRD400DLR)
+        let glo = GloEphemeris {
+            position: (-1.498655615234E4, 1.971978173828E4, -6.031792968750E3),
+            velocity: (5.806941986084E-1, -6.196203231812E-1, -3.461117744446),
+            acceleration: (9.313225746155E-10, -9.313225746155E-10, 0.0),
+            ..Default::default()
+        };
+        assert_eq!(glo.position_at(0.0), glo.position);
+        // R05 2023 03 12 00:45:00 (BRD400DLR)
+        let (x, y, z) = glo.position_at(1800.0);
+        assert!((x + 1.370943896484E4).abs() < 1.0E-2, "x error {} km", x);
+        assert!((y - 1.786140917969E4).abs() < 1.0E-2, "y error {} km", y);
+        assert!((z + 1.194746289062E4).abs() < 1.0E-2, "z error {} km", z);
+        // back and forth
+        let (position, velocity) = glo.state_at(1800.0);
+        let backward = GloEphemeris {
+            position,
+            velocity,
+            acceleration: glo.acceleration,
+            ..Default::default()
+        };
+        let (x, y, z) = backward.position_at(-1800.0);
+        assert!((x - glo.position.0).abs() < 1.0E-6);
+        assert!((y - glo.position.1).abs() < 1.0E-6);
+        assert!((z - glo.position.2).abs() < 1.0E-6);
+    }
+    #[test]
+    fn gal_data_source() {
+        for (bits, msg) in [
+            (258, Some(NavMsgType::FNAV)),
+            (513, Some(NavMsgType::INAV)),
+            (517, Some(NavMsgType::INAV)),
+            (516, Some(NavMsgType::INAV)),
+            (0, None),
+        ] {
+            let eph = ephemeris(vec![("dataSrc", OrbitItem::F64(bits as f64))]);
+            assert_eq!(eph.gal_data_source().bits(), bits);
+            assert_eq!(eph.gal_data_source().msg_type(), msg);
+        }
+    }
+    #[test]
+    fn bds_orbit_class() {
+        for (sqrt_a, i0, class) in [
+            (6.493469865800E+03, 6.070467367360E-02, BdsOrbitClass::GEO),
+            (6.493357843400E+03, 9.652266349580E-01, BdsOrbitClass::IGSO),
+            (5.282614929200E+03, 9.629439765550E-01, BdsOrbitClass::MEO),
+        ] {
+            let eph = ephemeris(vec![
+                ("sqrta", OrbitItem::F64(sqrt_a)),
+                ("i0", OrbitItem::F64(i0)),
+                ("week", OrbitItem::U32(782)),
+            ]);
+            assert_eq!(eph.beidou().unwrap().orbit_class(), class);
+        }
+        for (prn, class) in [
+            (1, Some(BdsOrbitClass::GEO)),
+            (5, Some(BdsOrbitClass::GEO)),
+            (60, Some(BdsOrbitClass::GEO)),
+            (8, Some(BdsOrbitClass::IGSO)),
+            (38, Some(BdsOrbitClass::IGSO)),
+            (11, Some(BdsOrbitClass::MEO)),
+            (45, Some(BdsOrbitClass::MEO)),
+        ] {
+            let sv = SV::new(Constellation::BeiDou, prn);
+            assert_eq!(BdsOrbitClass::from_sv(sv), class);
+        }
+        let sv = SV::new(Constellation::GPS, 1);
+        assert!(BdsOrbitClass::from_sv(sv).is_none());
+    }
+}
@@ -0,0 +1,162 @@
+//! Satellite visibility prediction, from broadcast ephemeris
+use super::Ephemeris;
+use crate::prelude::{Constellation, Duration, Epoch, GroundPosition, Rinex, SV};
+use std::collections::{BTreeMap, HashMap};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Satellite pass: time frame during which a satellite
+/// remains above the elevation mask
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Pass {
+    /// Rising epoch (mask crossing), or start of the prediction window
+    pub rise: Epoch,
+    /// Setting epoch (mask crossing), or end of the prediction window
+    pub set: Epoch,
+    /// Epoch of highest elevation
+    pub culmination: Epoch,
+    /// Highest elevation, in degrees
+    pub max_elevation: f64,
+}
+
+impl Pass {
+    /// Duration of this pass
+    pub fn duration(&self) -> Duration {
+        self.set - self.rise
+    }
+    /// Returns true if this satellite is in sight at `t`
+    pub fn contains(&self, t: Epoch) -> bool {
+        t >= self.rise && t <= self.set
+    }
+}
+
+/*
+ * Epoch at which the elevation crosses the mask, linearly interpolated
+ * between two consecutive samples
+ */
+fn crossing(t0: Epoch, el0: f64, t1: Epoch, el1: f64, mask: f64) -> Epoch {
+    if el1 == el0 {
+        return t1;
+    }
+    let dt = (t1 - t0).to_seconds() * (mask - el0) / (el1 - el0);
+    t0 + Duration::from_seconds(dt)
+}
+
+/// Predicts the passes of every satellite described by the Navigation RINEX,
+/// seen from given ground position, above given elevation mask (in degrees),
+/// within [start, end]. Elevations are sampled every `step`, mask crossings
+/// are then interpolated. Keplerian ephemerides are propagated from their closest
+/// publication, so the window may extend past the Navigation RINEX (with slowly
+/// decreasing accuracy), while state vectors (Glonass, SBAS) are only used
+/// within their validity period. No pass is predicted when `step` is not positive.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::navigation::visibility::predict;
+/// use std::str::FromStr;
+/// let nav = Rinex::from_file("../test_resources/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+///     .unwrap();
+/// let position = GroundPosition::from_ecef_wgs84((3582105.291, 532589.7313, 5232754.8054));
+/// let start = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+/// let end = Epoch::from_str("2020-06-25T12:00:00 GPST").unwrap();
+/// let passes = predict(&nav, position, start, end, Duration::from_seconds(60.0), 10.0);
+/// assert!(!passes.is_empty());
+/// for passes in passes.values() {
+///     for pass in passes {
+///         assert!(pass.rise < pass.set);
+///         assert!(pass.max_elevation >= 10.0);
+///     }
+/// }
+/// ```
+pub fn predict(
+    nav: &Rinex,
+    position: GroundPosition,
+    start: Epoch,
+    end: Epoch,
+    step: Duration,
+    mask: f64,
+) -> BTreeMap<SV, Vec<Pass>> {
+    let mut ret = BTreeMap::<SV, Vec<Pass>>::new();
+    if step <= Duration::ZERO {
+        return ret;
+    }
+    /* sort ephemerides once, per SV */
+    let mut ephemerides = HashMap::<SV, Vec<(Epoch, &Ephemeris)>>::new();
+    for (toc, (_, sv, eph)) in nav.ephemeris() {
+        ephemerides.entry(sv).or_default().push((*toc, eph));
+    }
+    for ephemerides in ephemerides.values_mut() {
+        ephemerides.sort_by_key(|(toc, _)| *toc);
+    }
+
+    let rx = position.to_ecef_wgs84();
+
+    for (sv, ephemerides) in ephemerides {
+        let propagated = sv.constellation == Constellation::Glonass || sv.constellation.is_sbas();
+        let max_dtoe = Ephemeris::max_dtoe(sv.constellation);
+        /* elevation at t, using the closest ephemeris */
+        let elevation = |t: Epoch| -> Option<f64> {
+            let index = ephemerides.partition_point(|(toc, _)| *toc <= t);
+            let (toc, eph) = [index.checked_sub(1), Some(index)]
+                .iter()
+                .flatten()
+                .filter_map(|i| ephemerides.get(*i))
+                .min_by_key(|(toc, _)| (t - *toc).abs())?;
+            if propagated && (t - *toc).abs() > max_dtoe? {
+                return None;
+            }
+            let (x, y, z) = eph.sv_position_at(sv, *toc, t)?;
+            let (el, _) = Ephemeris::elevation_azimuth((x * 1.0E3, y * 1.0E3, z * 1.0E3), rx);
+            Some(el)
+        };
+
+        let mut passes = Vec::<Pass>::new();
+        let mut current: Option<Pass> = None;
+        let mut previous: Option<(Epoch, f64)> = None;
+        let mut t = start;
+        while t <= end {
+            let el = elevation(t);
+            let visible = el.filter(|el| *el >= mask);
+            match (visible, current.as_mut()) {
+                (Some(el), Some(pass)) if el > pass.max_elevation => {
+                    pass.max_elevation = el;
+                    pass.culmination = t;
+                },
+                (Some(_), Some(_)) => {},
+                (Some(el), None) => {
+                    let rise = match previous {
+                        Some((t0, el0)) => crossing(t0, el0, t, el, mask),
+                        None => t,
+                    };
+                    current = Some(Pass {
+                        rise,
+                        set: t,
+                        culmination: t,
+                        max_elevation: el,
+                    });
+                },
+                (None, Some(_)) => {
+                    let mut pass = current.take().unwrap();
+                    pass.set = match (previous, el) {
+                        (Some((t0, el0)), Some(el)) => crossing(t0, el0, t, el, mask),
+                        (Some((t0, _)), None) => t0,
+                        _ => t,
+                    };
+                    passes.push(pass);
+                },
+                (None, None) => {},
+            }
+            previous = el.map(|el| (t, el));
+            t += step;
+        }
+        if let Some(mut pass) = current {
+            pass.set = previous.map(|(t, _)| t).unwrap_or(end);
+            passes.push(pass);
+        }
+        if !passes.is_empty() {
+            ret.insert(sv, passes);
+        }
+    }
+    ret
+}
@@ -1,4 +1,5 @@
-use crate::{carrier, Carrier, Constellation};
+use crate::{carrier, version::Version, Carrier, Constellation};
+use std::str::FromStr;
 use thiserror::Error;
 
 #[derive(Error, Debug, Clone, PartialEq)]
@@ -72,6 +73,16 @@ impl Observable {
     pub fn is_channel_number(&self) -> bool {
         matches!(self, Self::ChannelNumber(_))
     }
+    pub fn is_meteo_observable(&self) -> bool {
+        !matches!(
+            self,
+            Self::Phase(_)
+                | Self::Doppler(_)
+                | Self::SSI(_)
+                | Self::PseudoRange(_)
+                | Self::ChannelNumber(_)
+        )
+    }
     pub fn code(&self) -> Option<String> {
         match self {
             Self::Phase(c) | Self::Doppler(c) | Self::SSI(c) | Self::PseudoRange(c) => {
@@ -84,6 +95,53 @@ impl Observable {
             _ => None,
         }
     }
+    /// Compares observables in canonical order: Pseudo Range first,
+    /// then Phase, Doppler and signal strength, each by ascending frequency band
+    /// then tracking code. Channel numbers and other observables come last.
+    /// ```
+    /// use rinex::prelude::Observable;
+    /// use std::cmp::Ordering;
+    /// use std::str::FromStr;
+    /// let c5q = Observable::from_str("C5Q").unwrap();
+    /// let l1c = Observable::from_str("L1C").unwrap();
+    /// let c1w = Observable::from_str("C1W").unwrap();
+    /// let c1c = Observable::from_str("C1C").unwrap();
+    /// assert_eq!(c5q.canonical_cmp(&l1c), Ordering::Less);
+    /// assert_eq!(c1w.canonical_cmp(&c5q), Ordering::Less);
+    /// assert_eq!(c1w.canonical_cmp(&c1c), Ordering::Greater);
+    /// ```
+    pub fn canonical_cmp(&self, rhs: &Self) -> std::cmp::Ordering {
+        self.canonical_key().cmp(&rhs.canonical_key())
+    }
+    /*
+     * (kind, band, code) sorting key
+     */
+    fn canonical_key(&self) -> (u8, Option<char>, String) {
+        let kind = match self {
+            Self::PseudoRange(_) => 0,
+            Self::Phase(_) => 1,
+            Self::Doppler(_) => 2,
+            Self::SSI(_) => 3,
+            Self::ChannelNumber(_) => 4,
+            _ => 5,
+        };
+        let code = self.to_string();
+        let band = code.chars().find(|c| c.is_ascii_digit());
+        (kind, band, code)
+    }
+    /*
+     * Heap memory held by self, in bytes
+     */
+    pub(crate) fn heap_usage(&self) -> usize {
+        match self {
+            Self::Phase(c)
+            | Self::Doppler(c)
+            | Self::SSI(c)
+            | Self::PseudoRange(c)
+            | Self::ChannelNumber(c) => c.capacity(),
+            _ => 0,
+        }
+    }
     pub fn carrier(&self, c: Constellation) -> Result<Carrier, carrier::Error> {
         Carrier::from_observable(c, self)
     }
@@ -273,6 +331,84 @@ impl Observable {
     }
 }
 
+/// Observation codes that the RINEX standards define, per [Constellation].
+/// Use them to validate or convert Observation RINEX, or to describe
+/// all observations in the header of files built programmatically.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::version::Version;
+/// use std::str::FromStr;
+/// let c1w = Observable::from_str("C1W").unwrap();
+/// let p1 = Observable::from_str("P1").unwrap();
+/// // RINEX3 and 4 describe observations with 3 characters
+/// let codes = Constellation::GPS.known_codes(Version::new(3, 5));
+/// assert!(codes.contains(&c1w));
+/// assert!(!codes.contains(&p1));
+/// // RINEX2 with 2 characters
+/// assert!(Constellation::GPS.is_known_code(&p1, Version::new(2, 11)));
+/// assert!(!Constellation::Galileo.is_known_code(&p1, Version::new(2, 11)));
+/// ```
+pub trait ObservationCodes {
+    /// Returns the observation codes defined for this constellation
+    /// in given RINEX revision, in canonical order (see [Observable::canonical_cmp]).
+    /// RINEX2 only describes GPS, Glonass, Galileo and SBAS, other constellations
+    /// have no codes in that revision.
+    fn known_codes(&self, version: Version) -> Vec<Observable>;
+    /// Returns true if given observable is defined for this constellation
+    /// in given RINEX revision
+    fn is_known_code(&self, observable: &Observable, version: Version) -> bool {
+        self.known_codes(version).contains(observable)
+    }
+}
+
+impl ObservationCodes for Constellation {
+    fn known_codes(&self, version: Version) -> Vec<Observable> {
+        let codes: Vec<String> = if version.major < 3 {
+            let (bands, p_code) = match self {
+                Self::GPS => ("125", true),
+                Self::Glonass => ("12", true),
+                Self::Galileo => ("15678", false),
+                c if c.is_sbas() => ("15", false),
+                _ => ("", false),
+            };
+            let mut codes = Vec::new();
+            for band in bands.chars() {
+                for kind in ['C', 'L', 'D', 'S'] {
+                    codes.push(format!("{}{}", kind, band));
+                }
+                if p_code && band != '5' {
+                    codes.push(format!("P{}", band));
+                }
+            }
+            codes
+        } else {
+            Carrier::constellation_codes(*self)
+                .into_iter()
+                .filter(|code| code.len() == 3)
+                .map(|code| {
+                    // BeiDou B1I was described on band 1 up to RINEX 3.01
+                    if *self == Self::BeiDou
+                        && version.major == 3
+                        && version.minor < 2
+                        && code.get(1..2) == Some("2")
+                    {
+                        format!("{}1{}", &code[..1], &code[2..])
+                    } else {
+                        code.to_string()
+                    }
+                })
+                .collect()
+        };
+        let mut observables: Vec<Observable> = codes
+            .iter()
+            .filter_map(|code| Observable::from_str(code).ok())
+            .collect();
+        observables.sort_by(|a, b| a.canonical_cmp(b));
+        observables.dedup();
+        observables
+    }
+}
+
 impl std::fmt::Display for Observable {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -444,4 +580,50 @@ mod test {
             "C7X"
         );
     }
+    #[test]
+    fn known_codes() {
+        let v2 = Version::new(2, 11);
+        let v3 = Version::new(3, 5);
+        for constellation in [
+            Constellation::GPS,
+            Constellation::Glonass,
+            Constellation::Galileo,
+            Constellation::BeiDou,
+            Constellation::QZSS,
+            Constellation::IRNSS,
+            Constellation::SBAS,
+        ] {
+            let codes = constellation.known_codes(v3);
+            assert!(!codes.is_empty(), "no codes for {}", constellation);
+            for code in &codes {
+                assert_eq!(code.to_string().len(), 3);
+                assert!(
+                    code.carrier(constellation).is_ok(),
+                    "{} {} has no carrier",
+                    constellation,
+                    code
+                );
+            }
+            let mut sorted = codes.clone();
+            sorted.sort_by(|a, b| a.canonical_cmp(b));
+            sorted.dedup();
+            assert_eq!(sorted, codes);
+        }
+
+        let gps = Constellation::GPS.known_codes(v2);
+        assert_eq!(gps.len(), 14);
+        assert!(gps.iter().all(|code| code.to_string().len() == 2));
+        assert_eq!(Constellation::Glonass.known_codes(v2).len(), 10);
+        assert_eq!(Constellation::Galileo.known_codes(v2).len(), 20);
+        assert!(Constellation::BeiDou.known_codes(v2).is_empty());
+
+        // BeiDou B1I
+        let c2i = Observable::from_str("C2I").unwrap();
+        let c1i = Observable::from_str("C1I").unwrap();
+        assert!(Constellation::BeiDou.is_known_code(&c2i, v3));
+        assert!(!Constellation::BeiDou.is_known_code(&c1i, v3));
+        let v301 = Version::new(3, 1);
+        assert!(Constellation::BeiDou.is_known_code(&c1i, v301));
+        assert!(!Constellation::BeiDou.is_known_code(&c2i, v301));
+    }
 }
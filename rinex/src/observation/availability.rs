@@ -0,0 +1,300 @@
+//! Observation availability matrix
+use super::Record;
+use crate::prelude::{Epoch, Observable, SV};
+use std::collections::BTreeMap;
+
+/// [`EpochBitmap`] is a compact presence indicator, one bit per epoch
+/// of an [`AvailabilityMatrix`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EpochBitmap {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl EpochBitmap {
+    /// Builds an empty bitmap, covering `len` epochs
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; (len + 63) / 64],
+            len,
+        }
+    }
+    /// Number of epochs covered by this bitmap
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns true if this bitmap covers no epoch at all
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Marks epoch at given index as present
+    pub fn set(&mut self, index: usize) {
+        if index < self.len {
+            self.words[index / 64] |= 1 << (index % 64);
+        }
+    }
+    /// Returns true if epoch at given index is present
+    pub fn get(&self, index: usize) -> bool {
+        index < self.len && (self.words[index / 64] >> (index % 64)) & 1 == 1
+    }
+    /// Number of epochs present
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+    /// Intersection: epochs present in both bitmaps
+    pub fn and(&self, rhs: &Self) -> Self {
+        Self {
+            words: self
+                .words
+                .iter()
+                .zip(rhs.words.iter())
+                .map(|(lhs, rhs)| lhs & rhs)
+                .collect(),
+            len: self.len.min(rhs.len),
+        }
+    }
+    /// Union: epochs present in either bitmap
+    pub fn or(&self, rhs: &Self) -> Self {
+        let (long, short) = if self.len >= rhs.len {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        let mut ret = long.clone();
+        for (lhs, rhs) in ret.words.iter_mut().zip(short.words.iter()) {
+            *lhs |= rhs;
+        }
+        ret
+    }
+    /// Iterates the indexes of the epochs present
+    pub fn indexes(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len).filter(|i| self.get(*i))
+    }
+    /// Returns the runs of consecutive epochs present,
+    /// as inclusive (first, last) index intervals
+    pub fn runs(&self) -> Vec<(usize, usize)> {
+        let mut ret = Vec::new();
+        let mut start = Option::<usize>::None;
+        for i in 0..self.len {
+            match (self.get(i), start) {
+                (true, None) => start = Some(i),
+                (false, Some(first)) => {
+                    ret.push((first, i - 1));
+                    start = None;
+                },
+                _ => {},
+            }
+        }
+        if let Some(first) = start {
+            ret.push((first, self.len - 1));
+        }
+        ret
+    }
+}
+
+/// [`AvailabilityMatrix`] describes which signals were actually observed, per satellite,
+/// at every epoch of an Observation RINEX. This is what the record contains,
+/// not what the header announces. Presence is stored as an [`EpochBitmap`]
+/// per satellite and [`Observable`], indexed against [`AvailabilityMatrix::epochs`],
+/// which makes gap analysis and common epochs searches cheap.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AvailabilityMatrix {
+    epochs: Vec<Epoch>,
+    signals: BTreeMap<SV, BTreeMap<Observable, EpochBitmap>>,
+}
+
+impl AvailabilityMatrix {
+    /// Builds the availability matrix of an Observation [`Record`]
+    pub(crate) fn from_record(record: &Record) -> Self {
+        let mut epochs: Vec<Epoch> = record.keys().map(|(t, _)| *t).collect();
+        epochs.dedup(); // record is sorted
+        let mut signals: BTreeMap<SV, BTreeMap<Observable, EpochBitmap>> = BTreeMap::new();
+        let mut index = 0;
+        for ((t, _), (_, vehicles)) in record {
+            if epochs[index] != *t {
+                index += 1;
+            }
+            for (sv, observations) in vehicles {
+                for observable in observations.keys() {
+                    signals
+                        .entry(*sv)
+                        .or_default()
+                        .entry(observable.clone())
+                        .or_insert_with(|| EpochBitmap::new(epochs.len()))
+                        .set(index);
+                }
+            }
+        }
+        Self { epochs, signals }
+    }
+    /// Returns all epochs of the record, sorted. Bitmaps are indexed against this list.
+    pub fn epochs(&self) -> &[Epoch] {
+        &self.epochs
+    }
+    /// Returns the index of given epoch in [`Self::epochs`]
+    pub fn epoch_index(&self, epoch: Epoch) -> Option<usize> {
+        self.epochs.binary_search(&epoch).ok()
+    }
+    /// Returns all satellites observed at least once
+    pub fn sv(&self) -> impl Iterator<Item = SV> + '_ {
+        self.signals.keys().copied()
+    }
+    /// Returns all observables reported for given satellite
+    pub fn observables(&self, sv: SV) -> Vec<&Observable> {
+        self.signals
+            .get(&sv)
+            .map(|observables| observables.keys().collect())
+            .unwrap_or_default()
+    }
+    /// Iterates the bitmaps of every satellite and signal
+    pub fn iter(&self) -> impl Iterator<Item = (SV, &Observable, &EpochBitmap)> + '_ {
+        self.signals.iter().flat_map(|(sv, observables)| {
+            observables
+                .iter()
+                .map(move |(observable, bitmap)| (*sv, observable, bitmap))
+        })
+    }
+    /// Returns the presence bitmap of given signal
+    pub fn bitmap(&self, sv: SV, observable: &Observable) -> Option<&EpochBitmap> {
+        self.signals.get(&sv)?.get(observable)
+    }
+    /// Returns the presence bitmap of given satellite, whatever the signal
+    pub fn sv_bitmap(&self, sv: SV) -> Option<EpochBitmap> {
+        self.signals
+            .get(&sv)?
+            .values()
+            .fold(None, |acc: Option<EpochBitmap>, bitmap| match acc {
+                Some(acc) => Some(acc.or(bitmap)),
+                None => Some(bitmap.clone()),
+            })
+    }
+    /// Returns true if given signal was observed at given epoch
+    pub fn is_available(&self, sv: SV, observable: &Observable, epoch: Epoch) -> bool {
+        match (self.bitmap(sv, observable), self.epoch_index(epoch)) {
+            (Some(bitmap), Some(index)) => bitmap.get(index),
+            _ => false,
+        }
+    }
+    /// Returns the epochs where given satellite was observed (any signal)
+    pub fn sv_epochs(&self, sv: SV) -> Vec<Epoch> {
+        match self.sv_bitmap(sv) {
+            Some(bitmap) => bitmap.indexes().map(|i| self.epochs[i]).collect(),
+            None => Vec::new(),
+        }
+    }
+    /// Returns the (first, last) epochs of every continuous run of given signal.
+    /// Continuity is expressed in the record: any epoch where the signal is missing
+    /// interrupts the run.
+    pub fn intervals(&self, sv: SV, observable: &Observable) -> Vec<(Epoch, Epoch)> {
+        match self.bitmap(sv, observable) {
+            Some(bitmap) => bitmap
+                .runs()
+                .into_iter()
+                .map(|(first, last)| (self.epochs[first], self.epochs[last]))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+    /// Returns the epochs where all given signals were observed simultaneously
+    pub fn common_epochs(&self, signals: &[(SV, &Observable)]) -> Vec<Epoch> {
+        let mut common = Option::<EpochBitmap>::None;
+        for (sv, observable) in signals {
+            let bitmap = match self.bitmap(*sv, observable) {
+                Some(bitmap) => bitmap,
+                None => return Vec::new(),
+            };
+            common = match common {
+                Some(common) => Some(common.and(bitmap)),
+                None => Some(bitmap.clone()),
+            };
+        }
+        match common {
+            Some(bitmap) => bitmap.indexes().map(|i| self.epochs[i]).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Observation availability trait
+pub trait Availability {
+    /// Returns the [`AvailabilityMatrix`]: which signals were actually
+    /// observed, per satellite and epoch.
+    fn availability(&self) -> AvailabilityMatrix;
+}
+
+impl Availability for Record {
+    fn availability(&self) -> AvailabilityMatrix {
+        AvailabilityMatrix::from_record(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::observation::ObservationData;
+    use crate::prelude::EpochFlag;
+    use std::collections::HashMap;
+    use std::str::FromStr;
+    #[test]
+    fn epoch_bitmap() {
+        let mut bitmap = EpochBitmap::new(130);
+        assert_eq!(bitmap.len(), 130);
+        for i in [0, 1, 2, 63, 64, 65, 129, 130] {
+            bitmap.set(i);
+        }
+        assert!(bitmap.get(63) && bitmap.get(64) && bitmap.get(129));
+        assert!(!bitmap.get(3) && !bitmap.get(130));
+        assert_eq!(bitmap.count(), 7);
+        assert_eq!(bitmap.runs(), vec![(0, 2), (63, 65), (129, 129)]);
+
+        let mut rhs = EpochBitmap::new(130);
+        rhs.set(2);
+        rhs.set(3);
+        rhs.set(64);
+        assert_eq!(bitmap.and(&rhs).indexes().collect::<Vec<_>>(), vec![2, 64]);
+        assert_eq!(bitmap.or(&rhs).count(), 8);
+    }
+    #[test]
+    fn availability_matrix() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+        let c1c = Observable::from_str("C1C").unwrap();
+        let l1c = Observable::from_str("L1C").unwrap();
+        let t0 = Epoch::from_str("2023-01-01T00:00:00 GPST").unwrap();
+        let dt = hifitime::Duration::from_seconds(30.0);
+        let mut record = Record::new();
+        for i in 0..10 {
+            let mut vehicles = BTreeMap::new();
+            let mut observations = HashMap::new();
+            observations.insert(c1c.clone(), ObservationData::new(1.0, None, None));
+            if i != 4 {
+                observations.insert(l1c.clone(), ObservationData::new(1.0, None, None));
+            }
+            vehicles.insert(g01, observations);
+            if i >= 5 {
+                let mut observations = HashMap::new();
+                observations.insert(c1c.clone(), ObservationData::new(1.0, None, None));
+                vehicles.insert(g02, observations);
+            }
+            record.insert((t0 + dt * i as f64, EpochFlag::Ok), (None, vehicles));
+        }
+        let matrix = record.availability();
+        assert_eq!(matrix.epochs().len(), 10);
+        assert_eq!(matrix.sv().collect::<Vec<_>>(), vec![g01, g02]);
+        assert_eq!(matrix.observables(g01), vec![&l1c, &c1c]);
+        assert!(matrix.is_available(g01, &c1c, t0 + dt * 4.0));
+        assert!(!matrix.is_available(g01, &l1c, t0 + dt * 4.0));
+        assert!(!matrix.is_available(g02, &c1c, t0));
+        assert_eq!(
+            matrix.intervals(g01, &l1c),
+            vec![(t0, t0 + dt * 3.0), (t0 + dt * 5.0, t0 + dt * 9.0)]
+        );
+        assert_eq!(matrix.sv_epochs(g01).len(), 10);
+        assert_eq!(matrix.sv_epochs(g02).len(), 5);
+        let common = matrix.common_epochs(&[(g01, &l1c), (g02, &c1c)]);
+        assert_eq!(common.len(), 5);
+        assert_eq!(common[0], t0 + dt * 5.0);
+        assert!(matrix.common_epochs(&[(g02, &l1c)]).is_empty());
+        assert_eq!(matrix.iter().count(), 3);
+    }
+}
@@ -0,0 +1,222 @@
+//! Compact Observation storage
+use super::{LliFlags, ObservationData, Record, SNR};
+use crate::prelude::{Epoch, EpochFlag, Observable, SV};
+use std::collections::{BTreeMap, HashMap};
+
+/*
+ * SNR codes, indexed by their RINEX digit
+ */
+const SNR_CODES: [SNR; 10] = [
+    SNR::DbHz0,
+    SNR::DbHz12,
+    SNR::DbHz12_17,
+    SNR::DbHz18_23,
+    SNR::DbHz24_29,
+    SNR::DbHz30_35,
+    SNR::DbHz36_41,
+    SNR::DbHz42_47,
+    SNR::DbHz48_53,
+    SNR::DbHz54,
+];
+
+/// [`CompactObservation`] is a single observation stored in 16 bytes:
+/// vehicle and observable are indices into the [`CompactRecord`] tables,
+/// LLI and SSI flags are packed into a single byte each, [`CompactObservation::NONE`]
+/// marking a missing flag.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CompactObservation {
+    /// physical measurement
+    pub value: f64,
+    /// Index of the vehicle, see [`CompactRecord::satellites`]
+    pub sv: u16,
+    /// Index of the observable, see [`CompactRecord::observables`]
+    pub observable: u16,
+    /// Lock loss indicator bits
+    pub lli: u8,
+    /// Signal strength RINEX digit
+    pub snr: u8,
+}
+
+impl CompactObservation {
+    /// Missing flag sentinel
+    pub const NONE: u8 = 0xFF;
+    /// Returns Lock Loss indicator, if any
+    pub fn lli(&self) -> Option<LliFlags> {
+        if self.lli == Self::NONE {
+            None
+        } else {
+            LliFlags::from_bits(self.lli)
+        }
+    }
+    /// Returns signal strength indicator, if any
+    pub fn snr(&self) -> Option<SNR> {
+        SNR_CODES.get(self.snr as usize).copied()
+    }
+    /// Expands self as [`ObservationData`]
+    pub fn data(&self) -> ObservationData {
+        ObservationData::new(self.value, self.lli(), self.snr())
+    }
+}
+
+/*
+ * Epoch descriptor: observations of this epoch
+ * end at index `end` (exclusive) in the observation table
+ */
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct CompactEpoch {
+    pub(crate) epoch: Epoch,
+    pub(crate) flag: EpochFlag,
+    /// NaN when clock offset is missing
+    pub(crate) clock_offset: f64,
+    pub(crate) end: u32,
+}
+
+/// [`CompactRecord`] is a read-only, flat representation of an Observation [`Record`].
+/// Vehicles and observables are stored once, observations are contiguous
+/// and free of `Option` overhead. This divides the memory footprint of
+/// a typical record by about 4.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::observation::CompactRecord;
+/// let rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+///     .unwrap();
+/// let record = rinex.record.as_obs().unwrap();
+/// let compact = CompactRecord::from(record);
+/// assert_eq!(compact.len(), record.len());
+/// assert!(compact.memory_usage() < rinex.memory_usage());
+/// assert_eq!(&compact.to_record(), record);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CompactRecord {
+    pub(crate) satellites: Vec<SV>,
+    pub(crate) observables: Vec<Observable>,
+    pub(crate) epochs: Vec<CompactEpoch>,
+    pub(crate) observations: Vec<CompactObservation>,
+}
+
+impl From<&Record> for CompactRecord {
+    fn from(record: &Record) -> Self {
+        let mut satellites: Vec<SV> = Vec::new();
+        let mut observables: Vec<Observable> = Vec::new();
+        for (_, vehicles) in record.values() {
+            for (sv, observations) in vehicles {
+                if !satellites.contains(sv) {
+                    satellites.push(*sv);
+                }
+                for observable in observations.keys() {
+                    if !observables.contains(observable) {
+                        observables.push(observable.clone());
+                    }
+                }
+            }
+        }
+        satellites.sort();
+        observables.sort();
+
+        let mut epochs = Vec::with_capacity(record.len());
+        let mut observations = Vec::new();
+        for ((epoch, flag), (clock_offset, vehicles)) in record {
+            for (sv, data) in vehicles {
+                let sv = satellites.binary_search(sv).unwrap() as u16;
+                // sorted, for reproducible layouts
+                let data: BTreeMap<&Observable, &ObservationData> = data.iter().collect();
+                for (observable, data) in data {
+                    observations.push(CompactObservation {
+                        value: data.obs,
+                        sv,
+                        observable: observables.binary_search(observable).unwrap() as u16,
+                        lli: data
+                            .lli
+                            .map(|lli| lli.bits())
+                            .unwrap_or(CompactObservation::NONE),
+                        snr: data
+                            .snr
+                            .and_then(|snr| SNR_CODES.iter().position(|code| *code == snr))
+                            .map(|code| code as u8)
+                            .unwrap_or(CompactObservation::NONE),
+                    });
+                }
+            }
+            epochs.push(CompactEpoch {
+                epoch: *epoch,
+                flag: *flag,
+                clock_offset: clock_offset.unwrap_or(f64::NAN),
+                end: observations.len() as u32,
+            });
+        }
+        observations.shrink_to_fit();
+        Self {
+            satellites,
+            observables,
+            epochs,
+            observations,
+        }
+    }
+}
+
+impl CompactRecord {
+    /// Number of epochs
+    pub fn len(&self) -> usize {
+        self.epochs.len()
+    }
+    /// Returns true if this record contains no epoch
+    pub fn is_empty(&self) -> bool {
+        self.epochs.is_empty()
+    }
+    /// Vehicles table, sorted
+    pub fn satellites(&self) -> &[SV] {
+        &self.satellites
+    }
+    /// Observables table, sorted
+    pub fn observables(&self) -> &[Observable] {
+        &self.observables
+    }
+    /// Iterates over all epochs, with their clock offset and observations
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = ((Epoch, EpochFlag), Option<f64>, &[CompactObservation])> + '_ {
+        let mut start = 0;
+        self.epochs.iter().map(move |epoch| {
+            let end = epoch.end as usize;
+            let observations = &self.observations[start..end];
+            start = end;
+            let clock_offset = if epoch.clock_offset.is_nan() {
+                None
+            } else {
+                Some(epoch.clock_offset)
+            };
+            ((epoch.epoch, epoch.flag), clock_offset, observations)
+        })
+    }
+    /// Expands self back to a [`Record`]
+    pub fn to_record(&self) -> Record {
+        let mut record = Record::new();
+        for (key, clock_offset, observations) in self.iter() {
+            let mut vehicles: BTreeMap<SV, HashMap<Observable, ObservationData>> = BTreeMap::new();
+            for observation in observations {
+                vehicles
+                    .entry(self.satellites[observation.sv as usize])
+                    .or_default()
+                    .insert(
+                        self.observables[observation.observable as usize].clone(),
+                        observation.data(),
+                    );
+            }
+            record.insert(key, (clock_offset, vehicles));
+        }
+        record
+    }
+    /// Memory footprint of self, in bytes
+    pub fn memory_usage(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.satellites.capacity() * std::mem::size_of::<SV>()
+            + self.observables.capacity() * std::mem::size_of::<Observable>()
+            + self
+                .observables
+                .iter()
+                .map(|observable| observable.heap_usage())
+                .sum::<usize>()
+            + self.epochs.capacity() * std::mem::size_of::<CompactEpoch>()
+            + self.observations.capacity() * std::mem::size_of::<CompactObservation>()
+    }
+}
@@ -0,0 +1,105 @@
+//! Grouping adaptors, over flat observation iterators
+use super::ObservationData;
+use crate::prelude::{Epoch, Observable, SV};
+use std::collections::BTreeMap;
+
+/// [`ObservationGrouping`] is implemented by all iterators over flat
+/// observations, like [`crate::Rinex::observations`], filtered or not.
+/// It groups them per satellite or per observable, in chronological order.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::observation::ObservationGrouping;
+///
+/// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+///     .unwrap();
+///
+/// // phase observations, per satellite
+/// let phase = rnx.observations()
+///     .filter(|(_, _, observable, _)| observable.is_phase_observable())
+///     .group_by_sv();
+/// for (sv, observations) in phase {
+///     for (epoch, observable, data) in observations {
+///         assert!(observable.is_phase_observable());
+///     }
+/// }
+///
+/// // mean signal strength, per observable
+/// for (observable, observations) in rnx.observations().group_by_observable() {
+///     if observable.is_ssi_observable() {
+///         let mean = observations.iter()
+///             .map(|(_, _, data)| data.obs)
+///             .sum::<f64>() / observations.len() as f64;
+///         assert!(mean > 20.0);
+///     }
+/// }
+/// ```
+pub trait ObservationGrouping<'a>:
+    Iterator<Item = (Epoch, SV, &'a Observable, &'a ObservationData)> + Sized
+{
+    /// Groups observations per satellite
+    fn group_by_sv(self) -> BTreeMap<SV, Vec<(Epoch, &'a Observable, &'a ObservationData)>> {
+        let mut ret: BTreeMap<SV, Vec<(Epoch, &'a Observable, &'a ObservationData)>> =
+            BTreeMap::new();
+        for (epoch, sv, observable, data) in self {
+            ret.entry(sv).or_default().push((epoch, observable, data));
+        }
+        ret
+    }
+    /// Groups observations per observable
+    fn group_by_observable(
+        self,
+    ) -> BTreeMap<&'a Observable, Vec<(Epoch, SV, &'a ObservationData)>> {
+        let mut ret: BTreeMap<&'a Observable, Vec<(Epoch, SV, &'a ObservationData)>> =
+            BTreeMap::new();
+        for (epoch, sv, observable, data) in self {
+            ret.entry(observable).or_default().push((epoch, sv, data));
+        }
+        ret
+    }
+}
+
+impl<'a, I> ObservationGrouping<'a> for I where
+    I: Iterator<Item = (Epoch, SV, &'a Observable, &'a ObservationData)>
+{
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+    #[test]
+    fn grouping() {
+        let t0 = Epoch::from_str("2023-01-01T00:00:00 GPST").unwrap();
+        let t1 = Epoch::from_str("2023-01-01T00:00:30 GPST").unwrap();
+        let g01 = SV::from_str("G01").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+        let c1c = Observable::from_str("C1C").unwrap();
+        let l1c = Observable::from_str("L1C").unwrap();
+        let data = ObservationData::new(1.0, None, None);
+
+        let flat = vec![
+            (t0, g01, &c1c, &data),
+            (t0, e05, &l1c, &data),
+            (t1, g01, &l1c, &data),
+            (t1, g01, &c1c, &data),
+        ];
+
+        let per_sv = flat.clone().into_iter().group_by_sv();
+        assert_eq!(per_sv.keys().collect::<Vec<_>>(), vec![&g01, &e05]);
+        assert_eq!(
+            per_sv[&g01],
+            vec![(t0, &c1c, &data), (t1, &l1c, &data), (t1, &c1c, &data)]
+        );
+        assert_eq!(per_sv[&e05], vec![(t0, &l1c, &data)]);
+
+        let per_observable = flat.into_iter().group_by_observable();
+        assert_eq!(
+            per_observable[&c1c],
+            vec![(t0, g01, &data), (t1, g01, &data)]
+        );
+        assert_eq!(
+            per_observable[&l1c],
+            vec![(t0, e05, &data), (t1, g01, &data)]
+        );
+    }
+}
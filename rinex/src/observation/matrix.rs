@@ -0,0 +1,145 @@
+//! Vectorized export of observations, to [ndarray] matrices
+use super::Record;
+use crate::prelude::{Epoch, Observable, SV};
+use ndarray::Array2;
+
+/// [`ObservationMatrix`] is the (epochs × satellites) matrix
+/// of a given observable, returned by [`ToNdArray::to_ndarray`].
+/// Missing observations are NaN.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ObservationMatrix {
+    /// Epochs, sorted: row axis
+    pub epochs: Vec<Epoch>,
+    /// Satellites that observed this signal at least once, sorted: column axis
+    pub satellites: Vec<SV>,
+    /// Observations, with NaN for missing data
+    pub data: Array2<f64>,
+}
+
+impl ObservationMatrix {
+    /// Returns the index of given epoch, along the row axis
+    pub fn epoch_index(&self, epoch: Epoch) -> Option<usize> {
+        self.epochs.binary_search(&epoch).ok()
+    }
+    /// Returns the index of given satellite, along the column axis
+    pub fn sv_index(&self, sv: SV) -> Option<usize> {
+        self.satellites.binary_search(&sv).ok()
+    }
+    /// Returns observation of given satellite at given epoch,
+    /// None if missing
+    pub fn get(&self, epoch: Epoch, sv: SV) -> Option<f64> {
+        let value = self.data[(self.epoch_index(epoch)?, self.sv_index(sv)?)];
+        if value.is_nan() {
+            None
+        } else {
+            Some(value)
+        }
+    }
+}
+
+/// Vectorized export of observations, to perform linear algebra
+/// (like epoch-wise least squares) directly on the data.
+pub trait ToNdArray {
+    /// Returns the (epochs × satellites) [`ObservationMatrix`] of given observable.
+    /// Rows are all epochs of the record, including those where
+    /// this signal was not observed at all.
+    fn to_ndarray(&self, observable: &Observable) -> ObservationMatrix;
+}
+
+impl ToNdArray for Record {
+    fn to_ndarray(&self, observable: &Observable) -> ObservationMatrix {
+        let mut epochs: Vec<Epoch> = self.keys().map(|(t, _)| *t).collect();
+        epochs.dedup(); // record is sorted
+
+        let mut satellites: Vec<SV> = Vec::new();
+        for (_, vehicles) in self.values() {
+            for (sv, observations) in vehicles {
+                if observations.contains_key(observable) && !satellites.contains(sv) {
+                    satellites.push(*sv);
+                }
+            }
+        }
+        satellites.sort();
+
+        let mut data = Array2::from_elem((epochs.len(), satellites.len()), f64::NAN);
+        let mut row = 0;
+        for ((t, _), (_, vehicles)) in self {
+            if epochs[row] != *t {
+                row += 1;
+            }
+            for (sv, observations) in vehicles {
+                if let Some(observation) = observations.get(observable) {
+                    if let Ok(column) = satellites.binary_search(sv) {
+                        data[(row, column)] = observation.obs;
+                    }
+                }
+            }
+        }
+        ObservationMatrix {
+            epochs,
+            satellites,
+            data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::observation::ObservationData;
+    use crate::prelude::EpochFlag;
+    use std::collections::{BTreeMap, HashMap};
+    use std::str::FromStr;
+    #[test]
+    fn observation_matrix() {
+        let t0 = Epoch::from_str("2023-01-01T00:00:00 GPST").unwrap();
+        let t1 = Epoch::from_str("2023-01-01T00:00:30 GPST").unwrap();
+        let t2 = Epoch::from_str("2023-01-01T00:01:00 GPST").unwrap();
+        let g01 = SV::from_str("G01").unwrap();
+        let g05 = SV::from_str("G05").unwrap();
+        let e03 = SV::from_str("E03").unwrap();
+        let c1c = Observable::from_str("C1C").unwrap();
+        let l1c = Observable::from_str("L1C").unwrap();
+
+        let mut record = Record::new();
+        for (t, content) in [
+            (t0, vec![(g05, 5.0), (g01, 1.0)]),
+            (t1, vec![]),
+            (t2, vec![(g01, 2.0)]),
+        ] {
+            let mut vehicles = BTreeMap::new();
+            for (sv, value) in content {
+                let mut observations = HashMap::new();
+                observations.insert(c1c.clone(), ObservationData::new(value, None, None));
+                vehicles.insert(sv, observations);
+            }
+            // E03 only has phase
+            let mut observations = HashMap::new();
+            observations.insert(l1c.clone(), ObservationData::new(3.0, None, None));
+            vehicles.insert(e03, observations);
+            record.insert((t, EpochFlag::Ok), (None, vehicles));
+        }
+
+        let matrix = record.to_ndarray(&c1c);
+        assert_eq!(matrix.epochs, vec![t0, t1, t2]);
+        assert_eq!(matrix.satellites, vec![g01, g05]);
+        assert_eq!(matrix.data.dim(), (3, 2));
+        assert_eq!(matrix.data[(0, 0)], 1.0);
+        assert_eq!(matrix.data[(0, 1)], 5.0);
+        assert!(matrix.data.row(1).iter().all(|v| v.is_nan()));
+        assert_eq!(matrix.get(t2, g01), Some(2.0));
+        assert_eq!(matrix.get(t2, g05), None);
+        assert_eq!(matrix.get(t2, e03), None);
+
+        // column wise processing
+        let g01_sum: f64 = matrix.data.column(0).iter().filter(|v| !v.is_nan()).sum();
+        assert_eq!(g01_sum, 3.0);
+
+        let matrix = record.to_ndarray(&l1c);
+        assert_eq!(matrix.satellites, vec![e03]);
+        assert!(matrix.data.iter().all(|v| *v == 3.0));
+
+        let matrix = record.to_ndarray(&Observable::from_str("D1C").unwrap());
+        assert_eq!(matrix.data.dim(), (3, 0));
+    }
+}
@@ -1,5 +1,5 @@
 //! Observation RINEX module
-use super::{epoch, prelude::*, version::Version};
+use super::{epoch, formatter::Precision, prelude::*, version::Version};
 use std::collections::HashMap;
 
 pub mod record;
@@ -7,6 +7,41 @@ pub mod record;
 mod snr;
 pub use snr::SNR;
 
+mod snr_elevation;
+pub use snr_elevation::{SnrElevationModel, SnrTemplate};
+
+mod noise;
+pub use noise::{NoiseEstimate, ObservationNoise};
+
+mod residuals;
+pub use residuals::PseudoRangeResidual;
+#[cfg(all(feature = "obs", feature = "nav"))]
+pub(crate) use residuals::{median, tropo_delay};
+
+mod stacking;
+pub use stacking::{Repeatability, SiderealStack, StackedSignal, SIDEREAL_DAY_SECONDS};
+
+mod unit;
+pub use unit::{DbHz, ObservationUnit};
+
+mod availability;
+pub use availability::{Availability, AvailabilityMatrix, EpochBitmap};
+
+mod grouping;
+pub use grouping::ObservationGrouping;
+
+mod profile;
+pub use profile::{MappingProfile, MissingDataRules};
+
+mod compact;
+pub(crate) use compact::CompactEpoch;
+pub use compact::{CompactObservation, CompactRecord};
+
+#[cfg(feature = "ndarray")]
+mod matrix;
+#[cfg(feature = "ndarray")]
+pub use matrix::{ObservationMatrix, ToNdArray};
+
 #[cfg(docrs)]
 use crate::Bibliography;
 
@@ -88,11 +123,12 @@ impl std::fmt::Display for Crinex {
             value = "",
             width = 19
         )?;
-        write!(f, "{:<width$}", self.prog, width = 20)?;
+        let prog: String = self.prog.chars().take(20).collect();
+        write!(f, "{:<width$}", prog, width = 20)?;
         write!(f, "{:20}", "")?;
         let (y, m, d, hh, mm, _, _) = self.date.to_gregorian_utc();
         let m = fmt_month!(m);
-        let date = format!("{:02}-{}-{} {:02}:{:02}", d, m, y - 2000, hh, mm);
+        let date = format!("{:02}-{}-{:02} {:02}:{:02}", d, m, y % 100, hh, mm);
         write!(f, "{:<width$}", date, width = 20)?;
         f.write_str("CRINEX PROG / DATE")
     }
@@ -111,10 +147,19 @@ pub struct HeaderFields {
     pub time_of_last_obs: Option<Epoch>,
     /// Observables per constellation basis
     pub codes: HashMap<Constellation, Vec<Observable>>,
-    /// True if local clock drift is compensated for
+    /// True if receiver clock offsets were applied (RCV CLOCK OFFS APPL):
+    /// epochs, pseudo ranges and phases are then corrected
+    /// with the reported receiver clock offset
     pub clock_offset_applied: bool,
     /// Optionnal data scalings
     pub scalings: HashMap<(Constellation, Observable), u16>,
+    /// Resolution of the observations, used when formatting
+    /// and (de)compressing. Not described in the header section,
+    /// unless [Self::precision_comment] is set.
+    pub precision: Precision,
+    /// Describes a non standard [Precision] with a header COMMENT,
+    /// which is a private extension of this library. Set when parsing such a file.
+    pub precision_comment: bool,
 }
 
 impl HeaderFields {
@@ -130,6 +175,26 @@ impl HeaderFields {
         s.time_of_last_obs = Some(epoch);
         s
     }
+    /// Copies and declares whether receiver clock offsets
+    /// were applied to the observations (RCV CLOCK OFFS APPL)
+    pub fn with_clock_offset_applied(&self, applied: bool) -> Self {
+        let mut s = self.clone();
+        s.clock_offset_applied = applied;
+        s
+    }
+    /// Copies and sets the observations [Precision] policy
+    pub fn with_precision(&self, precision: Precision) -> Self {
+        let mut s = self.clone();
+        s.precision = precision;
+        s
+    }
+    /// Copies and declares whether a non standard [Precision]
+    /// should be described by a header COMMENT
+    pub fn with_precision_comment(&self, comment: bool) -> Self {
+        let mut s = self.clone();
+        s.precision_comment = comment;
+        s
+    }
     /// Insert a data scaling
     pub(crate) fn insert_scaling(
         &mut self,
@@ -141,6 +206,7 @@ impl HeaderFields {
     }
     /// Returns given scaling to apply for given GNSS system
     /// and given observation. Returns 1.0 by default, so it always applies
+    #[cfg(feature = "obs")]
     pub(crate) fn scaling(&self, c: Constellation, observable: Observable) -> Option<&u16> {
         self.scalings.get(&(c, observable))
     }
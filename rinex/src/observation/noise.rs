@@ -0,0 +1,254 @@
+//! Measurement noise estimation, from third order time differences
+#[cfg(feature = "obs")]
+use super::{LliFlags, Record};
+use crate::prelude::{Constellation, Epoch, Observable, SV};
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/*
+ * Variance amplification of the third order difference of a white noise:
+ * (1 + 3² + 3² + 1) σ²
+ */
+#[cfg(feature = "obs")]
+const THIRD_DIFF_VARIANCE_GAIN: f64 = 20.0;
+
+/*
+ * Median Absolute Deviation to standard deviation scaling,
+ * for a normal distribution
+ */
+const MAD_TO_SIGMA: f64 = 1.4826;
+
+/// Measurement noise estimate of a given signal
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct NoiseEstimate {
+    /// Number of third differences this estimate was obtained from
+    pub samples: usize,
+    /// Standard deviation of a single measurement,
+    /// in the observable unit: meters for Pseudo Range, cycles for Phase.
+    pub sigma: f64,
+}
+
+impl NoiseEstimate {
+    /*
+     * Robust estimate from scaled third differences,
+     * which are zero mean
+     */
+    fn from_samples(samples: &[f64]) -> Option<Self> {
+        let mut abs: Vec<f64> = samples
+            .iter()
+            .filter(|d| d.is_finite())
+            .map(|d| d.abs())
+            .collect();
+        if abs.is_empty() {
+            return None;
+        }
+        abs.sort_by(|a, b| a.total_cmp(b));
+        let n = abs.len();
+        let median = match n % 2 {
+            0 => (abs[n / 2 - 1] + abs[n / 2]) / 2.0,
+            _ => abs[n / 2],
+        };
+        Some(Self {
+            samples: n,
+            sigma: MAD_TO_SIGMA * median,
+        })
+    }
+}
+
+/// [`ObservationNoise`] estimates the Pseudo Range and Phase measurement noise,
+/// by forming third order time differences of consecutive observations,
+/// per satellite and signal. This removes the satellite to receiver
+/// dynamics (range, range rate and acceleration), and what remains is
+/// dominated by the measurement noise.
+/// Only evenly spaced observations contribute: data gaps,
+/// abnormal epochs and phase lock losses restart the differentiation.
+/// Estimates are robust (Median Absolute Deviation) so remaining cycle slips
+/// and outliers do not contaminate them.
+/// Residual dynamics grow with the sampling interval: with 30s sampling,
+/// Phase estimates are usually dominated by residual dynamics rather than noise,
+/// high rate (1s) data should be preferred.
+#[derive(Debug, Default, Clone)]
+pub struct ObservationNoise {
+    /// Scaled third differences: measurement noise time series,
+    /// per satellite and signal
+    pub series: HashMap<(SV, Observable), Vec<(Epoch, f64)>>,
+}
+
+impl ObservationNoise {
+    /// Builds the noise time series from an Observation [`Record`]
+    #[cfg(feature = "obs")]
+    pub(crate) fn from_record(record: &Record) -> Self {
+        let mut ret = Self::default();
+        let mut buffers: HashMap<(SV, Observable), Vec<(Epoch, f64)>> = HashMap::new();
+        for ((t, flag), (_, vehicles)) in record {
+            if !flag.is_ok() {
+                buffers.clear();
+                continue;
+            }
+            for (sv, observations) in vehicles {
+                for (observable, data) in observations {
+                    let phase = observable.is_phase_observable();
+                    if !phase && !observable.is_pseudorange_observable() {
+                        continue;
+                    }
+                    let buffer = buffers.entry((*sv, observable.clone())).or_default();
+                    if phase {
+                        if let Some(lli) = data.lli {
+                            if lli.intersects(LliFlags::LOCK_LOSS) {
+                                buffer.clear();
+                            }
+                        }
+                    }
+                    buffer.push((*t, data.obs));
+                    if buffer.len() > 4 {
+                        buffer.remove(0);
+                    }
+                    if buffer.len() < 4 {
+                        continue;
+                    }
+                    let dt = buffer[1].0 - buffer[0].0;
+                    if buffer[2].0 - buffer[1].0 != dt || buffer[3].0 - buffer[2].0 != dt {
+                        continue; // data gap
+                    }
+                    let d3 = buffer[3].1 - 3.0 * buffer[2].1 + 3.0 * buffer[1].1 - buffer[0].1;
+                    ret.series
+                        .entry((*sv, observable.clone()))
+                        .or_default()
+                        .push((*t, d3 / THIRD_DIFF_VARIANCE_GAIN.sqrt()));
+                }
+            }
+        }
+        ret
+    }
+    /// Returns the list of signals we have noise samples for, sorted
+    pub fn signals(&self) -> Vec<(Constellation, Observable)> {
+        let mut ret: Vec<(Constellation, Observable)> = Vec::new();
+        for (sv, observable) in self.series.keys() {
+            if !ret.contains(&(sv.constellation, observable.clone())) {
+                ret.push((sv.constellation, observable.clone()));
+            }
+        }
+        ret.sort();
+        ret
+    }
+    /// Returns the measurement noise estimate of given signal,
+    /// all satellites of said constellation contributing.
+    pub fn estimate(
+        &self,
+        constellation: Constellation,
+        observable: &Observable,
+    ) -> Option<NoiseEstimate> {
+        let samples: Vec<f64> = self
+            .series
+            .iter()
+            .filter(|((sv, obs), _)| sv.constellation == constellation && obs == observable)
+            .flat_map(|(_, series)| series.iter().map(|(_, noise)| *noise))
+            .collect();
+        NoiseEstimate::from_samples(&samples)
+    }
+    /// Returns the measurement noise estimate of given signal, for given satellite.
+    pub fn sv_estimate(&self, sv: SV, observable: &Observable) -> Option<NoiseEstimate> {
+        let series = self.series.get(&(sv, observable.clone()))?;
+        let samples: Vec<f64> = series.iter().map(|(_, noise)| *noise).collect();
+        NoiseEstimate::from_samples(&samples)
+    }
+    /// Returns the measurement noise estimate of every signal, sorted by signal
+    pub fn estimates(&self) -> Vec<(Constellation, Observable, NoiseEstimate)> {
+        self.signals()
+            .into_iter()
+            .filter_map(|(constellation, observable)| {
+                let estimate = self.estimate(constellation, &observable)?;
+                Some((constellation, observable, estimate))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::observation::ObservationData;
+    use crate::prelude::EpochFlag;
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    #[test]
+    fn third_difference_noise() {
+        let g01 = SV::from_str("G01").unwrap();
+        let c1c = Observable::from_str("C1C").unwrap();
+        let l1c = Observable::from_str("L1C").unwrap();
+        let s1c = Observable::from_str("S1C").unwrap();
+        let t0 = Epoch::from_str("2023-01-01T00:00:00 GPST").unwrap();
+        let dt = hifitime::Duration::from_seconds(30.0);
+
+        // deterministic pseudo random noise
+        let mut seed = 12345_u64;
+        let mut noise = move |sigma: f64| {
+            // sum of uniforms ~ gaussian
+            let mut sum = 0.0_f64;
+            for _ in 0..12 {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                sum += (seed >> 11) as f64 / (1_u64 << 53) as f64;
+            }
+            (sum - 6.0) * sigma
+        };
+
+        let mut record = Record::new();
+        for i in 0..2000 {
+            if i == 1000 {
+                continue; // data gap
+            }
+            let t = t0 + dt * i as f64;
+            let x = i as f64;
+            // strong dynamics: quadratic range
+            let range = 2.0E7 + 500.0 * x + 0.25 * x * x;
+            let mut observations = HashMap::new();
+            observations.insert(
+                c1c.clone(),
+                ObservationData::new(range + noise(0.3), None, None),
+            );
+            observations.insert(
+                l1c.clone(),
+                ObservationData::new(range / 0.19 + noise(0.01), None, None),
+            );
+            observations.insert(s1c.clone(), ObservationData::new(45.0, None, None));
+            let mut vehicles = BTreeMap::new();
+            vehicles.insert(g01, observations);
+            record.insert((t, EpochFlag::Ok), (None, vehicles));
+        }
+
+        let noise = ObservationNoise::from_record(&record);
+        assert_eq!(
+            noise.signals(),
+            vec![
+                (Constellation::GPS, l1c.clone()),
+                (Constellation::GPS, c1c.clone())
+            ],
+            "only code and phase observables should contribute"
+        );
+
+        let code = noise.estimate(Constellation::GPS, &c1c).unwrap();
+        assert_eq!(code.samples, 2000 - 1 - 3 - 3);
+        assert!((code.sigma - 0.3).abs() < 0.03, "code sigma {}", code.sigma);
+
+        let phase = noise.sv_estimate(g01, &l1c).unwrap();
+        assert!(
+            (phase.sigma - 0.01).abs() < 0.001,
+            "phase sigma {}",
+            phase.sigma
+        );
+
+        assert!(noise.estimate(Constellation::Galileo, &c1c).is_none());
+        assert_eq!(noise.estimates().len(), 2);
+
+        // non finite samples do not contribute
+        let estimate = NoiseEstimate::from_samples(&[1.0, f64::NAN, -3.0, 2.0]).unwrap();
+        assert_eq!(estimate.samples, 3);
+        assert_eq!(estimate.sigma, MAD_TO_SIGMA * 2.0);
+        assert!(NoiseEstimate::from_samples(&[f64::INFINITY]).is_none());
+    }
+}
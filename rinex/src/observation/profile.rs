@@ -0,0 +1,302 @@
+//! Mapping profiles, for receivers that do not comply with the standards
+use super::HeaderFields;
+use crate::{
+    fieldslice,
+    prelude::{Constellation, Observable},
+    version::Version,
+};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// [MappingProfile] describes how to fix the Observation RINEX produced by
+/// a non compliant receiver. It is applied at parse time, see [crate::Rinex::from_file_with_profile],
+/// so such files become usable without editing them first:
+///  - `constellations`: constellation letters substitutions,
+///    applied to the header definitions and to all satellites of the record
+///  - `observables`: observation codes renaming (header definitions)
+///  - `order`: preferred column order, per constellation letter. Listed codes come first,
+///    the other ones follow in their original order.
+///  - `missing`: placeholders written in place of missing observations,
+///    normalized to absent data, see [MissingDataRules].
+///
+/// With the "serde" feature, profiles are easily described in a configuration file,
+/// for example TOML:
+/// ```toml
+/// [constellations]
+/// Q = "J"
+///
+/// [observables]
+/// X1C = "C1C"
+///
+/// [order]
+/// G = ["C1C", "L1C", "D1C", "S1C"]
+///
+/// [missing]
+/// zeros = true
+/// ranges = { C = [1.0e6, 1.0e8] }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct MappingProfile {
+    /// Constellation letters substitutions
+    pub constellations: BTreeMap<String, String>,
+    /// Observation codes renaming
+    pub observables: BTreeMap<String, String>,
+    /// Preferred column order, per constellation letter
+    pub order: BTreeMap<String, Vec<String>>,
+    /// Missing data placeholders
+    pub missing: MissingDataRules,
+}
+
+/// [MissingDataRules] describe the placeholders that some receivers write
+/// in place of missing observations: null values or absurd values.
+/// Such observations are normalized to absent data at parse time
+/// (see [MappingProfile]), or later on with [crate::record::Record::normalize_missing],
+/// so they do not bias the statistics. Blank fields are always parsed as absent data.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct MissingDataRules {
+    /// Null GNSS observations are missing. This rule does not apply to
+    /// meteo observations, where null values are legitimate (temperature, rain..).
+    pub zeros: bool,
+    /// Valid range (min, max), per observation code (for example "C1C"),
+    /// or per physics letter ("C" pseudo range, "L" phase, "D" doppler, "S" signal strength).
+    /// Observations out of range are missing. Codes prevail over physics.
+    pub ranges: BTreeMap<String, (f64, f64)>,
+}
+
+impl MissingDataRules {
+    /// Rules suited to most ground receivers: null GNSS observations,
+    /// pseudo ranges out of [1E6, 1E8] m and signal strengths above 100 dB-Hz.
+    pub fn standard() -> Self {
+        Self {
+            zeros: true,
+            ranges: [
+                ("C".to_string(), (1.0E6, 1.0E8)),
+                ("S".to_string(), (0.0, 100.0)),
+            ]
+            .into_iter()
+            .collect(),
+        }
+    }
+    /// Returns true if no rule is defined
+    pub fn is_empty(&self) -> bool {
+        !self.zeros && self.ranges.is_empty()
+    }
+    /// Returns true if given `value` of this [Observable] is a placeholder
+    pub fn is_missing(&self, observable: &Observable, value: f64) -> bool {
+        if self.zeros && value == 0.0 && !observable.is_meteo_observable() {
+            return true;
+        }
+        let code = observable.to_string();
+        let range = self
+            .ranges
+            .get(&code)
+            .or_else(|| code.get(..1).and_then(|physics| self.ranges.get(physics)));
+        match range {
+            Some((min, max)) => value < *min || value > *max,
+            None => false,
+        }
+    }
+}
+
+impl MappingProfile {
+    /// Returns the constellation letter to use in place of `letter`
+    pub fn constellation(&self, letter: char) -> char {
+        self.constellations
+            .get(letter.to_string().as_str())
+            .and_then(|subst| subst.trim().chars().next())
+            .unwrap_or(letter)
+    }
+    /// Returns the observation code to use in place of `code`
+    pub fn observable<'a>(&'a self, code: &'a str) -> &'a str {
+        self.observables
+            .get(code)
+            .map(|subst| subst.trim())
+            .unwrap_or(code)
+    }
+    /*
+     * Applies the constellation letters substitutions to the satellites
+     * of given epoch content (header definitions excluded).
+     * Returns None when there is nothing to substitute.
+     */
+    pub(crate) fn fix_epoch(&self, content: &str, version: Version) -> Option<String> {
+        if self.constellations.is_empty() {
+            return None;
+        }
+        let mut ret = String::with_capacity(content.len());
+        if version.major > 2 {
+            // each observation line starts with the satellite
+            for line in content.lines() {
+                let mut chars = line.chars();
+                match chars.next() {
+                    Some(c) if c != '>' => {
+                        ret.push(self.constellation(c));
+                        ret.push_str(chars.as_str());
+                    },
+                    _ => ret.push_str(line),
+                }
+                ret.push('\n');
+            }
+            return Some(ret);
+        }
+        // satellites are listed in the epoch descriptor (12 per line)
+        let mut lines = content.lines();
+        let first = lines.next()?;
+        let flag = fieldslice::field(first, 28, 29).ok()?.trim();
+        if !matches!(flag, "" | "0" | "1" | "6") {
+            return None; // event: special records
+        }
+        let count = fieldslice::field(first, 29, 32)
+            .ok()?
+            .trim()
+            .parse::<usize>()
+            .ok()?;
+        let descriptor = std::iter::once(first).chain(lines.by_ref().take((count.max(1) - 1) / 12));
+        for line in descriptor {
+            for (index, c) in line.char_indices() {
+                match (32..68).contains(&index) && (index - 32) % 3 == 0 {
+                    true => ret.push(self.constellation(c)),
+                    false => ret.push(c),
+                }
+            }
+            ret.push('\n');
+        }
+        for line in lines {
+            ret.push_str(line);
+            ret.push('\n');
+        }
+        Some(ret)
+    }
+    /*
+     * Applies the preferred column order to given header definitions
+     */
+    pub(crate) fn reorder(&self, fields: &mut HeaderFields) {
+        for (letter, order) in &self.order {
+            let constellation = match Constellation::from_str(letter) {
+                Ok(constellation) => constellation,
+                Err(_) => continue,
+            };
+            if let Some(codes) = fields.codes.get_mut(&constellation) {
+                codes.sort_by_key(|observable| {
+                    let code = observable.to_string();
+                    order
+                        .iter()
+                        .position(|listed| listed.trim() == code)
+                        .unwrap_or(order.len())
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prelude::Observable;
+    fn profile() -> MappingProfile {
+        MappingProfile {
+            constellations: [("Q".to_string(), "J".to_string())].into_iter().collect(),
+            observables: [("X1C".to_string(), "C1C".to_string())]
+                .into_iter()
+                .collect(),
+            order: [("G".to_string(), vec!["L1C".to_string(), "C1C".to_string()])]
+                .into_iter()
+                .collect(),
+            missing: MissingDataRules::default(),
+        }
+    }
+    #[test]
+    fn substitutions() {
+        let profile = profile();
+        assert_eq!(profile.constellation('Q'), 'J');
+        assert_eq!(profile.constellation('G'), 'G');
+        assert_eq!(profile.observable("X1C"), "C1C");
+        assert_eq!(profile.observable("L1C"), "L1C");
+        assert!(MappingProfile::default()
+            .fix_epoch("> 2022 03 04 00 00  0.0000000  0  1\n", Version::new(3, 0))
+            .is_none());
+    }
+    #[test]
+    fn v3_epoch() {
+        let content = "> 2022 03 04 00 00  0.0000000  0  2
+Q01  20243517.560 6
+G02  21208865.370 7
+";
+        let fixed = profile().fix_epoch(content, Version::new(3, 0)).unwrap();
+        assert_eq!(
+            fixed,
+            "> 2022 03 04 00 00  0.0000000  0  2
+J01  20243517.560 6
+G02  21208865.370 7
+"
+        );
+    }
+    #[test]
+    fn v2_epoch() {
+        let content = " 21 12 21  0  0  0.0000000  0 14G07Q01G09G11G13G15G17G19G21G23G25G27
+                                G29Q02
+  20243517.560 6
+";
+        let fixed = profile().fix_epoch(content, Version::new(2, 11)).unwrap();
+        assert_eq!(
+            fixed,
+            " 21 12 21  0  0  0.0000000  0 14G07J01G09G11G13G15G17G19G21G23G25G27
+                                G29J02
+  20243517.560 6
+"
+        );
+        // event: special records are left untouched
+        let event = " 21 12 21  0  0  0.0000000  4  1
+Q01                                                         COMMENT
+";
+        assert!(profile().fix_epoch(event, Version::new(2, 11)).is_none());
+    }
+    #[test]
+    fn missing_data_rules() {
+        let c1c = Observable::from_str("C1C").unwrap();
+        let c2w = Observable::from_str("C2W").unwrap();
+        let s1c = Observable::from_str("S1C").unwrap();
+        let l1c = Observable::from_str("L1C").unwrap();
+        let temperature = Observable::from_str("TD").unwrap();
+        assert!(MissingDataRules::default().is_empty());
+        assert!(!MissingDataRules::default().is_missing(&c1c, 0.0));
+        let mut rules = MissingDataRules::standard();
+        assert!(rules.is_missing(&c1c, 0.0));
+        assert!(rules.is_missing(&c1c, 9999999999.999));
+        assert!(!rules.is_missing(&c1c, 20243517.560));
+        assert!(rules.is_missing(&s1c, 999.0));
+        assert!(!rules.is_missing(&s1c, 45.0));
+        assert!(!rules.is_missing(&l1c, 1.0E12));
+        // null meteo observations are legitimate
+        assert!(!rules.is_missing(&temperature, 0.0));
+        rules.ranges.insert("TD".to_string(), (-90.0, 60.0));
+        assert!(rules.is_missing(&temperature, 999.9));
+        // codes prevail over physics
+        rules.ranges.insert("C2W".to_string(), (1.0E5, 1.0E6));
+        assert!(rules.is_missing(&c2w, 20243517.560));
+        assert!(!rules.is_missing(&c1c, 20243517.560));
+    }
+    #[test]
+    fn reordering() {
+        let mut fields = HeaderFields::default();
+        let codes: Vec<Observable> = ["C1C", "D1C", "L1C", "S1C"]
+            .iter()
+            .map(|code| Observable::from_str(code).unwrap())
+            .collect();
+        fields.codes.insert(Constellation::GPS, codes.clone());
+        fields.codes.insert(Constellation::Galileo, codes.clone());
+        profile().reorder(&mut fields);
+        let gps: Vec<String> = fields.codes[&Constellation::GPS]
+            .iter()
+            .map(|obs| obs.to_string())
+            .collect();
+        assert_eq!(gps, vec!["L1C", "C1C", "D1C", "S1C"]);
+        assert_eq!(fields.codes[&Constellation::Galileo], codes);
+    }
+}
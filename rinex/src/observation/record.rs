@@ -4,11 +4,11 @@ use std::str::FromStr;
 use thiserror::Error;
 
 use crate::{
-    epoch, merge, merge::Merge, prelude::*, split, split::Split, types::Type, version::Version,
-    Carrier, Observable,
+    epoch, fieldslice, merge, merge::Merge, prelude::*, record, split, split::Split, types::Type,
+    version::Version, Carrier, Observable,
 };
 
-use crate::observation::SNR;
+use crate::observation::{DbHz, SNR};
 use hifitime::Duration;
 
 #[derive(Error, Debug)]
@@ -27,6 +27,8 @@ pub enum Error {
     EpochParsingError,
     #[error("line is empty")]
     MissingData,
+    #[error("malformed field")]
+    FieldError(#[from] fieldslice::Error),
 }
 
 #[cfg(feature = "serde")]
@@ -123,6 +125,34 @@ impl ObservationData {
     pub fn pr_real_distance(&self, rcvr_offset: f64, sv_offset: f64, biases: f64) -> f64 {
         self.obs + 299_792_458.0_f64 * (rcvr_offset - sv_offset) + biases
     }
+    /// Converts self, a Phase observation expressed in carrier cycles,
+    /// to meters, using the wavelength of given [`Carrier`].
+    /// ```
+    /// use rinex::carrier::Carrier;
+    /// use rinex::observation::ObservationData;
+    /// let phase = ObservationData::new(100.0, None, None);
+    /// let meters = phase.to_meters(Carrier::L1);
+    /// assert_eq!(meters, 100.0 * Carrier::L1.wavelength());
+    /// let cycles = ObservationData::new(meters, None, None).to_cycles(Carrier::L1);
+    /// assert!((cycles - 100.0).abs() < 1.0E-9);
+    /// ```
+    pub fn to_meters(&self, carrier: Carrier) -> f64 {
+        self.obs * carrier.wavelength()
+    }
+    /// Converts self, a Phase observation expressed in meters,
+    /// back to carrier cycles, using the wavelength of given [`Carrier`].
+    pub fn to_cycles(&self, carrier: Carrier) -> f64 {
+        self.obs / carrier.wavelength()
+    }
+    /// Interprets self as a carrier to noise density ratio,
+    /// if given [`Observable`] is a signal strength observable.
+    pub fn to_dbhz(&self, observable: &Observable) -> Option<DbHz> {
+        if observable.is_ssi_observable() {
+            Some(DbHz(self.obs))
+        } else {
+            None
+        }
+    }
 }
 
 /// Observation Record content, sorted by [`Epoch`], per [`SV`] and per
@@ -135,6 +165,38 @@ pub type Record = BTreeMap<
     ),
 >;
 
+/*
+ * Estimated memory footprint of an Observation record, in bytes
+ */
+pub(crate) fn memory_usage(rec: &Record) -> usize {
+    let mut size = record::btreemap_usage(rec);
+    for (_, vehicles) in rec.values() {
+        size += record::btreemap_usage(vehicles);
+        for observations in vehicles.values() {
+            size += record::hashmap_usage(observations);
+            size += observations
+                .keys()
+                .map(|observable| observable.heap_usage())
+                .sum::<usize>();
+        }
+    }
+    size
+}
+
+/*
+ * Observations timescale: defined by TIME OF FIRST OBS
+ * or by the constellation of single GNSS files
+ */
+pub(crate) fn timescale(header: &Header) -> Option<TimeScale> {
+    match header.constellation {
+        Some(Constellation::Mixed) | None => {
+            let obs = header.obs.as_ref()?;
+            Some(obs.time_of_first_obs?.time_scale)
+        },
+        Some(constellation) => constellation.timescale(),
+    }
+}
+
 /// Returns true if given content matches a new OBSERVATION data epoch
 pub(crate) fn is_new_epoch(line: &str, v: Version) -> bool {
     if v.major < 3 {
@@ -189,12 +251,17 @@ pub(crate) fn parse_epoch(
     }
 
     // V > 2 might start with a ">" marker
-    if line.starts_with('>') {
-        line = line.split_at(1).1;
+    if let Some(stripped) = line.strip_prefix('>') {
+        line = stripped;
     }
 
-    let (date, rem) = line.split_at(offset + 3);
-    let (n_sat, rem) = rem.split_at(3);
+    if line.len() < offset + 6 {
+        // truncated epoch descriptor
+        return Err(Error::MissingData);
+    }
+
+    let (date, rem) = fieldslice::split_at(line, offset + 3)?;
+    let (n_sat, rem) = fieldslice::split_at(rem, 3)?;
     let n_sat = n_sat.trim().parse::<u16>()?;
     let epoch = epoch::parse_in_timescale(date, ts)?;
 
@@ -203,12 +270,12 @@ pub(crate) fn parse_epoch(
     let observables = &obs.codes;
 
     // grab possible clock offset
-    let offs: Option<&str> = match header.version.major < 2 {
+    let offs: Option<&str> = match header.version.major < 3 {
         true => {
             // RINEX 2
-            // clock offsets are last 12 characters
-            if line.len() > 60 - 12 {
-                Some(line.split_at(60 - 12).1.trim())
+            // clock offsets are in columns 69-80
+            if line.len() > 68 {
+                Some(fieldslice::remainder(line, 68)?.trim())
             } else {
                 None
             }
@@ -225,7 +292,7 @@ pub(crate) fn parse_epoch(
                 +3; // n_sat
             if line.len() > min_len {
                 // RINEX3: clock offset precision was increased
-                Some(line.split_at(min_len).1.trim()) // this handles it naturally
+                Some(fieldslice::remainder(line, min_len)?.trim()) // this handles it naturally
             } else {
                 None
             }
@@ -250,7 +317,13 @@ pub(crate) fn parse_epoch(
             // This remains empty on RINEX3, because we have such information
             // on following lines, which is much more convenient
             let mut systems = String::with_capacity(24 * 3); //SVNN
-            systems.push_str(rem.trim());
+
+            // up to 12 vehicles, followed by possible clock offset
+            let vehicles = match rem.char_indices().nth(36) {
+                Some((offset, _)) => &rem[..offset],
+                None => rem,
+            };
+            systems.push_str(vehicles.trim());
             while systems.len() / 3 < n_sat.into() {
                 if let Some(l) = lines.next() {
                     systems.push_str(l.trim());
@@ -265,6 +338,30 @@ pub(crate) fn parse_epoch(
     Ok((epoch, clock_offset, data))
 }
 
+/*
+ * Identifies an event epoch (flags 2 to 5) from given epoch descriptor.
+ * Returns its date, flag and the number of special records that follow.
+ */
+pub(crate) fn parse_event(
+    header: &Header,
+    line: &str,
+    ts: TimeScale,
+) -> Option<((Epoch, EpochFlag), usize)> {
+    let line = line.strip_prefix('>').unwrap_or(line);
+    // date and flag, on 4 digit years in V > 2
+    let offset = match header.version.major > 2 {
+        true => 31,
+        false => 29,
+    };
+    let n = line.get(offset..offset + 3)?.trim().parse::<usize>().ok()?;
+    let (epoch, flag) = epoch::parse_in_timescale(line.get(..offset)?, ts).ok()?;
+    if flag.is_event() {
+        Some(((epoch, flag), n))
+    } else {
+        None
+    }
+}
+
 /*
  * Parses a V2 epoch from given lines iteratoor
  * Vehicle description is contained in the epoch descriptor
@@ -298,8 +395,10 @@ fn parse_v2(
     /*
      * identify 1st system
      */
-    let max = std::cmp::min(svnn_size, systems.len()); // for epochs with a single vehicle
-    let system = &systems[0..max];
+    let system = match fieldslice::field(systems, 0, svnn_size) {
+        Ok(system) => system,
+        Err(_) => return data,
+    };
 
     if let Ok(ssv) = SV::from_str(system) {
         sv = ssv;
@@ -367,34 +466,27 @@ fn parse_v2(
                     //  parsing would fail
                     break;
                 }
-                let slice: &str = match i {
-                    0 => {
-                        &line[0..std::cmp::min(17, line_width)] // manage trimmed single obs
-                    },
-                    _ => {
-                        let start = i * observable_width;
-                        let end = std::cmp::min((i + 1) * observable_width, line_width); // trimmed lines
-                        &line[start..end]
-                    },
+                let slice = match i {
+                    0 => fieldslice::field(line, 0, 17), // manage trimmed single obs
+                    _ => fieldslice::field(line, i * observable_width, (i + 1) * observable_width),
                 };
+                // fields that split a multibyte character are dropped
+                let slice = slice.unwrap_or_default();
                 //println!("WORK CONTENT \"{}\"", slice); //DEBUG
-                //TODO: improve please
-                let obs = &slice[0..std::cmp::min(slice.len(), 14)]; // trimmed observations
-                                                                     //println!("OBS \"{}\"", obs); //DEBUG
+                let obs = fieldslice::field(slice, 0, 14).unwrap_or_default(); // trimmed observations
+                                                                               //println!("OBS \"{}\"", obs); //DEBUG
                 let mut lli: Option<LliFlags> = None;
                 let mut snr: Option<SNR> = None;
                 if let Ok(obs) = obs.trim().parse::<f64>() {
-                    // parse obs
-                    if slice.len() > 14 {
-                        let lli_str = &slice[14..15];
+                    // parse obs, omitted flags are blank
+                    if let Ok(lli_str) = fieldslice::field(slice, 14, 15) {
                         if let Ok(u) = lli_str.parse::<u8>() {
                             lli = LliFlags::from_bits(u);
                         }
-                        if slice.len() > 15 {
-                            let snr_str = &slice[15..16];
-                            if let Ok(s) = SNR::from_str(snr_str) {
-                                snr = Some(s);
-                            }
+                    }
+                    if let Ok(snr_str) = fieldslice::field(slice, 15, 16) {
+                        if let Ok(s) = SNR::from_str(snr_str) {
+                            snr = Some(s);
                         }
                     }
                     //println!("{} {:?} {:?} ==> {}", obs, lli, snr, obscodes[obs_ptr-1]); //DEBUG
@@ -413,8 +505,7 @@ fn parse_v2(
         if obs_ptr >= observables.len() {
             // we're done with current vehicle
             // build data
-            data.insert(sv, inner.clone());
-            inner.clear(); // prepare for next vehicle
+            data.insert(sv, std::mem::take(&mut inner)); // prepare for next vehicle
             obs_ptr = 0;
             //identify next vehicle
             if sv_ptr >= systems.len() {
@@ -422,9 +513,10 @@ fn parse_v2(
                 return data;
             }
             // identify next vehicle
-            let start = sv_ptr;
-            let end = std::cmp::min(sv_ptr + svnn_size, systems.len()); // trimed epoch description
-            let system = &systems[start..end];
+            let system = match fieldslice::field(systems, sv_ptr, sv_ptr + svnn_size) {
+                Ok(system) => system,
+                Err(_) => return data,
+            };
             if let Ok(s) = SV::from_str(system) {
                 sv = s;
             } else {
@@ -486,7 +578,10 @@ fn parse_v3(
     for line in lines {
         // browse all lines
         //println!("parse_v3: \"{}\"", line); //DEBUG
-        let (sv, line) = line.split_at(svnn_size);
+        let (sv, line) = match fieldslice::split_at(line, svnn_size) {
+            Ok((sv, line)) if sv.len() == svnn_size => (sv, line),
+            _ => continue, // truncated or invalid line
+        };
         if let Ok(sv) = SV::from_str(sv) {
             let obscodes = match sv.constellation.is_sbas() {
                 true => observables.get(&Constellation::SBAS),
@@ -503,27 +598,28 @@ fn parse_v3(
                                // does not match previous Header definitions
                                // => would not be able to sort data
                     }
-                    let split_offset = std::cmp::min(observable_width, rem.len()); // avoid overflow on last obs
-                    let (content, r) = rem.split_at(split_offset);
+                    let (content, r) = match fieldslice::split_at(rem, observable_width) {
+                        Ok(split) => split,
+                        Err(_) => break, // non ASCII content
+                    };
                     //println!("content \"{}\" \"{}\"", content, r); //DEBUG
                     rem = r;
-                    let content_len = content.len();
                     let mut snr: Option<SNR> = None;
                     let mut lli: Option<LliFlags> = None;
-                    let obs = &content[0..std::cmp::min(observable_width - 2, content_len)];
+                    let obs =
+                        fieldslice::field(content, 0, observable_width - 2).unwrap_or_default();
                     //println!("OBS \"{}\"", obs); //DEBUG
                     if let Ok(obs) = f64::from_str(obs.trim()) {
-                        if content_len > observable_width - 2 {
-                            let lli_str = &content[observable_width - 2..observable_width - 1];
-                            if let Ok(u) = u8::from_str_radix(lli_str, 10) {
-                                lli = LliFlags::from_bits(u);
-                            }
+                        // omitted flags are blank
+                        let lli_str =
+                            fieldslice::field(content, observable_width - 2, observable_width - 1);
+                        if let Ok(u) = u8::from_str_radix(lli_str.unwrap_or_default(), 10) {
+                            lli = LliFlags::from_bits(u);
                         }
-                        if content_len > observable_width - 1 {
-                            let snr_str = &content[observable_width - 1..observable_width];
-                            if let Ok(s) = SNR::from_str(snr_str) {
-                                snr = Some(s);
-                            }
+                        let snr_str =
+                            fieldslice::field(content, observable_width - 1, observable_width);
+                        if let Ok(s) = SNR::from_str(snr_str.unwrap_or_default()) {
+                            snr = Some(s);
                         }
                         //println!("LLI {:?}", lli); //DEBUG
                         //println!("SSI {:?}", snr);
@@ -531,28 +627,28 @@ fn parse_v3(
                         inner.insert(obscodes[i].clone(), ObservationData { obs, lli, snr });
                     }
                 }
-                if rem.len() >= observable_width - 2 {
+                let observable = obscodes.get(nb_obs);
+                if let (Some(observable), Ok(obs)) =
+                    (observable, fieldslice::exact(rem, 0, observable_width - 2))
+                {
                     let mut snr: Option<SNR> = None;
                     let mut lli: Option<LliFlags> = None;
-                    let obs = &rem[0..observable_width - 2];
                     if let Ok(obs) = obs.trim().parse::<f64>() {
-                        if rem.len() > observable_width - 2 {
-                            let lli_str = &rem[observable_width - 2..observable_width - 1];
-                            if let Ok(u) = lli_str.parse::<u8>() {
-                                lli = LliFlags::from_bits(u);
-                                if rem.len() > observable_width - 1 {
-                                    let snr_str = &rem[observable_width - 1..];
-                                    if let Ok(s) = SNR::from_str(snr_str) {
-                                        snr = Some(s);
-                                    }
-                                }
+                        // omitted flags are blank
+                        let lli_str =
+                            fieldslice::field(rem, observable_width - 2, observable_width - 1);
+                        if let Ok(u) = lli_str.unwrap_or_default().parse::<u8>() {
+                            lli = LliFlags::from_bits(u);
+                            let snr_str = fieldslice::remainder(rem, observable_width - 1);
+                            if let Ok(s) = SNR::from_str(snr_str.unwrap_or_default()) {
+                                snr = Some(s);
                             }
                         }
-                        inner.insert(obscodes[nb_obs].clone(), ObservationData { obs, lli, snr });
+                        inner.insert(observable.clone(), ObservationData { obs, lli, snr });
                     }
                 }
                 if !inner.is_empty() {
-                    data.insert(sv, inner.clone());
+                    data.insert(sv, std::mem::take(&mut inner));
                 }
             } //got some observables to work with
         } // SV::from_str failed()
@@ -575,6 +671,34 @@ pub(crate) fn fmt_epoch(
     }
 }
 
+/// Formats one event epoch: its descriptor, followed by the special records
+pub(crate) fn fmt_event(
+    epoch: Epoch,
+    flag: EpochFlag,
+    records: &[String],
+    header: &Header,
+) -> String {
+    let mut lines = String::with_capacity(128);
+    if header.version.major < 3 {
+        lines.push_str(&format!(
+            " {} {:2}\n",
+            epoch::format(epoch, Some(flag), Type::ObservationData, 2),
+            records.len()
+        ));
+    } else {
+        lines.push_str(&format!(
+            "> {} {:2}\n",
+            epoch::format(epoch, Some(flag), Type::ObservationData, 3),
+            records.len()
+        ));
+    }
+    for record in records {
+        lines.push_str(record.trim_end());
+        lines.push('\n');
+    }
+    lines
+}
+
 fn fmt_epoch_v3(
     epoch: Epoch,
     flag: EpochFlag,
@@ -584,6 +708,7 @@ fn fmt_epoch_v3(
 ) -> String {
     let mut lines = String::with_capacity(128);
     let observables = &header.obs.as_ref().unwrap().codes;
+    let precision = header.obs.as_ref().unwrap().precision;
 
     lines.push_str(&format!(
         "> {} {:2}",
@@ -592,7 +717,8 @@ fn fmt_epoch_v3(
     ));
 
     if let Some(data) = clock_offset {
-        lines.push_str(&format!("{:13.4}", data));
+        // F15.12 in columns 42-56
+        lines.push_str(&format!("      {:15.12}", data));
     }
 
     lines.push('\n');
@@ -605,7 +731,7 @@ fn fmt_epoch_v3(
         if let Some(observables) = observables {
             for observable in observables {
                 if let Some(observation) = data.get(observable) {
-                    lines.push_str(&format!("{:14.3}", observation.obs));
+                    lines.push_str(&precision.float_format(observable).format(observation.obs));
                     if let Some(flag) = observation.lli {
                         lines.push_str(&format!("{}", flag.bits()));
                     } else {
@@ -635,6 +761,7 @@ fn fmt_epoch_v2(
 ) -> String {
     let mut lines = String::with_capacity(128);
     let observables = &header.obs.as_ref().unwrap().codes;
+    let precision = header.obs.as_ref().unwrap().precision;
 
     lines.push_str(&format!(
         " {} {:2}",
@@ -649,8 +776,8 @@ fn fmt_epoch_v2(
             if sv_index == 12 {
                 // first line
                 if let Some(data) = clock_offset {
-                    // push clock offsets
-                    lines.push_str(&format!(" {:9.1}", data));
+                    // F12.9 in columns 69-80
+                    lines.push_str(&format!("{:12.9}", data));
                 }
             }
             lines.push_str("\n                                ");
@@ -658,6 +785,17 @@ fn fmt_epoch_v2(
         lines.push_str(&format!("{:x}", sv));
         index += 1;
     }
+    if data.len() <= 12 {
+        if let Some(data) = clock_offset {
+            // F12.9 in columns 69-80
+            lines.push_str(&format!(
+                "{:>width$}",
+                "",
+                width = 68_usize.saturating_sub(lines.len())
+            ));
+            lines.push_str(&format!("{:12.9}", data));
+        }
+    }
     let obs_per_line = 5;
     // for each vehicle per epoch
     for (sv, observations) in data.iter() {
@@ -673,7 +811,7 @@ fn fmt_epoch_v2(
                     lines.push('\n');
                 }
                 if let Some(observation) = observations.get(observable) {
-                    let formatted_obs = format!("{:14.3}", observation.obs);
+                    let formatted_obs = precision.float_format(observable).format(observation.obs);
                     let formatted_flags: String = match observation.lli {
                         Some(lli) => match observation.snr {
                             Some(snr) => format!("{}{:x}", lli.bits(), snr),
@@ -737,6 +875,51 @@ impl Merge for Record {
     }
 }
 
+/*
+ * Merges `rhs` into `lhs`, resolving overlapping epochs with given policy
+ */
+pub(crate) fn merge_with_mut(
+    lhs: &mut Record,
+    rhs: &Record,
+    policy: merge::MergePolicy,
+) -> Result<(), merge::Error> {
+    if policy == merge::MergePolicy::Error {
+        if let Some((epoch, _)) = merge::first_conflict(lhs, rhs) {
+            return Err(merge::Error::EpochConflict(*epoch));
+        }
+    }
+    for (rhs_epoch, (rhs_clk, rhs_vehicles)) in rhs {
+        if let Some((clk, vehicles)) = lhs.get_mut(rhs_epoch) {
+            merge::resolve_option_f64(clk, *rhs_clk, policy);
+            for (rhs_vehicle, rhs_observations) in rhs_vehicles {
+                let observations = vehicles.entry(*rhs_vehicle).or_default();
+                for (rhs_observable, rhs_data) in rhs_observations {
+                    if let Some(data) = observations.get_mut(rhs_observable) {
+                        merge::resolve_f64(&mut data.obs, rhs_data.obs, policy);
+                        match policy {
+                            merge::MergePolicy::PreferOther => {
+                                data.lli = rhs_data.lli.or(data.lli);
+                                data.snr = rhs_data.snr.or(data.snr);
+                            },
+                            _ => {
+                                data.lli = data.lli.or(rhs_data.lli);
+                                data.snr = data.snr.or(rhs_data.snr);
+                            },
+                        }
+                    } else {
+                        // new observation: insert it
+                        observations.insert(rhs_observable.clone(), *rhs_data);
+                    }
+                }
+            }
+        } else {
+            // this epoch did not exist previously: insert it
+            lhs.insert(*rhs_epoch, (*rhs_clk, rhs_vehicles.clone()));
+        }
+    }
+    Ok(())
+}
+
 impl Split for Record {
     fn split(&self, epoch: Epoch) -> Result<(Self, Self), split::Error> {
         let r0 = self
@@ -872,6 +1055,41 @@ impl Smooth for Record {
     }
 }
 
+/*
+ * Retains the observations for which the predicate returns true.
+ * Satellites without remaining observations are dropped,
+ * and so are epochs without remaining satellites.
+ */
+pub(crate) fn retain_where<F>(rec: &mut Record, mut f: F)
+where
+    F: FnMut(Epoch, SV, &Observable, &ObservationData) -> bool,
+{
+    rec.retain(|(epoch, _), (_, vehicles)| {
+        vehicles.retain(|sv, observations| {
+            observations.retain(|observable, data| f(*epoch, *sv, observable, data));
+            !observations.is_empty()
+        });
+        !vehicles.is_empty()
+    });
+}
+
+/*
+ * Applies `f` to all values of given observable,
+ * LLI and SNR flags are preserved
+ */
+pub(crate) fn apply<F>(rec: &mut Record, observable: &Observable, mut f: F)
+where
+    F: FnMut(f64) -> f64,
+{
+    for (_, vehicles) in rec.values_mut() {
+        for observations in vehicles.values_mut() {
+            if let Some(data) = observations.get_mut(observable) {
+                data.obs = f(data.obs);
+            }
+        }
+    }
+}
+
 #[cfg(feature = "processing")]
 impl Mask for Record {
     fn mask(&self, mask: MaskFilter) -> Self {
@@ -910,28 +1128,13 @@ impl Mask for Record {
                     });
                 },
                 TargetItem::ObservableItem(filter) => {
-                    self.retain(|_, (_, svs)| {
-                        svs.retain(|_, obs| {
-                            obs.retain(|code, _| filter.contains(code));
-                            !obs.is_empty()
-                        });
-                        !svs.is_empty()
-                    });
+                    retain_where(self, |_, _, code, _| filter.contains(code));
                 },
                 TargetItem::SNRItem(filter) => {
                     let filter = SNR::from(filter);
-                    self.retain(|_, (_, svs)| {
-                        svs.retain(|_, obs| {
-                            obs.retain(|_, data| {
-                                if let Some(snr) = data.snr {
-                                    snr == filter
-                                } else {
-                                    false // no snr: drop out
-                                }
-                            });
-                            !obs.is_empty()
-                        });
-                        !svs.is_empty()
+                    // no snr: drop out
+                    retain_where(self, |_, _, _, data| {
+                        data.snr.map(|snr| snr == filter).unwrap_or(false)
                     });
                 },
                 _ => {},
@@ -955,13 +1158,7 @@ impl Mask for Record {
                     });
                 },
                 TargetItem::ObservableItem(filter) => {
-                    self.retain(|_, (_, svs)| {
-                        svs.retain(|_, obs| {
-                            obs.retain(|code, _| !filter.contains(code));
-                            !obs.is_empty()
-                        });
-                        !svs.is_empty()
-                    });
+                    retain_where(self, |_, _, code, _| !filter.contains(code));
                 },
                 _ => {},
             },
@@ -985,18 +1182,9 @@ impl Mask for Record {
                 },
                 TargetItem::SNRItem(filter) => {
                     let filter = SNR::from(filter);
-                    self.retain(|_, (_, svs)| {
-                        svs.retain(|_, obs| {
-                            obs.retain(|_, data| {
-                                if let Some(snr) = data.snr {
-                                    snr >= filter
-                                } else {
-                                    false // no snr: drop out
-                                }
-                            });
-                            !obs.is_empty()
-                        });
-                        !svs.is_empty()
+                    // no snr: drop out
+                    retain_where(self, |_, _, _, data| {
+                        data.snr.map(|snr| snr >= filter).unwrap_or(false)
                     });
                 },
                 _ => {},
@@ -1021,18 +1209,9 @@ impl Mask for Record {
                 },
                 TargetItem::SNRItem(filter) => {
                     let filter = SNR::from(filter);
-                    self.retain(|_, (_, svs)| {
-                        svs.retain(|_, obs| {
-                            obs.retain(|_, data| {
-                                if let Some(snr) = data.snr {
-                                    snr > filter
-                                } else {
-                                    false // no snr: drop out
-                                }
-                            });
-                            !obs.is_empty()
-                        });
-                        !svs.is_empty()
+                    // no snr: drop out
+                    retain_where(self, |_, _, _, data| {
+                        data.snr.map(|snr| snr > filter).unwrap_or(false)
                     });
                 },
                 _ => {},
@@ -1057,18 +1236,9 @@ impl Mask for Record {
                 },
                 TargetItem::SNRItem(filter) => {
                     let filter = SNR::from(filter);
-                    self.retain(|_, (_, svs)| {
-                        svs.retain(|_, obs| {
-                            obs.retain(|_, data| {
-                                if let Some(snr) = data.snr {
-                                    snr <= filter
-                                } else {
-                                    false // no snr: drop out
-                                }
-                            });
-                            !obs.is_empty()
-                        });
-                        !svs.is_empty()
+                    // no snr: drop out
+                    retain_where(self, |_, _, _, data| {
+                        data.snr.map(|snr| snr <= filter).unwrap_or(false)
                     });
                 },
                 _ => {},
@@ -1093,18 +1263,9 @@ impl Mask for Record {
                 },
                 TargetItem::SNRItem(filter) => {
                     let filter = SNR::from(filter);
-                    self.retain(|_, (_, svs)| {
-                        svs.retain(|_, obs| {
-                            obs.retain(|_, data| {
-                                if let Some(snr) = data.snr {
-                                    snr < filter
-                                } else {
-                                    false // no snr: drop out
-                                }
-                            });
-                            !obs.is_empty()
-                        });
-                        !svs.is_empty()
+                    // no snr: drop out
+                    retain_where(self, |_, _, _, data| {
+                        data.snr.map(|snr| snr < filter).unwrap_or(false)
                     });
                 },
                 _ => {},
@@ -1270,7 +1431,7 @@ impl Preprocessing for Record {
                 },
                 DecimationType::DecimByInterval(dt) => {
                     if filter.target.is_none() {
-                        self.decimate_by_interval_mut(dt);
+                        self.decimate_by_sampling_interval_mut(dt);
                         return; // no need to proceed further
                     }
 
@@ -1283,7 +1444,7 @@ impl Preprocessing for Record {
                     };
 
                     // and decimate
-                    let subset = self.mask(mask).decimate_by_interval(dt);
+                    let subset = self.mask(mask).decimate_by_sampling_interval(dt);
 
                     // adapt self's subset to new data rates
                     decimate_data_subset(self, &subset, &item);
@@ -1311,10 +1472,14 @@ impl Decimate for Record {
     fn decimate_by_interval_mut(&mut self, interval: Duration) {
         let mut last_retained: Option<Epoch> = None;
         self.retain(|(e, _), _| {
-            if last_retained.is_some() {
-                let dt = *e - last_retained.unwrap();
-                last_retained = Some(*e);
-                dt > interval
+            if let Some(last) = last_retained {
+                // compared to last retained epoch, so high rate
+                // data can be decimated to any larger interval
+                let retained = *e - last >= interval;
+                if retained {
+                    last_retained = Some(*e);
+                }
+                retained
             } else {
                 last_retained = Some(*e);
                 true // always retain 1st epoch
@@ -1368,7 +1533,7 @@ fn dual_freq_combination(
                 }
 
                 // find L1 reference observation
-                let mut reference: Option<(Observable, f64)> = None;
+                let mut reference: Option<(Observable, ObservationData)> = None;
                 for (ref_observable, ref_data) in observations {
                     let mut shared_physics = ref_observable.is_phase_observable()
                         && lhs_observable.is_phase_observable();
@@ -1380,7 +1545,7 @@ fn dual_freq_combination(
 
                     let refcode = ref_observable.to_string();
                     if refcode.contains('1') {
-                        reference = Some((ref_observable.clone(), ref_data.obs));
+                        reference = Some((ref_observable.clone(), *ref_data));
                         break; // DONE searching
                     }
                 }
@@ -1399,7 +1564,6 @@ fn dual_freq_combination(
 
                 let (lhs_carrier, ref_carrier) = (lhs_carrier.unwrap(), ref_carrier.unwrap());
                 let (fj, fi) = (lhs_carrier.frequency(), ref_carrier.frequency());
-                let (lambda_j, lambda_i) = (lhs_carrier.wavelength(), ref_carrier.wavelength());
 
                 let alpha = match combination {
                     Combination::GeometryFree => 1.0_f64,
@@ -1426,16 +1590,22 @@ fn dual_freq_combination(
                 let (v_j, v_i) = match combination {
                     Combination::GeometryFree => {
                         if ref_observable.is_pseudorange_observable() {
-                            (ref_data, lhs_data.obs)
+                            (ref_data.obs, lhs_data.obs)
                         } else {
-                            (lhs_data.obs * lambda_j, ref_data * lambda_i)
+                            (
+                                lhs_data.to_meters(lhs_carrier),
+                                ref_data.to_meters(ref_carrier),
+                            )
                         }
                     },
                     _ => {
                         if ref_observable.is_pseudorange_observable() {
-                            (lhs_data.obs, ref_data)
+                            (lhs_data.obs, ref_data.obs)
                         } else {
-                            (lhs_data.obs * lambda_j, ref_data * lambda_i)
+                            (
+                                lhs_data.to_meters(lhs_carrier),
+                                ref_data.to_meters(ref_carrier),
+                            )
                         }
                     },
                 };
@@ -1680,6 +1850,73 @@ mod test {
             Version { major: 3, minor: 0 }
         ));
     }
+    #[test]
+    fn obs_record_clock_offset() {
+        let mut codes = HashMap::new();
+        codes.insert(
+            Constellation::GPS,
+            vec![Observable::from_str("C1C").unwrap()],
+        );
+        let fields = crate::observation::HeaderFields {
+            codes,
+            ..Default::default()
+        };
+        for (major, content) in [
+            (
+                2,
+                " 21  1  1  0  0  0.0000000  0  1G01                                  0.000123456\n  22345678.123  ",
+            ),
+            (
+                3,
+                "> 2021 01 01 00 00  0.0000000  0  1       0.000123456789\nG01  22345678.123  ",
+            ),
+        ] {
+            let header = Header::basic_obs()
+                .with_version(Version { major, minor: 0 })
+                .with_observation_fields(fields.clone());
+            let ((epoch, flag), clock_offset, data) =
+                parse_epoch(&header, content, TimeScale::GPST).unwrap();
+            let expected = match major {
+                2 => 0.000123456,
+                _ => 0.000123456789,
+            };
+            assert_eq!(clock_offset, Some(expected), "V{} clock offset", major);
+            assert_eq!(data.len(), 1, "V{} vehicles", major);
+            let formatted = fmt_epoch(epoch, flag, &clock_offset, &data, &header);
+            assert_eq!(
+                formatted.lines().next(),
+                content.lines().next(),
+                "V{} clock offset formatting",
+                major
+            );
+        }
+    }
+    #[test]
+    #[cfg(feature = "obs")]
+    fn geometry_free_phase_in_meters() {
+        let g01 = SV::from_str("G01").unwrap();
+        let l1c = Observable::from_str("L1C").unwrap();
+        let l2w = Observable::from_str("L2W").unwrap();
+        let t = Epoch::from_str("2023-01-01T00:00:00 GPST").unwrap();
+
+        let mut observations = HashMap::new();
+        observations.insert(l1c.clone(), ObservationData::new(100.0, None, None));
+        observations.insert(l2w.clone(), ObservationData::new(80.0, None, None));
+        let mut vehicles = BTreeMap::new();
+        vehicles.insert(g01, observations);
+        let mut record = Record::new();
+        record.insert((t, EpochFlag::Ok), (None, vehicles));
+
+        let gf = record.combine(Combination::GeometryFree);
+        let value = gf[&(l2w, l1c)][&g01][&(t, EpochFlag::Ok)];
+        let expected = 100.0 * Carrier::L1.wavelength() - 80.0 * Carrier::L2.wavelength();
+        assert!(
+            (value - expected).abs() < 1.0E-9,
+            "gf: {} expecting {}",
+            value,
+            expected
+        );
+    }
 }
 
 /*
@@ -1720,22 +1957,21 @@ pub(crate) fn code_multipath(
                         continue;
                     }
                     let rhs_carrier = rhs_carrier.unwrap();
-                    let lambda = rhs_carrier.wavelength();
 
                     if code_is_l1 {
                         if rhs_code.contains('2') {
                             f_j = Some(rhs_carrier.frequency());
-                            phase_j = Some(rhs_data.obs * lambda);
+                            phase_j = Some(rhs_data.to_meters(rhs_carrier));
                         } else if rhs_code.contains(carrier) {
                             f_i = Some(rhs_carrier.frequency());
-                            phase_i = Some(rhs_data.obs * lambda);
+                            phase_i = Some(rhs_data.to_meters(rhs_carrier));
                         }
                     } else if rhs_code.contains('1') {
                         f_j = Some(rhs_carrier.frequency());
-                        phase_j = Some(rhs_data.obs * lambda);
+                        phase_j = Some(rhs_data.to_meters(rhs_carrier));
                     } else if rhs_code.contains(carrier) {
                         f_i = Some(rhs_carrier.frequency());
-                        phase_i = Some(rhs_data.obs * lambda);
+                        phase_i = Some(rhs_data.to_meters(rhs_carrier));
                     }
 
                     if phase_i.is_some() && phase_j.is_some() {
@@ -0,0 +1,52 @@
+//! Pseudo range residuals against a known position
+use crate::prelude::{Epoch, Observable, SV};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Pseudo range residual of one satellite and signal, observed
+/// from a surveyed (known) position: measured pseudo range minus
+/// the modeled one. The receiver clock offset is not known a priori:
+/// it is estimated at each epoch, per constellation and signal, as the median
+/// of the raw residuals, and removed. What remains is mostly multipath, code noise,
+/// unmodeled ionospheric delay and biases: multipath shows as scattered,
+/// elevation dependent residuals, biases as constant offsets.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PseudoRangeResidual {
+    /// Sampling epoch
+    pub epoch: Epoch,
+    /// Satellite
+    pub sv: SV,
+    /// Pseudo range observable
+    pub observable: Observable,
+    /// Elevation angle [°]
+    pub elevation: f64,
+    /// Azimuth angle [°]
+    pub azimuth: f64,
+    /// Residual [m]
+    pub residual: f64,
+}
+
+/*
+ * Zenith tropospheric delay [m] mapped to given elevation [°]:
+ * simple nominal model, accurate to a few decimeters at low elevation
+ */
+#[cfg(all(feature = "obs", feature = "nav"))]
+pub(crate) fn tropo_delay(elevation: f64) -> f64 {
+    2.47 / (elevation.to_radians().sin() + 0.0121)
+}
+
+/*
+ * Median of given values, which are sorted in place
+ */
+#[cfg(all(feature = "obs", feature = "nav"))]
+pub(crate) fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let n = values.len();
+    if n % 2 == 0 {
+        (values[n / 2 - 1] + values[n / 2]) / 2.0
+    } else {
+        values[n / 2]
+    }
+}
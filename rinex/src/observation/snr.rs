@@ -1,3 +1,4 @@
+use super::DbHz;
 use std::str::FromStr;
 
 #[cfg(feature = "serde")]
@@ -179,6 +180,51 @@ impl SNR {
     pub fn excellent(self) -> bool {
         self > SNR::DbHz42_47
     }
+    /// Returns the [min, max[ carrier to noise density ratio range
+    /// this indicator stands for, as defined in RINEX specifications.
+    /// Returns None for [SNR::DbHz0], that RINEX uses for unknown signal strength.
+    /// ```
+    /// use rinex::observation::{DbHz, SNR};
+    /// assert_eq!(SNR::DbHz36_41.dbhz_range(), Some((DbHz(36.0), DbHz(42.0))));
+    /// assert_eq!(SNR::DbHz54.dbhz_range(), Some((DbHz(54.0), DbHz(f64::INFINITY))));
+    /// assert_eq!(SNR::DbHz0.dbhz_range(), None);
+    /// ```
+    pub fn dbhz_range(self) -> Option<(DbHz, DbHz)> {
+        let min = match self {
+            Self::DbHz0 => return None,
+            Self::DbHz12 => return Some((DbHz(0.0), DbHz(12.0))),
+            Self::DbHz54 => return Some((DbHz(54.0), DbHz(f64::INFINITY))),
+            Self::DbHz12_17 => 12.0,
+            Self::DbHz18_23 => 18.0,
+            Self::DbHz24_29 => 24.0,
+            Self::DbHz30_35 => 30.0,
+            Self::DbHz36_41 => 36.0,
+            Self::DbHz42_47 => 42.0,
+            Self::DbHz48_53 => 48.0,
+        };
+        Some((DbHz(min), DbHz(min + 6.0)))
+    }
+    /// Approximates the carrier to noise density ratio from this indicator,
+    /// as the center of its [Self::dbhz_range]: for receivers that
+    /// only populate the indicator.
+    pub fn to_dbhz(self) -> Option<DbHz> {
+        match self {
+            Self::DbHz12 => Some(DbHz(9.0)),
+            Self::DbHz54 => Some(DbHz(57.0)),
+            _ => self
+                .dbhz_range()
+                .map(|(min, max)| DbHz((min.0 + max.0) / 2.0)),
+        }
+    }
+    /// Returns true if given signal strength observation is consistent with this indicator,
+    /// within `tolerance` dB of its [Self::dbhz_range].
+    /// An unknown indicator ([SNR::DbHz0]) is always consistent.
+    pub fn is_consistent(self, dbhz: DbHz, tolerance: f64) -> bool {
+        match self.dbhz_range() {
+            Some((min, max)) => dbhz.0 >= min.0 - tolerance && dbhz.0 < max.0 + tolerance,
+            None => true,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -220,5 +266,15 @@ mod test {
         assert!(SNR::from_str("weak").unwrap().weak());
         assert!(SNR::from_str("strong").unwrap().strong());
         assert!(SNR::from_str("excellent").unwrap().excellent());
+
+        assert_eq!(SNR::DbHz42_47.to_dbhz(), Some(DbHz(45.0)));
+        assert_eq!(SNR::DbHz12.to_dbhz(), Some(DbHz(9.0)));
+        assert_eq!(SNR::DbHz0.to_dbhz(), None);
+        assert!(SNR::DbHz42_47.is_consistent(DbHz(44.0), 0.0));
+        assert!(SNR::DbHz42_47.is_consistent(DbHz(48.5), 1.0));
+        assert!(!SNR::DbHz42_47.is_consistent(DbHz(48.5), 0.0));
+        assert!(!SNR::DbHz42_47.is_consistent(DbHz(35.0), 1.0));
+        assert!(SNR::DbHz54.is_consistent(DbHz(62.0), 0.0));
+        assert!(SNR::DbHz0.is_consistent(DbHz(35.0), 0.0));
     }
 }
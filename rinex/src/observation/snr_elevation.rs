@@ -0,0 +1,178 @@
+//! SNR versus elevation characterization
+use crate::estimation::{weighted_least_squares, Matrix};
+use crate::prelude::{Constellation, Observable};
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Template curve describing the expected SNR [dB.Hz] of a given signal,
+/// as a function of the elevation angle `e` [°]:
+/// SNR(e) = a + b * e + c * e²
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SnrTemplate {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+}
+
+impl SnrTemplate {
+    /// Evaluates this template at given elevation angle [°]
+    pub fn eval(&self, elevation: f64) -> f64 {
+        self.a + self.b * elevation + self.c * elevation.powi(2)
+    }
+    /*
+     * Least squares fit of a 2nd order polynomial
+     */
+    fn fit(samples: &[(f64, f64)]) -> Option<Self> {
+        if samples.len() < 3 {
+            return None;
+        }
+        let rows: Vec<Vec<f64>> = samples.iter().map(|(x, _)| vec![1.0, *x, x * x]).collect();
+        let y: Vec<f64> = samples.iter().map(|(_, y)| *y).collect();
+        // fails on degenerate elevation coverage
+        let h = Matrix::from_rows(&rows).ok()?;
+        let coefs = weighted_least_squares(&h, &y, &vec![1.0; y.len()]).ok()?.x;
+        Some(Self {
+            a: coefs[0],
+            b: coefs[1],
+            c: coefs[2],
+        })
+    }
+}
+
+/// [`SnrElevationModel`] gathers SNR [dB.Hz] versus elevation [°] samples
+/// for each signal, and fits a [`SnrTemplate`] curve per signal.
+/// This is typically used in antenna gain characterization,
+/// or multipath site studies, where measured SNR is compared to
+/// the template at the same elevation.
+/// Signals are identified by their [`Constellation`] and SSI [`Observable`].
+#[derive(Debug, Default, Clone)]
+pub struct SnrElevationModel {
+    /// Raw (elevation [°], SNR [dB.Hz]) samples, per signal
+    pub samples: HashMap<(Constellation, Observable), Vec<(f64, f64)>>,
+    /// Fitted template curves, per signal
+    pub templates: HashMap<(Constellation, Observable), SnrTemplate>,
+}
+
+impl SnrElevationModel {
+    /// Adds a new sample, does not update the templates:
+    /// call [`Self::fit`] once all samples were gathered.
+    /// Samples below the horizon are discarded.
+    pub fn add_sample(
+        &mut self,
+        constellation: Constellation,
+        observable: Observable,
+        elevation: f64,
+        snr: f64,
+    ) {
+        if elevation < 0.0 {
+            return;
+        }
+        self.samples
+            .entry((constellation, observable))
+            .or_default()
+            .push((elevation, snr));
+    }
+    /// (Re)fits all templates from the gathered samples.
+    /// Signals with insufficient elevation coverage do not get a template.
+    pub fn fit(&mut self) {
+        self.templates.clear();
+        for (signal, samples) in &self.samples {
+            if let Some(template) = SnrTemplate::fit(samples) {
+                self.templates.insert(signal.clone(), template);
+            }
+        }
+    }
+    /// Returns template for given signal, if it could be fitted
+    pub fn template(
+        &self,
+        constellation: Constellation,
+        observable: &Observable,
+    ) -> Option<&SnrTemplate> {
+        self.templates.get(&(constellation, observable.clone()))
+    }
+    /// Returns the expected SNR [dB.Hz] of given signal at given elevation [°]
+    pub fn expected_snr(
+        &self,
+        constellation: Constellation,
+        observable: &Observable,
+        elevation: f64,
+    ) -> Option<f64> {
+        let template = self.template(constellation, observable)?;
+        Some(template.eval(elevation))
+    }
+    /// Returns measured minus expected SNR [dB], for given signal, at given elevation [°].
+    /// Strongly negative values usually indicate multipath or obstructions.
+    pub fn snr_residual(
+        &self,
+        constellation: Constellation,
+        observable: &Observable,
+        elevation: f64,
+        snr: f64,
+    ) -> Option<f64> {
+        let expected = self.expected_snr(constellation, observable, elevation)?;
+        Some(snr - expected)
+    }
+}
+
+impl FromIterator<(Constellation, Observable, f64, f64)> for SnrElevationModel {
+    /// Builds and fits a model from (constellation, observable, elevation [°], SNR [dB.Hz]) samples
+    fn from_iter<I: IntoIterator<Item = (Constellation, Observable, f64, f64)>>(iter: I) -> Self {
+        let mut model = Self::default();
+        for (constellation, observable, elevation, snr) in iter {
+            model.add_sample(constellation, observable, elevation, snr);
+        }
+        model.fit();
+        model
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+    #[test]
+    fn snr_template_fit() {
+        let s1c = Observable::from_str("S1C").unwrap();
+        let s2w = Observable::from_str("S2W").unwrap();
+        let truth = SnrTemplate {
+            a: 32.0,
+            b: 0.4,
+            c: -0.0025,
+        };
+        let mut samples = Vec::<(Constellation, Observable, f64, f64)>::new();
+        for elev in 0..90 {
+            let elev = elev as f64;
+            samples.push((Constellation::GPS, s1c.clone(), elev, truth.eval(elev)));
+        }
+        // not enough elevation coverage
+        samples.push((Constellation::GPS, s2w.clone(), 10.0, 35.0));
+        samples.push((Constellation::GPS, s2w.clone(), 10.0, 36.0));
+        samples.push((Constellation::GPS, s2w.clone(), -5.0, 20.0));
+
+        let model: SnrElevationModel = samples.into_iter().collect();
+        let template = model
+            .template(Constellation::GPS, &s1c)
+            .expect("failed to fit S1C template");
+        assert!((template.a - truth.a).abs() < 1.0E-6);
+        assert!((template.b - truth.b).abs() < 1.0E-6);
+        assert!((template.c - truth.c).abs() < 1.0E-6);
+
+        let expected = model.expected_snr(Constellation::GPS, &s1c, 45.0).unwrap();
+        assert!((expected - truth.eval(45.0)).abs() < 1.0E-6);
+        let residual = model
+            .snr_residual(Constellation::GPS, &s1c, 45.0, expected - 3.0)
+            .unwrap();
+        assert!((residual + 3.0).abs() < 1.0E-6);
+
+        assert!(model.template(Constellation::Galileo, &s1c).is_none());
+        assert!(model.template(Constellation::GPS, &s2w).is_none());
+        assert_eq!(
+            model.samples.get(&(Constellation::GPS, s2w)).unwrap().len(),
+            2,
+            "samples below horizon should be discarded"
+        );
+    }
+}
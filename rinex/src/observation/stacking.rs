@@ -0,0 +1,350 @@
+//! Sidereal day stacking, for multipath characterization
+use crate::prelude::{Duration, Epoch, Observable, SV};
+use std::collections::BTreeMap;
+
+#[cfg(feature = "obs")]
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Sidereal day, in seconds: the GPS geometry repeats
+/// about 236 seconds earlier every (solar) day
+pub const SIDEREAL_DAY_SECONDS: f64 = 86164.0905;
+
+/// Quantity that is stacked, per satellite
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum StackedSignal {
+    /// Signal strength observation, in dB-Hz
+    Ssi(Observable),
+    /// Code multipath of given Pseudo Range observable, in meters.
+    /// Each day is made zero mean, to remove the phase ambiguity,
+    /// and so is each arc that follows a cycle slip.
+    Multipath(Observable),
+}
+
+impl std::fmt::Display for StackedSignal {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Ssi(observable) => write!(f, "{}", observable),
+            Self::Multipath(observable) => write!(f, "MP({})", observable),
+        }
+    }
+}
+
+/// Day to day repeatability of a stacked series
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Repeatability {
+    /// Number of days contributing
+    pub days: usize,
+    /// Number of samples that have a counterpart on another day
+    pub samples: usize,
+    /// RMS deviation from the stacked (mean) series
+    pub rms: f64,
+    /// Mean correlation of consecutive days: close to 1 when the
+    /// multipath signature repeats, which is expected from a static site.
+    pub correlation: Option<f64>,
+}
+
+/// [SiderealStack] aligns the observations of consecutive days by sidereal day:
+/// the satellite geometry repeats, and so does the site multipath.
+/// Each sample is described by the day it belongs to and its offset within that day.
+/// Stacking the days reveals the multipath signature, while their scatter
+/// ([Repeatability]) separates it from the measurement noise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SiderealStack {
+    /// Start of the first day
+    pub start: Epoch,
+    /// Stacking period, usually [SIDEREAL_DAY_SECONDS]
+    pub period: Duration,
+    /// Samples (offset within the day, value), per satellite, signal and day
+    pub series: BTreeMap<(SV, StackedSignal), BTreeMap<usize, Vec<(Duration, f64)>>>,
+}
+
+impl SiderealStack {
+    /// Builds an empty stack, days starting at `start`
+    pub fn new(start: Epoch, period: Duration) -> Self {
+        Self {
+            start,
+            period,
+            series: BTreeMap::new(),
+        }
+    }
+    /// Stacks a new sample. Samples prior `start` are dropped.
+    pub fn push(&mut self, sv: SV, signal: StackedSignal, t: Epoch, value: f64) {
+        if let Some((day, offset)) = self.locate(t) {
+            self.series
+                .entry((sv, signal))
+                .or_default()
+                .entry(day)
+                .or_default()
+                .push((offset, value));
+        }
+    }
+    /*
+     * Returns the day `t` belongs to and its offset within that day
+     */
+    fn locate(&self, t: Epoch) -> Option<(usize, Duration)> {
+        if t < self.start {
+            return None;
+        }
+        let elapsed = (t - self.start).to_seconds();
+        let period = self.period.to_seconds();
+        let day = (elapsed / period).floor();
+        Some((day as usize, Duration::from_seconds(elapsed - day * period)))
+    }
+    /// Returns the number of days spanned by this stack
+    pub fn days(&self) -> usize {
+        self.series
+            .values()
+            .flat_map(|days| days.keys())
+            .max()
+            .map(|day| day + 1)
+            .unwrap_or(0)
+    }
+    /*
+     * Averages the samples of each day per offset bin
+     */
+    fn binned(
+        days: &BTreeMap<usize, Vec<(Duration, f64)>>,
+        bin: Duration,
+    ) -> BTreeMap<i64, BTreeMap<usize, f64>> {
+        let width = bin.to_seconds();
+        let mut sums = BTreeMap::<i64, BTreeMap<usize, (f64, usize)>>::new();
+        for (day, samples) in days {
+            for (offset, value) in samples {
+                let index = (offset.to_seconds() / width).round() as i64;
+                let (sum, count) = sums.entry(index).or_default().entry(*day).or_default();
+                *sum += value;
+                *count += 1;
+            }
+        }
+        sums.into_iter()
+            .map(|(index, days)| {
+                let means = days
+                    .into_iter()
+                    .map(|(day, (sum, count))| (day, sum / count as f64))
+                    .collect();
+                (index, means)
+            })
+            .collect()
+    }
+    /// Returns the stacked series of given satellite and signal: the mean
+    /// of all days, per `bin` of offset within the day (usually the sampling interval).
+    pub fn stacked(&self, sv: SV, signal: &StackedSignal, bin: Duration) -> Vec<(Duration, f64)> {
+        let days = match self.series.get(&(sv, signal.clone())) {
+            Some(days) => days,
+            None => return Vec::new(),
+        };
+        Self::binned(days, bin)
+            .into_iter()
+            .map(|(index, values)| {
+                let mean = values.values().sum::<f64>() / values.len() as f64;
+                (bin * index as f64, mean)
+            })
+            .collect()
+    }
+    /// Returns the day to day repeatability of given satellite and signal,
+    /// samples being compared per `bin` of offset within the day.
+    /// Returns None if no sample has a counterpart on another day.
+    pub fn repeatability(
+        &self,
+        sv: SV,
+        signal: &StackedSignal,
+        bin: Duration,
+    ) -> Option<Repeatability> {
+        let days = self.series.get(&(sv, signal.clone()))?;
+        let binned = Self::binned(days, bin);
+        let (mut sum, mut samples) = (0.0_f64, 0_usize);
+        for values in binned.values() {
+            if values.len() < 2 {
+                continue;
+            }
+            let mean = values.values().sum::<f64>() / values.len() as f64;
+            for value in values.values() {
+                sum += (value - mean).powi(2);
+                samples += 1;
+            }
+        }
+        if samples == 0 {
+            return None;
+        }
+        let indexes: Vec<usize> = days.keys().copied().collect();
+        let correlations: Vec<f64> = indexes
+            .windows(2)
+            .filter_map(|pair| {
+                let (x, y): (Vec<f64>, Vec<f64>) = binned
+                    .values()
+                    .filter_map(|values| Some((*values.get(&pair[0])?, *values.get(&pair[1])?)))
+                    .unzip();
+                correlation(&x, &y)
+            })
+            .collect();
+        Some(Repeatability {
+            days: days.len(),
+            samples,
+            rms: (sum / samples as f64).sqrt(),
+            correlation: match correlations.is_empty() {
+                true => None,
+                false => Some(correlations.iter().sum::<f64>() / correlations.len() as f64),
+            },
+        })
+    }
+    /// Returns the repeatability of every satellite and signal, see [Self::repeatability]
+    pub fn repeatabilities(&self, bin: Duration) -> Vec<(SV, StackedSignal, Repeatability)> {
+        self.series
+            .keys()
+            .filter_map(|(sv, signal)| {
+                let repeatability = self.repeatability(*sv, signal, bin)?;
+                Some((*sv, signal.clone(), repeatability))
+            })
+            .collect()
+    }
+    /*
+     * Makes each arc of the multipath series zero mean: arcs are delimited
+     * by the days and by the cycle slips (per satellite), that change the phase ambiguity
+     */
+    #[cfg(feature = "obs")]
+    pub(crate) fn center_multipath(&mut self, slips: &HashMap<SV, Vec<Epoch>>) {
+        let slips: HashMap<SV, Vec<(usize, Duration)>> = slips
+            .iter()
+            .map(|(sv, epochs)| (*sv, epochs.iter().filter_map(|t| self.locate(*t)).collect()))
+            .collect();
+        for ((sv, signal), days) in self.series.iter_mut() {
+            if let StackedSignal::Multipath(_) = signal {
+                let slips = slips.get(sv).map(|slips| slips.as_slice()).unwrap_or(&[]);
+                for (day, samples) in days.iter_mut() {
+                    let arc = |offset: &Duration| {
+                        slips
+                            .iter()
+                            .filter(|(slip_day, slip)| slip_day == day && slip <= offset)
+                            .count()
+                    };
+                    let mut sums = BTreeMap::<usize, (f64, usize)>::new();
+                    for (offset, value) in samples.iter() {
+                        let (sum, count) = sums.entry(arc(offset)).or_default();
+                        *sum += value;
+                        *count += 1;
+                    }
+                    for (offset, value) in samples.iter_mut() {
+                        let (sum, count) = sums[&arc(offset)];
+                        *value -= sum / count as f64;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/*
+ * Pearson correlation coefficient
+ */
+fn correlation(x: &[f64], y: &[f64]) -> Option<f64> {
+    if x.len() < 3 {
+        return None;
+    }
+    let n = x.len() as f64;
+    let mean_x = x.iter().sum::<f64>() / n;
+    let mean_y = y.iter().sum::<f64>() / n;
+    let (mut sxy, mut sxx, mut syy) = (0.0_f64, 0.0_f64, 0.0_f64);
+    for (x, y) in x.iter().zip(y) {
+        sxy += (x - mean_x) * (y - mean_y);
+        sxx += (x - mean_x).powi(2);
+        syy += (y - mean_y).powi(2);
+    }
+    if sxx == 0.0 || syy == 0.0 {
+        return None;
+    }
+    Some(sxy / (sxx * syy).sqrt())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+    #[test]
+    fn sidereal_stacking() {
+        let g01 = SV::from_str("G01").unwrap();
+        let signal = StackedSignal::Multipath(Observable::from_str("C1C").unwrap());
+        let t0 = Epoch::from_str("2023-01-01T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(30.0);
+        let sidereal = Duration::from_seconds(SIDEREAL_DAY_SECONDS);
+        let solar = Duration::from_seconds(86400.0);
+
+        // multipath signature repeating every sidereal day
+        let multipath = |t: Epoch| {
+            let offset = (t - t0).to_seconds() % SIDEREAL_DAY_SECONDS;
+            (2.0 * std::f64::consts::PI * offset / 1000.0).sin()
+        };
+
+        let mut stacks = [
+            SiderealStack::new(t0, sidereal),
+            SiderealStack::new(t0, solar),
+        ];
+        for i in 0..3 * 2880 {
+            let t = t0 + dt * i as f64;
+            for stack in stacks.iter_mut() {
+                stack.push(g01, signal.clone(), t, multipath(t) + 1.0);
+            }
+        }
+        for stack in stacks.iter_mut() {
+            stack.center_multipath(&HashMap::new());
+        }
+        let [sidereal, solar] = stacks;
+
+        assert_eq!(
+            sidereal.days(),
+            4,
+            "last samples belong to a 4th sidereal day"
+        );
+        assert_eq!(solar.days(), 3);
+
+        let aligned = sidereal.repeatability(g01, &signal, dt).unwrap();
+        assert_eq!(aligned.days, 4);
+        assert!(aligned.rms < 0.05, "rms {}", aligned.rms);
+        assert!(aligned.correlation.unwrap() > 0.99);
+
+        let misaligned = solar.repeatability(g01, &signal, dt).unwrap();
+        assert!(misaligned.rms > 0.5, "rms {}", misaligned.rms);
+        assert!(misaligned.correlation.unwrap() < 0.5);
+
+        // the stacked series preserves the signature
+        let stacked = sidereal.stacked(g01, &signal, dt);
+        let (offset, value) = stacked[100];
+        assert_eq!(offset, dt * 100.0);
+        assert!((value - (2.0 * std::f64::consts::PI * 3000.0 / 1000.0).sin()).abs() < 0.05);
+
+        assert_eq!(sidereal.repeatabilities(dt).len(), 1);
+        let e01 = SV::from_str("E01").unwrap();
+        assert!(sidereal.repeatability(e01, &signal, dt).is_none());
+        assert!(sidereal.stacked(e01, &signal, dt).is_empty());
+    }
+    #[test]
+    fn cycle_slip_recentering() {
+        let g01 = SV::from_str("G01").unwrap();
+        let signal = StackedSignal::Multipath(Observable::from_str("C1C").unwrap());
+        let t0 = Epoch::from_str("2023-01-01T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(30.0);
+        let slip = t0 + dt * 100.0;
+        let mut stack = SiderealStack::new(t0, Duration::from_seconds(SIDEREAL_DAY_SECONDS));
+        // the ambiguity jumps at the slip
+        for i in 0..200 {
+            let t = t0 + dt * i as f64;
+            let ambiguity = if t < slip { 1.0 } else { 5.0 };
+            stack.push(g01, signal.clone(), t, ambiguity);
+        }
+        let mut unaware = stack.clone();
+        unaware.center_multipath(&HashMap::new());
+        assert!(unaware.series[&(g01, signal.clone())][&0]
+            .iter()
+            .all(|(_, value)| value.abs() == 2.0));
+
+        let slips: HashMap<SV, Vec<Epoch>> = [(g01, vec![slip])].into_iter().collect();
+        stack.center_multipath(&slips);
+        assert!(stack.series[&(g01, signal.clone())][&0]
+            .iter()
+            .all(|(_, value)| *value == 0.0));
+    }
+}
@@ -0,0 +1,88 @@
+//! Physical units of Observation RINEX measurements
+use super::SNR;
+use crate::prelude::Observable;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Physical unit of a GNSS observation, as expressed in RINEX.
+/// Mixing units is a classical mistake: Phase observations
+/// are expressed in carrier cycles, whereas Pseudo Ranges are expressed in meters.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum ObservationUnit {
+    /// Pseudo Range observations
+    Meters,
+    /// Phase observations: whole and fractional carrier cycles
+    Cycles,
+    /// Doppler observations
+    Hertz,
+    /// Signal strength observations: carrier to noise density ratio
+    DbHz,
+}
+
+impl std::fmt::Display for ObservationUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Meters => f.write_str("m"),
+            Self::Cycles => f.write_str("cycles"),
+            Self::Hertz => f.write_str("Hz"),
+            Self::DbHz => f.write_str("dB-Hz"),
+        }
+    }
+}
+
+impl ObservationUnit {
+    /// Returns the unit of given [`Observable`],
+    /// None for pseudo observables (channel numbers) and Meteo observables.
+    pub fn from_observable(observable: &Observable) -> Option<Self> {
+        match observable {
+            Observable::PseudoRange(_) => Some(Self::Meters),
+            Observable::Phase(_) => Some(Self::Cycles),
+            Observable::Doppler(_) => Some(Self::Hertz),
+            Observable::SSI(_) => Some(Self::DbHz),
+            _ => None,
+        }
+    }
+}
+
+/// Carrier to noise density ratio, in dB-Hz.
+/// This is what signal strength observations represent,
+/// and can be converted to the (coarser) [`SNR`] indication.
+#[derive(Debug, Default, Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct DbHz(pub f64);
+
+impl std::fmt::Display for DbHz {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:.3} dB-Hz", self.0)
+    }
+}
+
+impl From<DbHz> for SNR {
+    fn from(dbhz: DbHz) -> Self {
+        Self::from(dbhz.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+    #[test]
+    fn observation_units() {
+        for (observable, unit) in [
+            ("C1C", Some(ObservationUnit::Meters)),
+            ("L1C", Some(ObservationUnit::Cycles)),
+            ("D2W", Some(ObservationUnit::Hertz)),
+            ("S5Q", Some(ObservationUnit::DbHz)),
+            ("PR", None),
+        ] {
+            let observable = Observable::from_str(observable).unwrap();
+            assert_eq!(ObservationUnit::from_observable(&observable), unit);
+        }
+        assert_eq!(ObservationUnit::DbHz.to_string(), "dB-Hz");
+        assert_eq!(SNR::from(DbHz(44.0)), SNR::DbHz42_47);
+        assert_eq!(DbHz(44.0).to_string(), "44.000 dB-Hz");
+    }
+}
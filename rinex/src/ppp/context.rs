@@ -0,0 +1,144 @@
+//! PPP from a [RnxContext]
+use super::{Candidate, Config, Error, Solution, Solver};
+use crate::carrier::Carrier;
+use crate::constants::SPEED_OF_LIGHT;
+use crate::context::interpolate;
+use crate::observation::LliFlags;
+use crate::prelude::{Constellation, Duration, Epoch, Observable, RnxContext, SV};
+use log::{error, trace};
+use std::collections::BTreeMap;
+
+/*
+ * Ionosphere free combination [m] of two measurements [m]
+ */
+fn iono_free(f1: f64, m1: f64, f2: f64, m2: f64) -> f64 {
+    (f1.powi(2) * m1 - f2.powi(2) * m2) / (f1.powi(2) - f2.powi(2))
+}
+
+/// Resolves a static PPP float solution, from the Observation RINEX
+/// and the SP3 orbits of given [RnxContext]. Precise clocks are picked
+/// from the Clock RINEX when provided, otherwise from the SP3 file.
+/// Requires dual frequency code and phase observations.
+/// Epochs that could not be resolved are not reported.
+pub fn resolve(ctx: &RnxContext, cfg: Config) -> Result<BTreeMap<Epoch, Solution>, Error> {
+    let obs = ctx.obs_data().ok_or(Error::MissingObservationData)?;
+    let sp3 = ctx.sp3_data().ok_or(Error::MissingSp3Data)?;
+
+    /* gather precise clocks once, per SV: in seconds */
+    let clocks = ctx.precise_clocks();
+
+    /* SV position at t, in meters ECEF */
+    let sv_position = |sv: SV, t: Epoch| -> Option<(f64, f64, f64)> {
+        let (x, y, z) = sp3.sv_position_interpolate(sv, t, cfg.interpolation_order)?;
+        Some((x * 1.0E3, y * 1.0E3, z * 1.0E3))
+    };
+
+    let mut solver = Solver::new(cfg);
+    let mut ret = BTreeMap::<Epoch, Solution>::new();
+
+    for ((t, flag), (_, vehicles)) in obs.observation() {
+        if !flag.is_ok() {
+            continue;
+        }
+        let mut candidates = Vec::<Candidate>::with_capacity(vehicles.len());
+        for (sv, observations) in vehicles {
+            if sv.constellation == Constellation::Glonass {
+                /* FDMA: not supported */
+                continue;
+            }
+            /* code and phase observables, in a deterministic order */
+            let mut observables: Vec<&Observable> = observations.keys().collect();
+            observables.sort();
+            let mut codes = Vec::<(Carrier, f64)>::new();
+            let mut phases = Vec::<(Carrier, f64, bool)>::new();
+            for observable in observables {
+                let data = &observations[observable];
+                let carrier = match Carrier::from_observable(sv.constellation, observable) {
+                    Ok(carrier) => carrier,
+                    Err(_) => continue,
+                };
+                if observable.is_pseudorange_observable() {
+                    if !codes.iter().any(|(c, _)| *c == carrier) {
+                        codes.push((carrier, data.obs));
+                    }
+                } else if observable.is_phase_observable()
+                    && !phases.iter().any(|(c, _, _)| *c == carrier)
+                {
+                    let slip = data
+                        .lli
+                        .map(|lli| lli.intersects(LliFlags::LOCK_LOSS))
+                        .unwrap_or(false);
+                    phases.push((carrier, data.to_meters(carrier), slip));
+                }
+            }
+            /* highest and lowest frequencies, both in code and phase */
+            let mut common: Vec<Carrier> = codes
+                .iter()
+                .map(|(c, _)| *c)
+                .filter(|c| phases.iter().any(|(p, _, _)| p == c))
+                .collect();
+            common.sort_by(|a, b| b.frequency().total_cmp(&a.frequency()));
+            if common.len() < 2 {
+                trace!("{}({}): not dual frequency", t, sv);
+                continue;
+            }
+            let (c1, c2) = (common[0], common[common.len() - 1]);
+            let (f1, f2) = (c1.frequency(), c2.frequency());
+            let code = |c: Carrier| codes.iter().find(|(k, _)| *k == c).unwrap().1;
+            let phase = |c: Carrier| *phases.iter().find(|(k, _, _)| *k == c).unwrap();
+            let (p1, p2) = (code(c1), code(c2));
+            let ((_, l1, slip1), (_, l2, slip2)) = (phase(c1), phase(c2));
+            let code = iono_free(f1, p1, f2, p2);
+
+            /* transmission time */
+            let clock = match clocks.get(sv) {
+                Some(clocks) => clocks,
+                None => {
+                    trace!("{}({}): missing clock", t, sv);
+                    continue;
+                },
+            };
+            let t_tx = *t - Duration::from_seconds(code / SPEED_OF_LIGHT);
+            let dts = match interpolate(clock, t_tx) {
+                Some(dts) => dts,
+                None => {
+                    trace!("{}({}): clock interpolation failed", t, sv);
+                    continue;
+                },
+            };
+            let t_tx = t_tx - Duration::from_seconds(dts);
+            let (position, before, after) = match (
+                sv_position(*sv, t_tx),
+                sv_position(*sv, t_tx - Duration::from_seconds(0.5)),
+                sv_position(*sv, t_tx + Duration::from_seconds(0.5)),
+            ) {
+                (Some(p), Some(b), Some(a)) => (p, b, a),
+                _ => {
+                    trace!("{}({}): orbit interpolation failed", t, sv);
+                    continue;
+                },
+            };
+            /* relativistic clock correction */
+            let velocity = (after.0 - before.0, after.1 - before.1, after.2 - before.2);
+            let dtr = -2.0
+                * (position.0 * velocity.0 + position.1 * velocity.1 + position.2 * velocity.2)
+                / SPEED_OF_LIGHT.powi(2);
+
+            candidates.push(Candidate {
+                sv: *sv,
+                code,
+                phase: iono_free(f1, l1, f2, l2),
+                sv_position: position,
+                sv_clock: dts + dtr,
+                cycle_slip: slip1 || slip2,
+            });
+        }
+        match solver.resolve(*t, &candidates) {
+            Ok(solution) => {
+                ret.insert(*t, solution);
+            },
+            Err(e) => error!("{}: ppp failed with {}", t, e),
+        }
+    }
+    Ok(ret)
+}
@@ -0,0 +1,406 @@
+//! Static Precise Point Positioning (PPP), float solution.
+//!
+//! Ionosphere free code and phase combinations are processed by an
+//! (iterated) extended Kalman filter, whose states are the receiver position,
+//! the receiver clock offset (white noise), the zenith wet delay (random walk)
+//! and one float ambiguity per tracked satellite.
+//! The zenith hydrostatic delay is modeled (Saastamoinen, standard atmosphere).
+//!
+//! Antenna phase center offsets (ANTEX), phase wind up and tidal displacements (BLQ)
+//! are not modeled: the solution does not reach the centimeter level.
+use crate::constants::SPEED_OF_LIGHT;
+use crate::prelude::{Epoch, SV};
+use crate::propagation::earth_rotation;
+use map_3d::{ecef2geodetic, Ellipsoid};
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+mod context;
+
+use crate::estimation::{weighted_least_squares, Error as EstimationError, Kalman, Matrix};
+pub use context::resolve;
+
+/// Receiver clock offset variance, when re-initialized [m²]
+const CLOCK_VARIANCE: f64 = 900.0;
+
+/// Initial position variance [m²]
+const POSITION_VARIANCE: f64 = 1.0E4;
+
+/// Initial ambiguity variance [m²]
+const AMBIGUITY_VARIANCE: f64 = 900.0;
+
+/// Maximal number of filter iterations, per epoch
+const MAX_ITERATIONS: usize = 10;
+
+/* state vector layout */
+const CLOCK: usize = 3;
+const ZWD: usize = 4;
+const AMBIGUITIES: usize = 5;
+
+/// PPP errors
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("not enough candidates")]
+    NotEnoughCandidates,
+    #[error("failed to initialize the receiver position")]
+    InitializationError,
+    #[error("estimation error")]
+    EstimationError(#[from] EstimationError),
+    #[error("missing observation rinex")]
+    MissingObservationData,
+    #[error("missing sp3 orbits")]
+    MissingSp3Data,
+}
+
+/// PPP [Solver] configuration
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Config {
+    /// Elevation mask [°]
+    pub elevation_mask: f64,
+    /// Ionosphere free pseudo range standard deviation, at zenith [m]
+    pub code_sigma: f64,
+    /// Ionosphere free phase standard deviation, at zenith [m]
+    pub phase_sigma: f64,
+    /// Zenith wet delay random walk process noise [m²/s]
+    pub zwd_process_noise: f64,
+    /// SP3 orbits interpolation order
+    pub interpolation_order: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            elevation_mask: 10.0,
+            code_sigma: 1.0,
+            phase_sigma: 0.01,
+            zwd_process_noise: 1.0E-8,
+            interpolation_order: 11,
+        }
+    }
+}
+
+/// Measurements and products of a single satellite, at a given epoch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candidate {
+    /// Satellite
+    pub sv: SV,
+    /// Ionosphere free pseudo range [m]
+    pub code: f64,
+    /// Ionosphere free phase [m]
+    pub phase: f64,
+    /// Satellite position [m ECEF], at transmission time
+    pub sv_position: (f64, f64, f64),
+    /// Satellite clock offset [s], at transmission time,
+    /// including the relativistic correction
+    pub sv_clock: f64,
+    /// True if a cycle slip (or lock loss) affects the phase
+    pub cycle_slip: bool,
+}
+
+/// PPP solution
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Solution {
+    /// Receiver position [m ECEF]
+    pub position: (f64, f64, f64),
+    /// Receiver position standard deviation [m], on each axis
+    pub position_sigma: (f64, f64, f64),
+    /// Receiver clock offset [s]
+    pub clock_offset: f64,
+    /// Zenith wet delay [m]
+    pub zwd: f64,
+    /// Zenith total delay [m]
+    pub ztd: f64,
+    /// Number of satellites contributing to this solution
+    pub satellites: usize,
+}
+
+/*
+ * Line of sight geometry
+ */
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Geometry {
+    /// Geometric range [m], corrected for the earth rotation
+    pub range: f64,
+    /// Unit vector, from receiver to satellite
+    pub los: (f64, f64, f64),
+    /// Elevation [rad]
+    pub elevation: f64,
+}
+
+/*
+ * Line of sight geometry, from receiver to satellite.
+ * The satellite position is rotated by the earth rotation during signal propagation.
+ */
+pub(crate) fn geometry(rx: (f64, f64, f64), sv: (f64, f64, f64)) -> Geometry {
+    let dist = ((sv.0 - rx.0).powi(2) + (sv.1 - rx.1).powi(2) + (sv.2 - rx.2).powi(2)).sqrt();
+    let sv = earth_rotation(sv, dist / SPEED_OF_LIGHT);
+    let d = (sv.0 - rx.0, sv.1 - rx.1, sv.2 - rx.2);
+    let range = (d.0.powi(2) + d.1.powi(2) + d.2.powi(2)).sqrt();
+    let los = (d.0 / range, d.1 / range, d.2 / range);
+    let (lat, lon, _) = ecef2geodetic(rx.0, rx.1, rx.2, Ellipsoid::WGS84);
+    let up = (lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin());
+    let elevation = (los.0 * up.0 + los.1 * up.1 + los.2 * up.2).asin();
+    Geometry {
+        range,
+        los,
+        elevation,
+    }
+}
+
+/*
+ * Zenith hydrostatic delay [m] (Saastamoinen),
+ * with standard atmosphere pressure at given altitude
+ */
+pub(crate) fn zenith_hydrostatic_delay(rx: (f64, f64, f64)) -> f64 {
+    let (lat, _, h) = ecef2geodetic(rx.0, rx.1, rx.2, Ellipsoid::WGS84);
+    let h = h.clamp(-500.0, 9000.0);
+    let pressure = 1013.25 * (1.0 - 2.2557E-5 * h).powf(5.2568);
+    0.0022768 * pressure / (1.0 - 0.00266 * (2.0 * lat).cos() - 0.28E-6 * h)
+}
+
+/*
+ * Tropospheric mapping function
+ */
+pub(crate) fn mapping(elevation: f64) -> f64 {
+    1.001 / (0.002001 + elevation.sin().powi(2)).sqrt()
+}
+
+/// Static PPP solver: feed it with [Candidate]s, epoch after epoch,
+/// in chronological order. Satellites no longer tracked are dropped,
+/// new satellites (or cycle slips) introduce new ambiguities.
+#[derive(Debug, Clone)]
+pub struct Solver {
+    /// Configuration
+    pub cfg: Config,
+    /// Filter, once initialized
+    filter: Option<Kalman>,
+    /// Satellites, in the order of the ambiguity states
+    ambiguities: Vec<SV>,
+    /// Latest resolved epoch
+    last_epoch: Option<Epoch>,
+}
+
+impl Solver {
+    /// Creates a new [Solver]
+    pub fn new(cfg: Config) -> Self {
+        Self {
+            cfg,
+            filter: None,
+            ambiguities: Vec::new(),
+            last_epoch: None,
+        }
+    }
+    /*
+     * Code only least squares (Gauss Newton), to initialize the filter
+     */
+    fn initialize(&self, candidates: &[Candidate]) -> Result<Kalman, Error> {
+        let mut state = [0.0_f64; 4];
+        for _ in 0..MAX_ITERATIONS {
+            let rx = (state[0], state[1], state[2]);
+            let mut h = Matrix::zeros(candidates.len(), 4);
+            let mut y = Vec::with_capacity(candidates.len());
+            for (i, c) in candidates.iter().enumerate() {
+                let geo = geometry(rx, c.sv_position);
+                h[(i, 0)] = -geo.los.0;
+                h[(i, 1)] = -geo.los.1;
+                h[(i, 2)] = -geo.los.2;
+                h[(i, 3)] = 1.0;
+                y.push(c.code - (geo.range + state[3] - SPEED_OF_LIGHT * c.sv_clock));
+            }
+            let dx = weighted_least_squares(&h, &y, &vec![1.0; y.len()])?.x;
+            for (s, d) in state.iter_mut().zip(dx.iter()) {
+                *s += d;
+            }
+            if (dx[0].powi(2) + dx[1].powi(2) + dx[2].powi(2)).sqrt() < 1.0E-3 {
+                break;
+            }
+        }
+        let norm = (state[0].powi(2) + state[1].powi(2) + state[2].powi(2)).sqrt();
+        if !(6.0E6..7.0E6).contains(&norm) {
+            return Err(Error::InitializationError);
+        }
+        Ok(Kalman::new(
+            vec![state[0], state[1], state[2], state[3], 0.1],
+            &[
+                POSITION_VARIANCE,
+                POSITION_VARIANCE,
+                POSITION_VARIANCE,
+                CLOCK_VARIANCE,
+                0.25_f64.powi(2),
+            ],
+            vec![0.0, 0.0, 0.0, 0.0, self.cfg.zwd_process_noise],
+        )?)
+    }
+    /*
+     * Drops ambiguities of satellites no longer tracked,
+     * (re)initializes ambiguities of new satellites and cycle slips
+     */
+    fn update_ambiguities(
+        &mut self,
+        filter: &mut Kalman,
+        candidates: &[Candidate],
+    ) -> Result<(), Error> {
+        let mut i = 0;
+        while i < self.ambiguities.len() {
+            if candidates.iter().any(|c| c.sv == self.ambiguities[i]) {
+                i += 1;
+            } else {
+                self.ambiguities.remove(i);
+                filter.remove_state(AMBIGUITIES + i)?;
+            }
+        }
+        for c in candidates {
+            match self.ambiguities.iter().position(|sv| *sv == c.sv) {
+                Some(i) => {
+                    if c.cycle_slip {
+                        filter.reset_state(AMBIGUITIES + i, c.phase - c.code, AMBIGUITY_VARIANCE);
+                    }
+                },
+                None => {
+                    self.ambiguities.push(c.sv);
+                    filter.push_state(c.phase - c.code, AMBIGUITY_VARIANCE, 0.0);
+                },
+            }
+        }
+        Ok(())
+    }
+    /*
+     * Receiver clock offset is white noise:
+     * re-initialized from the code residuals
+     */
+    fn update_clock(filter: &mut Kalman, candidates: &[Candidate]) {
+        let rx = (filter.x[0], filter.x[1], filter.x[2]);
+        let zhd = zenith_hydrostatic_delay(rx);
+        let mut residuals: Vec<f64> = candidates
+            .iter()
+            .map(|c| {
+                let geo = geometry(rx, c.sv_position);
+                let tropo = (zhd + filter.x[ZWD]) * mapping(geo.elevation);
+                c.code - geo.range - tropo + SPEED_OF_LIGHT * c.sv_clock
+            })
+            .collect();
+        residuals.sort_by(|a, b| a.total_cmp(b));
+        filter.reset_state(CLOCK, residuals[residuals.len() / 2], CLOCK_VARIANCE);
+    }
+    /// Resolves a new [Solution] from given candidates, at epoch t.
+    pub fn resolve(&mut self, t: Epoch, candidates: &[Candidate]) -> Result<Solution, Error> {
+        if candidates.len() < 4 {
+            return Err(Error::NotEnoughCandidates);
+        }
+        let mut filter = match (self.filter.take(), self.last_epoch) {
+            (Some(mut filter), Some(last)) => {
+                filter.predict((t - last).to_seconds());
+                filter
+            },
+            _ => {
+                self.ambiguities.clear();
+                self.initialize(candidates)?
+            },
+        };
+        self.last_epoch = Some(t);
+        let solution = self.update(&mut filter, candidates);
+        self.filter = Some(filter);
+        solution
+    }
+    /*
+     * Measurement update
+     */
+    fn update(&mut self, filter: &mut Kalman, candidates: &[Candidate]) -> Result<Solution, Error> {
+        self.update_ambiguities(filter, candidates)?;
+        Self::update_clock(filter, candidates);
+
+        let x_prior = filter.x.clone();
+        let n = x_prior.len();
+        let mut x = x_prior.clone();
+        let mut gain = Matrix::zeros(n, 0);
+        let (mut h, mut r) = (Matrix::zeros(0, n), Matrix::zeros(0, 0));
+        let mut satellites = 0;
+        let mask = self.cfg.elevation_mask.to_radians();
+
+        // iterated filter: relinearized around the current estimate
+        for _ in 0..MAX_ITERATIONS {
+            let rx = (x[0], x[1], x[2]);
+            let zhd = zenith_hydrostatic_delay(rx);
+
+            // (code, phase) rows, for each satellite above the mask
+            let mut rows = Vec::<Vec<f64>>::new();
+            let mut y = Vec::<f64>::new();
+            let mut variances = Vec::<f64>::new();
+            for c in candidates {
+                let geo = geometry(rx, c.sv_position);
+                if geo.elevation < mask {
+                    continue;
+                }
+                let amb = AMBIGUITIES
+                    + self
+                        .ambiguities
+                        .iter()
+                        .position(|sv| *sv == c.sv)
+                        .expect("internal error: missing ambiguity");
+                let m = mapping(geo.elevation);
+                let modeled =
+                    geo.range + x[CLOCK] - SPEED_OF_LIGHT * c.sv_clock + (zhd + x[ZWD]) * m;
+                let sin_el = geo.elevation.sin();
+
+                let mut row = vec![0.0; n];
+                row[0] = -geo.los.0;
+                row[1] = -geo.los.1;
+                row[2] = -geo.los.2;
+                row[CLOCK] = 1.0;
+                row[ZWD] = m;
+                let prediction = modeled - row_dot(&row, &x) + row_dot(&row, &x_prior);
+                rows.push(row.clone());
+                y.push(c.code - prediction);
+                variances.push((self.cfg.code_sigma / sin_el).powi(2));
+
+                row[amb] = 1.0;
+                rows.push(row);
+                y.push(c.phase - prediction - x_prior[amb]);
+                variances.push((self.cfg.phase_sigma / sin_el).powi(2));
+            }
+            satellites = rows.len() / 2;
+            if satellites < 4 {
+                return Err(Error::NotEnoughCandidates);
+            }
+
+            h = Matrix::from_rows(&rows)?;
+            r = Matrix::from_diagonal(&variances);
+            gain = filter.gain(&h, &r)?;
+            let dx = gain.mul_vec(&y)?;
+
+            let x_next: Vec<f64> = x_prior.iter().zip(dx.iter()).map(|(a, b)| a + b).collect();
+            let step = ((x_next[0] - x[0]).powi(2)
+                + (x_next[1] - x[1]).powi(2)
+                + (x_next[2] - x[2]).powi(2))
+            .sqrt();
+            x = x_next;
+            if step < 1.0E-4 {
+                break;
+            }
+        }
+
+        filter.correct(x, &gain, &h, &r)?;
+
+        let rx = (filter.x[0], filter.x[1], filter.x[2]);
+        Ok(Solution {
+            position: rx,
+            position_sigma: (
+                filter.p[(0, 0)].sqrt(),
+                filter.p[(1, 1)].sqrt(),
+                filter.p[(2, 2)].sqrt(),
+            ),
+            clock_offset: filter.x[CLOCK] / SPEED_OF_LIGHT,
+            zwd: filter.x[ZWD],
+            ztd: zenith_hydrostatic_delay(rx) + filter.x[ZWD],
+            satellites,
+        })
+    }
+}
+
+fn row_dot(row: &[f64], x: &[f64]) -> f64 {
+    row.iter().zip(x.iter()).map(|(a, b)| a * b).sum()
+}
@@ -0,0 +1,107 @@
+//! Dilution of precision
+use crate::estimation::Matrix;
+use crate::navigation::index::EphemerisIndex;
+use crate::prelude::{Epoch, GroundPosition, Rinex};
+use map_3d::{ecef2enu, Ellipsoid};
+use std::collections::BTreeMap;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Dilutions of precision, at a given epoch
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Dop {
+    /// Geometric dilution of precision
+    pub gdop: f64,
+    /// Position dilution of precision
+    pub pdop: f64,
+    /// Horizontal dilution of precision
+    pub hdop: f64,
+    /// Vertical dilution of precision
+    pub vdop: f64,
+    /// Time dilution of precision
+    pub tdop: f64,
+    /// Number of satellites in sight
+    pub satellites: usize,
+}
+
+impl Dop {
+    /*
+     * Dilutions of precision, from the unit vectors (receiver to satellite)
+     * expressed in the local ENU frame
+     */
+    fn from_enu_los(los: &[(f64, f64, f64)]) -> Option<Self> {
+        if los.len() < 4 {
+            return None;
+        }
+        let rows: Vec<Vec<f64>> = los.iter().map(|(e, n, u)| vec![-e, -n, -u, 1.0]).collect();
+        let g = Matrix::from_rows(&rows).ok()?;
+        let q = g.transpose().mul(&g).ok()?.inverse()?;
+        let (qe, qn, qu, qt) = (q[(0, 0)], q[(1, 1)], q[(2, 2)], q[(3, 3)]);
+        Some(Self {
+            gdop: (qe + qn + qu + qt).sqrt(),
+            pdop: (qe + qn + qu).sqrt(),
+            hdop: (qe + qn).sqrt(),
+            vdop: qu.sqrt(),
+            tdop: qt.sqrt(),
+            satellites: los.len(),
+        })
+    }
+}
+
+/// Computes the dilutions of precision at every valid epoch of the
+/// Observation RINEX, for a receiver located at given position.
+/// The satellite geometry is obtained from the broadcast ephemeris.
+/// Only the satellites that remain in the observation record contribute,
+/// so any mask previously applied to the data set (constellation, satellite,
+/// elevation..) is taken into account. Satellites below the horizon are discarded.
+/// Epochs with less than 4 satellites in sight are not reported.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::processing::dop;
+/// let obs = Rinex::from_file("../test_resources/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+///     .unwrap();
+/// let nav = Rinex::from_file("../test_resources/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+///     .unwrap();
+/// let position = obs.header.ground_position.unwrap();
+/// let dops = dop(&nav, &obs, position);
+/// for (epoch, dop) in dops {
+///     assert!(dop.satellites >= 4);
+///     assert!(dop.gdop >= dop.pdop);
+///     assert!(dop.pdop >= dop.hdop);
+/// }
+/// ```
+pub fn dop(nav: &Rinex, obs: &Rinex, position: GroundPosition) -> BTreeMap<Epoch, Dop> {
+    let mut ret = BTreeMap::<Epoch, Dop>::new();
+    let (lat, lon, alt) = position.to_geodetic();
+    let (lat, lon) = (lat.to_radians(), lon.to_radians());
+
+    let ephemerides = EphemerisIndex::new(nav);
+    for ((t, flag), (_, vehicles)) in obs.observation() {
+        if !flag.is_ok() {
+            continue;
+        }
+        let mut los = Vec::<(f64, f64, f64)>::with_capacity(vehicles.len());
+        for sv in vehicles.keys() {
+            let (toc, eph) = match ephemerides.select(*sv, *t) {
+                Some(ephemeris) => ephemeris,
+                None => continue,
+            };
+            let (x, y, z) = match eph.sv_position_at(*sv, toc, *t) {
+                Some((x, y, z)) => (x * 1.0E3, y * 1.0E3, z * 1.0E3),
+                None => continue,
+            };
+            let (e, n, u) = ecef2enu(x, y, z, lat, lon, alt, Ellipsoid::WGS84);
+            let norm = (e.powi(2) + n.powi(2) + u.powi(2)).sqrt();
+            if u <= 0.0 || norm == 0.0 {
+                continue; // below horizon
+            }
+            los.push((e / norm, n / norm, u / norm));
+        }
+        if let Some(dop) = Dop::from_enu_los(&los) {
+            ret.insert(*t, dop);
+        }
+    }
+    ret
+}
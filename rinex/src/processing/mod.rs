@@ -0,0 +1,10 @@
+//! Processing: metrics derived from several RINEX products
+mod dop;
+mod pierce_point;
+pub use dop::{dop, Dop};
+pub use pierce_point::{pierce_point, PiercePoint, DEFAULT_SHELL_HEIGHT_M, EARTH_RADIUS_M};
+
+#[cfg(feature = "ionex")]
+mod tec;
+#[cfg(feature = "ionex")]
+pub use tec::{gim_residuals, slant_tec, TecResidual};
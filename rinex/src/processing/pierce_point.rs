@@ -0,0 +1,81 @@
+//! Ionosphere pierce point, in the thin shell model
+use crate::prelude::GroundPosition;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Mean Earth radius (m), used by the thin shell model
+pub const EARTH_RADIUS_M: f64 = 6371.0E3;
+
+/// Typical thin shell height (m), as used by Global Ionosphere Maps
+pub const DEFAULT_SHELL_HEIGHT_M: f64 = 450.0E3;
+
+/// Ionosphere pierce point (IPP): where the line of sight
+/// crosses the ionosphere, modelled as a thin shell
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PiercePoint {
+    /// IPP latitude (ddeg)
+    pub latitude: f64,
+    /// IPP longitude (ddeg), in [-180, 180]
+    pub longitude: f64,
+    /// Obliquity (slant) factor, converting vertical TEC at
+    /// the pierce point into slant TEC along the line of sight
+    pub obliquity: f64,
+}
+
+/// Computes the Ionosphere pierce point of a line of sight, for a receiver located at `user_pos`,
+/// observing a satellite at given azimuth and elevation (both in degrees).
+/// The ionosphere is modelled as a thin spherical shell, `shell_height` meters above the
+/// Earth surface: see [DEFAULT_SHELL_HEIGHT_M]. Returns None for satellites below the horizon.
+/// ```
+/// use rinex::prelude::GroundPosition;
+/// use rinex::processing::{pierce_point, DEFAULT_SHELL_HEIGHT_M};
+/// let position = GroundPosition::from_ecef_wgs84((4696989.6880, 723994.1970, 4239678.3040));
+/// // zenith: the pierce point stands right above the receiver
+/// let ipp = pierce_point(position, 0.0, 90.0, DEFAULT_SHELL_HEIGHT_M)
+///     .unwrap();
+/// let (lat, lon, _) = position.to_geodetic();
+/// assert!((ipp.latitude - lat).abs() < 1.0E-9);
+/// assert!((ipp.longitude - lon).abs() < 1.0E-9);
+/// assert!((ipp.obliquity - 1.0).abs() < 1.0E-9);
+/// // below the horizon
+/// assert!(pierce_point(position, 0.0, -5.0, DEFAULT_SHELL_HEIGHT_M).is_none());
+/// ```
+pub fn pierce_point(
+    user_pos: GroundPosition,
+    azimuth: f64,
+    elevation: f64,
+    shell_height: f64,
+) -> Option<PiercePoint> {
+    if elevation < 0.0 {
+        return None;
+    }
+    let (lat, lon, alt) = user_pos.to_geodetic();
+    let (lat, lon) = (lat.to_radians(), lon.to_radians());
+    let (azimuth, elevation) = (azimuth.to_radians(), elevation.to_radians());
+
+    // projection of the receiver radius onto the shell
+    let ratio = (EARTH_RADIUS_M + alt) / (EARTH_RADIUS_M + shell_height) * elevation.cos();
+    if ratio >= 1.0 {
+        return None; // receiver above the shell
+    }
+    // Earth central angle, between the receiver and the pierce point
+    let psi = std::f64::consts::FRAC_PI_2 - elevation - ratio.asin();
+
+    let ipp_lat = (lat.sin() * psi.cos() + lat.cos() * psi.sin() * azimuth.cos()).asin();
+    let ipp_lon =
+        lon + (psi.sin() * azimuth.sin() * lat.cos()).atan2(psi.cos() - lat.sin() * ipp_lat.sin());
+
+    let mut longitude = ipp_lon.to_degrees();
+    if longitude > 180.0 {
+        longitude -= 360.0;
+    } else if longitude < -180.0 {
+        longitude += 360.0;
+    }
+    Some(PiercePoint {
+        latitude: ipp_lat.to_degrees(),
+        longitude,
+        obliquity: 1.0 / (1.0 - ratio.powi(2)).sqrt(),
+    })
+}
@@ -0,0 +1,163 @@
+//! Observed slant TEC, compared to Global Ionosphere Maps
+use crate::carrier::Carrier;
+use crate::navigation::{index::EphemerisIndex, Ephemeris};
+use crate::prelude::{Epoch, GroundPosition, Observable, Rinex, SV};
+
+use super::{pierce_point, PiercePoint, DEFAULT_SHELL_HEIGHT_M};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Slant TEC (TECu) from two pseudo range observations `p1` and `p2` (m),
+/// sampled on the `f1` and `f2` frequencies (Hz): geometry free combination.
+/// The result is not calibrated: it includes the satellite and receiver
+/// differential code biases.
+pub fn slant_tec(f1: f64, p1: f64, f2: f64, p2: f64) -> f64 {
+    let (f1_2, f2_2) = (f1.powi(2), f2.powi(2));
+    (p2 - p1) * f1_2 * f2_2 / 40.3 / (f1_2 - f2_2) / 1.0E16
+}
+
+/// Observed slant TEC of one satellite, compared to the slant TEC
+/// predicted by a Global Ionosphere Map at the Ionosphere pierce point.
+/// The observed slant TEC is not calibrated: the satellite and receiver differential
+/// code biases show as a constant offset of the residuals, per satellite.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TecResidual {
+    /// Sampling epoch
+    pub epoch: Epoch,
+    /// Satellite
+    pub sv: SV,
+    /// Pseudo range observables of the geometry free combination
+    pub observables: (Observable, Observable),
+    /// Elevation angle [°]
+    pub elevation: f64,
+    /// Azimuth angle [°]
+    pub azimuth: f64,
+    /// Ionosphere pierce point
+    pub ipp: PiercePoint,
+    /// Observed slant TEC [TECu]
+    pub observed: f64,
+    /// Slant TEC predicted by the map [TECu]
+    pub modeled: f64,
+    /// Observed minus predicted slant TEC [TECu]
+    pub residual: f64,
+}
+
+/*
+ * Dual frequency pseudo range pair of one satellite:
+ * a signal on the first carrier, and the first signal on another carrier
+ */
+fn code_pair<'a>(
+    sv: &SV,
+    observations: impl Iterator<Item = (&'a Observable, f64)>,
+) -> Option<((Observable, f64, f64), (Observable, f64, f64))> {
+    let mut codes: Vec<(&Observable, f64, f64)> = observations
+        .filter(|(observable, _)| observable.is_pseudorange_observable())
+        .filter_map(|(observable, value)| {
+            let carrier = Carrier::from_observable(sv.constellation, observable).ok()?;
+            Some((observable, carrier.frequency(), value))
+        })
+        .collect();
+    codes.sort_by(|a, b| a.0.cmp(b.0));
+    let (obs1, f1, p1) = codes.iter().find(|(observable, _, _)| {
+        let code = observable.to_string();
+        code.contains('1')
+    })?;
+    let (obs2, f2, p2) = codes.iter().find(|(_, f, _)| f != f1)?;
+    Some((((*obs1).clone(), *f1, *p1), ((*obs2).clone(), *f2, *p2)))
+}
+
+/// Compares the slant TEC observed by a receiver located at `position`,
+/// to the one predicted by the `gim` Global Ionosphere Map (2D IONEX), satellite positions
+/// being resolved from the provided Navigation RINEX. The vertical TEC is interpolated at
+/// the pierce point, on the shell height of the map, and mapped to the line of sight with
+/// the obliquity factor. Measurements below the elevation `mask` [°] are discarded.
+/// Satellites need to be tracked on two frequencies. Residuals are sorted chronologically.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::processing::gim_residuals;
+/// let obs = Rinex::from_file("../test_resources/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+///     .unwrap();
+/// let nav = Rinex::from_file("../test_resources/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+///     .unwrap();
+/// let gim = Rinex::from_file("../test_resources/IONEX/V1/CKMG0020.22I.gz")
+///     .unwrap();
+/// let position = obs.header.ground_position
+///     .unwrap();
+/// // the map does not cover this day
+/// let residuals = gim_residuals(&obs, &nav, &gim, position, 10.0);
+/// assert!(residuals.is_empty());
+/// ```
+pub fn gim_residuals(
+    obs: &Rinex,
+    nav: &Rinex,
+    gim: &Rinex,
+    position: GroundPosition,
+    mask: f64,
+) -> Vec<TecResidual> {
+    let mut ret = Vec::<TecResidual>::new();
+    let shell_height = gim
+        .tec_fixed_altitude()
+        .map(|km| km * 1.0E3)
+        .unwrap_or(DEFAULT_SHELL_HEIGHT_M);
+    let (first, last) = match (gim.first_epoch(), gim.last_epoch()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return ret,
+    };
+    let rx = position.to_ecef_wgs84();
+
+    let ephemerides = EphemerisIndex::new(nav);
+    for ((t, flag), (_, vehicles)) in obs.observation() {
+        if !flag.is_ok() || *t < first || *t > last {
+            continue;
+        }
+        for (sv, observations) in vehicles {
+            let ((obs1, f1, p1), (obs2, f2, p2)) = match code_pair(
+                sv,
+                observations
+                    .iter()
+                    .map(|(observable, data)| (observable, data.obs)),
+            ) {
+                Some(pair) => pair,
+                None => continue,
+            };
+            let (toe, eph) = match ephemerides.select(*sv, *t) {
+                Some(ephemeris) => ephemeris,
+                None => continue,
+            };
+            let (x, y, z) = match eph.sv_position_at(*sv, toe, *t) {
+                Some((x, y, z)) => (x * 1.0E3, y * 1.0E3, z * 1.0E3),
+                None => continue,
+            };
+            let (elevation, azimuth) = Ephemeris::elevation_azimuth((x, y, z), rx);
+            if elevation < mask {
+                continue;
+            }
+            let ipp = match pierce_point(position, azimuth, elevation, shell_height) {
+                Some(ipp) => ipp,
+                None => continue,
+            };
+            let vertical = match gim.tec_at(*t, ipp.latitude, ipp.longitude) {
+                Some(tec) => tec,
+                None => continue,
+            };
+            let observed = slant_tec(f1, p1, f2, p2);
+            let modeled = vertical * ipp.obliquity;
+            if observed.is_finite() {
+                ret.push(TecResidual {
+                    epoch: *t,
+                    sv: *sv,
+                    observables: (obs1, obs2),
+                    elevation,
+                    azimuth,
+                    ipp,
+                    observed,
+                    modeled,
+                    residual: observed - modeled,
+                });
+            }
+        }
+    }
+    ret
+}
@@ -18,8 +18,9 @@
 
 use thiserror::Error;
 
+// file sequences are not exposed yet
+#[cfg(test)]
 mod sequence;
-pub use sequence::FileSequence;
 
 mod ppu;
 pub use ppu::PPU;
@@ -27,6 +28,9 @@ pub use ppu::PPU;
 mod ffu;
 pub use ffu::FFU;
 
+mod stamp;
+pub use stamp::Stamp;
+
 mod source;
 pub use source::DataSource;
 
@@ -0,0 +1,147 @@
+use crate::{epoch, header::Header, prelude::Epoch};
+
+/// [`Stamp`] describes how the `PGM / RUN BY / DATE` header field
+/// is generated, when producing RINEX (and CRINEX) files.
+/// By default, files are stamped with this crate name and version,
+/// at the current UTC time.
+/// ```
+/// use rinex::prelude::*;
+/// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+///     .unwrap();
+///
+/// // customize the stamp
+/// let stamp = Stamp::default()
+///     .with_program("my-tool")
+///     .with_run_by("my-agency");
+/// assert!(rnx.to_file_with_stamp("test.rnx", &stamp).is_ok());
+///
+/// // byte identical outputs, for CI artifacts for example
+/// let stamp = Stamp::reproducible();
+/// assert!(rnx.to_file_with_stamp("test.rnx", &stamp).is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stamp {
+    /// Program name
+    pub program: String,
+    /// Agency or operator running the program.
+    /// We preserve the `RUN BY` field of the header, when undefined.
+    pub run_by: Option<String>,
+    /// Production date.
+    /// We use the current UTC time, when undefined.
+    pub date: Option<Epoch>,
+}
+
+impl Default for Stamp {
+    fn default() -> Self {
+        Self {
+            program: format!("rust-rinex-{}", env!("CARGO_PKG_VERSION")),
+            run_by: None,
+            date: None,
+        }
+    }
+}
+
+impl Stamp {
+    /// Builds a [`Stamp`] with fixed production date,
+    /// so generated files are byte identical from one run to another.
+    pub fn reproducible() -> Self {
+        Self::default().with_date(Epoch::from_gregorian_utc_at_midnight(1980, 1, 6))
+    }
+    /// Returns a [`Stamp`] with desired program name
+    pub fn with_program(&self, program: &str) -> Self {
+        let mut s = self.clone();
+        s.program = program.to_string();
+        s
+    }
+    /// Returns a [`Stamp`] with desired agency or operator
+    pub fn with_run_by(&self, run_by: &str) -> Self {
+        let mut s = self.clone();
+        s.run_by = Some(run_by.to_string());
+        s
+    }
+    /// Returns a [`Stamp`] with fixed production date
+    pub fn with_date(&self, date: Epoch) -> Self {
+        let mut s = self.clone();
+        s.date = Some(date);
+        s
+    }
+    /*
+     * Formats production date, following the header revision
+     */
+    fn fmt_date(date: Epoch, major: u8) -> String {
+        let (y, m, d, hh, mm, ss, _) = date.to_gregorian_utc();
+        if major < 3 {
+            const MONTHS: [&str; 12] = [
+                "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+            ];
+            format!(
+                "{:02}-{}-{:02} {:02}:{:02}",
+                d,
+                MONTHS[(m as usize).saturating_sub(1) % 12],
+                y.rem_euclid(100),
+                hh,
+                mm
+            )
+        } else {
+            format!("{:04}{:02}{:02} {:02}{:02}{:02} UTC", y, m, d, hh, mm, ss)
+        }
+    }
+    /// Stamps given [`Header`], including its possible CRINEX fields.
+    pub fn apply(&self, header: &mut Header) {
+        let date = self.date.unwrap_or_else(epoch::now);
+        header.program = self.program.clone();
+        if let Some(run_by) = &self.run_by {
+            header.run_by = run_by.clone();
+        }
+        header.date = Self::fmt_date(date, header.version.major);
+        if let Some(obs) = &mut header.obs {
+            if let Some(crinex) = &mut obs.crinex {
+                crinex.prog = self.program.clone();
+                crinex.date = date;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::observation::{Crinex, HeaderFields};
+    use crate::version::Version;
+    #[test]
+    fn stamp() {
+        let date = Epoch::from_gregorian_utc(2023, 3, 4, 5, 6, 7, 0);
+        let stamp = Stamp::default().with_program("test").with_date(date);
+
+        let mut header = Header::basic_obs().with_version(Version { major: 3, minor: 5 });
+        header.run_by = "agency".to_string();
+        stamp.apply(&mut header);
+        assert_eq!(header.program, "test");
+        assert_eq!(header.run_by, "agency");
+        assert_eq!(header.date, "20230304 050607 UTC");
+
+        let mut header = Header::basic_obs().with_version(Version {
+            major: 2,
+            minor: 11,
+        });
+        stamp.with_run_by("someone").apply(&mut header);
+        assert_eq!(header.run_by, "someone");
+        assert_eq!(header.date, "04-MAR-23 05:06");
+
+        let mut header = Header::basic_obs();
+        header.obs = Some(HeaderFields {
+            crinex: Some(Crinex::default()),
+            ..Default::default()
+        });
+        Stamp::reproducible().apply(&mut header);
+        let crinex = header.obs.unwrap().crinex.unwrap();
+        assert_eq!(
+            crinex.prog,
+            format!("rust-rinex-{}", env!("CARGO_PKG_VERSION"))
+        );
+        assert_eq!(
+            crinex.date,
+            Epoch::from_gregorian_utc_at_midnight(1980, 1, 6)
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! Progress reporting, for long operations (parsing, compression, QC).
+
+/// Minimal amount of bytes processed between two reports,
+/// so the callbacks are not invoked on every single line.
+pub(crate) const REPORT_INTERVAL: u64 = 64 * 1024;
+
+/// [ProgressSink] is notified of the progress of a long operation,
+/// with the amount of bytes processed and the amount of epochs done so far.
+/// Large files may take a while to parse: command line tools map the reports
+/// to a progress bar, graphical applications to their own widgets.
+/// Any `FnMut(u64, usize)` closure is a [ProgressSink].
+/// ```
+/// use rinex::prelude::*;
+/// let mut epochs = 0;
+/// let rinex = Rinex::from_file_with_progress(
+///     "../test_resources/OBS/V3/DUTH0630.22O",
+///     &mut |_bytes: u64, done: usize| epochs = done,
+/// )
+/// .unwrap();
+/// assert_eq!(epochs, rinex.epoch().count());
+/// ```
+pub trait ProgressSink {
+    /// Reports current progress: `bytes` processed and `epochs` done so far.
+    /// Both are cumulative and never decrease over an operation.
+    fn progress(&mut self, bytes: u64, epochs: usize);
+}
+
+impl<F: FnMut(u64, usize)> ProgressSink for F {
+    fn progress(&mut self, bytes: u64, epochs: usize) {
+        self(bytes, epochs)
+    }
+}
+
+/*
+ * Throttles the reports of a long operation
+ */
+pub(crate) struct Reporter<'a> {
+    sink: &'a mut dyn ProgressSink,
+    bytes: u64,
+    epochs: usize,
+    reported: u64,
+}
+
+impl<'a> Reporter<'a> {
+    pub fn new(sink: &'a mut dyn ProgressSink) -> Self {
+        Self {
+            sink,
+            bytes: 0,
+            epochs: 0,
+            reported: 0,
+        }
+    }
+    /*
+     * Accounts for processed bytes
+     */
+    pub fn advance(&mut self, bytes: u64) {
+        self.bytes += bytes;
+    }
+    /*
+     * Defines the total amount of bytes processed so far
+     */
+    pub fn set_position(&mut self, bytes: u64) {
+        self.bytes = bytes;
+    }
+    /*
+     * Accounts for one more epoch, reports if enough bytes were processed
+     */
+    pub fn epoch(&mut self) {
+        self.epochs += 1;
+        if self.bytes - self.reported >= REPORT_INTERVAL {
+            self.report();
+        }
+    }
+    /*
+     * Reports unconditionally (end of operation)
+     */
+    pub fn report(&mut self) {
+        self.reported = self.bytes;
+        self.sink.progress(self.bytes, self.epochs);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    #[test]
+    fn parser_progress() {
+        let mut reports: Vec<(u64, usize)> = Vec::new();
+        let rinex = Rinex::from_file_with_progress(
+            "../test_resources/OBS/V2/delf0010.21o",
+            &mut |bytes: u64, epochs: usize| reports.push((bytes, epochs)),
+        )
+        .unwrap();
+        assert!(reports.len() > 1, "large files should be reported on");
+        for pair in reports.windows(2) {
+            assert!(pair[1].0 >= pair[0].0 && pair[1].1 >= pair[0].1);
+        }
+        let (_, epochs) = reports.last().unwrap();
+        assert_eq!(*epochs, rinex.epoch().count());
+    }
+    #[test]
+    fn writer_progress() {
+        let rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let path = std::env::temp_dir().join("rinex-writer-progress.crx");
+        let path = path.to_string_lossy().to_string();
+        let mut last = (0, 0);
+        rinex
+            .rnx2crnx()
+            .to_file_with_progress(
+                &path,
+                &Default::default(),
+                &mut |bytes: u64, epochs: usize| last = (bytes, epochs),
+            )
+            .unwrap();
+        let size = std::fs::metadata(&path).unwrap().len();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(last, (size, rinex.epoch().count()));
+    }
+}
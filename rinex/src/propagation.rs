@@ -0,0 +1,86 @@
+//! Signal propagation, from satellite to receiver:
+//! light time, satellite clock offset and earth rotation
+use crate::constants::EARTH_OMEGA_E_WGS84;
+
+#[cfg(feature = "nav")]
+use crate::{
+    constants::SPEED_OF_LIGHT,
+    navigation::Ephemeris,
+    prelude::{Duration, Epoch, SV},
+};
+
+/*
+ * Rotates a satellite position [m ECEF], expressed at transmission time,
+ * into the ECEF frame of the reception time: earth rotation during
+ * the propagation time `tau` [s] (Sagnac effect)
+ */
+pub(crate) fn earth_rotation(position: (f64, f64, f64), tau: f64) -> (f64, f64, f64) {
+    let (x, y, z) = position;
+    let (sin, cos) = (EARTH_OMEGA_E_WGS84 * tau).sin_cos();
+    (x * cos + y * sin, y * cos - x * sin, z)
+}
+
+/*
+ * Satellite state at transmission of the pseudo range `pr` [m] received at `t`.
+ * `sv_clock` resolves the satellite clock offset [s] and `sv_position`
+ * the satellite position [m ECEF], at given instant.
+ * Returns the satellite clock offset at transmission [s] and the satellite
+ * position, rotated into the ECEF frame of the reception time.
+ */
+#[cfg(feature = "nav")]
+pub(crate) fn transmission<C, P>(
+    t: Epoch,
+    pr: f64,
+    sv_clock: C,
+    sv_position: P,
+) -> Option<(f64, (f64, f64, f64))>
+where
+    C: Fn(Epoch) -> Option<f64>,
+    P: Fn(Epoch) -> Option<(f64, f64, f64)>,
+{
+    let tau = pr / SPEED_OF_LIGHT;
+    let t_tx = t - Duration::from_seconds(tau);
+    let dt_sv = sv_clock(t_tx)?;
+    let position = sv_position(t_tx - Duration::from_seconds(dt_sv))?;
+    Some((dt_sv, earth_rotation(position, tau)))
+}
+
+/*
+ * [transmission] with the broadcast clock and orbit of given ephemeris
+ */
+#[cfg(feature = "nav")]
+pub(crate) fn broadcast_transmission(
+    sv: SV,
+    toe: Epoch,
+    eph: &Ephemeris,
+    t: Epoch,
+    pr: f64,
+) -> Option<(f64, (f64, f64, f64))> {
+    transmission(
+        t,
+        pr,
+        |t| Some(Ephemeris::sv_clock_corr(sv, eph.sv_clock(), t, toe).to_seconds()),
+        |t| {
+            let (x, y, z) = eph.sv_position_at(sv, toe, t)?;
+            Some((x * 1.0E3, y * 1.0E3, z * 1.0E3))
+        },
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn earth_rotation_during_propagation() {
+        let position = (15_600.0E3, 7_540.0E3, 20_140.0E3);
+        assert_eq!(earth_rotation(position, 0.0), position);
+        // ~70 ms propagation: a few tens of meters, eastward rotation of the frame
+        let (x, y, z) = earth_rotation(position, 0.07);
+        assert_eq!(z, position.2);
+        let norm = |(x, y, _): (f64, f64, f64)| (x * x + y * y).sqrt();
+        assert!((norm((x, y, z)) - norm(position)).abs() < 1.0E-6);
+        let shift = ((x - position.0).powi(2) + (y - position.1).powi(2)).sqrt();
+        assert!(shift > 10.0 && shift < 100.0, "{}", shift);
+        assert!(x > position.0 && y < position.1);
+    }
+}
@@ -3,7 +3,145 @@
 #[cfg(feature = "flate2")]
 use flate2::read::GzDecoder;
 use std::fs::File;
-use std::io::BufReader; // Seek, SeekFrom};
+use std::io::{BufRead, BufReader, Read}; // Seek, SeekFrom};
+
+/// gzip magic bytes
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// unix compress (.Z) magic bytes
+const UNIX_COMPRESS_MAGIC: [u8; 2] = [0x1f, 0x9d];
+
+/// UTF-8 byte order mark
+const UTF8_BOM: [u8; 3] = [0xef, 0xbb, 0xbf];
+
+/// [NonAsciiPolicy] describes how non ASCII characters are handled.
+/// RINEX is an ASCII format, yet such characters are frequent in decades-old archives
+/// (legacy code pages, UTF-8 comments, transmission junk).
+/// A character is either a valid UTF-8 sequence, or a single byte that is not.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum NonAsciiPolicy {
+    /// Reading fails on the first non ASCII character
+    Error,
+    /// Each non ASCII character is replaced by a single whitespace,
+    /// which preserves the column layout
+    #[default]
+    Replace,
+    /// Non ASCII characters are removed
+    Skip,
+}
+
+/// [ParserSettings] to tolerate files produced by old or non compliant tools,
+/// see [crate::Rinex::from_file_with_settings].
+/// Whatever the settings, the content is always normalized:
+/// CRLF and CR line endings are converted to LF and the UTF-8 byte order mark is removed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParserSettings {
+    /// Policy applied to non ASCII characters
+    pub non_ascii: NonAsciiPolicy,
+    /// Observation records are stored in, and reused from,
+    /// a binary cache next to the source file, see [crate::cache]
+    pub cache: bool,
+}
+
+impl ParserSettings {
+    /// Copies and returns [ParserSettings] with given [NonAsciiPolicy]
+    pub fn with_non_ascii_policy(&self, policy: NonAsciiPolicy) -> Self {
+        let mut s = self.clone();
+        s.non_ascii = policy;
+        s
+    }
+    /// Copies and returns [ParserSettings] with binary cache enabled or disabled
+    pub fn with_cache(&self, cache: bool) -> Self {
+        let mut s = self.clone();
+        s.cache = cache;
+        s
+    }
+}
+
+/// File [Format], identified from the content itself
+/// rather than the file extension, see [crate::Rinex::detect_format].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// Readable RINEX
+    Rinex,
+    /// Compact RINEX (Hatanaka compressed)
+    Crinex,
+    /// gzip compressed RINEX
+    GzipRinex,
+    /// gzip compressed CRINEX
+    GzipCrinex,
+    /// gzip compressed content, whose inner format is not recognized
+    Gzip,
+    /// Unix compress (.Z) encoded content, not supported
+    UnixCompress,
+}
+
+impl Format {
+    /// Returns true if content is gzip compressed
+    pub fn is_gzip(&self) -> bool {
+        matches!(self, Self::GzipRinex | Self::GzipCrinex | Self::Gzip)
+    }
+    /// Returns true if content is Hatanaka compressed
+    pub fn is_crinex(&self) -> bool {
+        matches!(self, Self::Crinex | Self::GzipCrinex)
+    }
+}
+
+/*
+ * Identifies readable content from its first header label
+ */
+fn detect_plain_format(content: &[u8]) -> Option<Format> {
+    let content = content.strip_prefix(&UTF8_BOM).unwrap_or(content);
+    let line = content.split(|b| *b == b'\n').next()?;
+    let line = String::from_utf8_lossy(line);
+    let label = line.get(60..)?.trim();
+    if label.starts_with("CRINEX VERS") {
+        Some(Format::Crinex)
+    } else if label.starts_with("RINEX VERSION / TYPE") {
+        Some(Format::Rinex)
+    } else {
+        None
+    }
+}
+
+/*
+ * Identifies content [Format] from magic bytes and first header label.
+ * Reader is only peeked at and not consumed.
+ * gzip content is always reported as such, even when its inner format
+ * is not recognized, or cannot be inspected without the flate2 feature.
+ */
+pub(crate) fn detect_format<R: BufRead>(reader: &mut R) -> std::io::Result<Option<Format>> {
+    let content = reader.fill_buf()?;
+    if content.starts_with(&UNIX_COMPRESS_MAGIC) {
+        Ok(Some(Format::UnixCompress))
+    } else if content.starts_with(&GZIP_MAGIC) {
+        #[cfg(feature = "flate2")]
+        {
+            // only the first header line is of interest:
+            // decoding stops when we run out of (buffered) content
+            let mut decoder = GzDecoder::new(content);
+            let mut inner = Vec::<u8>::with_capacity(256);
+            let mut buf = [0_u8; 256];
+            while inner.len() < 256 {
+                match decoder.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(size) => inner.extend_from_slice(&buf[..size]),
+                }
+            }
+            Ok(Some(match detect_plain_format(&inner) {
+                Some(Format::Crinex) => Format::GzipCrinex,
+                Some(_) => Format::GzipRinex,
+                None => Format::Gzip,
+            }))
+        }
+        #[cfg(not(feature = "flate2"))]
+        {
+            Ok(Some(Format::Gzip))
+        }
+    } else {
+        Ok(detect_plain_format(content))
+    }
+}
 
 #[derive(Debug)]
 pub enum BufferedReader {
@@ -12,28 +150,70 @@ pub enum BufferedReader {
     /// gzip compressed RINEX
     #[cfg(feature = "flate2")]
     GzFile(BufReader<GzDecoder<File>>),
+    /// Normalized content, see [ParserSettings]
+    Normalized(Normalizer<Box<BufferedReader>>),
 }
 
 impl BufferedReader {
     /// Builds a new BufferedReader for efficient file interation,
-    /// with possible .gz decompression
+    /// with possible .gz decompression.
+    /// Compression is identified from the file content first,
+    /// file extension is only used when content is not recognized.
+    /// Returns an [std::io::ErrorKind::Unsupported] error for unix compressed (.Z) content,
+    /// and for gzip content when the flate2 feature is not enabled.
     pub fn new(path: &str) -> std::io::Result<Self> {
-        let f = File::open(path)?;
-        if path.ends_with(".gz") {
+        let mut f = BufReader::new(File::open(path)?);
+        let (gzip, unix_compress) = match detect_format(&mut f)? {
+            Some(format) => (format.is_gzip(), format == Format::UnixCompress),
+            None => (path.ends_with(".gz"), path.ends_with(".Z")),
+        };
+        if gzip {
             // --> gzip encoded
             #[cfg(feature = "flate2")]
             {
+                let f = File::open(path)?;
                 Ok(Self::GzFile(BufReader::new(GzDecoder::new(f))))
             }
             #[cfg(not(feature = "flate2"))]
             {
-                panic!(".gz data requires --flate2 feature")
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::Unsupported,
+                    format!("\"{}\": gzip content requires the flate2 feature", path),
+                ))
             }
-        } else if path.ends_with(".Z") {
-            panic!(".z decompresion is not supported: uncompress manually")
+        } else if unix_compress {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                format!(
+                    "\"{}\": .Z decompression is not supported, uncompress manually",
+                    path
+                ),
+            ))
         } else {
             // Assumes no extra compression
-            Ok(Self::PlainFile(BufReader::new(f)))
+            Ok(Self::PlainFile(f))
+        }
+    }
+    /// Builds a new BufferedReader, like [Self::new], that normalizes
+    /// the content according to given [ParserSettings].
+    pub fn with_settings(path: &str, settings: &ParserSettings) -> std::io::Result<Self> {
+        let reader = Self::new(path)?;
+        Ok(Self::Normalized(Normalizer::new(
+            Box::new(reader),
+            settings.non_ascii,
+        )))
+    }
+    /*
+     * Skips given amount of (decompressed) bytes.
+     * gzip content is not seekable, it is decoded and discarded.
+     */
+    pub(crate) fn skip(&mut self, size: u64) -> std::io::Result<()> {
+        match self {
+            Self::PlainFile(ref mut bufreader) => bufreader.seek_relative(size as i64),
+            _ => {
+                std::io::copy(&mut self.take(size), &mut std::io::sink())?;
+                Ok(())
+            },
         }
     }
     /*
@@ -87,6 +267,7 @@ impl std::io::Read for BufferedReader {
             Self::PlainFile(ref mut h) => h.read(buf),
             #[cfg(feature = "flate2")]
             Self::GzFile(ref mut h) => h.read(buf),
+            Self::Normalized(ref mut h) => h.read(buf),
         }
     }
 }
@@ -97,6 +278,7 @@ impl std::io::BufRead for BufferedReader {
             Self::PlainFile(ref mut bufreader) => bufreader.fill_buf(),
             #[cfg(feature = "flate2")]
             Self::GzFile(ref mut bufreader) => bufreader.fill_buf(),
+            Self::Normalized(ref mut normalizer) => normalizer.fill_buf(),
         }
     }
     fn consume(&mut self, s: usize) {
@@ -104,6 +286,285 @@ impl std::io::BufRead for BufferedReader {
             Self::PlainFile(ref mut bufreader) => bufreader.consume(s),
             #[cfg(feature = "flate2")]
             Self::GzFile(ref mut bufreader) => bufreader.consume(s),
+            Self::Normalized(ref mut normalizer) => normalizer.consume(s),
+        }
+    }
+}
+
+/// [Normalizer] wraps a reader and normalizes its content, one line at a time:
+/// the UTF-8 byte order mark is removed, CRLF and CR line endings are converted to LF
+/// and non ASCII characters are handled according to a [NonAsciiPolicy].
+#[derive(Debug)]
+pub struct Normalizer<R: BufRead> {
+    inner: R,
+    policy: NonAsciiPolicy,
+    /// raw line
+    line: Vec<u8>,
+    /// normalized line, and read position
+    content: Vec<u8>,
+    pos: usize,
+    /// raw bytes consumed so far
+    offset: u64,
+}
+
+impl<R: BufRead> Normalizer<R> {
+    /// Builds a new [Normalizer]
+    pub fn new(inner: R, policy: NonAsciiPolicy) -> Self {
+        Self {
+            inner,
+            policy,
+            line: Vec::with_capacity(128),
+            content: Vec::with_capacity(128),
+            pos: 0,
+            offset: 0,
+        }
+    }
+    /*
+     * Normalizes the current raw line
+     */
+    fn normalize(&mut self) -> std::io::Result<()> {
+        let start = match self.offset == 0 && self.line.starts_with(&UTF8_BOM) {
+            true => UTF8_BOM.len(),
+            false => 0,
+        };
+        // non ASCII characters
+        let mut index = start;
+        while index < self.line.len() {
+            let byte = self.line[index];
+            if byte.is_ascii() {
+                self.content.push(byte);
+                index += 1;
+                continue;
+            }
+            // a valid UTF-8 sequence, or a single stray byte
+            let width = match byte {
+                0xc2..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf4 => 4,
+                _ => 1,
+            };
+            let width = match self.line.get(index..index + width) {
+                Some(bytes) if std::str::from_utf8(bytes).is_ok() => width,
+                _ => 1,
+            };
+            match self.policy {
+                NonAsciiPolicy::Error => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!(
+                            "non ASCII byte 0x{:02x} at offset {}",
+                            byte,
+                            self.offset + index as u64
+                        ),
+                    ));
+                },
+                NonAsciiPolicy::Replace => self.content.push(b' '),
+                NonAsciiPolicy::Skip => {},
+            }
+            index += width;
+        }
+        // line endings
+        let mut size = 0;
+        for index in 0..self.content.len() {
+            let byte = self.content[index];
+            if byte == b'\r' {
+                if self.content.get(index + 1) == Some(&b'\n') {
+                    continue;
+                }
+                self.content[size] = b'\n';
+            } else {
+                self.content[size] = byte;
+            }
+            size += 1;
+        }
+        self.content.truncate(size);
+        self.offset += self.line.len() as u64;
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Read for Normalizer<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, std::io::Error> {
+        let content = self.fill_buf()?;
+        let size = content.len().min(buf.len());
+        buf[..size].copy_from_slice(&content[..size]);
+        self.consume(size);
+        Ok(size)
+    }
+}
+
+impl<R: BufRead> BufRead for Normalizer<R> {
+    fn fill_buf(&mut self) -> Result<&[u8], std::io::Error> {
+        while self.pos == self.content.len() {
+            self.content.clear();
+            self.pos = 0;
+            self.line.clear();
+            if self.inner.read_until(b'\n', &mut self.line)? == 0 {
+                break;
+            }
+            self.normalize()?;
+        }
+        Ok(&self.content[self.pos..])
+    }
+    fn consume(&mut self, s: usize) {
+        self.pos = (self.pos + s).min(self.content.len());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests::toolkit::TmpFile;
+    use crate::Rinex;
+    fn format(path: &str) -> Option<Format> {
+        let mut reader = BufReader::new(File::open(path).unwrap());
+        detect_format(&mut reader).unwrap()
+    }
+    #[test]
+    fn format_detection() {
+        assert_eq!(
+            format("../test_resources/OBS/V2/AJAC3550.21O"),
+            Some(Format::Rinex)
+        );
+        assert_eq!(
+            format("../test_resources/CRNX/V1/AJAC3550.21D"),
+            Some(Format::Crinex)
+        );
+        assert_eq!(
+            format("../test_resources/MET/V2/abvi0010.15m"),
+            Some(Format::Rinex)
+        );
+        assert_eq!(format("../README.md"), None);
+
+        let mut reader = "not a rinex".as_bytes();
+        assert!(Rinex::detect_format(&mut reader).is_err());
+    }
+    #[test]
+    fn unix_compress_not_supported() {
+        let tmp_path = TmpFile::new("Z");
+        std::fs::write(&tmp_path, [0x1f, 0x9d, 0x90, 0x43, 0x52]).unwrap();
+        assert_eq!(format(&tmp_path), Some(Format::UnixCompress));
+        let err = BufferedReader::new(&tmp_path).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+        assert!(Rinex::from_file(&tmp_path).is_err());
+    }
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn gzip_format_detection() {
+        assert_eq!(
+            format("../test_resources/NAV/V2/cbw10010.21n.gz"),
+            Some(Format::GzipRinex)
+        );
+        assert_eq!(
+            format("../test_resources/CRNX/V3/GRAS00FRA_R_20223151700_15M_01S_MO.crx.gz"),
+            Some(Format::GzipCrinex)
+        );
+        // unknown inner format: still gzip, whatever the extension
+        let tmp_path = TmpFile::new("rnx");
+        let mut encoder = flate2::write::GzEncoder::new(
+            File::create(&tmp_path).unwrap(),
+            flate2::Compression::default(),
+        );
+        std::io::Write::write_all(&mut encoder, b"not a rinex\n").unwrap();
+        encoder.finish().unwrap();
+        assert_eq!(format(&tmp_path), Some(Format::Gzip));
+        assert!(Format::Gzip.is_gzip());
+        match BufferedReader::new(&tmp_path).unwrap() {
+            BufferedReader::GzFile(mut reader) => {
+                let mut content = String::new();
+                reader.read_to_string(&mut content).unwrap();
+                assert_eq!(content, "not a rinex\n");
+            },
+            _ => panic!("gzip content should be decompressed"),
+        }
+    }
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn wrong_extension() {
+        for (path, ext) in [
+            ("../test_resources/NAV/V2/cbw10010.21n.gz", "rnx"),
+            ("../test_resources/OBS/V2/AJAC3550.21O", "gz"),
+            ("../test_resources/CRNX/V1/AJAC3550.21D", "txt"),
+        ] {
+            let model = Rinex::from_file(path).unwrap();
+            let tmp_path = TmpFile::new(ext);
+            std::fs::copy(path, &tmp_path).unwrap();
+            let rnx = Rinex::from_file(&tmp_path);
+            let rnx = rnx.unwrap_or_else(|e| panic!("failed to parse mislabeled {}: {}", path, e));
+            assert_eq!(rnx.header, model.header);
+            assert_eq!(rnx.record, model.record);
+        }
+    }
+    fn normalize(content: &[u8], policy: NonAsciiPolicy) -> std::io::Result<String> {
+        let mut normalized = String::new();
+        Normalizer::new(content, policy).read_to_string(&mut normalized)?;
+        Ok(normalized)
+    }
+    #[test]
+    fn normalization() {
+        let content = b"\xef\xbb\xbffirst\r\nsecond\rthird\nfo\xe9rth\r\n";
+        assert_eq!(
+            normalize(content, NonAsciiPolicy::Replace).unwrap(),
+            "first\nsecond\nthird\nfo rth\n"
+        );
+        assert_eq!(
+            normalize(content, NonAsciiPolicy::Skip).unwrap(),
+            "first\nsecond\nthird\nforth\n"
+        );
+        let error = normalize(content, NonAsciiPolicy::Error).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("offset 25"));
+        // valid UTF-8: one character, one whitespace
+        let content = "Zürich\n".as_bytes();
+        assert_eq!(
+            normalize(content, NonAsciiPolicy::Replace).unwrap(),
+            "Z rich\n"
+        );
+        assert_eq!(normalize(content, NonAsciiPolicy::Skip).unwrap(), "Zrich\n");
+        let error = normalize(content, NonAsciiPolicy::Error).unwrap_err();
+        assert!(error.to_string().contains("0xc3 at offset 1"));
+        // the label column is preserved
+        let mut latin1 = b"Z\xfcrich".to_vec();
+        latin1.resize(60, b' ');
+        latin1.extend_from_slice(b"COMMENT\n");
+        for content in [
+            format!("{:<60}COMMENT\n", "Zürich").into_bytes(),
+            format!("{:<60}COMMENT\n", "Zürich 12 €").into_bytes(),
+            latin1,
+        ] {
+            let normalized = normalize(&content, NonAsciiPolicy::Replace).unwrap();
+            assert_eq!(normalized.find("COMMENT"), Some(60));
+        }
+    }
+    #[test]
+    fn legacy_encoding() {
+        let path = "../test_resources/OBS/V3/DUTH0630.22O";
+        let model = Rinex::from_file(path).unwrap();
+        // BOM, CRLF line endings and a legacy code page comment
+        let mut content = UTF8_BOM.to_vec();
+        for line in std::fs::read_to_string(path).unwrap().lines() {
+            let mut bytes = line.as_bytes().to_vec();
+            if line.starts_with("HEADER CHANGED") {
+                bytes[9] = 0xc4; // 'Ä' in latin-1
+            }
+            content.extend_from_slice(&bytes);
+            content.extend_from_slice(b"\r\n");
         }
+        let tmp_path = TmpFile::new("22O");
+        std::fs::write(&tmp_path, &content).unwrap();
+        let rnx = Rinex::from_file(&tmp_path);
+        let strict = Rinex::from_file_with_settings(
+            &tmp_path,
+            &ParserSettings::default().with_non_ascii_policy(NonAsciiPolicy::Error),
+        );
+        let rnx = rnx.unwrap();
+        assert_eq!(rnx.record, model.record);
+        assert_eq!(rnx.header.obs, model.header.obs);
+        assert!(rnx
+            .header
+            .comments
+            .iter()
+            .any(|comment| comment.starts_with("HEADER CH NGED")));
+        assert!(strict.is_err());
     }
 }
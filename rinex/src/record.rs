@@ -8,19 +8,39 @@ use serde::Serialize;
 use super::{
     antex, clocks,
     clocks::{ClockData, ClockDataType},
+    constants::SPEED_OF_LIGHT,
+    fieldslice,
     hatanaka::{Compressor, Decompressor},
     header, ionex, is_rinex_comment, merge,
-    merge::Merge,
+    merge::{Merge, MergePolicy},
     meteo, navigation, observation,
+    progress::{ProgressSink, Reporter},
     reader::BufferedReader,
     split,
     split::Split,
     types::Type,
+    writer,
     writer::BufferedWriter,
     *,
 };
 use hifitime::Duration;
 
+/*
+ * Estimated heap footprint of a HashMap, in bytes:
+ * one control byte per bucket
+ */
+pub(crate) fn hashmap_usage<K, V>(map: &HashMap<K, V>) -> usize {
+    map.capacity() * (std::mem::size_of::<(K, V)>() + 1)
+}
+
+/*
+ * Estimated heap footprint of a BTreeMap, in bytes,
+ * node overhead is neglected
+ */
+pub(crate) fn btreemap_usage<K, V>(map: &BTreeMap<K, V>) -> usize {
+    map.len() * std::mem::size_of::<(K, V)>()
+}
+
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
 pub enum Record {
@@ -40,9 +60,19 @@ pub enum Record {
 
 /// Record comments are high level informations, sorted by epoch
 /// (timestamp) of appearance. We deduce the "associated" timestamp from the
-/// previosuly parsed epoch, when parsing the record.
+/// previosuly parsed epoch, when parsing the record:
+/// comments are located right after the epoch they are indexed by.
+/// Comments that precede the first epoch are indexed by `Epoch::default()`.
+/// This positioning is preserved when the record is formatted back to a file.
 pub type Comments = BTreeMap<Epoch, Vec<String>>;
 
+/// Observation event epochs (flags 2 to 5), like antenna swaps or
+/// a new site occupation, are not part of the Observation record.
+/// They are stored here with their special records: complete header lines,
+/// that describe what changed at this epoch (for example `MARKER NAME`).
+/// They are written back in place when producing a file, compressed or not.
+pub type Events = BTreeMap<(Epoch, EpochFlag), Vec<String>>;
+
 impl Record {
     /// Unwraps self as ANTEX record
     pub fn as_antex(&self) -> Option<&antex::Record> {
@@ -128,32 +158,265 @@ impl Record {
             _ => None,
         }
     }
-    /// Streams into given file writer
+    /// Returns estimated memory footprint of self, in bytes.
+    /// Shared allocator overhead is not accounted for.
+    pub fn memory_usage(&self) -> usize {
+        let size = match self {
+            Self::AntexRecord(r) => {
+                r.capacity()
+                    * std::mem::size_of::<(
+                        antex::Antenna,
+                        HashMap<Carrier, antex::FrequencyDependentData>,
+                    )>()
+                    + r.iter()
+                        .map(|(_, freqs)| hashmap_usage(freqs))
+                        .sum::<usize>()
+            },
+            Self::ClockRecord(r) => clocks::record::memory_usage(r),
+            Self::IonexRecord(r) => {
+                btreemap_usage(r) + r.values().map(hashmap_usage).sum::<usize>()
+            },
+            Self::MeteoRecord(r) => meteo::record::memory_usage(r),
+            Self::NavRecord(r) => navigation::record::memory_usage(r),
+            Self::ObsRecord(r) => observation::record::memory_usage(r),
+        };
+        std::mem::size_of::<Self>() + size
+    }
+    /// Shifts all epochs of this record by given `offset`, which may be negative.
+    /// Only the timestamps are modified: the time scale and the data
+    /// (for example ephemeris time of issue) are left untouched.
+    /// This has no effect on ANTEX records, which are not indexed by epoch.
+    pub fn shift_epochs(&mut self, offset: Duration) {
+        match self {
+            Self::AntexRecord(_) => {},
+            Self::ClockRecord(r) => {
+                *r = std::mem::take(r)
+                    .into_iter()
+                    .map(|(t, data)| (t + offset, data))
+                    .collect();
+            },
+            Self::IonexRecord(r) => {
+                *r = std::mem::take(r)
+                    .into_iter()
+                    .map(|((t, h), plane)| ((t + offset, h), plane))
+                    .collect();
+            },
+            Self::MeteoRecord(r) => {
+                *r = std::mem::take(r)
+                    .into_iter()
+                    .map(|(t, data)| (t + offset, data))
+                    .collect();
+            },
+            Self::NavRecord(r) => {
+                *r = std::mem::take(r)
+                    .into_iter()
+                    .map(|(t, frames)| (t + offset, frames))
+                    .collect();
+            },
+            Self::ObsRecord(r) => {
+                *r = std::mem::take(r)
+                    .into_iter()
+                    .map(|((t, flag), data)| ((t + offset, flag), data))
+                    .collect();
+            },
+        }
+    }
+    /// Retains the observations for which the predicate `f(epoch, sv, observable, data)`
+    /// returns true. Arbitrary filters are expressed this way, without iterating
+    /// through the nested structure. Satellites without remaining observations are dropped,
+    /// and so are epochs without remaining satellites.
+    /// This has no effect on records other than Observation records.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let mut rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// // GPS phase observations, with a strong signal
+    /// rinex.record.retain_where(|_, sv, observable, data| {
+    ///     sv.constellation == Constellation::GPS
+    ///         && observable.is_phase_observable()
+    ///         && data.snr.map(|snr| snr.strong()).unwrap_or(false)
+    /// });
+    /// for (_, (_, vehicles)) in rinex.observation() {
+    ///     for (sv, observations) in vehicles {
+    ///         assert_eq!(sv.constellation, Constellation::GPS);
+    ///         assert!(observations.keys().all(|obs| obs.is_phase_observable()));
+    ///     }
+    /// }
+    /// ```
+    pub fn retain_where<F>(&mut self, f: F)
+    where
+        F: FnMut(Epoch, SV, &Observable, &observation::ObservationData) -> bool,
+    {
+        if let Self::ObsRecord(r) = self {
+            observation::record::retain_where(r, f);
+        }
+    }
+    /// Applies `f` to all values of given [Observable], in place, so correction steps
+    /// are composed without iterating through the nested structure.
+    /// LLI and SNR flags are preserved. This applies to Observation and Meteo records,
+    /// it has no effect on other records.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let mut rinex = Rinex::from_file("../test_resources/MET/V2/abvi0010.15m")
+    ///     .unwrap();
+    /// let temperature = Observable::from_str("TD").unwrap();
+    /// // °C to K
+    /// rinex.record.apply(&temperature, |t| t + 273.15);
+    /// for (_, observations) in rinex.meteo() {
+    ///     if let Some(t) = observations.get(&temperature) {
+    ///         assert!(*t > 273.15);
+    ///     }
+    /// }
+    /// ```
+    pub fn apply<F>(&mut self, observable: &Observable, f: F)
+    where
+        F: FnMut(f64) -> f64,
+    {
+        match self {
+            Self::ObsRecord(r) => observation::record::apply(r, observable, f),
+            Self::MeteoRecord(r) => meteo::record::apply(r, observable, f),
+            _ => {},
+        }
+    }
+    /// Adds a constant `offset` to all values of given [Observable], see [Self::apply].
+    pub fn offset(&mut self, observable: &Observable, offset: f64) {
+        self.apply(observable, |value| value + offset);
+    }
+    /// Scales all values of given [Observable] by `factor`, typically
+    /// to convert units, see [Self::apply].
+    pub fn scale(&mut self, observable: &Observable, factor: f64) {
+        self.apply(observable, |value| value * factor);
+    }
+    /// Removes a known code `bias`, for example a differential code bias (DCB)
+    /// expressed in time, from given pseudo range [Observable] (in meters).
+    /// This has no effect on other kinds of observables.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let mut rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let c1c = Observable::from_str("C1C").unwrap();
+    /// let first = |rinex: &Rinex| {
+    ///     rinex.observation()
+    ///         .flat_map(|(_, (_, vehicles))| vehicles.values())
+    ///         .find_map(|observations| observations.get(&c1c).cloned())
+    ///         .unwrap()
+    /// };
+    /// let raw = first(&rinex);
+    /// rinex.record.remove_code_bias(&c1c, Duration::from_nanoseconds(10.0));
+    /// let corrected = first(&rinex);
+    /// assert!((raw.obs - corrected.obs - 2.99792458).abs() < 1.0E-6);
+    /// // flags are preserved
+    /// assert_eq!(raw.lli, corrected.lli);
+    /// assert_eq!(raw.snr, corrected.snr);
+    /// ```
+    pub fn remove_code_bias(&mut self, observable: &Observable, bias: Duration) {
+        if observable.is_pseudorange_observable() {
+            self.offset(observable, -bias.to_seconds() * SPEED_OF_LIGHT);
+        }
+    }
+    /// Normalizes the missing data placeholders described by given [observation::MissingDataRules]
+    /// (null or absurd values) to absent data, and returns the amount of normalized
+    /// observations, per [Observable]. Like [Self::retain_where], satellites without remaining
+    /// observations are dropped, and so are epochs without remaining satellites.
+    /// This applies to Observation and Meteo records, it has no effect on other records.
+    /// Null meteo observations are preserved, only their valid ranges apply.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::observation::MissingDataRules;
+    /// let mut rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let normalized = rinex.record.normalize_missing(&MissingDataRules::standard());
+    /// for (_, (_, vehicles)) in rinex.observation() {
+    ///     for observations in vehicles.values() {
+    ///         assert!(observations.values().all(|data| data.obs != 0.0));
+    ///     }
+    /// }
+    /// // rules are idempotent
+    /// assert!(rinex.record.normalize_missing(&MissingDataRules::standard()).is_empty());
+    /// ```
+    pub fn normalize_missing(
+        &mut self,
+        rules: &observation::MissingDataRules,
+    ) -> BTreeMap<Observable, usize> {
+        let mut normalized = BTreeMap::<Observable, usize>::new();
+        if rules.is_empty() {
+            return normalized;
+        }
+        let mut is_missing = |observable: &Observable, value: f64| -> bool {
+            let missing = rules.is_missing(observable, value);
+            if missing {
+                *normalized.entry(observable.clone()).or_default() += 1;
+            }
+            missing
+        };
+        match self {
+            Self::ObsRecord(r) => {
+                observation::record::retain_where(r, |_, _, observable, data| {
+                    !is_missing(observable, data.obs)
+                });
+            },
+            Self::MeteoRecord(r) => {
+                r.retain(|_, observations| {
+                    observations.retain(|observable, value| !is_missing(observable, *value));
+                    !observations.is_empty()
+                });
+            },
+            _ => {},
+        }
+        normalized
+    }
+    /// Streams into given file writer.
+    /// Record `comments` are reinserted at their original location, see [Comments],
+    /// and so are Observation `events`, see [Events].
+    /// The file is concluded once the record is written, see [BufferedWriter::finish].
     pub fn to_file(
         &self,
         header: &header::Header,
+        comments: &Comments,
+        events: &Events,
+        writer: &mut BufferedWriter,
+    ) -> Result<(), Error> {
+        self.to_file_with_progress(header, comments, events, writer, &mut |_, _| {})
+    }
+    /// Same as [Self::to_file], the amount of bytes produced (CRINEX compression included)
+    /// and epochs written being reported to given [ProgressSink].
+    pub fn to_file_with_progress(
+        &self,
+        header: &header::Header,
+        comments: &Comments,
+        events: &Events,
         writer: &mut BufferedWriter,
+        progress: &mut dyn ProgressSink,
     ) -> Result<(), Error> {
+        let mut reporter = Reporter::new(progress);
+        writer.set_max_width(max_line_width(header));
+        let mut comments = comments.iter().peekable();
+        let mut events = events.iter().peekable();
         match &header.rinex_type {
             Type::MeteoData => {
                 let record = self.as_meteo().unwrap();
                 for (epoch, data) in record.iter() {
-                    if let Ok(epoch) = meteo::record::fmt_epoch(epoch, data, header) {
-                        let _ = write!(writer, "{}", epoch);
+                    write!(writer, "{}", fmt_comments(&mut comments, *epoch, false))?;
+                    if let Ok(content) = meteo::record::fmt_epoch(epoch, data, header) {
+                        let _ = write!(writer, "{}", content);
                     }
+                    write!(writer, "{}", fmt_comments(&mut comments, *epoch, true))?;
+                    reporter.set_position(writer.bytes());
+                    reporter.epoch();
                 }
             },
             Type::ObservationData => {
                 let record = self.as_obs().unwrap();
                 let obs_fields = &header.obs.as_ref().unwrap();
-                let mut compressor = Compressor::default();
-                for ((epoch, flag), (clock_offset, data)) in record.iter() {
-                    let epoch =
-                        observation::record::fmt_epoch(*epoch, *flag, clock_offset, data, header);
+                let mut compressor = Compressor::with_precision(obs_fields.precision);
+                // events are not accounted for as epochs
+                let mut write_epoch = |content: String, is_epoch: bool| -> Result<(), Error> {
                     if obs_fields.crinex.is_some() {
                         let major = header.version.major;
                         let constell = &header.constellation.as_ref().unwrap();
-                        for line in epoch.lines() {
+                        for line in content.lines() {
                             let line = line.to_owned() + "\n"; // helps the following .lines() iterator
                                                                // embedded in compression method
                             if let Ok(compressed) =
@@ -163,24 +426,60 @@ impl Record {
                             }
                         }
                     } else {
-                        write!(writer, "{}", epoch)?;
+                        write!(writer, "{}", content)?;
+                    }
+                    reporter.set_position(writer.bytes());
+                    if is_epoch {
+                        reporter.epoch();
                     }
+                    Ok(())
+                };
+                for ((epoch, flag), (clock_offset, data)) in record.iter() {
+                    // events are interleaved in chronological order
+                    while let Some(((t, event), records)) =
+                        events.next_if(|(key, _)| **key < (*epoch, *flag))
+                    {
+                        write_epoch(fmt_event(&mut comments, *t, *event, records, header), false)?;
+                    }
+                    // comments are passed through the compressor as is
+                    let epoch = fmt_comments(&mut comments, *epoch, false)
+                        + &observation::record::fmt_epoch(
+                            *epoch,
+                            *flag,
+                            clock_offset,
+                            data,
+                            header,
+                        )
+                        + &fmt_comments(&mut comments, *epoch, true);
+                    write_epoch(epoch, true)?;
+                }
+                // events that follow the last epoch
+                for ((t, event), records) in events {
+                    write_epoch(fmt_event(&mut comments, *t, *event, records, header), false)?;
                 }
             },
             Type::NavigationData => {
                 let record = self.as_nav().unwrap();
                 for (epoch, frames) in record.iter() {
-                    if let Ok(epoch) = navigation::record::fmt_epoch(epoch, frames, header) {
-                        let _ = write!(writer, "{}", epoch);
+                    write!(writer, "{}", fmt_comments(&mut comments, *epoch, false))?;
+                    if let Ok(content) = navigation::record::fmt_epoch(epoch, frames, header) {
+                        let _ = write!(writer, "{}", content);
                     }
+                    write!(writer, "{}", fmt_comments(&mut comments, *epoch, true))?;
+                    reporter.set_position(writer.bytes());
+                    reporter.epoch();
                 }
             },
             Type::ClockData => {
                 if let Some(r) = self.as_clock() {
                     for (epoch, data) in r {
-                        if let Ok(epoch) = clocks::record::fmt_epoch(epoch, data) {
-                            let _ = write!(writer, "{}", epoch);
+                        write!(writer, "{}", fmt_comments(&mut comments, *epoch, false))?;
+                        if let Ok(content) = clocks::record::fmt_epoch(epoch, data) {
+                            let _ = write!(writer, "{}", content);
                         }
+                        write!(writer, "{}", fmt_comments(&mut comments, *epoch, true))?;
+                        reporter.set_position(writer.bytes());
+                        reporter.epoch();
                     }
                 }
             },
@@ -221,8 +520,167 @@ impl Record {
             },
             _ => panic!("record type not supported yet"),
         }
+        // comments that follow the last epoch
+        for (_, lines) in comments {
+            for line in lines {
+                writeln!(writer, "{}", fmt_comment(line))?;
+            }
+        }
+        // conclude the file first: the last report accounts for the final terminator(s)
+        writer.finish()?;
+        reporter.set_position(writer.bytes());
+        reporter.report();
         Ok(())
     }
+    /// Merges `rhs` into `Self` without mutable access, like [Merge::merge],
+    /// but epochs that exist in both records are resolved with given [MergePolicy].
+    /// This is typically used to combine streams from redundant loggers of the same station.
+    pub fn merge_with(&self, rhs: &Self, policy: MergePolicy) -> Result<Self, merge::Error> {
+        let mut lhs = self.clone();
+        lhs.merge_with_mut(rhs, policy)?;
+        Ok(lhs)
+    }
+    /// Merges `rhs` into `Self`, resolving overlapping epochs with given [MergePolicy].
+    /// Self is not modified when an error is returned.
+    /// Navigation and ANTEX records have no numeric observations to average,
+    /// [MergePolicy::Average] is rejected. Navigation frames conflict when they describe
+    /// the same message of the same satellite: Self's or `rhs` frame is then preserved.
+    /// ANTEX frames are combined like [Merge::merge_mut]. IONEX maps prefer Self's or `rhs` TEC values.
+    pub fn merge_with_mut(&mut self, rhs: &Self, policy: MergePolicy) -> Result<(), merge::Error> {
+        match (self, rhs) {
+            (Record::ObsRecord(lhs), Record::ObsRecord(rhs)) => {
+                observation::record::merge_with_mut(lhs, rhs, policy)
+            },
+            (Record::MeteoRecord(lhs), Record::MeteoRecord(rhs)) => {
+                meteo::record::merge_with_mut(lhs, rhs, policy)
+            },
+            (Record::ClockRecord(lhs), Record::ClockRecord(rhs)) => {
+                clocks::record::merge_with_mut(lhs, rhs, policy)
+            },
+            (Record::NavRecord(lhs), Record::NavRecord(rhs)) => {
+                navigation::record::merge_with_mut(lhs, rhs, policy)
+            },
+            (Record::AntexRecord(lhs), Record::AntexRecord(rhs)) => match policy {
+                MergePolicy::Average => Err(merge::Error::UnsupportedPolicy(policy)),
+                _ => lhs.merge_mut(rhs),
+            },
+            (Record::IonexRecord(lhs), Record::IonexRecord(rhs)) => match policy {
+                MergePolicy::Average => Err(merge::Error::UnsupportedPolicy(policy)),
+                MergePolicy::Error => match merge::first_conflict(lhs, rhs) {
+                    Some((epoch, _)) => Err(merge::Error::EpochConflict(*epoch)),
+                    None => lhs.merge_mut(rhs),
+                },
+                MergePolicy::PreferSelf => lhs.merge_mut(rhs),
+                MergePolicy::PreferOther => {
+                    let mut merged = rhs.clone();
+                    merged.merge_mut(lhs)?;
+                    *lhs = merged;
+                    Ok(())
+                },
+            },
+            _ => Err(merge::Error::FileTypeMismatch),
+        }
+    }
+    /// Interpolates Meteo observations or Clock data at given [Epoch],
+    /// linearly, from the closest samples that surround it.
+    /// Returns a record that only contains `epoch`, or None if `epoch`
+    /// is not within this record, or this type of record is not supported.
+    /// This is typically used to align Meteo data with Observation epochs.
+    #[cfg(feature = "processing")]
+    pub fn interpolate_at(&self, epoch: Epoch) -> Option<Self> {
+        let method = InterpMethod::Linear;
+        match self {
+            Self::MeteoRecord(r) => {
+                let data = meteo::record::interpolate_at(r, epoch, &method, None)?;
+                Some(Self::MeteoRecord([(epoch, data)].into_iter().collect()))
+            },
+            Self::ClockRecord(r) => {
+                let data = clocks::record::interpolate_at(r, epoch, &method, None)?;
+                Some(Self::ClockRecord([(epoch, data)].into_iter().collect()))
+            },
+            _ => None,
+        }
+    }
+    /// Fills the gaps of Meteo and Clock records with interpolated data,
+    /// at the dominant sampling interval, without mutable access.
+    /// Only gaps that do not exceed `max_gap` (duration between the two samples
+    /// surrounding the gap) are filled. Other types of record are not modified.
+    #[cfg(feature = "processing")]
+    pub fn fill_gaps(&self, max_gap: Duration, method: InterpMethod) -> Self {
+        let mut s = self.clone();
+        s.fill_gaps_mut(max_gap, method);
+        s
+    }
+    /// Fills the gaps of Meteo and Clock records with interpolated data, see [Self::fill_gaps].
+    #[cfg(feature = "processing")]
+    pub fn fill_gaps_mut(&mut self, max_gap: Duration, method: InterpMethod) {
+        match self {
+            Self::MeteoRecord(r) => meteo::record::fill_gaps_mut(r, max_gap, &method),
+            Self::ClockRecord(r) => clocks::record::fill_gaps_mut(r, max_gap, &method),
+            _ => {},
+        }
+    }
+}
+
+/*
+ * Formats record comments located prior to given epoch,
+ * or up to given epoch (included) when `inclusive`.
+ * Comments are consumed from the iterator, so each one is only emitted once,
+ * even though the epoch it is indexed by no longer exists (filtered record).
+ */
+fn fmt_comments<'a, I: Iterator<Item = (&'a Epoch, &'a Vec<String>)>>(
+    comments: &mut std::iter::Peekable<I>,
+    epoch: Epoch,
+    inclusive: bool,
+) -> String {
+    let mut content = String::new();
+    while let Some((_, lines)) =
+        comments.next_if(|(e, _)| **e < epoch || (inclusive && **e == epoch))
+    {
+        for line in lines {
+            content.push_str(&fmt_comment(line));
+            content.push('\n');
+        }
+    }
+    content
+}
+
+/*
+ * Line width limit of the record, see [writer::MAX_LINE_WIDTH].
+ * RINEX3 Observations and CRINEX are not limited, and neither are
+ * Clock records, that we describe on a single line.
+ */
+fn max_line_width(header: &header::Header) -> Option<usize> {
+    match header.rinex_type {
+        Type::ObservationData => {
+            let crinex = header.obs.as_ref().and_then(|obs| obs.crinex.as_ref());
+            if crinex.is_none() && header.version.major < 3 {
+                Some(writer::MAX_LINE_WIDTH)
+            } else {
+                None
+            }
+        },
+        Type::NavigationData | Type::MeteoData | Type::IonosphereMaps => {
+            Some(writer::MAX_LINE_WIDTH)
+        },
+        Type::ClockData | Type::AntennaData => None,
+    }
+}
+
+/*
+ * Formats given event epoch, surrounded by the record comments
+ * that are located prior to it and up to it (included).
+ */
+fn fmt_event<'a, I: Iterator<Item = (&'a Epoch, &'a Vec<String>)>>(
+    comments: &mut std::iter::Peekable<I>,
+    epoch: Epoch,
+    flag: EpochFlag,
+    records: &[String],
+    header: &header::Header,
+) -> String {
+    fmt_comments(comments, epoch, false)
+        + &observation::record::fmt_event(epoch, flag, records, header)
+        + &fmt_comments(comments, epoch, true)
 }
 
 impl Default for Record {
@@ -270,17 +728,47 @@ pub fn is_new_epoch(line: &str, header: &header::Header) -> bool {
 pub fn parse_record(
     reader: &mut BufferedReader,
     header: &mut header::Header,
-) -> Result<(Record, Comments), Error> {
+) -> Result<(Record, Comments, Events), Error> {
+    parse_record_with_profile(reader, header, None, &mut |_, _| {})
+}
+
+/*
+ * Builds a `Record`, satellites of the Observation record
+ * being possibly fixed by a mapping profile.
+ * Progress is reported to given sink.
+ */
+pub(crate) fn parse_record_with_profile(
+    reader: &mut BufferedReader,
+    header: &mut header::Header,
+    profile: Option<&observation::MappingProfile>,
+    progress: &mut dyn ProgressSink,
+) -> Result<(Record, Comments, Events), Error> {
+    let mut reporter = Reporter::new(progress);
     let mut first_epoch = true;
-    let mut content = String::default();
     let mut epoch_content = String::with_capacity(6 * 64);
 
+    // line pool: input lines are read into a single buffer,
+    // and CRINEX lines are recovered into another one,
+    // parsers then work on slices of these buffers
+    let mut buffer = String::with_capacity(128);
+    let mut crx_line = String::with_capacity(128);
+    let mut recovered = String::with_capacity(6 * 64);
+
     // to manage `record` comments
     let mut comments: Comments = Comments::new();
     let mut comment_ts = Epoch::default();
     let mut comment_content: Vec<String> = Vec::with_capacity(4);
 
-    let mut decompressor = Decompressor::new();
+    // to manage Observation `events`:
+    // special records that remain to be collected, for the current event
+    let mut events: Events = Events::new();
+    let mut event_key = (Epoch::default(), EpochFlag::default());
+    let mut event_records = 0_usize;
+
+    let mut decompressor = match &header.obs {
+        Some(obs) => Decompressor::with_precision(obs.precision),
+        None => Decompressor::new(),
+    };
     // record
     let mut atx_rec = antex::Record::new(); // ATX
     let mut nav_rec = navigation::Record::new(); // NAV
@@ -316,14 +804,25 @@ pub fn parse_record(
     let mut ionx_rec = ionex::Record::new();
     let mut ionex_rms_plane = false;
 
-    for l in reader.lines() {
+    loop {
         // iterates one line at a time
-        let line = l.unwrap();
+        buffer.clear();
+        let size = reader.read_line(&mut buffer)?;
+        if size == 0 {
+            break;
+        }
+        reporter.advance(size as u64);
+        let line = buffer.trim_end_matches(|c| c == '\n' || c == '\r');
         // COMMENTS special case
         // --> store
         // ---> append later with epoch.timestamp attached to it
-        if is_rinex_comment(&line) {
-            let comment = line.split_at(60).0.trim_end();
+        //  unless it is one of the special records of an event
+        if is_rinex_comment(line) && event_records == 0 {
+            let comment = match fieldslice::split_at(line, 60) {
+                Ok((comment, _)) => comment,
+                Err(_) => line.trim_end().trim_end_matches("COMMENT"),
+            };
+            let comment = comment.trim_end();
             comment_content.push(comment.to_string());
             continue;
         }
@@ -331,7 +830,7 @@ pub fn parse_record(
         //  and used in TEC map parsing
         if line.contains("EXPONENT") {
             if let Some(ionex) = header.ionex.as_mut() {
-                let content = line.split_at(60).0;
+                let content = fieldslice::field(line, 0, 60).unwrap_or_default();
                 if let Ok(e) = content.trim().parse::<i8>() {
                     *ionex = ionex.with_exponent(e); // scaling update
                 }
@@ -341,57 +840,59 @@ pub fn parse_record(
          * If plain RINEX: content is passed as is
          *      if CRINEX: decompress and pass recovered content
          */
-
-        if let Some(obs) = &header.obs {
-            if let Some(crinex) = &obs.crinex {
-                /*
-                 * CRINEX
-                 */
-                let constellation = &header.constellation.as_ref().unwrap();
-                if let Ok(recovered) = decompressor.decompress(
-                    crinex.version.major,
-                    constellation,
-                    header.version.major,
-                    &obs.codes,
-                    // we might encounter empty lines
-                    //   like missing clock offsets
-                    //   and .lines() will destroy them
-                    &(line.to_owned() + "\n"),
-                ) {
-                    content = recovered.clone();
-                } else {
-                    content.clear();
+        let content: &str = match header.obs.as_ref().and_then(|obs| {
+            obs.crinex
+                .as_ref()
+                .map(|crinex| (crinex.version.major, &obs.codes))
+        }) {
+            Some((crx_major, codes)) => {
+                let constellation = header.constellation.unwrap_or(Constellation::Mixed);
+                // we might encounter empty lines
+                //   like missing clock offsets
+                //   and .lines() will destroy them
+                crx_line.clear();
+                crx_line.push_str(line);
+                crx_line.push('\n');
+                recovered.clear();
+                if decompressor
+                    .decompress_into(
+                        crx_major,
+                        &constellation,
+                        header.version.major,
+                        codes,
+                        &crx_line,
+                        &mut recovered,
+                    )
+                    .is_err()
+                {
+                    recovered.clear();
                 }
-            } else {
-                /*
-                 * RINEX
-                 */
+                &recovered
+            },
+            None => {
                 if line.is_empty() {
                     // we might encounter empty lines
                     // and the following parsers (.lines() iterator)
                     // do not like it
-                    content = String::from("\n");
+                    "\n"
                 } else {
-                    content = line.to_string();
+                    line
                 }
-            }
-        } else {
-            /*
-             * RINEX
-             */
-            if line.is_empty() {
-                // we might encounter empty lines
-                // and the following parsers (.lines() iterator)
-                // do not like it
-                content = String::from("\n");
-            } else {
-                content = line.to_string();
-            }
-        }
+            },
+        };
 
         for line in content.lines() {
             // in case of CRINEX -> RINEX < 3 being recovered,
             // we have more than 1 ligne to process
+            if event_records > 0 {
+                // special records of an event
+                events
+                    .entry(event_key)
+                    .or_default()
+                    .push(line.trim_end().to_string());
+                event_records -= 1;
+                continue;
+            }
             let new_epoch = is_new_epoch(line, header);
             ionex_rms_plane = ionex::record::is_new_rms_plane(line);
 
@@ -412,8 +913,11 @@ pub fn parse_record(
                         }
                     },
                     Type::ObservationData => {
+                        let fixed = profile
+                            .and_then(|profile| profile.fix_epoch(&epoch_content, header.version));
+                        let content = fixed.as_deref().unwrap_or(&epoch_content);
                         if let Ok((e, ck_offset, map)) =
-                            observation::record::parse_epoch(header, &epoch_content, obs_ts)
+                            observation::record::parse_epoch(header, content, obs_ts)
                         {
                             obs_rec.insert(e, (ck_offset, map));
                             comment_ts = e.0; // for comments classification & management
@@ -492,22 +996,39 @@ pub fn parse_record(
                         }
                     },
                 }
-
-                // new comments ?
-                if !comment_content.is_empty() {
-                    comments.insert(comment_ts, comment_content.clone());
-                    comment_content.clear() // reset
+                // event epochs leave no content
+                if !epoch_content.is_empty() {
+                    reporter.epoch();
                 }
             } //is_new_epoch() +!first
 
             if new_epoch {
+                // new comments ?
+                // comments prior to the first epoch remain attached to Epoch::default()
+                if !comment_content.is_empty() {
+                    comments
+                        .entry(comment_ts)
+                        .or_default()
+                        .append(&mut comment_content);
+                }
                 if !first_epoch {
                     epoch_content.clear()
                 }
                 first_epoch = false;
+                // event epochs are not part of the record
+                if header.rinex_type == Type::ObservationData {
+                    if let Some((key, n)) = observation::record::parse_event(header, line, obs_ts) {
+                        events.entry(key).or_default();
+                        event_key = key;
+                        event_records = n;
+                        comment_ts = key.0; // for comments classification & management
+                        continue;
+                    }
+                }
             }
             // epoch content builder
-            epoch_content.push_str(&(line.to_owned() + "\n"));
+            epoch_content.push_str(line);
+            epoch_content.push('\n');
         }
     }
 
@@ -529,8 +1050,11 @@ pub fn parse_record(
             }
         },
         Type::ObservationData => {
+            let fixed =
+                profile.and_then(|profile| profile.fix_epoch(&epoch_content, header.version));
+            let content = fixed.as_deref().unwrap_or(&epoch_content);
             if let Ok((e, ck_offset, map)) =
-                observation::record::parse_epoch(header, &epoch_content, obs_ts)
+                observation::record::parse_epoch(header, content, obs_ts)
             {
                 obs_rec.insert(e, (ck_offset, map));
                 comment_ts = e.0; // for comments classification + management
@@ -608,9 +1132,16 @@ pub fn parse_record(
             atx_rec.push((antenna, content));
         },
     }
+    if !epoch_content.is_empty() {
+        reporter.epoch();
+    }
+    reporter.report();
     // new comments ?
     if !comment_content.is_empty() {
-        comments.insert(comment_ts, comment_content.clone());
+        comments
+            .entry(comment_ts)
+            .or_default()
+            .append(&mut comment_content);
     }
     // wrap record
     let record = match &header.rinex_type {
@@ -621,7 +1152,7 @@ pub fn parse_record(
         Type::NavigationData => Record::NavRecord(nav_rec),
         Type::ObservationData => Record::ObsRecord(obs_rec),
     };
-    Ok((record, comments))
+    Ok((record, comments, events))
 }
 
 impl Merge for Record {
@@ -689,7 +1220,7 @@ impl Split for Record {
 }
 
 #[cfg(feature = "processing")]
-use crate::algorithm::{Filter, Preprocessing};
+use crate::algorithm::{Filter, InterpMethod, Preprocessing};
 
 #[cfg(feature = "processing")]
 impl Preprocessing for Record {
@@ -0,0 +1,177 @@
+//! Sampling interval, as described by the INTERVAL header field
+//! and used by decimation filters.
+//!
+//! [SamplingInterval] wraps a [Duration] and is parsed from a value
+//! followed by its unit, exactly (to the nanosecond):
+//! ```
+//! use rinex::prelude::*;
+//! use rinex::sampling::SamplingInterval;
+//! use std::str::FromStr;
+//!
+//! let dt = SamplingInterval::from_str("30 s").unwrap();
+//! assert_eq!(dt.duration(), Duration::from_seconds(30.0));
+//! assert_eq!(SamplingInterval::from_str("1 min").unwrap(), SamplingInterval::from_str("60s").unwrap());
+//! assert_eq!(
+//!     SamplingInterval::from_str("0.1 s").unwrap().duration(),
+//!     Duration::from_milliseconds(100.0),
+//! );
+//! // comparison with tolerance
+//! assert!(dt.approx_eq(Duration::from_seconds(30.001), Duration::from_milliseconds(10.0)));
+//! // header field
+//! assert_eq!(dt.to_header_field(), "    30.000");
+//! ```
+use crate::formatter::parse_fixed_point;
+use hifitime::Duration;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum Error {
+    #[error("missing or unknown time unit in \"{0}\"")]
+    UnknownUnit(String),
+    #[error("invalid interval value \"{0}\"")]
+    InvalidValue(String),
+    #[error("sampling interval must be positive")]
+    NonPositive,
+}
+
+/*
+ * Supported units, with their value in nanoseconds.
+ * Longer units first, so they are not mistaken for a shorter suffix.
+ */
+const UNITS: [(&str, i128); 9] = [
+    ("min", 60_000_000_000),
+    ("ns", 1),
+    ("us", 1_000),
+    ("ms", 1_000_000),
+    ("sec", 1_000_000_000),
+    ("s", 1_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("day", 86_400_000_000_000),
+    ("d", 86_400_000_000_000),
+];
+
+/// [SamplingInterval] is a strictly positive [Duration]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SamplingInterval(Duration);
+
+impl SamplingInterval {
+    /// Builds a [SamplingInterval] from given [Duration],
+    /// which must be strictly positive.
+    pub fn new(dt: Duration) -> Result<Self, Error> {
+        if dt > Duration::ZERO {
+            Ok(Self(dt))
+        } else {
+            Err(Error::NonPositive)
+        }
+    }
+    /// Returns the [Duration] of this interval
+    pub fn duration(&self) -> Duration {
+        self.0
+    }
+    /// Returns true if given [Duration] matches this interval,
+    /// within given tolerance
+    pub fn approx_eq(&self, rhs: Duration, tolerance: Duration) -> bool {
+        (self.0 - rhs).abs() <= tolerance
+    }
+    /// Parses the content of an INTERVAL header field, expressed in seconds.
+    /// INTERVAL = 0 is used by varying sampling rates and returns None.
+    pub fn from_header_field(content: &str) -> Option<Self> {
+        // parsed to the nanosecond, sub second (high rate) intervals
+        // are not exactly represented as floating point numbers
+        let nanos = parse_fixed_point(content, 9)?;
+        Self::new(Duration::from_total_nanoseconds(nanos as i128)).ok()
+    }
+    /// Formats this interval as the content of an INTERVAL header field (F10.3 seconds)
+    pub fn to_header_field(&self) -> String {
+        format!("{:10.3}", self.0.to_seconds())
+    }
+}
+
+impl From<SamplingInterval> for Duration {
+    fn from(dt: SamplingInterval) -> Self {
+        dt.0
+    }
+}
+
+impl TryFrom<Duration> for SamplingInterval {
+    type Error = Error;
+    fn try_from(dt: Duration) -> Result<Self, Self::Error> {
+        Self::new(dt)
+    }
+}
+
+impl FromStr for SamplingInterval {
+    type Err = Error;
+    /// Parses a value followed by its unit, possibly separated by whitespaces:
+    /// "ns", "us", "ms", "s" (or "sec"), "min", "h", "d" (or "day").
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let content = content.trim();
+        let (value, factor) = UNITS
+            .iter()
+            .find_map(|(unit, factor)| Some((content.strip_suffix(unit)?, *factor)))
+            .ok_or_else(|| Error::UnknownUnit(content.to_string()))?;
+        let fixed = parse_fixed_point(value, 9)
+            .ok_or_else(|| Error::InvalidValue(value.trim().to_string()))?;
+        let nanos = fixed as i128 * factor / 1_000_000_000;
+        Self::new(Duration::from_total_nanoseconds(nanos))
+    }
+}
+
+impl std::fmt::Display for SamplingInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn parsing() {
+        for (content, expected) in [
+            ("30 s", Duration::from_seconds(30.0)),
+            ("30s", Duration::from_seconds(30.0)),
+            (" 15 sec ", Duration::from_seconds(15.0)),
+            ("1 min", Duration::from_seconds(60.0)),
+            ("0.1 s", Duration::from_total_nanoseconds(100_000_000)),
+            ("0.5 min", Duration::from_seconds(30.0)),
+            ("50 ms", Duration::from_total_nanoseconds(50_000_000)),
+            ("250 us", Duration::from_total_nanoseconds(250_000)),
+            ("2 h", Duration::from_seconds(7200.0)),
+            ("1 d", Duration::from_seconds(86400.0)),
+            ("1 day", Duration::from_seconds(86400.0)),
+        ] {
+            let dt = SamplingInterval::from_str(content).unwrap();
+            assert_eq!(dt.duration(), expected, "failed to parse \"{}\"", content);
+        }
+        assert!(SamplingInterval::from_str("30").is_err());
+        assert!(SamplingInterval::from_str("30 weeks").is_err());
+        assert!(SamplingInterval::from_str("s").is_err());
+        assert_eq!(SamplingInterval::from_str("0 s"), Err(Error::NonPositive));
+        assert_eq!(SamplingInterval::from_str("-30 s"), Err(Error::NonPositive));
+    }
+    #[test]
+    fn header_field() {
+        let dt = SamplingInterval::from_header_field("    30.000").unwrap();
+        assert_eq!(dt.duration(), Duration::from_seconds(30.0));
+        assert_eq!(dt.to_header_field(), "    30.000");
+        let dt = SamplingInterval::from_header_field("     0.100").unwrap();
+        assert_eq!(dt.duration(), Duration::from_total_nanoseconds(100_000_000));
+        assert_eq!(dt.to_header_field(), "     0.100");
+        assert!(SamplingInterval::from_header_field("     0.000").is_none());
+        assert!(SamplingInterval::from_header_field("").is_none());
+    }
+    #[test]
+    fn tolerance() {
+        let dt = SamplingInterval::from_str("30 s").unwrap();
+        let tol = Duration::from_milliseconds(1.0);
+        assert!(dt.approx_eq(Duration::from_seconds(30.0), tol));
+        assert!(dt.approx_eq(Duration::from_seconds(29.9995), tol));
+        assert!(!dt.approx_eq(Duration::from_seconds(30.01), tol));
+    }
+}
@@ -0,0 +1,354 @@
+//! Satellite metadata: PRN to SVN mapping, block types and usable periods
+use crate::prelude::{Constellation, Epoch, SV};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use strum_macros::EnumString;
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+include!(concat!(env!("OUT_DIR"), "/sv_db.rs"));
+
+/// Satellite block type (generation), as described in our satellite database.
+/// Some corrections depend on it, for example the satellite antenna offsets.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, EnumString)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BlockType {
+    /// GPS Block IIA
+    #[strum(serialize = "IIA")]
+    GpsIIA,
+    /// GPS Block IIR
+    #[strum(serialize = "IIR")]
+    GpsIIR,
+    /// GPS Block IIR-M (modernized)
+    #[strum(serialize = "IIR-M")]
+    GpsIIRM,
+    /// GPS Block IIF
+    #[strum(serialize = "IIF")]
+    GpsIIF,
+    /// GPS Block III
+    #[strum(serialize = "III")]
+    GpsIII,
+}
+
+impl std::fmt::Display for BlockType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::GpsIIA => write!(f, "IIA"),
+            Self::GpsIIR => write!(f, "IIR"),
+            Self::GpsIIRM => write!(f, "IIR-M"),
+            Self::GpsIIF => write!(f, "IIF"),
+            Self::GpsIII => write!(f, "III"),
+        }
+    }
+}
+
+/// Satellite database errors
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("invalid satellite \"{0}\"")]
+    InvalidSatellite(String),
+    #[error("invalid constellation \"{0}\"")]
+    InvalidConstellation(String),
+    #[error("unknown block type \"{0}\"")]
+    UnknownBlockType(String),
+    #[error("invalid epoch \"{0}\"")]
+    InvalidEpoch(String),
+}
+
+/// Assignment of a PRN to a given satellite (SVN), over a period of time.
+/// The period starts when the satellite was set usable under this PRN
+/// (after its commissioning, not at launch) and ends when the PRN
+/// was reassigned, or when the satellite was decommissioned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvAssignment {
+    /// Assigned PRN
+    pub sv: SV,
+    /// Space Vehicle Number, like "G074"
+    pub svn: String,
+    /// Block type
+    pub block: BlockType,
+    /// Start of the assignment: satellite set usable
+    pub from: Epoch,
+    /// End of the assignment, None while still active
+    pub until: Option<Epoch>,
+}
+
+impl SvAssignment {
+    /// Returns true if this assignment is active at given epoch
+    pub fn contains(&self, t: Epoch) -> bool {
+        t >= self.from && self.until.map(|until| t < until).unwrap_or(true)
+    }
+}
+
+/// [SatelliteDatabase] describes the PRN assignments of a constellation
+/// over a period of time. Our database (`db/SV/satellites.json`) is bundled at build time,
+/// see [SatelliteDatabase::bundled]. It only describes the GPS constellation:
+/// other constellations are unknown to it.
+///
+/// With the "serde" feature, an updated database is loaded at runtime,
+/// with the layout of the bundled one, for example in JSON:
+/// ```json
+/// {
+///     "coverage": {
+///         "GPS": "2019-01-01T00:00:00 UTC"
+///     },
+///     "satellites": [
+///         {
+///             "sv": "G11",
+///             "svn": "G046",
+///             "block": "IIR",
+///             "from": "2000-01-03T00:00:00 UTC",
+///             "until": "2021-06-17T00:00:00 UTC"
+///         }
+///     ]
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "RawDatabase", into = "RawDatabase")
+)]
+pub struct SatelliteDatabase {
+    /// Start of the description, per constellation: prior that,
+    /// the database cannot tell whether a PRN was in use.
+    pub coverage: BTreeMap<Constellation, Epoch>,
+    /// PRN assignments
+    pub satellites: Vec<SvAssignment>,
+}
+
+impl SatelliteDatabase {
+    /// Returns our bundled satellite database
+    pub fn bundled() -> &'static Self {
+        &SV_DATABASE
+    }
+    /// Returns the assignment of this PRN active at given epoch, if known
+    pub fn assignment(&self, sv: SV, t: Epoch) -> Option<&SvAssignment> {
+        self.satellites
+            .iter()
+            .find(|assignment| assignment.sv == sv && assignment.contains(t))
+    }
+    /// Returns the block type of the latest satellite this PRN was assigned to
+    pub fn block_type(&self, sv: SV) -> Option<BlockType> {
+        self.satellites
+            .iter()
+            .filter(|assignment| assignment.sv == sv)
+            .max_by_key(|assignment| assignment.from)
+            .map(|assignment| assignment.block)
+    }
+    /// Returns true if this PRN was in use at given epoch.
+    /// Returns None when this database does not describe this constellation
+    /// at that epoch, in which case nothing can be said.
+    pub fn is_active(&self, sv: SV, t: Epoch) -> Option<bool> {
+        let since = self.coverage.get(&sv.constellation)?;
+        if t < *since {
+            return None;
+        }
+        Some(self.assignment(sv, t).is_some())
+    }
+}
+
+/*
+ * Serialized layout of the database, identical to db/SV/satellites.json
+ */
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct RawAssignment {
+    sv: String,
+    svn: String,
+    block: String,
+    from: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    until: Option<String>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct RawDatabase {
+    coverage: BTreeMap<String, String>,
+    satellites: Vec<RawAssignment>,
+}
+
+#[cfg(feature = "serde")]
+fn parse_epoch(s: &str) -> Result<Epoch, Error> {
+    Epoch::from_str(s.trim()).map_err(|_| Error::InvalidEpoch(s.to_string()))
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawDatabase> for SatelliteDatabase {
+    type Error = Error;
+    fn try_from(raw: RawDatabase) -> Result<Self, Self::Error> {
+        let mut coverage = BTreeMap::new();
+        for (constellation, since) in &raw.coverage {
+            let constellation = Constellation::from_str(constellation.trim())
+                .map_err(|_| Error::InvalidConstellation(constellation.to_string()))?;
+            coverage.insert(constellation, parse_epoch(since)?);
+        }
+        let mut satellites = Vec::with_capacity(raw.satellites.len());
+        for raw in raw.satellites {
+            satellites.push(SvAssignment {
+                sv: SV::from_str(raw.sv.trim())
+                    .map_err(|_| Error::InvalidSatellite(raw.sv.clone()))?,
+                block: BlockType::from_str(raw.block.trim())
+                    .map_err(|_| Error::UnknownBlockType(raw.block.clone()))?,
+                from: parse_epoch(&raw.from)?,
+                until: match &raw.until {
+                    Some(until) => Some(parse_epoch(until)?),
+                    None => None,
+                },
+                svn: raw.svn,
+            });
+        }
+        Ok(Self {
+            coverage,
+            satellites,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<SatelliteDatabase> for RawDatabase {
+    fn from(db: SatelliteDatabase) -> Self {
+        Self {
+            coverage: db
+                .coverage
+                .iter()
+                .map(|(constellation, since)| (constellation.to_string(), since.to_string()))
+                .collect(),
+            satellites: db
+                .satellites
+                .into_iter()
+                .map(|assignment| RawAssignment {
+                    sv: assignment.sv.to_string(),
+                    svn: assignment.svn,
+                    block: assignment.block.to_string(),
+                    from: assignment.from.to_string(),
+                    until: assignment.until.map(|until| until.to_string()),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Satellite metadata, from our bundled satellite database (see [SatelliteDatabase]),
+/// that only describes the GPS constellation: other constellations are unknown to these methods.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::satellite::{BlockType, SatelliteMetadata};
+/// use std::str::FromStr;
+/// let g11 = SV::from_str("G11").unwrap();
+/// // PRN11 was reassigned to a Block III satellite in 2021
+/// let t = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+/// assert_eq!(g11.svn_at(t), Some("G046"));
+/// assert_eq!(g11.block_type_at(t), Some(BlockType::GpsIIR));
+/// assert_eq!(g11.block_type(), Some(BlockType::GpsIII));
+/// ```
+pub trait SatelliteMetadata {
+    /// Returns the PRN assignment active at given epoch, if known
+    fn assignment_at(&self, t: Epoch) -> Option<&'static SvAssignment>;
+    /// Returns the Space Vehicle Number this PRN was assigned to at given epoch
+    fn svn_at(&self, t: Epoch) -> Option<&'static str> {
+        self.assignment_at(t)
+            .map(|assignment| assignment.svn.as_str())
+    }
+    /// Returns the block type of the satellite this PRN was assigned to at given epoch
+    fn block_type_at(&self, t: Epoch) -> Option<BlockType> {
+        self.assignment_at(t).map(|assignment| assignment.block)
+    }
+    /// Returns the block type of the latest satellite this PRN was assigned to
+    fn block_type(&self) -> Option<BlockType>;
+    /// Returns true if this PRN was in use at given epoch.
+    /// Returns None when the database does not describe this constellation
+    /// at that epoch, in which case nothing can be said.
+    fn is_active_at(&self, t: Epoch) -> Option<bool>;
+}
+
+impl SatelliteMetadata for SV {
+    fn assignment_at(&self, t: Epoch) -> Option<&'static SvAssignment> {
+        SatelliteDatabase::bundled().assignment(*self, t)
+    }
+    fn block_type(&self) -> Option<BlockType> {
+        SatelliteDatabase::bundled().block_type(*self)
+    }
+    fn is_active_at(&self, t: Epoch) -> Option<bool> {
+        SatelliteDatabase::bundled().is_active(*self, t)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn sv_database() {
+        let t = Epoch::from_str("2022-01-01T00:00:00 GPST").unwrap();
+        for (sv, svn, block) in [
+            ("G04", "G074", BlockType::GpsIII),
+            ("G05", "G050", BlockType::GpsIIRM),
+            ("G13", "G043", BlockType::GpsIIR),
+            ("G14", "G077", BlockType::GpsIII),
+            ("G28", "G044", BlockType::GpsIIR),
+            ("G32", "G070", BlockType::GpsIIF),
+        ] {
+            let sv = SV::from_str(sv).unwrap();
+            assert_eq!(sv.svn_at(t), Some(svn), "{}", sv);
+            assert_eq!(sv.block_type_at(t), Some(block), "{}", sv);
+            assert_eq!(sv.is_active_at(t), Some(true), "{}", sv);
+        }
+        // G074 was launched in december 2018, but only set usable in 2020
+        let g04 = SV::from_str("G04").unwrap();
+        let commissioning = Epoch::from_str("2019-06-01T00:00:00 GPST").unwrap();
+        assert_eq!(g04.is_active_at(commissioning), Some(false));
+        assert!(g04.svn_at(commissioning).is_none());
+        // no satellite flies under PRN33
+        let g33 = SV::from_str("G33").unwrap();
+        assert_eq!(g33.is_active_at(t), Some(false));
+        assert!(g33.block_type().is_none());
+        // prior to the database coverage
+        let t = Epoch::from_str("2010-01-01T00:00:00 GPST").unwrap();
+        assert!(g33.is_active_at(t).is_none());
+        // undescribed constellation
+        let e01 = SV::from_str("E01").unwrap();
+        assert!(e01.is_active_at(t).is_none());
+        assert!(e01.svn_at(t).is_none());
+    }
+    #[test]
+    #[cfg(feature = "serde")]
+    fn runtime_loading() {
+        let content = std::fs::read_to_string("db/SV/satellites.json").unwrap();
+        let db: SatelliteDatabase = serde_json::from_str(&content).unwrap();
+        assert_eq!(&db, SatelliteDatabase::bundled());
+        // serialization preserves the layout
+        let serialized = serde_json::to_string(&db).unwrap();
+        let parsed: SatelliteDatabase = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(parsed, db);
+        // an updated database describes new assignments
+        let content = r#"{
+            "coverage": { "GPS": "2019-01-01T00:00:00 UTC" },
+            "satellites": [
+                { "sv": "G33", "svn": "G099", "block": "III", "from": "2030-01-01T00:00:00 UTC" }
+            ]
+        }"#;
+        let db: SatelliteDatabase = serde_json::from_str(content).unwrap();
+        let g33 = SV::from_str("G33").unwrap();
+        let t = Epoch::from_str("2031-01-01T00:00:00 GPST").unwrap();
+        assert_eq!(db.is_active(g33, t), Some(true));
+        assert_eq!(db.block_type(g33), Some(BlockType::GpsIII));
+        assert_eq!(g33.is_active_at(t), Some(false));
+        let invalid = content.replace("\"III\"", "\"IIZ\"");
+        assert!(serde_json::from_str::<SatelliteDatabase>(&invalid).is_err());
+    }
+    #[test]
+    fn block_type_parsing() {
+        for block in [
+            BlockType::GpsIIA,
+            BlockType::GpsIIR,
+            BlockType::GpsIIRM,
+            BlockType::GpsIIF,
+            BlockType::GpsIII,
+        ] {
+            assert_eq!(BlockType::from_str(&block.to_string()), Ok(block));
+        }
+    }
+}
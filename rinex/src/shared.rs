@@ -0,0 +1,136 @@
+//! Thread-safe shared RINEX
+use crate::{header::Header, record::Record, Rinex};
+use std::sync::Arc;
+
+#[cfg(feature = "processing")]
+use crate::preprocessing::{Filter, Preprocessing};
+
+/// [`SharedRinex`] is a cheaply clonable and thread-safe handle
+/// on a parsed [`Rinex`]. Clones share the same data,
+/// which is only copied when modified.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::shared::SharedRinex;
+/// let rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+///     .unwrap();
+/// let shared = SharedRinex::from(rinex);
+/// let handles: Vec<_> = (0..4)
+///     .map(|_| {
+///         let shared = shared.clone();
+///         std::thread::spawn(move || shared.epoch().count())
+///     })
+///     .collect();
+/// for handle in handles {
+///     assert_eq!(handle.join().unwrap(), shared.epoch().count());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedRinex(Arc<Rinex>);
+
+impl From<Rinex> for SharedRinex {
+    fn from(rinex: Rinex) -> Self {
+        Self(Arc::new(rinex))
+    }
+}
+
+impl std::ops::Deref for SharedRinex {
+    type Target = Rinex;
+    fn deref(&self) -> &Rinex {
+        &self.0
+    }
+}
+
+impl SharedRinex {
+    /// Returns a [`RecordView`] of the entire record, sharing its data
+    pub fn view(&self) -> RecordView {
+        RecordView {
+            source: self.0.clone(),
+            record: None,
+        }
+    }
+    /// Returns mutable access to the inner [`Rinex`].
+    /// Data is copied if it is currently shared.
+    pub fn make_mut(&mut self) -> &mut Rinex {
+        Arc::make_mut(&mut self.0)
+    }
+    /// Unwraps the inner [`Rinex`], copying it if it is currently shared
+    pub fn into_inner(self) -> Rinex {
+        Arc::try_unwrap(self.0).unwrap_or_else(|shared| (*shared).clone())
+    }
+    /// Returns true if both handles share the same data
+    pub fn ptr_eq(&self, rhs: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &rhs.0)
+    }
+}
+
+/// [`RecordView`] is a copy-on-write view of a [`SharedRinex`] record.
+/// An unmodified view does not copy anything, the record is only
+/// copied once it is filtered or modified. Views are cheap to clone.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::preprocessing::*;
+/// use rinex::shared::SharedRinex;
+/// use std::str::FromStr;
+/// let shared = SharedRinex::from(
+///     Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap());
+/// let view = shared.view();
+/// assert!(view.is_shared());
+/// let gps = view.filter(Filter::from_str("GPS").unwrap());
+/// assert!(!gps.is_shared());
+/// // source is left untouched
+/// assert_eq!(view.record(), &shared.record);
+/// let gps = gps.to_rinex();
+/// assert_eq!(gps.constellation().count(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RecordView {
+    source: Arc<Rinex>,
+    /// Record, when it differs from the source record
+    record: Option<Arc<Record>>,
+}
+
+impl RecordView {
+    /// Header of the source [`Rinex`]
+    pub fn header(&self) -> &Header {
+        &self.source.header
+    }
+    /// Viewed [`Record`]
+    pub fn record(&self) -> &Record {
+        match &self.record {
+            Some(record) => record,
+            None => &self.source.record,
+        }
+    }
+    /// Returns true if this view still shares the source record
+    pub fn is_shared(&self) -> bool {
+        self.record.is_none()
+    }
+    /// Returns mutable access to the viewed [`Record`].
+    /// Data is copied if it is currently shared.
+    pub fn to_mut(&mut self) -> &mut Record {
+        let record = self
+            .record
+            .get_or_insert_with(|| Arc::new(self.source.record.clone()));
+        Arc::make_mut(record)
+    }
+    /// Builds a [`Rinex`] from this view
+    pub fn to_rinex(&self) -> Rinex {
+        self.source.with_record(self.record().clone())
+    }
+}
+
+#[cfg(feature = "processing")]
+#[cfg_attr(docrs, doc(cfg(feature = "processing")))]
+impl RecordView {
+    /// Returns a new view, with given [`Filter`] applied.
+    pub fn filter(&self, f: Filter) -> Self {
+        Self {
+            source: self.source.clone(),
+            record: Some(Arc::new(self.record().filter(f))),
+        }
+    }
+    /// Applies given [`Filter`] to this view
+    pub fn filter_mut(&mut self, f: Filter) {
+        self.to_mut().filter_mut(f);
+    }
+}
@@ -0,0 +1,193 @@
+//! BLQ ocean loading coefficients files
+use super::OceanLoading;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// BLQ parsing errors
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("i/o error")]
+    IoError(#[from] std::io::Error),
+    #[error("coefficients found prior station name")]
+    MissingStationName,
+    #[error("failed to parse coefficient \"{0}\"")]
+    CoefficientParsing(String),
+    #[error("expecting 11 coefficients per line")]
+    BadNumberOfCoefficients,
+    #[error("incomplete coefficients for station \"{0}\"")]
+    IncompleteStation(String),
+    #[error("too many coefficient rows for station \"{0}\"")]
+    TooManyRows(String),
+}
+
+/// BLQ file content: ocean loading coefficients for a list of sites
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Blq {
+    /// Ocean loading coefficients, per site
+    pub stations: Vec<OceanLoading>,
+}
+
+impl Blq {
+    /// Parses BLQ coefficients from given local file
+    pub fn from_file(path: &str) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(path)?;
+        Self::from_str(&content)
+    }
+    /// Returns coefficients for given station, if it exists.
+    /// Station names are case insensitive.
+    pub fn station(&self, name: &str) -> Option<&OceanLoading> {
+        self.stations
+            .iter()
+            .find(|station| station.station.eq_ignore_ascii_case(name))
+    }
+}
+
+impl FromStr for Blq {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let mut stations = Vec::<OceanLoading>::new();
+        let mut current: Option<OceanLoading> = None;
+        let mut row = 0_usize;
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("$$") {
+                continue; // comments
+            }
+            let is_numeric = line
+                .split_ascii_whitespace()
+                .next()
+                .map(|item| f64::from_str(item).is_ok())
+                .unwrap_or(false);
+
+            if !is_numeric {
+                /* new site */
+                if let Some(station) = current.take() {
+                    if row < 6 {
+                        return Err(Error::IncompleteStation(station.station));
+                    }
+                    stations.push(station);
+                }
+                current = Some(OceanLoading {
+                    station: line.to_string(),
+                    ..Default::default()
+                });
+                row = 0;
+                continue;
+            }
+
+            let station = current.as_mut().ok_or(Error::MissingStationName)?;
+            if row >= 6 {
+                return Err(Error::TooManyRows(station.station.clone()));
+            }
+
+            let mut coefficients = [0.0_f64; 11];
+            let mut count = 0;
+            for (index, item) in line.split_ascii_whitespace().enumerate() {
+                if index >= 11 {
+                    return Err(Error::BadNumberOfCoefficients);
+                }
+                coefficients[index] =
+                    f64::from_str(item).map_err(|_| Error::CoefficientParsing(item.to_string()))?;
+                count += 1;
+            }
+            if count != 11 {
+                return Err(Error::BadNumberOfCoefficients);
+            }
+            /* 3 rows of amplitudes, then 3 rows of phases */
+            if row < 3 {
+                station.amplitudes[row] = coefficients;
+            } else {
+                station.phases[row - 3] = coefficients;
+            }
+            row += 1;
+        }
+
+        if let Some(station) = current {
+            if row < 6 {
+                return Err(Error::IncompleteStation(station.station));
+            }
+            stations.push(station);
+        }
+        Ok(Self { stations })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::site_displacement::TidalWave;
+    use hifitime::Epoch;
+    const ONSA: &str = "$$ Ocean loading displacement
+$$
+$$ Columns designate Ssa Mm Mf Q1 P1 O1 K1 K2 N2 S2 M2 in reverse
+  ONSA
+$$ GOT00.2 ocean tide model
+  .00352 .00123 .00080 .00032 .00187 .00112 .00063 .00003 .00082 .00044 .00037
+  .00144 .00035 .00035 .00008 .00053 .00049 .00018 .00009 .00012 .00005 .00006
+  .00086 .00023 .00023 .00006 .00029 .00028 .00010 .00007 .00004 .00002 .00001
+  -64.7  -52.0  -96.2  -55.2  -58.8 -151.4  -65.6 -138.1    8.4    5.2    2.1
+   85.5  114.5   56.5  113.6   99.4   19.1   94.1  -10.4 -167.4 -170.0 -177.7
+  109.5  147.0   92.7  148.8   50.5  -55.1   36.4 -170.4   -1.0    0.2    0.0
+$$ END TABLE";
+    #[test]
+    fn blq_parsing() {
+        let blq = Blq::from_str(ONSA).unwrap();
+        assert_eq!(blq.stations.len(), 1);
+
+        let onsa = blq.station("onsa").unwrap();
+        assert_eq!(onsa.station, "ONSA");
+        assert_eq!(onsa.amplitudes[0][0], 0.00352);
+        assert_eq!(onsa.amplitudes[2][10], 0.00001);
+        assert_eq!(onsa.phases[0][0], -64.7);
+        assert_eq!(onsa.phases[2][10], 0.0);
+        assert!(blq.station("WTZR").is_none());
+
+        let incomplete = ONSA.replace(
+            "  109.5  147.0   92.7  148.8   50.5  -55.1   36.4 -170.4   -1.0    0.2    0.0",
+            "",
+        );
+        assert!(matches!(
+            Blq::from_str(&incomplete),
+            Err(Error::IncompleteStation(_))
+        ));
+
+        let extra_row = ONSA.replace(
+            "$$ END TABLE",
+            "  109.5  147.0   92.7  148.8   50.5  -55.1   36.4 -170.4   -1.0    0.2    0.0",
+        );
+        match Blq::from_str(&extra_row) {
+            Err(Error::TooManyRows(station)) => assert_eq!(station, "ONSA"),
+            other => panic!("expecting TooManyRows error, got {:?}", other),
+        }
+    }
+    #[test]
+    fn ocean_loading() {
+        let blq = Blq::from_str(ONSA).unwrap();
+        let onsa = blq.station("ONSA").unwrap();
+
+        // S2 is purely solar: argument only depends on time of day
+        let t = Epoch::from_gregorian_utc_at_midnight(2009, 6, 25);
+        assert!(TidalWave::S2.argument(t).abs() < 1.0E-9);
+        let t = Epoch::from_gregorian_utc_hms(2009, 6, 25, 6, 0, 0);
+        assert!((TidalWave::S2.argument(t) - std::f64::consts::PI).abs() < 1.0E-3);
+
+        // displacement bounded by the sum of all amplitudes
+        let bounds: Vec<f64> = onsa
+            .amplitudes
+            .iter()
+            .map(|amplitudes| amplitudes.iter().sum())
+            .collect();
+        for hour in 0..24 {
+            let t = Epoch::from_gregorian_utc_hms(2009, 6, 25, hour, 0, 0);
+            let (north, east, up) = onsa.displacement(t);
+            assert!(up.abs() <= bounds[0]);
+            assert!(east.abs() <= bounds[1]);
+            assert!(north.abs() <= bounds[2]);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Site displacement models.
+//! Ocean tide loading is described by BLQ coefficients,
+//! as published by the Onsala Space Observatory loading service,
+//! and evaluated per epoch following the IERS conventions (ARG2).
+use hifitime::{Epoch, Unit};
+
+mod blq;
+pub use blq::{Blq, Error};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Tidal waves described in BLQ files, in order of appearance
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum TidalWave {
+    /// Principal lunar semidiurnal
+    M2,
+    /// Principal solar semidiurnal
+    S2,
+    /// Larger lunar elliptic semidiurnal
+    N2,
+    /// Lunisolar semidiurnal
+    K2,
+    /// Lunisolar diurnal
+    K1,
+    /// Principal lunar diurnal
+    O1,
+    /// Principal solar diurnal
+    P1,
+    /// Larger lunar elliptic diurnal
+    Q1,
+    /// Lunisolar fortnightly
+    Mf,
+    /// Lunar monthly
+    Mm,
+    /// Solar semiannual
+    Ssa,
+}
+
+impl TidalWave {
+    /// All tidal waves, in BLQ order
+    pub const ALL: [Self; 11] = [
+        Self::M2,
+        Self::S2,
+        Self::N2,
+        Self::K2,
+        Self::K1,
+        Self::O1,
+        Self::P1,
+        Self::Q1,
+        Self::Mf,
+        Self::Mm,
+        Self::Ssa,
+    ];
+    /*
+     * Angular speed [rad.s⁻¹]
+     */
+    fn speed(&self) -> f64 {
+        match self {
+            Self::M2 => 1.40519E-4,
+            Self::S2 => 1.45444E-4,
+            Self::N2 => 1.37880E-4,
+            Self::K2 => 1.45842E-4,
+            Self::K1 => 0.72921E-4,
+            Self::O1 => 0.67598E-4,
+            Self::P1 => 0.72523E-4,
+            Self::Q1 => 0.64959E-4,
+            Self::Mf => 0.053234E-4,
+            Self::Mm => 0.026392E-4,
+            Self::Ssa => 0.003982E-4,
+        }
+    }
+    /*
+     * Multipliers of the mean longitude of the Sun (h0),
+     * of the Moon (s0), of the lunar perigee (p0) and phase offset in cycles
+     */
+    fn factors(&self) -> (f64, f64, f64, f64) {
+        match self {
+            Self::M2 => (2.0, -2.0, 0.0, 0.0),
+            Self::S2 => (0.0, 0.0, 0.0, 0.0),
+            Self::N2 => (2.0, -3.0, 1.0, 0.0),
+            Self::K2 => (2.0, 0.0, 0.0, 0.0),
+            Self::K1 => (1.0, 0.0, 0.0, 0.25),
+            Self::O1 => (1.0, -2.0, 0.0, -0.25),
+            Self::P1 => (-1.0, 0.0, 0.0, -0.25),
+            Self::Q1 => (1.0, -3.0, 1.0, -0.25),
+            Self::Mf => (0.0, 2.0, 0.0, 0.0),
+            Self::Mm => (0.0, 1.0, -1.0, 0.0),
+            Self::Ssa => (2.0, 0.0, 0.0, 0.0),
+        }
+    }
+    /// Returns the astronomical argument [rad] of this wave at given epoch,
+    /// in [0; 2π[.
+    pub fn argument(&self, t: Epoch) -> f64 {
+        let (y, m, d, hh, mm, ss, ns) = t.to_gregorian_utc();
+        let day_of_year = Epoch::from_gregorian_utc_at_midnight(y, m, d)
+            - Epoch::from_gregorian_utc_at_midnight(y, 1, 1);
+        let day_of_year = day_of_year.to_unit(Unit::Day).round() as i32 + 1;
+        let seconds_of_day =
+            (hh as f64) * 3600.0 + (mm as f64) * 60.0 + ss as f64 + ns as f64 * 1.0E-9;
+
+        /* days since 1975, then julian centuries since 1900 */
+        let icapd = day_of_year + 365 * (y - 1975) + (y - 1973) / 4;
+        let capt = (27392.500528 + 1.000000035 * icapd as f64) / 36525.0;
+
+        let h0 = (279.69668 + (36000.768930485 + 3.03E-4 * capt) * capt).to_radians();
+        let s0 = (((1.9E-6 * capt - 0.001133) * capt + 481267.88314137) * capt + 270.434358)
+            .to_radians();
+        let p0 = (((-1.2E-5 * capt - 0.010325) * capt + 4069.0340329577) * capt + 334.329653)
+            .to_radians();
+
+        let (fh, fs, fp, fc) = self.factors();
+        let tau = 2.0 * std::f64::consts::PI;
+        let angle = self.speed() * seconds_of_day + fh * h0 + fs * s0 + fp * p0 + fc * tau;
+        angle.rem_euclid(tau)
+    }
+}
+
+/// Ocean tide loading coefficients, for a single site
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct OceanLoading {
+    /// Site name
+    pub station: String,
+    /// Amplitudes [m] of the radial, western and southern components,
+    /// for each [TidalWave]
+    pub amplitudes: [[f64; 11]; 3],
+    /// Phase lags [°] of the radial, western and southern components,
+    /// for each [TidalWave]
+    pub phases: [[f64; 11]; 3],
+}
+
+impl OceanLoading {
+    /// Evaluates the site displacement due to ocean tide loading at given Epoch,
+    /// returned as NEU coordinates in meters.
+    pub fn displacement(&self, t: Epoch) -> (f64, f64, f64) {
+        let mut dxyz = [0.0_f64; 3];
+        for (i, wave) in TidalWave::ALL.iter().enumerate() {
+            let arg = wave.argument(t);
+            for (component, dx) in dxyz.iter_mut().enumerate() {
+                let phase = self.phases[component][i].to_radians();
+                *dx += self.amplitudes[component][i] * (arg - phase).cos();
+            }
+        }
+        let [up, west, south] = dxyz;
+        (-south, -west, up)
+    }
+}
@@ -0,0 +1,359 @@
+//! SQL export of parsed records, into normalized tables
+//! (files, epochs, observations, ephemerides), for SQL analysis
+//! of observation archives. Statements follow the SQLite dialect.
+use crate::prelude::{Rinex, TimeScale};
+use std::io::Write;
+
+/// Database schema: tables and indices are only created when missing,
+/// so successive exports can be gathered in the same database,
+/// as long as their [`Exporter`] starts past the identifiers already in use.
+/// - `files`: one row per exported file
+/// - `epochs`: one row per observation epoch, refers to its file
+/// - `observations`: one row per measurement, refers to its epoch
+/// - `ephemerides`: one row per ephemeris frame, refers to its file
+/// - `ephemeris_orbits`: orbital fields of each ephemeris frame
+///
+/// Epochs are stored as text, in their own timescale, along
+/// `gpst_seconds`: elapsed seconds since the GPST origin, for time based queries.
+pub const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS files (
+    id INTEGER PRIMARY KEY,
+    path TEXT NOT NULL,
+    rinex_type TEXT NOT NULL,
+    version TEXT NOT NULL,
+    constellation TEXT,
+    station TEXT
+);
+CREATE TABLE IF NOT EXISTS epochs (
+    id INTEGER PRIMARY KEY,
+    file_id INTEGER NOT NULL REFERENCES files (id),
+    epoch TEXT NOT NULL,
+    gpst_seconds REAL NOT NULL,
+    flag INTEGER NOT NULL,
+    clock_offset REAL
+);
+CREATE TABLE IF NOT EXISTS observations (
+    epoch_id INTEGER NOT NULL REFERENCES epochs (id),
+    sv TEXT NOT NULL,
+    observable TEXT NOT NULL,
+    value REAL,
+    lli INTEGER,
+    snr REAL
+);
+CREATE TABLE IF NOT EXISTS ephemerides (
+    id INTEGER PRIMARY KEY,
+    file_id INTEGER NOT NULL REFERENCES files (id),
+    toc TEXT NOT NULL,
+    gpst_seconds REAL NOT NULL,
+    sv TEXT NOT NULL,
+    msg TEXT NOT NULL,
+    clock_bias REAL,
+    clock_drift REAL,
+    clock_drift_rate REAL
+);
+CREATE TABLE IF NOT EXISTS ephemeris_orbits (
+    ephemeris_id INTEGER NOT NULL REFERENCES ephemerides (id),
+    field TEXT NOT NULL,
+    value REAL
+);
+CREATE INDEX IF NOT EXISTS epochs_time ON epochs (file_id, gpst_seconds);
+CREATE INDEX IF NOT EXISTS observations_epoch ON observations (epoch_id);
+CREATE INDEX IF NOT EXISTS observations_signal ON observations (sv, observable);
+CREATE INDEX IF NOT EXISTS ephemerides_time ON ephemerides (sv, gpst_seconds);
+CREATE INDEX IF NOT EXISTS ephemeris_orbits_frame ON ephemeris_orbits (ephemeris_id);
+";
+
+/// SQL value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// Missing value
+    Null,
+    /// Integer value
+    Integer(i64),
+    /// Floating point value
+    Real(f64),
+    /// Text
+    Text(String),
+}
+
+impl Value {
+    /*
+     * Non finite numbers are not representable
+     */
+    fn real(value: f64) -> Self {
+        if value.is_finite() {
+            Self::Real(value)
+        } else {
+            Self::Null
+        }
+    }
+    /// SQL literal
+    pub fn literal(&self) -> String {
+        match self {
+            Self::Null => "NULL".to_string(),
+            Self::Integer(value) => value.to_string(),
+            Self::Real(value) => format!("{:?}", value),
+            Self::Text(text) => format!("'{}'", text.replace('\'', "''")),
+        }
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map(|value| value.into()).unwrap_or(Self::Null)
+    }
+}
+
+impl From<String> for Value {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Self::real(value)
+    }
+}
+
+/// A row to be inserted
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    /// Table name
+    pub table: &'static str,
+    /// Column names
+    pub columns: &'static [&'static str],
+    /// One value per column
+    pub values: Vec<Value>,
+}
+
+impl Row {
+    /// Insertion statement, with numbered parameters (?1, ?2..),
+    /// to be prepared once per table
+    pub fn statement(&self) -> String {
+        let params: Vec<String> = (1..=self.columns.len())
+            .map(|i| format!("?{}", i))
+            .collect();
+        format!(
+            "INSERT INTO {} ({}) VALUES ({});",
+            self.table,
+            self.columns.join(", "),
+            params.join(", ")
+        )
+    }
+    /// Insertion statement, with literal values
+    pub fn to_sql(&self) -> String {
+        let values: Vec<String> = self.values.iter().map(|value| value.literal()).collect();
+        format!(
+            "INSERT INTO {} ({}) VALUES ({});",
+            self.table,
+            self.columns.join(", "),
+            values.join(", ")
+        )
+    }
+}
+
+const FILES: &[&str] = &[
+    "id",
+    "path",
+    "rinex_type",
+    "version",
+    "constellation",
+    "station",
+];
+const EPOCHS: &[&str] = &[
+    "id",
+    "file_id",
+    "epoch",
+    "gpst_seconds",
+    "flag",
+    "clock_offset",
+];
+const OBSERVATIONS: &[&str] = &["epoch_id", "sv", "observable", "value", "lli", "snr"];
+#[cfg(feature = "nav")]
+const EPHEMERIDES: &[&str] = &[
+    "id",
+    "file_id",
+    "toc",
+    "gpst_seconds",
+    "sv",
+    "msg",
+    "clock_bias",
+    "clock_drift",
+    "clock_drift_rate",
+];
+#[cfg(feature = "nav")]
+const EPHEMERIS_ORBITS: &[&str] = &["ephemeris_id", "field", "value"];
+
+/// [`Exporter`] converts parsed records into [`Row`]s, and assigns
+/// their identifiers. When exporting into an existing database,
+/// identifiers should start past the ones already in use.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Exporter {
+    /// Identifier of the next file
+    pub next_file_id: i64,
+    /// Identifier of the next epoch
+    pub next_epoch_id: i64,
+    /// Identifier of the next ephemeris frame
+    pub next_ephemeris_id: i64,
+}
+
+impl Default for Exporter {
+    fn default() -> Self {
+        Self {
+            next_file_id: 1,
+            next_epoch_id: 1,
+            next_ephemeris_id: 1,
+        }
+    }
+}
+
+impl Exporter {
+    /// Converts given [`Rinex`] into [`Row`]s, passed to `insert` as they come.
+    /// Observations and ephemerides (requires the "nav" feature) are exported,
+    /// other records only produce their `files` entry.
+    pub fn export<E, F>(&mut self, path: &str, rinex: &Rinex, mut insert: F) -> Result<(), E>
+    where
+        F: FnMut(Row) -> Result<(), E>,
+    {
+        let file_id = self.next_file_id;
+        self.next_file_id += 1;
+        insert(Row {
+            table: "files",
+            columns: FILES,
+            values: vec![
+                file_id.into(),
+                path.to_string().into(),
+                format!("{}", rinex.header.rinex_type).into(),
+                rinex.header.version.to_string().into(),
+                rinex.header.constellation.map(|c| c.to_string()).into(),
+                rinex
+                    .header
+                    .geodetic_marker
+                    .as_ref()
+                    .map(|marker| marker.name.trim().to_string())
+                    .into(),
+            ],
+        })?;
+
+        for ((epoch, flag), (clock_offset, vehicles)) in rinex.observation() {
+            let epoch_id = self.next_epoch_id;
+            self.next_epoch_id += 1;
+            insert(Row {
+                table: "epochs",
+                columns: EPOCHS,
+                values: vec![
+                    epoch_id.into(),
+                    file_id.into(),
+                    epoch.to_string().into(),
+                    gpst_seconds(epoch).into(),
+                    flag.to_string().parse::<i64>().ok().into(),
+                    (*clock_offset).into(),
+                ],
+            })?;
+            for (sv, observations) in vehicles {
+                // sorted, for reproducible production
+                let mut observations: Vec<_> = observations.iter().collect();
+                observations.sort_by(|(a, _), (b, _)| a.cmp(b));
+                for (observable, data) in observations {
+                    insert(Row {
+                        table: "observations",
+                        columns: OBSERVATIONS,
+                        values: vec![
+                            epoch_id.into(),
+                            sv.to_string().into(),
+                            observable.to_string().into(),
+                            data.obs.into(),
+                            data.lli.map(|lli| lli.bits() as i64).into(),
+                            data.snr.map(f64::from).into(),
+                        ],
+                    })?;
+                }
+            }
+        }
+
+        #[cfg(feature = "nav")]
+        for (toc, (msg, sv, ephemeris)) in rinex.ephemeris() {
+            let ephemeris_id = self.next_ephemeris_id;
+            self.next_ephemeris_id += 1;
+            insert(Row {
+                table: "ephemerides",
+                columns: EPHEMERIDES,
+                values: vec![
+                    ephemeris_id.into(),
+                    file_id.into(),
+                    toc.to_string().into(),
+                    gpst_seconds(toc).into(),
+                    sv.to_string().into(),
+                    msg.to_string().into(),
+                    ephemeris.clock_bias.into(),
+                    ephemeris.clock_drift.into(),
+                    ephemeris.clock_drift_rate.into(),
+                ],
+            })?;
+            // sorted, for reproducible exports
+            let mut orbits: Vec<_> = ephemeris.orbits.iter().collect();
+            orbits.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (field, item) in orbits {
+                insert(Row {
+                    table: "ephemeris_orbits",
+                    columns: EPHEMERIS_ORBITS,
+                    values: vec![
+                        ephemeris_id.into(),
+                        field.clone().into(),
+                        item.to_f64().into(),
+                    ],
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/*
+ * Elapsed seconds since the GPST origin
+ */
+fn gpst_seconds(epoch: &crate::prelude::Epoch) -> f64 {
+    epoch
+        .to_duration_in_time_scale(TimeScale::GPST)
+        .to_seconds()
+}
+
+/// Writes given [`Rinex`] as a SQL script: schema, followed by the
+/// insertion statements, in a single transaction. The script can be
+/// executed by any SQLite client, for example `sqlite3 data.db < data.sql`.
+/// Identifiers are assigned by the provided [`Exporter`]: reuse it to gather
+/// several files, and start it past the identifiers already in use
+/// to append to an existing database.
+/// ```
+/// use rinex::prelude::Rinex;
+/// use rinex::sql::Exporter;
+/// let path = "../test_resources/OBS/V2/AJAC3550.21O";
+/// let rinex = Rinex::from_file(path)
+///     .unwrap();
+/// let mut exporter = Exporter::default();
+/// let mut script = Vec::<u8>::new();
+/// rinex::sql::to_sql(path, &rinex, &mut exporter, &mut script)
+///     .unwrap();
+/// let script = String::from_utf8(script)
+///     .unwrap();
+/// assert!(script.starts_with(rinex::sql::SCHEMA));
+/// assert!(script.contains("INSERT INTO files (id, path, rinex_type, version, constellation, station) VALUES (1, '../test_resources/OBS/V2/AJAC3550.21O', 'OBS DATA', '2.11', 'MIXED', 'AJAC');"));
+/// assert_eq!(exporter.next_file_id, 2);
+/// ```
+pub fn to_sql<W: Write>(
+    path: &str,
+    rinex: &Rinex,
+    exporter: &mut Exporter,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    writer.write_all(SCHEMA.as_bytes())?;
+    writeln!(writer, "BEGIN;")?;
+    exporter.export(path, rinex, |row| writeln!(writer, "{}", row.to_sql()))?;
+    writeln!(writer, "COMMIT;")
+}
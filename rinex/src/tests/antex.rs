@@ -128,4 +128,77 @@ mod test {
             assert_eq!(apc.unwrap(), expected);
         }
     }
+    #[cfg(feature = "antex")]
+    #[cfg(feature = "obs")]
+    #[test]
+    fn obs_antenna_corrections() {
+        let atx = Rinex::from_file(
+            &(env!("CARGO_MANIFEST_DIR").to_owned()
+                + "/../test_resources/ATX/V1/TROSAR25.R4__LEIT_2020_09_23.atx"),
+        )
+        .unwrap();
+        let obs = Rinex::from_file(
+            &(env!("CARGO_MANIFEST_DIR").to_owned()
+                + "/../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx"),
+        )
+        .unwrap();
+
+        let (antenna, calibrations) = atx
+            .rx_antenna_calibration(
+                obs.first_epoch().unwrap(),
+                AntennaMatcher::IGSCode("TROSAR25.R4".to_string()),
+            )
+            .expect("failed to locate TROSAR25.R4 calibration");
+        let l1 = calibrations.get(&Carrier::L1).unwrap();
+
+        // PCV interpolation
+        let pcv_0 = l1.pcv(&antenna.zenith_grid, 0.0).unwrap();
+        let pcv_5 = l1.pcv(&antenna.zenith_grid, 5.0).unwrap();
+        let pcv = l1.pcv(&antenna.zenith_grid, 2.5).unwrap();
+        assert!((pcv - (pcv_0 + pcv_5) / 2.0).abs() < 1.0E-9);
+        assert!(l1.pcv(&antenna.zenith_grid, 95.0).is_none());
+
+        // zenith: only the upper APC component contributes
+        let correction = l1
+            .phase_center_correction(&antenna.zenith_grid, 90.0, 0.0)
+            .unwrap();
+        assert!((correction - (l1.apc_eccentricity.2 - pcv_0)).abs() < 1.0E-9);
+
+        // apply to G01 only, seen at zenith
+        let g01 = SV::from_str("G01").unwrap();
+        let corrected = obs.apply_antenna_corrections(
+            &atx,
+            AntennaMatcher::IGSCode("TROSAR25.R4".to_string()),
+            |_, sv| {
+                if sv == g01 {
+                    Some((90.0, 0.0))
+                } else {
+                    None
+                }
+            },
+        );
+        let l1c = Observable::from_str("L1C").unwrap();
+        let expected = correction * 1.0E-3 / Carrier::L1.wavelength();
+
+        let (record, corrected) = (
+            obs.record.as_obs().unwrap(),
+            corrected.record.as_obs().unwrap(),
+        );
+        for ((k, (_, vehicles)), (_, (_, corrected))) in record.iter().zip(corrected.iter()) {
+            for (sv, observations) in vehicles {
+                for (observable, data) in observations {
+                    let value = corrected[sv][observable].obs;
+                    if *sv == g01 && *observable == l1c {
+                        assert!(
+                            (value - data.obs - expected).abs() < 1.0E-6,
+                            "{:?}: bad L1C correction",
+                            k
+                        );
+                    } else if *sv != g01 || !observable.is_phase_observable() {
+                        assert_eq!(value, data.obs, "{:?}: {} should not be corrected", k, sv);
+                    }
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod test {
+    use crate::catalog::{Catalog, Query};
+    use crate::prelude::*;
+    use std::path::Path;
+    #[test]
+    fn navigation_catalog() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("NAV")
+            .join("V2");
+        let catalog = Catalog::from_directory(&dir).unwrap();
+        if cfg!(feature = "flate2") {
+            assert_eq!(catalog.entries.len(), 5);
+        } else {
+            assert_eq!(catalog.entries.len(), 3);
+        }
+        for entry in &catalog.entries {
+            assert_eq!(entry.rinex_type, RinexType::NavigationData);
+            assert_eq!(entry.version.major, 2);
+            assert!(!entry.crinex);
+            // NAV headers do not describe a time span:
+            // it is deduced from the file name
+            assert!(entry.first_epoch.is_none());
+            assert_eq!(entry.year, Some(2021));
+        }
+
+        let query = Query {
+            station: Some("amel".to_string()),
+            year: Some(2021),
+            doy: Some(1),
+            ..Default::default()
+        };
+        let found: Vec<_> = catalog.query(&query).collect();
+        assert_eq!(found.len(), 1);
+        assert!(found[0].path.ends_with("amel0010.21g"));
+        assert_eq!(found[0].constellations, vec![Constellation::Glonass]);
+
+        let query = Query {
+            constellation: Some(Constellation::Galileo),
+            ..Default::default()
+        };
+        assert_eq!(catalog.query(&query).count(), 0);
+    }
+    #[test]
+    fn observation_catalog() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("CRNX")
+            .join("V3");
+        let catalog = Catalog::from_directory(&dir).unwrap();
+        for entry in &catalog.entries {
+            assert_eq!(entry.rinex_type, RinexType::ObservationData);
+            assert!(entry.crinex);
+        }
+        let query = Query {
+            station: Some("ACOR".to_string()),
+            year: Some(2021),
+            doy: Some(355),
+            constellation: Some(Constellation::GPS),
+            ..Default::default()
+        };
+        // KUNZ00CZE.crx header describes the ACOR station as well
+        let found: Vec<_> = catalog.query(&query).collect();
+        assert_eq!(found.len(), 2);
+        for entry in found {
+            assert_eq!(entry.station, Some("ACOR".to_string()));
+            assert!(entry.first_epoch.is_some());
+            assert_eq!(entry.interval, Some(Duration::from_seconds(30.0)));
+        }
+    }
+}
@@ -182,4 +182,79 @@ mod test {
             }
         }*/
     }
+    #[test]
+    fn alignment() {
+        use crate::clocks::{ClockAlignment, ClockData};
+        use std::str::FromStr;
+        let t0 = Epoch::from_str("2023-09-11T00:00:00 GPST").unwrap();
+        let refclock = System::Station("USNO".to_string());
+        let g01 = System::SV(SV::from_str("G01").unwrap());
+        let mut record = clocks::Record::new();
+        for i in 0..4 {
+            let t = t0 + Duration::from_seconds(30.0 * i as f64);
+            let dt = 30.0 * i as f64;
+            let mut dtypes = std::collections::HashMap::new();
+            dtypes.insert(
+                ClockDataType::AS,
+                [(
+                    g01.clone(),
+                    ClockData {
+                        bias: 1.0E-4 + 2.0E-9 * dt,
+                        bias_dev: Some(3.0E-12),
+                        drift: Some(2.0E-9),
+                        ..Default::default()
+                    },
+                )]
+                .into_iter()
+                .collect(),
+            );
+            // reference clock is missing on last epoch
+            if i < 3 {
+                dtypes.insert(
+                    ClockDataType::AR,
+                    [(
+                        refclock.clone(),
+                        ClockData {
+                            bias: 5.0E-5 + 1.0E-9 * dt,
+                            bias_dev: Some(4.0E-12),
+                            drift: Some(1.0E-9),
+                            ..Default::default()
+                        },
+                    )]
+                    .into_iter()
+                    .collect(),
+                );
+            }
+            record.insert(t, dtypes);
+        }
+
+        let aligned = record.rereference(&refclock);
+        assert_eq!(aligned.len(), 3);
+        for (i, (_, dtypes)) in aligned.iter().enumerate() {
+            let reference = &dtypes[&ClockDataType::AR][&refclock];
+            assert_eq!(reference.bias, 0.0);
+            assert_eq!(reference.drift, Some(0.0));
+            let sv = &dtypes[&ClockDataType::AS][&g01];
+            let expected = 5.0E-5 + 1.0E-9 * 30.0 * i as f64;
+            assert!((sv.bias - expected).abs() < 1.0E-15);
+            assert!((sv.drift.unwrap() - 1.0E-9).abs() < 1.0E-18);
+            assert!((sv.bias_dev.unwrap() - 5.0E-12).abs() < 1.0E-18);
+        }
+
+        let detrended = record.detrend();
+        for dtypes in detrended.values() {
+            for systems in dtypes.values() {
+                for data in systems.values() {
+                    assert!(data.bias.abs() < 1.0E-15);
+                    assert!(data.drift.unwrap().abs() < 1.0E-18);
+                }
+            }
+        }
+
+        let demeaned = record.demean();
+        let sv = |t: Epoch| demeaned[&t][&ClockDataType::AS][&g01].bias;
+        assert!((sv(t0) + 2.0E-9 * 45.0).abs() < 1.0E-15);
+        assert!((sv(t0 + Duration::from_seconds(90.0)) - 2.0E-9 * 45.0).abs() < 1.0E-15);
+        assert_eq!(demeaned[&t0][&ClockDataType::AS][&g01].drift, Some(2.0E-9));
+    }
 }
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod test {
     use crate::prelude::*;
-    use crate::tests::toolkit::{random_name, test_against_model};
+    use crate::tests::toolkit::{test_against_model, TmpFile};
     use std::path::PathBuf;
     #[test]
     #[ignore]
@@ -87,7 +87,7 @@ mod test {
             let rnx = rnx.unwrap();
             let compressed = rnx.rnx2crnx1();
 
-            let tmp_path = format!("test-{}.crx", random_name(8));
+            let tmp_path = TmpFile::new("crx");
 
             assert!(
                 compressed.to_file(&tmp_path).is_ok(),
@@ -104,9 +104,6 @@ mod test {
                 "reciprocity test failed for \"{}\"",
                 testfile
             );
-
-            // remove generated file
-            let _ = std::fs::remove_file(&tmp_path);
         }
     }
     #[test]
@@ -191,7 +188,7 @@ mod test {
             let rnx = rnx.unwrap();
             let compressed = rnx.rnx2crnx1();
 
-            let tmp_path = format!("test-{}.crx", random_name(8));
+            let tmp_path = TmpFile::new("crx");
 
             assert!(
                 compressed.to_file(&tmp_path).is_ok(),
@@ -208,9 +205,511 @@ mod test {
                 "reciprocity test failed for \"{}\"",
                 testfile
             );
+        }
+    }
+    #[test]
+    fn crinex_header() {
+        use crate::version::Version;
+        for (testfile, crinex_major) in [
+            ("../test_resources/OBS/V2/AJAC3550.21O", 1),
+            ("../test_resources/OBS/V3/DUTH0630.22O", 3),
+        ] {
+            let rnx = Rinex::from_file(testfile).unwrap();
+            let compressed = rnx.rnx2crnx();
+            let tmp_path = TmpFile::new("crx");
+            compressed.to_file(&tmp_path).unwrap();
+
+            let content = std::fs::read_to_string(&tmp_path).unwrap();
+            let lines: Vec<&str> = content.lines().collect();
+            // labels start at column 61
+            assert_eq!(&lines[0][60..], "CRINEX VERS   / TYPE");
+            assert_eq!(&lines[1][60..], "CRINEX PROG / DATE");
+            assert_eq!(&lines[2][60..], "RINEX VERSION / TYPE");
+
+            let parsed = Rinex::from_file(&tmp_path);
+            let parsed = parsed.unwrap();
+            assert!(parsed.header.is_crinex());
+            let crinex = parsed.header.obs.as_ref().unwrap().crinex.as_ref().unwrap();
+            assert_eq!(crinex.version, Version::new(crinex_major, 0));
+            assert!(crinex.prog.starts_with("rust-rinex-"));
+            assert_eq!(parsed.header.version, rnx.header.version);
+            assert_eq!(parsed.epoch().count(), rnx.epoch().count());
+        }
+
+        // program name is limited to its field
+        let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let header = rnx
+            .header
+            .to_crinex_header(Version::new(3, 0), "a-very-long-program-name");
+        let crinex = header.obs.as_ref().unwrap().crinex.as_ref().unwrap();
+        assert_eq!(crinex.prog, "a-very-long-program-");
+
+        // not an observation header
+        let rnx = Rinex::from_file("../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx")
+            .unwrap();
+        assert!(!rnx.rnx2crnx().header.is_crinex());
+    }
+    #[test]
+    fn compression_estimate() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V2/AJAC3550.21O").unwrap();
+        let estimate = rnx.compression_estimate().unwrap();
+        assert!(estimate.compressed_size > 0);
+        assert!(estimate.ratio() < 0.5, "poor compression ratio");
+        assert_eq!(estimate.kernel_resets(), 0);
+        assert!(!estimate.observables.is_empty());
+        for ((constellation, observable), stats) in &estimate.observables {
+            assert!(
+                stats.kernel_inits > 0,
+                "{:X}({}) kernel was never initialized",
+                constellation,
+                observable
+            );
+            assert!(stats.samples >= stats.kernel_inits);
+            assert!(stats.entropy() >= 0.0);
+        }
+
+        // non observation data
+        let rnx = Rinex::from_file("../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx")
+            .unwrap();
+        assert!(rnx.compression_estimate().is_err());
+    }
+    #[test]
+    fn satellite_gap_resynchronization() {
+        use crate::hatanaka::Compressor;
+        use std::collections::HashMap;
+        use std::str::FromStr;
+        let mut observables: HashMap<Constellation, Vec<Observable>> = HashMap::new();
+        observables.insert(
+            Constellation::GPS,
+            vec![
+                Observable::from_str("L1").unwrap(),
+                Observable::from_str("C1").unwrap(),
+            ],
+        );
+        // G05 is missing in the 2nd epoch
+        let epochs = [
+            (0, vec![(1, 0.0), (5, 0.0)]),
+            (30, vec![(1, 1.0)]),
+            (60, vec![(1, 2.0), (5, 2.0)]),
+            (90, vec![(1, 3.0), (5, 3.0)]),
+        ];
+        let mut content = Vec::new();
+        for (secs, vehicles) in &epochs {
+            let mut epoch = format!(
+                " 21  1  1  0 {:2} {:2}.0000000  0 {:2}",
+                secs / 60,
+                secs % 60,
+                vehicles.len()
+            );
+            let mut body = String::new();
+            for (prn, x) in vehicles {
+                epoch.push_str(&format!("G{:02}", prn));
+                let range = 2.0E7 + *prn as f64 * 1.0E5 + 1000.0 * x;
+                body.push_str(&format!("{:14.3}  {:14.3}  \n", range / 0.19, range));
+            }
+            content.push(format!("{}\n{}", epoch, body));
+        }
+
+        let mut compressor = Compressor::default();
+        let compressed: Vec<String> = content
+            .iter()
+            .map(|epoch| {
+                compressor
+                    .compress(2, &observables, &Constellation::GPS, epoch)
+                    .unwrap()
+            })
+            .collect();
+
+        // G01 is continuous: only initialized once
+        // G05 is re-initialized when coming back
+        let g05 = |epoch: &str| epoch.lines().last().unwrap().to_string();
+        assert!(g05(&compressed[0]).starts_with("3&"));
+        let g01 = compressed[2].lines().nth(2).unwrap();
+        assert!(
+            !g01.contains('&'),
+            "G01 should not be re-initialized: \"{}\"",
+            g01
+        );
+        assert!(
+            g05(&compressed[2]).starts_with("3&"),
+            "G05 kernels should have been re-initialized: \"{}\"",
+            compressed[2]
+        );
+        assert!(!g05(&compressed[3]).contains('&'));
+
+        let estimate =
+            Compressor::estimate(2, &observables, &Constellation::GPS, &content.concat()).unwrap();
+        assert_eq!(estimate.resynchronizations, 1);
+        assert_eq!(estimate.kernel_resets(), 0);
+    }
+    #[test]
+    fn precision_policy() {
+        use crate::formatter::Precision;
+        use crate::hatanaka::{Compressor, Decompressor};
+        use std::collections::HashMap;
+        use std::str::FromStr;
+        let mut observables: HashMap<Constellation, Vec<Observable>> = HashMap::new();
+        observables.insert(
+            Constellation::GPS,
+            vec![
+                Observable::from_str("L1").unwrap(),
+                Observable::from_str("C1").unwrap(),
+            ],
+        );
+        // 1E-5 cycle phase resolution
+        let precision = Precision::default().with_phase(5).unwrap();
+        let mut content = Vec::new();
+        let mut expected = Vec::new();
+        for i in 0..4 {
+            let phase = 1234567.12345 + 12.34567 * i as f64;
+            let range = 22345678.123 + 2.345 * i as f64;
+            content.push(format!(
+                " 21  1  1  0  {} {:2}.0000000  0  1G01\n{:14.5}  {:14.3}  \n",
+                i / 2,
+                (i % 2) * 30,
+                phase,
+                range
+            ));
+            // expected values, at said resolution
+            let phase = format!("{:.5}", phase).parse::<f64>().unwrap();
+            let range = format!("{:.3}", range).parse::<f64>().unwrap();
+            expected.push((phase, range));
+        }
 
-            // remove generated file
-            let _ = std::fs::remove_file(&tmp_path);
+        let mut compressor = Compressor::with_precision(precision);
+        let mut decompressor = Decompressor::with_precision(precision);
+        for (epoch, (phase, range)) in content.iter().zip(expected) {
+            let compressed = compressor
+                .compress(2, &observables, &Constellation::GPS, epoch)
+                .unwrap();
+            let mut recovered = String::new();
+            for line in compressed.lines() {
+                recovered.push_str(
+                    &decompressor
+                        .decompress(
+                            1,
+                            &Constellation::GPS,
+                            2,
+                            &observables,
+                            &format!("{}\n", line),
+                        )
+                        .unwrap(),
+                );
+            }
+            let body = recovered.lines().nth(1).unwrap();
+            let values: Vec<f64> = body
+                .split_whitespace()
+                .map(|item| item.parse::<f64>().unwrap())
+                .collect();
+            assert_eq!(values, vec![phase, range], "recovered \"{}\"", recovered);
         }
+
+        // default policy would have lost the 1E-5 digits
+        let mut compressor = Compressor::default();
+        let compressed = compressor
+            .compress(2, &observables, &Constellation::GPS, &content[0])
+            .unwrap();
+        assert!(compressed.contains("3&1234567123 "), "{}", compressed);
+    }
+    #[test]
+    fn precision_policy_file_roundtrip() {
+        use crate::formatter::Precision;
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("OBS")
+            .join("V2")
+            .join("AJAC3550.21O");
+        let mut rnx = Rinex::from_file(&path.to_string_lossy()).unwrap();
+        let precision = Precision::default().with_phase(4).unwrap();
+        rnx.header.obs.as_mut().unwrap().precision = precision;
+
+        // 1E-4 cycle: only preserved by this policy
+        // (1E-5 would not fit the F14 field, for such phase values)
+        let (_, (_, vehicles)) = rnx.record.as_mut_obs().unwrap().iter_mut().next().unwrap();
+        let (_, observations) = vehicles.iter_mut().next().unwrap();
+        let (l1c, data) = observations
+            .iter_mut()
+            .find(|(observable, _)| observable.is_phase_observable())
+            .unwrap();
+        let l1c = l1c.clone();
+        data.obs += 0.0002;
+        let expected = data.obs;
+
+        // not described by default
+        let tmp_path = TmpFile::new("crx");
+        assert!(rnx.rnx2crnx().to_file(&tmp_path).is_ok());
+        let content = std::fs::read_to_string(&tmp_path).unwrap();
+        assert!(!content.contains("PRECISION"));
+
+        rnx.header.obs.as_mut().unwrap().precision_comment = true;
+        let tmp_path = TmpFile::new("crx");
+        assert!(rnx.rnx2crnx().to_file(&tmp_path).is_ok());
+        let content = std::fs::read_to_string(&tmp_path).unwrap();
+        let parsed = Rinex::from_file(&tmp_path);
+        assert!(content.contains("OBS PRECISION (DECIMALS) C3 L4 D3 S3 X3"));
+
+        let parsed = parsed.unwrap();
+        let obs = parsed.header.obs.as_ref().unwrap();
+        assert_eq!(obs.precision, precision);
+        assert!(obs.precision_comment);
+        assert!(
+            parsed
+                .header
+                .comments
+                .iter()
+                .all(|c| !c.contains("PRECISION")),
+            "policy should not be duplicated in the comments"
+        );
+        let (_, (_, vehicles)) = parsed.record.as_obs().unwrap().iter().next().unwrap();
+        let (_, observations) = vehicles.iter().next().unwrap();
+        let recovered = observations.get(&l1c).unwrap().obs;
+        assert!(
+            (recovered - expected).abs() < 1.0E-6,
+            "recovered {} expecting {}",
+            recovered,
+            expected
+        );
+    }
+    #[test]
+    fn clock_offsets() {
+        use crate::hatanaka::{Compressor, Decompressor};
+        use std::collections::HashMap;
+        use std::str::FromStr;
+        let mut observables: HashMap<Constellation, Vec<Observable>> = HashMap::new();
+        observables.insert(
+            Constellation::GPS,
+            vec![Observable::from_str("C1").unwrap()],
+        );
+        // receiver clock offsets, missing on 3rd epoch
+        let offsets = [
+            Some(0.000123456),
+            Some(0.000123556),
+            None,
+            Some(-0.000012345),
+        ];
+        let mut compressor = Compressor::default();
+        let mut decompressor = Decompressor::default();
+        for (i, offset) in offsets.iter().enumerate() {
+            let mut descriptor = format!(" 21  1  1  0  0 {:2}.0000000  0  1G01", i * 10);
+            if let Some(offset) = offset {
+                // columns 69-80
+                descriptor = format!("{:<68}{:12.9}", descriptor, offset);
+            }
+            let content = format!("{}\n  22345678.123  \n", descriptor);
+            let compressed = compressor
+                .compress(2, &observables, &Constellation::GPS, &content)
+                .unwrap();
+            let clock_line = compressed.lines().nth(1).unwrap();
+            match i {
+                0 => assert_eq!(clock_line, "3&123456"),
+                1 => assert_eq!(clock_line, "100"),
+                2 => assert_eq!(clock_line, ""),
+                _ => assert_eq!(clock_line, "3&-12345"),
+            }
+            let mut recovered = String::new();
+            for line in compressed.lines() {
+                recovered.push_str(
+                    &decompressor
+                        .decompress(
+                            1,
+                            &Constellation::GPS,
+                            2,
+                            &observables,
+                            &format!("{}\n", line),
+                        )
+                        .unwrap(),
+                );
+            }
+            assert_eq!(recovered.lines().next(), Some(descriptor.as_str()));
+        }
+        // RINEX3: F15.12 in 1E-12 s units
+        let mut compressor = Compressor::default();
+        let mut decompressor = Decompressor::default();
+        for (i, offset) in offsets.iter().enumerate() {
+            let mut descriptor = format!("> 2021 01 01 00 00{:11.7}  0  1", i as f64 * 10.0);
+            if let Some(offset) = offset {
+                // columns 42-56
+                descriptor = format!("{}      {:15.12}", descriptor, offset);
+            }
+            let content = format!("{}\nG01  22345678.123  \n", descriptor);
+            let compressed = compressor
+                .compress(3, &observables, &Constellation::GPS, &content)
+                .unwrap();
+            let clock_line = compressed.lines().nth(1).unwrap();
+            match i {
+                0 => assert_eq!(clock_line, "3&123456000"),
+                1 => assert_eq!(clock_line, "100000"),
+                2 => assert_eq!(clock_line, ""),
+                _ => assert_eq!(clock_line, "3&-12345000"),
+            }
+            let mut recovered = String::new();
+            for line in compressed.lines() {
+                recovered.push_str(
+                    &decompressor
+                        .decompress(
+                            3,
+                            &Constellation::GPS,
+                            3,
+                            &observables,
+                            &format!("{}\n", line),
+                        )
+                        .unwrap(),
+                );
+            }
+            assert_eq!(recovered.lines().next(), Some(descriptor.as_str()));
+        }
+    }
+    #[test]
+    fn epoch_granularity() {
+        use crate::hatanaka::{Compressor, Decompressor, EpochBlock};
+        let rnx = Rinex::from_file("../test_resources/OBS/V2/delf0010.21o").unwrap();
+        let crx = rnx.rnx2crnx1();
+        let mut compressor = Compressor::default();
+        let mut decompressor = Decompressor::default();
+        for ((epoch, flag), (clock_offset, vehicles)) in rnx.observation() {
+            let block = EpochBlock {
+                epoch: *epoch,
+                flag: *flag,
+                clock_offset: *clock_offset,
+                vehicles: vehicles.clone(),
+            };
+            let compressed = compressor.compress_epoch(&crx.header, &block).unwrap();
+            let recovered = decompressor
+                .decompress_epoch(&crx.header, &compressed)
+                .unwrap();
+            assert_eq!(recovered, block, "epoch {} round trip", epoch);
+        }
+        // partial content is rejected
+        let mut compressor = Compressor::default();
+        let mut decompressor = Decompressor::default();
+        let ((epoch, flag), (clock_offset, vehicles)) = rnx.observation().next().unwrap();
+        let block = EpochBlock {
+            epoch: *epoch,
+            flag: *flag,
+            clock_offset: *clock_offset,
+            vehicles: vehicles.clone(),
+        };
+        let compressed = compressor.compress_epoch(&crx.header, &block).unwrap();
+        let partial: String = compressed
+            .lines()
+            .take(3)
+            .map(|l| l.to_owned() + "\n")
+            .collect();
+        assert!(decompressor
+            .decompress_epoch(&crx.header, &partial)
+            .is_err());
+    }
+    #[test]
+    fn empty_epochs() {
+        use crate::hatanaka::{Compressor, Decompressor, EpochBlock};
+        for path in [
+            "../test_resources/OBS/V2/delf0010.21o",
+            "../test_resources/OBS/V3/DUTH0630.22O",
+        ] {
+            let rnx = Rinex::from_file(path).unwrap();
+            let crx = rnx.rnx2crnx();
+            let mut blocks: Vec<EpochBlock> = rnx
+                .observation()
+                .take(5)
+                .map(|((epoch, flag), (clock_offset, vehicles))| EpochBlock {
+                    epoch: *epoch,
+                    flag: *flag,
+                    clock_offset: *clock_offset,
+                    vehicles: vehicles.clone(),
+                })
+                .collect();
+            // receiver reset: no satellites at all
+            let reset = EpochBlock {
+                epoch: blocks[1].epoch + Duration::from_seconds(15.0),
+                flag: EpochFlag::PowerFailure,
+                clock_offset: None,
+                vehicles: Default::default(),
+            };
+            blocks.insert(2, reset);
+
+            let mut compressor = Compressor::default();
+            let mut decompressor = Decompressor::default();
+            for (index, block) in blocks.iter().enumerate() {
+                let compressed = compressor.compress_epoch(&crx.header, block).unwrap();
+                if index == 2 {
+                    assert_eq!(
+                        compressed.lines().count(),
+                        2,
+                        "empty epoch is a descriptor and a clock offset line: \"{}\"",
+                        compressed
+                    );
+                }
+                let recovered = decompressor
+                    .decompress_epoch(&crx.header, &compressed)
+                    .unwrap();
+                assert_eq!(&recovered, block, "{}: epoch #{} round trip", path, index);
+            }
+
+            // file round trip
+            let mut rnx = rnx.clone();
+            rnx.record.as_mut_obs().unwrap().insert(
+                (blocks[2].epoch, blocks[2].flag),
+                (None, Default::default()),
+            );
+            let tmp_path = TmpFile::new("crx");
+            rnx.rnx2crnx().to_file(&tmp_path).unwrap();
+            let parsed = Rinex::from_file(&tmp_path);
+            let parsed = parsed.unwrap();
+            assert!(parsed.epoch().eq(rnx.epoch()), "{}: epochs differ", path);
+            let (_, vehicles) = parsed
+                .record
+                .as_obs()
+                .unwrap()
+                .get(&(blocks[2].epoch, blocks[2].flag))
+                .unwrap();
+            assert!(vehicles.is_empty());
+        }
+    }
+    #[test]
+    fn codec_state() {
+        use crate::hatanaka::{compressor, decompressor, Compressor, Decompressor, HatanakaCodec};
+        use crate::reader::BufferedReader;
+        use std::io::BufRead;
+        let mut reader = BufferedReader::new("../test_resources/CRNX/V3/DUTH0630.22D").unwrap();
+        let header = Header::new(&mut reader).unwrap();
+        let content: Vec<String> = reader.lines().map(|line| line.unwrap() + "\n").collect();
+
+        let mut decompressor = Decompressor::default();
+        let recovered: String = content
+            .iter()
+            .map(|line| decompressor.process(&header, line).unwrap())
+            .collect();
+        assert!(matches!(
+            decompressor.state(),
+            decompressor::State::EpochDescriptor
+        ));
+        // epoch descriptor only
+        decompressor.reset();
+        decompressor.process(&header, &content[0]).unwrap();
+        assert!(!matches!(
+            decompressor.state(),
+            decompressor::State::EpochDescriptor
+        ));
+        // a reset codec starts over
+        decompressor.reset();
+        let again: String = content
+            .iter()
+            .map(|line| decompressor.process(&header, line).unwrap())
+            .collect();
+        assert_eq!(again, recovered);
+
+        let mut compressor = Compressor::default();
+        let descriptor = recovered.lines().next().unwrap().to_owned() + "\n";
+        compressor.process(&header, &descriptor).unwrap();
+        assert_eq!(compressor.state(), compressor::State::Body);
+        compressor.reset();
+        assert_eq!(compressor.state(), compressor::State::EpochDescriptor);
+        let compressed: String = recovered
+            .lines()
+            .map(|line| compressor.process(&header, &format!("{}\n", line)).unwrap())
+            .collect();
+        assert_eq!(compressed, content.concat());
     }
 }
@@ -0,0 +1,233 @@
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    #[cfg(all(feature = "obs", feature = "nav"))]
+    use crate::{
+        clocks::{ClockData, ClockDataType, System},
+        context::ProvidedData,
+        navigation::Ephemeris,
+        record::Record,
+    };
+    use std::path::PathBuf;
+    #[cfg(all(feature = "obs", feature = "nav"))]
+    use std::str::FromStr;
+    #[test]
+    #[cfg(all(feature = "obs", feature = "nav"))]
+    fn rx_clock_estimate() {
+        let prefix = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources");
+        let mut ctx = RnxContext::new(
+            &prefix
+                .join("CRNX")
+                .join("V3")
+                .join("ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz"),
+        )
+        .unwrap();
+
+        // ground position but no SV data source
+        assert!(ctx.rx_clock_estimate().is_empty());
+
+        ctx.load(
+            &prefix
+                .join("NAV")
+                .join("V3")
+                .join("ESBC00DNK_R_20201770000_01D_MN.rnx.gz"),
+        )
+        .unwrap();
+
+        let estimates = ctx.rx_clock_estimate();
+        assert_eq!(estimates.len(), 2880, "missing receiver clock estimates");
+
+        let n = estimates.len() as f64;
+        let mean = estimates.values().sum::<f64>() / n;
+        let std = (estimates
+            .values()
+            .map(|dt| (dt - mean).powi(2))
+            .sum::<f64>()
+            / n)
+            .sqrt();
+        assert!(
+            (mean - 481.0E-6).abs() < 1.0E-6,
+            "bad receiver clock offset: {}",
+            mean
+        );
+        assert!(std < 100.0E-9, "receiver clock estimate too noisy: {}", std);
+
+        // precise clocks prevail: broadcast clocks, offset by 1 us, every 5 minutes
+        let nav = ctx.nav_data().unwrap();
+        let index = crate::navigation::index::EphemerisIndex::new(nav);
+        let sv_list: Vec<SV> = nav.sv().collect();
+        let mut record = crate::clocks::Record::new();
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        // the first signals were transmitted before midnight
+        for i in -1..=288 {
+            let t = t0 + Duration::from_seconds(i as f64 * 300.0);
+            let systems = record
+                .entry(t)
+                .or_default()
+                .entry(ClockDataType::AS)
+                .or_default();
+            for sv in &sv_list {
+                if let Some((toe, eph)) = index.select(*sv, t.max(t0)) {
+                    let dt = Ephemeris::sv_clock_corr(*sv, eph.sv_clock(), t, toe);
+                    let data = ClockData {
+                        bias: dt.to_seconds() + 1.0E-6,
+                        ..Default::default()
+                    };
+                    systems.insert(System::SV(*sv), data);
+                }
+            }
+        }
+        ctx.clk = Some(ProvidedData {
+            paths: Vec::new(),
+            data: Rinex::new(Header::default(), Record::ClockRecord(record)),
+        });
+        let precise = ctx.rx_clock_estimate();
+        assert_eq!(precise.len(), estimates.len());
+        for (t, dt) in &precise {
+            let broadcast = estimates[t];
+            assert!(
+                (dt - broadcast - 1.0E-6).abs() < 20.0E-9,
+                "{}: precise clocks not used ({} vs {})",
+                t,
+                dt,
+                broadcast
+            );
+        }
+    }
+    #[test]
+    #[cfg(all(feature = "obs", feature = "nav"))]
+    fn bootstrap_ground_position() {
+        let prefix = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources");
+        let mut ctx = RnxContext::new(
+            &prefix
+                .join("CRNX")
+                .join("V3")
+                .join("ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz"),
+        )
+        .unwrap();
+        let surveyed = ctx.ground_position().unwrap();
+
+        // no navigation data: not feasible
+        ctx.obs_data_mut().unwrap().header.ground_position = None;
+        assert!(ctx.bootstrap_ground_position_mut(false).is_none());
+
+        ctx.load(
+            &prefix
+                .join("NAV")
+                .join("V3")
+                .join("ESBC00DNK_R_20201770000_01D_MN.rnx.gz"),
+        )
+        .unwrap();
+        let estimated = ctx.bootstrap_ground_position_mut(false).unwrap();
+        let (e, n, u) = estimated.to_enu(&surveyed);
+        let error = (e.powi(2) + n.powi(2) + u.powi(2)).sqrt();
+        assert!(error < 100.0, "coarse position error: {} m", error);
+        assert_eq!(ctx.ground_position(), Some(estimated));
+        // header is preserved
+        assert!(ctx.obs_data().unwrap().header.ground_position.is_none());
+
+        // defined positions are never overwritten
+        ctx.obs_data_mut().unwrap().header.ground_position = Some(surveyed);
+        assert_eq!(ctx.bootstrap_ground_position_mut(true), Some(surveyed));
+        assert_eq!(
+            ctx.obs_data().unwrap().header.ground_position,
+            Some(surveyed)
+        );
+    }
+    #[test]
+    fn load_products() {
+        let prefix = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources");
+        let obs = prefix.join("OBS").join("V2").join("AJAC3550.21O");
+        let clk = prefix.join("CLK").join("V2").join("COD20352.CLK");
+        let met = prefix.join("MET").join("V2").join("abvi0010.15m");
+
+        let mut ctx = RnxContext::default();
+        ctx.load_product(&obs, ProductType::Observation).unwrap();
+        ctx.load_product(&clk, ProductType::HighPrecisionClock)
+            .unwrap();
+        ctx.load_product(&met, ProductType::MeteoObservation)
+            .unwrap();
+        assert!(ctx.has_observation_data());
+        assert!(ctx.has_clock_data());
+        assert!(ctx.has_meteo_data());
+        assert_eq!(ctx.clk_paths(), Some(&[clk.clone()][..]));
+        assert_eq!(ctx.rinex_path(), Some(&obs));
+
+        // explicit role must match the content
+        let mut ctx = RnxContext::default();
+        assert!(ctx
+            .load_product(&obs, ProductType::BroadcastNavigation)
+            .is_err());
+        assert!(ctx.load_product(&met, ProductType::Observation).is_err());
+        assert!(ctx
+            .load_product(&obs, ProductType::HighPrecisionOrbit)
+            .is_err());
+        assert!(!ctx.has_observation_data());
+        assert!(!ctx.has_navigation_data());
+
+        // clock RINEX is now identified when loading blindly
+        ctx.load(&clk).unwrap();
+        assert!(ctx.has_clock_data());
+        assert_eq!(ctx.rinex_path(), Some(&clk));
+    }
+    #[test]
+    fn time_spans() {
+        let prefix = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources");
+        let obs = prefix.join("OBS").join("V2").join("delf0010.21o");
+        let nav = prefix
+            .join("NAV")
+            .join("V3")
+            .join("AMEL00NLD_R_20210010000_01D_MN.rnx");
+        let met = prefix.join("MET").join("V2").join("abvi0010.15m");
+
+        let mut ctx = RnxContext::default();
+        assert!(ctx.common_time_span().is_none());
+
+        ctx.load_product(&obs, ProductType::Observation).unwrap();
+        ctx.load_product(&nav, ProductType::BroadcastNavigation)
+            .unwrap();
+        assert_eq!(ctx.time_spans().len(), 2);
+
+        // observations are within the ephemeris time frame
+        let obs_start = ctx.obs_data().unwrap().first_epoch().unwrap();
+        let obs_end = ctx.obs_data().unwrap().last_epoch().unwrap();
+        let (start, end) = ctx.common_time_span().unwrap();
+        assert_eq!((start, end), (obs_start, obs_end));
+
+        #[cfg(feature = "processing")]
+        {
+            let end = start + Duration::from_seconds(60.0);
+            ctx.trim_mut(start, end);
+            assert!(ctx
+                .obs_data()
+                .unwrap()
+                .epoch()
+                .all(|t| t >= start && t <= end));
+            // ephemerides are trimmed with a margin
+            let nav = ctx.nav_data().unwrap();
+            assert!(nav.epoch().count() > 0);
+            assert!(nav
+                .epoch()
+                .all(|t| t >= start - Duration::from_hours(4.0)
+                    && t <= end + Duration::from_hours(4.0)));
+        }
+
+        // 2015 meteo observations do not overlap
+        ctx.load_product(&met, ProductType::MeteoObservation)
+            .unwrap();
+        assert_eq!(ctx.time_spans().len(), 3);
+        assert!(ctx.common_time_span().is_none());
+    }
+}
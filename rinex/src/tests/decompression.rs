@@ -1,11 +1,11 @@
 #[cfg(test)]
 mod test {
-    use crate::hatanaka::Decompressor;
+    use crate::hatanaka::{verify, CrinexIndex, Decompressor};
     use crate::tests::toolkit::obsrinex_check_observables;
-    use crate::tests::toolkit::random_name;
     use crate::tests::toolkit::test_observation_rinex;
+    use crate::tests::toolkit::TmpFile;
     use crate::{erratic_time_frame, evenly_spaced_time_frame, tests::toolkit::TestTimeFrame};
-    use crate::{observable, prelude::*};
+    use crate::{observable, observation, prelude::*};
     use itertools::Itertools;
     use std::collections::HashMap;
     use std::path::Path;
@@ -138,7 +138,7 @@ mod test {
                     Some("MIXED"),
                     "GPS, GLO, GAL, EGNOS",
                     "G07, G08, G10, G16, G18, G21, G23, G26, G32, R04, R05, R10, R12, R19, R20, R21, E04, E11, E12, E19, E24, E25, E31, E33, S23, S36",
-                    "L1, L2, C1, C2, P1, P2, D1, D2, S1, S2, L5, C5, D5, S5, L7, C7, D7, S7, L8, C8, D8, S8",
+                    "L1, L2, C1, C2, P2, D1, D2, S1, S2, L5, C5, D5, S5, L7, C7, D7, S7, L8, C8, D8, S8",
                     Some("2021-12-21T00:00:00 GPST"),
                     None,
                     evenly_spaced_time_frame!(
@@ -149,7 +149,7 @@ mod test {
             }
             // decompress and write to file
             rnx.crnx2rnx_mut();
-            let filename = format!("{}.rnx", random_name(10));
+            let filename = TmpFile::new("rnx");
             assert!(
                 rnx.to_file(&filename).is_ok(),
                 "failed to dump \"{}\" after decompression",
@@ -165,12 +165,20 @@ mod test {
             let model = Rinex::from_file(&path);
             assert!(model.is_ok(), "Failed to parse test model \"{}\"", path);
 
-            //let model = model.unwrap();
+            // decompressed content describes the same observables
+            let model = model.unwrap();
+            let mut expected: Vec<_> = model.observable().collect();
+            let mut observables: Vec<_> = rnx.observable().collect();
+            expected.sort();
+            observables.sort();
+            assert_eq!(
+                observables, expected,
+                "{}: decompressed observables",
+                crnx_name
+            );
+
             // run testbench
             // test_toolkit::test_against_model(&rnx, &model, &path);
-
-            // remove copy
-            let _ = std::fs::remove_file(filename);
         }
     }
     #[test]
@@ -263,6 +271,38 @@ mod test {
             .decompress(1, &Constellation::Mixed, 2, &obscodes, content)
             .is_err());
     }
+    /*
+     * Truncated fields and multibyte characters must not panic
+     */
+    #[test]
+    fn faulty_fields() {
+        let mut obscodes: HashMap<Constellation, Vec<Observable>> = HashMap::new();
+        obscodes.insert(
+            Constellation::GPS,
+            vec![
+                Observable::from_str("C1C").unwrap(),
+                Observable::from_str("L1C").unwrap(),
+            ],
+        );
+        for content in [
+            "> 2022 03 04 00 00  0.0000000  0  2      G01G0\n",
+            "> 2022 03 04 00 00  0.0000000  0  1      G0é\n",
+            "> 2022 03 04 00 00  0.0000000  0  1      G01\n\n3&2024351756é 3&105381224220\n",
+            "> 2022 03 04 00 00  0.0000000  0  1      G01\n1&€\n",
+            "> 2022 03 04 é\n",
+        ] {
+            let mut decompressor = Decompressor::new();
+            for line in content.lines() {
+                let _ = decompressor.decompress(
+                    3,
+                    &Constellation::Mixed,
+                    3,
+                    &obscodes,
+                    &(line.to_owned() + "\n"),
+                );
+            }
+        }
+    }
     #[test]
     fn crnx_v1_zegv0010_21d() {
         let path = Path::new(env!("CARGO_MANIFEST_DIR"))
@@ -853,4 +893,105 @@ mod test {
             &["C1C", "C5I", "D1C", "D5I", "L1C", "L5I", "S1C", "S5I"],
         );
     }
+    #[test]
+    fn crinex_index() {
+        for (path, spacing) in [
+            ("../test_resources/CRNX/V1/AJAC3550.21D", 1),
+            ("../test_resources/CRNX/V1/AJAC3550.21D", 7),
+            ("../test_resources/CRNX/V3/DUTH0630.22D", 3),
+            #[cfg(feature = "flate2")]
+            (
+                "../test_resources/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz",
+                120,
+            ),
+        ] {
+            let rinex = Rinex::from_file(path).unwrap();
+            let record = rinex.record.as_obs().unwrap();
+            let index = CrinexIndex::build(path, spacing).unwrap();
+            assert_eq!(index.len(), (record.len() + spacing - 1) / spacing);
+            for checkpoint in index.checkpoints() {
+                assert!(record.keys().any(|(e, _)| *e == checkpoint.epoch));
+            }
+            let epochs: Vec<Epoch> = rinex.epoch().collect();
+            let n = epochs.len();
+            for (start, end) in [
+                (epochs[0], epochs[n - 1]),
+                (epochs[n / 3], epochs[n / 2]),
+                (epochs[n - 1], epochs[n - 1]),
+            ] {
+                let window = index.decompress_window(start, end).unwrap();
+                let expected: observation::Record = record
+                    .iter()
+                    .filter(|((e, _), _)| *e >= start && *e <= end)
+                    .map(|(k, v)| (*k, v.clone()))
+                    .collect();
+                assert_eq!(
+                    window.record.as_obs().unwrap(),
+                    &expected,
+                    "{}: [{}, {}] window",
+                    path,
+                    start,
+                    end
+                );
+            }
+        }
+        assert!(CrinexIndex::build("../test_resources/OBS/V2/AJAC3550.21O", 10).is_err());
+    }
+    #[test]
+    fn crinex_index_corrupt() {
+        let content = std::fs::read_to_string("../test_resources/CRNX/V3/DUTH0630.22D").unwrap();
+        // faulty kernel initialization, within the first epoch
+        let corrupt = content.replacen("\n3&20619020680 ", "\nx&20619020680 ", 1);
+        assert_ne!(content, corrupt);
+        let path = TmpFile::new("22D");
+        std::fs::write(&path, &corrupt).unwrap();
+        assert!(CrinexIndex::build(&path, 3).is_err());
+
+        // file corrupted after the index was built
+        std::fs::write(&path, &content).unwrap();
+        let index = CrinexIndex::build(&path, 3).unwrap();
+        let first = index.checkpoints()[0].epoch;
+        std::fs::write(&path, &corrupt).unwrap();
+        assert!(index.decompress_window(first, first).is_err());
+
+        // file replaced by readable RINEX
+        std::fs::copy("../test_resources/OBS/V3/DUTH0630.22O", &path).unwrap();
+        assert!(index.decompress_window(first, first).is_err());
+    }
+    #[test]
+    fn crinex_verification() {
+        for path in [
+            "../test_resources/CRNX/V1/AJAC3550.21D",
+            "../test_resources/CRNX/V1/KOSG0010.95D",
+            "../test_resources/CRNX/V1/aopr0010.17d",
+            "../test_resources/CRNX/V1/eijs0010.21d",
+            "../test_resources/CRNX/V1/npaz3550.21d",
+            "../test_resources/CRNX/V1/zegv0010.21d",
+            "../test_resources/CRNX/V3/ACOR00ESP_R_20213550000_01D_30S_MO.crx",
+            "../test_resources/CRNX/V3/DUTH0630.22D",
+            "../test_resources/CRNX/V3/KMS300DNK_R_20221591000_01H_30S_MO.crx",
+            "../test_resources/CRNX/V3/VLNS0010.22D",
+            "../test_resources/CRNX/V3/flrs0010.12d",
+        ] {
+            let report = verify(path).unwrap();
+            assert!(report.is_ok(), "{}: {:?}", path, report.mismatch);
+            assert!(report.lines > 0, "{}", path);
+        }
+
+        // garbled residual
+        let content = std::fs::read_to_string("../test_resources/CRNX/V3/DUTH0630.22D").unwrap();
+        let corrupt = content.replacen("\n561875520 ", "\n5618755#0 ", 1);
+        assert_ne!(content, corrupt);
+        let path = TmpFile::new("22D");
+        std::fs::write(&path, corrupt).unwrap();
+        let report = verify(&path).unwrap();
+        let mismatch = report.mismatch.unwrap();
+        assert_eq!(
+            mismatch.original.as_deref(),
+            Some("5618755#0 2952674197 -950289 -500 561875800 2300784492 -740484 250")
+        );
+        assert_eq!(mismatch.line, 60);
+
+        assert!(verify("../test_resources/OBS/V2/AJAC3550.21O").is_err());
+    }
 }
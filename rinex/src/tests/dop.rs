@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod test {
+    use crate::filter;
+    use crate::prelude::*;
+    use crate::preprocessing::*;
+    use crate::processing::dop;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    #[test]
+    fn esbc00dnk_dop() {
+        let prefix = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources");
+        let obs = Rinex::from_file(
+            prefix
+                .join("CRNX")
+                .join("V3")
+                .join("ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+                .to_string_lossy()
+                .as_ref(),
+        )
+        .unwrap()
+        .filter(filter!("<2020-06-25T02:00:00 GPST"));
+        let nav = Rinex::from_file(
+            prefix
+                .join("NAV")
+                .join("V3")
+                .join("ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+                .to_string_lossy()
+                .as_ref(),
+        )
+        .unwrap();
+        let position = obs.header.ground_position.unwrap();
+
+        let dops = dop(&nav, &obs, position);
+        assert_eq!(dops.len(), 240, "missing dop estimates");
+        for (t, dop) in &dops {
+            assert!(dop.satellites >= 4);
+            assert!(
+                (dop.gdop.powi(2) - dop.pdop.powi(2) - dop.tdop.powi(2)).abs() < 1.0E-9,
+                "{}: inconsistent gdop",
+                t
+            );
+            assert!(
+                (dop.pdop.powi(2) - dop.hdop.powi(2) - dop.vdop.powi(2)).abs() < 1.0E-9,
+                "{}: inconsistent pdop",
+                t
+            );
+            assert!(
+                dop.hdop < dop.vdop,
+                "{}: hdop should be smaller than vdop",
+                t
+            );
+        }
+        // multi-GNSS, open sky
+        let mean = dops.values().map(|dop| dop.gdop).sum::<f64>() / dops.len() as f64;
+        assert!(mean > 0.5 && mean < 3.0, "unexpected mean gdop: {}", mean);
+
+        // masks are taken into account: fewer satellites, degraded geometry
+        let gps = obs.filter(filter!("GPS"));
+        let gps_dops = dop(&nav, &gps, position);
+        let gps_mean = gps_dops.values().map(|dop| dop.gdop).sum::<f64>() / gps_dops.len() as f64;
+        assert!(gps_mean > mean, "masked geometry should be degraded");
+        for (t, dop) in &gps_dops {
+            assert!(dop.satellites <= dops[t].satellites);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod test {
+    use crate::estimation::{weighted_least_squares, Error, Kalman, Matrix};
+
+    #[test]
+    fn matrix_inverse() {
+        let m = Matrix::from_rows(&[
+            vec![4.0, 7.0, 2.0],
+            vec![3.0, 6.0, 1.0],
+            vec![2.0, 5.0, 3.0],
+        ])
+        .unwrap();
+        let inv = m.inverse().expect("failed to invert");
+        let identity = m.mul(&inv).unwrap();
+        for i in 0..3 {
+            for j in 0..3 {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((identity[(i, j)] - expected).abs() < 1.0E-12);
+            }
+        }
+        // singular
+        let m = Matrix::from_rows(&[vec![1.0, 2.0], vec![2.0, 4.0]]).unwrap();
+        assert!(m.inverse().is_none());
+        // not square
+        assert!(Matrix::zeros(2, 3).inverse().is_none());
+    }
+
+    #[test]
+    fn matrix_dimensions() {
+        assert_eq!(
+            Matrix::from_rows(&[vec![1.0, 2.0], vec![3.0]]),
+            Err(Error::DimensionMismatch)
+        );
+        let (a, b) = (Matrix::zeros(2, 3), Matrix::zeros(2, 3));
+        assert_eq!(a.mul(&b), Err(Error::DimensionMismatch));
+        assert_eq!(a.mul(&b.transpose()), Ok(Matrix::zeros(2, 2)));
+        assert_eq!(a.add(&b.transpose()), Err(Error::DimensionMismatch));
+        assert_eq!(a.sub(&b.transpose()), Err(Error::DimensionMismatch));
+        assert_eq!(a.mul_vec(&[1.0, 2.0]), Err(Error::DimensionMismatch));
+        assert_eq!(
+            Kalman::new(vec![0.0, 0.0], &[1.0], vec![0.0, 0.0]),
+            Err(Error::DimensionMismatch)
+        );
+        assert_eq!(
+            Kalman::new(vec![0.0], &[1.0], vec![]),
+            Err(Error::DimensionMismatch)
+        );
+    }
+
+    #[test]
+    fn matrix_states() {
+        let mut m = Matrix::from_rows(&[vec![1.0, 0.5], vec![0.5, 2.0]]).unwrap();
+        m.push_state(3.0);
+        assert_eq!(m.diagonal(), vec![1.0, 2.0, 3.0]);
+        assert_eq!(m[(0, 2)], 0.0);
+        m.reset_state(1, 4.0);
+        assert_eq!(m[(0, 1)], 0.0);
+        assert_eq!(m[(1, 0)], 0.0);
+        m.remove_state(0).unwrap();
+        assert_eq!(m, Matrix::from_diagonal(&[4.0, 3.0]));
+        assert_eq!(m.remove_state(2), Err(Error::InvalidState(2)));
+    }
+
+    #[test]
+    fn least_squares() {
+        // y = 2 - 3t + 0.5t², one outlier downweighted
+        let t: Vec<f64> = (0..10).map(|i| i as f64).collect();
+        let mut y: Vec<f64> = t.iter().map(|t| 2.0 - 3.0 * t + 0.5 * t * t).collect();
+        y[5] += 100.0;
+        let mut weights = vec![1.0; 10];
+        weights[5] = 1.0E-12;
+        let h =
+            Matrix::from_rows(&t.iter().map(|t| vec![1.0, *t, t * t]).collect::<Vec<_>>()).unwrap();
+
+        let solution = weighted_least_squares(&h, &y, &weights).unwrap();
+        assert!((solution.x[0] - 2.0).abs() < 1.0E-6);
+        assert!((solution.x[1] + 3.0).abs() < 1.0E-6);
+        assert!((solution.x[2] - 0.5).abs() < 1.0E-6);
+        assert!((solution.residuals[5] - 100.0).abs() < 1.0E-3);
+        assert_eq!(solution.covariance.rows(), 3);
+
+        let solution = weighted_least_squares(&h, &y, &[1.0; 10]).unwrap();
+        assert!((solution.x[0] - 2.0).abs() > 1.0);
+
+        assert_eq!(
+            weighted_least_squares(&h, &y[..9], &weights),
+            Err(Error::DimensionMismatch)
+        );
+        let h = Matrix::from_rows(&[vec![1.0, 1.0], vec![1.0, 1.0]]).unwrap();
+        assert_eq!(
+            weighted_least_squares(&h, &[1.0, 2.0], &[1.0, 1.0]),
+            Err(Error::SingularMatrix)
+        );
+    }
+
+    #[test]
+    fn kalman_random_walk() {
+        // position and drifting bias, observed through two sensors
+        let mut kf = Kalman::new(vec![0.0, 0.0], &[1.0E4, 1.0E4], vec![0.0, 1.0E-2]).unwrap();
+        let h = Matrix::from_rows(&[vec![1.0, 0.0], vec![1.0, 1.0]]).unwrap();
+        let r = Matrix::from_diagonal(&[0.01, 0.01]);
+        for i in 0..100 {
+            let bias = 0.01 * i as f64;
+            let (y1, y2) = (5.0, 5.0 + bias);
+            kf.predict(1.0);
+            let innovations = [y1 - kf.x[0], y2 - kf.x[0] - kf.x[1]];
+            kf.update(&h, &innovations, &r).unwrap();
+        }
+        assert!((kf.x[0] - 5.0).abs() < 1.0E-2);
+        assert!((kf.x[1] - 0.99).abs() < 5.0E-2);
+        // static state converged, random walk state did not
+        assert!(kf.p[(0, 0)] < 1.0E-2);
+        assert!(kf.p[(1, 1)] > kf.p[(0, 0)]);
+
+        kf.push_state(1.0, 100.0, 0.0);
+        assert_eq!(kf.len(), 3);
+        kf.remove_state(2).unwrap();
+        assert_eq!(kf.len(), 2);
+        assert!(kf
+            .update(&Matrix::zeros(1, 3), &[0.0], &Matrix::identity(1))
+            .is_err());
+
+        let mut kf = Kalman::new(vec![], &[], vec![]).unwrap();
+        assert!(kf.is_empty());
+        assert_eq!(kf.remove_state(0), Err(Error::InvalidState(0)));
+        assert!(kf.is_empty());
+    }
+}
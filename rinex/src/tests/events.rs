@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod test {
+    use crate::marker::GeodeticMarker;
+    use crate::prelude::*;
+    use crate::tests::toolkit::TmpFile;
+    use crate::{hardware::Antenna, record::Events, version::Version};
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    /*
+     * Inserts events prior, inside and after the record,
+     * and verifies they survive a RINEX and a CRINEX round trip
+     */
+    fn events_round_trip(path: PathBuf) {
+        let mut rinex = Rinex::from_file(&path.to_string_lossy()).unwrap();
+        let epochs: Vec<Epoch> = rinex.epoch().collect();
+        let first = epochs[0];
+        let last = epochs[epochs.len() - 1];
+
+        let antenna = Antenna::default()
+            .with_model("TRM57971.00     NONE")
+            .with_serial_number("1440911917")
+            .with_height(0.0614);
+        rinex.insert_antenna_swap(first + Duration::from_seconds(15.0), &antenna);
+        rinex.insert_event(
+            epochs[2],
+            EpochFlag::ExternalEvent,
+            vec!["Receiver power cycled                                       COMMENT".to_string()],
+        );
+        let marker = GeodeticMarker::default().with_name("MOVED");
+        rinex.insert_marker_change(last + Duration::from_seconds(30.0), &marker);
+        // not an event
+        rinex.insert_event(last, EpochFlag::Ok, vec!["dropped".to_string()]);
+        assert_eq!(rinex.events.len(), 3);
+
+        let expected: Events = rinex.events.clone();
+        for crinex in [false, true] {
+            let produced = match crinex {
+                true => rinex.rnx2crnx(),
+                false => rinex.clone(),
+            };
+            let tmp_path = TmpFile::new("rnx");
+            produced.to_file(&tmp_path).unwrap();
+            let parsed = Rinex::from_file(&tmp_path);
+            let parsed = parsed.unwrap();
+
+            assert_eq!(
+                parsed.events, expected,
+                "events not preserved (crinex={})",
+                crinex
+            );
+            assert!(
+                parsed.epoch().eq(epochs.iter().copied()),
+                "events should not alter the record (crinex={})",
+                crinex
+            );
+            let (_, records) = parsed
+                .events
+                .iter()
+                .find(|((_, flag), _)| *flag == EpochFlag::HeaderInformationFollows)
+                .unwrap();
+            assert!(records[0].ends_with("ANT # / TYPE"));
+            assert!(records[1].ends_with("ANTENNA: DELTA H/E/N"));
+            assert!(
+                parsed
+                    .comments
+                    .values()
+                    .flatten()
+                    .all(|comment| !comment.contains("power cycled")),
+                "special records are not record comments"
+            );
+        }
+    }
+    #[test]
+    fn v3_events() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("../")
+            .join("test_resources")
+            .join("OBS")
+            .join("V3")
+            .join("DUTH0630.22O");
+        events_round_trip(path);
+    }
+    #[test]
+    fn v2_events() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("../")
+            .join("test_resources")
+            .join("OBS")
+            .join("V2")
+            .join("aopr0010.17o");
+        events_round_trip(path);
+    }
+    #[test]
+    fn event_descriptor() {
+        let header = Header::basic_obs().with_version(Version { major: 3, minor: 0 });
+        let t = Epoch::from_str("2022-03-04T00:00:15 GPST").unwrap();
+        let line = "> 2022 03 04 00 00 15.0000000  4  2";
+        assert_eq!(
+            crate::observation::record::parse_event(&header, line, TimeScale::GPST),
+            Some(((t, EpochFlag::HeaderInformationFollows), 2))
+        );
+        let line = "> 2022 03 04 00 00 15.0000000  0  2";
+        assert!(crate::observation::record::parse_event(&header, line, TimeScale::GPST).is_none());
+    }
+}
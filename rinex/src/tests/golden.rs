@@ -0,0 +1,372 @@
+//! Regression corpus: curated real world files,
+//! their expected parse results, byte exact production and fault injection.
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::tests::toolkit::TmpFile;
+    use std::path::Path;
+
+    struct Golden {
+        /// File path, relative to test_resources
+        path: &'static str,
+        /// Expected RINEX type
+        rinex_type: RinexType,
+        /// Expected major revision
+        major: u8,
+        /// Expected number of epochs
+        epochs: usize,
+        /// Expected number of satellites (OBS and NAV RINEX only)
+        satellites: usize,
+        /// True when production is byte exact: the record we generate
+        /// is identical to the original record (decompressed).
+        /// We do not preserve the order of the header lines nor the order
+        /// of the satellites within an epoch.
+        byte_exact: bool,
+    }
+
+    const fn golden(
+        path: &'static str,
+        rinex_type: RinexType,
+        major: u8,
+        epochs: usize,
+        satellites: usize,
+        byte_exact: bool,
+    ) -> Golden {
+        Golden {
+            path,
+            rinex_type,
+            major,
+            epochs,
+            satellites,
+            byte_exact,
+        }
+    }
+
+    /*
+     * RINEX 2/3/4, CRINEX 1/3, gzip compressed files,
+     * omitted LLI/SSI flags, epochs of more than 12 satellites
+     * and events (epoch flags 2 to 5, with their special records)
+     */
+    const CORPUS: [Golden; 21] = [
+        golden(
+            "OBS/V2/AJAC3550.21O",
+            RinexType::ObservationData,
+            2,
+            2,
+            26,
+            false,
+        ),
+        golden(
+            "OBS/V2/aopr0010.17o",
+            RinexType::ObservationData,
+            2,
+            3,
+            19,
+            false,
+        ),
+        golden(
+            "OBS/V2/aopr0010_events.17o",
+            RinexType::ObservationData,
+            2,
+            3,
+            19,
+            true,
+        ),
+        golden(
+            "OBS/V3/DUTH0630.22O",
+            RinexType::ObservationData,
+            3,
+            3,
+            20,
+            false,
+        ),
+        golden(
+            "OBS/V3/DUTH0630_events.22O",
+            RinexType::ObservationData,
+            3,
+            3,
+            20,
+            true,
+        ),
+        golden(
+            "OBS/V3/NOA10630.22O",
+            RinexType::ObservationData,
+            3,
+            4,
+            10,
+            false,
+        ),
+        golden(
+            "OBS/V3/ALAC00ESP_R_20220090000_01D_30S_MO.rnx",
+            RinexType::ObservationData,
+            3,
+            3,
+            40,
+            false,
+        ),
+        golden(
+            "CRNX/V1/AJAC3550.21D",
+            RinexType::ObservationData,
+            2,
+            2,
+            26,
+            false,
+        ),
+        golden(
+            "CRNX/V1/aopr0010.17d",
+            RinexType::ObservationData,
+            2,
+            3,
+            19,
+            false,
+        ),
+        golden(
+            "CRNX/V3/DUTH0630.22D",
+            RinexType::ObservationData,
+            3,
+            3,
+            20,
+            false,
+        ),
+        golden(
+            "CRNX/V3/VLNS0010.22D",
+            RinexType::ObservationData,
+            3,
+            3,
+            18,
+            false,
+        ),
+        golden("MET/V2/abvi0010.15m", RinexType::MeteoData, 2, 74, 0, true),
+        golden("MET/V2/cari0010.07m", RinexType::MeteoData, 2, 3, 0, true),
+        golden(
+            "MET/V3/POTS00DEU_R_20232540000_01D_05M_MM.rnx.gz",
+            RinexType::MeteoData,
+            3,
+            288,
+            0,
+            false,
+        ),
+        golden("MET/V4/example1.txt", RinexType::MeteoData, 4, 5, 0, false),
+        golden(
+            "NAV/V2/amel0010.21g",
+            RinexType::NavigationData,
+            2,
+            4,
+            6,
+            false,
+        ),
+        golden(
+            "NAV/V2/egno0010.21h",
+            RinexType::NavigationData,
+            2,
+            2,
+            2,
+            true,
+        ),
+        golden(
+            "NAV/V2/cbw10010.21n.gz",
+            RinexType::NavigationData,
+            2,
+            27,
+            32,
+            false,
+        ),
+        golden(
+            "NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx",
+            RinexType::NavigationData,
+            3,
+            6,
+            6,
+            false,
+        ),
+        golden("CLK/V3/USNO1.txt", RinexType::ClockData, 3, 1, 0, false),
+        golden("CLK/V3/example1.txt", RinexType::ClockData, 3, 1, 0, false),
+    ];
+
+    fn corpus() -> impl Iterator<Item = (&'static Golden, String)> {
+        CORPUS
+            .iter()
+            .filter(|golden| !golden.path.ends_with(".gz") || cfg!(feature = "flate2"))
+            .map(|golden| {
+                let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+                    .join("..")
+                    .join("test_resources")
+                    .join(golden.path);
+                (golden, path.to_string_lossy().to_string())
+            })
+    }
+
+    fn produce(rinex: &Rinex) -> Vec<u8> {
+        let tmp_path = TmpFile::new("rnx");
+        rinex.to_file(&tmp_path).unwrap();
+        let content = std::fs::read(&tmp_path).unwrap();
+        content
+    }
+
+    /*
+     * Reads given file, decompressed
+     */
+    fn read_plain(path: &str) -> Vec<u8> {
+        let content = std::fs::read(path).unwrap();
+        #[cfg(feature = "flate2")]
+        if path.ends_with(".gz") {
+            use std::io::Read;
+            let mut plain = Vec::new();
+            flate2::read::GzDecoder::new(content.as_slice())
+                .read_to_end(&mut plain)
+                .unwrap();
+            return plain;
+        }
+        content
+    }
+
+    /*
+     * Returns the record section of given content
+     */
+    fn record(content: &[u8]) -> &[u8] {
+        let label = b"END OF HEADER";
+        let end = content
+            .windows(label.len())
+            .position(|w| w == label)
+            .expect("missing END OF HEADER");
+        match content[end..].iter().position(|b| *b == b'\n') {
+            Some(eol) => &content[end + eol + 1..],
+            None => &[],
+        }
+    }
+
+    /*
+     * Returns the first line (starting at 0) that differs
+     */
+    fn first_difference(lhs: &[u8], rhs: &[u8]) -> Option<usize> {
+        if lhs == rhs {
+            return None;
+        }
+        let lhs = String::from_utf8_lossy(lhs);
+        let rhs = String::from_utf8_lossy(rhs);
+        let (mut lhs_lines, mut rhs_lines) = (lhs.split('\n'), rhs.split('\n'));
+        let mut line = 0;
+        loop {
+            if lhs_lines.next() != rhs_lines.next() {
+                return Some(line);
+            }
+            line += 1;
+        }
+    }
+
+    /*
+     * Parses given content, as if it were a file,
+     * reporting panics as errors
+     */
+    fn parse_content(content: &[u8]) -> std::thread::Result<Result<usize, String>> {
+        let tmp_path = TmpFile::new("rnx");
+        std::fs::write(&tmp_path, content).unwrap();
+        std::panic::catch_unwind(|| {
+            Rinex::from_file(&tmp_path)
+                .map(|rinex| rinex.epoch().count())
+                .map_err(|e| e.to_string())
+        })
+    }
+
+    #[test]
+    fn golden_parsing() {
+        for (golden, path) in corpus() {
+            let rinex = Rinex::from_file(&path).unwrap();
+            assert_eq!(
+                rinex.header.rinex_type, golden.rinex_type,
+                "{}",
+                golden.path
+            );
+            assert_eq!(rinex.header.version.major, golden.major, "{}", golden.path);
+            assert_eq!(rinex.epoch().count(), golden.epochs, "{}", golden.path);
+            if rinex.is_observation_rinex() || rinex.is_navigation_rinex() {
+                assert_eq!(rinex.sv().count(), golden.satellites, "{}", golden.path);
+            }
+        }
+    }
+
+    #[test]
+    fn golden_byte_exact_production() {
+        // Clock RINEX production is not reliable yet
+        for (golden, path) in
+            corpus().filter(|(golden, _)| golden.rinex_type != RinexType::ClockData)
+        {
+            let rinex = Rinex::from_file(&path).unwrap();
+            let produced = produce(&rinex);
+
+            // header content is preserved, record is byte exact
+            let tmp_path = TmpFile::new("rnx");
+            std::fs::write(&tmp_path, &produced).unwrap();
+            let parsed = Rinex::from_file(&tmp_path).unwrap();
+            assert_eq!(parsed.epoch().count(), golden.epochs, "{}", golden.path);
+            assert_eq!(parsed.events, rinex.events, "{}", golden.path);
+            if !golden.byte_exact {
+                continue;
+            }
+            assert_eq!(parsed.header, rinex.header, "{}", golden.path);
+            let original = read_plain(&path);
+            if let Some(line) = first_difference(record(&original), record(&produced)) {
+                panic!(
+                    "{}: production is not byte exact, record line {}:\n{:?}\n{:?}",
+                    golden.path,
+                    line + 1,
+                    String::from_utf8_lossy(record(&original))
+                        .lines()
+                        .nth(line)
+                        .unwrap_or_default(),
+                    String::from_utf8_lossy(record(&produced))
+                        .lines()
+                        .nth(line)
+                        .unwrap_or_default(),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fault_injection() {
+        for (golden, path) in corpus() {
+            // work on our own (plain text) production
+            let rinex = Rinex::from_file(&path).unwrap();
+            let content = match rinex.header.rinex_type {
+                RinexType::ClockData => std::fs::read(&path).unwrap(),
+                _ => produce(&rinex),
+            };
+
+            // interrupted transfers
+            for k in 1..20 {
+                let truncated = &content[..content.len() * k / 20];
+                match parse_content(truncated) {
+                    Ok(Ok(epochs)) => assert!(
+                        epochs <= golden.epochs,
+                        "{}: truncated at {} bytes: too many epochs",
+                        golden.path,
+                        truncated.len()
+                    ),
+                    Ok(Err(_)) => {},
+                    Err(_) => panic!(
+                        "{}: truncated at {} bytes: parser panicked",
+                        golden.path,
+                        truncated.len()
+                    ),
+                }
+            }
+
+            // corrupted bytes
+            for k in 1..40 {
+                let pos = content.len() * k / 40;
+                for junk in [b'x', b' ', b'-', b'\n'] {
+                    let mut corrupted = content.clone();
+                    corrupted[pos] = junk;
+                    assert!(
+                        parse_content(&corrupted).is_ok(),
+                        "{}: byte {} corrupted to {:?}: parser panicked",
+                        golden.path,
+                        pos,
+                        junk as char
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+#[cfg(test)]
+mod test {
+    use crate::clocks::{ClockData, ClockDataType, System};
+    use crate::prelude::*;
+    use crate::preprocessing::*;
+    use crate::record::Record;
+    use std::collections::HashMap;
+    use std::str::FromStr;
+    fn meteo_record() -> (Epoch, Record) {
+        let t0 = Epoch::from_str("2023-09-11T00:00:00 UTC").unwrap();
+        let minutes = |m: f64| t0 + Duration::from_seconds(m * 60.0);
+        let samples = vec![
+            (
+                0.0,
+                vec![
+                    (Observable::Pressure, 1000.0),
+                    (Observable::Temperature, 10.0),
+                ],
+            ),
+            (
+                1.0,
+                vec![
+                    (Observable::Pressure, 1001.0),
+                    (Observable::Temperature, 11.0),
+                ],
+            ),
+            (2.0, vec![(Observable::Pressure, 1002.0)]),
+            (
+                5.0,
+                vec![
+                    (Observable::Pressure, 1005.0),
+                    (Observable::Temperature, 14.0),
+                ],
+            ),
+            (
+                6.0,
+                vec![
+                    (Observable::Pressure, 1006.0),
+                    (Observable::Temperature, 15.0),
+                ],
+            ),
+            (60.0, vec![(Observable::Pressure, 1060.0)]),
+        ];
+        let record = samples
+            .into_iter()
+            .map(|(m, data)| (minutes(m), data.into_iter().collect()))
+            .collect();
+        (t0, Record::MeteoRecord(record))
+    }
+    #[test]
+    fn meteo_interpolate_at() {
+        let (t0, record) = meteo_record();
+        let minutes = |m: f64| t0 + Duration::from_seconds(m * 60.0);
+
+        let t = minutes(0.5);
+        let interpolated = record.interpolate_at(t).unwrap();
+        let data = &interpolated.as_meteo().unwrap()[&t];
+        assert_eq!(data[&Observable::Pressure], 1000.5);
+        assert_eq!(data[&Observable::Temperature], 10.5);
+
+        // temperature is missing @ 2', its closest samples are used
+        let t = minutes(3.0);
+        let interpolated = record.interpolate_at(t).unwrap();
+        let data = &interpolated.as_meteo().unwrap()[&t];
+        assert_eq!(data[&Observable::Pressure], 1003.0);
+        assert_eq!(data[&Observable::Temperature], 12.5);
+
+        // existing epoch is returned as is
+        let interpolated = record.interpolate_at(minutes(2.0)).unwrap();
+        assert_eq!(interpolated.as_meteo().unwrap()[&minutes(2.0)].len(), 1);
+
+        // no extrapolation
+        assert!(record.interpolate_at(minutes(-1.0)).is_none());
+        assert!(record.interpolate_at(minutes(61.0)).is_none());
+    }
+    #[test]
+    fn meteo_fill_gaps() {
+        let (t0, record) = meteo_record();
+        let minutes = |m: f64| t0 + Duration::from_seconds(m * 60.0);
+
+        let filled = record.fill_gaps(Duration::from_seconds(300.0), InterpMethod::Linear);
+        let filled = filled.as_meteo().unwrap();
+        // 2' to 5' gap is filled, 6' to 60' gap is too large
+        assert_eq!(filled.len(), 8);
+        let data = &filled[&minutes(3.0)];
+        assert_eq!(data[&Observable::Pressure], 1003.0);
+        assert_eq!(data[&Observable::Temperature], 12.5);
+        let data = &filled[&minutes(4.0)];
+        assert_eq!(data[&Observable::Pressure], 1004.0);
+        assert_eq!(data[&Observable::Temperature], 13.25);
+        assert!(filled.get(&minutes(7.0)).is_none());
+
+        // temperature samples surrounding the gap are 4' apart
+        let filled = record.fill_gaps(Duration::from_seconds(180.0), InterpMethod::Linear);
+        let filled = filled.as_meteo().unwrap();
+        assert_eq!(filled.len(), 8);
+        assert_eq!(filled[&minutes(3.0)].len(), 1);
+
+        let filled = record.fill_gaps(Duration::from_seconds(120.0), InterpMethod::Linear);
+        assert_eq!(filled, record);
+
+        let filled = record.fill_gaps(Duration::from_seconds(300.0), InterpMethod::Nearest);
+        let filled = filled.as_meteo().unwrap();
+        assert_eq!(filled[&minutes(3.0)][&Observable::Pressure], 1002.0);
+        assert_eq!(filled[&minutes(4.0)][&Observable::Pressure], 1005.0);
+        assert_eq!(filled[&minutes(3.0)][&Observable::Temperature], 11.0);
+    }
+    #[test]
+    fn meteo_interpolate() {
+        let (t0, record) = meteo_record();
+        let series = TimeSeries::inclusive(
+            t0,
+            t0 + Duration::from_seconds(120.0),
+            Duration::from_seconds(30.0),
+        );
+        let record = record.as_meteo().unwrap().interpolate(series);
+        assert_eq!(record.len(), 5);
+        let t = t0 + Duration::from_seconds(90.0);
+        assert_eq!(record[&t][&Observable::Pressure], 1001.5);
+    }
+    #[test]
+    fn clock_interpolate_at() {
+        let t0 = Epoch::from_str("2023-09-11T00:00:00 GPST").unwrap();
+        let g01 = System::SV(SV::from_str("G01").unwrap());
+        let data =
+            |bias: f64, drift: Option<f64>| -> HashMap<ClockDataType, HashMap<System, ClockData>> {
+                let data = ClockData {
+                    bias,
+                    drift,
+                    ..Default::default()
+                };
+                [(
+                    ClockDataType::AS,
+                    [(g01.clone(), data)].into_iter().collect(),
+                )]
+                .into_iter()
+                .collect()
+            };
+        let record = Record::ClockRecord(
+            [
+                (t0, data(1.0E-4, Some(1.0E-12))),
+                (
+                    t0 + Duration::from_seconds(30.0),
+                    data(2.0E-4, Some(3.0E-12)),
+                ),
+                (t0 + Duration::from_seconds(60.0), data(3.0E-4, None)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let t = t0 + Duration::from_seconds(15.0);
+        let interpolated = record.interpolate_at(t).unwrap();
+        let clock = &interpolated.as_clock().unwrap()[&t][&ClockDataType::AS][&g01];
+        assert!((clock.bias - 1.5E-4).abs() < 1.0E-15);
+        assert!((clock.drift.unwrap() - 2.0E-12).abs() < 1.0E-20);
+        assert!(clock.bias_dev.is_none());
+
+        // drift is missing on one side
+        let t = t0 + Duration::from_seconds(45.0);
+        let interpolated = record.interpolate_at(t).unwrap();
+        let clock = &interpolated.as_clock().unwrap()[&t][&ClockDataType::AS][&g01];
+        assert!((clock.bias - 2.5E-4).abs() < 1.0E-15);
+        assert!(clock.drift.is_none());
+
+        let filled = record.fill_gaps(Duration::from_seconds(60.0), InterpMethod::Linear);
+        assert_eq!(filled, record);
+    }
+    #[test]
+    fn unsupported_record() {
+        let rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let t = rinex.first_epoch().unwrap() + Duration::from_seconds(1.0);
+        assert!(rinex.record.interpolate_at(t).is_none());
+        let filled = rinex
+            .record
+            .fill_gaps(Duration::from_seconds(3600.0), InterpMethod::Linear);
+        assert_eq!(filled, rinex.record);
+    }
+}
@@ -202,4 +202,55 @@ mod test {
         //     }
         // }
     }
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn v1_ckmg0020_22i_tec_interpolation() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("IONEX")
+            .join("V1")
+            .join("CKMG0020.22I.gz");
+        let rinex = Rinex::from_file(path.to_string_lossy().as_ref()).unwrap();
+
+        // grid nodes, on map epochs
+        let t0 = Epoch::from_gregorian_utc(2022, 1, 2, 0, 0, 0, 0);
+        let t1 = Epoch::from_gregorian_utc(2022, 1, 2, 1, 0, 0, 0);
+        for (t, lat, lon, tec) in [
+            (t0, 2.5, 150.0, 30.5),
+            (t0, 0.0, 155.0, 31.7),
+            (t1, 2.5, 150.0, 33.8),
+            (t1, 0.0, 155.0, 34.6),
+        ] {
+            let value = rinex.tec_at(t, lat, lon).unwrap();
+            assert!(
+                (value - tec).abs() < 1.0E-6,
+                "{} ({}, {}): {}",
+                t,
+                lat,
+                lon,
+                value
+            );
+        }
+        // between nodes and maps
+        let t = Epoch::from_gregorian_utc(2022, 1, 2, 0, 30, 0, 0);
+        let value = rinex.tec_at(t, 1.25, 152.5).unwrap();
+        assert!(
+            (value - 32.65).abs() < 1.0E-6,
+            "bad interpolation: {}",
+            value
+        );
+
+        // outside the time frame
+        let t = Epoch::from_gregorian_utc(2022, 1, 4, 0, 0, 0, 0);
+        assert!(rinex.tec_at(t, 1.25, 152.5).is_none());
+    }
+    #[test]
+    #[cfg(feature = "processing")]
+    fn gps_l1_l2_slant_tec() {
+        use crate::processing::slant_tec;
+        // 1 m of L2 - L1 code delay is about 9.52 TECu
+        let tec = slant_tec(1575.42E6, 20.0E6, 1227.60E6, 20.0E6 + 1.0);
+        assert!((tec - 9.5196).abs() < 1.0E-4, "bad slant tec: {}", tec);
+    }
 }
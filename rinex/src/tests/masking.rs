@@ -45,4 +45,34 @@ mod test {
         let dut = rnx.filter(filter!("< 1996-04-01T00:00:30 UTC"));
         assert_eq!(dut.epoch().count(), 1);
     }
+    #[test]
+    fn retain_where_v3_duth0630() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        // equivalent to the named filters
+        let mut dut = rnx.clone();
+        dut.record
+            .retain_where(|_, sv, _, _| sv.constellation == Constellation::GPS);
+        assert_eq!(dut.record, rnx.filter(filter!("GPS")).record);
+
+        let mut dut = rnx.clone();
+        let l1c = Observable::from_str("L1C").unwrap();
+        dut.record
+            .retain_where(|_, _, observable, _| *observable == l1c);
+        assert_eq!(dut.record, rnx.filter(filter!("L1C")).record);
+        assert_eq!(dut.observable().collect::<Vec<_>>(), vec![&l1c]);
+
+        // arbitrary conditions
+        let t0 = rnx.first_epoch().unwrap();
+        let mut dut = rnx.clone();
+        dut.record
+            .retain_where(|t, _, observable, _| t > t0 && observable.is_pseudorange_observable());
+        assert_eq!(dut.epoch().count(), rnx.epoch().count() - 1);
+        assert!(dut.observable().all(|obs| obs.is_pseudorange_observable()));
+
+        // other records are left untouched
+        let meteo = Rinex::from_file("../test_resources/MET/V2/cari0010.07m").unwrap();
+        let mut dut = meteo.clone();
+        dut.record.retain_where(|_, _, _, _| false);
+        assert_eq!(dut, meteo);
+    }
 }
@@ -2,6 +2,7 @@
 mod test {
     use crate::prelude::*;
     use crate::tests::toolkit::test_observation_rinex;
+    use crate::tests::toolkit::TmpFile;
     use crate::Merge;
     use crate::{
         //erratic_time_frame,
@@ -68,8 +69,9 @@ mod test {
 
         // dump
         let merged = merged.unwrap();
+        let tmp_path = TmpFile::new("txt");
         assert!(
-            merged.to_file("merge.txt").is_ok(),
+            merged.to_file(&tmp_path).is_ok(),
             "failed to generate file previously merged"
         );
         assert!(
@@ -78,7 +80,7 @@ mod test {
         );
 
         // parse back
-        let rnx = Rinex::from_file("merge.txt");
+        let rnx = Rinex::from_file(&tmp_path);
         assert!(rnx.is_ok(), "Failed to parsed back previously merged file");
 
         let rnx = rnx.unwrap();
@@ -93,9 +95,6 @@ mod test {
          *  due to formatting issues
          */
         // assert_eq!(rnx, merged, "Merge::ops reciprocity");
-
-        // remove file we just generated
-        let _ = std::fs::remove_file("merge.txt");
     }
     #[test]
     #[ignore]
@@ -145,8 +144,9 @@ mod test {
         );
 
         // dump
+        let tmp_path = TmpFile::new("txt");
         assert!(
-            merged.to_file("merge.txt").is_ok(),
+            merged.to_file(&tmp_path).is_ok(),
             "failed to generate file previously merged"
         );
         assert!(
@@ -155,7 +155,7 @@ mod test {
         );
 
         // parse back
-        let rnx = Rinex::from_file("merge.txt");
+        let rnx = Rinex::from_file(&tmp_path);
         assert!(rnx.is_ok(), "Failed to parsed back previously merged file");
 
         let rnx = rnx.unwrap();
@@ -165,9 +165,6 @@ mod test {
         );
 
         assert_eq!(rnx, merged, "merge() reciprocity");
-
-        // remove file we just generated
-        let _ = std::fs::remove_file("merge.txt");
     }
     #[cfg(feature = "antex")]
     use crate::antex::antenna::AntennaMatcher;
@@ -209,4 +206,180 @@ mod test {
             assert_eq!(apc.unwrap(), expected_apc);
         }
     }
+    #[test]
+    fn merge_with_policies() {
+        use crate::merge::{Error, MergePolicy};
+        use crate::Split;
+        let rnx = Rinex::from_file("../test_resources/OBS/V2/AJAC3550.21O").unwrap();
+        // redundant logger: same epochs, biased observations
+        let mut backup = rnx.clone();
+        for (_, (_, vehicles)) in backup.record.as_mut_obs().unwrap().iter_mut() {
+            for (_, observations) in vehicles.iter_mut() {
+                for (_, data) in observations.iter_mut() {
+                    data.obs += 2.0;
+                }
+            }
+        }
+        let record = rnx.record.as_obs().unwrap();
+        for (policy, bias) in [
+            (MergePolicy::PreferSelf, 0.0),
+            (MergePolicy::PreferOther, 2.0),
+            (MergePolicy::Average, 1.0),
+        ] {
+            let merged = rnx.merge_with(&backup, policy).unwrap();
+            let merged = merged.record.as_obs().unwrap();
+            assert_eq!(merged.len(), record.len());
+            for (epoch, (_, vehicles)) in merged {
+                let (_, ref_vehicles) = record.get(epoch).unwrap();
+                for (sv, observations) in vehicles {
+                    let ref_observations = ref_vehicles.get(sv).unwrap();
+                    for (observable, data) in observations {
+                        let expected = ref_observations.get(observable).unwrap().obs + bias;
+                        assert!(
+                            (data.obs - expected).abs() < 1.0E-6,
+                            "{:?}: {} {} {} expecting {}, got {}",
+                            policy,
+                            epoch.0,
+                            sv,
+                            observable,
+                            expected,
+                            data.obs
+                        );
+                    }
+                }
+            }
+        }
+        match rnx.merge_with(&backup, MergePolicy::Error) {
+            Err(Error::EpochConflict(epoch)) => {
+                assert_eq!(epoch, rnx.first_epoch().unwrap());
+            },
+            _ => panic!("overlapping epochs should be reported"),
+        }
+
+        // data only provided by rhs is always preserved
+        let mut partial = rnx.clone();
+        let (epoch, _) = *record.iter().next().unwrap().0;
+        let (_, vehicles) = partial
+            .record
+            .as_mut_obs()
+            .unwrap()
+            .iter_mut()
+            .next()
+            .unwrap()
+            .1;
+        let sv = *vehicles.keys().next().unwrap();
+        vehicles.remove(&sv);
+        let merged = partial.merge_with(&rnx, MergePolicy::PreferSelf).unwrap();
+        assert_eq!(
+            merged.record, rnx.record,
+            "{} at {} was not restored",
+            sv, epoch
+        );
+
+        // distinct time frames never conflict
+        let (r0, r1) = rnx.split(rnx.epoch().nth(1).unwrap()).unwrap();
+        let merged = r0.merge_with(&r1, MergePolicy::Error).unwrap();
+        assert_eq!(merged.record, rnx.record);
+
+        let meteo = Rinex::from_file("../test_resources/MET/V2/abvi0010.15m").unwrap();
+        assert!(
+            rnx.merge_with(&meteo, MergePolicy::PreferSelf).is_err(),
+            "type mismatch should be reported"
+        );
+        let merged = meteo.merge_with(&meteo, MergePolicy::Average).unwrap();
+        assert_eq!(merged.record, meteo.record);
+
+        // failed merge leaves Self untouched: r0 epochs would come first
+        let mut lhs = r1.clone();
+        assert!(lhs.merge_with_mut(&rnx, MergePolicy::Error).is_err());
+        assert_eq!(lhs, r1);
+
+        // nothing to average in NAV frames
+        let nav = Rinex::from_file("../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx")
+            .unwrap();
+        match nav.merge_with(&nav, MergePolicy::Average) {
+            Err(Error::UnsupportedPolicy(MergePolicy::Average)) => {},
+            _ => panic!("averaging NAV frames should be rejected"),
+        }
+        let merged = nav.merge_with(&nav, MergePolicy::PreferOther).unwrap();
+        assert_eq!(merged.record, nav.record);
+        assert!(nav.merge_with(&nav, MergePolicy::Error).is_err());
+    }
+    #[test]
+    fn nav_merge_with_policies() {
+        use crate::merge::MergePolicy;
+        let nav = Rinex::from_file("../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx")
+            .unwrap();
+        // redundant logger: same frames, biased clock
+        let mut rhs = nav.clone();
+        for (_, frames) in rhs.record.as_mut_nav().unwrap().iter_mut() {
+            for fr in frames.iter_mut() {
+                if let Some((_, _, eph)) = fr.as_mut_eph() {
+                    eph.clock_bias += 1.0E-6;
+                }
+            }
+        }
+        assert_ne!(rhs.record, nav.record);
+
+        let merged = nav.merge_with(&rhs, MergePolicy::PreferSelf).unwrap();
+        assert_eq!(merged.record, nav.record);
+        let merged = nav.merge_with(&rhs, MergePolicy::PreferOther).unwrap();
+        assert_eq!(merged.record, rhs.record);
+
+        // frames only provided by rhs are always preserved
+        let mut partial = nav.clone();
+        let (epoch, frames) = partial
+            .record
+            .as_mut_nav()
+            .unwrap()
+            .iter_mut()
+            .next()
+            .unwrap();
+        let (epoch, fr) = (*epoch, frames.remove(0));
+        let merged = partial.merge_with(&rhs, MergePolicy::PreferSelf).unwrap();
+        let merged = merged.record.as_nav().unwrap();
+        assert!(
+            merged.get(&epoch).unwrap().iter().any(|merged_fr| {
+                let (_, sv, eph) = merged_fr.as_eph().unwrap();
+                let (_, ref_sv, ref_eph) = fr.as_eph().unwrap();
+                sv == ref_sv && eph.clock_bias == ref_eph.clock_bias + 1.0E-6
+            }),
+            "{:?} was not restored",
+            fr
+        );
+    }
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn ionex_merge_with_policies() {
+        use crate::merge::{Error, MergePolicy};
+        let rnx = Rinex::from_file("../test_resources/IONEX/V1/CKMG0020.22I.gz").unwrap();
+        let record = rnx.record.as_ionex().unwrap();
+        // rhs: same maps, biased TEC values
+        let mut rhs = rnx.clone();
+        for (_, plane) in rhs.record.as_mut_ionex().unwrap().iter_mut() {
+            for (_, tec) in plane.iter_mut() {
+                tec.tec += 1.0;
+            }
+        }
+        let merged = rnx.merge_with(&rhs, MergePolicy::PreferSelf).unwrap();
+        assert_eq!(merged.record, rnx.record);
+
+        let merged = rnx.merge_with(&rhs, MergePolicy::PreferOther).unwrap();
+        let merged = merged.record.as_ionex().unwrap();
+        assert_eq!(merged.len(), record.len());
+        for (key, plane) in merged {
+            let ref_plane = record.get(key).unwrap();
+            for (latlon, tec) in plane {
+                assert_eq!(tec.tec, ref_plane.get(latlon).unwrap().tec + 1.0);
+            }
+        }
+        assert!(matches!(
+            rnx.merge_with(&rhs, MergePolicy::Error),
+            Err(Error::EpochConflict(_))
+        ));
+        assert!(matches!(
+            rnx.merge_with(&rhs, MergePolicy::Average),
+            Err(Error::UnsupportedPolicy(_))
+        ));
+    }
 }
@@ -285,4 +285,77 @@ mod test {
             }
         }
     }
+    #[test]
+    fn builder() {
+        use crate::meteo::{builder::MeteoBuilder, sensor::Sensor};
+        use crate::tests::toolkit::TmpFile;
+        use crate::version::Version;
+        // every meteo observable: requires a continuation line
+        let observables = vec![
+            Observable::Pressure,
+            Observable::Temperature,
+            Observable::HumidityRate,
+            Observable::ZenithWetDelay,
+            Observable::ZenithDryDelay,
+            Observable::ZenithTotalDelay,
+            Observable::WindDirection,
+            Observable::WindSpeed,
+            Observable::RainIncrement,
+            Observable::HailIndicator,
+        ];
+        for version in [Version::new(2, 11), Version::new(3, 5)] {
+            let mut builder = MeteoBuilder::new("TEST")
+                .with_version(version)
+                .with_general_infos("operator", "agency")
+                .with_sensor(
+                    Sensor::new(Observable::Pressure)
+                        .with_model("PTU200")
+                        .with_type("Vaisala")
+                        .with_accuracy(0.1)
+                        .with_position((0.0, 0.0, 0.0, 132.8177)),
+                )
+                .unwrap()
+                .with_sensor(Sensor::new(Observable::Temperature).with_accuracy(10.5))
+                .unwrap();
+            assert_eq!(
+                builder.with_sensor(Sensor::new(Observable::Pressure)).err(),
+                Some(crate::meteo::builder::Error::DuplicateSensor(
+                    Observable::Pressure
+                ))
+            );
+            assert!(builder
+                .push(
+                    Epoch::from_gregorian_utc_at_midnight(2023, 9, 11),
+                    Observable::Phase("L1C".to_string()),
+                    1.0
+                )
+                .is_err());
+
+            for i in 0..10 {
+                let t = Epoch::from_gregorian_utc(2023, 9, 11, 0, 5 * i, 0, 0);
+                for (j, observable) in observables.iter().enumerate() {
+                    // leave one blank field, in the continuation line
+                    if i == 3 && j == 9 {
+                        continue;
+                    }
+                    let value = (10000 + 11 * i as i32 - 1000 * j as i32) as f64 / 10.0;
+                    builder.push(t, observable.clone(), value).unwrap();
+                }
+            }
+            let rinex = builder.build().unwrap();
+            assert_eq!(rinex.header.meteo.as_ref().unwrap().codes, observables);
+
+            let tmp_path = TmpFile::new("rnx");
+            assert!(rinex.to_file(&tmp_path).is_ok());
+            let parsed = Rinex::from_file(&tmp_path);
+            let parsed = parsed.unwrap();
+
+            assert!(parsed.is_meteo_rinex());
+            assert_eq!(parsed.header.version, version);
+            let meteo = parsed.header.meteo.as_ref().unwrap();
+            assert_eq!(meteo.codes, observables);
+            assert_eq!(meteo.sensors, rinex.header.meteo.as_ref().unwrap().sensors);
+            assert_eq!(parsed.record, rinex.record);
+        }
+    }
 }
@@ -2,15 +2,23 @@
 pub mod toolkit;
 
 mod antex;
+#[cfg(feature = "catalog")]
+mod catalog;
 mod clocks;
 mod compression;
+#[cfg(feature = "sp3")]
+mod context;
 mod decompression;
+mod estimation;
+mod events;
 mod filename;
+mod golden;
 mod merge;
 mod nav;
 mod obs;
 mod parsing;
 mod production;
+mod profile;
 mod sampling;
 mod smoothing;
 
@@ -22,3 +30,28 @@ mod ionex;
 
 #[cfg(feature = "processing")]
 mod masking;
+
+#[cfg(feature = "processing")]
+mod interpolation;
+
+#[cfg(feature = "processing")]
+mod shared;
+
+#[cfg(all(feature = "sp3", feature = "obs"))]
+mod ppp;
+
+#[cfg(feature = "processing")]
+mod dop;
+
+#[cfg(all(feature = "obs", feature = "nav"))]
+mod residuals;
+
+#[cfg(feature = "processing")]
+mod pierce_point;
+
+#[cfg(all(feature = "obs", feature = "nav"))]
+mod sql;
+mod visibility;
+
+#[cfg(all(feature = "obs", feature = "nav"))]
+mod thinning;
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod test {
     use crate::carrier::Carrier;
+    use crate::merge::Merge;
     use crate::navigation::*;
     use crate::prelude::*;
     use gnss_rs::prelude::SV;
@@ -177,6 +178,54 @@ mod test {
         }
     }
     #[test]
+    fn v2_egno0010_21h() {
+        let test_resource =
+            env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/NAV/V2/egno0010.21h";
+        let rinex = Rinex::from_file(&test_resource);
+        assert!(rinex.is_ok(), "failed to parse GEO NAV: {:?}", rinex.err());
+        let rinex = rinex.unwrap();
+        assert!(rinex.is_navigation_rinex());
+        assert_eq!(rinex.header.constellation, Some(Constellation::SBAS));
+
+        let epochs = vec![
+            Epoch::from_str("2021-01-01T00:01:36 GPST").unwrap(),
+            Epoch::from_str("2021-01-01T00:05:52 GPST").unwrap(),
+        ];
+        assert!(rinex.epoch().eq(epochs), "parsed wrong epoch content");
+        assert_eq!(rinex.ephemeris().count(), 3);
+
+        for (_, (msg, sv, ephemeris)) in rinex.ephemeris() {
+            assert_eq!(msg, NavMsgType::LNAV);
+            assert!(sv.constellation.is_sbas(), "bad constellation for {}", sv);
+            let sbas = ephemeris.sbas().expect("missing state vector");
+            // satellite number is PRN - 100
+            if sv.prn == 136 {
+                assert_eq!(ephemeris.clock_bias, -1.862645149230E-09);
+                assert_eq!(ephemeris.clock_drift, 9.094947017730E-13);
+                assert_eq!(sbas.t_tm, 4.320960000000E+05);
+                assert_eq!(
+                    sbas.position,
+                    (4.194006320000E+04, 4.351468000000E+03, -1.304160000000E+02)
+                );
+                assert_eq!(sbas.velocity.0, -6.904000000000E-04);
+                assert_eq!(sbas.ura, 4.0);
+                assert_eq!(sbas.iodn, 128.0);
+            } else {
+                assert_eq!(sv.prn, 123);
+                assert_eq!(sbas.ura, 2.0);
+            }
+        }
+
+        // PRN - 100 is written back
+        let tmp_path = crate::tests::toolkit::TmpFile::new("21h");
+        assert!(rinex.to_file(&tmp_path).is_ok());
+        let content = std::fs::read_to_string(&tmp_path).unwrap();
+        let copy = Rinex::from_file(&tmp_path).unwrap();
+        assert!(content.contains("\n23 21  1  1  0  1 36.0"));
+        assert!(content.contains("\n36 21  1  1  0  1 36.0"));
+        assert_eq!(copy.record, rinex.record);
+    }
+    #[test]
     #[cfg(feature = "flate2")]
     fn v2_cbw10010_21n() {
         let test_resources =
@@ -478,6 +527,52 @@ mod test {
         }
     }
     #[test]
+    fn v3_galileo_spare_field() {
+        // the 5th orbit line ends with a spare field, following the GAL week:
+        // fields of the 6th and 7th lines must not be shifted.
+        // F/NAV (data source 258) and I/NAV (data source 517) frames
+        for (path, sv, toc, (src, sisa, bgd_e5a, bgd_e5b, t_tm)) in [
+            (
+                "../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx",
+                sv!("E01"),
+                "2021-01-01T10:10:00 GST",
+                (258.0, 3.12, 0.232830643654e-09, 0.0, 0.469330000000e+06),
+            ),
+            (
+                "../test_resources/NAV/V3/CBW100NLD_R_20210010000_01D_MN.rnx",
+                sv!("E33"),
+                "2021-01-01T02:20:00 GST",
+                (
+                    517.0,
+                    3.12,
+                    -3.026798367500e-09,
+                    -3.492459654808e-09,
+                    4.415740000000e+05,
+                ),
+            ),
+        ] {
+            let rinex = Rinex::from_file(path).unwrap();
+            let toc = Epoch::from_str(toc).unwrap();
+            let (_, (_, _, ephemeris)) = rinex
+                .ephemeris()
+                .find(|(e, (_, sv_i, _))| **e == toc && *sv_i == sv)
+                .unwrap_or_else(|| panic!("{}: missing {} frame", path, sv));
+            assert_eq!(ephemeris.get_orbit_f64("dataSrc"), Some(src));
+            assert_eq!(ephemeris.get_week(), Some(2138));
+            assert_eq!(ephemeris.get_orbit_f64("sisa"), Some(sisa));
+            let health = ephemeris
+                .orbits
+                .get("health")
+                .and_then(|health| health.as_gal_health())
+                .unwrap_or_else(|| panic!("{}: {} health is missing", path, sv));
+            assert_eq!(health.bits(), 0);
+            assert_eq!(ephemeris.get_orbit_f64("bgdE5aE1"), Some(bgd_e5a));
+            assert_eq!(ephemeris.get_orbit_f64("bgdE5bE1"), Some(bgd_e5b));
+            assert_eq!(ephemeris.get_orbit_f64("t_tm"), Some(t_tm));
+            assert!(ephemeris.is_healthy());
+        }
+    }
+    #[test]
     #[cfg(feature = "flate2")]
     fn v4_kms300dnk_r_202215910() {
         let test_resource = env!("CARGO_MANIFEST_DIR").to_owned()
@@ -1499,4 +1594,304 @@ mod test {
             }
         }
     }
+    #[test]
+    #[cfg(feature = "nav")]
+    fn v3_sbas() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("NAV")
+            .join("V3")
+            .join("MOJN00DNK_R_20201770000_01D_MN.rnx.gz");
+        let rinex = Rinex::from_file(&path.to_string_lossy()).unwrap();
+
+        let s23 = SV::from_str("S23").unwrap();
+        let toc = Epoch::from_str("2020-06-25T00:00:16 GPST").unwrap();
+
+        let (_, (_, _, ephemeris)) = rinex
+            .ephemeris()
+            .find(|(e, (_, sv, _))| **e == toc && *sv == s23)
+            .expect("missing S23 frame");
+
+        let sbas = ephemeris.sbas().expect("S23: sbas ephemeris");
+        assert_eq!(sbas.position, (3.594460000000e+04, 2.204414000000e+04, 0.0));
+        assert_eq!(sbas.velocity, (0.0, 0.0, 0.0));
+        assert_eq!(sbas.ura, 3.276700000000e+04);
+        assert_eq!(sbas.iodn, 92.0);
+        assert_eq!(sbas.t_tm, 3.456300000000e+05);
+        assert!(ephemeris.gps().is_none());
+
+        assert_eq!(
+            ephemeris.sv_position(s23, toc),
+            Some((3.594460000000e+04, 2.204414000000e+04, 0.0))
+        );
+
+        /*
+         * SBAS ephemeris selection: referenced to toc
+         */
+        let t = toc + Duration::from_seconds(60.0);
+        let (seltoc, seleph) = rinex
+            .sv_ephemeris(s23, t)
+            .expect("S23: ephemeris selection");
+        assert_eq!(seltoc, toc);
+        assert_eq!(seleph, ephemeris);
+
+        // S23 is not transmitting prior 00:00:16
+        let t = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        assert!(rinex.sv_ephemeris(s23, t).is_none());
+    }
+    #[test]
+    #[cfg(all(feature = "processing", feature = "flate2"))]
+    fn indexed_ephemeris_selection() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("NAV")
+            .join("V3")
+            .join("MOJN00DNK_R_20201770000_01D_MN.rnx.gz");
+        let rinex = Rinex::from_file(&path.to_string_lossy()).unwrap();
+        let index = crate::navigation::index::EphemerisIndex::new(&rinex);
+        let sv_list: Vec<_> = rinex.sv().collect();
+        let t0 = Epoch::from_str("2020-06-24T23:00:00 GPST").unwrap();
+        let mut selected = 0;
+        for i in 0..26 {
+            let t = t0 + Duration::from_seconds(i as f64 * 3600.0 + 17.0);
+            for sv in &sv_list {
+                let expected = rinex.sv_ephemeris(*sv, t);
+                assert_eq!(index.select(*sv, t), expected, "{} @ {}", sv, t);
+                selected += expected.is_some() as usize;
+            }
+        }
+        assert!(selected > 0);
+    }
+    #[test]
+    #[cfg(all(feature = "nav", feature = "flate2"))]
+    fn gps_gal_orbits_against_sp3() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("NAV")
+            .join("V3")
+            .join("MOJN00DNK_R_20201770000_01D_MN.rnx.gz");
+        let rinex = Rinex::from_file(&path.to_string_lossy()).unwrap();
+        // GRG0MGXFIN_20201770000_01D_15M_ORB.SP3 (km ECEF, center of mass)
+        let t = Epoch::from_str("2020-06-25T12:00:00 GPST").unwrap();
+        for (sv, sp3) in [
+            ("G02", (-21763.192092, 13697.004574, -5902.542198)),
+            ("G18", (6124.221488, 14111.934618, 21638.434631)),
+            ("G31", (22168.148872, -3357.85377, -14622.755209)),
+            ("E01", (-14819.317591, -15656.395751, 20287.373001)),
+            ("E19", (-11296.458813, -27360.863097, -20.319916)),
+            ("E30", (28369.533132, 7063.835519, -4653.592)),
+        ] {
+            let sv = SV::from_str(sv).unwrap();
+            let (_, eph) = rinex
+                .sv_ephemeris(sv, t)
+                .unwrap_or_else(|| panic!("{}: no ephemeris", sv));
+            let (x, y, z) = eph.sv_position(sv, t).unwrap();
+            let err = ((x - sp3.0).powi(2) + (y - sp3.1).powi(2) + (z - sp3.2).powi(2)).sqrt();
+            assert!(
+                err * 1.0E3 < 5.0,
+                "{}: {:.3} m away from SP3",
+                sv,
+                err * 1.0E3
+            );
+
+            // UTC epochs are interpreted as datetimes of the GNSS timescale
+            let t_utc = Epoch::from_str("2020-06-25T12:00:00 UTC").unwrap();
+            let (x_utc, y_utc, z_utc) = eph.sv_position(sv, t_utc).unwrap();
+            let dist = ((x - x_utc).powi(2) + (y - y_utc).powi(2) + (z - z_utc).powi(2)).sqrt();
+            assert!(dist * 1.0E3 < 1.0E-3, "{}: UTC/GPST mismatch", sv);
+        }
+    }
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn sv_health() {
+        let rinex =
+            Rinex::from_file("../test_resources/NAV/V4/KMS300DNK_R_20221591000_01H_MN.rnx.gz")
+                .unwrap();
+        let e01 = SV::from_str("E01").unwrap();
+        let toc = Epoch::from_str("2022-06-08T09:40:00 GST").unwrap();
+        let (_, (_, _, ephemeris)) = rinex
+            .ephemeris()
+            .find(|(e, (_, sv, _))| **e == toc && *sv == e01)
+            .expect("missing E01 frame");
+        assert_eq!(ephemeris.get_orbit_f64("sisa"), Some(3.12));
+        assert_eq!(ephemeris.get_orbit_f64("t_tm"), Some(2.946650000000E+05));
+        assert!(ephemeris.is_healthy());
+
+        let rinex =
+            Rinex::from_file("../test_resources/NAV/V4/BRD400DLR_S_20230710000_01D_MN.rnx.gz")
+                .unwrap();
+        let unhealthy: Vec<SV> = rinex
+            .ephemeris()
+            .filter_map(|(_, (_, sv, ephemeris))| {
+                if ephemeris.is_healthy() {
+                    None
+                } else {
+                    Some(sv)
+                }
+            })
+            .unique()
+            .sorted()
+            .collect();
+        for sv in ["E14", "E18", "R25", "R26", "C35"] {
+            let sv = SV::from_str(sv).unwrap();
+            assert!(unhealthy.contains(&sv), "{} should be unhealthy", sv);
+        }
+        for sv in ["G01", "E01", "R01"] {
+            let sv = SV::from_str(sv).unwrap();
+            assert!(!unhealthy.contains(&sv), "{} should be healthy", sv);
+        }
+    }
+    #[test]
+    #[cfg(all(feature = "nav", feature = "flate2"))]
+    fn ephemeris_dedup() {
+        let rinex =
+            Rinex::from_file("../test_resources/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+                .unwrap();
+        let count = rinex.ephemeris().count();
+        // Galileo I/NAV and F/NAV share the same data sets, yet are distinct
+        assert_eq!(rinex.nav_dedup().ephemeris().count(), count);
+
+        // repeated uploads, with a later transmission time
+        let mut dut = rinex.clone();
+        for frames in dut.record.as_mut_nav().unwrap().values_mut() {
+            let mut uploads = frames.clone();
+            for frame in uploads.iter_mut() {
+                if let Some((_, _, eph)) = frame.as_mut_eph() {
+                    let t_tm = eph.get_orbit_f64("t_tm").unwrap_or_default();
+                    eph.set_orbit_f64("t_tm", t_tm + 30.0);
+                }
+            }
+            frames.extend(uploads);
+        }
+        assert_eq!(dut.ephemeris().count(), 2 * count);
+        dut.nav_dedup_mut();
+        assert_eq!(dut.record, rinex.record, "first upload should be kept");
+
+        // latest ephemeris selection
+        let t = Epoch::from_str("2020-06-25T12:00:00 GPST").unwrap();
+        let latest = rinex.record.as_nav().unwrap().latest_before(t);
+        assert!(!latest.is_empty());
+        for (sv, (toc, _)) in &latest {
+            assert!(*toc <= t);
+            assert!(
+                rinex
+                    .ephemeris()
+                    .all(|(e, (_, svnn, _))| svnn != *sv || *e <= *toc || *e > t),
+                "{}: a more recent ephemeris exists",
+                sv
+            );
+        }
+    }
+    #[test]
+    #[cfg(feature = "nav")]
+    fn brdc_header_merge() {
+        let amel = Rinex::from_file("../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx")
+            .unwrap();
+        let cbw1 = Rinex::from_file("../test_resources/NAV/V3/CBW100NLD_R_20210010000_01D_MN.rnx")
+            .unwrap();
+        let nav = amel.header.nav.as_ref().unwrap();
+        assert_eq!(
+            nav.ionod_corrections.keys().collect::<Vec<_>>(),
+            vec![
+                &Constellation::GPS,
+                &Constellation::QZSS,
+                &Constellation::Galileo
+            ]
+        );
+        let kb = nav.ionod_corrections[&Constellation::QZSS]
+            .as_klobuchar()
+            .unwrap();
+        assert_eq!(
+            kb.alpha,
+            (8.3820e-09, -2.9800e-08, -2.3840e-07, -1.1920e-07)
+        );
+        assert_eq!(kb.beta, (6.9630e+04, -1.6380e+05, 5.8980e+05, 4.1290e+06));
+        assert_eq!(kb.region, KbRegionCode::JapanArea);
+        assert_eq!(nav.time_corrections.len(), 4);
+        // the GPS model is preferred
+        assert_eq!(
+            amel.header.ionod_correction(),
+            Some(nav.ionod_corrections[&Constellation::GPS])
+        );
+        #[allow(deprecated)]
+        let deprecated = amel.header.ionod_correction;
+        assert_eq!(deprecated, amel.header.ionod_correction());
+        let gput = nav
+            .time_correction(TimeSystem::GNSS(Constellation::GPS), TimeSystem::UTC)
+            .unwrap();
+        assert_eq!(gput.a0, -0.3725290298E-08);
+        assert_eq!((gput.t_ref, gput.week), (61440, 2139));
+
+        // BRDC: models and corrections of both stations, first station prevails
+        let brdc = amel.merge(&cbw1).unwrap();
+        let nav = brdc.header.nav.as_ref().unwrap();
+        assert_eq!(nav.ionod_corrections.len(), 4);
+        assert!(nav.ionod_corrections.contains_key(&Constellation::BeiDou));
+        let kb = nav.ionod_corrections[&Constellation::GPS]
+            .as_klobuchar()
+            .unwrap();
+        assert_eq!(kb.alpha.0, 7.4510e-09);
+        let systems: Vec<_> = nav
+            .time_corrections
+            .iter()
+            .map(|corr| corr.code())
+            .collect();
+        assert_eq!(systems, vec!["GAGP", "GAUT", "GPUT", "QZUT", "BDUT"]);
+        let gaut = nav
+            .time_correction(TimeSystem::GNSS(Constellation::Galileo), TimeSystem::UTC)
+            .unwrap();
+        assert_eq!(gaut.a0, 0.1862645149E-08);
+
+        // header corrections are preserved when formatting
+        let tmp_path = crate::tests::toolkit::TmpFile::new("rnx");
+        brdc.to_file(&tmp_path).unwrap();
+        let parsed = Rinex::from_file(&tmp_path).unwrap();
+        assert_eq!(parsed.header.nav, brdc.header.nav);
+
+        // a header without corrections takes all of them
+        let mut header = Header::basic_nav();
+        header.merge_mut(&amel.header).unwrap();
+        assert_eq!(header.nav, amel.header.nav);
+    }
+    #[test]
+    #[cfg(feature = "nav")]
+    fn time_system_offsets() {
+        let rinex = Rinex::from_file("../test_resources/NAV/V3/CBW100NLD_R_20210010000_01D_MN.rnx")
+            .unwrap();
+        let (gpst, gst, bdt, qzsst) = (
+            TimeSystem::GNSS(Constellation::GPS),
+            TimeSystem::GNSS(Constellation::Galileo),
+            TimeSystem::GNSS(Constellation::BeiDou),
+            TimeSystem::GNSS(Constellation::QZSS),
+        );
+        let t = Epoch::from_str("2021-01-01T12:00:00 GPST").unwrap();
+        assert_eq!(
+            rinex.time_system_offset(gpst, gpst, t),
+            Some(Duration::ZERO)
+        );
+
+        // GAGP is described
+        let ggto = rinex.time_system_offset(gst, gpst, t).unwrap();
+        assert_eq!(rinex.time_system_offset(gpst, gst, t), Some(-ggto));
+
+        // through UTC: GAUT - GPUT, consistent with GAGP
+        let nav = rinex.header.nav.as_ref().unwrap();
+        let gaut = nav.system_offset(gst, TimeSystem::UTC, t).unwrap();
+        let gput = nav.system_offset(gpst, TimeSystem::UTC, t).unwrap();
+        assert!((ggto - (gaut - gput)).abs() < Duration::from_nanoseconds(10.0));
+
+        // BDT - GPST: only through UTC
+        assert!(nav.time_correction(bdt, gpst).is_none());
+        let offset = rinex.time_system_offset(bdt, gpst, t).unwrap();
+        assert!(offset.abs() < Duration::from_nanoseconds(100.0));
+
+        // QZSS is not described
+        assert!(rinex.time_system_offset(qzsst, gpst, t).is_none());
+    }
 }
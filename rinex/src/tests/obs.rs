@@ -706,6 +706,49 @@ mod test {
         assert_eq!(epoch.len(), 47);
     }
     #[test]
+    fn implausible_observables() {
+        let path = env!("CARGO_MANIFEST_DIR").to_owned()
+            + "/../test_resources/CRNX/V3/KMS300DNK_R_20221591000_01H_30S_MO.crx";
+        let mut rinex = Rinex::from_file(&path).unwrap();
+        assert!(
+            rinex.implausible_observables().is_empty(),
+            "declared receiver should be consistent with observations"
+        );
+        // mislabeled header: GPS only receiver
+        let rcvr = rinex.header.rcvr.as_mut().unwrap();
+        rcvr.model = String::from("TRIMBLE 4000SSI");
+        let implausible = rinex.implausible_observables();
+        assert!(!implausible.is_empty());
+        assert!(
+            implausible.contains(&(Constellation::Galileo, Observable::from_str("C1C").unwrap()))
+        );
+        assert!(implausible.contains(&(Constellation::GPS, Observable::from_str("C5Q").unwrap())));
+        assert!(!implausible.contains(&(Constellation::GPS, Observable::from_str("C1C").unwrap())));
+        // unknown receiver: nothing to say
+        let rcvr = rinex.header.rcvr.as_mut().unwrap();
+        rcvr.model = String::from("UNKNOWN");
+        assert!(rinex.implausible_observables().is_empty());
+    }
+    #[test]
+    fn antenna_type() {
+        let path = env!("CARGO_MANIFEST_DIR").to_owned()
+            + "/../test_resources/CRNX/V3/KMS300DNK_R_20221591000_01H_30S_MO.crx";
+        let rinex = Rinex::from_file(&path).unwrap();
+        let antenna = rinex.header.rcvr_antenna.as_ref().unwrap();
+        assert_eq!(antenna.sn, "6200520017");
+        assert_eq!(antenna.model, "ASH701945E_M    NONE");
+        assert_eq!(antenna.radome(), Some("NONE"));
+        assert!(antenna.validate_igs_name().is_ok());
+
+        let path = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/OBS/V2/barq071q.19o";
+        let rinex = Rinex::from_file(&path).unwrap();
+        let antenna = rinex.header.rcvr_antenna.as_ref().unwrap();
+        assert_eq!(antenna.sn, "13291007");
+        assert_eq!(antenna.model, "LEIAS10");
+        assert!(antenna.validate_igs_name().is_err());
+        assert_eq!(antenna.igs_name(), "LEIAS10         NONE");
+    }
+    #[test]
     #[ignore]
     fn v2_kosg0010_95o() {
         let path = Path::new(env!("CARGO_MANIFEST_DIR"))
@@ -1445,4 +1488,159 @@ mod test {
             test_combinations(combinations, signals);
         }
     */
+    #[test]
+    fn compact_record() {
+        let rinex = Rinex::from_file("../test_resources/OBS/V2/AJAC3550.21O").unwrap();
+        let record = rinex.record.as_obs().unwrap();
+        let compact = CompactRecord::from(record);
+        assert_eq!(compact.len(), record.len());
+        assert_eq!(compact.to_record(), *record);
+
+        let g07 = compact
+            .satellites()
+            .iter()
+            .position(|sv| *sv == SV::from_str("G07").unwrap())
+            .unwrap();
+        let l1 = compact
+            .observables()
+            .iter()
+            .position(|obs| *obs == Observable::from_str("L1").unwrap())
+            .unwrap();
+        let l2 = compact
+            .observables()
+            .iter()
+            .position(|obs| *obs == Observable::from_str("L2").unwrap())
+            .unwrap();
+        let (_, clock_offset, observations) = compact.iter().next().unwrap();
+        assert!(clock_offset.is_none());
+        let observation = observations
+            .iter()
+            .find(|obs| obs.sv as usize == g07 && obs.observable as usize == l1)
+            .unwrap();
+        assert_eq!(observation.value, 131857102.133);
+        assert_eq!(observation.snr(), Some(SNR::from_str("6").unwrap()));
+        assert_eq!(observation.lli, CompactObservation::NONE);
+        assert!(observation.lli().is_none());
+        let observation = observations
+            .iter()
+            .find(|obs| obs.sv as usize == g07 && obs.observable as usize == l2)
+            .unwrap();
+        assert_eq!(observation.value, 102745756.542);
+        assert_eq!(observation.lli(), Some(LliFlags::UNDER_ANTI_SPOOFING));
+        assert_eq!(observation.snr(), Some(SNR::from_str("5").unwrap()));
+
+        let memory_usage = rinex.record.memory_usage();
+        assert!(memory_usage > 0);
+        assert!(
+            compact.memory_usage() * 2 < memory_usage,
+            "compact: {} record: {}",
+            compact.memory_usage(),
+            memory_usage
+        );
+    }
+    #[test]
+    fn obs_cache() {
+        let source =
+            std::env::temp_dir().join(format!("rinex-obs-cache-{}.21O", std::process::id()));
+        let cache = crate::cache::cache_path(&source);
+        std::fs::copy("../test_resources/OBS/V2/AJAC3550.21O", &source).unwrap();
+        let fullpath = source.to_string_lossy().to_string();
+
+        let rinex = Rinex::from_file(&fullpath).unwrap();
+        let first = Rinex::from_file_cached(&fullpath).unwrap();
+        assert_eq!(first, rinex);
+        assert!(cache.exists(), "cache was not produced");
+
+        // cache is reused and left untouched
+        let content = std::fs::read(&cache).unwrap();
+        let second = Rinex::from_file_cached(&fullpath).unwrap();
+        assert_eq!(second, rinex);
+        assert_eq!(std::fs::read(&cache).unwrap(), content);
+
+        // corrupted cache: source is parsed and cache rebuilt
+        std::fs::write(&cache, &content[..content.len() / 2]).unwrap();
+        let third = Rinex::from_file_cached(&fullpath).unwrap();
+        assert_eq!(third, rinex);
+        assert_eq!(std::fs::read(&cache).unwrap(), content);
+
+        // out of range vehicle index, in the last observation
+        let mut corrupt = content.clone();
+        let len = corrupt.len();
+        corrupt[len - 6..len - 4].copy_from_slice(&u16::MAX.to_le_bytes());
+        std::fs::write(&cache, &corrupt).unwrap();
+        let sv_index = Rinex::from_file_cached(&fullpath).unwrap();
+        assert_eq!(sv_index, rinex);
+        assert_eq!(std::fs::read(&cache).unwrap(), content);
+
+        // source modification invalidates the cache
+        std::fs::copy("../test_resources/OBS/V3/DUTH0630.22O", &source).unwrap();
+        let rinex = Rinex::from_file(&fullpath).unwrap();
+        let fourth = Rinex::from_file_cached(&fullpath).unwrap();
+        assert_eq!(fourth, rinex);
+        assert_ne!(std::fs::read(&cache).unwrap(), content);
+
+        std::fs::remove_file(&source).unwrap();
+        std::fs::remove_file(&cache).unwrap();
+    }
+    #[test]
+    fn ssi_indicator() {
+        let rinex = Rinex::from_file("../test_resources/OBS/V2/AJAC3550.21O").unwrap();
+        let g07 = sv!("G07");
+        let t0 = Epoch::from_str("2021-12-21T00:00:00 GPST").unwrap();
+        let indicators: Vec<_> = rinex
+            .ssi_indicator()
+            .filter(|((e, _), sv, _, _, _)| *e == t0 && *sv == g07)
+            .map(|(_, _, observable, dbhz, snr)| (observable, dbhz, snr))
+            .collect();
+        assert_eq!(
+            indicators,
+            vec![
+                (observable!("S1"), Some(37.35), SNR::DbHz36_41),
+                (observable!("S2"), Some(35.3), SNR::DbHz30_35),
+            ]
+        );
+        // this receiver derives the indicator from the observation
+        let mut total = 0;
+        for (_, sv, observable, dbhz, snr) in rinex.ssi_indicator() {
+            let dbhz = dbhz.unwrap();
+            assert!(
+                snr.is_consistent(DbHz(dbhz), 0.0),
+                "{} {}: {} dB-Hz reported as {:e}",
+                sv,
+                observable,
+                dbhz,
+                snr
+            );
+            total += 1;
+        }
+        assert!(total > 0);
+    }
+    #[test]
+    fn recvr_clock_drift() {
+        let mut rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        assert!(rinex.recvr_clock_drift().is_none());
+        // 1 ms offset, drifting by 50 ppb
+        let t0 = rinex.first_epoch().unwrap();
+        let record = rinex.record.as_mut_obs().unwrap();
+        for ((t, _), (clk, _)) in record.iter_mut() {
+            *clk = Some(1.0E-3 + 50.0E-9 * (*t - t0).to_seconds());
+        }
+        let drift = rinex.recvr_clock_drift().unwrap();
+        assert!(
+            (drift * 1.0E9 - 50.0).abs() < 1.0E-6,
+            "drift: {} ppb",
+            drift * 1.0E9
+        );
+        let (t, offset, _) = rinex.recvr_clock_fit().unwrap();
+        assert_eq!(t, t0);
+        assert!((offset - 1.0E-3).abs() < 1.0E-12, "offset: {} s", offset);
+        // single offset: unfeasible
+        let record = rinex.record.as_mut_obs().unwrap();
+        for (index, (_, (clk, _))) in record.iter_mut().enumerate() {
+            if index > 0 {
+                *clk = None;
+            }
+        }
+        assert!(rinex.recvr_clock_drift().is_none());
+    }
 }
@@ -44,6 +44,12 @@ mod test {
                     );
                     let rinex = rinex.unwrap();
 
+                    // header quick scan must be consistent
+                    let header = Header::quick_scan(full_path).unwrap();
+                    assert_eq!(header.rinex_type, rinex.header.rinex_type);
+                    assert_eq!(header.version, rinex.header.version);
+                    assert_eq!(header.is_crinex(), rinex.header.is_crinex());
+
                     match data {
                         "ATX" => {
                             assert!(rinex.is_antex());
@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod test {
+    use crate::prelude::GroundPosition;
+    use crate::processing::pierce_point;
+    #[test]
+    fn thin_shell_pierce_point() {
+        /*
+         * Reference values: intersection of the line of sight
+         * with the 450 km shell, on a spherical Earth
+         */
+        for (pos, azimuth, elevation, lat, lon, obliquity) in [
+            (
+                (45.0, 10.0, 0.0),
+                90.0,
+                30.0,
+                44.685707,
+                18.471665,
+                1.700801,
+            ),
+            ((45.0, 10.0, 0.0), 0.0, 30.0, 51.012246, 10.0, 1.700801),
+            (
+                (-30.0, 179.0, 0.0),
+                60.0,
+                15.0,
+                -24.346640,
+                -170.976084,
+                2.318487,
+            ),
+        ] {
+            let position = GroundPosition::from_geodetic(pos);
+            let ipp = pierce_point(position, azimuth, elevation, 450.0E3).unwrap();
+            assert!(
+                (ipp.latitude - lat).abs() < 1.0E-5,
+                "{:?}: bad latitude {}",
+                pos,
+                ipp.latitude
+            );
+            assert!(
+                (ipp.longitude - lon).abs() < 1.0E-5,
+                "{:?}: bad longitude {}",
+                pos,
+                ipp.longitude
+            );
+            assert!(
+                (ipp.obliquity - obliquity).abs() < 1.0E-5,
+                "{:?}: bad obliquity {}",
+                pos,
+                ipp.obliquity
+            );
+        }
+        // obliquity increases as the satellite sets
+        let position = GroundPosition::from_geodetic((45.0, 10.0, 0.0));
+        let mut obliquity = 1.0;
+        for elevation in [80.0, 60.0, 40.0, 20.0, 5.0, 0.0] {
+            let ipp = pierce_point(position, 180.0, elevation, 350.0E3).unwrap();
+            assert!(ipp.obliquity > obliquity);
+            assert!(ipp.latitude < 45.0);
+            obliquity = ipp.obliquity;
+        }
+        assert!(pierce_point(position, 180.0, -1.0, 350.0E3).is_none());
+    }
+}
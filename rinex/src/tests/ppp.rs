@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod test {
+    use crate::constants::{EARTH_OMEGA_E_WGS84, SPEED_OF_LIGHT};
+    use crate::ppp::{geometry, mapping, zenith_hydrostatic_delay};
+    use crate::ppp::{Candidate, Config, Solver};
+    use crate::prelude::{Constellation, Duration, Epoch, TimeScale, SV};
+    use map_3d::{geodetic2ecef, Ellipsoid};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    const ORBIT_RADIUS: f64 = 26_560.0E3;
+    const GM: f64 = 3.986004418E14;
+
+    /*
+     * Gaussian noise (Box Muller)
+     */
+    fn gaussian(rng: &mut StdRng, sigma: f64) -> f64 {
+        let (u1, u2): (f64, f64) = (rng.gen_range(1.0E-12..1.0), rng.gen());
+        sigma * (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+
+    /*
+     * Circular orbit: 6 planes (55° inclination), 4 satellites per plane,
+     * position at t (seconds), ECEF
+     */
+    fn sv_position(prn: u8, t: f64) -> (f64, f64, f64) {
+        let plane = ((prn - 1) / 4) as f64;
+        let slot = ((prn - 1) % 4) as f64;
+        let (raan, inc) = ((plane * 60.0).to_radians(), 55.0_f64.to_radians());
+        let n = (GM / ORBIT_RADIUS.powi(3)).sqrt();
+        let u = (slot * 90.0 + plane * 15.0).to_radians() + n * t;
+        let (xp, yp) = (ORBIT_RADIUS * u.cos(), ORBIT_RADIUS * u.sin());
+        let x = xp * raan.cos() - yp * inc.cos() * raan.sin();
+        let y = xp * raan.sin() + yp * inc.cos() * raan.cos();
+        let z = yp * inc.sin();
+        let theta = EARTH_OMEGA_E_WGS84 * t;
+        (
+            x * theta.cos() + y * theta.sin(),
+            -x * theta.sin() + y * theta.cos(),
+            z,
+        )
+    }
+
+    #[test]
+    fn static_float_convergence() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (x, y, z) = geodetic2ecef(
+            48.8_f64.to_radians(),
+            2.3_f64.to_radians(),
+            100.0,
+            Ellipsoid::WGS84,
+        );
+        let rx = (x, y, z);
+        let zwd = 0.15;
+        let zhd = zenith_hydrostatic_delay(rx);
+        let ambiguities: Vec<f64> = (0..24).map(|_| rng.gen_range(-1.0E3..1.0E3)).collect();
+
+        let cfg = Config {
+            code_sigma: 0.3,
+            phase_sigma: 0.003,
+            ..Default::default()
+        };
+        let mask = cfg.elevation_mask.to_radians();
+        let mut solver = Solver::new(cfg);
+        let t0 = Epoch::from_gregorian(2023, 6, 1, 0, 0, 0, 0, TimeScale::GPST);
+
+        let mut solution = None;
+        for i in 0..480 {
+            let t = i as f64 * 30.0;
+            let dt_rx = 1.0E-4 + gaussian(&mut rng, 1.0E-8);
+            let mut candidates = Vec::<Candidate>::new();
+            for prn in 1..=24_u8 {
+                let sv_position = sv_position(prn, t - 0.075);
+                let geo = geometry(rx, sv_position);
+                if geo.elevation < mask {
+                    continue;
+                }
+                let sv_clock = 1.0E-5 * prn as f64;
+                let model = geo.range
+                    + SPEED_OF_LIGHT * (dt_rx - sv_clock)
+                    + (zhd + zwd) * mapping(geo.elevation);
+                let sin_el = geo.elevation.sin();
+                candidates.push(Candidate {
+                    sv: SV::new(Constellation::GPS, prn),
+                    code: model + gaussian(&mut rng, 0.3 / sin_el),
+                    phase: model
+                        + ambiguities[prn as usize - 1]
+                        + gaussian(&mut rng, 0.003 / sin_el),
+                    sv_position,
+                    sv_clock,
+                    cycle_slip: false,
+                });
+            }
+            let epoch = t0 + Duration::from_seconds(t);
+            solution = Some(solver.resolve(epoch, &candidates).unwrap());
+        }
+
+        let solution = solution.unwrap();
+        let error = ((solution.position.0 - rx.0).powi(2)
+            + (solution.position.1 - rx.1).powi(2)
+            + (solution.position.2 - rx.2).powi(2))
+        .sqrt();
+        assert!(error < 0.05, "3D error: {} m", error);
+        assert!(
+            (solution.zwd - zwd).abs() < 0.02,
+            "zwd error: {} m",
+            solution.zwd - zwd
+        );
+        assert!((solution.ztd - zhd - zwd).abs() < 0.02);
+        assert!(solution.satellites >= 4);
+    }
+}
@@ -1,13 +1,15 @@
 #[cfg(test)]
 mod test {
-    use crate::tests::toolkit::{random_name, test_against_model};
+    use crate::tests::toolkit::{test_against_model, validate_output, TmpFile};
     use crate::*;
     use std::path::Path;
+    use std::str::FromStr;
     fn testbench(path: &str) {
         // parse this file
         let rnx = Rinex::from_file(path).unwrap(); // already tested elsewhere
-        let tmp_path = format!("test-{}.rnx", random_name(5));
+        let tmp_path = TmpFile::new("rnx");
         assert!(rnx.to_file(&tmp_path).is_ok()); // test writer
+        validate_output(&tmp_path);
         let copy = Rinex::from_file(&tmp_path);
         assert!(copy.is_ok()); // content should be valid
         let copy = copy.unwrap();
@@ -16,8 +18,110 @@ mod test {
             test_against_model(&copy, &rnx, path, 1.0E-6);
         }
         println!("production test passed for \"{}\"", path);
-        // remove copy
-        let _ = std::fs::remove_file(tmp_path);
+    }
+    #[test]
+    fn verbatim_and_stamped_production() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("OBS")
+            .join("V3")
+            .join("DUTH0630.22O");
+        let rnx = Rinex::from_file(&path.to_string_lossy()).unwrap();
+
+        // to_file: header is preserved as is
+        let tmp_path = TmpFile::new("rnx");
+        assert!(rnx.to_file(&tmp_path).is_ok());
+        let copy = Rinex::from_file(&tmp_path).unwrap();
+        assert_eq!(copy.header.program, rnx.header.program);
+        assert_eq!(copy.header.run_by, rnx.header.run_by);
+        assert_eq!(copy.header.date, rnx.header.date);
+
+        // to_file_with_stamp: production is stamped
+        let tmp_path = TmpFile::new("rnx");
+        assert!(rnx
+            .to_file_with_stamp(&tmp_path, &Stamp::reproducible())
+            .is_ok());
+        let copy = Rinex::from_file(&tmp_path).unwrap();
+        assert_eq!(
+            copy.header.program,
+            format!("rust-rinex-{}", env!("CARGO_PKG_VERSION"))
+        );
+        assert_eq!(copy.header.run_by, rnx.header.run_by);
+        assert_ne!(copy.header.date, rnx.header.date);
+    }
+    #[test]
+    fn output_conventions() {
+        use crate::writer::{EndOfFile, LineEnding, WriterSettings};
+        for path in [
+            "../test_resources/OBS/V3/DUTH0630.22O",
+            "../test_resources/CRNX/V3/DUTH0630.22D",
+        ] {
+            let rnx = Rinex::from_file(path).unwrap();
+            let tmp_path = TmpFile::new("rnx");
+            assert!(rnx.to_file(&tmp_path).is_ok());
+            let reference = std::fs::read_to_string(&tmp_path).unwrap();
+            assert!(reference.ends_with('\n'));
+
+            for (line_ending, end_of_file, expected) in [
+                (
+                    LineEnding::CrLf,
+                    EndOfFile::Terminated,
+                    reference.replace('\n', "\r\n"),
+                ),
+                (
+                    LineEnding::CrLf,
+                    EndOfFile::BlankLine,
+                    reference.replace('\n', "\r\n") + "\r\n",
+                ),
+                (
+                    LineEnding::Lf,
+                    EndOfFile::BlankLine,
+                    reference.clone() + "\n",
+                ),
+                (
+                    LineEnding::Lf,
+                    EndOfFile::Unterminated,
+                    reference.strip_suffix('\n').unwrap().to_string(),
+                ),
+            ] {
+                let settings = WriterSettings::default()
+                    .with_line_ending(line_ending)
+                    .with_end_of_file(end_of_file);
+                let tmp_path = TmpFile::new("rnx");
+                assert!(rnx.to_file_with_settings(&tmp_path, &settings).is_ok());
+                let content = std::fs::read_to_string(&tmp_path).unwrap();
+                assert!(
+                    content == expected,
+                    "{:?} {:?} not applied to {}",
+                    line_ending,
+                    end_of_file,
+                    path
+                );
+
+                // progress reports and stamps follow the same conventions
+                let mut last = (0, 0);
+                let tmp_path = TmpFile::new("rnx");
+                assert!(rnx
+                    .to_file_with_progress(
+                        &tmp_path,
+                        &settings.with_stamp(&Stamp::reproducible()),
+                        &mut |bytes: u64, epochs: usize| last = (bytes, epochs),
+                    )
+                    .is_ok());
+                let content = std::fs::read_to_string(&tmp_path).unwrap();
+                assert_eq!(last, (content.len() as u64, rnx.epoch().count()));
+                assert_eq!(
+                    content.matches('\n').count(),
+                    expected.matches('\n').count()
+                );
+                assert_eq!(
+                    content.matches("\r\n").count(),
+                    expected.matches("\r\n").count()
+                );
+                assert_eq!(content.ends_with('\n'), expected.ends_with('\n'));
+            }
+        }
     }
     #[test]
     #[cfg(feature = "flate2")]
@@ -90,7 +194,6 @@ mod test {
     }
     #[test]
     #[cfg(feature = "flate2")]
-    #[ignore]
     fn nav_v2() {
         let folder = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/NAV/V2/";
         for file in std::fs::read_dir(folder).unwrap() {
@@ -121,4 +224,167 @@ mod test {
             testbench(fp.to_str().unwrap());
         }
     }
+    #[test]
+    fn reordered_observables() {
+        for path in [
+            "../test_resources/OBS/V2/AJAC3550.21O",
+            "../test_resources/OBS/V3/DUTH0630.22O",
+        ] {
+            let rnx = Rinex::from_file(path).unwrap();
+            let reordered = rnx.reorder_observables();
+            let codes = &reordered.header.obs.as_ref().unwrap().codes;
+            for observables in codes.values() {
+                for pair in observables.windows(2) {
+                    assert!(
+                        pair[0].canonical_cmp(&pair[1]).is_lt(),
+                        "{}: {} should not preceed {}",
+                        path,
+                        pair[0],
+                        pair[1]
+                    );
+                }
+            }
+
+            // columns follow the header definitions: content is preserved
+            let tmp_path = TmpFile::new("rnx");
+            assert!(reordered.to_file(&tmp_path).is_ok());
+            let copy = Rinex::from_file(&tmp_path).unwrap();
+            assert_eq!(copy.header.obs.as_ref().unwrap().codes, *codes);
+            assert_eq!(copy.record, rnx.record, "{}: content altered", path);
+        }
+    }
+    #[test]
+    fn header_continuations() {
+        // each COMMENT line is a comment on its own
+        let rnx = Rinex::from_file("../test_resources/OBS/V2/delf0010.21o").unwrap();
+        let official = "The coordinates in the RINEX header are the adopted official national coordinates in the system ETRS89/ETRF2000.";
+        let wrapped = [
+            "The coordinates in the RINEX header are the adopted official",
+            "national coordinates in the system ETRS89/ETRF2000.",
+        ];
+        for comment in wrapped {
+            assert!(rnx.header.comments.iter().any(|c| c == comment));
+        }
+        assert!(rnx.header.comments.iter().any(|c| c == &"-".repeat(60)));
+
+        let v2_codes: Vec<Observable> = [
+            "C1", "L1", "D1", "S1", "P1", "C2", "L2", "D2", "S2", "P2", "C5", "L5", "D5", "S5",
+            "C7", "L7", "D7", "S7", "C8", "L8", "D8", "S8",
+        ]
+        .iter()
+        .map(|code| Observable::from_str(code).unwrap())
+        .collect();
+        let v3_codes: Vec<Observable> = ['1', '2', '5', '6', '7', '8']
+            .iter()
+            .flat_map(|band| {
+                ['C', 'L', 'D', 'S']
+                    .iter()
+                    .map(move |kind| Observable::from_str(&format!("{}{}X", kind, band)).unwrap())
+            })
+            .collect();
+
+        for (path, codes) in [
+            ("../test_resources/OBS/V2/AJAC3550.21O", v2_codes),
+            ("../test_resources/OBS/V3/DUTH0630.22O", v3_codes),
+        ] {
+            let mut rnx = Rinex::from_file(path).unwrap();
+            let obs = rnx.header.obs.as_mut().unwrap();
+            for observables in obs.codes.values_mut() {
+                *observables = codes.clone();
+            }
+            rnx.header.comments.push(official.to_string());
+
+            let tmp_path = TmpFile::new("rnx");
+            assert!(rnx.to_file(&tmp_path).is_ok());
+            let content = std::fs::read_to_string(&tmp_path).unwrap();
+            let copy = Rinex::from_file(&tmp_path).unwrap();
+
+            let lists: Vec<_> = content
+                .lines()
+                .filter(|l| l.ends_with("OBS TYPES") || l.ends_with("TYPES OF OBSERV"))
+                .collect();
+            for line in &lists {
+                let marker = line.split_at(60).1;
+                assert!(
+                    marker == "SYS / # / OBS TYPES" || marker == "# / TYPES OF OBSERV",
+                    "{}: malformed \"{}\"",
+                    path,
+                    line
+                );
+            }
+            let continuations = lists.iter().filter(|l| l.starts_with("      ")).count();
+            assert!(continuations > 0, "{}: code list not continued", path);
+
+            let copy_obs = copy.header.obs.as_ref().unwrap();
+            for (constell, observables) in &copy_obs.codes {
+                assert_eq!(observables, &codes, "{}: {} codes", path, constell);
+            }
+            // long comments are wrapped on word boundaries
+            let (original, appended) = copy.header.comments.split_at(rnx.header.comments.len() - 1);
+            assert_eq!(original, &rnx.header.comments[..original.len()], "{}", path);
+            assert_eq!(appended, wrapped, "{}", path);
+        }
+    }
+    #[test]
+    fn record_comments() {
+        for path in [
+            "../test_resources/OBS/V2/wsra0010.21o",
+            "../test_resources/CRNX/V1/wsra0010.21d",
+            "../test_resources/MET/V2/abvi0010.15m",
+        ] {
+            let mut rnx = Rinex::from_file(path).unwrap();
+            let epochs: Vec<_> = rnx.epoch().collect();
+            let last = epochs[epochs.len() - 1];
+            rnx.comments = record::Comments::new();
+            // prior to first epoch
+            rnx.comments
+                .insert(Epoch::default(), vec!["PROCESSED BY RINEX-CLI".to_string()]);
+            // interleaved: mid file header update
+            rnx.comments.insert(
+                epochs[1],
+                vec![
+                    "RINEX FILE SPLICE".to_string(),
+                    "ANTENNA CHANGED AT THIS POINT".to_string(),
+                ],
+            );
+            // following last epoch
+            rnx.comments
+                .insert(last, vec!["END OF SESSION".to_string()]);
+
+            let tmp_path = TmpFile::new("rnx");
+            assert!(rnx.to_file(&tmp_path).is_ok());
+
+            let content = std::fs::read_to_string(&tmp_path).unwrap();
+            let lines: Vec<_> = content.lines().collect();
+            let header_end = lines
+                .iter()
+                .position(|l| l.ends_with("END OF HEADER"))
+                .unwrap();
+            assert!(
+                lines[header_end + 1].starts_with("PROCESSED BY RINEX-CLI"),
+                "leading comment not preserved for {}",
+                path
+            );
+            let splice = lines
+                .iter()
+                .position(|l| l.starts_with("RINEX FILE SPLICE"))
+                .unwrap_or_else(|| panic!("interleaved comment not written for {}", path));
+            assert!(lines[splice + 1].starts_with("ANTENNA CHANGED AT THIS POINT"));
+            assert!(
+                lines[lines.len() - 1].starts_with("END OF SESSION"),
+                "trailing comment not preserved for {}",
+                path
+            );
+
+            let copy = Rinex::from_file(&tmp_path).unwrap();
+            for (epoch, content) in rnx.comments.iter().take(2) {
+                assert_eq!(
+                    copy.comments.get(epoch),
+                    Some(content),
+                    "comments not preserved for {}",
+                    path
+                );
+            }
+        }
+    }
 }
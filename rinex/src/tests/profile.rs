@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod test {
+    use crate::observation::MappingProfile;
+    use crate::prelude::*;
+    use crate::tests::toolkit::TmpFile;
+    use std::path::PathBuf;
+    /*
+     * Emulates a non compliant receiver: Q designates GPS
+     * and C1C is labeled X1C. The profile recovers the original file.
+     */
+    #[test]
+    fn duth0630_profile() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("OBS")
+            .join("V3")
+            .join("DUTH0630.22O");
+        let original = Rinex::from_file(&path.to_string_lossy()).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let mut faulty = String::with_capacity(content.len());
+        let mut in_record = false;
+        for line in content.lines() {
+            let obs_types = line.trim_end().ends_with("SYS / # / OBS TYPES");
+            let line = match (in_record || obs_types, line.strip_prefix('G')) {
+                (true, Some(rem)) => format!("Q{}", rem),
+                _ => line.to_string(),
+            };
+            let line = match obs_types {
+                true => line.replacen("C1C", "X1C", 1),
+                false => line,
+            };
+            in_record |= line.trim_end().ends_with("END OF HEADER");
+            faulty.push_str(&line);
+            faulty.push('\n');
+        }
+        let tmp_path = TmpFile::new("22O");
+        std::fs::write(&tmp_path, faulty).unwrap();
+
+        // unusable as is
+        let rinex = Rinex::from_file(&tmp_path).unwrap();
+        assert!(rinex.sv().all(|sv| sv.constellation != Constellation::GPS));
+
+        let mut profile = MappingProfile::default();
+        profile
+            .constellations
+            .insert("Q".to_string(), "G".to_string());
+        profile
+            .observables
+            .insert("X1C".to_string(), "C1C".to_string());
+        let rinex = Rinex::from_file_with_profile(&tmp_path, &profile).unwrap();
+
+        assert_eq!(rinex.header.obs, original.header.obs);
+        assert_eq!(rinex.record, original.record);
+    }
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::preprocessing::*;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    #[test]
+    fn esbc00dnk_residuals() {
+        let prefix = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources");
+        let obs = Rinex::from_file(
+            prefix
+                .join("CRNX")
+                .join("V3")
+                .join("ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+                .to_string_lossy()
+                .as_ref(),
+        )
+        .unwrap();
+        let nav = Rinex::from_file(
+            prefix
+                .join("NAV")
+                .join("V3")
+                .join("ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+                .to_string_lossy()
+                .as_ref(),
+        )
+        .unwrap();
+        let position = obs.header.ground_position.unwrap();
+        let obs = obs.decimate_by_sampling_interval(SamplingInterval::from_str("300 s").unwrap());
+
+        let residuals = obs.pseudo_range_residuals(&nav, position, 10.0);
+        assert!(!residuals.is_empty());
+        let mut signals = HashMap::<(Constellation, Observable), Vec<f64>>::new();
+        for residual in &residuals {
+            assert!(residual.elevation >= 10.0 && residual.elevation <= 90.0);
+            assert!(residual.azimuth >= 0.0 && residual.azimuth < 360.0);
+            assert!(residual.observable.is_pseudorange_observable());
+            if residual.sv.constellation.is_sbas() {
+                // S25 ephemeris published in this file does not match
+                // the actual satellite: that is exactly what this analysis reveals
+                continue;
+            }
+            // ionospheric delay is not modeled
+            assert!(
+                residual.residual.abs() < 50.0,
+                "{} {}({}): {:.3} m",
+                residual.epoch,
+                residual.sv,
+                residual.observable,
+                residual.residual
+            );
+            signals
+                .entry((residual.sv.constellation, residual.observable.clone()))
+                .or_default()
+                .push(residual.residual.abs());
+        }
+        assert!(signals.contains_key(&(Constellation::GPS, Observable::from_str("C1C").unwrap())));
+        for ((constellation, observable), mut residuals) in signals {
+            residuals.sort_by(|a, b| a.total_cmp(b));
+            let median = residuals[residuals.len() / 2];
+            assert!(
+                median < 5.0,
+                "{} {}: median residual {:.3} m",
+                constellation,
+                observable,
+                median
+            );
+        }
+        // chronological order
+        assert!(residuals.windows(2).all(|w| w[0].epoch <= w[1].epoch));
+        // high mask
+        let high = obs.pseudo_range_residuals(&nav, position, 60.0);
+        assert!(high.len() < residuals.len());
+        assert!(high.iter().all(|residual| residual.elevation >= 60.0));
+    }
+}
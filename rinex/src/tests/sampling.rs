@@ -121,4 +121,115 @@ mod sampling {
             "data_gaps(tol=3h) failed",
         );
     }
+    #[test]
+    fn high_rate() {
+        use crate::observation::Record;
+        use crate::tests::toolkit::TmpFile;
+        let rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let record = rinex.record.as_obs().unwrap();
+        let ((t0, flag), content) = record.iter().next().unwrap();
+
+        // 10 Hz sampling
+        let dt = Duration::from_milliseconds(100.0);
+        let high_rate: Record = (0..20)
+            .map(|k| ((*t0 + dt * k as i64, *flag), content.clone()))
+            .collect();
+        let mut rinex = Rinex::new(
+            rinex.header.clone(),
+            crate::record::Record::ObsRecord(high_rate),
+        );
+        rinex.header.sampling_interval = SamplingInterval::new(dt).ok();
+        assert_eq!(rinex.dominant_sample_rate(), Some(dt));
+
+        // RINEX and CRINEX production
+        for crinex in [false, true] {
+            let produced = if crinex {
+                rinex.rnx2crnx()
+            } else {
+                rinex.clone()
+            };
+            let tmp_path = TmpFile::new("rnx");
+            assert!(produced.to_file(&tmp_path).is_ok());
+            let parsed = Rinex::from_file(&tmp_path);
+            let parsed = parsed.unwrap();
+            assert_eq!(parsed.sample_rate(), Some(dt));
+            assert!(
+                parsed.epoch().eq(rinex.epoch()),
+                "high rate epochs not preserved (crinex: {})",
+                crinex
+            );
+        }
+
+        // decimation by fractional intervals
+        let decimated =
+            rinex.decimate_by_sampling_interval(SamplingInterval::from_str("0.2 s").unwrap());
+        assert_eq!(decimated.epoch().count(), 10);
+        assert_eq!(
+            decimated.sample_rate(),
+            Some(Duration::from_milliseconds(200.0))
+        );
+        assert_eq!(
+            decimated.dominant_sample_rate(),
+            Some(Duration::from_milliseconds(200.0))
+        );
+        let decimated = rinex.filter(Filter::from_str("decim:500 ms").unwrap());
+        assert_eq!(decimated.epoch().count(), 4);
+        let decimated = rinex.filter(Filter::from_str("decim:0.5 s").unwrap());
+        assert_eq!(decimated.epoch().count(), 4);
+        let interval = SamplingInterval::from_str("500 ms").unwrap();
+        assert!(interval.approx_eq(
+            decimated.sample_rate().unwrap(),
+            Duration::from_microseconds(1.0)
+        ));
+        let decimated = rinex.filter(Filter::from_str("decim:1 s").unwrap());
+        assert!(decimated
+            .epoch()
+            .eq(vec![*t0, *t0 + Duration::from_seconds(1.0)]));
+        let decimated = rinex.decimate_by_ratio(4);
+        assert_eq!(decimated.epoch().count(), 5);
+        assert_eq!(
+            decimated.sample_rate(),
+            Some(Duration::from_milliseconds(400.0))
+        );
+    }
+    #[test]
+    fn time_shift() {
+        let prefix = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources");
+        let offset = Duration::from_seconds(-18.0);
+        for path in [
+            prefix.join("OBS").join("V3").join("DUTH0630.22O"),
+            prefix
+                .join("CRNX")
+                .join("V3")
+                .join("ACOR00ESP_R_20213550000_01D_30S_MO.crx"),
+            prefix.join("MET").join("V2").join("abvi0010.15m"),
+        ] {
+            let fullpath = path.to_string_lossy();
+            let rinex = Rinex::from_file(fullpath.as_ref()).unwrap();
+            let shifted = rinex.shift_epochs(offset);
+            assert_eq!(shifted.epoch().count(), rinex.epoch().count());
+            for (t, shifted_t) in rinex.epoch().zip(shifted.epoch()) {
+                assert_eq!(shifted_t, t + offset, "\"{}\": bad epoch", fullpath);
+            }
+            assert_eq!(shifted.dominant_sample_rate(), rinex.dominant_sample_rate());
+            if let Some(obs) = &rinex.header.obs {
+                let shifted_obs = shifted.header.obs.as_ref().unwrap();
+                assert_eq!(
+                    shifted_obs.time_of_first_obs,
+                    obs.time_of_first_obs.map(|t| t + offset)
+                );
+                assert_eq!(
+                    shifted_obs.time_of_last_obs,
+                    obs.time_of_last_obs.map(|t| t + offset)
+                );
+            }
+            // reversible
+            let restored = shifted.shift_epochs(-offset);
+            assert_eq!(restored.record, rinex.record);
+            assert_eq!(restored.comments, rinex.comments);
+            assert_eq!(restored.header.obs, rinex.header.obs);
+        }
+    }
 }
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod test {
+    use crate::filter;
+    use crate::prelude::*;
+    use crate::preprocessing::*;
+    use crate::shared::{RecordView, SharedRinex};
+    use std::str::FromStr;
+    fn is_send_sync<T: Send + Sync>() {}
+    #[test]
+    fn send_sync() {
+        is_send_sync::<Rinex>();
+        is_send_sync::<SharedRinex>();
+        is_send_sync::<RecordView>();
+    }
+    #[test]
+    fn copy_on_write() {
+        let rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let mut shared = SharedRinex::from(rinex.clone());
+        let other = shared.clone();
+        assert!(shared.ptr_eq(&other));
+
+        let views: Vec<RecordView> = (0..4).map(|_| shared.view()).collect();
+        let handles: Vec<_> = views
+            .into_iter()
+            .map(|mut view| {
+                std::thread::spawn(move || {
+                    assert!(view.is_shared());
+                    view.filter_mut(filter!("G01"));
+                    assert!(!view.is_shared());
+                    view.to_rinex()
+                })
+            })
+            .collect();
+        for handle in handles {
+            let g01 = handle.join().unwrap();
+            assert_eq!(
+                g01.sv().collect::<Vec<_>>(),
+                vec![SV::from_str("G01").unwrap()]
+            );
+        }
+        // source is left untouched
+        assert_eq!(shared.record, rinex.record);
+        assert!(shared.ptr_eq(&other));
+
+        // modification detaches the handle
+        shared.make_mut().filter_mut(filter!("GPS"));
+        assert!(!shared.ptr_eq(&other));
+        assert_eq!(other.record, rinex.record);
+        assert_eq!(other.into_inner(), rinex);
+    }
+}
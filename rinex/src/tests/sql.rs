@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::sql::{to_sql, Exporter, Row, Value};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    fn export(exporter: &mut Exporter, path: &str, rinex: &Rinex) -> Vec<Row> {
+        let mut rows = Vec::<Row>::new();
+        exporter
+            .export(path, rinex, |row| -> Result<(), ()> {
+                rows.push(row);
+                Ok(())
+            })
+            .unwrap();
+        rows
+    }
+    #[test]
+    fn sql_export() {
+        let prefix = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources");
+        let obs_path = prefix.join("OBS").join("V3").join("DUTH0630.22O");
+        let obs_path = obs_path.to_string_lossy().to_string();
+        let obs = Rinex::from_file(&obs_path).unwrap();
+        let nav_path = prefix
+            .join("NAV")
+            .join("V3")
+            .join("AMEL00NLD_R_20210010000_01D_MN.rnx");
+        let nav_path = nav_path.to_string_lossy().to_string();
+        let nav = Rinex::from_file(&nav_path).unwrap();
+
+        let mut exporter = Exporter::default();
+        let mut rows = export(&mut exporter, &obs_path, &obs);
+        rows.extend(export(&mut exporter, &nav_path, &nav));
+
+        let mut counts = HashMap::<&str, usize>::new();
+        for row in &rows {
+            assert_eq!(row.columns.len(), row.values.len(), "{:?}", row);
+            *counts.entry(row.table).or_insert(0) += 1;
+        }
+        let observations: usize = obs
+            .observation()
+            .map(|(_, (_, vehicles))| vehicles.values().map(|obs| obs.len()).sum::<usize>())
+            .sum();
+        assert_eq!(counts["files"], 2);
+        assert_eq!(counts["epochs"], obs.epoch().count());
+        assert_eq!(counts["observations"], observations);
+        assert_eq!(counts["ephemerides"], nav.ephemeris().count());
+        assert!(counts["ephemeris_orbits"] > counts["ephemerides"]);
+
+        // identifiers: the second file follows the first one
+        assert_eq!(exporter.next_file_id, 3);
+        assert_eq!(exporter.next_epoch_id, obs.epoch().count() as i64 + 1);
+        assert_eq!(
+            exporter.next_ephemeris_id,
+            nav.ephemeris().count() as i64 + 1
+        );
+        let files: Vec<&Row> = rows.iter().filter(|row| row.table == "files").collect();
+        assert_eq!(files[0].values[0], Value::Integer(1));
+        assert_eq!(files[0].values[2], Value::Text("OBS DATA".to_string()));
+        assert_eq!(files[1].values[0], Value::Integer(2));
+        assert_eq!(
+            files[1].values[2],
+            Value::Text("NAVIGATION DATA".to_string())
+        );
+
+        // observations refer to the preceding epoch
+        let first_epoch = rows.iter().find(|row| row.table == "epochs").unwrap();
+        assert_eq!(first_epoch.values[0], Value::Integer(1));
+        assert_eq!(first_epoch.values[1], Value::Integer(1));
+        assert_eq!(first_epoch.values[4], Value::Integer(0));
+        let first_obs = rows.iter().find(|row| row.table == "observations").unwrap();
+        assert_eq!(first_obs.values[0], Value::Integer(1));
+        assert_eq!(
+            first_obs.statement(),
+            "INSERT INTO observations (epoch_id, sv, observable, value, lli, snr) VALUES (?1, ?2, ?3, ?4, ?5, ?6);"
+        );
+
+        // ephemerides refer to the navigation file
+        let first_eph = rows.iter().find(|row| row.table == "ephemerides").unwrap();
+        assert_eq!(first_eph.values[1], Value::Integer(2));
+
+        // literals
+        assert_eq!(Value::Text("it's".to_string()).literal(), "'it''s'");
+        assert_eq!(Value::from(f64::NAN), Value::Null);
+        assert_eq!(Value::from(1.5E-9).literal(), "1.5e-9");
+        assert_eq!(Value::from(Option::<i64>::None).literal(), "NULL");
+
+        let mut exporter = Exporter::default();
+        let mut script = Vec::<u8>::new();
+        to_sql(&obs_path, &obs, &mut exporter, &mut script).unwrap();
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.starts_with(crate::sql::SCHEMA));
+        assert!(script.ends_with("COMMIT;\n"));
+        assert_eq!(
+            script
+                .lines()
+                .filter(|l| l.starts_with("INSERT INTO"))
+                .count(),
+            1 + counts["epochs"] + counts["observations"]
+        );
+
+        // successive exports do not reuse identifiers
+        let mut script = Vec::<u8>::new();
+        to_sql(&obs_path, &obs, &mut exporter, &mut script).unwrap();
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains(
+            "INSERT INTO files (id, path, rinex_type, version, constellation, station) VALUES (2, "
+        ));
+        assert_eq!(exporter.next_epoch_id, 2 * counts["epochs"] as i64 + 1);
+    }
+}
@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod test {
+    use crate::navigation::Ephemeris;
+    use crate::prelude::*;
+    use std::path::PathBuf;
+    #[test]
+    fn duth0630_snr_thinning() {
+        let prefix = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources");
+        let obs = Rinex::from_file(
+            prefix
+                .join("OBS")
+                .join("V3")
+                .join("DUTH0630.22O")
+                .to_string_lossy()
+                .as_ref(),
+        )
+        .unwrap();
+        let thinned = obs.thin_out_by_snr(4);
+        assert_eq!(thinned.epoch().count(), obs.epoch().count());
+        for (key, (_, vehicles)) in thinned.observation() {
+            let (_, original) = obs.record.as_obs().unwrap().get(key).unwrap();
+            assert_eq!(vehicles.len(), original.len().min(4), "{:?}", key);
+            let strength = |sv: &SV| -> Option<f64> {
+                let ssi: Vec<f64> = original[sv]
+                    .iter()
+                    .filter(|(observable, _)| observable.is_ssi_observable())
+                    .map(|(_, data)| data.obs)
+                    .collect();
+                match ssi.is_empty() {
+                    true => None,
+                    false => Some(ssi.iter().sum::<f64>() / ssi.len() as f64),
+                }
+            };
+            let weakest_kept = vehicles
+                .keys()
+                .filter_map(strength)
+                .fold(f64::INFINITY, f64::min);
+            for sv in original.keys() {
+                if vehicles.contains_key(sv) {
+                    continue;
+                }
+                if let Some(strength) = strength(sv) {
+                    assert!(strength <= weakest_kept, "{:?}: {} should be kept", key, sv);
+                }
+            }
+        }
+        // no effect when enough slots are available
+        assert_eq!(obs.thin_out_by_snr(1000).record, obs.record);
+    }
+    #[test]
+    fn esbc00dnk_elevation_thinning() {
+        let prefix = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources");
+        let obs = Rinex::from_file(
+            prefix
+                .join("CRNX")
+                .join("V3")
+                .join("ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+                .to_string_lossy()
+                .as_ref(),
+        )
+        .unwrap();
+        let nav = Rinex::from_file(
+            prefix
+                .join("NAV")
+                .join("V3")
+                .join("ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+                .to_string_lossy()
+                .as_ref(),
+        )
+        .unwrap();
+        let position = obs.header.ground_position.unwrap();
+        let elevation = |t: Epoch, sv: SV| -> Option<f64> {
+            let (_, eph) = nav.sv_ephemeris(sv, t)?;
+            let (x, y, z) = eph.sv_position(sv, t)?;
+            let (elev, _) = Ephemeris::elevation_azimuth(
+                (x * 1.0E3, y * 1.0E3, z * 1.0E3),
+                position.to_ecef_wgs84(),
+            );
+            Some(elev)
+        };
+        let thinned = obs.thin_out_by_elevation(8, &nav, None);
+        for ((t, flag), (_, vehicles)) in thinned.observation() {
+            let (_, original) = obs.record.as_obs().unwrap().get(&(*t, *flag)).unwrap();
+            assert_eq!(vehicles.len(), original.len().min(8), "{}", t);
+            let lowest_kept = vehicles
+                .keys()
+                .filter_map(|sv| elevation(*t, *sv))
+                .fold(f64::INFINITY, f64::min);
+            for sv in original.keys() {
+                if vehicles.contains_key(sv) {
+                    continue;
+                }
+                if let Some(elev) = elevation(*t, *sv) {
+                    assert!(elev <= lowest_kept, "{}: {} should be kept", t, sv);
+                }
+            }
+        }
+    }
+}
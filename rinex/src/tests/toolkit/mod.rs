@@ -68,6 +68,55 @@ pub fn random_name(size: usize) -> String {
         .collect()
 }
 
+/*
+ * File produced by a test, with a random name in the system temporary
+ * directory. The file is removed when the guard goes out of scope,
+ * which also happens when the test fails and unwinds.
+ */
+pub struct TmpFile(String);
+
+impl TmpFile {
+    pub fn new(extension: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("test-{}.{}", random_name(8), extension));
+        Self(path.to_string_lossy().to_string())
+    }
+}
+
+impl std::ops::Deref for TmpFile {
+    type Target = str;
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<std::path::Path> for TmpFile {
+    fn as_ref(&self) -> &std::path::Path {
+        std::path::Path::new(&self.0)
+    }
+}
+
+impl std::fmt::Display for TmpFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Drop for TmpFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/*
+ * Verifies that a produced file respects line widths and continuations
+ */
+pub fn validate_output(path: &str) {
+    let content = std::fs::read_to_string(path).unwrap();
+    if let Err(violations) = crate::writer::validate_output(&content) {
+        panic!("\"{}\": invalid output: {:?}", path, violations);
+    }
+}
+
 /*
  * Creates list of observables
  */
@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod test {
+    use crate::navigation::visibility::predict;
+    use crate::prelude::*;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    #[test]
+    fn esbc00dnk_visibility() {
+        let prefix = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources");
+        let obs = Rinex::from_file(
+            prefix
+                .join("CRNX")
+                .join("V3")
+                .join("ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+                .to_string_lossy()
+                .as_ref(),
+        )
+        .unwrap();
+        let nav = Rinex::from_file(
+            prefix
+                .join("NAV")
+                .join("V3")
+                .join("ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+                .to_string_lossy()
+                .as_ref(),
+        )
+        .unwrap();
+        let position = obs.header.ground_position.unwrap();
+        let start = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let end = Epoch::from_str("2020-06-25T23:59:30 GPST").unwrap();
+
+        let passes = predict(
+            &nav,
+            position,
+            start,
+            end,
+            Duration::from_seconds(30.0),
+            0.0,
+        );
+        for (sv, passes) in &passes {
+            for pass in passes {
+                assert!(pass.rise < pass.set, "{}: invalid pass {:?}", sv, pass);
+                assert!(pass.rise >= start && pass.set <= end);
+                assert!(pass.contains(pass.culmination));
+                assert!(pass.max_elevation >= 0.0 && pass.max_elevation <= 90.0);
+            }
+        }
+
+        /* GPS constellation, seen from mid latitudes */
+        let gps = passes
+            .keys()
+            .filter(|sv| sv.constellation == Constellation::GPS)
+            .count();
+        assert!(gps > 20, "only {} GPS satellites predicted", gps);
+        let noon = Epoch::from_str("2020-06-25T12:00:00 GPST").unwrap();
+        let in_sight = passes
+            .iter()
+            .filter(|(sv, passes)| {
+                sv.constellation == Constellation::GPS && passes.iter().any(|p| p.contains(noon))
+            })
+            .count();
+        assert!(
+            (5..=15).contains(&in_sight),
+            "{} GPS satellites in sight",
+            in_sight
+        );
+
+        /* the GPS satellites that were tracked should have been predicted */
+        let (mut tracked, mut predicted) = (0, 0);
+        for ((t, _), (_, vehicles)) in obs.observation() {
+            for sv in vehicles.keys() {
+                if sv.constellation != Constellation::GPS {
+                    continue;
+                }
+                tracked += 1;
+                let contained = passes
+                    .get(sv)
+                    .map(|passes| passes.iter().any(|p| p.contains(*t)))
+                    .unwrap_or(false);
+                if contained {
+                    predicted += 1;
+                }
+            }
+        }
+        assert!(
+            predicted as f64 / tracked as f64 > 0.99,
+            "{}/{} tracked satellites predicted in sight",
+            predicted,
+            tracked
+        );
+
+        /* sampling must go forward */
+        for step in [Duration::ZERO, Duration::from_seconds(-30.0)] {
+            assert!(predict(&nav, position, start, end, step, 0.0).is_empty());
+        }
+    }
+}
@@ -63,7 +63,7 @@ impl std::str::FromStr for Type {
     type Err = ParsingError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let s = s.to_lowercase();
-        if s.eq("navigation data") || s.contains("nav data") {
+        if s.eq("navigation data") || s.contains("nav data") || s.contains("nav msg data") {
             Ok(Self::NavigationData)
         } else if s.eq("observation data") {
             Ok(Self::ObservationData)
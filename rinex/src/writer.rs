@@ -1,9 +1,115 @@
 //! Generic Buffered Writer, for efficient record production,
 //! with integrated optionnal .gz compression
+//! and line width verification
 #[cfg(feature = "flate2")]
 use flate2::{write::GzEncoder, Compression};
 use std::fs::File;
-use std::io::BufWriter; // Seek, SeekFrom};
+use std::io::{BufWriter, Write}; // Seek, SeekFrom};
+use std::str::FromStr;
+
+use crate::{navigation, observation, production::Stamp, version::Version};
+
+/// Maximal width of a RINEX line. Header lines (60 characters of content,
+/// followed by their label in columns 61-80) and most records
+/// (RINEX2 Observations, Navigation, Meteo, IONEX) are limited to this width.
+/// Several tools hard fail on longer lines.
+pub const MAX_LINE_WIDTH: usize = 80;
+
+/// Formatting rule that a produced line does not respect
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineViolation {
+    /// Line exceeds the maximal width
+    TooLong {
+        /// line number (starting at 1)
+        line: usize,
+        /// line width
+        width: usize,
+    },
+    /// Header line is not labeled in columns 61-80
+    MissingLabel {
+        /// line number (starting at 1)
+        line: usize,
+    },
+    /// Continuation line is not indented like the standards require
+    Indentation {
+        /// line number (starting at 1)
+        line: usize,
+    },
+}
+
+/// [LineEnding] of the produced content
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// Unix line ending, like the standards expect
+    #[default]
+    Lf,
+    /// Windows line ending, that some downstream tools require
+    CrLf,
+}
+
+impl LineEnding {
+    /// Returns the line terminator
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        }
+    }
+    /// Converts LF terminated content to this [LineEnding]
+    pub fn apply(&self, content: &str) -> String {
+        match self {
+            Self::Lf => content.to_string(),
+            Self::CrLf => content.replace('\n', "\r\n"),
+        }
+    }
+}
+
+/// [EndOfFile] convention: how the last line of a file is concluded
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum EndOfFile {
+    /// Last line is terminated, like any other line
+    #[default]
+    Terminated,
+    /// Last line is terminated and followed by a blank line
+    BlankLine,
+    /// Last line is not terminated
+    Unterminated,
+}
+
+/// [WriterSettings] to satisfy downstream tools that expect
+/// other conventions than ours, see [crate::Rinex::to_file_with_settings].
+/// They apply to the whole content, CRINEX compression included.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WriterSettings {
+    /// Line terminator
+    pub line_ending: LineEnding,
+    /// End of file convention
+    pub end_of_file: EndOfFile,
+    /// `PGM / RUN BY / DATE` [Stamp] applied to the produced header.
+    /// The header is written as is, when undefined.
+    pub stamp: Option<Stamp>,
+}
+
+impl WriterSettings {
+    /// Copies and returns [WriterSettings] with given [LineEnding]
+    pub fn with_line_ending(&self, line_ending: LineEnding) -> Self {
+        let mut s = self.clone();
+        s.line_ending = line_ending;
+        s
+    }
+    /// Copies and returns [WriterSettings] with given [EndOfFile] convention
+    pub fn with_end_of_file(&self, end_of_file: EndOfFile) -> Self {
+        let mut s = self.clone();
+        s.end_of_file = end_of_file;
+        s
+    }
+    /// Copies and returns [WriterSettings] with given [Stamp]
+    pub fn with_stamp(&self, stamp: &Stamp) -> Self {
+        let mut s = self.clone();
+        s.stamp = Some(stamp.clone());
+        s
+    }
+}
 
 #[derive(Debug)]
 pub enum WriterWrapper {
@@ -17,12 +123,31 @@ pub enum WriterWrapper {
 pub struct BufferedWriter {
     /// internal writer,
     writer: WriterWrapper,
+    /// current line number (starting at 1)
+    line: usize,
+    /// current line width
+    column: usize,
+    /// line width limit, if any
+    max_width: Option<usize>,
+    /// lines that exceeded the limit
+    violations: Vec<LineViolation>,
+    /// bytes produced so far
+    bytes: u64,
+    /// output conventions
+    settings: WriterSettings,
+    /// line terminator held back, until we know whether
+    /// it concludes the file or not
+    pending_eol: bool,
 }
 
 impl BufferedWriter {
     /// Opens given file for efficient buffered write operation
     /// with possible .gz compression
     pub fn new(path: &str) -> std::io::Result<Self> {
+        Self::with_settings(path, &WriterSettings::default())
+    }
+    /// Same as [Self::new], with custom [WriterSettings]
+    pub fn with_settings(path: &str, settings: &WriterSettings) -> std::io::Result<Self> {
         let f = std::fs::File::create(path)?;
         if path.ends_with(".gz") {
             // --> .gz compression
@@ -30,12 +155,13 @@ impl BufferedWriter {
             {
                 // .gz
                 // example : i.gz, .n.gz, .crx.gz
-                Ok(Self {
-                    writer: WriterWrapper::GzFile(
+                Ok(Self::from_wrapper(
+                    WriterWrapper::GzFile(
                         // compression lvl 6 seems to be the optimal standard
                         BufWriter::new(GzEncoder::new(f, Compression::new(6))),
                     ),
-                })
+                    settings,
+                ))
             }
             #[cfg(not(feature = "flate2"))]
             {
@@ -45,20 +171,119 @@ impl BufferedWriter {
             panic!(".z compression is not supported yet, compress manually")
         } else {
             // Assumes no extra compression
-            Ok(Self {
-                writer: WriterWrapper::PlainFile(BufWriter::new(f)),
-            })
+            Ok(Self::from_wrapper(
+                WriterWrapper::PlainFile(BufWriter::new(f)),
+                settings,
+            ))
+        }
+    }
+    fn from_wrapper(writer: WriterWrapper, settings: &WriterSettings) -> Self {
+        Self {
+            writer,
+            line: 1,
+            column: 0,
+            max_width: Some(MAX_LINE_WIDTH),
+            violations: Vec::new(),
+            bytes: 0,
+            settings: settings.clone(),
+            pending_eol: false,
+        }
+    }
+    /// Concludes the file, following the [EndOfFile] convention,
+    /// and flushes the content. Dropping the writer concludes the file as well,
+    /// but possible errors are then ignored.
+    pub fn finish(&mut self) -> std::io::Result<()> {
+        if self.pending_eol {
+            self.pending_eol = false;
+            let eol = self.settings.line_ending.as_str();
+            let terminator = match self.settings.end_of_file {
+                EndOfFile::Terminated => eol.to_string(),
+                EndOfFile::BlankLine => eol.repeat(2),
+                EndOfFile::Unterminated => String::new(),
+            };
+            self.write_inner(terminator.as_bytes())?;
+        }
+        std::io::Write::flush(self)
+    }
+    /*
+     * Writes into the internal writer
+     */
+    fn write_inner(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        match self.writer {
+            WriterWrapper::PlainFile(ref mut writer) => writer.write_all(buf),
+            #[cfg(feature = "flate2")]
+            WriterWrapper::GzFile(ref mut writer) => writer.write_all(buf),
+        }?;
+        self.bytes += buf.len() as u64;
+        Ok(())
+    }
+    /// Defines the line width limit of the following content,
+    /// `None` to lift it (for example: RINEX3 Observations, CRINEX).
+    /// Defaults to [MAX_LINE_WIDTH].
+    pub fn set_max_width(&mut self, max_width: Option<usize>) {
+        self.max_width = max_width;
+    }
+    /// Returns the lines produced so far, that exceeded the width limit
+    pub fn violations(&self) -> &[LineViolation] {
+        &self.violations
+    }
+    /// Returns the amount of bytes produced so far (prior .gz compression)
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+    /*
+     * Tracks the width of the lines being produced
+     */
+    fn track(&mut self, buf: &[u8]) {
+        for byte in buf {
+            if *byte == b'\n' {
+                if let Some(max_width) = self.max_width {
+                    if self.column > max_width {
+                        self.violations.push(LineViolation::TooLong {
+                            line: self.line,
+                            width: self.column,
+                        });
+                    }
+                }
+                self.line += 1;
+                self.column = 0;
+            } else if *byte != b'\r' && (*byte & 0xC0) != 0x80 {
+                // utf-8 continuation bytes do not count
+                self.column += 1;
+            }
         }
     }
 }
 
 impl std::io::Write for BufferedWriter {
+    /*
+     * Content is LF terminated (CR are dropped): line terminators
+     * are converted to the desired [LineEnding], the last one being held back.
+     */
     fn write(&mut self, buf: &[u8]) -> Result<usize, std::io::Error> {
-        match self.writer {
-            WriterWrapper::PlainFile(ref mut writer) => writer.write(buf),
-            #[cfg(feature = "flate2")]
-            WriterWrapper::GzFile(ref mut writer) => writer.write(buf),
+        let eol = self.settings.line_ending.as_str().as_bytes();
+        let mut content = Vec::with_capacity(buf.len());
+        for byte in buf {
+            match *byte {
+                b'\r' => {},
+                b'\n' => {
+                    if self.pending_eol {
+                        content.extend_from_slice(eol);
+                    }
+                    self.pending_eol = true;
+                },
+                _ => {
+                    if self.pending_eol {
+                        content.extend_from_slice(eol);
+                        self.pending_eol = false;
+                    }
+                    content.push(*byte);
+                },
+            }
         }
+        self.write_inner(&content)?;
+        self.track(buf);
+        Ok(buf.len())
     }
     fn flush(&mut self) -> Result<(), std::io::Error> {
         match self.writer {
@@ -68,3 +293,162 @@ impl std::io::Write for BufferedWriter {
         }
     }
 }
+
+impl Drop for BufferedWriter {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
+
+/// Verifies that given RINEX content (as produced) respects the line width
+/// and continuation rules: header lines are labeled in columns 61-80,
+/// records are limited to [MAX_LINE_WIDTH] where the format requires it,
+/// and continuation lines (RINEX2 Observation vehicles, Navigation orbits)
+/// are correctly indented. CRINEX records are not verified.
+/// This is the hook our test suite runs on produced files.
+pub fn validate_output(content: &str) -> Result<(), Vec<LineViolation>> {
+    let mut violations = Vec::new();
+    let mut in_header = true;
+    let mut crinex = false;
+    let mut version = Version::default();
+    let mut rinex_type = ' ';
+    // pending vehicle continuation lines (RINEX2 Observations)
+    let mut continuations = 0_usize;
+    // pending special records of an event (RINEX2 Observations)
+    let mut records = 0_usize;
+    for (index, line) in content.lines().enumerate() {
+        let number = index + 1;
+        let width = line.chars().count();
+        if in_header {
+            if width > MAX_LINE_WIDTH {
+                violations.push(LineViolation::TooLong {
+                    line: number,
+                    width,
+                });
+            }
+            let label = line.get(60..).unwrap_or_default().trim();
+            if label.is_empty() {
+                violations.push(LineViolation::MissingLabel { line: number });
+            } else if label.starts_with("CRINEX VERS") {
+                crinex = true;
+            } else if label.ends_with("VERSION / TYPE") {
+                version =
+                    Version::from_str(line.get(..9).unwrap_or_default().trim()).unwrap_or_default();
+                rinex_type = line.chars().nth(20).unwrap_or(' ');
+            } else if label == "END OF HEADER" {
+                in_header = false;
+            }
+            continue;
+        }
+        let max_width = match rinex_type {
+            'O' if crinex || version.major > 2 => None,
+            'O' | 'N' | 'G' | 'H' | 'M' | 'I' => Some(MAX_LINE_WIDTH),
+            _ => None,
+        };
+        if let Some(max_width) = max_width {
+            if width > max_width {
+                violations.push(LineViolation::TooLong {
+                    line: number,
+                    width,
+                });
+            }
+        }
+        let indented = match rinex_type {
+            'O' if !crinex && version.major < 3 => {
+                if records > 0 {
+                    records -= 1;
+                    None
+                } else if continuations > 0 {
+                    continuations -= 1;
+                    Some(32)
+                } else {
+                    if observation::record::is_new_epoch(line, version) {
+                        let n = line
+                            .get(29..32)
+                            .and_then(|n| n.trim().parse::<usize>().ok())
+                            .unwrap_or(0);
+                        match line.get(28..29) {
+                            Some("2" | "3" | "4" | "5") => records = n,
+                            _ => continuations = n.saturating_sub(1) / 12,
+                        }
+                    }
+                    None
+                }
+            },
+            'N' | 'G' | 'H' if version.major < 4 => {
+                if navigation::record::is_new_epoch(line, version) {
+                    None
+                } else if version.major < 3 {
+                    Some(3)
+                } else {
+                    Some(4)
+                }
+            },
+            _ => None,
+        };
+        if let Some(indent) = indented {
+            let blank = line.get(..indent).map(|s| s.trim().is_empty());
+            if blank != Some(true) {
+                violations.push(LineViolation::Indentation { line: number });
+            }
+        }
+    }
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn output_validation() {
+        let header = format!(
+            "{:<60}{}\n{:<60}{}\n",
+            "     2.11           OBSERVATION DATA    G (GPS)",
+            "RINEX VERSION / TYPE",
+            "",
+            "END OF HEADER"
+        );
+        let epoch = " 21  1  1  0  0  0.0000000  0 13G01G02G03G04G05G06G07G08G09G10G11G12\n";
+        let valid = header.clone()
+            + epoch
+            + "                                G13\n"
+            + "  20000000.000 8  20000000.000 8\n";
+        assert_eq!(validate_output(&valid), Ok(()));
+
+        let invalid = header.clone() + epoch + "G13\n";
+        assert_eq!(
+            validate_output(&invalid),
+            Err(vec![LineViolation::Indentation { line: 4 }])
+        );
+
+        let invalid = header.clone() + &format!("{:81}\n", "  20000000.000");
+        assert_eq!(
+            validate_output(&invalid),
+            Err(vec![LineViolation::TooLong { line: 3, width: 81 }])
+        );
+
+        let invalid = "     2.11           OBSERVATION DATA    G (GPS)\n";
+        assert_eq!(
+            validate_output(invalid),
+            Err(vec![LineViolation::MissingLabel { line: 1 }])
+        );
+    }
+    #[test]
+    fn width_violations() {
+        let tmp_path = crate::tests::toolkit::TmpFile::new("txt");
+        let mut writer = BufferedWriter::new(&tmp_path).unwrap();
+        write!(writer, "{:80}\n{:81}\n", "", "").unwrap();
+        assert!(
+            matches!(
+                writer.violations(),
+                [LineViolation::TooLong { width: 81, .. }]
+            ),
+            "{:?}",
+            writer.violations()
+        );
+    }
+}
@@ -54,7 +54,17 @@ fn main() -> Result<(), Error> {
         _ => rinex.standard_filename(cli.matches.get_flag("short"), None, None),
     };
 
-    rinex.to_file(&output_path)?;
+    // custom compression date, if any, also stamps the header
+    let mut stamp = Stamp::default();
+    if cli.date().is_some() || cli.time().is_some() {
+        if let Some(obs) = &rinex.header.obs {
+            if let Some(crx) = &obs.crinex {
+                stamp = stamp.with_date(crx.date);
+            }
+        }
+    }
+
+    rinex.to_file_with_stamp(&output_path, &stamp)?;
     println!("{} generated", output_path);
     Ok(())
 }
@@ -501,7 +501,7 @@ impl SP3 {
                 ref_geo.2,
                 map_3d::Ellipsoid::WGS84,
             );
-            (t, sv, (map_3d::rad2deg(elev), map_3d::rad2deg(azim)))
+            (t, sv, (elev.to_degrees(), azim.to_degrees()))
         })
     }
     /// Returns an Iterator over SV velocities estimates,